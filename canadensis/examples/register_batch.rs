@@ -0,0 +1,222 @@
+//! Runs a UAVCAN node that writes register values from a file to other nodes, or saves the
+//! register values of other nodes to a file
+//!
+//! Usage:
+//!
+//! * `register_batch [SocketCAN interface name] [Local node ID] apply [File]`
+//! * `register_batch [SocketCAN interface name] [Local node ID] dump [File] [Node ID]...`
+//!
+//! Files with the extension `.json` are JSON documents. All other files are YAML documents.
+//! See [`RegisterBatch`] for the format.
+//!
+//! `apply` writes each register and checks that the node accepted the value. `dump` saves
+//! all registers of the listed nodes, in a format that `apply` accepts.
+//!
+//! This program exits with status 1 if any register could not be read or written.
+//!
+//! # Testing
+//!
+//! ## Create a virtual CAN device
+//!
+//! ```
+//! sudo modprobe vcan
+//! sudo ip link add dev vcan0 type vcan
+//! sudo ip link set up vcan0
+//! ```
+//!
+//! ## Save and restore the registers of a node
+//!
+//! ```
+//! register_batch vcan0 [Local node ID] dump registers.yaml [Target node ID]
+//! register_batch vcan0 [Local node ID] apply registers.yaml
+//! ```
+
+extern crate canadensis;
+extern crate canadensis_data_types;
+extern crate canadensis_linux;
+extern crate rand;
+extern crate socketcan;
+
+use std::convert::TryFrom;
+use std::env;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::process;
+use std::time::Duration;
+
+use socketcan::CANSocket;
+
+use canadensis::core::time::Microseconds64;
+use canadensis::node::{BasicNode, CoreNode};
+use canadensis::register::batch::{BatchClient, BatchEvent, RegisterBatch};
+use canadensis::requester::TransferIdFixedMap;
+use canadensis::Node;
+use canadensis_can::queue::{ArrayQueue, SingleQueueDriver};
+use canadensis_can::{CanNodeId, CanReceiver, CanTransmitter, CanTransport, Error, Mtu};
+use canadensis_data_types::uavcan::node::get_info_1_0::GetInfoResponse;
+use canadensis_data_types::uavcan::node::version_1_0::Version;
+use canadensis_linux::{LinuxCan, SystemClock};
+
+enum Command {
+    /// Write the registers in a file
+    Apply { path: String },
+    /// Save the registers of some nodes to a file
+    Dump {
+        path: String,
+        targets: Vec<CanNodeId>,
+    },
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut args = env::args().skip(1);
+    let can_interface = args.next().expect("Expected CAN interface name");
+    let node_id = parse_node_id(&args.next().expect("Expected node ID"));
+    let command = match args.next().as_deref() {
+        Some("apply") => Command::Apply {
+            path: args.next().expect("Expected file path"),
+        },
+        Some("dump") => Command::Dump {
+            path: args.next().expect("Expected file path"),
+            targets: args.map(|arg| parse_node_id(&arg)).collect(),
+        },
+        _ => panic!("Expected apply or dump command"),
+    };
+
+    let can = CANSocket::open(&can_interface).expect("Failed to open CAN interface");
+    can.set_read_timeout(Duration::from_millis(5))?;
+    can.set_write_timeout(Duration::from_millis(500))?;
+    let can = LinuxCan::new(can);
+
+    // Set up information about this node
+    let node_info = GetInfoResponse {
+        protocol_version: Version { major: 1, minor: 0 },
+        hardware_version: Version { major: 0, minor: 0 },
+        software_version: Version { major: 0, minor: 1 },
+        software_vcs_revision_id: 0,
+        unique_id: rand::random(),
+        name: heapless::Vec::from_slice(b"org.samcrow.register_batch").unwrap(),
+        software_image_crc: heapless::Vec::new(),
+        certificate_of_authenticity: Default::default(),
+    };
+
+    // Create a node with capacity for 2 publishers and 2 requesters
+    type Queue = SingleQueueDriver<ArrayQueue<Microseconds64, 64>, LinuxCan>;
+    type ClientCoreNode = CoreNode<
+        SystemClock,
+        CanTransmitter<Microseconds64, Queue>,
+        CanReceiver<Microseconds64, Queue>,
+        TransferIdFixedMap<CanTransport, TRANSFER_IDS>,
+        Queue,
+        PUBLISHERS,
+        REQUESTERS,
+    >;
+    const TRANSFER_IDS: usize = 2;
+    const PUBLISHERS: usize = 2;
+    const REQUESTERS: usize = 2;
+
+    let queue = Queue::new(ArrayQueue::new(), can);
+    let transmitter = CanTransmitter::new(Mtu::Can8);
+    let receiver = CanReceiver::new(node_id, Mtu::Can8);
+    let core_node: ClientCoreNode =
+        CoreNode::new(SystemClock::new(), node_id, transmitter, receiver, queue);
+    let mut node = BasicNode::new(core_node, node_info).unwrap();
+
+    let mut client = BatchClient::new(&mut node).unwrap();
+    match &command {
+        Command::Apply { path } => {
+            let document = fs::read_to_string(path)?;
+            let batch = if is_json(path) {
+                RegisterBatch::from_json(&document)?
+            } else {
+                RegisterBatch::from_yaml(&document)?
+            };
+            client.apply(&batch)?;
+        }
+        Command::Dump { targets, .. } => {
+            for target in targets {
+                client.dump(*target);
+            }
+        }
+    }
+
+    let start_time = std::time::Instant::now();
+    let mut prev_seconds = 0;
+    let mut failed = false;
+    while !client.is_idle() {
+        match node.receive(&mut client) {
+            Ok(_) => { /* Keep receiving */ }
+            Err(Error::Driver(e)) if e.kind() == ErrorKind::WouldBlock => {
+                // Keep receiving
+            }
+            Err(e) => panic!("{:?}", e),
+        }
+        client.poll(&mut node);
+        while let Some(event) = client.pop_event() {
+            match event {
+                BatchEvent::Written { server, name } => {
+                    println!("Node {}: wrote {}", u8::from(server), name)
+                }
+                BatchEvent::NotWritten {
+                    server,
+                    name,
+                    mutable,
+                    ..
+                } => {
+                    if mutable {
+                        eprintln!("Node {}: {} rejected the value", u8::from(server), name);
+                    } else {
+                        eprintln!("Node {}: {} is not mutable", u8::from(server), name);
+                    }
+                    failed = true;
+                }
+                BatchEvent::Failed {
+                    server,
+                    name,
+                    error,
+                } => {
+                    eprintln!(
+                        "Node {}: {}: {}",
+                        u8::from(server),
+                        name.as_deref().unwrap_or("(list)"),
+                        error
+                    );
+                    failed = true;
+                }
+            }
+        }
+
+        let seconds = std::time::Instant::now()
+            .duration_since(start_time)
+            .as_secs();
+        if seconds != prev_seconds {
+            prev_seconds = seconds;
+            node.run_per_second_tasks().unwrap();
+        }
+        node.flush().unwrap();
+    }
+
+    if let Command::Dump { path, .. } = &command {
+        let snapshot = client.take_snapshot();
+        let document = if is_json(path) {
+            snapshot.to_json()
+        } else {
+            snapshot.to_yaml()
+        };
+        fs::write(path, document)?;
+        println!("Saved {} registers to {}", snapshot.len(), path);
+    }
+    process::exit(if failed { 1 } else { 0 });
+}
+
+fn parse_node_id(text: &str) -> CanNodeId {
+    CanNodeId::try_from(text.parse::<u8>().expect("Invalid node ID format"))
+        .expect("Node ID too large")
+}
+
+fn is_json(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .and_then(|extension| extension.to_str())
+        == Some("json")
+}
@@ -15,7 +15,7 @@
 //! ## Start the node
 //!
 //! ```
-//! register_client [SocketCAN interface name] [Local node ID] [Target node ID] [delay time after each message]
+//! register_client [SocketCAN interface name] [Local node ID] [Target node ID]
 //! ```
 
 extern crate canadensis;
@@ -26,33 +26,22 @@ extern crate socketcan;
 
 use std::convert::TryFrom;
 use std::env;
-use std::str;
+use std::io::ErrorKind;
 use std::time::Duration;
 
 use socketcan::CANSocket;
 
-use canadensis::core::time::{milliseconds, Clock, Microseconds64};
-use canadensis::core::transfer::ServiceTransfer;
-use canadensis::core::Priority;
-use canadensis::encoding::Deserialize;
+use canadensis::core::time::Microseconds64;
 use canadensis::node::{BasicNode, CoreNode};
+use canadensis::register::client::{RegisterClient, RegisterEvent};
 use canadensis::requester::TransferIdFixedMap;
-use canadensis::{Node, ServiceToken, TransferHandler};
+use canadensis::Node;
 use canadensis_can::queue::{ArrayQueue, SingleQueueDriver};
-use canadensis_can::{
-    CanNodeId, CanReceiver, CanTransferId, CanTransmitter, CanTransport, Error, Mtu,
-};
+use canadensis_can::{CanNodeId, CanReceiver, CanTransmitter, CanTransport, Error, Mtu};
 use canadensis_data_types::uavcan::node::get_info_1_0::GetInfoResponse;
 use canadensis_data_types::uavcan::node::version_1_0::Version;
-use canadensis_data_types::uavcan::primitive::empty_1_0::Empty;
-use canadensis_data_types::uavcan::register::access_1_0::{self, AccessRequest, AccessResponse};
-use canadensis_data_types::uavcan::register::list_1_0::{self, ListRequest, ListResponse};
-use canadensis_data_types::uavcan::register::name_1_0::Name;
 use canadensis_data_types::uavcan::register::value_1_0::Value;
 use canadensis_linux::{LinuxCan, SystemClock};
-use std::collections::BTreeMap;
-use std::io::ErrorKind;
-use std::thread;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let mut args = env::args().skip(1);
@@ -71,11 +60,6 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             .expect("Invalid node ID format"),
     )
     .expect("Node ID too large");
-    let delay_time_seconds: f32 = args.next().map(|s| s.parse().unwrap()).unwrap_or(0.0);
-    let delay_time = Duration::new(
-        delay_time_seconds.floor() as u64,
-        (delay_time_seconds.fract() * 1e9) as u32,
-    );
 
     let can = CANSocket::open(&can_interface).expect("Failed to open CAN interface");
     can.set_read_timeout(Duration::from_millis(5))?;
@@ -96,7 +80,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Create a node with capacity for 2 publishers and 2 requesters
     type Queue = SingleQueueDriver<ArrayQueue<Microseconds64, 64>, LinuxCan>;
-    const TRANSFER_IDS: usize = 1;
+    const TRANSFER_IDS: usize = 2;
     const PUBLISHERS: usize = 2;
     const REQUESTERS: usize = 2;
 
@@ -113,47 +97,38 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         REQUESTERS,
     > = CoreNode::new(SystemClock::new(), node_id, transmitter, receiver, queue);
     let mut node = BasicNode::new(core_node, node_info).unwrap();
-    let list_request_token: ServiceToken<ListRequest> = node
-        .start_sending_requests(list_1_0::SERVICE, milliseconds(1000), 256, Priority::Low)
-        .unwrap();
-    let access_token = node
-        .start_sending_requests(access_1_0::SERVICE, milliseconds(1000), 267, Priority::Low)
-        .unwrap();
 
-    // Send a register list request for the register at index 0
-    node.send_request(
-        &list_request_token,
-        &ListRequest { index: 0 },
-        target_node_id,
-    )
-    .unwrap();
-    node.flush().unwrap();
-
-    let timeout_duration = std::time::Duration::from_secs(1);
-
-    let mut handler = RegisterHandler {
-        target_node_id,
-        next_register_index: 1,
-        registers: BTreeMap::new(),
-        list_request_token,
-        access_token,
-        all_registers_listed: false,
-        done: false,
-        timeout: std::time::Instant::now() + timeout_duration,
-        timeout_duration,
-        delay_time,
-    };
+    // List and read all the registers of the target node
+    let mut client = RegisterClient::new(&mut node).unwrap();
+    client.read_all(target_node_id);
 
     let start_time = std::time::Instant::now();
     let mut prev_seconds = 0;
-    while !handler.done && handler.timeout > std::time::Instant::now() {
-        match node.receive(&mut handler) {
+    let mut failed = false;
+    while !client.is_idle() {
+        match node.receive(&mut client) {
             Ok(_) => { /* Keep receiving */ }
             Err(Error::Driver(e)) if e.kind() == ErrorKind::WouldBlock => {
                 // Keep receiving
             }
             Err(e) => panic!("{:?}", e),
         }
+        client.poll(&mut node);
+        while let Some(event) = client.pop_event() {
+            match event {
+                RegisterEvent::Listed { names, .. } => {
+                    println!("Found {} registers", names.len())
+                }
+                RegisterEvent::Read { name, value, .. } => {
+                    println!("{}: {:?}", name, DebugValue(&value))
+                }
+                RegisterEvent::Failed { name, error, .. } => {
+                    eprintln!("Failed to read {:?}: {:?}", name, error);
+                    failed = true;
+                }
+                _ => {}
+            }
+        }
 
         let seconds = std::time::Instant::now()
             .duration_since(start_time)
@@ -164,171 +139,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
         node.flush().unwrap();
     }
-    // Either finished or timed out
-    if handler.done {
-        // Print register information
-        for (name, state) in handler.registers {
-            if let RegisterState::Done(value) = state {
-                println!("{}: {:?}", name, DebugValue(&value));
-            }
-        }
-        std::process::exit(0);
-    } else {
-        println!("Timed out, registers found:");
-        for (name, state) in handler.registers {
-            if let RegisterState::Done(value) = state {
-                println!("{}: {:?}", name, DebugValue(&value));
-            } else {
-                println!("{}: <unknown>", name);
-            }
-        }
-        std::process::exit(1);
-    }
-}
-
-struct RegisterHandler {
-    /// The ID of the node to query
-    target_node_id: CanNodeId,
-    /// The index of the next register to query
-    next_register_index: u16,
-    /// Each known register and its value
-    registers: BTreeMap<String, RegisterState>,
-    /// Token used to send register list requests
-    list_request_token: ServiceToken<ListRequest>,
-    /// Token used to send register access request
-    access_token: ServiceToken<AccessRequest>,
-    /// True if all register list responses have been received
-    all_registers_listed: bool,
-    /// True if all register values are known
-    done: bool,
-    /// The time when the read operation will time out
-    timeout: std::time::Instant,
-    /// The timeout interval (used to update `timeout` after each successful receive)
-    timeout_duration: Duration,
-    /// The time to wait after sending each outgoing transfer
-    delay_time: Duration,
-}
-
-impl TransferHandler<<SystemClock as Clock>::Instant, CanTransport> for RegisterHandler {
-    fn handle_response<N>(
-        &mut self,
-        node: &mut N,
-        transfer: &ServiceTransfer<Vec<u8>, <SystemClock as Clock>::Instant, CanTransport>,
-    ) -> bool
-    where
-        N: Node<Instant = <SystemClock as Clock>::Instant, Transport = CanTransport>,
-    {
-        self.timeout = std::time::Instant::now() + self.timeout_duration;
-        match transfer.header.service {
-            list_1_0::SERVICE => {
-                if let Ok(list_response) = ListResponse::deserialize_from_bytes(&transfer.payload) {
-                    match str::from_utf8(&list_response.name.name) {
-                        Ok(register_name) => {
-                            if register_name.is_empty() {
-                                // No more registers
-                                self.all_registers_listed = true;
-                                self.check_if_done();
-                            } else {
-                                // Record information about this register and send a request to
-                                // read its value
-                                let read_transfer_id = node
-                                    .send_request(
-                                        &self.access_token,
-                                        &AccessRequest {
-                                            name: Name {
-                                                name: list_response.name.name.clone(),
-                                            },
-                                            value: Value::Empty(Empty {}),
-                                        },
-                                        self.target_node_id.clone(),
-                                    )
-                                    .unwrap();
-                                node.flush().unwrap();
-                                thread::sleep(self.delay_time);
-
-                                self.registers.insert(
-                                    register_name.to_owned(),
-                                    RegisterState::Waiting(read_transfer_id),
-                                );
-                                // Send a request for the next register
-                                node.send_request(
-                                    &self.list_request_token,
-                                    &ListRequest {
-                                        index: self.next_register_index,
-                                    },
-                                    self.target_node_id,
-                                )
-                                .unwrap();
-                                node.flush().unwrap();
-                                thread::sleep(self.delay_time);
-
-                                self.next_register_index += 1;
-                            }
-                        }
-                        Err(_) => {
-                            println!("Invalid UTF-8 in register name");
-                            self.done = true;
-                        }
-                    }
-                    // Transfer handled
-                    true
-                } else {
-                    false
-                }
-            }
-            access_1_0::SERVICE => {
-                if let Ok(response) = AccessResponse::deserialize_from_bytes(&transfer.payload) {
-                    // Find the register name with the matching transfer ID
-                    let register_entry =
-                        self.registers
-                            .iter_mut()
-                            .find(|(_name, state)| match state {
-                                RegisterState::Waiting(transfer_id) => {
-                                    transfer_id == &transfer.header.transfer_id
-                                }
-                                RegisterState::Done(_) => false,
-                            });
-                    if let Some((_name, state)) = register_entry {
-                        *state = RegisterState::Done(response.value);
-                    } else {
-                        eprintln!(
-                            "Couldn't find a corresponding register for transfer ID {:?}",
-                            transfer.header.transfer_id
-                        );
-                    }
-
-                    self.check_if_done();
-                    true
-                } else {
-                    false
-                }
-            }
-            _ => false,
-        }
-    }
-}
-
-impl RegisterHandler {
-    fn check_if_done(&mut self) {
-        // Done if all register values are known
-        if self.all_registers_listed
-            && self
-                .registers
-                .iter()
-                .all(|(_name, state)| matches!(state, RegisterState::Done(_)))
-        {
-            self.done = true;
-        }
-    }
-}
-
-enum RegisterState {
-    /// Waiting for a response with the register value
-    ///
-    /// The response will match the enclosed transfer ID
-    Waiting(CanTransferId),
-    /// The register value has been received
-    Done(Value),
+    std::process::exit(if failed { 1 } else { 0 });
 }
 
 struct DebugValue<'v>(&'v Value);
@@ -78,14 +78,19 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     const REQUESTERS: usize = 8;
     const MTU: usize = 1200;
 
-    let transmitter = UdpTransmitter::<MTU>::new(local_address.clone()).unwrap();
+    let transmitter = UdpTransmitter::<Microseconds64, MTU>::new(local_address.clone()).unwrap();
     let receiver = UdpReceiver::new(local_address);
     let core_node: CoreNode<
         SystemClock,
-        UdpTransmitter<MTU>,
+        UdpTransmitter<Microseconds64, MTU>,
         UdpReceiver<
             Microseconds64,
-            SessionDynamicMap<Microseconds64, UdpNodeId, UdpTransferId, UdpSessionData>,
+            SessionDynamicMap<
+                Microseconds64,
+                UdpNodeId,
+                UdpTransferId,
+                UdpSessionData<Microseconds64>,
+            >,
             MTU,
         >,
         TransferIdFixedMap<UdpTransport, TRANSFER_IDS>,
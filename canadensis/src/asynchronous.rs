@@ -0,0 +1,849 @@
+//!
+//! An asynchronous (`Future`-based) interface to a node
+//!
+//! This module is available when the `async` feature is enabled. It requires the standard library.
+//!
+//! An [`AsyncNode`] wraps a node (usually a [`CoreNode`](crate::node::CoreNode)) and sends
+//! heartbeat messages like a [`MinimalNode`]. An `AsyncNode` can be cloned, and all clones
+//! share the same underlying node.
+//!
+//! One task must call [`AsyncNode::run`] (or call [`AsyncNode::poll`] frequently) to receive
+//! transfers, send heartbeats, and flush outgoing frames. Other tasks can publish messages,
+//! send requests and wait for the responses, and receive messages or requests from streams.
+//!
+//! This module does not depend on any particular executor.
+//!
+
+use alloc::collections::{BTreeMap, VecDeque};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt::{Debug, Formatter};
+use core::future::{poll_fn, Future};
+use core::marker::PhantomData;
+use core::pin::Pin;
+use core::task::{Context, Poll, Waker};
+use std::sync::{Mutex, MutexGuard};
+
+use futures_core::Stream;
+
+use canadensis_core::time::{Clock, Duration, Instant};
+use canadensis_core::transfer::{MessageTransfer, ServiceTransfer};
+use canadensis_core::transport::{Receiver, Transmitter, Transport};
+use canadensis_core::{nb, ServiceId, SubjectId};
+use canadensis_data_types::uavcan::node::health_1_0::Health;
+use canadensis_data_types::uavcan::node::mode_1_0::Mode;
+use canadensis_encoding::{Deserialize, DeserializeError, Message, Request, Response, Serialize};
+
+use crate::node::{MinimalNode, NodeError};
+use crate::{Node, PublishToken, ResponseToken, ServiceToken, StartSendError, TransferHandler};
+
+/// The error type of a node's transmitter
+type TransmitError<N> = <<N as Node>::Transmitter as Transmitter<<N as Node>::Instant>>::Error;
+/// The error type of a node's receiver
+type ReceiveError<N> = <<N as Node>::Receiver as Receiver<<N as Node>::Instant>>::Error;
+
+/// A node that can be used from asynchronous code
+///
+/// The underlying node type `N` is usually a [`CoreNode`](crate::node::CoreNode). The `AsyncNode`
+/// uses one publisher slot in the underlying node to send heartbeat messages.
+pub struct AsyncNode<N>
+where
+    N: Node,
+{
+    shared: Arc<Mutex<Shared<N>>>,
+}
+
+/// The state that all clones of an `AsyncNode` share
+struct Shared<N>
+where
+    N: Node,
+{
+    /// The node, which sends heartbeats
+    node: MinimalNode<N>,
+    /// Destinations for incoming transfers
+    routes: Routes<N::Instant, N::Transport>,
+    /// The time when the next heartbeat should be sent
+    next_heartbeat: N::Instant,
+    /// Tasks that are waiting for space in the transmit queue
+    transmit_wakers: Vec<Waker>,
+}
+
+impl<N> AsyncNode<N>
+where
+    N: Node,
+    <N::Transport as Transport>::TransferId: PartialEq,
+{
+    /// Creates an asynchronous node
+    ///
+    /// * `node`: The underlying node (this is usually a [`CoreNode`](crate::node::CoreNode))
+    pub fn new(node: N) -> Result<Self, StartSendError<TransmitError<N>>> {
+        let mut node = MinimalNode::new(node)?;
+        // Send the first heartbeat as soon as possible
+        let now = node.node_mut().clock_mut().now();
+        Ok(AsyncNode {
+            shared: Arc::new(Mutex::new(Shared {
+                node,
+                routes: Routes::new(),
+                next_heartbeat: now,
+                transmit_wakers: Vec::new(),
+            })),
+        })
+    }
+
+    /// Receives incoming transfers, sends a heartbeat if one is due, and flushes outgoing frames
+    ///
+    /// [`run`](#method.run) calls this function repeatedly. Code that does not use `run` must
+    /// call this function frequently.
+    pub fn poll(&self) -> Result<(), NodeError<TransmitError<N>, ReceiveError<N>>> {
+        let mut shared = self.lock();
+        let shared = &mut *shared;
+
+        // Receive all available transfers
+        loop {
+            shared.routes.received = false;
+            shared
+                .node
+                .node_mut()
+                .receive(&mut shared.routes)
+                .map_err(NodeError::Receiver)?;
+            if !shared.routes.received {
+                break;
+            }
+        }
+
+        let now = shared.node.node_mut().clock_mut().now();
+        if now.overflow_safe_compare(&shared.next_heartbeat) != Ordering::Less {
+            let one_second = <N::Instant as Instant>::Duration::from_millis(1000)
+                .expect("Duration type can't represent 1 second");
+            shared.next_heartbeat = one_second + shared.next_heartbeat;
+            if shared.next_heartbeat.overflow_safe_compare(&now) != Ordering::Greater {
+                // Fell behind, don't try to catch up
+                shared.next_heartbeat = one_second + now;
+            }
+            match shared.node.run_per_second_tasks() {
+                Ok(()) | Err(nb::Error::WouldBlock) => {}
+                Err(nb::Error::Other(e)) => return Err(NodeError::Transmitter(e)),
+            }
+        }
+
+        let flush_result = shared.node.node_mut().flush();
+        // Some space in the transmit queue may be available now
+        for waker in shared.transmit_wakers.drain(..) {
+            waker.wake();
+        }
+        match flush_result {
+            Ok(()) | Err(nb::Error::WouldBlock) => Ok(()),
+            Err(nb::Error::Other(e)) => Err(NodeError::Transmitter(e)),
+        }
+    }
+
+    /// Repeatedly calls [`poll`](#method.poll), waiting for the future returned by `sleep`
+    /// between calls
+    ///
+    /// This function never returns. Errors are logged and do not stop the loop.
+    ///
+    /// With Tokio, this can be used like
+    /// `node.run(|| tokio::time::sleep(Duration::from_millis(1)))`.
+    pub async fn run<S, F>(&self, mut sleep: S)
+    where
+        S: FnMut() -> F,
+        F: Future<Output = ()>,
+    {
+        loop {
+            if let Err(e) = self.poll() {
+                log::warn!("Async node error: {:?}", e);
+            }
+            sleep().await;
+        }
+    }
+
+    /// Calls a function with a mutable reference to the enclosed minimal node
+    ///
+    /// This can be used to access the underlying node or change the heartbeat status.
+    ///
+    /// The function should not block, because other tasks cannot use this node until it returns.
+    pub fn with_node<F, R>(&self, operation: F) -> R
+    where
+        F: FnOnce(&mut MinimalNode<N>) -> R,
+    {
+        operation(&mut self.lock().node)
+    }
+
+    /// Sets the operating mode that will be reported in the heartbeat messages
+    pub fn set_mode(&self, mode: Mode) {
+        self.lock().node.set_mode(mode)
+    }
+    /// Sets the health status that will be reported in the heartbeat messages
+    pub fn set_health(&self, health: Health) {
+        self.lock().node.set_health(health)
+    }
+
+    /// Starts publishing messages on subject
+    ///
+    /// This is equivalent to [`Node::start_publishing`].
+    pub fn start_publishing<M>(
+        &self,
+        subject: SubjectId,
+        timeout: <N::Instant as Instant>::Duration,
+        priority: <N::Transport as Transport>::Priority,
+    ) -> Result<PublishToken<M>, StartSendError<TransmitError<N>>>
+    where
+        M: Message,
+    {
+        self.lock()
+            .node
+            .node_mut()
+            .start_publishing(subject, timeout, priority)
+    }
+
+    /// Publishes a message
+    ///
+    /// The returned future completes when the message has been added to the transmit queue.
+    /// If the queue is full, it waits until some frames have been sent.
+    pub async fn publish<M>(
+        &self,
+        token: &PublishToken<M>,
+        payload: &M,
+    ) -> Result<(), TransmitError<N>>
+    where
+        M: Message + Serialize,
+    {
+        poll_fn(|cx| {
+            let mut shared = self.lock();
+            let result = shared.node.node_mut().publish(token, payload);
+            shared.poll_transmit(cx, result)
+        })
+        .await
+    }
+
+    /// Sets up to send requests for a service
+    ///
+    /// This is equivalent to [`Node::start_sending_requests`].
+    pub fn start_sending_requests<Q>(
+        &self,
+        service: ServiceId,
+        receive_timeout: <N::Instant as Instant>::Duration,
+        response_payload_size_max: usize,
+        priority: <N::Transport as Transport>::Priority,
+    ) -> Result<ServiceToken<Q>, StartSendError<ReceiveError<N>>>
+    where
+        Q: Request,
+    {
+        self.lock().node.node_mut().start_sending_requests(
+            service,
+            receive_timeout,
+            response_payload_size_max,
+            priority,
+        )
+    }
+
+    /// Sends a service request and waits for the response
+    ///
+    /// The returned future does not time out. To stop waiting for a response, drop the future
+    /// (this can be done using the timeout function of the executor).
+    ///
+    /// Type parameters:
+    /// * `Q`: The request type
+    /// * `S`: The response type
+    pub async fn request<Q, S>(
+        &self,
+        token: &ServiceToken<Q>,
+        payload: &Q,
+        destination: <N::Transport as Transport>::NodeId,
+    ) -> Result<ServiceTransfer<S, N::Instant, N::Transport>, RequestError<TransmitError<N>>>
+    where
+        Q: Request + Serialize,
+        S: Response + Deserialize,
+    {
+        let key = poll_fn(|cx| {
+            let mut shared = self.lock();
+            let result = shared
+                .node
+                .node_mut()
+                .send_request(token, payload, destination.clone());
+            // The response can't be received until the lock is released, so there is no
+            // chance of missing it.
+            let result = result.map(|transfer_id| {
+                shared.routes.add_pending_response(
+                    token.service_id(),
+                    destination.clone(),
+                    transfer_id,
+                )
+            });
+            shared.poll_transmit(cx, result)
+        })
+        .await
+        .map_err(RequestError::Transmitter)?;
+
+        // If this future is dropped before the response arrives, the guard removes the
+        // pending response.
+        let guard = PendingResponseGuard {
+            shared: &self.shared,
+            key,
+        };
+        let transfer = poll_fn(|cx| {
+            let mut shared = guard.shared.lock().expect("Async node mutex poisoned");
+            shared.routes.poll_response(guard.key, cx)
+        })
+        .await;
+
+        let response =
+            S::deserialize_from_bytes(&transfer.payload).map_err(RequestError::Deserialize)?;
+        Ok(ServiceTransfer {
+            header: transfer.header,
+            payload: response,
+        })
+    }
+
+    /// Subscribes to messages on a subject and returns a stream of the messages received
+    ///
+    /// * `queue_capacity`: The maximum number of messages to hold until the stream is polled.
+    ///   If the queue is full when another message arrives, the oldest message is discarded.
+    ///
+    /// Messages that cannot be deserialized are discarded.
+    ///
+    /// When the stream is dropped, incoming messages are discarded but the underlying transport
+    /// remains subscribed.
+    pub fn subscribe_message<M>(
+        &self,
+        subject: SubjectId,
+        payload_size_max: usize,
+        timeout: <N::Instant as Instant>::Duration,
+        queue_capacity: usize,
+    ) -> Result<MessageStream<N, M>, SubscribeError<ReceiveError<N>>>
+    where
+        M: Message + Deserialize,
+    {
+        let mut shared = self.lock();
+        if shared.routes.messages.contains_key(&subject) {
+            return Err(SubscribeError::Duplicate);
+        }
+        shared
+            .node
+            .node_mut()
+            .subscribe_message(subject, payload_size_max, timeout)
+            .map_err(SubscribeError::Transport)?;
+        shared
+            .routes
+            .messages
+            .insert(subject, Channel::new(queue_capacity));
+        Ok(MessageStream {
+            shared: Arc::clone(&self.shared),
+            subject,
+            _message: PhantomData,
+        })
+    }
+
+    /// Subscribes to requests for a service and returns a stream of the requests received
+    ///
+    /// Each request comes with a token that can be passed to
+    /// [`send_response`](#method.send_response).
+    ///
+    /// The queue capacity and deserialization failures are handled in the same way as
+    /// [`subscribe_message`](#method.subscribe_message).
+    pub fn subscribe_request<Q>(
+        &self,
+        service: ServiceId,
+        payload_size_max: usize,
+        timeout: <N::Instant as Instant>::Duration,
+        queue_capacity: usize,
+    ) -> Result<RequestStream<N, Q>, SubscribeError<ReceiveError<N>>>
+    where
+        Q: Request + Deserialize,
+    {
+        let mut shared = self.lock();
+        if shared.routes.requests.contains_key(&service) {
+            return Err(SubscribeError::Duplicate);
+        }
+        shared
+            .node
+            .node_mut()
+            .subscribe_request(service, payload_size_max, timeout)
+            .map_err(SubscribeError::Transport)?;
+        shared
+            .routes
+            .requests
+            .insert(service, Channel::new(queue_capacity));
+        Ok(RequestStream {
+            shared: Arc::clone(&self.shared),
+            service,
+            _request: PhantomData,
+        })
+    }
+
+    /// Responds to a service request
+    ///
+    /// The returned future completes when the response has been added to the transmit queue.
+    pub async fn send_response<S>(
+        &self,
+        token: ResponseToken<N::Transport>,
+        timeout: <N::Instant as Instant>::Duration,
+        payload: &S,
+    ) -> Result<(), TransmitError<N>>
+    where
+        S: Response + Serialize,
+    {
+        poll_fn(|cx| {
+            let mut shared = self.lock();
+            let result = shared
+                .node
+                .node_mut()
+                .send_response(token.clone(), timeout, payload);
+            shared.poll_transmit(cx, result)
+        })
+        .await
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Shared<N>> {
+        self.shared.lock().expect("Async node mutex poisoned")
+    }
+}
+
+impl<N> Clone for AsyncNode<N>
+where
+    N: Node,
+{
+    fn clone(&self) -> Self {
+        AsyncNode {
+            shared: Arc::clone(&self.shared),
+        }
+    }
+}
+
+impl<N> Shared<N>
+where
+    N: Node,
+{
+    /// Converts the result of an attempt to add a transfer to the transmit queue into a poll result
+    ///
+    /// If the queue was full, this registers the task to be woken after the next flush.
+    fn poll_transmit<R>(
+        &mut self,
+        cx: &mut Context<'_>,
+        result: nb::Result<R, TransmitError<N>>,
+    ) -> Poll<Result<R, TransmitError<N>>> {
+        match result {
+            Ok(value) => Poll::Ready(Ok(value)),
+            Err(nb::Error::Other(e)) => Poll::Ready(Err(e)),
+            Err(nb::Error::WouldBlock) => {
+                self.transmit_wakers.push(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// Removes a pending response when a request future is dropped
+struct PendingResponseGuard<'n, N>
+where
+    N: Node,
+{
+    shared: &'n Mutex<Shared<N>>,
+    key: u64,
+}
+
+impl<N> Drop for PendingResponseGuard<'_, N>
+where
+    N: Node,
+{
+    fn drop(&mut self) {
+        if let Ok(mut shared) = self.shared.lock() {
+            let key = self.key;
+            shared.routes.responses.retain(|pending| pending.key != key);
+        }
+    }
+}
+
+/// A stream of incoming messages on one subject
+///
+/// This is created by [`AsyncNode::subscribe_message`].
+pub struct MessageStream<N, M>
+where
+    N: Node,
+{
+    shared: Arc<Mutex<Shared<N>>>,
+    subject: SubjectId,
+    _message: PhantomData<fn() -> M>,
+}
+
+impl<N, M> MessageStream<N, M>
+where
+    N: Node,
+{
+    /// Returns the subject ID that this stream receives messages on
+    pub fn subject_id(&self) -> SubjectId {
+        self.subject
+    }
+}
+
+impl<N, M> Stream for MessageStream<N, M>
+where
+    N: Node,
+    M: Message + Deserialize,
+{
+    type Item = MessageTransfer<M, N::Instant, N::Transport>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut shared = self.shared.lock().expect("Async node mutex poisoned");
+        let channel = shared
+            .routes
+            .messages
+            .get_mut(&self.subject)
+            .expect("No channel for message stream");
+        channel
+            .poll_next(cx, |transfer| {
+                M::deserialize_from_bytes(&transfer.payload).map(|message| MessageTransfer {
+                    header: transfer.header,
+                    payload: message,
+                })
+            })
+            .map(Some)
+    }
+}
+
+impl<N, M> Drop for MessageStream<N, M>
+where
+    N: Node,
+{
+    fn drop(&mut self) {
+        if let Ok(mut shared) = self.shared.lock() {
+            shared.routes.messages.remove(&self.subject);
+        }
+    }
+}
+
+impl<N, M> Debug for MessageStream<N, M>
+where
+    N: Node,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("MessageStream")
+            .field("subject", &self.subject)
+            .finish()
+    }
+}
+
+/// A stream of incoming requests for one service
+///
+/// This is created by [`AsyncNode::subscribe_request`].
+pub struct RequestStream<N, Q>
+where
+    N: Node,
+{
+    shared: Arc<Mutex<Shared<N>>>,
+    service: ServiceId,
+    _request: PhantomData<fn() -> Q>,
+}
+
+impl<N, Q> RequestStream<N, Q>
+where
+    N: Node,
+{
+    /// Returns the service ID that this stream receives requests for
+    pub fn service_id(&self) -> ServiceId {
+        self.service
+    }
+}
+
+impl<N, Q> Stream for RequestStream<N, Q>
+where
+    N: Node,
+    Q: Request + Deserialize,
+{
+    type Item = (
+        ResponseToken<N::Transport>,
+        ServiceTransfer<Q, N::Instant, N::Transport>,
+    );
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut shared = self.shared.lock().expect("Async node mutex poisoned");
+        let channel = shared
+            .routes
+            .requests
+            .get_mut(&self.service)
+            .expect("No channel for request stream");
+        channel
+            .poll_next(cx, |(token, transfer)| {
+                Q::deserialize_from_bytes(&transfer.payload).map(|request| {
+                    (
+                        token,
+                        ServiceTransfer {
+                            header: transfer.header,
+                            payload: request,
+                        },
+                    )
+                })
+            })
+            .map(Some)
+    }
+}
+
+impl<N, Q> Drop for RequestStream<N, Q>
+where
+    N: Node,
+{
+    fn drop(&mut self) {
+        if let Ok(mut shared) = self.shared.lock() {
+            shared.routes.requests.remove(&self.service);
+        }
+    }
+}
+
+impl<N, Q> Debug for RequestStream<N, Q>
+where
+    N: Node,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("RequestStream")
+            .field("service", &self.service)
+            .finish()
+    }
+}
+
+/// Errors that may occur when sending a request and waiting for a response
+#[derive(Debug)]
+pub enum RequestError<E> {
+    /// The transmitter returned an error
+    Transmitter(E),
+    /// The response could not be deserialized
+    Deserialize(DeserializeError),
+}
+
+/// Errors that may occur when subscribing
+#[derive(Debug)]
+pub enum SubscribeError<E> {
+    /// A stream for the provided subject ID or service ID already exists
+    Duplicate,
+    /// The transport returned an error
+    Transport(E),
+}
+
+/// A queue of incoming transfers for one stream
+struct Channel<X> {
+    /// Transfers that have not been taken by the stream
+    queue: VecDeque<X>,
+    /// The maximum number of transfers in the queue
+    capacity: usize,
+    /// The task waiting for a transfer, if any
+    waker: Option<Waker>,
+}
+
+impl<X> Channel<X> {
+    fn new(capacity: usize) -> Self {
+        Channel {
+            queue: VecDeque::new(),
+            capacity,
+            waker: None,
+        }
+    }
+
+    /// Adds a transfer to the queue, discarding the oldest transfer if the queue is full,
+    /// and wakes the stream task
+    fn push(&mut self, item: X) {
+        if self.queue.len() >= self.capacity {
+            log::debug!("Stream queue full, discarding oldest transfer");
+            self.queue.pop_front();
+        }
+        if self.capacity != 0 {
+            self.queue.push_back(item);
+        }
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+
+    /// Takes the oldest item that `convert` can convert, or registers the task to be woken when
+    /// another item arrives
+    fn poll_next<Y, F>(&mut self, cx: &mut Context<'_>, mut convert: F) -> Poll<Y>
+    where
+        F: FnMut(X) -> Result<Y, DeserializeError>,
+    {
+        while let Some(item) = self.queue.pop_front() {
+            match convert(item) {
+                Ok(converted) => return Poll::Ready(converted),
+                Err(e) => log::warn!(
+                    "Discarding transfer that could not be deserialized: {:?}",
+                    e
+                ),
+            }
+        }
+        self.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+/// A request that is waiting for a response
+struct PendingResponse<I, T>
+where
+    T: Transport,
+{
+    /// A unique identifier for this pending response
+    key: u64,
+    /// The service that the request was sent on
+    service: ServiceId,
+    /// The node that the request was sent to
+    server: T::NodeId,
+    /// The transfer ID of the request (and the response)
+    transfer_id: T::TransferId,
+    /// The response, if it has arrived
+    response: Option<ServiceTransfer<Vec<u8>, I, T>>,
+    /// The task waiting for the response, if any
+    waker: Option<Waker>,
+}
+
+/// A request that has been received, with a token that can be used to respond
+type IncomingRequest<I, T> = (ResponseToken<T>, ServiceTransfer<Vec<u8>, I, T>);
+
+/// Sends incoming transfers to the streams and request futures that are waiting for them
+struct Routes<I, T>
+where
+    T: Transport,
+{
+    /// Channels for each message subject
+    messages: BTreeMap<SubjectId, Channel<MessageTransfer<Vec<u8>, I, T>>>,
+    /// Channels for each service that this node handles requests for
+    requests: BTreeMap<ServiceId, Channel<IncomingRequest<I, T>>>,
+    /// Requests waiting for responses
+    responses: Vec<PendingResponse<I, T>>,
+    /// The key to assign to the next pending response
+    next_key: u64,
+    /// Set to true when any transfer is handled
+    received: bool,
+}
+
+impl<I, T> Routes<I, T>
+where
+    I: Instant,
+    T: Transport,
+    T::TransferId: PartialEq,
+{
+    fn new() -> Self {
+        Routes {
+            messages: BTreeMap::new(),
+            requests: BTreeMap::new(),
+            responses: Vec::new(),
+            next_key: 0,
+            received: false,
+        }
+    }
+
+    /// Records a request that is waiting for a response and returns its key
+    fn add_pending_response(
+        &mut self,
+        service: ServiceId,
+        server: T::NodeId,
+        transfer_id: T::TransferId,
+    ) -> u64 {
+        let key = self.next_key;
+        self.next_key = self.next_key.wrapping_add(1);
+        self.responses.push(PendingResponse {
+            key,
+            service,
+            server,
+            transfer_id,
+            response: None,
+            waker: None,
+        });
+        key
+    }
+
+    /// Removes and returns the response for a pending request if it has arrived, or registers
+    /// the task to be woken when it arrives
+    fn poll_response(
+        &mut self,
+        key: u64,
+        cx: &mut Context<'_>,
+    ) -> Poll<ServiceTransfer<Vec<u8>, I, T>> {
+        let index = self
+            .responses
+            .iter()
+            .position(|pending| pending.key == key)
+            .expect("No pending response");
+        let pending = &mut self.responses[index];
+        match pending.response.take() {
+            Some(response) => {
+                self.responses.swap_remove(index);
+                Poll::Ready(response)
+            }
+            None => {
+                pending.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl<I, T> TransferHandler<I, T> for Routes<I, T>
+where
+    I: Instant,
+    T: Transport,
+    T::TransferId: PartialEq,
+{
+    fn handle_message<N: Node<Instant = I, Transport = T>>(
+        &mut self,
+        _node: &mut N,
+        transfer: &MessageTransfer<Vec<u8>, I, T>,
+    ) -> bool {
+        self.received = true;
+        match self.messages.get_mut(&transfer.header.subject) {
+            Some(channel) => {
+                channel.push(MessageTransfer {
+                    header: transfer.header.clone(),
+                    payload: transfer.payload.clone(),
+                });
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn handle_request<N: Node<Instant = I, Transport = T>>(
+        &mut self,
+        _node: &mut N,
+        token: ResponseToken<T>,
+        transfer: &ServiceTransfer<Vec<u8>, I, T>,
+    ) -> bool {
+        self.received = true;
+        match self.requests.get_mut(&transfer.header.service) {
+            Some(channel) => {
+                channel.push((
+                    token,
+                    ServiceTransfer {
+                        header: transfer.header.clone(),
+                        payload: transfer.payload.clone(),
+                    },
+                ));
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn handle_response<N: Node<Instant = I, Transport = T>>(
+        &mut self,
+        _node: &mut N,
+        transfer: &ServiceTransfer<Vec<u8>, I, T>,
+    ) -> bool {
+        self.received = true;
+        let pending = self.responses.iter_mut().find(|pending| {
+            pending.response.is_none()
+                && pending.service == transfer.header.service
+                && pending.server == transfer.header.source
+                && pending.transfer_id == transfer.header.transfer_id
+        });
+        match pending {
+            Some(pending) => {
+                pending.response = Some(ServiceTransfer {
+                    header: transfer.header.clone(),
+                    payload: transfer.payload.clone(),
+                });
+                if let Some(waker) = pending.waker.take() {
+                    waker.wake();
+                }
+                true
+            }
+            None => false,
+        }
+    }
+}
@@ -0,0 +1,267 @@
+//!
+//! A client that sends service requests and matches them with their responses
+//!
+
+use core::cmp::Ordering;
+use core::fmt::{Debug, Formatter};
+use core::marker::PhantomData;
+
+use alloc::vec::Vec;
+use canadensis_core::time::{Clock, Instant};
+use canadensis_core::transfer::ServiceTransfer;
+use canadensis_core::transport::{Transmitter, Transport};
+use canadensis_core::{nb, ServiceId};
+use canadensis_encoding::{Deserialize, DeserializeError, Request, Response, Serialize};
+
+use crate::{Node, ServiceToken};
+
+/// Sends requests for one service and matches responses with the corresponding requests
+///
+/// Each outstanding request is identified by the server node ID and the transfer ID. When a
+/// response arrives, it is deserialized into the response type `S`. If no response arrives before
+/// the response timeout, the request is reported as timed out.
+///
+/// Type parameters:
+/// * `I`: The instant type of the node's clock
+/// * `T`: The transport
+/// * `Q`: The request type
+/// * `S`: The response type
+/// * `C`: The maximum number of requests that can be waiting for responses at the same time
+pub struct ServiceClient<I, T, Q, S, const C: usize>
+where
+    I: Instant,
+    T: Transport,
+{
+    /// The token used to send requests
+    token: ServiceToken<Q>,
+    /// The maximum time to wait for a response after sending a request
+    response_timeout: I::Duration,
+    /// Requests that are waiting for responses
+    pending: heapless::Vec<PendingRequest<I, T>, C>,
+    _response: PhantomData<fn() -> S>,
+}
+
+/// A request that has been sent and is waiting for a response
+struct PendingRequest<I, T>
+where
+    T: Transport,
+{
+    /// The node that the request was sent to
+    server: T::NodeId,
+    /// The transfer ID of the request (and the response)
+    transfer_id: T::TransferId,
+    /// The time after which the response will not be accepted
+    deadline: I,
+}
+
+impl<I, T, Q, S, const C: usize> ServiceClient<I, T, Q, S, C>
+where
+    I: Instant,
+    T: Transport,
+    T::TransferId: PartialEq,
+    Q: Request + Serialize,
+    S: Response + Deserialize,
+{
+    /// Creates a service client
+    ///
+    /// * `token`: A token from [`Node::start_sending_requests`]
+    /// * `response_timeout`: The maximum time to wait for each response
+    pub fn new(token: ServiceToken<Q>, response_timeout: I::Duration) -> Self {
+        ServiceClient {
+            token,
+            response_timeout,
+            pending: heapless::Vec::new(),
+            _response: PhantomData,
+        }
+    }
+
+    /// Returns the ID of the service that this client uses
+    pub fn service_id(&self) -> ServiceId {
+        self.token.service_id()
+    }
+
+    /// Returns the number of requests that are waiting for responses
+    pub fn pending_count(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Sends a request to a server and records it as waiting for a response
+    ///
+    /// On success, this function returns the transfer ID of the request.
+    ///
+    /// This function returns an error if `C` requests are already waiting for responses.
+    pub fn send<N>(
+        &mut self,
+        node: &mut N,
+        payload: &Q,
+        server: T::NodeId,
+    ) -> nb::Result<T::TransferId, ClientSendError<<N::Transmitter as Transmitter<I>>::Error>>
+    where
+        N: Node<Instant = I, Transport = T>,
+    {
+        if self.pending.is_full() {
+            return Err(nb::Error::Other(ClientSendError::Capacity));
+        }
+        let transfer_id = node
+            .send_request(&self.token, payload, server.clone())
+            .map_err(|e| e.map(ClientSendError::Transport))?;
+        let deadline = self.response_timeout + node.clock_mut().now();
+        // There is space because the vector was not full
+        let _ = self.pending.push(PendingRequest {
+            server,
+            transfer_id: transfer_id.clone(),
+            deadline,
+        });
+        Ok(transfer_id)
+    }
+
+    /// Checks if a response transfer matches a request that this client sent
+    ///
+    /// If the response matches, this function removes the request from the set of requests
+    /// waiting for responses and returns the deserialized response (or a deserialization error).
+    ///
+    /// This function returns None if the response does not match any request sent by this
+    /// client, or if it arrived after the response timeout. Responses that return None
+    /// may be passed on to other handlers.
+    pub fn handle_response(
+        &mut self,
+        transfer: &ServiceTransfer<Vec<u8>, I, T>,
+    ) -> Option<Result<ServiceTransfer<S, I, T>, ClientError<T>>> {
+        if transfer.header.service != self.token.service_id() {
+            return None;
+        }
+        let index = self.pending.iter().position(|pending| {
+            pending.server == transfer.header.source
+                && pending.transfer_id == transfer.header.transfer_id
+        })?;
+        if transfer
+            .header
+            .timestamp
+            .overflow_safe_compare(&self.pending[index].deadline)
+            == Ordering::Greater
+        {
+            // Too late, will be reported by check_timeouts()
+            return None;
+        }
+        let pending = self.pending.swap_remove(index);
+
+        Some(match S::deserialize_from_bytes(&transfer.payload) {
+            Ok(response) => Ok(ServiceTransfer {
+                header: transfer.header.clone(),
+                payload: response,
+            }),
+            Err(e) => Err(ClientError::Deserialize {
+                server: pending.server,
+                transfer_id: pending.transfer_id,
+                error: e,
+            }),
+        })
+    }
+
+    /// Checks for a request that has not received a response before its deadline
+    ///
+    /// If a request has timed out, this function removes it from the set of requests waiting for
+    /// responses and returns a [`ClientError::Timeout`]. Because only one error is returned
+    /// for each call, this function should be called repeatedly until it returns None.
+    pub fn check_timeouts(&mut self, now: I) -> Option<ClientError<T>> {
+        let index = self.pending.iter().position(|pending| {
+            now.overflow_safe_compare(&pending.deadline) == Ordering::Greater
+        })?;
+        let pending = self.pending.swap_remove(index);
+        Some(ClientError::Timeout {
+            server: pending.server,
+            transfer_id: pending.transfer_id,
+        })
+    }
+
+    /// Stops waiting for a response to the request with the provided server and transfer ID
+    ///
+    /// This function returns true if a matching request was waiting for a response.
+    pub fn cancel(&mut self, server: &T::NodeId, transfer_id: &T::TransferId) -> bool {
+        match self
+            .pending
+            .iter()
+            .position(|pending| pending.server == *server && pending.transfer_id == *transfer_id)
+        {
+            Some(index) => {
+                self.pending.swap_remove(index);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Consumes this client and returns the token that it used to send requests
+    ///
+    /// The token can be passed to [`Node::stop_sending_requests`].
+    pub fn into_token(self) -> ServiceToken<Q> {
+        self.token
+    }
+}
+
+/// Errors that can occur when sending a request
+#[derive(Debug)]
+pub enum ClientSendError<E> {
+    /// The maximum number of requests are already waiting for responses
+    Capacity,
+    /// The transport returned an error
+    Transport(E),
+}
+
+/// Errors that prevent a request from getting a valid response
+pub enum ClientError<T: Transport> {
+    /// No response arrived before the response timeout
+    Timeout {
+        /// The node that the request was sent to
+        server: T::NodeId,
+        /// The transfer ID of the request
+        transfer_id: T::TransferId,
+    },
+    /// A response arrived, but could not be deserialized
+    Deserialize {
+        /// The node that sent the response
+        server: T::NodeId,
+        /// The transfer ID of the request and response
+        transfer_id: T::TransferId,
+        /// The deserialization error
+        error: DeserializeError,
+    },
+}
+
+impl<T: Transport> Debug for ClientError<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ClientError::Timeout {
+                server,
+                transfer_id,
+            } => f
+                .debug_struct("Timeout")
+                .field("server", server)
+                .field("transfer_id", transfer_id)
+                .finish(),
+            ClientError::Deserialize {
+                server,
+                transfer_id,
+                error,
+            } => f
+                .debug_struct("Deserialize")
+                .field("server", server)
+                .field("transfer_id", transfer_id)
+                .field("error", error)
+                .finish(),
+        }
+    }
+}
+
+impl<I, T, Q, S, const C: usize> Debug for ServiceClient<I, T, Q, S, C>
+where
+    I: Instant,
+    T: Transport,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ServiceClient")
+            .field("service", &self.token.service_id())
+            .field("pending", &self.pending.len())
+            .finish()
+    }
+}
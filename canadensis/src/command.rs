@@ -0,0 +1,258 @@
+//!
+//! Handling of `uavcan.node.ExecuteCommand` requests
+//!
+
+use alloc::vec::Vec;
+
+use crate::node::BasicNode;
+use crate::{Node, ResponseToken, TransferHandler};
+use canadensis_core::time::{milliseconds, Instant};
+use canadensis_core::transfer::ServiceTransfer;
+use canadensis_core::transport::{Receiver, Transport};
+use canadensis_data_types::uavcan::node::execute_command_1_1::{
+    self, ExecuteCommandRequest, ExecuteCommandResponse,
+};
+use canadensis_data_types::uavcan::node::mode_1_0::Mode;
+use canadensis_encoding::{DataType, Deserialize};
+
+/// Functions that carry out commands received from other nodes
+///
+/// The default implementation of each function returns [`CommandStatus::BadCommand`], which
+/// tells the sender that the command is not supported.
+///
+/// The response is sent after the function returns. Functions that restart or power off the
+/// node should schedule the operation instead of performing it immediately, so that the response
+/// can be sent first.
+pub trait Commands<T: Transport> {
+    /// Restarts the node
+    fn restart(&mut self) -> CommandStatus {
+        CommandStatus::BadCommand
+    }
+    /// Powers off the node
+    fn power_off(&mut self) -> CommandStatus {
+        CommandStatus::BadCommand
+    }
+    /// Starts a software update
+    ///
+    /// The node should read the new software image from the file `path` on the node `server`
+    /// (for example, using `canadensis_file`), install it, and then restart.
+    ///
+    /// If this function returns [`CommandStatus::Success`], the command handler reports that a
+    /// software update is in progress until
+    /// [`CommandHandler::finish_software_update`] is called.
+    fn begin_software_update(&mut self, server: T::NodeId, path: &[u8]) -> CommandStatus {
+        let _ = (server, path);
+        CommandStatus::BadCommand
+    }
+    /// Returns all configuration parameters to their default values
+    fn factory_reset(&mut self) -> CommandStatus {
+        CommandStatus::BadCommand
+    }
+    /// Stops all actuators and other potentially dangerous activity
+    fn emergency_stop(&mut self) -> CommandStatus {
+        CommandStatus::BadCommand
+    }
+    /// Writes all configuration parameters and other persistent state to non-volatile storage
+    fn store_persistent_states(&mut self) -> CommandStatus {
+        CommandStatus::BadCommand
+    }
+    /// Handles a vendor-specific command
+    ///
+    /// `command` is never one of the standard commands.
+    fn other(&mut self, command: u16, parameter: &[u8]) -> CommandStatus {
+        let _ = (command, parameter);
+        CommandStatus::BadCommand
+    }
+}
+
+/// The result of a command
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CommandStatus {
+    /// The command was executed, or started successfully
+    Success,
+    /// The command failed
+    Failure,
+    /// The sender is not allowed to execute this command
+    NotAuthorized,
+    /// The command is not supported
+    BadCommand,
+    /// The command parameter is invalid
+    BadParameter,
+    /// The node is not in a state where it can execute the command
+    BadState,
+    /// An unexpected error occurred
+    InternalError,
+}
+
+impl From<CommandStatus> for u8 {
+    fn from(status: CommandStatus) -> Self {
+        match status {
+            CommandStatus::Success => ExecuteCommandResponse::STATUS_SUCCESS,
+            CommandStatus::Failure => ExecuteCommandResponse::STATUS_FAILURE,
+            CommandStatus::NotAuthorized => ExecuteCommandResponse::STATUS_NOT_AUTHORIZED,
+            CommandStatus::BadCommand => ExecuteCommandResponse::STATUS_BAD_COMMAND,
+            CommandStatus::BadParameter => ExecuteCommandResponse::STATUS_BAD_PARAMETER,
+            CommandStatus::BadState => ExecuteCommandResponse::STATUS_BAD_STATE,
+            CommandStatus::InternalError => ExecuteCommandResponse::STATUS_INTERNAL_ERROR,
+        }
+    }
+}
+
+/// Handles `uavcan.node.ExecuteCommand` requests by calling functions of a [`Commands`]
+/// implementation
+///
+/// Versions 1.0 and 1.1 of `ExecuteCommand` use the same service ID and compatible layouts, so
+/// this handler accepts requests of both versions.
+///
+/// Basic steps:
+/// 1. Create a handler using `CommandHandler::new`
+/// 2. Call `CommandHandler::subscribe_requests` to subscribe to command requests
+/// 3. When receiving transfers on the node, pass the command handler as a transfer handler
+/// 4. Once per second, before calling
+///    [`BasicNode::run_per_second_tasks`](crate::node::BasicNode::run_per_second_tasks), call
+///    [`set_heartbeat_mode`](#method.set_heartbeat_mode) so that the heartbeat messages report
+///    any software update in progress
+pub struct CommandHandler<C> {
+    commands: C,
+    software_update_in_progress: bool,
+}
+
+impl<C> CommandHandler<C> {
+    /// Creates a command handler
+    pub fn new(commands: C) -> Self {
+        CommandHandler {
+            commands,
+            software_update_in_progress: false,
+        }
+    }
+
+    /// Subscribes to command requests
+    ///
+    /// This function returns an error if the provided node does not have enough space to listen
+    /// for requests.
+    pub fn subscribe_requests<N>(
+        node: &mut N,
+    ) -> Result<(), <N::Receiver as Receiver<N::Instant>>::Error>
+    where
+        N: Node,
+    {
+        node.subscribe_request(
+            execute_command_1_1::SERVICE,
+            ExecuteCommandRequest::extent(),
+            milliseconds(1000),
+        )
+    }
+
+    /// Returns a reference to the command functions
+    pub fn commands(&self) -> &C {
+        &self.commands
+    }
+    /// Returns a mutable reference to the command functions
+    pub fn commands_mut(&mut self) -> &mut C {
+        &mut self.commands
+    }
+
+    /// Returns true if a software update has started and has not finished
+    pub fn software_update_in_progress(&self) -> bool {
+        self.software_update_in_progress
+    }
+
+    /// Records that a software update has finished (successfully or not)
+    ///
+    /// After this function is called, [`mode`](#method.mode) returns None.
+    pub fn finish_software_update(&mut self) {
+        self.software_update_in_progress = false;
+    }
+
+    /// Returns the mode that the node should report in its heartbeat messages, or None if
+    /// commands do not require a particular mode
+    ///
+    /// While a software update is in progress, this function returns a software update mode.
+    pub fn mode(&self) -> Option<Mode> {
+        if self.software_update_in_progress {
+            Some(Mode {
+                value: Mode::SOFTWARE_UPDATE,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Sets the mode that a node reports in its heartbeat messages
+    ///
+    /// While a software update is in progress, this function sets the software update mode.
+    /// Otherwise, it sets `normal_mode`.
+    pub fn set_heartbeat_mode<N>(&self, node: &mut BasicNode<N>, normal_mode: Mode)
+    where
+        N: Node,
+    {
+        node.set_mode(self.mode().unwrap_or(normal_mode));
+    }
+
+    fn handle_command<T>(&mut self, client: T::NodeId, request: &ExecuteCommandRequest) -> u8
+    where
+        T: Transport,
+        C: Commands<T>,
+    {
+        let command = request.command;
+        log::debug!("Handling command {}", command);
+        let status = match command {
+            ExecuteCommandRequest::COMMAND_RESTART => self.commands.restart(),
+            ExecuteCommandRequest::COMMAND_POWER_OFF => self.commands.power_off(),
+            ExecuteCommandRequest::COMMAND_BEGIN_SOFTWARE_UPDATE => {
+                if self.software_update_in_progress {
+                    CommandStatus::BadState
+                } else if request.parameter.is_empty() {
+                    CommandStatus::BadParameter
+                } else {
+                    let status = self
+                        .commands
+                        .begin_software_update(client, &request.parameter);
+                    if status == CommandStatus::Success {
+                        self.software_update_in_progress = true;
+                    }
+                    status
+                }
+            }
+            ExecuteCommandRequest::COMMAND_FACTORY_RESET => self.commands.factory_reset(),
+            ExecuteCommandRequest::COMMAND_EMERGENCY_STOP => self.commands.emergency_stop(),
+            ExecuteCommandRequest::COMMAND_STORE_PERSISTENT_STATES => {
+                self.commands.store_persistent_states()
+            }
+            _ => self.commands.other(command, &request.parameter),
+        };
+        status.into()
+    }
+}
+
+impl<I, C, T> TransferHandler<I, T> for CommandHandler<C>
+where
+    I: Instant,
+    C: Commands<T>,
+    T: Transport,
+{
+    fn handle_request<N: Node<Instant = I, Transport = T>>(
+        &mut self,
+        node: &mut N,
+        token: ResponseToken<T>,
+        transfer: &ServiceTransfer<Vec<u8>, I, T>,
+    ) -> bool {
+        if transfer.header.service != execute_command_1_1::SERVICE {
+            return false;
+        }
+        match ExecuteCommandRequest::deserialize_from_bytes(&transfer.payload) {
+            Ok(request) => {
+                let status = self.handle_command::<T>(transfer.header.source.clone(), &request);
+                let response = ExecuteCommandResponse { status };
+                if node
+                    .send_response(token, milliseconds(1000), &response)
+                    .is_err()
+                {
+                    log::warn!("Out of memory when sending command response");
+                }
+                true
+            }
+            Err(_) => false,
+        }
+    }
+}
@@ -0,0 +1,319 @@
+//!
+//! Publishing log messages as `uavcan.diagnostic.Record`
+//!
+//! A [`DiagnosticLogger`] implements [`log::Log`] and keeps log records in a fixed-capacity
+//! queue. A [`DiagnosticPublisher`] takes records from the queue and publishes them, so that log
+//! messages appear in any Cyphal tool that displays diagnostic records.
+//!
+//! The logger can be used from any context, including interrupt handlers. It uses the
+//! [`critical-section`](https://docs.rs/critical-section) crate to protect the queue, so the
+//! application must provide a critical section implementation (for example, the
+//! `critical-section-single-core` feature of `cortex-m`, or the `std` feature of
+//! `critical-section`).
+//!
+//! # Examples
+//!
+//! ```ignore
+//! static LOGGER: DiagnosticLogger<16> = DiagnosticLogger::new();
+//!
+//! log::set_logger(&LOGGER).unwrap();
+//! log::set_max_level(log::LevelFilter::Info);
+//! let mut publisher = DiagnosticPublisher::new(&mut node, 10).unwrap();
+//! loop {
+//!     // ...
+//!     publisher.poll(&mut node, &LOGGER, &NotSynchronized).unwrap();
+//! }
+//! ```
+//!
+
+use core::cell::RefCell;
+use core::cmp::Ordering;
+use core::fmt::{self, Write};
+
+use crate::time_sync::SynchronizedClock;
+use crate::{Node, PublishToken, StartSendError};
+use canadensis_core::time::{milliseconds, Clock, Instant};
+use canadensis_core::transport::Transmitter;
+use canadensis_core::{nb, Priority};
+use canadensis_data_types::uavcan::diagnostic::record_1_1::{self, Record};
+use canadensis_data_types::uavcan::diagnostic::severity_1_0::Severity;
+use canadensis_data_types::uavcan::time::synchronized_timestamp_1_0::SynchronizedTimestamp;
+use critical_section::Mutex;
+use heapless::Deque;
+use log::{Level, Log, Metadata};
+
+/// The maximum length of the text of a record, in bytes
+const MAX_TEXT_LENGTH: usize = 255;
+
+/// Record text
+type Text = heapless::Vec<u8, MAX_TEXT_LENGTH>;
+
+/// A logger that keeps up to `N` log records until a [`DiagnosticPublisher`] publishes them
+///
+/// The text of each record is truncated to 255 bytes. If the queue is full, new records are
+/// discarded and counted. The publisher reports the number of discarded records in a warning.
+///
+/// The logger does not filter records itself. Use `log::set_max_level` to choose which records
+/// are published.
+pub struct DiagnosticLogger<const N: usize> {
+    state: Mutex<RefCell<LoggerState<N>>>,
+}
+
+struct LoggerState<const N: usize> {
+    /// Records waiting to be published
+    records: Deque<QueuedRecord, N>,
+    /// The number of records that were discarded because the queue was full
+    dropped: u32,
+}
+
+/// A log record waiting to be published
+struct QueuedRecord {
+    severity: u8,
+    text: Text,
+}
+
+impl<const N: usize> DiagnosticLogger<N> {
+    /// Creates a logger with an empty queue
+    pub const fn new() -> Self {
+        DiagnosticLogger {
+            state: Mutex::new(RefCell::new(LoggerState {
+                records: Deque::new(),
+                dropped: 0,
+            })),
+        }
+    }
+
+    /// Returns the number of records waiting to be published
+    pub fn len(&self) -> usize {
+        critical_section::with(|cs| self.state.borrow_ref(cs).records.len())
+    }
+
+    /// Returns true if no records are waiting to be published
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Adds a record to the back of the queue, or counts it as dropped if the queue is full
+    fn push_back(&self, record: QueuedRecord) {
+        critical_section::with(|cs| {
+            let mut state = self.state.borrow_ref_mut(cs);
+            if state.records.push_back(record).is_err() {
+                state.dropped = state.dropped.saturating_add(1);
+            }
+        })
+    }
+
+    /// Returns a record that could not be published to the front of the queue
+    fn push_front(&self, record: QueuedRecord) {
+        critical_section::with(|cs| {
+            let mut state = self.state.borrow_ref_mut(cs);
+            if state.records.push_front(record).is_err() {
+                state.dropped = state.dropped.saturating_add(1);
+            }
+        })
+    }
+
+    fn pop_front(&self) -> Option<QueuedRecord> {
+        critical_section::with(|cs| self.state.borrow_ref_mut(cs).records.pop_front())
+    }
+
+    /// Returns the number of dropped records and resets it to zero
+    fn take_dropped(&self) -> u32 {
+        critical_section::with(|cs| core::mem::take(&mut self.state.borrow_ref_mut(cs).dropped))
+    }
+
+    /// Adds back dropped records that could not be reported
+    fn restore_dropped(&self, dropped: u32) {
+        critical_section::with(|cs| {
+            let mut state = self.state.borrow_ref_mut(cs);
+            state.dropped = state.dropped.saturating_add(dropped);
+        })
+    }
+}
+
+impl<const N: usize> Default for DiagnosticLogger<N> {
+    fn default() -> Self {
+        DiagnosticLogger::new()
+    }
+}
+
+impl<const N: usize> Log for DiagnosticLogger<N> {
+    fn enabled(&self, _metadata: &Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &log::Record) {
+        // Format the text before entering the critical section
+        let mut writer = TruncatingWriter::default();
+        // An error here means that the text was truncated
+        let _ = write!(writer, "{}", record.args());
+        self.push_back(QueuedRecord {
+            severity: level_to_severity(record.level()),
+            text: writer.text,
+        });
+    }
+
+    fn flush(&self) {}
+}
+
+/// Publishes log records from a [`DiagnosticLogger`]
+///
+/// The publisher limits the number of records that it publishes each second. Records that exceed
+/// the limit stay in the logger's queue until a later call to [`poll`](#method.poll).
+///
+/// Basic steps:
+/// 1. Create a logger and install it using `log::set_logger`
+/// 2. Create a publisher using `DiagnosticPublisher::new`
+/// 3. Call `DiagnosticPublisher::poll` regularly
+pub struct DiagnosticPublisher<I> {
+    token: PublishToken<Record>,
+    /// The maximum number of records to publish in each one-second period
+    max_records_per_second: u32,
+    /// The start of the current rate limiting period
+    period_start: Option<I>,
+    /// The number of records published since period_start
+    period_records: u32,
+}
+
+impl<I: Instant> DiagnosticPublisher<I> {
+    /// Creates a publisher that publishes up to `max_records_per_second` records per second
+    pub fn new<N>(
+        node: &mut N,
+        max_records_per_second: u32,
+    ) -> Result<Self, StartSendError<<N::Transmitter as Transmitter<I>>::Error>>
+    where
+        N: Node<Instant = I>,
+    {
+        let token = node.start_publishing(
+            record_1_1::SUBJECT,
+            milliseconds(1000),
+            Priority::Optional.into(),
+        )?;
+        Ok(DiagnosticPublisher {
+            token,
+            max_records_per_second,
+            period_start: None,
+            period_records: 0,
+        })
+    }
+
+    /// Publishes records from a logger, if the rate limit allows
+    ///
+    /// The timestamp of each record is the synchronized time according to `clock` when the record
+    /// is published, or zero (unknown) if the synchronized time is not available.
+    /// Use [`NotSynchronized`](crate::time_sync::NotSynchronized) if there is no synchronized
+    /// time.
+    ///
+    /// If a record cannot be published because of a transmit error, it stays in the queue and
+    /// this function returns the error.
+    pub fn poll<N, C, const Q: usize>(
+        &mut self,
+        node: &mut N,
+        logger: &DiagnosticLogger<Q>,
+        clock: &C,
+    ) -> nb::Result<(), <N::Transmitter as Transmitter<I>>::Error>
+    where
+        N: Node<Instant = I>,
+        C: SynchronizedClock<I>,
+    {
+        let now = node.clock_mut().now();
+        match self.period_start {
+            Some(start)
+                if (milliseconds::<I::Duration>(1000) + start).overflow_safe_compare(&now)
+                    == Ordering::Greater => {}
+            _ => {
+                self.period_start = Some(now);
+                self.period_records = 0;
+            }
+        }
+        let timestamp = clock
+            .synchronized_time(now)
+            .unwrap_or(SynchronizedTimestamp::UNKNOWN);
+
+        while self.period_records < self.max_records_per_second {
+            let dropped = logger.take_dropped();
+            if dropped != 0 {
+                let mut writer = TruncatingWriter::default();
+                let _ = write!(writer, "{} log records dropped", dropped);
+                let message = Record {
+                    timestamp: SynchronizedTimestamp {
+                        microsecond: timestamp,
+                    },
+                    severity: Severity {
+                        value: Severity::WARNING,
+                    },
+                    text: writer.text,
+                };
+                if let Err(e) = node.publish(&self.token, &message) {
+                    logger.restore_dropped(dropped);
+                    return Err(e);
+                }
+                self.period_records += 1;
+                continue;
+            }
+
+            let record = match logger.pop_front() {
+                Some(record) => record,
+                None => break,
+            };
+            let message = Record {
+                timestamp: SynchronizedTimestamp {
+                    microsecond: timestamp,
+                },
+                severity: Severity {
+                    value: record.severity,
+                },
+                text: record.text,
+            };
+            if let Err(e) = node.publish(&self.token, &message) {
+                logger.push_front(QueuedRecord {
+                    severity: message.severity.value,
+                    text: message.text,
+                });
+                return Err(e);
+            }
+            self.period_records += 1;
+        }
+        Ok(())
+    }
+}
+
+/// Converts a log level into a `uavcan.diagnostic.Severity` value
+fn level_to_severity(level: Level) -> u8 {
+    match level {
+        Level::Error => Severity::ERROR,
+        Level::Warn => Severity::WARNING,
+        Level::Info => Severity::INFO,
+        Level::Debug => Severity::DEBUG,
+        Level::Trace => Severity::TRACE,
+    }
+}
+
+/// A formatting destination that keeps as much text as fits in a record
+///
+/// Text is truncated at a character boundary. After the text has been truncated, writing
+/// returns an error to stop formatting early.
+#[derive(Default)]
+struct TruncatingWriter {
+    text: Text,
+}
+
+impl Write for TruncatingWriter {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let available = self.text.capacity() - self.text.len();
+        if s.len() <= available {
+            self.text
+                .extend_from_slice(s.as_bytes())
+                .map_err(|_| fmt::Error)
+        } else {
+            let mut end = available;
+            while !s.is_char_boundary(end) {
+                end -= 1;
+            }
+            self.text
+                .extend_from_slice(&s.as_bytes()[..end])
+                .map_err(|_| fmt::Error)?;
+            Err(fmt::Error)
+        }
+    }
+}
@@ -9,9 +9,13 @@
 //!
 
 extern crate alloc;
+extern crate critical_section;
+extern crate embedded_storage;
 extern crate fallible_collections;
 extern crate hash32;
 extern crate heapless;
+#[cfg(feature = "std")]
+extern crate std;
 
 extern crate canadensis_core;
 extern crate canadensis_encoding;
@@ -28,11 +32,21 @@ pub mod encoding {
 pub use canadensis_core::nb;
 
 pub mod anonymous;
+#[cfg(feature = "async")]
+pub mod asynchronous;
+pub mod client;
+pub mod command;
+pub mod diagnostic;
+pub mod monitor;
 pub mod node;
 mod publisher;
 pub mod register;
 pub mod requester;
 mod serialize;
+pub mod simulation;
+pub mod statistics;
+pub mod time_sync;
+pub mod typed;
 
 use ::core::fmt::{Debug, Formatter};
 use ::core::marker::PhantomData;
@@ -44,7 +58,7 @@ use canadensis_core::time::{Clock, Instant};
 use canadensis_core::transfer::*;
 use canadensis_core::transport::{Receiver, Transmitter};
 use canadensis_core::{ServiceId, SubjectId};
-use canadensis_encoding::{Message, Request, Response, Serialize};
+use canadensis_encoding::{Deserialize, Message, Request, Response, Serialize};
 
 /// A token from a request that is needed to send a response
 pub struct ResponseToken<T: Transport> {
@@ -137,6 +151,24 @@ pub trait TransferHandler<I: Instant, T: Transport> {
         false
     }
 
+    /// Potentially handles a report that an outgoing transfer marked for loopback has been
+    /// transmitted
+    ///
+    /// The timestamp in the header is the time when the transfer was transmitted.
+    ///
+    /// This function returns true if the report was handled and should not be sent on to other
+    /// handlers.
+    ///
+    /// The default implementation does nothing and returns false.
+    fn handle_loopback<N: Node<Instant = I, Transport = T>>(
+        &mut self,
+        node: &mut N,
+        header: &Header<I, T>,
+    ) -> bool {
+        drop((node, header));
+        false
+    }
+
     /// Chains another handler after this handler and returns the combined handler
     ///
     /// For each incoming transfer, this handler will be given the transfer before the next handler.
@@ -180,6 +212,14 @@ where
         <H as TransferHandler<I, T>>::handle_response(self, node, transfer)
     }
 
+    fn handle_loopback<N: Node<Instant = I, Transport = T>>(
+        &mut self,
+        node: &mut N,
+        header: &Header<I, T>,
+    ) -> bool {
+        <H as TransferHandler<I, T>>::handle_loopback(self, node, header)
+    }
+
     fn chain<H1>(self, next: H1) -> TransferHandlerChain<Self, H1>
     where
         Self: Sized,
@@ -273,6 +313,19 @@ where
             self.handler1.handle_response(node, transfer)
         }
     }
+
+    fn handle_loopback<N: Node<Instant = I, Transport = T>>(
+        &mut self,
+        node: &mut N,
+        header: &Header<I, T>,
+    ) -> bool {
+        let handled = self.handler0.handle_loopback(node, header);
+        if handled {
+            true
+        } else {
+            self.handler1.handle_loopback(node, header)
+        }
+    }
 }
 
 /// A UAVCAN node
@@ -295,6 +348,9 @@ pub trait Node {
     ///
     /// If the frame completes a transfer, the transfer is passed to the provided handler.
     ///
+    /// This function also passes any reports of transmitted loopback transfers (see
+    /// [`publish_loopback`](#tymethod.publish_loopback)) to the handler.
+    ///
     /// This function returns an error if memory for the received transfer could not be allocated.
     /// Other types of errors, like an invalid frame format or an incorrect transfer CRC,
     /// may cause transfers to be lost but are not reported as errors here.
@@ -340,6 +396,20 @@ pub trait Node {
     where
         T: Message + Serialize;
 
+    /// Publishes a message and requests a report when it has been transmitted
+    ///
+    /// When the transport reports that the message has been transmitted,
+    /// [`receive`](#tymethod.receive) passes its header, with the time of transmission, to
+    /// [`TransferHandler::handle_loopback`]. If the transport or driver does not support loopback,
+    /// no report is provided.
+    fn publish_loopback<T>(
+        &mut self,
+        token: &PublishToken<T>,
+        payload: &T,
+    ) -> nb::Result<(), <Self::Transmitter as Transmitter<Self::Instant>>::Error>
+    where
+        T: Message + Serialize;
+
     /// Sets up to send requests for a service
     ///
     /// This also subscribes to the corresponding responses.
@@ -357,6 +427,8 @@ pub trait Node {
         T: Request;
 
     /// Stops sending requests for a service
+    ///
+    /// This also unsubscribes from the corresponding responses.
     fn stop_sending_requests<T>(&mut self, token: ServiceToken<T>)
     where
         T: Request;
@@ -384,6 +456,38 @@ pub trait Node {
         timeout: <<<Self as Node>::Clock as Clock>::Instant as Instant>::Duration,
     ) -> Result<(), <Self::Receiver as Receiver<Self::Instant>>::Error>;
 
+    /// Subscribes to messages of type `T` on a subject
+    ///
+    /// The maximum payload size is the extent of `T` (or its maximum size, if `T` is sealed).
+    ///
+    /// The returned [`SubscriptionToken`] can be used to create a
+    /// [`MessageAdapter`](typed::MessageAdapter) that deserializes incoming messages.
+    fn subscribe<T>(
+        &mut self,
+        subject: SubjectId,
+        timeout: <<<Self as Node>::Clock as Clock>::Instant as Instant>::Duration,
+    ) -> Result<SubscriptionToken<T>, <Self::Receiver as Receiver<Self::Instant>>::Error>
+    where
+        T: Message + Deserialize,
+    {
+        let payload_size_max = T::extent();
+        self.subscribe_message(subject, payload_size_max, timeout)?;
+        Ok(SubscriptionToken(subject, PhantomData))
+    }
+
+    /// Unsubscribes from messages on a subject
+    ///
+    /// This function has no effect if this node is not subscribed to the subject.
+    fn unsubscribe_message(&mut self, subject: SubjectId);
+
+    /// Unsubscribes from messages using a token returned by [`subscribe`](#method.subscribe)
+    fn unsubscribe<T>(&mut self, token: SubscriptionToken<T>)
+    where
+        T: Message,
+    {
+        self.unsubscribe_message(token.0);
+    }
+
     /// Subscribes to requests for a service
     fn subscribe_request(
         &mut self,
@@ -392,6 +496,11 @@ pub trait Node {
         timeout: <<<Self as Node>::Clock as Clock>::Instant as Instant>::Duration,
     ) -> Result<(), <Self::Receiver as Receiver<Self::Instant>>::Error>;
 
+    /// Unsubscribes from requests for a service
+    ///
+    /// This function has no effect if this node is not subscribed to requests for the service.
+    fn unsubscribe_request(&mut self, service: ServiceId);
+
     /// Responds to a service request
     ///
     /// This function requires a response token to match this response to its corresponding
@@ -445,7 +554,7 @@ impl<T> PublishToken<T> {
 }
 
 mod fmt_impl {
-    use super::PublishToken;
+    use super::{PublishToken, SubscriptionToken};
     use core::fmt::{Debug, Formatter, Result};
 
     impl<T> Debug for PublishToken<T> {
@@ -453,6 +562,12 @@ mod fmt_impl {
             f.debug_tuple("PublishToken").field(&self.0).finish()
         }
     }
+
+    impl<T> Debug for SubscriptionToken<T> {
+        fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+            f.debug_tuple("SubscriptionToken").field(&self.0).finish()
+        }
+    }
 }
 
 /// A token returned from [`Node::start_sending_requests`](Node#tymethod.start_sending_requests)
@@ -468,6 +583,19 @@ impl<T> ServiceToken<T> {
     }
 }
 
+/// A token returned from [`Node::subscribe`](Node#method.subscribe) that identifies a
+/// subscription to messages on a subject
+///
+/// The type parameter `T` is the type of message received.
+pub struct SubscriptionToken<T>(SubjectId, PhantomData<fn() -> T>);
+
+impl<T> SubscriptionToken<T> {
+    /// Returns the subject ID that this token is used to receive messages on
+    pub fn subject_id(&self) -> SubjectId {
+        self.0
+    }
+}
+
 /// Errors that may occur when starting to send messages or requests
 #[derive(Debug)]
 pub enum StartSendError<E> {
@@ -0,0 +1,328 @@
+//!
+//! Tracking the other nodes on a network
+//!
+//! A [`NodeMonitor`] listens for `uavcan.node.Heartbeat` messages and keeps a table of the nodes
+//! that are online. It can also request information from each new node using
+//! `uavcan.node.GetInfo`.
+//!
+
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+use crate::client::ServiceClient;
+use crate::{Node, StartSendError, TransferHandler};
+use canadensis_core::time::{milliseconds, Clock, Instant};
+use canadensis_core::transfer::{MessageTransfer, ServiceTransfer};
+use canadensis_core::transport::{Receiver, Transport};
+use canadensis_core::Priority;
+use canadensis_data_types::uavcan::node::get_info_1_0::{self, GetInfoRequest, GetInfoResponse};
+use canadensis_data_types::uavcan::node::health_1_0::Health;
+use canadensis_data_types::uavcan::node::heartbeat_1_0::{self, Heartbeat};
+use canadensis_data_types::uavcan::node::mode_1_0::Mode;
+use canadensis_encoding::{DataType, Deserialize};
+use heapless::Deque;
+
+/// The time after the last heartbeat when a node is considered offline, in milliseconds
+const OFFLINE_TIMEOUT_MS: u32 = Heartbeat::OFFLINE_TIMEOUT as u32 * 1000;
+
+/// The maximum time to wait for a node information response, in milliseconds
+const INFO_RESPONSE_TIMEOUT_MS: u32 = 1000;
+
+/// The number of node information requests to send to a node before giving up
+const INFO_ATTEMPTS: u8 = 3;
+
+/// Something that happened to a node on the network
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MonitorEvent<N> {
+    /// A heartbeat was received from a node that was not online
+    NodeOnline(N),
+    /// A node has not sent a heartbeat within `Heartbeat::OFFLINE_TIMEOUT`
+    NodeOffline(N),
+    /// A node sent a heartbeat with a lower uptime than its previous heartbeat
+    NodeRestarted(N),
+    /// Information about a node was received, and can be accessed using [`NodeStatus::info`]
+    InfoUpdated(N),
+}
+
+/// The status of a node that is online
+pub struct NodeStatus<I, T: Transport> {
+    node_id: T::NodeId,
+    uptime: u32,
+    health: Health,
+    mode: Mode,
+    vendor_specific_status_code: u8,
+    /// The time when the last heartbeat was received
+    last_seen: I,
+    info: Option<GetInfoResponse>,
+    /// The number of node information requests that have not received a response
+    info_attempts: u8,
+}
+
+impl<I: Instant, T: Transport> NodeStatus<I, T> {
+    /// Returns the ID of this node
+    pub fn node_id(&self) -> T::NodeId {
+        self.node_id.clone()
+    }
+    /// Returns the uptime from the last heartbeat, in seconds
+    pub fn uptime(&self) -> u32 {
+        self.uptime
+    }
+    /// Returns the health from the last heartbeat
+    pub fn health(&self) -> &Health {
+        &self.health
+    }
+    /// Returns the mode from the last heartbeat
+    pub fn mode(&self) -> &Mode {
+        &self.mode
+    }
+    /// Returns the vendor-specific status code from the last heartbeat
+    pub fn vendor_specific_status_code(&self) -> u8 {
+        self.vendor_specific_status_code
+    }
+    /// Returns the time when the last heartbeat was received
+    pub fn last_seen(&self) -> I {
+        self.last_seen
+    }
+    /// Returns the node information, if it has been received
+    ///
+    /// The information is cleared when the node restarts.
+    pub fn info(&self) -> Option<&GetInfoResponse> {
+        self.info.as_ref()
+    }
+
+    fn update(&mut self, heartbeat: &Heartbeat, timestamp: I) {
+        self.uptime = heartbeat.uptime;
+        self.health = Health {
+            value: heartbeat.health.value,
+        };
+        self.mode = Mode {
+            value: heartbeat.mode.value,
+        };
+        self.vendor_specific_status_code = heartbeat.vendor_specific_status_code;
+        self.last_seen = timestamp;
+    }
+}
+
+/// Keeps track of the nodes that are online
+///
+/// The monitor keeps the status of up to `N` nodes. If more nodes are online, the additional nodes
+/// are ignored until space is available. Up to `E` events are queued until the application
+/// reads them using [`pop_event`](#method.pop_event). If the event queue is full, new events
+/// are discarded.
+///
+/// When node information queries are enabled, the monitor sends a `uavcan.node.GetInfo` request
+/// to each node that comes online or restarts, one request at a time. It tries up to three times
+/// for each node.
+///
+/// Basic steps:
+/// 1. Create a monitor using `NodeMonitor::new`
+/// 2. Pass incoming transfers to the monitor (it implements `TransferHandler`)
+/// 3. Call `NodeMonitor::poll` regularly to detect nodes that have gone offline and send
+///    information requests
+/// 4. Call `NodeMonitor::pop_event` to find out what has happened
+pub struct NodeMonitor<I, T, const N: usize, const E: usize>
+where
+    I: Instant,
+    T: Transport,
+{
+    nodes: heapless::Vec<NodeStatus<I, T>, N>,
+    events: Deque<MonitorEvent<T::NodeId>, E>,
+    /// The client used to request node information, if enabled
+    info_client: Option<ServiceClient<I, T, GetInfoRequest, GetInfoResponse, 1>>,
+}
+
+impl<I, T, const N: usize, const E: usize> NodeMonitor<I, T, N, E>
+where
+    I: Instant,
+    T: Transport,
+    T::TransferId: PartialEq,
+{
+    /// Creates a monitor and subscribes to heartbeat messages
+    ///
+    /// If `query_info` is true, the monitor also requests information from new nodes.
+    pub fn new<M>(
+        node: &mut M,
+        query_info: bool,
+    ) -> Result<Self, StartSendError<<M::Receiver as Receiver<I>>::Error>>
+    where
+        M: Node<Instant = I, Transport = T>,
+    {
+        node.subscribe_message(
+            heartbeat_1_0::SUBJECT,
+            Heartbeat::extent(),
+            milliseconds(1000),
+        )?;
+        let info_client = if query_info {
+            let token = node.start_sending_requests(
+                get_info_1_0::SERVICE,
+                milliseconds(INFO_RESPONSE_TIMEOUT_MS),
+                GetInfoResponse::extent(),
+                Priority::Low.into(),
+            )?;
+            Some(ServiceClient::new(
+                token,
+                milliseconds(INFO_RESPONSE_TIMEOUT_MS),
+            ))
+        } else {
+            None
+        };
+        Ok(NodeMonitor {
+            nodes: heapless::Vec::new(),
+            events: Deque::new(),
+            info_client,
+        })
+    }
+
+    /// Returns the status of a node, if it is online
+    pub fn node(&self, node_id: &T::NodeId) -> Option<&NodeStatus<I, T>> {
+        self.nodes.iter().find(|status| status.node_id == *node_id)
+    }
+
+    /// Returns an iterator over the nodes that are online
+    pub fn nodes(&self) -> impl Iterator<Item = &NodeStatus<I, T>> {
+        self.nodes.iter()
+    }
+
+    /// Removes and returns the oldest event
+    pub fn pop_event(&mut self) -> Option<MonitorEvent<T::NodeId>> {
+        self.events.pop_front()
+    }
+
+    /// Removes nodes that have gone offline and sends a node information request if necessary
+    pub fn poll<M>(&mut self, node: &mut M)
+    where
+        M: Node<Instant = I, Transport = T>,
+    {
+        let now = node.clock_mut().now();
+        let offline_timeout = milliseconds::<I::Duration>(OFFLINE_TIMEOUT_MS);
+        let mut i = 0;
+        while i < self.nodes.len() {
+            let deadline = offline_timeout + self.nodes[i].last_seen;
+            if deadline.overflow_safe_compare(&now) == Ordering::Less {
+                let status = self.nodes.swap_remove(i);
+                log::debug!("Node {:?} offline", status.node_id);
+                self.push_event(MonitorEvent::NodeOffline(status.node_id));
+            } else {
+                i += 1;
+            }
+        }
+
+        if let Some(client) = self.info_client.as_mut() {
+            while let Some(error) = client.check_timeouts(now) {
+                log::debug!("Node information request failed: {:?}", error);
+            }
+            if client.pending_count() == 0 {
+                if let Some(status) = self
+                    .nodes
+                    .iter_mut()
+                    .find(|status| status.info.is_none() && status.info_attempts < INFO_ATTEMPTS)
+                {
+                    match client.send(node, &GetInfoRequest {}, status.node_id.clone()) {
+                        Ok(_) => status.info_attempts += 1,
+                        Err(e) => log::warn!("Failed to send node information request: {:?}", e),
+                    }
+                }
+            }
+        }
+    }
+
+    fn handle_heartbeat(&mut self, source: T::NodeId, heartbeat: &Heartbeat, timestamp: I) {
+        match self
+            .nodes
+            .iter_mut()
+            .find(|status| status.node_id == source)
+        {
+            Some(status) => {
+                let restarted = heartbeat.uptime < status.uptime;
+                status.update(heartbeat, timestamp);
+                if restarted {
+                    // The software may have changed
+                    status.info = None;
+                    status.info_attempts = 0;
+                    self.push_event(MonitorEvent::NodeRestarted(source));
+                }
+            }
+            None => {
+                let mut status = NodeStatus {
+                    node_id: source.clone(),
+                    uptime: 0,
+                    health: Health { value: 0 },
+                    mode: Mode { value: 0 },
+                    vendor_specific_status_code: 0,
+                    last_seen: timestamp,
+                    info: None,
+                    info_attempts: 0,
+                };
+                status.update(heartbeat, timestamp);
+                if self.nodes.push(status).is_ok() {
+                    self.push_event(MonitorEvent::NodeOnline(source));
+                } else {
+                    log::warn!("Node table full, ignoring node {:?}", source);
+                }
+            }
+        }
+    }
+
+    fn push_event(&mut self, event: MonitorEvent<T::NodeId>) {
+        if let Err(event) = self.events.push_back(event) {
+            log::warn!("Node monitor event queue full, discarding {:?}", event);
+        }
+    }
+}
+
+impl<I, T, const N: usize, const E: usize> TransferHandler<I, T> for NodeMonitor<I, T, N, E>
+where
+    I: Instant,
+    T: Transport,
+    T::TransferId: PartialEq,
+{
+    fn handle_message<M: Node<Instant = I, Transport = T>>(
+        &mut self,
+        _node: &mut M,
+        transfer: &MessageTransfer<Vec<u8>, I, T>,
+    ) -> bool {
+        if transfer.header.subject != heartbeat_1_0::SUBJECT {
+            return false;
+        }
+        // Anonymous nodes do not have node IDs to track
+        if let Some(source) = transfer.header.source.clone() {
+            match Heartbeat::deserialize_from_bytes(&transfer.payload) {
+                Ok(heartbeat) => {
+                    self.handle_heartbeat(source, &heartbeat, transfer.header.timestamp)
+                }
+                Err(e) => log::debug!("Invalid heartbeat from {:?}: {:?}", source, e),
+            }
+        }
+        true
+    }
+
+    fn handle_response<M: Node<Instant = I, Transport = T>>(
+        &mut self,
+        _node: &mut M,
+        transfer: &ServiceTransfer<Vec<u8>, I, T>,
+    ) -> bool {
+        let client = match self.info_client.as_mut() {
+            Some(client) => client,
+            None => return false,
+        };
+        match client.handle_response(transfer) {
+            Some(Ok(response)) => {
+                let source = response.header.source;
+                if let Some(status) = self
+                    .nodes
+                    .iter_mut()
+                    .find(|status| status.node_id == source)
+                {
+                    status.info = Some(response.payload);
+                    self.push_event(MonitorEvent::InfoUpdated(source));
+                }
+                true
+            }
+            Some(Err(e)) => {
+                log::debug!("Invalid node information response: {:?}", e);
+                true
+            }
+            None => false,
+        }
+    }
+}
@@ -14,16 +14,25 @@ use canadensis_data_types::uavcan::node::port::list_0_1::{self, List};
 use canadensis_data_types::uavcan::node::port::service_id_list_0_1::ServiceIDList;
 use canadensis_data_types::uavcan::node::port::subject_id_1_0;
 use canadensis_data_types::uavcan::node::port::subject_id_list_0_1::SubjectIDList;
+use canadensis_data_types::uavcan::primitive::empty_1_0::Empty;
 use canadensis_encoding::bits::BitArray;
 use canadensis_encoding::{Message, Request, Response, Serialize};
 
+/// The maximum number of subject IDs in the sparse list representation of a `SubjectIDList`
+const SPARSE_LIST_CAPACITY: usize = 255;
+
 /// A node that provides all basic application-layer functionality
 ///
 /// This node performs the following functions:
 ///
 /// * Sending a `uavcan.node.Heartbeat` every second
 /// * Responding to `uavcan.node.GetInfo` requests
-/// * Sending a `uavcan.node.port.List` message every 10 seconds
+/// * Sending a `uavcan.node.port.List` message every 10 seconds, and also when ports are added
+///   or removed
+///
+/// The port list includes all publishers, subscriptions, clients, and servers that are started
+/// or stopped through the BasicNode. Ports that are set up directly on the underlying node are not
+/// included.
 ///
 /// A BasicNode uses up two publisher slots in the underlying node.
 ///
@@ -35,6 +44,8 @@ where
     node: MinimalNode<N>,
     port_list_token: PublishToken<List>,
     port_list: List,
+    /// True if the port list has changed since it was last published
+    port_list_changed: bool,
     node_info: GetInfoResponse,
     seconds_since_port_list_published: u8,
 }
@@ -105,25 +116,38 @@ where
             node: minimal,
             port_list_token,
             port_list,
+            // Publish the initial port list soon after startup
+            port_list_changed: true,
             node_info,
             seconds_since_port_list_published: 0,
         })
     }
 
     /// This function must be called once per second to send heartbeat and port list messages
+    ///
+    /// The port list is published every 10 seconds. If the port list has changed, it is published
+    /// at the next call to this function, so that changes are published at most once per second.
     pub fn run_per_second_tasks(
         &mut self,
     ) -> nb::Result<(), <N::Transmitter as Transmitter<N::Instant>>::Error> {
         self.node.run_per_second_tasks()?;
-        if self.seconds_since_port_list_published == 10 {
-            self.seconds_since_port_list_published = 1;
+        if self.port_list_changed
+            || self.seconds_since_port_list_published >= List::MAX_PUBLICATION_PERIOD
+        {
             self.publish_port_list()?;
+            self.port_list_changed = false;
+            self.seconds_since_port_list_published = 1;
         } else {
             self.seconds_since_port_list_published += 1;
         }
         Ok(())
     }
 
+    /// Returns the current port list
+    pub fn port_list(&self) -> &List {
+        &self.port_list
+    }
+
     fn publish_port_list(
         &mut self,
     ) -> nb::Result<(), <N::Transmitter as Transmitter<N::Instant>>::Error> {
@@ -193,7 +217,7 @@ where
             .node_mut()
             .start_publishing(subject, timeout, priority)?;
         // Record that this port is in use
-        insert_into_list(&mut self.port_list.publishers, subject);
+        self.port_list_changed |= insert_into_list(&mut self.port_list.publishers, subject);
         Ok(token)
     }
 
@@ -203,7 +227,7 @@ where
     {
         let subject = token.subject_id();
         self.node.node_mut().stop_publishing(token);
-        remove_from_list(&mut self.port_list.publishers, subject);
+        self.port_list_changed |= remove_from_list(&mut self.port_list.publishers, subject);
     }
 
     fn publish<T>(
@@ -217,6 +241,17 @@ where
         self.node.node_mut().publish(token, payload)
     }
 
+    fn publish_loopback<T>(
+        &mut self,
+        token: &PublishToken<T>,
+        payload: &T,
+    ) -> nb::Result<(), <N::Transmitter as Transmitter<N::Instant>>::Error>
+    where
+        T: Message + Serialize,
+    {
+        self.node.node_mut().publish_loopback(token, payload)
+    }
+
     fn start_sending_requests<T>(
         &mut self,
         service: ServiceId,
@@ -234,7 +269,7 @@ where
             priority,
         )?;
         // Record that this node is a client for the service
-        self.port_list.clients.mask.set(service.into(), true);
+        self.port_list_changed |= set_service(&mut self.port_list.clients, service, true);
 
         Ok(token)
     }
@@ -245,7 +280,7 @@ where
     {
         let service_id = token.service_id();
         self.node.node_mut().stop_sending_requests(token);
-        self.port_list.clients.mask.set(service_id.into(), false);
+        self.port_list_changed |= set_service(&mut self.port_list.clients, service_id, false);
     }
 
     fn send_request<T>(
@@ -276,11 +311,16 @@ where
             .subscribe_message(subject, payload_size_max, timeout)?;
 
         // Record that this node is subscribed
-        insert_into_list(&mut self.port_list.subscribers, subject);
+        self.port_list_changed |= insert_into_list(&mut self.port_list.subscribers, subject);
 
         Ok(())
     }
 
+    fn unsubscribe_message(&mut self, subject: SubjectId) {
+        self.node.node_mut().unsubscribe_message(subject);
+        self.port_list_changed |= remove_from_list(&mut self.port_list.subscribers, subject);
+    }
+
     fn subscribe_request(
         &mut self,
         service: ServiceId,
@@ -292,11 +332,16 @@ where
             .subscribe_request(service, payload_size_max, timeout)?;
 
         // Record that this node provides the service
-        self.port_list.servers.mask.set(service.into(), true);
+        self.port_list_changed |= set_service(&mut self.port_list.servers, service, true);
 
         Ok(())
     }
 
+    fn unsubscribe_request(&mut self, service: ServiceId) {
+        self.node.node_mut().unsubscribe_request(service);
+        self.port_list_changed |= set_service(&mut self.port_list.servers, service, false);
+    }
+
     fn send_response<T>(
         &mut self,
         token: ResponseToken<Self::Transport>,
@@ -399,46 +444,67 @@ where
     }
 }
 
-fn insert_into_list(subject_list: &mut SubjectIDList, subject: SubjectId) {
-    match subject_list {
+/// Adds a subject to a list, and returns true if the list changed
+fn insert_into_list(subject_list: &mut SubjectIDList, subject: SubjectId) -> bool {
+    let changed = match subject_list {
         SubjectIDList::Mask(mask) => {
+            let in_list = mask.get(subject.into());
             mask.set(subject.into(), true);
+            !in_list
         }
         SubjectIDList::SparseList(list) => {
             // Check that this subject is not already in the list
-            if !list.iter().any(|in_list| in_list.value == subject.into()) {
-                match list.push(subject_id_1_0::SubjectID {
-                    value: subject.into(),
-                }) {
-                    Ok(_) => {}
-                    Err(_) => {
-                        // The list is full, need to switch to the mask representation
-                        let mut mask = BitArray::new(SubjectIDList::CAPACITY as usize);
-                        for port in list.iter() {
-                            mask.set(port.value.into(), true);
-                        }
-                        // Set the bit for the topic that's now subscribed
-                        mask.set(subject.into(), true);
-                        *subject_list = SubjectIDList::Mask(mask);
+            if list
+                .iter()
+                .any(|in_list| in_list.value == u16::from(subject))
+            {
+                false
+            } else {
+                if list
+                    .push(subject_id_1_0::SubjectID {
+                        value: subject.into(),
+                    })
+                    .is_err()
+                {
+                    // The list is full, need to switch to the mask representation
+                    let mut mask = BitArray::new(SubjectIDList::CAPACITY as usize);
+                    for port in list.iter() {
+                        mask.set(port.value.into(), true);
                     }
+                    // Set the bit for the topic that's now subscribed
+                    mask.set(subject.into(), true);
+                    *subject_list = SubjectIDList::Mask(mask);
                 }
+                true
             }
         }
-        SubjectIDList::Total(_) => { /* All subject IDs in use, can't add */ }
+        SubjectIDList::Total(_) => {
+            // All subject IDs in use, can't add
+            false
+        }
     };
+    use_compact_encoding(subject_list);
+    changed
 }
 
-fn remove_from_list(subject_list: &mut SubjectIDList, subject: SubjectId) {
-    match subject_list {
+/// Removes a subject from a list, and returns true if the list changed
+fn remove_from_list(subject_list: &mut SubjectIDList, subject: SubjectId) -> bool {
+    let changed = match subject_list {
         SubjectIDList::Mask(mask) => {
+            let in_list = mask.get(subject.into());
             mask.set(subject.into(), false);
+            in_list
         }
         SubjectIDList::SparseList(list) => {
-            if let Some(position) = list
+            match list
                 .iter()
                 .position(|id_in_list| id_in_list.value == u16::from(subject))
             {
-                list.swap_remove(position);
+                Some(position) => {
+                    list.swap_remove(position);
+                    true
+                }
+                None => false,
             }
         }
         SubjectIDList::Total(_) => {
@@ -447,6 +513,40 @@ fn remove_from_list(subject_list: &mut SubjectIDList, subject: SubjectId) {
             mask.fill(true);
             mask.set(subject.into(), false);
             *subject_list = SubjectIDList::Mask(mask);
+            true
+        }
+    };
+    use_compact_encoding(subject_list);
+    changed
+}
+
+/// Adds a service to or removes a service from a list, and returns true if the list changed
+fn set_service(service_list: &mut ServiceIDList, service: ServiceId, in_list: bool) -> bool {
+    let changed = service_list.mask.get(service.into()) != in_list;
+    service_list.mask.set(service.into(), in_list);
+    changed
+}
+
+/// Switches a subject list that uses the mask representation to the sparse list or total
+/// representation, if that representation can hold the subjects in the list
+///
+/// A sparse list is always smaller than a mask, so the mask is used only when the sparse list
+/// would be too long.
+fn use_compact_encoding(subject_list: &mut SubjectIDList) {
+    if let SubjectIDList::Mask(mask) = subject_list {
+        let count = mask.iter().filter(|&in_list| in_list).count();
+        if count == usize::from(SubjectIDList::CAPACITY) {
+            *subject_list = SubjectIDList::Total(Empty {});
+        } else if count <= SPARSE_LIST_CAPACITY {
+            let list = mask
+                .iter()
+                .enumerate()
+                .filter(|&(_, in_list)| in_list)
+                .map(|(subject, _)| subject_id_1_0::SubjectID {
+                    value: subject as u16,
+                })
+                .collect();
+            *subject_list = SubjectIDList::SparseList(list);
         }
     }
 }
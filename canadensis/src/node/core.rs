@@ -169,6 +169,9 @@ where
         if let Some(transfer) = self.receiver.receive(self.clock.now(), &mut self.driver)? {
             self.handle_incoming_transfer(transfer, handler)
         }
+        while let Some(header) = self.transmitter.pop_loopback(&mut self.driver) {
+            handler.handle_loopback(self, &header);
+        }
         Ok(())
     }
 
@@ -214,6 +217,29 @@ where
             &mut self.clock,
             token.0,
             payload,
+            false,
+            &mut self.transmitter,
+            &mut self.driver,
+        )
+    }
+
+    fn publish_loopback<M>(
+        &mut self,
+        token: &PublishToken<M>,
+        payload: &M,
+    ) -> nb::Result<(), T::Error>
+    where
+        M: Message + Serialize,
+    {
+        let publisher = self
+            .publishers
+            .get_mut(&token.0)
+            .expect("Bug: Token exists but no publisher");
+        publisher.publish(
+            &mut self.clock,
+            token.0,
+            payload,
+            true,
             &mut self.transmitter,
             &mut self.driver,
         )
@@ -269,6 +295,8 @@ where
         M: Request,
     {
         self.requesters.remove(&token.0);
+        self.receiver
+            .unsubscribe_response(token.0, &mut self.driver);
     }
 
     fn send_request<M>(
@@ -304,6 +332,10 @@ where
             .subscribe_message(subject, payload_size_max, timeout, &mut self.driver)
     }
 
+    fn unsubscribe_message(&mut self, subject: SubjectId) {
+        self.receiver.unsubscribe_message(subject, &mut self.driver);
+    }
+
     fn subscribe_request(
         &mut self,
         service: ServiceId,
@@ -320,6 +352,10 @@ where
         })
     }
 
+    fn unsubscribe_request(&mut self, service: ServiceId) {
+        self.receiver.unsubscribe_request(service, &mut self.driver);
+    }
+
     fn send_response<M>(
         &mut self,
         token: ResponseToken<Self::Transport>,
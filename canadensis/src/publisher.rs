@@ -43,6 +43,7 @@ impl<I: Instant, T: Transmitter<I>> Publisher<I, T> {
         clock: &mut C,
         subject: SubjectId,
         payload: &M,
+        loopback: bool,
         transmitter: &mut T,
         driver: &mut T::Driver,
     ) -> nb::Result<(), T::Error>
@@ -55,24 +56,22 @@ impl<I: Instant, T: Transmitter<I>> Publisher<I, T> {
         // Part 1: Serialize
         do_serialize(payload, |payload_bytes| {
             // Part 2: Split into frames and put frames in the queue
-            self.send_payload(subject, payload_bytes, deadline, transmitter, clock, driver)
+            let transfer = self.make_transfer(subject, payload_bytes, deadline);
+            if loopback {
+                transmitter.push_loopback(transfer, clock, driver)
+            } else {
+                transmitter.push(transfer, clock, driver)
+            }
         })
     }
 
-    pub fn send_payload<C>(
+    /// Assembles a transfer and increments the transfer ID
+    fn make_transfer<'p>(
         &mut self,
         subject: SubjectId,
-        payload: &[u8],
+        payload: &'p [u8],
         deadline: I,
-        transmitter: &mut T,
-        clock: &mut C,
-        driver: &mut T::Driver,
-    ) -> nb::Result<(), T::Error>
-    where
-        I: Clone,
-        C: Clock<Instant = I>,
-    {
-        // Assemble the transfer
+    ) -> Transfer<&'p [u8], I, T::Transport> {
         let transfer = Transfer {
             header: Header::Message(MessageHeader {
                 timestamp: deadline,
@@ -84,8 +83,7 @@ impl<I: Instant, T: Transmitter<I>> Publisher<I, T> {
             payload,
         };
         self.next_transfer_id = self.next_transfer_id.clone().increment();
-
-        transmitter.push(transfer, clock, driver)
+        transfer
     }
 }
 
@@ -2,12 +2,27 @@
 //! Node configuration registers that can be accessed from other nodes
 
 pub mod basic;
+#[cfg(any(feature = "yaml", feature = "json"))]
+pub mod batch;
+pub mod client;
+#[cfg(any(feature = "toml", feature = "yaml", feature = "json"))]
+mod document;
+#[cfg(feature = "std")]
+pub mod dynamic;
+#[cfg(feature = "std")]
+pub mod environment;
+pub mod names;
+pub mod port;
+pub mod storage;
 
 use alloc::vec::Vec;
+use core::fmt;
 use core::str;
 
+use crate::register::names::NameEntry;
+use crate::time_sync::{NotSynchronized, SynchronizedClock};
 use crate::{Node, ResponseToken, TransferHandler};
-use canadensis_core::time::{milliseconds, Instant};
+use canadensis_core::time::{milliseconds, Clock, Instant};
 use canadensis_core::transfer::ServiceTransfer;
 use canadensis_core::transport::{Receiver, Transport};
 use canadensis_data_types::uavcan::register::access_1_0::{self, AccessRequest, AccessResponse};
@@ -15,7 +30,7 @@ use canadensis_data_types::uavcan::register::list_1_0::{self, ListRequest, ListR
 use canadensis_data_types::uavcan::register::name_1_0::Name;
 use canadensis_data_types::uavcan::register::value_1_0::Value;
 use canadensis_data_types::uavcan::time::synchronized_timestamp_1_0::SynchronizedTimestamp;
-use canadensis_encoding::Deserialize;
+use canadensis_encoding::{Deserialize, Serialize, WriteCursor};
 
 pub use canadensis_derive_register_block::RegisterBlock;
 
@@ -43,6 +58,33 @@ pub use canadensis_derive_register_block::RegisterBlock;
 ///     }
 /// }
 /// ```
+///
+/// The names, access flags, and default values can also be specified with `#[register]`
+/// attributes. This implements `Default` and [`DefaultRegisterBlock`], and allows nested blocks:
+///
+/// ```
+/// # use canadensis::register::basic::{SimpleRegister, RegisterString};
+/// # use canadensis::register::RegisterBlock;
+/// #[derive(RegisterBlock)]
+/// struct MotorRegisters {
+///     #[register(name = "kp", mutable, persistent, default = 1.0)]
+///     kp: SimpleRegister<f32>,
+/// }
+///
+/// #[derive(RegisterBlock)]
+/// struct Registers {
+///     #[register(name = "uavcan.node.id", mutable, persistent, default = 65535)]
+///     node_id: SimpleRegister<u16>,
+///     #[register(name = "uavcan.node.description", mutable, persistent)]
+///     description: SimpleRegister<RegisterString>,
+///     // Registers motor.0.kp and motor.1.kp
+///     #[register(block, prefix = "motor")]
+///     motors: [MotorRegisters; 2],
+/// }
+///
+/// let registers = Registers::default();
+/// assert_eq!("motor.1.kp", registers.register_by_index(3).unwrap().name());
+/// ```
 pub trait RegisterBlock {
     /// Returns a reference to the register at the provided index
     ///
@@ -56,6 +98,66 @@ pub trait RegisterBlock {
     fn register_by_name_mut(&mut self, name: &str) -> Option<&mut dyn Register>;
 }
 
+/// A register block that knows the names and default values of its registers
+///
+/// `#[derive(RegisterBlock)]` implements this trait and `Default` for structs where every field
+/// has a `#[register]` attribute. Blocks that implement this trait can be nested in other
+/// derived blocks.
+pub trait DefaultRegisterBlock: RegisterBlock {
+    /// The names of the registers in this block
+    const NAMES: &'static [NameEntry];
+
+    /// Creates a block with the default register values, adding a prefix to each register name
+    ///
+    /// The prefix must be empty or end with `.`.
+    fn with_prefix(prefix: &str) -> Self;
+}
+
+/// Two register blocks combined into one
+///
+/// The registers of the first block come before the registers of the second block.
+impl<A, B> RegisterBlock for (A, B)
+where
+    A: RegisterBlock,
+    B: RegisterBlock,
+{
+    fn register_by_index(&self, index: usize) -> Option<&dyn Register> {
+        let first_count = register_count(&self.0);
+        if index < first_count {
+            self.0.register_by_index(index)
+        } else {
+            self.1.register_by_index(index - first_count)
+        }
+    }
+
+    fn register_by_index_mut(&mut self, index: usize) -> Option<&mut dyn Register> {
+        let first_count = register_count(&self.0);
+        if index < first_count {
+            self.0.register_by_index_mut(index)
+        } else {
+            self.1.register_by_index_mut(index - first_count)
+        }
+    }
+
+    fn register_by_name_mut(&mut self, name: &str) -> Option<&mut dyn Register> {
+        let in_first = (0..)
+            .map_while(|index| self.0.register_by_index(index))
+            .any(|register| register.name() == name);
+        if in_first {
+            self.0.register_by_name_mut(name)
+        } else {
+            self.1.register_by_name_mut(name)
+        }
+    }
+}
+
+/// Returns the number of registers in a block
+fn register_count<B: RegisterBlock>(block: &B) -> usize {
+    (0..)
+        .take_while(|&index| block.register_by_index(index).is_some())
+        .count()
+}
+
 /// Information about how a register can be accessed
 #[derive(Debug, Clone)]
 pub struct Access {
@@ -92,7 +194,8 @@ pub trait Register {
     /// if this register is not mutable.
     ///
     /// This function returns an error if the provided value does not have an appropriate type
-    /// for this register.
+    /// for this register, if the value is out of range or otherwise invalid, or if the value
+    /// could not be stored.
     ///
     /// If this function returns an error, the value of this register must be the same as before
     /// the call to write().
@@ -100,14 +203,65 @@ pub trait Register {
     /// # Panics
     ///
     /// This function may panic if `self.access()` returned a value with `mutable` set to false.
+    /// Registers that can never be written may return [`WriteError::ReadOnly`] instead.
     fn write(&mut self, value: &Value) -> Result<(), WriteError>;
 }
 
 /// Errors that can occur when attempting to write a register
-#[derive(Debug)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum WriteError {
     /// The type of the value, or the number of values in an array, was incorrect
     Type,
+    /// The value had an appropriate type, but was out of range or rejected by a validator
+    Invalid,
+    /// The register is not mutable
+    ReadOnly,
+    /// The value was valid, but could not be saved to the storage that backs the register
+    Storage,
+}
+
+impl fmt::Display for WriteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WriteError::Type => f.write_str("Incorrect value type or length"),
+            WriteError::Invalid => f.write_str("Value out of range or invalid"),
+            WriteError::ReadOnly => f.write_str("Register is not mutable"),
+            WriteError::Storage => f.write_str("Failed to store value"),
+        }
+    }
+}
+
+/// Receives notifications when another node writes a register through a [`RegisterHandler`]
+///
+/// This trait is implemented for all `FnMut` closures that accept a register name, the old
+/// value, and the new value. Closures are only notified of successful writes.
+pub trait WriteHook {
+    /// Called after another node has successfully written a register
+    fn register_written(&mut self, name: &str, old_value: &Value, new_value: &Value);
+
+    /// Called when another node attempted to write a register, but the write failed
+    ///
+    /// The default implementation does nothing.
+    fn write_failed(&mut self, name: &str, value: &Value, error: WriteError) {
+        let _ = (name, value, error);
+    }
+}
+
+impl<F> WriteHook for F
+where
+    F: FnMut(&str, &Value, &Value),
+{
+    fn register_written(&mut self, name: &str, old_value: &Value, new_value: &Value) {
+        self(name, old_value, new_value)
+    }
+}
+
+/// A write hook that ignores all writes
+#[derive(Debug, Default, Copy, Clone)]
+pub struct NoWriteHook;
+
+impl WriteHook for NoWriteHook {
+    fn register_written(&mut self, _name: &str, _old_value: &Value, _new_value: &Value) {}
 }
 
 /// Handles access requests for registers
@@ -120,8 +274,40 @@ pub enum WriteError {
 /// 3. When calling `accept` on the node, pass the register handler as a transfer handler
 ///    (or use some other method to pass incoming service requests to the register handler).
 ///    This lets the register handler process requests and send responses.
-pub struct RegisterHandler<B> {
+///
+/// A handler created with [`with_clock`](#method.with_clock) uses a
+/// [`SynchronizedClock`] to timestamp access responses. Otherwise, the timestamps are zero
+/// (unknown).
+///
+/// A [`WriteHook`] added with [`with_write_hook`](#method.with_write_hook) is notified when
+/// another node writes a register:
+///
+/// ```
+/// # use canadensis::register::basic::SimpleRegister;
+/// # use canadensis::register::{RegisterBlock, RegisterHandler};
+/// # use canadensis::register::client::value_as;
+/// # use canadensis_data_types::uavcan::register::value_1_0::Value;
+/// #[derive(RegisterBlock)]
+/// struct Registers {
+///     #[register(name = "controller.gain", mutable, persistent, default = 1.0)]
+///     gain: SimpleRegister<f32>,
+/// }
+///
+/// let handler = RegisterHandler::new(Registers::default()).with_write_hook(
+///     |name: &str, _old: &Value, new: &Value| {
+///         if let Ok(gain) = value_as::<f32>(new) {
+///             println!("{} changed to {}", name, gain);
+///         }
+///     },
+/// );
+/// ```
+pub struct RegisterHandler<B, C = NotSynchronized, H = NoWriteHook> {
     block: B,
+    clock: C,
+    hook: H,
+    /// If another node has written a persistent register since the last call to
+    /// `take_persistent_modified`
+    persistent_modified: bool,
 }
 
 impl<B> RegisterHandler<B>
@@ -130,7 +316,49 @@ where
 {
     /// Creates a register handler
     pub fn new(block: B) -> Self {
-        RegisterHandler { block }
+        RegisterHandler {
+            block,
+            clock: NotSynchronized,
+            hook: NoWriteHook,
+            persistent_modified: false,
+        }
+    }
+}
+
+impl<B, C> RegisterHandler<B, C>
+where
+    B: RegisterBlock,
+{
+    /// Creates a register handler that timestamps access responses using a synchronized clock
+    pub fn with_clock(block: B, clock: C) -> Self {
+        RegisterHandler {
+            block,
+            clock,
+            hook: NoWriteHook,
+            persistent_modified: false,
+        }
+    }
+}
+
+impl<B, C, H> RegisterHandler<B, C, H>
+where
+    B: RegisterBlock,
+    H: WriteHook,
+{
+    /// Returns this handler with a hook that is notified when other nodes write registers
+    ///
+    /// The hook is called after each write request has been handled, before the response
+    /// is sent. It replaces any hook that was set before.
+    pub fn with_write_hook<H2>(self, hook: H2) -> RegisterHandler<B, C, H2>
+    where
+        H2: WriteHook,
+    {
+        RegisterHandler {
+            block: self.block,
+            clock: self.clock,
+            hook,
+            persistent_modified: self.persistent_modified,
+        }
     }
 
     /// Subscribes to register list and register access requests
@@ -161,12 +389,47 @@ where
         &mut self.block
     }
 
-    fn handle_access_request(&mut self, request: &AccessRequest) -> AccessResponse {
+    /// Returns a reference to the synchronized clock
+    pub fn clock(&self) -> &C {
+        &self.clock
+    }
+    /// Returns a mutable reference to the synchronized clock
+    ///
+    /// This can be used to update the clock, for example with a new
+    /// [`TimeReference`](crate::time_sync::TimeReference).
+    pub fn clock_mut(&mut self) -> &mut C {
+        &mut self.clock
+    }
+
+    /// Returns a reference to the write hook
+    pub fn write_hook(&self) -> &H {
+        &self.hook
+    }
+    /// Returns a mutable reference to the write hook
+    pub fn write_hook_mut(&mut self) -> &mut H {
+        &mut self.hook
+    }
+
+    /// Returns true if another node has successfully written a persistent register since the
+    /// last call to this function
+    ///
+    /// This can be used to save persistent registers some time after they change (see
+    /// [`RegisterPersistence::schedule_save`](storage::RegisterPersistence::schedule_save)).
+    pub fn take_persistent_modified(&mut self) -> bool {
+        core::mem::replace(&mut self.persistent_modified, false)
+    }
+
+    fn handle_access_request(&mut self, request: &AccessRequest, timestamp: u64) -> AccessResponse {
         match str::from_utf8(&request.name.name) {
             Ok(register_name) => {
                 log::debug!("Handling access request for {}", register_name);
                 if let Some(register) = self.block.register_by_name_mut(register_name) {
-                    register_handle_access(register, request)
+                    let (response, written) =
+                        register_handle_access(register, request, timestamp, &mut self.hook);
+                    if written && response.persistent {
+                        self.persistent_modified = true;
+                    }
+                    response
                 } else {
                     // Register doesn't exist, return empty
                     AccessResponse {
@@ -224,30 +487,56 @@ where
     }
 }
 
-fn register_handle_access(register: &mut dyn Register, request: &AccessRequest) -> AccessResponse {
+fn register_handle_access<H>(
+    register: &mut dyn Register,
+    request: &AccessRequest,
+    timestamp: u64,
+    hook: &mut H,
+) -> (AccessResponse, bool)
+where
+    H: WriteHook,
+{
     let access = register.access();
-    if access.mutable
-        && !matches!(
-            request.value,
-            Value::Empty(canadensis_data_types::uavcan::primitive::empty_1_0::Empty {})
-        )
-    {
-        // Write errors are reported by returning the unmodified register value.
-        let _ = register.write(&request.value);
+    let mut written = false;
+    if !matches!(
+        request.value,
+        Value::Empty(canadensis_data_types::uavcan::primitive::empty_1_0::Empty {})
+    ) {
+        // Write errors are reported to the other node by returning the unmodified register value.
+        if access.mutable {
+            let old_value = register.read();
+            match register.write(&request.value) {
+                Ok(()) => {
+                    written = true;
+                    hook.register_written(register.name(), &old_value, &register.read());
+                }
+                Err(e) => {
+                    log::debug!("Failed to write register {}: {}", register.name(), e);
+                    hook.write_failed(register.name(), &request.value, e);
+                }
+            }
+        } else {
+            hook.write_failed(register.name(), &request.value, WriteError::ReadOnly);
+        }
     }
     // Now read the register and return its properties
-    AccessResponse {
-        timestamp: SynchronizedTimestamp { microsecond: 0 },
+    let response = AccessResponse {
+        timestamp: SynchronizedTimestamp {
+            microsecond: timestamp,
+        },
         mutable: access.mutable,
         persistent: access.persistent,
         value: register.read(),
-    }
+    };
+    (response, written)
 }
 
-impl<I, B, T> TransferHandler<I, T> for RegisterHandler<B>
+impl<I, B, C, H, T> TransferHandler<I, T> for RegisterHandler<B, C, H>
 where
     I: Instant,
     B: RegisterBlock,
+    C: SynchronizedClock<I>,
+    H: WriteHook,
     T: Transport,
 {
     fn handle_request<N: Node<Instant = I, Transport = T>>(
@@ -259,7 +548,12 @@ where
         match transfer.header.service {
             access_1_0::SERVICE => {
                 if let Ok(request) = AccessRequest::deserialize_from_bytes(&transfer.payload) {
-                    let response = self.handle_access_request(&request);
+                    let now = node.clock_mut().now();
+                    let timestamp = self
+                        .clock
+                        .synchronized_time(now)
+                        .unwrap_or(SynchronizedTimestamp::UNKNOWN);
+                    let response = self.handle_access_request(&request, timestamp);
                     let status = node.send_response(token, milliseconds(1000), &response);
                     if status.is_err() {
                         log::warn!("Out of memory when sending register access response");
@@ -285,3 +579,15 @@ where
         }
     }
 }
+
+/// Makes a copy of a value (the generated `Value` type does not implement `Clone`)
+pub(crate) fn copy_value(value: &Value) -> Value {
+    Value::deserialize_from_bytes(&serialize_value(value)).expect("Value did not round-trip")
+}
+
+/// Serializes a value into bytes
+pub(crate) fn serialize_value(value: &Value) -> Vec<u8> {
+    let mut bytes = alloc::vec![0u8; value.size_bits().div_ceil(8)];
+    value.serialize(&mut WriteCursor::new(&mut bytes));
+    bytes
+}
@@ -1,5 +1,7 @@
 //! Basic register types
 
+use alloc::borrow::Cow;
+
 use crate::register::{Access, Register, WriteError};
 use canadensis_data_types::uavcan::primitive::array::bit_1_0::Bit;
 use canadensis_data_types::uavcan::primitive::array::integer16_1_0::Integer16;
@@ -23,7 +25,7 @@ use half::f16;
 /// A register containing its name, value, and mutable/persistent flags
 #[derive(Debug, Clone)]
 pub struct SimpleRegister<T> {
-    name: &'static str,
+    name: Cow<'static, str>,
     access: Access,
     value: T,
 }
@@ -32,8 +34,9 @@ impl<T> SimpleRegister<T> {
     /// Creates a register initialized to the default value of type T
     ///
     /// The name should not be more than 256 bytes long.
-    pub fn new(name: &'static str, mutable: bool, persistent: bool) -> Self
+    pub fn new<N>(name: N, mutable: bool, persistent: bool) -> Self
     where
+        N: Into<Cow<'static, str>>,
         T: Default,
     {
         Self::with_value(name, mutable, persistent, T::default())
@@ -42,9 +45,12 @@ impl<T> SimpleRegister<T> {
     /// Creates a register initialized to the default value of type T
     ///
     /// The name should not be more than 256 bytes long.
-    pub fn with_value(name: &'static str, mutable: bool, persistent: bool, value: T) -> Self {
+    pub fn with_value<N>(name: N, mutable: bool, persistent: bool, value: T) -> Self
+    where
+        N: Into<Cow<'static, str>>,
+    {
         SimpleRegister {
-            name,
+            name: name.into(),
             access: Access {
                 mutable,
                 persistent,
@@ -72,7 +78,7 @@ where
     T: RegisterType,
 {
     fn name(&self) -> &str {
-        self.name
+        &self.name
     }
 
     fn access(&self) -> Access {
@@ -93,7 +99,7 @@ where
 /// A register with a validation function that can reject invalid write operations
 ///
 /// Each write operation is checked with the validator. If the validator returns false, the value
-/// of the register does not change.
+/// of the register does not change and the write returns [`WriteError::Invalid`].
 ///
 /// # Examples
 ///
@@ -127,7 +133,7 @@ where
 ///     .is_err());
 /// ```
 pub struct ValidatedRegister<T, V = fn(&T) -> bool> {
-    name: &'static str,
+    name: Cow<'static, str>,
     access: Access,
     value: T,
     validator: V,
@@ -153,7 +159,10 @@ where
     /// Creates a register containing the default value of type T
     ///
     /// The validator should consider `T::default()` to be valid.
-    pub fn new(name: &'static str, mutable: bool, persistent: bool, validator: V) -> Self {
+    pub fn new<N>(name: N, mutable: bool, persistent: bool, validator: V) -> Self
+    where
+        N: Into<Cow<'static, str>>,
+    {
         Self::with_value(name, mutable, persistent, T::default(), validator)
     }
 }
@@ -162,15 +171,12 @@ impl<T, V> ValidatedRegister<T, V> {
     /// Creates a register with the provided initial value
     ///
     /// The validator should consider the provided value to be valid
-    pub fn with_value(
-        name: &'static str,
-        mutable: bool,
-        persistent: bool,
-        value: T,
-        validator: V,
-    ) -> Self {
+    pub fn with_value<N>(name: N, mutable: bool, persistent: bool, value: T, validator: V) -> Self
+    where
+        N: Into<Cow<'static, str>>,
+    {
         ValidatedRegister {
-            name,
+            name: name.into(),
             access: Access {
                 mutable,
                 persistent,
@@ -200,7 +206,7 @@ where
     V: Validator<T>,
 {
     fn name(&self) -> &str {
-        self.name
+        &self.name
     }
 
     fn access(&self) -> Access {
@@ -218,7 +224,7 @@ where
             self.value = new_value;
             Ok(())
         } else {
-            Err(WriteError::Type)
+            Err(WriteError::Invalid)
         }
     }
 }
@@ -522,6 +528,6 @@ impl Register for FixedStringRegister {
     }
 
     fn write(&mut self, _value: &Value) -> Result<(), WriteError> {
-        unimplemented!("A FixedStringRegister cannot be written")
+        Err(WriteError::ReadOnly)
     }
 }
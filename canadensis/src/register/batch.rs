@@ -0,0 +1,455 @@
+//!
+//! Writing and saving the register values of several nodes at once
+//!
+//! A [`RegisterBatch`] holds register names and values for several nodes, and can be loaded from
+//! or saved as a YAML or JSON document. A [`BatchClient`] writes a batch to the nodes, or reads
+//! the current register values of nodes into a batch. Together, they can be used to apply a
+//! saved configuration to a system and to take snapshots of the configuration for comparison
+//! (like `yakut register-batch`).
+//!
+
+use alloc::boxed::Box;
+use alloc::collections::{BTreeMap, VecDeque};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::fmt;
+use core::mem;
+use std::error::Error;
+
+use serde::ser::{Serialize, Serializer};
+
+use crate::register::client::{OperationId, RegisterClient, RegisterError, RegisterEvent};
+use crate::register::document::{convert, flatten, from_value, ConfigValue};
+use crate::{Node, StartSendError, TransferHandler};
+use canadensis_core::time::Instant;
+use canadensis_core::transfer::ServiceTransfer;
+use canadensis_core::transport::{Receiver, Transport};
+use canadensis_data_types::uavcan::register::value_1_0::Value;
+
+pub use crate::register::document::ValueErrorKind;
+
+/// Register names and values for several nodes
+///
+/// A batch document is a table of node IDs, each with a table of register names and values:
+///
+/// ```yaml
+/// 42:
+///   uavcan.node.description: gateway
+///   motor:
+///     gains: [0.5, 1.0, 2.0]
+/// 43:
+///   uavcan.node.description: sensor
+/// ```
+///
+/// Nested tables are flattened by joining the keys with `.`, so the example above sets the
+/// register `motor.gains` of node 42. Each value is a string, boolean, number, or array of
+/// booleans or numbers. When a [`BatchClient`] writes a value, it converts the value into the
+/// type of the register on the node.
+///
+/// Saved documents list the registers of each node by name, without nested tables.
+#[derive(Default)]
+pub struct RegisterBatch {
+    /// Node IDs, register names, and values
+    nodes: BTreeMap<u16, BTreeMap<String, ConfigValue>>,
+}
+
+impl RegisterBatch {
+    /// Creates an empty batch
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads a batch from a YAML document
+    #[cfg(feature = "yaml")]
+    pub fn from_yaml(document: &str) -> Result<Self, ParseError> {
+        let document = serde_yaml::from_str(document).map_err(|e| ParseError(e.to_string()))?;
+        Self::from_document(document)
+    }
+
+    /// Loads a batch from a JSON document
+    #[cfg(feature = "json")]
+    pub fn from_json(document: &str) -> Result<Self, ParseError> {
+        let document = serde_json::from_str(document).map_err(|e| ParseError(e.to_string()))?;
+        Self::from_document(document)
+    }
+
+    /// Saves this batch as a YAML document
+    #[cfg(feature = "yaml")]
+    pub fn to_yaml(&self) -> String {
+        serde_yaml::to_string(self).expect("Failed to serialize batch")
+    }
+
+    /// Saves this batch as a JSON document
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("Failed to serialize batch")
+    }
+
+    /// Adds a register value to this batch, replacing any value for the same node and register
+    ///
+    /// Empty values are ignored.
+    pub fn insert(&mut self, node: u16, name: &str, value: &Value) {
+        if let Some(value) = from_value(value) {
+            self.nodes
+                .entry(node)
+                .or_default()
+                .insert(String::from(name), value);
+        }
+    }
+
+    /// Returns true if this batch has a value for the provided node and register
+    pub fn contains(&self, node: u16, name: &str) -> bool {
+        self.nodes
+            .get(&node)
+            .map(|registers| registers.contains_key(name))
+            .unwrap_or(false)
+    }
+
+    /// Returns the IDs of the nodes in this batch, in increasing order
+    pub fn nodes(&self) -> impl Iterator<Item = u16> + '_ {
+        self.nodes.keys().copied()
+    }
+
+    /// Returns the names of the registers of a node in this batch, in alphabetical order
+    pub fn register_names(&self, node: u16) -> impl Iterator<Item = &str> + '_ {
+        self.nodes
+            .get(&node)
+            .into_iter()
+            .flat_map(|registers| registers.keys().map(String::as_str))
+    }
+
+    /// Returns the total number of register values in this batch
+    pub fn len(&self) -> usize {
+        self.nodes.values().map(BTreeMap::len).sum()
+    }
+
+    /// Returns true if this batch contains no register values
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn from_document(document: ConfigValue) -> Result<Self, ParseError> {
+        let nodes = match document {
+            ConfigValue::Map(nodes) => nodes,
+            _ => {
+                return Err(ParseError(String::from(
+                    "Document is not a table of node IDs",
+                )))
+            }
+        };
+        let mut batch = RegisterBatch::new();
+        for (node, registers) in nodes {
+            let node_id = node
+                .parse::<u16>()
+                .map_err(|_| ParseError(format!("Invalid node ID {}", node)))?;
+            let registers = match registers {
+                ConfigValue::Map(registers) => registers,
+                _ => {
+                    return Err(ParseError(format!(
+                        "Registers of node {} are not a table",
+                        node
+                    )))
+                }
+            };
+            let mut values = Vec::new();
+            flatten(String::new(), registers, &mut values);
+            batch.nodes.entry(node_id).or_default().extend(values);
+        }
+        Ok(batch)
+    }
+}
+
+impl Serialize for RegisterBatch {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_map(&self.nodes)
+    }
+}
+
+/// Writes register batches to nodes and reads the registers of nodes into batches
+///
+/// This uses a [`RegisterClient`]. To write each register, the client first reads the register
+/// to find its type, converts the value from the batch into that type, and then writes it. The
+/// client checks that the value the node returns after the write is the value that was written.
+///
+/// Basic steps:
+/// 1. Create a client using `BatchClient::new`
+/// 2. Call [`apply`](#method.apply) to write a batch, or [`dump`](#method.dump) to read the
+///    registers of a node
+/// 3. Pass incoming transfers to the client (it implements `TransferHandler`)
+/// 4. Call `BatchClient::poll` regularly to send requests and handle timeouts
+/// 5. Call `BatchClient::pop_event` to get the results of writes, and
+///    `BatchClient::take_snapshot` to get the registers that have been read
+pub struct BatchClient<I, T>
+where
+    I: Instant,
+    T: Transport,
+{
+    client: RegisterClient<I, T>,
+    /// Values to write after reading the registers, in the same order as the read operations
+    pending: VecDeque<PendingWrite>,
+    /// Operations that read registers into the snapshot
+    dumps: Vec<OperationId>,
+    /// Registers that have been read
+    snapshot: RegisterBatch,
+    events: VecDeque<BatchEvent<T::NodeId>>,
+}
+
+/// A value that will be written after the current value of its register has been read
+struct PendingWrite {
+    /// The operation that reads the register
+    id: OperationId,
+    value: ConfigValue,
+}
+
+impl<I, T> BatchClient<I, T>
+where
+    I: Instant,
+    T: Transport,
+    T::TransferId: PartialEq,
+{
+    /// Creates a batch client
+    ///
+    /// This function returns an error if the node can't start sending register list and access
+    /// requests.
+    pub fn new<N>(node: &mut N) -> Result<Self, StartSendError<<N::Receiver as Receiver<I>>::Error>>
+    where
+        N: Node<Instant = I, Transport = T>,
+    {
+        Ok(BatchClient {
+            client: RegisterClient::new(node)?,
+            pending: VecDeque::new(),
+            dumps: Vec::new(),
+            snapshot: RegisterBatch::new(),
+            events: VecDeque::new(),
+        })
+    }
+
+    /// Requests writes of all the register values in a batch
+    ///
+    /// The client reports a [`Written`](BatchEvent::Written),
+    /// [`NotWritten`](BatchEvent::NotWritten), or [`Failed`](BatchEvent::Failed) event for each
+    /// register.
+    ///
+    /// This function returns an error, and does not write anything, if the batch contains a node
+    /// ID that is not valid for the transport.
+    pub fn apply(&mut self, batch: &RegisterBatch) -> Result<(), InvalidNodeId> {
+        let mut nodes = Vec::new();
+        for (&node, registers) in &batch.nodes {
+            let server = T::NodeId::try_from(node).map_err(|_| InvalidNodeId(node))?;
+            nodes.push((server, registers));
+        }
+        for (server, registers) in nodes {
+            for (name, value) in registers {
+                let id = self.client.read(server.clone(), name);
+                self.pending.push_back(PendingWrite {
+                    id,
+                    value: value.clone(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Requests the names and values of all registers of a node, and adds them to the snapshot
+    ///
+    /// If a register can't be read, the client reports a [`Failed`](BatchEvent::Failed) event.
+    pub fn dump(&mut self, server: T::NodeId) {
+        let id = self.client.read_all(server);
+        self.dumps.push(id);
+    }
+
+    /// Returns true if all requested operations have finished
+    pub fn is_idle(&self) -> bool {
+        self.client.is_idle()
+    }
+
+    /// Returns and removes the oldest event
+    pub fn pop_event(&mut self) -> Option<BatchEvent<T::NodeId>> {
+        self.events.pop_front()
+    }
+
+    /// Returns the registers that have been read since the last call to this function, and
+    /// clears the snapshot
+    pub fn take_snapshot(&mut self) -> RegisterBatch {
+        mem::take(&mut self.snapshot)
+    }
+
+    /// Sends requests and handles timeouts
+    pub fn poll<N>(&mut self, node: &mut N)
+    where
+        N: Node<Instant = I, Transport = T>,
+    {
+        self.client.poll(node);
+        self.handle_client_events();
+    }
+
+    /// Handles the events from the register client, which may start more operations
+    fn handle_client_events(&mut self) {
+        while let Some(event) = self.client.pop_event() {
+            match event {
+                RegisterEvent::Listed { .. } => {}
+                RegisterEvent::Read {
+                    id,
+                    server,
+                    name,
+                    value,
+                } => {
+                    if self.dumps.contains(&id) {
+                        match u16::try_from(server.into()) {
+                            Ok(node) => self.snapshot.insert(node, &name, &value),
+                            Err(_) => log::warn!("Node ID too large for a batch"),
+                        }
+                    } else if let Some(pending) = self.take_pending(id) {
+                        match convert(&value, &pending.value) {
+                            Ok(value) => {
+                                self.client.write(server, &name, value);
+                            }
+                            Err(kind) => self.events.push_back(BatchEvent::Failed {
+                                server,
+                                name: Some(name),
+                                error: BatchError::Value(kind),
+                            }),
+                        }
+                    }
+                }
+                RegisterEvent::Written { server, name, .. } => {
+                    self.events.push_back(BatchEvent::Written { server, name })
+                }
+                RegisterEvent::NotWritten {
+                    server,
+                    name,
+                    value,
+                    mutable,
+                    ..
+                } => self.events.push_back(BatchEvent::NotWritten {
+                    server,
+                    name,
+                    value: Box::new(value),
+                    mutable,
+                }),
+                RegisterEvent::Failed {
+                    id,
+                    server,
+                    name,
+                    error,
+                } => {
+                    self.take_pending(id);
+                    self.events.push_back(BatchEvent::Failed {
+                        server,
+                        name,
+                        error: BatchError::Register(error),
+                    })
+                }
+            }
+        }
+        if self.client.is_idle() {
+            self.dumps.clear();
+        }
+    }
+
+    /// Removes and returns the pending write for a read operation, if any
+    fn take_pending(&mut self, id: OperationId) -> Option<PendingWrite> {
+        match self.pending.front() {
+            Some(pending) if pending.id == id => self.pending.pop_front(),
+            _ => None,
+        }
+    }
+}
+
+impl<I, T> TransferHandler<I, T> for BatchClient<I, T>
+where
+    I: Instant,
+    T: Transport,
+    T::TransferId: PartialEq,
+{
+    fn handle_response<N: Node<Instant = I, Transport = T>>(
+        &mut self,
+        node: &mut N,
+        transfer: &ServiceTransfer<Vec<u8>, I, T>,
+    ) -> bool {
+        let handled = self.client.handle_response(node, transfer);
+        self.handle_client_events();
+        handled
+    }
+}
+
+/// The result of writing a register from a batch
+pub enum BatchEvent<N> {
+    /// A register has been written, and the node returned the value that was written
+    Written {
+        /// The node that has the register
+        server: N,
+        /// The register name
+        name: String,
+    },
+    /// The node responded to the write request, but the value that it returned was not the value
+    /// that was written
+    NotWritten {
+        /// The node that has the register
+        server: N,
+        /// The register name
+        name: String,
+        /// The register value returned by the node
+        value: Box<Value>,
+        /// True if the node reported that the register is mutable
+        mutable: bool,
+    },
+    /// A register could not be read or written
+    Failed {
+        /// The node that the requests were sent to
+        server: N,
+        /// The register name, or None if the registers of a node could not be listed
+        name: Option<String>,
+        /// The reason for the failure
+        error: BatchError,
+    },
+}
+
+/// Reasons why a register in a batch could not be read or written
+#[derive(Debug)]
+pub enum BatchError {
+    /// The register could not be accessed
+    Register(RegisterError),
+    /// The value in the batch could not be converted into the type of the register
+    Value(ValueErrorKind),
+}
+
+impl fmt::Display for BatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BatchError::Register(e) => write!(f, "Register access failed: {:?}", e),
+            BatchError::Value(kind) => fmt::Display::fmt(kind, f),
+        }
+    }
+}
+
+impl Error for BatchError {}
+
+/// An error that occurred when loading a batch from a document
+#[derive(Debug)]
+pub struct ParseError(String);
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid batch document: {}", self.0)
+    }
+}
+
+impl Error for ParseError {}
+
+/// A node ID in a batch that is not valid for the transport
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct InvalidNodeId(pub u16);
+
+impl fmt::Display for InvalidNodeId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid node ID {}", self.0)
+    }
+}
+
+impl Error for InvalidNodeId {}
@@ -0,0 +1,579 @@
+//!
+//! Listing, reading, and writing the registers of other nodes
+//!
+
+use alloc::boxed::Box;
+use alloc::collections::VecDeque;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::str;
+
+use crate::client::{ClientError, ServiceClient};
+use crate::register::basic::RegisterType;
+use crate::register::{copy_value, serialize_value, WriteError};
+use crate::{Node, StartSendError, TransferHandler};
+use canadensis_core::time::{milliseconds, Clock, Instant};
+use canadensis_core::transfer::ServiceTransfer;
+use canadensis_core::transport::{Receiver, Transport};
+use canadensis_core::Priority;
+use canadensis_data_types::uavcan::primitive::empty_1_0::Empty;
+use canadensis_data_types::uavcan::register::access_1_0::{self, AccessRequest, AccessResponse};
+use canadensis_data_types::uavcan::register::list_1_0::{self, ListRequest, ListResponse};
+use canadensis_data_types::uavcan::register::name_1_0::Name;
+use canadensis_data_types::uavcan::register::value_1_0::Value;
+use canadensis_encoding::DataType;
+
+/// The maximum time to wait for each response, in milliseconds
+const RESPONSE_TIMEOUT_MS: u32 = 1000;
+
+/// The number of times to send each request before giving up
+const ATTEMPTS: u8 = 3;
+
+/// Identifies an operation requested from a [`RegisterClient`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct OperationId(u32);
+
+/// The result of an operation on the registers of another node
+pub enum RegisterEvent<N> {
+    /// All register names of a node have been listed
+    Listed {
+        /// The operation that listed the registers
+        id: OperationId,
+        /// The node that has the registers
+        server: N,
+        /// The register names, in the order that the node listed them
+        names: Vec<String>,
+    },
+    /// A register has been read
+    Read {
+        /// The operation that read the register
+        id: OperationId,
+        /// The node that has the register
+        server: N,
+        /// The register name
+        name: String,
+        /// The register value
+        value: Value,
+    },
+    /// A register has been written, and the value that the node returned was the value that
+    /// was written
+    Written {
+        /// The operation that wrote the register
+        id: OperationId,
+        /// The node that has the register
+        server: N,
+        /// The register name
+        name: String,
+        /// The new register value
+        value: Value,
+    },
+    /// The node responded to a write request, but the value that it returned was not the value
+    /// that was written
+    ///
+    /// This can happen if the register is not mutable or if the node rejected the value.
+    NotWritten {
+        /// The operation that tried to write the register
+        id: OperationId,
+        /// The node that has the register
+        server: N,
+        /// The register name
+        name: String,
+        /// The register value returned by the node
+        value: Value,
+        /// True if the node reported that the register is mutable
+        mutable: bool,
+    },
+    /// An operation failed
+    Failed {
+        /// The operation that failed
+        id: OperationId,
+        /// The node that the requests were sent to
+        server: N,
+        /// The register name, if the operation was a read or write
+        name: Option<String>,
+        /// The reason for the failure
+        error: RegisterError,
+    },
+}
+
+/// Reasons why an operation on a register can fail
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegisterError {
+    /// The node did not respond after several attempts
+    Timeout,
+    /// The register does not exist
+    NotFound,
+    /// A register name was not valid UTF-8 or was too long
+    InvalidName,
+    /// A response could not be deserialized
+    Deserialize,
+    /// A request could not be sent
+    Transmit,
+}
+
+/// An operation waiting to be completed
+struct Operation<N> {
+    id: OperationId,
+    server: N,
+    kind: OperationKind,
+}
+
+enum OperationKind {
+    /// List the registers
+    List {
+        /// The names received so far
+        names: Vec<String>,
+        /// If true, read each register after listing
+        read_values: bool,
+    },
+    /// Read a register
+    Read { name: String },
+    /// Write a register
+    Write { name: String, value: Box<Value> },
+}
+
+/// Lists, reads, and writes the registers of other nodes using the `uavcan.register.List` and
+/// `uavcan.register.Access` services
+///
+/// Operations are queued and performed in order, one request at a time. If a node does not
+/// respond to a request within one second, the client sends the request again, up to three
+/// times. When an operation finishes or fails, the client adds an event to a queue.
+///
+/// After writing a register, the client checks that the value returned by the node is the value
+/// that was written, and reports a [`NotWritten`](RegisterEvent::NotWritten) event if it is not.
+///
+/// Basic steps:
+/// 1. Create a client using `RegisterClient::new`
+/// 2. Request operations using `RegisterClient::list`, `RegisterClient::read`,
+///    `RegisterClient::write`, and the other request functions
+/// 3. Pass incoming transfers to the client (it implements `TransferHandler`)
+/// 4. Call `RegisterClient::poll` regularly to send requests and handle timeouts
+/// 5. Call `RegisterClient::pop_event` to get the results
+pub struct RegisterClient<I, T>
+where
+    I: Instant,
+    T: Transport,
+{
+    list_client: ServiceClient<I, T, ListRequest, ListResponse, 1>,
+    access_client: ServiceClient<I, T, AccessRequest, AccessResponse, 1>,
+    /// Operations that have not finished, in order
+    operations: VecDeque<Operation<T::NodeId>>,
+    /// The number of times the current request of the first operation has been sent
+    attempts: u8,
+    /// True if a request for the first operation is waiting for a response
+    waiting: bool,
+    events: VecDeque<RegisterEvent<T::NodeId>>,
+    next_id: u32,
+}
+
+impl<I, T> RegisterClient<I, T>
+where
+    I: Instant,
+    T: Transport,
+    T::TransferId: PartialEq,
+{
+    /// Creates a register client
+    ///
+    /// This function returns an error if the node can't start sending register list and access
+    /// requests.
+    pub fn new<N>(node: &mut N) -> Result<Self, StartSendError<<N::Receiver as Receiver<I>>::Error>>
+    where
+        N: Node<Instant = I, Transport = T>,
+    {
+        let list_token = node.start_sending_requests(
+            list_1_0::SERVICE,
+            milliseconds(RESPONSE_TIMEOUT_MS),
+            ListResponse::extent(),
+            Priority::Low.into(),
+        )?;
+        let access_token = match node.start_sending_requests(
+            access_1_0::SERVICE,
+            milliseconds(RESPONSE_TIMEOUT_MS),
+            AccessResponse::extent(),
+            Priority::Low.into(),
+        ) {
+            Ok(token) => token,
+            Err(e) => {
+                node.stop_sending_requests(list_token);
+                return Err(e);
+            }
+        };
+        Ok(RegisterClient {
+            list_client: ServiceClient::new(list_token, milliseconds(RESPONSE_TIMEOUT_MS)),
+            access_client: ServiceClient::new(access_token, milliseconds(RESPONSE_TIMEOUT_MS)),
+            operations: VecDeque::new(),
+            attempts: 0,
+            waiting: false,
+            events: VecDeque::new(),
+            next_id: 0,
+        })
+    }
+
+    /// Requests the names of all registers of a node
+    ///
+    /// When the operation finishes, the client reports a [`Listed`](RegisterEvent::Listed) event.
+    pub fn list(&mut self, server: T::NodeId) -> OperationId {
+        self.push_operation(
+            server,
+            OperationKind::List {
+                names: Vec::new(),
+                read_values: false,
+            },
+        )
+    }
+
+    /// Requests the names and values of all registers of a node
+    ///
+    /// The client reports a [`Listed`](RegisterEvent::Listed) event after listing the registers,
+    /// followed by a [`Read`](RegisterEvent::Read) or [`Failed`](RegisterEvent::Failed) event
+    /// for each register. All events have the returned operation ID.
+    pub fn read_all(&mut self, server: T::NodeId) -> OperationId {
+        self.push_operation(
+            server,
+            OperationKind::List {
+                names: Vec::new(),
+                read_values: true,
+            },
+        )
+    }
+
+    /// Requests the value of a register
+    pub fn read(&mut self, server: T::NodeId, name: &str) -> OperationId {
+        self.push_operation(
+            server,
+            OperationKind::Read {
+                name: name.to_string(),
+            },
+        )
+    }
+
+    /// Requests the values of several registers
+    ///
+    /// The client reports an event for each register. All events have the returned operation ID.
+    pub fn read_batch<'n, R>(&mut self, server: T::NodeId, names: R) -> OperationId
+    where
+        R: IntoIterator<Item = &'n str>,
+    {
+        let id = self.allocate_id();
+        for name in names {
+            self.operations.push_back(Operation {
+                id,
+                server: server.clone(),
+                kind: OperationKind::Read {
+                    name: name.to_string(),
+                },
+            });
+        }
+        id
+    }
+
+    /// Requests a change to the value of a register
+    pub fn write(&mut self, server: T::NodeId, name: &str, value: Value) -> OperationId {
+        self.push_operation(
+            server,
+            OperationKind::Write {
+                name: name.to_string(),
+                value: Box::new(value),
+            },
+        )
+    }
+
+    /// Requests a change to the value of a register, converting the value from a
+    /// [`RegisterType`]
+    pub fn write_typed<R>(&mut self, server: T::NodeId, name: &str, value: &R) -> OperationId
+    where
+        R: RegisterType,
+    {
+        self.write(server, name, value.read())
+    }
+
+    /// Requests changes to the values of several registers
+    ///
+    /// The client reports an event for each register. All events have the returned operation ID.
+    pub fn write_batch<'n, W>(&mut self, server: T::NodeId, values: W) -> OperationId
+    where
+        W: IntoIterator<Item = (&'n str, Value)>,
+    {
+        let id = self.allocate_id();
+        for (name, value) in values {
+            self.operations.push_back(Operation {
+                id,
+                server: server.clone(),
+                kind: OperationKind::Write {
+                    name: name.to_string(),
+                    value: Box::new(value),
+                },
+            });
+        }
+        id
+    }
+
+    /// Returns true if all requested operations have finished
+    pub fn is_idle(&self) -> bool {
+        self.operations.is_empty()
+    }
+
+    /// Removes and returns the oldest event
+    pub fn pop_event(&mut self) -> Option<RegisterEvent<T::NodeId>> {
+        self.events.pop_front()
+    }
+
+    /// Handles timeouts and sends the next request if necessary
+    pub fn poll<N>(&mut self, node: &mut N)
+    where
+        N: Node<Instant = I, Transport = T>,
+    {
+        let now = node.clock_mut().now();
+        let timed_out = self.list_client.check_timeouts(now).is_some()
+            | self.access_client.check_timeouts(now).is_some();
+        if timed_out {
+            self.waiting = false;
+            if self.attempts >= ATTEMPTS {
+                self.fail(RegisterError::Timeout);
+            }
+        }
+        if !self.waiting {
+            self.send_request(node);
+        }
+    }
+
+    fn send_request<N>(&mut self, node: &mut N)
+    where
+        N: Node<Instant = I, Transport = T>,
+    {
+        let operation = match self.operations.front() {
+            Some(operation) => operation,
+            None => return,
+        };
+        let server = operation.server.clone();
+        let status = match &operation.kind {
+            OperationKind::List { names, .. } => {
+                let request = ListRequest {
+                    index: names.len() as u16,
+                };
+                self.list_client.send(node, &request, server).map(drop)
+            }
+            OperationKind::Read { name } | OperationKind::Write { name, .. } => {
+                let name = match heapless::Vec::from_slice(name.as_bytes()) {
+                    Ok(name) => Name { name },
+                    Err(_) => {
+                        self.fail(RegisterError::InvalidName);
+                        return;
+                    }
+                };
+                let value = match &operation.kind {
+                    OperationKind::Write { value, .. } => copy_value(value),
+                    _ => Value::Empty(Empty {}),
+                };
+                let request = AccessRequest { name, value };
+                self.access_client.send(node, &request, server).map(drop)
+            }
+        };
+        match status {
+            Ok(()) => {
+                self.attempts += 1;
+                self.waiting = true;
+            }
+            Err(e) => {
+                log::warn!("Failed to send register request: {:?}", e);
+                self.fail(RegisterError::Transmit);
+            }
+        }
+    }
+
+    fn handle_list_response(&mut self, response: &ListResponse) {
+        let operation = match self.operations.front_mut() {
+            Some(operation) => operation,
+            None => return,
+        };
+        let names = match &mut operation.kind {
+            OperationKind::List { names, .. } => names,
+            _ => return,
+        };
+        if response.name.name.is_empty() {
+            // Finished listing
+            let operation = self.operations.pop_front().unwrap();
+            if let OperationKind::List { names, read_values } = operation.kind {
+                if read_values {
+                    // Read the registers next, before any other operations
+                    for name in names.iter().rev() {
+                        self.operations.push_front(Operation {
+                            id: operation.id,
+                            server: operation.server.clone(),
+                            kind: OperationKind::Read { name: name.clone() },
+                        });
+                    }
+                }
+                self.push_event(RegisterEvent::Listed {
+                    id: operation.id,
+                    server: operation.server,
+                    names,
+                });
+            }
+        } else {
+            match str::from_utf8(&response.name.name) {
+                Ok(name) => names.push(name.to_string()),
+                Err(_) => self.fail(RegisterError::InvalidName),
+            }
+        }
+    }
+
+    fn handle_access_response(&mut self, response: AccessResponse) {
+        let operation = match self.operations.front() {
+            Some(Operation {
+                kind: OperationKind::Read { .. } | OperationKind::Write { .. },
+                ..
+            }) => self.operations.pop_front().unwrap(),
+            _ => return,
+        };
+        let (id, server) = (operation.id, operation.server);
+        if matches!(response.value, Value::Empty(_)) {
+            let name = match operation.kind {
+                OperationKind::Read { name } | OperationKind::Write { name, .. } => name,
+                OperationKind::List { .. } => unreachable!(),
+            };
+            self.push_event(RegisterEvent::Failed {
+                id,
+                server,
+                name: Some(name),
+                error: RegisterError::NotFound,
+            });
+            return;
+        }
+        let event = match operation.kind {
+            OperationKind::Read { name } => RegisterEvent::Read {
+                id,
+                server,
+                name,
+                value: response.value,
+            },
+            OperationKind::Write { name, value } => {
+                if same_value(&value, &response.value) {
+                    RegisterEvent::Written {
+                        id,
+                        server,
+                        name,
+                        value: response.value,
+                    }
+                } else {
+                    RegisterEvent::NotWritten {
+                        id,
+                        server,
+                        name,
+                        value: response.value,
+                        mutable: response.mutable,
+                    }
+                }
+            }
+            OperationKind::List { .. } => unreachable!(),
+        };
+        self.push_event(event);
+    }
+
+    /// Removes the first operation and reports that it failed
+    fn fail(&mut self, error: RegisterError) {
+        self.attempts = 0;
+        self.waiting = false;
+        if let Some(operation) = self.operations.pop_front() {
+            let name = match operation.kind {
+                OperationKind::List { .. } => None,
+                OperationKind::Read { name } | OperationKind::Write { name, .. } => Some(name),
+            };
+            log::debug!("Register operation failed: {:?} {:?}", name, error);
+            self.push_event(RegisterEvent::Failed {
+                id: operation.id,
+                server: operation.server,
+                name,
+                error,
+            });
+        }
+    }
+
+    fn push_operation(&mut self, server: T::NodeId, kind: OperationKind) -> OperationId {
+        let id = self.allocate_id();
+        self.operations.push_back(Operation { id, server, kind });
+        id
+    }
+
+    fn allocate_id(&mut self) -> OperationId {
+        let id = OperationId(self.next_id);
+        self.next_id = self.next_id.wrapping_add(1);
+        id
+    }
+
+    fn push_event(&mut self, event: RegisterEvent<T::NodeId>) {
+        self.events.push_back(event);
+    }
+}
+
+impl<I, T> TransferHandler<I, T> for RegisterClient<I, T>
+where
+    I: Instant,
+    T: Transport,
+    T::TransferId: PartialEq,
+{
+    fn handle_response<N: Node<Instant = I, Transport = T>>(
+        &mut self,
+        _node: &mut N,
+        transfer: &ServiceTransfer<Vec<u8>, I, T>,
+    ) -> bool {
+        if let Some(result) = self.list_client.handle_response(transfer) {
+            self.handle_result(result, |client, response| {
+                client.handle_list_response(&response.payload)
+            });
+            true
+        } else if let Some(result) = self.access_client.handle_response(transfer) {
+            self.handle_result(result, |client, response| {
+                client.handle_access_response(response.payload)
+            });
+            true
+        } else {
+            false
+        }
+    }
+}
+
+impl<I, T> RegisterClient<I, T>
+where
+    I: Instant,
+    T: Transport,
+    T::TransferId: PartialEq,
+{
+    fn handle_result<S, F>(
+        &mut self,
+        result: Result<ServiceTransfer<S, I, T>, ClientError<T>>,
+        handle_response: F,
+    ) where
+        F: FnOnce(&mut Self, ServiceTransfer<S, I, T>),
+    {
+        self.waiting = false;
+        match result {
+            Ok(response) => {
+                self.attempts = 0;
+                handle_response(self, response);
+            }
+            Err(e) => {
+                log::debug!("Invalid register response: {:?}", e);
+                self.fail(RegisterError::Deserialize);
+            }
+        }
+    }
+}
+
+/// Converts a register value into a [`RegisterType`]
+///
+/// This function returns an error if the value has the wrong type or the wrong number of
+/// elements.
+pub fn value_as<R>(value: &Value) -> Result<R, WriteError>
+where
+    R: RegisterType + Default,
+{
+    let mut converted = R::default();
+    converted.write(value)?;
+    Ok(converted)
+}
+
+/// Returns true if two values have the same type and contents
+fn same_value(a: &Value, b: &Value) -> bool {
+    serialize_value(a) == serialize_value(b)
+}
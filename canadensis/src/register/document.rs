@@ -0,0 +1,413 @@
+//!
+//! Conversion between register values and the values in TOML, YAML, and JSON documents
+//!
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::fmt;
+
+use serde::de::{self, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::ser::{Serialize, Serializer};
+
+use crate::register::WriteError;
+use canadensis_data_types::uavcan::primitive::array::bit_1_0::Bit;
+use canadensis_data_types::uavcan::primitive::array::integer16_1_0::Integer16;
+use canadensis_data_types::uavcan::primitive::array::integer32_1_0::Integer32;
+use canadensis_data_types::uavcan::primitive::array::integer64_1_0::Integer64;
+use canadensis_data_types::uavcan::primitive::array::integer8_1_0::Integer8;
+use canadensis_data_types::uavcan::primitive::array::natural16_1_0::Natural16;
+use canadensis_data_types::uavcan::primitive::array::natural32_1_0::Natural32;
+use canadensis_data_types::uavcan::primitive::array::natural64_1_0::Natural64;
+use canadensis_data_types::uavcan::primitive::array::natural8_1_0::Natural8;
+use canadensis_data_types::uavcan::primitive::array::real16_1_0::Real16;
+use canadensis_data_types::uavcan::primitive::array::real32_1_0::Real32;
+use canadensis_data_types::uavcan::primitive::array::real64_1_0::Real64;
+use canadensis_data_types::uavcan::primitive::string_1_0;
+use canadensis_data_types::uavcan::primitive::unstructured_1_0;
+use canadensis_data_types::uavcan::register::value_1_0::Value;
+use canadensis_encoding::bits::BitArray;
+use half::f16;
+
+/// The maximum number of values in a bit array
+const MAX_BITS: usize = 2048;
+
+/// A value in a document
+#[derive(Clone)]
+pub(crate) enum ConfigValue {
+    Bool(bool),
+    Integer(i64),
+    Unsigned(u64),
+    Float(f64),
+    String(String),
+    Array(Vec<ConfigValue>),
+    Map(Vec<(String, ConfigValue)>),
+}
+
+impl<'de> Deserialize<'de> for ConfigValue {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(ConfigValueVisitor)
+    }
+}
+
+struct ConfigValueVisitor;
+
+impl<'de> Visitor<'de> for ConfigValueVisitor {
+    type Value = ConfigValue;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a string, boolean, number, array, or table")
+    }
+
+    fn visit_bool<E: de::Error>(self, v: bool) -> Result<Self::Value, E> {
+        Ok(ConfigValue::Bool(v))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+        Ok(ConfigValue::Integer(v))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+        Ok(ConfigValue::Unsigned(v))
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+        Ok(ConfigValue::Float(v))
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(ConfigValue::String(String::from(v)))
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> Result<Self::Value, E> {
+        Ok(ConfigValue::String(v))
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut items = Vec::new();
+        while let Some(item) = seq.next_element()? {
+            items.push(item);
+        }
+        Ok(ConfigValue::Array(items))
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut entries = Vec::new();
+        while let Some(entry) = map.next_entry()? {
+            entries.push(entry);
+        }
+        Ok(ConfigValue::Map(entries))
+    }
+}
+
+impl Serialize for ConfigValue {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            ConfigValue::Bool(value) => serializer.serialize_bool(*value),
+            ConfigValue::Integer(value) => serializer.serialize_i64(*value),
+            ConfigValue::Unsigned(value) => serializer.serialize_u64(*value),
+            ConfigValue::Float(value) => serializer.serialize_f64(*value),
+            ConfigValue::String(value) => serializer.serialize_str(value),
+            ConfigValue::Array(items) => serializer.collect_seq(items),
+            ConfigValue::Map(entries) => {
+                serializer.collect_map(entries.iter().map(|(key, value)| (key, value)))
+            }
+        }
+    }
+}
+
+/// Converts nested tables into a list of full register names and values
+pub(crate) fn flatten(
+    prefix: String,
+    entries: Vec<(String, ConfigValue)>,
+    values: &mut Vec<(String, ConfigValue)>,
+) {
+    for (key, value) in entries {
+        let name = if prefix.is_empty() {
+            key
+        } else {
+            format!("{}.{}", prefix, key)
+        };
+        match value {
+            ConfigValue::Map(entries) => flatten(name, entries, values),
+            value => values.push((name, value)),
+        }
+    }
+}
+
+/// Returns an empty value of the type that a document value should be stored as, or None if
+/// the type can't be determined
+pub(crate) fn infer_type(value: &ConfigValue) -> Option<Value> {
+    let items = match value {
+        ConfigValue::String(_) => {
+            return Some(Value::String(string_1_0::String {
+                value: heapless::Vec::new(),
+            }))
+        }
+        ConfigValue::Array(items) if !items.is_empty() => &items[..],
+        ConfigValue::Array(_) | ConfigValue::Map(_) => return None,
+        value => core::slice::from_ref(value),
+    };
+    if items
+        .iter()
+        .all(|item| matches!(item, ConfigValue::Bool(_)))
+    {
+        Some(Value::Bit(Bit {
+            value: BitArray::new(0),
+        }))
+    } else if items
+        .iter()
+        .all(|item| matches!(item, ConfigValue::Integer(_) | ConfigValue::Unsigned(_)))
+    {
+        Some(Value::Integer64(Integer64 {
+            value: heapless::Vec::new(),
+        }))
+    } else if items.iter().all(|item| {
+        matches!(
+            item,
+            ConfigValue::Integer(_) | ConfigValue::Unsigned(_) | ConfigValue::Float(_)
+        )
+    }) {
+        Some(Value::Real64(Real64 {
+            value: heapless::Vec::new(),
+        }))
+    } else {
+        None
+    }
+}
+
+/// Converts a document value into a value of the same type as `current`
+///
+/// The number of array elements is determined by the document, not by `current`.
+pub(crate) fn convert(current: &Value, value: &ConfigValue) -> Result<Value, ValueErrorKind> {
+    let items = match value {
+        ConfigValue::Array(items) => &items[..],
+        ConfigValue::Map(_) => return Err(ValueErrorKind::Type),
+        value => core::slice::from_ref(value),
+    };
+    let converted = match current {
+        Value::Empty(_) => return Err(ValueErrorKind::Type),
+        Value::String(_) => match value {
+            ConfigValue::String(text) => Value::String(string_1_0::String {
+                value: heapless::Vec::from_slice(text.as_bytes())
+                    .map_err(|_| ValueErrorKind::Length)?,
+            }),
+            _ => return Err(ValueErrorKind::Type),
+        },
+        Value::Unstructured(_) => Value::Unstructured(unstructured_1_0::Unstructured {
+            value: match value {
+                ConfigValue::String(text) => heapless::Vec::from_slice(text.as_bytes())
+                    .map_err(|_| ValueErrorKind::Length)?,
+                _ => integers(items)?,
+            },
+        }),
+        Value::Bit(_) => {
+            if items.len() > MAX_BITS {
+                return Err(ValueErrorKind::Length);
+            }
+            let mut bits = BitArray::new(items.len());
+            for (i, item) in items.iter().enumerate() {
+                let bit = match item {
+                    ConfigValue::Bool(bit) => *bit,
+                    ConfigValue::Integer(value) => *value != 0,
+                    ConfigValue::Unsigned(value) => *value != 0,
+                    _ => return Err(ValueErrorKind::Type),
+                };
+                bits.set(i, bit);
+            }
+            Value::Bit(Bit { value: bits })
+        }
+        Value::Integer64(_) => Value::Integer64(Integer64 {
+            value: integers(items)?,
+        }),
+        Value::Integer32(_) => Value::Integer32(Integer32 {
+            value: integers(items)?,
+        }),
+        Value::Integer16(_) => Value::Integer16(Integer16 {
+            value: integers(items)?,
+        }),
+        Value::Integer8(_) => Value::Integer8(Integer8 {
+            value: integers(items)?,
+        }),
+        Value::Natural64(_) => Value::Natural64(Natural64 {
+            value: integers(items)?,
+        }),
+        Value::Natural32(_) => Value::Natural32(Natural32 {
+            value: integers(items)?,
+        }),
+        Value::Natural16(_) => Value::Natural16(Natural16 {
+            value: integers(items)?,
+        }),
+        Value::Natural8(_) => Value::Natural8(Natural8 {
+            value: integers(items)?,
+        }),
+        Value::Real64(_) => Value::Real64(Real64 {
+            value: reals(items, |value| value)?,
+        }),
+        Value::Real32(_) => Value::Real32(Real32 {
+            value: reals(items, |value| value as f32)?,
+        }),
+        Value::Real16(_) => Value::Real16(Real16 {
+            value: reals(items, f16::from_f64)?,
+        }),
+    };
+    Ok(converted)
+}
+
+fn integers<T, const N: usize>(items: &[ConfigValue]) -> Result<heapless::Vec<T, N>, ValueErrorKind>
+where
+    T: TryFrom<i128>,
+{
+    let mut values = heapless::Vec::new();
+    for item in items {
+        let value = match item {
+            ConfigValue::Integer(value) => i128::from(*value),
+            ConfigValue::Unsigned(value) => i128::from(*value),
+            _ => return Err(ValueErrorKind::Type),
+        };
+        let value = T::try_from(value).map_err(|_| ValueErrorKind::Range)?;
+        values.push(value).map_err(|_| ValueErrorKind::Length)?;
+    }
+    Ok(values)
+}
+
+fn reals<T, const N: usize>(
+    items: &[ConfigValue],
+    convert: fn(f64) -> T,
+) -> Result<heapless::Vec<T, N>, ValueErrorKind> {
+    let mut values = heapless::Vec::new();
+    for item in items {
+        let value = match item {
+            ConfigValue::Float(value) => *value,
+            ConfigValue::Integer(value) => *value as f64,
+            ConfigValue::Unsigned(value) => *value as f64,
+            _ => return Err(ValueErrorKind::Type),
+        };
+        values
+            .push(convert(value))
+            .map_err(|_| ValueErrorKind::Length)?;
+    }
+    Ok(values)
+}
+
+#[cfg(any(feature = "yaml", feature = "json"))]
+/// Converts a register value into a document value, or returns None if the value is empty
+///
+/// Arrays with one element become single values, so that they look like the values that
+/// [`convert`] accepts. Unstructured values are always arrays of bytes.
+pub(crate) fn from_value(value: &Value) -> Option<ConfigValue> {
+    let mut items: Vec<ConfigValue> = match value {
+        Value::Empty(_) => return None,
+        Value::String(text) => {
+            return Some(ConfigValue::String(
+                String::from_utf8_lossy(&text.value).into_owned(),
+            ))
+        }
+        Value::Unstructured(bytes) => {
+            return Some(ConfigValue::Array(
+                bytes
+                    .value
+                    .iter()
+                    .map(|&byte| ConfigValue::Unsigned(byte.into()))
+                    .collect(),
+            ))
+        }
+        Value::Bit(bits) => bits.value.iter().map(ConfigValue::Bool).collect(),
+        Value::Integer64(values) => signed(&values.value),
+        Value::Integer32(values) => signed(&values.value),
+        Value::Integer16(values) => signed(&values.value),
+        Value::Integer8(values) => signed(&values.value),
+        Value::Natural64(values) => unsigned(&values.value),
+        Value::Natural32(values) => unsigned(&values.value),
+        Value::Natural16(values) => unsigned(&values.value),
+        Value::Natural8(values) => unsigned(&values.value),
+        Value::Real64(values) => floats(&values.value, |value| value),
+        Value::Real32(values) => floats(&values.value, f64::from),
+        Value::Real16(values) => floats(&values.value, f16::to_f64),
+    };
+    if items.len() == 1 {
+        items.pop()
+    } else {
+        Some(ConfigValue::Array(items))
+    }
+}
+
+#[cfg(any(feature = "yaml", feature = "json"))]
+fn signed<T>(values: &[T]) -> Vec<ConfigValue>
+where
+    T: Copy + Into<i64>,
+{
+    values
+        .iter()
+        .map(|&value| ConfigValue::Integer(value.into()))
+        .collect()
+}
+
+#[cfg(any(feature = "yaml", feature = "json"))]
+fn unsigned<T>(values: &[T]) -> Vec<ConfigValue>
+where
+    T: Copy + Into<u64>,
+{
+    values
+        .iter()
+        .map(|&value| ConfigValue::Unsigned(value.into()))
+        .collect()
+}
+
+#[cfg(any(feature = "yaml", feature = "json"))]
+fn floats<T>(values: &[T], convert: fn(T) -> f64) -> Vec<ConfigValue>
+where
+    T: Copy + fmt::Display,
+{
+    values
+        .iter()
+        .map(|&value| {
+            // Use the shortest decimal representation of the original value, so that 0.1f32
+            // becomes 0.1 and not 0.10000000149011612
+            let value = format!("{}", value)
+                .parse()
+                .unwrap_or_else(|_| convert(value));
+            ConfigValue::Float(value)
+        })
+        .collect()
+}
+
+/// The kinds of errors that can occur when storing a value from a document in a register
+#[derive(Debug)]
+pub enum ValueErrorKind {
+    /// The value can't be converted into the type of the register
+    Type,
+    /// A number is outside the range of the register type
+    Range,
+    /// The value has too many elements or is too long
+    Length,
+    /// The register name is empty or too long
+    Name,
+    /// The register did not accept the value
+    Write(WriteError),
+}
+
+impl fmt::Display for ValueErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValueErrorKind::Type => f.write_str("Value can't be converted to the register type"),
+            ValueErrorKind::Range => f.write_str("Value out of range"),
+            ValueErrorKind::Length => f.write_str("Value too long"),
+            ValueErrorKind::Name => f.write_str("Invalid register name"),
+            ValueErrorKind::Write(e) => write!(f, "Register rejected value: {}", e),
+        }
+    }
+}
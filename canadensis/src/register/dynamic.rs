@@ -0,0 +1,278 @@
+//!
+//! A register block with registers that are added and removed at runtime
+//!
+
+#[cfg(any(feature = "toml", feature = "yaml", feature = "json"))]
+mod config;
+
+#[cfg(any(feature = "toml", feature = "yaml", feature = "json"))]
+pub use self::config::ConfigError;
+#[cfg(any(feature = "toml", feature = "yaml", feature = "json"))]
+pub use crate::register::document::ValueErrorKind;
+
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::mem;
+use std::error::Error;
+
+use crate::register::{copy_value, Access, Register, RegisterBlock, WriteError};
+use canadensis_data_types::uavcan::register::value_1_0::Value;
+
+/// The maximum length of a register name, in bytes
+const MAX_NAME_LENGTH: usize = 256;
+
+/// A register block that stores its registers on the heap
+///
+/// Registers can be added and removed while the block is in use. The registers are always
+/// sorted by name, so the index of each register (used in `uavcan.register.List` responses)
+/// depends only on the set of registers, not on the order in which they were added.
+///
+/// Each register can have a validator that accepts or rejects new values, and a callback that
+/// is called after the register value changes.
+///
+/// # Examples
+///
+/// ```
+/// # use canadensis::register::dynamic::DynamicRegisterBlock;
+/// # use canadensis::register::{Access, RegisterBlock, RegisterHandler};
+/// # use canadensis_data_types::uavcan::primitive::array::natural16_1_0::Natural16;
+/// # use canadensis_data_types::uavcan::register::value_1_0::Value;
+/// let mut block = DynamicRegisterBlock::new();
+/// block
+///     .add(
+///         "uavcan.node.id",
+///         Value::Natural16(Natural16 {
+///             value: heapless::Vec::from_slice(&[42]).unwrap(),
+///         }),
+///         Access {
+///             mutable: true,
+///             persistent: true,
+///         },
+///     )
+///     .unwrap()
+///     .set_validator(|value| matches!(value, Value::Natural16(id) if id.value.len() == 1));
+/// let handler = RegisterHandler::new(block);
+/// ```
+#[derive(Default)]
+pub struct DynamicRegisterBlock {
+    /// The registers, sorted by name
+    registers: Vec<DynamicRegister>,
+}
+
+impl DynamicRegisterBlock {
+    /// Creates an empty register block
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a register
+    ///
+    /// The type of `value` determines the type of the register. Later writes to the register
+    /// must have the same type, but may have a different number of elements.
+    ///
+    /// On success, this function returns a reference to the new register, which can be used
+    /// to set a validator and a callback.
+    pub fn add<N>(
+        &mut self,
+        name: N,
+        value: Value,
+        access: Access,
+    ) -> Result<&mut DynamicRegister, AddError>
+    where
+        N: Into<String>,
+    {
+        let name = name.into();
+        if name.is_empty() || name.len() > MAX_NAME_LENGTH {
+            return Err(AddError::InvalidName);
+        }
+        if matches!(value, Value::Empty(_)) {
+            return Err(AddError::Empty);
+        }
+        match self.search(&name) {
+            Ok(_) => Err(AddError::Duplicate),
+            Err(index) => {
+                self.registers.insert(
+                    index,
+                    DynamicRegister {
+                        name,
+                        access,
+                        value,
+                        validator: None,
+                        callback: None,
+                    },
+                );
+                Ok(&mut self.registers[index])
+            }
+        }
+    }
+
+    /// Removes and returns the register with the provided name
+    pub fn remove(&mut self, name: &str) -> Option<DynamicRegister> {
+        self.search(name)
+            .ok()
+            .map(|index| self.registers.remove(index))
+    }
+
+    /// Returns a reference to the register with the provided name
+    pub fn get(&self, name: &str) -> Option<&DynamicRegister> {
+        self.search(name).ok().map(|index| &self.registers[index])
+    }
+
+    /// Returns a mutable reference to the register with the provided name
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut DynamicRegister> {
+        self.search(name)
+            .ok()
+            .map(move |index| &mut self.registers[index])
+    }
+
+    /// Returns true if this block contains a register with the provided name
+    pub fn contains(&self, name: &str) -> bool {
+        self.search(name).is_ok()
+    }
+
+    /// Returns the number of registers in this block
+    pub fn len(&self) -> usize {
+        self.registers.len()
+    }
+
+    /// Returns true if this block contains no registers
+    pub fn is_empty(&self) -> bool {
+        self.registers.is_empty()
+    }
+
+    /// Returns an iterator over the registers, sorted by name
+    pub fn iter(&self) -> impl Iterator<Item = &DynamicRegister> {
+        self.registers.iter()
+    }
+
+    fn search(&self, name: &str) -> Result<usize, usize> {
+        self.registers
+            .binary_search_by(|register| register.name.as_str().cmp(name))
+    }
+}
+
+impl RegisterBlock for DynamicRegisterBlock {
+    fn register_by_index(&self, index: usize) -> Option<&dyn Register> {
+        self.registers
+            .get(index)
+            .map(|register| register as &dyn Register)
+    }
+
+    fn register_by_index_mut(&mut self, index: usize) -> Option<&mut dyn Register> {
+        self.registers
+            .get_mut(index)
+            .map(|register| register as &mut dyn Register)
+    }
+
+    fn register_by_name_mut(&mut self, name: &str) -> Option<&mut dyn Register> {
+        self.get_mut(name)
+            .map(|register| register as &mut dyn Register)
+    }
+}
+
+/// A register in a [`DynamicRegisterBlock`]
+pub struct DynamicRegister {
+    name: String,
+    access: Access,
+    value: Value,
+    validator: Option<Validator>,
+    callback: Option<Callback>,
+}
+
+/// A function that accepts or rejects a new register value
+type Validator = Box<dyn FnMut(&Value) -> bool>;
+/// A function that is called with the name and new value of a register
+type Callback = Box<dyn FnMut(&str, &Value)>;
+
+impl DynamicRegister {
+    /// Returns the current value of this register
+    pub fn value(&self) -> &Value {
+        &self.value
+    }
+
+    /// Sets a function that accepts or rejects new values for this register
+    ///
+    /// The validator is only called with values of the correct type. If it returns false,
+    /// the value of the register does not change.
+    pub fn set_validator<F>(&mut self, validator: F) -> &mut Self
+    where
+        F: FnMut(&Value) -> bool + 'static,
+    {
+        self.validator = Some(Box::new(validator));
+        self
+    }
+
+    /// Sets a function that is called with the register name and new value after each
+    /// successful write
+    pub fn set_callback<F>(&mut self, callback: F) -> &mut Self
+    where
+        F: FnMut(&str, &Value) + 'static,
+    {
+        self.callback = Some(Box::new(callback));
+        self
+    }
+}
+
+impl Register for DynamicRegister {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn access(&self) -> Access {
+        self.access.clone()
+    }
+
+    fn read(&self) -> Value {
+        copy_value(&self.value)
+    }
+
+    fn write(&mut self, value: &Value) -> Result<(), WriteError> {
+        if mem::discriminant(value) != mem::discriminant(&self.value) {
+            return Err(WriteError::Type);
+        }
+        if let Some(validator) = &mut self.validator {
+            if !validator(value) {
+                return Err(WriteError::Invalid);
+            }
+        }
+        self.value = copy_value(value);
+        if let Some(callback) = &mut self.callback {
+            callback(&self.name, &self.value);
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for DynamicRegister {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DynamicRegister")
+            .field("name", &self.name)
+            .field("access", &self.access)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Errors that can occur when adding a register to a [`DynamicRegisterBlock`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AddError {
+    /// The name is empty or longer than 256 bytes
+    InvalidName,
+    /// The block already contains a register with the same name
+    Duplicate,
+    /// The value is empty, so the register would have no type
+    Empty,
+}
+
+impl fmt::Display for AddError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AddError::InvalidName => f.write_str("Register name is empty or too long"),
+            AddError::Duplicate => f.write_str("A register with the same name already exists"),
+            AddError::Empty => f.write_str("Register value is empty"),
+        }
+    }
+}
+
+impl Error for AddError {}
@@ -0,0 +1,132 @@
+//!
+//! Loading register values from TOML, YAML, and JSON documents
+//!
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use std::error::Error;
+
+use super::DynamicRegisterBlock;
+use crate::register::document::{convert, flatten, infer_type, ConfigValue, ValueErrorKind};
+use crate::register::{Access, Register};
+
+/// Loading values from documents
+///
+/// A document is a table (or map, or object) of register names and values. Nested tables are
+/// flattened by joining the keys with `.`, so these TOML documents are equivalent:
+///
+/// ```toml
+/// "uavcan.node.id" = 42
+/// ```
+///
+/// ```toml
+/// [uavcan.node]
+/// id = 42
+/// ```
+///
+/// Each value is a string, boolean, number, or array of booleans or numbers. If the block
+/// already has a register with the same name, the value is converted to the type of the register
+/// and written to it (this uses the validator and calls the callback, if any). Otherwise, a new
+/// mutable and persistent register is added with a type that depends on the value:
+///
+/// * String: `uavcan.primitive.String`
+/// * Booleans: `uavcan.primitive.array.Bit`
+/// * Integers: `uavcan.primitive.array.Integer64`
+/// * Other numbers: `uavcan.primitive.array.Real64`
+///
+/// On success, each function returns the number of registers that were written or added.
+impl DynamicRegisterBlock {
+    /// Loads register values from a TOML document
+    #[cfg(feature = "toml")]
+    pub fn load_toml(&mut self, document: &str) -> Result<usize, ConfigError> {
+        let document: ConfigValue =
+            toml::from_str(document).map_err(|e| ConfigError::Parse(e.to_string()))?;
+        self.load_document(document)
+    }
+
+    /// Loads register values from a YAML document
+    #[cfg(feature = "yaml")]
+    pub fn load_yaml(&mut self, document: &str) -> Result<usize, ConfigError> {
+        let document: ConfigValue =
+            serde_yaml::from_str(document).map_err(|e| ConfigError::Parse(e.to_string()))?;
+        self.load_document(document)
+    }
+
+    /// Loads register values from a JSON document
+    #[cfg(feature = "json")]
+    pub fn load_json(&mut self, document: &str) -> Result<usize, ConfigError> {
+        let document: ConfigValue =
+            serde_json::from_str(document).map_err(|e| ConfigError::Parse(e.to_string()))?;
+        self.load_document(document)
+    }
+
+    fn load_document(&mut self, document: ConfigValue) -> Result<usize, ConfigError> {
+        let entries = match document {
+            ConfigValue::Map(entries) => entries,
+            _ => {
+                return Err(ConfigError::Parse(String::from(
+                    "Document is not a table of register names and values",
+                )))
+            }
+        };
+        let mut values = Vec::new();
+        flatten(String::new(), entries, &mut values);
+
+        for (name, value) in &values {
+            let make_error = |kind| ConfigError::Value {
+                name: name.clone(),
+                kind,
+            };
+            match self.get_mut(name) {
+                Some(register) => {
+                    let value = convert(register.value(), value).map_err(make_error)?;
+                    register
+                        .write(&value)
+                        .map_err(|e| make_error(ValueErrorKind::Write(e)))?;
+                }
+                None => {
+                    let template =
+                        infer_type(value).ok_or_else(|| make_error(ValueErrorKind::Type))?;
+                    let value = convert(&template, value).map_err(make_error)?;
+                    self.add(
+                        name.as_str(),
+                        value,
+                        Access {
+                            mutable: true,
+                            persistent: true,
+                        },
+                    )
+                    .map_err(|_| make_error(ValueErrorKind::Name))?;
+                }
+            }
+            log::debug!("Loaded register {} from document", name);
+        }
+        Ok(values.len())
+    }
+}
+
+/// An error that occurred when loading register values from a document
+#[derive(Debug)]
+pub enum ConfigError {
+    /// The document could not be parsed, or was not a table
+    Parse(String),
+    /// A value in the document could not be stored in a register
+    Value {
+        /// The register name
+        name: String,
+        /// The reason the value could not be stored
+        kind: ValueErrorKind,
+    },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Parse(message) => write!(f, "Invalid document: {}", message),
+            ConfigError::Value { name, kind } => write!(f, "Register {}: {}", name, kind),
+        }
+    }
+}
+
+impl Error for ConfigError {}
@@ -0,0 +1,262 @@
+//!
+//! Loading register values from environment variables
+//!
+//! This uses the same convention as other Cyphal implementations, like PyCyphal and Yakut:
+//! the environment variable for a register has the name of the register in upper case, with
+//! each `.` replaced by `__`. For example, the value of `uavcan.node.id` comes from
+//! `UAVCAN__NODE__ID`.
+//!
+//! The text of an environment variable is interpreted according to the type of the current value
+//! of its register:
+//!
+//! * String: The text is the value
+//! * Unstructured: The bytes of the text are the value
+//! * Bit: Values separated by spaces or commas, each `true`, `false`, or an integer
+//!   (non-zero means true)
+//! * Integer, natural, and real: Numbers separated by spaces or commas
+//!
+
+use alloc::string::String;
+use core::fmt;
+use core::str::FromStr;
+use std::env;
+use std::error::Error;
+use std::ffi::OsString;
+
+use crate::register::{Register, RegisterBlock, WriteError};
+use canadensis_data_types::uavcan::primitive::array::bit_1_0::Bit;
+use canadensis_data_types::uavcan::primitive::array::integer16_1_0::Integer16;
+use canadensis_data_types::uavcan::primitive::array::integer32_1_0::Integer32;
+use canadensis_data_types::uavcan::primitive::array::integer64_1_0::Integer64;
+use canadensis_data_types::uavcan::primitive::array::integer8_1_0::Integer8;
+use canadensis_data_types::uavcan::primitive::array::natural16_1_0::Natural16;
+use canadensis_data_types::uavcan::primitive::array::natural32_1_0::Natural32;
+use canadensis_data_types::uavcan::primitive::array::natural64_1_0::Natural64;
+use canadensis_data_types::uavcan::primitive::array::natural8_1_0::Natural8;
+use canadensis_data_types::uavcan::primitive::array::real16_1_0::Real16;
+use canadensis_data_types::uavcan::primitive::array::real32_1_0::Real32;
+use canadensis_data_types::uavcan::primitive::array::real64_1_0::Real64;
+use canadensis_data_types::uavcan::primitive::string_1_0;
+use canadensis_data_types::uavcan::primitive::unstructured_1_0;
+use canadensis_data_types::uavcan::register::value_1_0::Value;
+use canadensis_encoding::bits::BitArray;
+use half::f16;
+
+/// The maximum number of values in a bit array
+const MAX_BITS: usize = 2048;
+
+/// Returns the name of the environment variable that holds the value of a register
+pub fn variable_name(register_name: &str) -> String {
+    register_name.to_uppercase().replace('.', "__")
+}
+
+/// Writes registers using values from the environment variables of this process
+///
+/// For each register in the block that has a corresponding environment variable, this function
+/// parses the variable and writes the value to the register. Registers without environment
+/// variables are not changed.
+///
+/// This function stops and returns an error if a variable can't be converted into a value for
+/// its register, if the register rejects the value, or if the variable corresponds to a
+/// register that is not mutable.
+///
+/// On success, this function returns the number of registers that were written.
+pub fn load_environment<B>(block: &mut B) -> Result<usize, EnvironmentError>
+where
+    B: RegisterBlock,
+{
+    load_variables(block, |name| env::var_os(name))
+}
+
+/// Writes registers using values from a function that looks up environment variables
+///
+/// `lookup` is called with the name of a variable and returns its value, or None if the variable
+/// does not exist. This is otherwise the same as [`load_environment`].
+pub fn load_variables<B, F>(block: &mut B, mut lookup: F) -> Result<usize, EnvironmentError>
+where
+    B: RegisterBlock,
+    F: FnMut(&str) -> Option<OsString>,
+{
+    let mut loaded = 0;
+    let mut index = 0;
+    while let Some(register) = block.register_by_index_mut(index) {
+        index += 1;
+        let variable = variable_name(register.name());
+        let text = match lookup(&variable) {
+            Some(text) => text,
+            None => continue,
+        };
+        let make_error = |kind| EnvironmentError {
+            variable: variable.clone(),
+            kind,
+        };
+        let text = text
+            .into_string()
+            .map_err(|_| make_error(EnvironmentErrorKind::NotUnicode))?;
+        write_register(register, &text).map_err(make_error)?;
+        log::debug!("Loaded register {} from {}", register.name(), variable);
+        loaded += 1;
+    }
+    Ok(loaded)
+}
+
+/// Parses text and writes it to a register
+fn write_register(register: &mut dyn Register, text: &str) -> Result<(), EnvironmentErrorKind> {
+    if !register.access().mutable {
+        return Err(EnvironmentErrorKind::NotMutable);
+    }
+    let value = parse_value(&register.read(), text)?;
+    register.write(&value).map_err(EnvironmentErrorKind::Write)
+}
+
+/// Parses text into a value of the same type as `current`
+///
+/// The number of array elements is determined by the text, not by `current`.
+pub fn parse_value(current: &Value, text: &str) -> Result<Value, EnvironmentErrorKind> {
+    let value = match current {
+        Value::Empty(_) => return Err(EnvironmentErrorKind::Parse),
+        Value::String(_) => Value::String(string_1_0::String {
+            value: heapless::Vec::from_slice(text.as_bytes())
+                .map_err(|_| EnvironmentErrorKind::Length)?,
+        }),
+        Value::Unstructured(_) => Value::Unstructured(unstructured_1_0::Unstructured {
+            value: heapless::Vec::from_slice(text.as_bytes())
+                .map_err(|_| EnvironmentErrorKind::Length)?,
+        }),
+        Value::Bit(_) => {
+            let count = items(text).count();
+            if count > MAX_BITS {
+                return Err(EnvironmentErrorKind::Length);
+            }
+            let mut bits = BitArray::new(count);
+            for (i, item) in items(text).enumerate() {
+                bits.set(i, parse_bool(item)?);
+            }
+            Value::Bit(Bit { value: bits })
+        }
+        Value::Integer64(_) => Value::Integer64(Integer64 {
+            value: parse_numbers(text)?,
+        }),
+        Value::Integer32(_) => Value::Integer32(Integer32 {
+            value: parse_numbers(text)?,
+        }),
+        Value::Integer16(_) => Value::Integer16(Integer16 {
+            value: parse_numbers(text)?,
+        }),
+        Value::Integer8(_) => Value::Integer8(Integer8 {
+            value: parse_numbers(text)?,
+        }),
+        Value::Natural64(_) => Value::Natural64(Natural64 {
+            value: parse_numbers(text)?,
+        }),
+        Value::Natural32(_) => Value::Natural32(Natural32 {
+            value: parse_numbers(text)?,
+        }),
+        Value::Natural16(_) => Value::Natural16(Natural16 {
+            value: parse_numbers(text)?,
+        }),
+        Value::Natural8(_) => Value::Natural8(Natural8 {
+            value: parse_numbers(text)?,
+        }),
+        Value::Real64(_) => Value::Real64(Real64 {
+            value: parse_numbers(text)?,
+        }),
+        Value::Real32(_) => Value::Real32(Real32 {
+            value: parse_numbers(text)?,
+        }),
+        Value::Real16(_) => {
+            let values: heapless::Vec<f32, 128> = parse_numbers(text)?;
+            Value::Real16(Real16 {
+                value: values.iter().map(|&value| f16::from_f32(value)).collect(),
+            })
+        }
+    };
+    Ok(value)
+}
+
+/// Returns the items in a list separated by spaces or commas
+fn items(text: &str) -> impl Iterator<Item = &str> {
+    text.split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|item| !item.is_empty())
+}
+
+fn parse_numbers<T, const N: usize>(text: &str) -> Result<heapless::Vec<T, N>, EnvironmentErrorKind>
+where
+    T: FromStr,
+{
+    let mut values = heapless::Vec::new();
+    for item in items(text) {
+        let value = item.parse().map_err(|_| EnvironmentErrorKind::Parse)?;
+        values
+            .push(value)
+            .map_err(|_| EnvironmentErrorKind::Length)?;
+    }
+    Ok(values)
+}
+
+fn parse_bool(item: &str) -> Result<bool, EnvironmentErrorKind> {
+    match item {
+        "true" => Ok(true),
+        "false" => Ok(false),
+        _ => item
+            .parse::<i64>()
+            .map(|value| value != 0)
+            .map_err(|_| EnvironmentErrorKind::Parse),
+    }
+}
+
+/// An error that occurred when loading a register value from an environment variable
+#[derive(Debug)]
+pub struct EnvironmentError {
+    variable: String,
+    kind: EnvironmentErrorKind,
+}
+
+impl EnvironmentError {
+    /// Returns the name of the environment variable
+    pub fn variable(&self) -> &str {
+        &self.variable
+    }
+    /// Returns the kind of error
+    pub fn kind(&self) -> &EnvironmentErrorKind {
+        &self.kind
+    }
+}
+
+impl fmt::Display for EnvironmentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Environment variable {}: {}", self.variable, self.kind)
+    }
+}
+
+impl Error for EnvironmentError {}
+
+/// The kinds of errors that can occur when loading a register value from an environment variable
+#[derive(Debug)]
+pub enum EnvironmentErrorKind {
+    /// The variable is not valid Unicode
+    NotUnicode,
+    /// The text could not be parsed as the type of the register
+    Parse,
+    /// The variable has too many values or is too long
+    Length,
+    /// The register is not mutable
+    NotMutable,
+    /// The register did not accept the value (for example, because the value had the wrong
+    /// number of elements)
+    Write(WriteError),
+}
+
+impl fmt::Display for EnvironmentErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EnvironmentErrorKind::NotUnicode => f.write_str("Value is not valid Unicode"),
+            EnvironmentErrorKind::Parse => {
+                f.write_str("Value can't be parsed as the type of the register")
+            }
+            EnvironmentErrorKind::Length => f.write_str("Value too long"),
+            EnvironmentErrorKind::NotMutable => f.write_str("Register is not mutable"),
+            EnvironmentErrorKind::Write(e) => write!(f, "Register rejected value: {}", e),
+        }
+    }
+}
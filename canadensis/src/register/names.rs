@@ -0,0 +1,266 @@
+//!
+//! Register names of blocks that are defined with `#[register]` attributes
+//!
+//! `#[derive(RegisterBlock)]` uses the items in this module to give nested blocks their name
+//! prefixes and to check at compile time that all register names in a block are distinct.
+//!
+
+use alloc::borrow::Cow;
+use alloc::format;
+use alloc::string::String;
+
+/// The maximum length of a register name, in bytes
+const MAX_NAME_LENGTH: usize = 256;
+
+/// The maximum number of name segments (prefixes, array indexes, and register names) in a full
+/// register name
+const MAX_SEGMENTS: usize = 16;
+
+/// Describes a register or a group of registers in a block
+#[derive(Debug)]
+pub enum NameEntry {
+    /// A register with a name
+    Register(&'static str),
+    /// A nested block
+    ///
+    /// If `prefix` is not empty, each register name in the block is `prefix.name`.
+    Block {
+        /// The prefix of each register name, or an empty string
+        prefix: &'static str,
+        /// The registers in the block
+        names: &'static [NameEntry],
+    },
+    /// An array of nested blocks
+    ///
+    /// Each register name in the block at index `i` is `prefix.i.name`.
+    Array {
+        /// The prefix of each register name
+        prefix: &'static str,
+        /// The number of blocks
+        length: usize,
+        /// The registers in each block
+        names: &'static [NameEntry],
+    },
+}
+
+/// Returns the number of registers described by a list of entries
+pub const fn count(entries: &[NameEntry]) -> usize {
+    let mut total = 0;
+    let mut i = 0;
+    while i < entries.len() {
+        total += match &entries[i] {
+            NameEntry::Register(_) => 1,
+            NameEntry::Block { names, .. } => count(names),
+            NameEntry::Array { length, names, .. } => *length * count(names),
+        };
+        i += 1;
+    }
+    total
+}
+
+/// Checks that all register names described by a list of entries are distinct, not empty, and
+/// not too long
+///
+/// # Panics
+///
+/// This function panics if any name is invalid. `#[derive(RegisterBlock)]` calls this function
+/// in a constant, so an invalid name causes a compile error.
+pub const fn check(entries: &[NameEntry]) {
+    let total = count(entries);
+    let mut i = 0;
+    while i < total {
+        let name = full_name(entries, i);
+        let length = name.len();
+        if length == 0 {
+            panic!("Register name is empty");
+        }
+        if length > MAX_NAME_LENGTH {
+            panic!("Register name is longer than 256 bytes");
+        }
+        let mut j = i + 1;
+        while j < total {
+            if name.equals(&full_name(entries, j)) {
+                panic!("Duplicate register name in register block");
+            }
+            j += 1;
+        }
+        i += 1;
+    }
+}
+
+/// Returns the name of a register in a nested block
+///
+/// `prefix` is empty or ends with `.`.
+pub fn join(prefix: &str, name: &'static str) -> Cow<'static, str> {
+    if prefix.is_empty() {
+        Cow::Borrowed(name)
+    } else {
+        Cow::Owned(format!("{}{}", prefix, name))
+    }
+}
+
+/// Returns the prefix for the registers of a nested block, ending with `.`
+///
+/// `outer_prefix` is the prefix of the block that contains the nested block. If `index` is
+/// not None, the nested block is an element of an array.
+pub fn block_prefix(outer_prefix: &str, prefix: &str, index: Option<usize>) -> String {
+    match (prefix.is_empty(), index) {
+        (true, None) => String::from(outer_prefix),
+        (true, Some(index)) => format!("{}{}.", outer_prefix, index),
+        (false, None) => format!("{}{}.", outer_prefix, prefix),
+        (false, Some(index)) => format!("{}{}.{}.", outer_prefix, prefix, index),
+    }
+}
+
+#[derive(Copy, Clone)]
+enum Segment {
+    Text(&'static str),
+    Index(usize),
+}
+
+impl Segment {
+    const fn len(&self) -> usize {
+        match *self {
+            Segment::Text(text) => text.len(),
+            Segment::Index(index) => digits(index),
+        }
+    }
+
+    const fn byte(&self, position: usize) -> u8 {
+        match *self {
+            Segment::Text(text) => text.as_bytes()[position],
+            Segment::Index(index) => {
+                let mut divisor = 1;
+                let mut i = position + 1;
+                while i < digits(index) {
+                    divisor *= 10;
+                    i += 1;
+                }
+                b'0' + ((index / divisor) % 10) as u8
+            }
+        }
+    }
+}
+
+/// A register name made of segments separated by `.`
+struct FullName {
+    segments: [Segment; MAX_SEGMENTS],
+    count: usize,
+}
+
+impl FullName {
+    const fn push(mut self, segment: Segment) -> Self {
+        if self.count == MAX_SEGMENTS {
+            panic!("Register blocks are nested too deeply");
+        }
+        self.segments[self.count] = segment;
+        self.count += 1;
+        self
+    }
+
+    const fn len(&self) -> usize {
+        let mut length = 0;
+        let mut i = 0;
+        while i < self.count {
+            if i != 0 {
+                length += 1;
+            }
+            length += self.segments[i].len();
+            i += 1;
+        }
+        length
+    }
+
+    const fn byte(&self, mut position: usize) -> u8 {
+        let mut i = 0;
+        loop {
+            if i != 0 {
+                if position == 0 {
+                    return b'.';
+                }
+                position -= 1;
+            }
+            let length = self.segments[i].len();
+            if position < length {
+                return self.segments[i].byte(position);
+            }
+            position -= length;
+            i += 1;
+        }
+    }
+
+    const fn equals(&self, other: &FullName) -> bool {
+        let length = self.len();
+        if length != other.len() {
+            return false;
+        }
+        let mut i = 0;
+        while i < length {
+            if self.byte(i) != other.byte(i) {
+                return false;
+            }
+            i += 1;
+        }
+        true
+    }
+}
+
+/// Returns the full name of the register at an index
+const fn full_name(entries: &[NameEntry], index: usize) -> FullName {
+    let name = FullName {
+        segments: [Segment::Index(0); MAX_SEGMENTS],
+        count: 0,
+    };
+    append_name(name, entries, index)
+}
+
+const fn append_name(mut name: FullName, entries: &[NameEntry], mut index: usize) -> FullName {
+    let mut i = 0;
+    while i < entries.len() {
+        match &entries[i] {
+            NameEntry::Register(register_name) => {
+                if index == 0 {
+                    return name.push(Segment::Text(register_name));
+                }
+                index -= 1;
+            }
+            NameEntry::Block { prefix, names } => {
+                let block_count = count(names);
+                if index < block_count {
+                    if !prefix.is_empty() {
+                        name = name.push(Segment::Text(prefix));
+                    }
+                    return append_name(name, names, index);
+                }
+                index -= block_count;
+            }
+            NameEntry::Array {
+                prefix,
+                length,
+                names,
+            } => {
+                let block_count = count(names);
+                if index < *length * block_count {
+                    if !prefix.is_empty() {
+                        name = name.push(Segment::Text(prefix));
+                    }
+                    name = name.push(Segment::Index(index / block_count));
+                    return append_name(name, names, index % block_count);
+                }
+                index -= *length * block_count;
+            }
+        }
+        i += 1;
+    }
+    panic!("Register index out of range")
+}
+
+/// Returns the number of decimal digits in a number
+const fn digits(mut value: usize) -> usize {
+    let mut digits = 1;
+    while value >= 10 {
+        value /= 10;
+        digits += 1;
+    }
+    digits
+}
@@ -0,0 +1,606 @@
+//!
+//! Standard registers that configure the subject and service IDs of ports
+//!
+//! The Cyphal specification defines registers that let an integrator choose the port IDs that a
+//! node uses without changing its software. For a port named `<name>`, the registers are:
+//!
+//! * `uavcan.pub.<name>.id` and `uavcan.pub.<name>.type` for a publisher
+//! * `uavcan.sub.<name>.id` and `uavcan.sub.<name>.type` for a subscriber
+//! * `uavcan.cln.<name>.id` and `uavcan.cln.<name>.type` for a service client
+//! * `uavcan.srv.<name>.id` and `uavcan.srv.<name>.type` for a service server
+//!
+//! The `.id` register is a mutable and persistent `natural16[1]`. The value 65535 means that the
+//! port is not configured and should not be used. The `.type` register is an immutable string
+//! with the full name and version of the data type, like `uavcan.node.Heartbeat.1.0`.
+//!
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::fmt;
+use core::marker::PhantomData;
+
+use crate::node::NodeError;
+use crate::register::{Access, Register, RegisterBlock, WriteError};
+use crate::{Node, PublishToken, ServiceToken, StartSendError, SubscriptionToken};
+use canadensis_core::time::milliseconds;
+use canadensis_core::transport::{Receiver, Transmitter};
+use canadensis_core::{Priority, ServiceId, SubjectId};
+use canadensis_data_types::uavcan::primitive::array::natural16_1_0::Natural16;
+use canadensis_data_types::uavcan::primitive::string_1_0;
+use canadensis_data_types::uavcan::register::value_1_0::Value;
+use canadensis_encoding::{DataType, Message, Request};
+
+/// The port ID value that means that a port is not configured
+pub const UNSET_PORT_ID: u16 = 0xffff;
+
+/// The kind of a port
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PortKind {
+    /// Publishes messages on a subject
+    Publisher,
+    /// Subscribes to messages on a subject
+    Subscriber,
+    /// Sends requests for a service and receives the responses
+    Client,
+    /// Receives requests for a service and sends responses
+    Server,
+}
+
+impl PortKind {
+    /// Returns the part of the register names that identifies this kind of port
+    /// (`pub`, `sub`, `cln`, or `srv`)
+    pub fn register_prefix(&self) -> &'static str {
+        match self {
+            PortKind::Publisher => "pub",
+            PortKind::Subscriber => "sub",
+            PortKind::Client => "cln",
+            PortKind::Server => "srv",
+        }
+    }
+
+    /// Returns true if a value is a valid subject ID or service ID for this kind of port
+    fn is_valid_id(&self, id: u16) -> bool {
+        match self {
+            PortKind::Publisher | PortKind::Subscriber => SubjectId::try_from(id).is_ok(),
+            PortKind::Client | PortKind::Server => ServiceId::try_from(id).is_ok(),
+        }
+    }
+}
+
+/// Identifies a port in a [`PortRegisters`]
+///
+/// The type parameter `T` is the message type (for publishers and subscribers) or the request
+/// type (for clients and servers).
+pub struct PortHandle<T> {
+    index: usize,
+    _type: PhantomData<fn() -> T>,
+}
+
+impl<T> Clone for PortHandle<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<T> Copy for PortHandle<T> {}
+
+impl<T> fmt::Debug for PortHandle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("PortHandle").field(&self.index).finish()
+    }
+}
+
+/// A register block that contains the standard port ID and type registers for a set of ports,
+/// and applies changes to the port IDs to a node
+///
+/// When another node writes an `.id` register, nothing changes until
+/// [`apply`](#method.apply) is called. Then this stops using the port with the old ID (by
+/// stopping publishing, unsubscribing, or stopping sending requests) and starts using the port
+/// with the new ID. When the node is a [`BasicNode`](crate::node::BasicNode), this also updates
+/// its port list.
+///
+/// Because publishers and clients may change their IDs, the application gets a token from
+/// [`publish_token`](#method.publish_token) or [`service_token`](#method.service_token) each
+/// time it publishes a message or sends a request.
+///
+/// Basic steps:
+/// 1. Create a `PortRegisters` and add the ports that the application uses
+/// 2. Combine the port registers with the other registers of the node (for example, in a tuple
+///    `(registers, port_registers)`), and optionally load persistent values from storage
+/// 3. Call `PortRegisters::apply` with the node to start using the configured ports
+/// 4. Pass the registers to a [`RegisterHandler`](crate::register::RegisterHandler) so that
+///    other nodes can change them
+/// 5. After the node handles incoming transfers, call `PortRegisters::apply` again to apply any
+///    changes
+pub struct PortRegisters {
+    ports: Vec<Port>,
+}
+
+/// The registers and state of a port
+struct Port {
+    id: PortIdRegister,
+    data_type: PortTypeRegister,
+    /// The ID that the node is currently using for this port
+    active: Option<u16>,
+    settings: TransferSettings,
+}
+
+/// Settings used when starting a port
+struct TransferSettings {
+    /// The maximum payload size of incoming transfers (messages for subscribers, requests for
+    /// servers, and responses for clients)
+    payload_size_max: usize,
+    /// The transfer ID timeout for incoming transfers, or the transmit timeout for publishers
+    timeout_ms: u32,
+    /// The priority of outgoing messages or requests
+    priority: Priority,
+}
+
+impl PortRegisters {
+    /// Creates an empty set of port registers
+    pub fn new() -> Self {
+        PortRegisters { ports: Vec::new() }
+    }
+
+    /// Adds a publisher
+    ///
+    /// * `name`: The name of the port, used in the register names
+    /// * `type_name`: The full name and version of the message type, like
+    ///   `uavcan.node.Heartbeat.1.0`
+    /// * `default_subject`: The subject ID to use if the register has not been written
+    /// * `priority`: The priority of published messages
+    ///
+    /// Messages have a transmit timeout of 1 second.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if a register name would be longer than 256 bytes.
+    pub fn add_publisher<T>(
+        &mut self,
+        name: &str,
+        type_name: &'static str,
+        default_subject: Option<SubjectId>,
+        priority: Priority,
+    ) -> PortHandle<T>
+    where
+        T: Message,
+    {
+        self.add(
+            PortKind::Publisher,
+            name,
+            type_name,
+            default_subject.map(u16::from),
+            TransferSettings {
+                payload_size_max: 0,
+                timeout_ms: 1000,
+                priority,
+            },
+        )
+    }
+
+    /// Adds a subscriber
+    ///
+    /// The maximum payload size is the extent of `T`, and the transfer ID timeout is 2 seconds
+    /// (the same as [`Node::subscribe`](crate::Node::subscribe)).
+    ///
+    /// The other parameters and panics are the same as [`add_publisher`](#method.add_publisher).
+    pub fn add_subscriber<T>(
+        &mut self,
+        name: &str,
+        type_name: &'static str,
+        default_subject: Option<SubjectId>,
+    ) -> PortHandle<T>
+    where
+        T: Message + DataType,
+    {
+        self.add(
+            PortKind::Subscriber,
+            name,
+            type_name,
+            default_subject.map(u16::from),
+            TransferSettings {
+                payload_size_max: T::extent(),
+                timeout_ms: 2000,
+                priority: Priority::Nominal,
+            },
+        )
+    }
+
+    /// Adds a service client
+    ///
+    /// * `type_name`: The full name and version of the service type, like
+    ///   `uavcan.node.GetInfo.1.0`
+    /// * `response_payload_size_max`: The maximum length of a response, in bytes
+    ///
+    /// The response timeout is 1 second.
+    ///
+    /// The other parameters and panics are the same as [`add_publisher`](#method.add_publisher).
+    pub fn add_client<T>(
+        &mut self,
+        name: &str,
+        type_name: &'static str,
+        default_service: Option<ServiceId>,
+        response_payload_size_max: usize,
+        priority: Priority,
+    ) -> PortHandle<T>
+    where
+        T: Request,
+    {
+        self.add(
+            PortKind::Client,
+            name,
+            type_name,
+            default_service.map(u16::from),
+            TransferSettings {
+                payload_size_max: response_payload_size_max,
+                timeout_ms: 1000,
+                priority,
+            },
+        )
+    }
+
+    /// Adds a service server
+    ///
+    /// The maximum request payload size is the extent of `T`, and the transfer ID timeout is
+    /// 1 second.
+    ///
+    /// The other parameters and panics are the same as [`add_client`](#method.add_client).
+    pub fn add_server<T>(
+        &mut self,
+        name: &str,
+        type_name: &'static str,
+        default_service: Option<ServiceId>,
+    ) -> PortHandle<T>
+    where
+        T: Request + DataType,
+    {
+        self.add(
+            PortKind::Server,
+            name,
+            type_name,
+            default_service.map(u16::from),
+            TransferSettings {
+                payload_size_max: T::extent(),
+                timeout_ms: 1000,
+                priority: Priority::Nominal,
+            },
+        )
+    }
+
+    fn add<T>(
+        &mut self,
+        kind: PortKind,
+        name: &str,
+        type_name: &'static str,
+        default_id: Option<u16>,
+        settings: TransferSettings,
+    ) -> PortHandle<T> {
+        let prefix = format!("uavcan.{}.{}", kind.register_prefix(), name);
+        let id_name = format!("{}.id", prefix);
+        let type_register_name = format!("{}.type", prefix);
+        assert!(
+            type_register_name.len() <= 256,
+            "Port register name too long"
+        );
+        assert!(type_name.len() <= 256, "Port type name too long");
+        let index = self.ports.len();
+        self.ports.push(Port {
+            id: PortIdRegister {
+                name: id_name,
+                kind,
+                value: default_id.unwrap_or(UNSET_PORT_ID),
+            },
+            data_type: PortTypeRegister {
+                name: type_register_name,
+                type_name,
+            },
+            active: None,
+            settings,
+        });
+        PortHandle {
+            index,
+            _type: PhantomData,
+        }
+    }
+
+    /// Returns the configured subject ID of a publisher or subscriber, or None if it is not
+    /// configured
+    ///
+    /// The configured ID may be different from the ID in use until [`apply`](#method.apply) is
+    /// called.
+    pub fn subject_id<T>(&self, port: PortHandle<T>) -> Option<SubjectId> {
+        SubjectId::try_from(self.ports[port.index].id.value).ok()
+    }
+
+    /// Returns the configured service ID of a client or server, or None if it is not configured
+    ///
+    /// The configured ID may be different from the ID in use until [`apply`](#method.apply) is
+    /// called.
+    pub fn service_id<T>(&self, port: PortHandle<T>) -> Option<ServiceId> {
+        ServiceId::try_from(self.ports[port.index].id.value).ok()
+    }
+
+    /// Returns a token that can be used to publish messages, or None if the publisher is not
+    /// in use
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the port is not a publisher.
+    pub fn publish_token<T>(&self, port: PortHandle<T>) -> Option<PublishToken<T>>
+    where
+        T: Message,
+    {
+        self.active_id(port, PortKind::Publisher)
+            .map(|id| PublishToken(SubjectId::try_from(id).unwrap(), PhantomData))
+    }
+
+    /// Returns a token that identifies the subscription of a subscriber, or None if the
+    /// subscriber is not in use
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the port is not a subscriber.
+    pub fn subscription_token<T>(&self, port: PortHandle<T>) -> Option<SubscriptionToken<T>>
+    where
+        T: Message,
+    {
+        self.active_id(port, PortKind::Subscriber)
+            .map(|id| SubscriptionToken(SubjectId::try_from(id).unwrap(), PhantomData))
+    }
+
+    /// Returns a token that can be used to send requests, or None if the client is not in use
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the port is not a client.
+    pub fn service_token<T>(&self, port: PortHandle<T>) -> Option<ServiceToken<T>>
+    where
+        T: Request,
+    {
+        self.active_id(port, PortKind::Client)
+            .map(|id| ServiceToken(ServiceId::try_from(id).unwrap(), PhantomData))
+    }
+
+    /// Returns the ID of a server that is in use, or None if the server is not in use
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the port is not a server.
+    pub fn server_service_id<T>(&self, port: PortHandle<T>) -> Option<ServiceId>
+    where
+        T: Request,
+    {
+        self.active_id(port, PortKind::Server)
+            .map(|id| ServiceId::try_from(id).unwrap())
+    }
+
+    fn active_id<T>(&self, port: PortHandle<T>, kind: PortKind) -> Option<u16> {
+        let port = &self.ports[port.index];
+        assert_eq!(port.id.kind, kind, "Incorrect port kind");
+        port.active
+    }
+
+    /// Makes the node use the configured ID of each port
+    ///
+    /// For each port whose configured ID is different from the ID in use, this function stops
+    /// using the old ID (if any) and starts using the new ID (if any).
+    ///
+    /// If the node returns an error when starting to use a port, this function continues to
+    /// update the other ports and then returns the first error. The port that failed remains
+    /// unused until its ID changes again.
+    ///
+    /// On success, this function returns true if any port changed.
+    pub fn apply<N>(&mut self, node: &mut N) -> Result<bool, ApplyError<N>>
+    where
+        N: Node,
+    {
+        let mut changed = false;
+        let mut first_error = None;
+        for port in self.ports.iter_mut() {
+            let configured = Some(port.id.value).filter(|&id| id != UNSET_PORT_ID);
+            if configured == port.active {
+                continue;
+            }
+            changed = true;
+            if let Some(old_id) = port.active.take() {
+                log::debug!("Stopping port {} with ID {}", port.id.name, old_id);
+                port.stop(node, old_id);
+            }
+            if let Some(new_id) = configured {
+                log::debug!("Starting port {} with ID {}", port.id.name, new_id);
+                match port.start(node, new_id) {
+                    Ok(()) => port.active = Some(new_id),
+                    Err(e) => {
+                        log::warn!("Can't start port {}", port.id.name);
+                        if first_error.is_none() {
+                            first_error = Some(e);
+                        }
+                    }
+                }
+            }
+        }
+        match first_error {
+            Some(e) => Err(e),
+            None => Ok(changed),
+        }
+    }
+}
+
+impl Default for PortRegisters {
+    fn default() -> Self {
+        PortRegisters::new()
+    }
+}
+
+impl Port {
+    fn stop<N>(&self, node: &mut N, id: u16)
+    where
+        N: Node,
+    {
+        match self.id.kind {
+            PortKind::Publisher => node.stop_publishing(PublishToken::<AnyType>(
+                SubjectId::try_from(id).unwrap(),
+                PhantomData,
+            )),
+            PortKind::Subscriber => node.unsubscribe_message(SubjectId::try_from(id).unwrap()),
+            PortKind::Client => node.stop_sending_requests(ServiceToken::<AnyType>(
+                ServiceId::try_from(id).unwrap(),
+                PhantomData,
+            )),
+            PortKind::Server => node.unsubscribe_request(ServiceId::try_from(id).unwrap()),
+        }
+    }
+
+    fn start<N>(&self, node: &mut N, id: u16) -> Result<(), ApplyError<N>>
+    where
+        N: Node,
+    {
+        let timeout = milliseconds(self.settings.timeout_ms);
+        match self.id.kind {
+            PortKind::Publisher => node
+                .start_publishing::<AnyType>(
+                    SubjectId::try_from(id).unwrap(),
+                    timeout,
+                    self.settings.priority.into(),
+                )
+                .map(drop)
+                .map_err(NodeError::Transmitter),
+            PortKind::Subscriber => node
+                .subscribe_message(
+                    SubjectId::try_from(id).unwrap(),
+                    self.settings.payload_size_max,
+                    timeout,
+                )
+                .map_err(|e| NodeError::Receiver(StartSendError::Transport(e))),
+            PortKind::Client => node
+                .start_sending_requests::<AnyType>(
+                    ServiceId::try_from(id).unwrap(),
+                    timeout,
+                    self.settings.payload_size_max,
+                    self.settings.priority.into(),
+                )
+                .map(drop)
+                .map_err(NodeError::Receiver),
+            PortKind::Server => node
+                .subscribe_request(
+                    ServiceId::try_from(id).unwrap(),
+                    self.settings.payload_size_max,
+                    timeout,
+                )
+                .map_err(|e| NodeError::Receiver(StartSendError::Transport(e))),
+        }
+    }
+}
+
+impl RegisterBlock for PortRegisters {
+    fn register_by_index(&self, index: usize) -> Option<&dyn Register> {
+        let port = self.ports.get(index / 2)?;
+        match index % 2 {
+            0 => Some(&port.id),
+            _ => Some(&port.data_type),
+        }
+    }
+
+    fn register_by_index_mut(&mut self, index: usize) -> Option<&mut dyn Register> {
+        let port = self.ports.get_mut(index / 2)?;
+        match index % 2 {
+            0 => Some(&mut port.id),
+            _ => Some(&mut port.data_type),
+        }
+    }
+
+    fn register_by_name_mut(&mut self, name: &str) -> Option<&mut dyn Register> {
+        for port in self.ports.iter_mut() {
+            if port.id.name == name {
+                return Some(&mut port.id);
+            }
+            if port.data_type.name == name {
+                return Some(&mut port.data_type);
+            }
+        }
+        None
+    }
+}
+
+/// Errors that can occur when applying port ID changes to a node
+pub type ApplyError<N> = NodeError<
+    StartSendError<<<N as Node>::Transmitter as Transmitter<<N as Node>::Instant>>::Error>,
+    StartSendError<<<N as Node>::Receiver as Receiver<<N as Node>::Instant>>::Error>,
+>;
+
+/// A `uavcan.*.<name>.id` register
+struct PortIdRegister {
+    name: String,
+    kind: PortKind,
+    value: u16,
+}
+
+impl Register for PortIdRegister {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn access(&self) -> Access {
+        Access {
+            mutable: true,
+            persistent: true,
+        }
+    }
+
+    fn read(&self) -> Value {
+        Value::Natural16(Natural16 {
+            value: heapless::Vec::from_slice(&[self.value]).unwrap(),
+        })
+    }
+
+    fn write(&mut self, value: &Value) -> Result<(), WriteError> {
+        match value {
+            Value::Natural16(Natural16 { value }) if value.len() == 1 => {
+                let id = value[0];
+                if id == UNSET_PORT_ID || self.kind.is_valid_id(id) {
+                    self.value = id;
+                    Ok(())
+                } else {
+                    Err(WriteError::Invalid)
+                }
+            }
+            _ => Err(WriteError::Type),
+        }
+    }
+}
+
+/// A `uavcan.*.<name>.type` register
+struct PortTypeRegister {
+    name: String,
+    type_name: &'static str,
+}
+
+impl Register for PortTypeRegister {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn access(&self) -> Access {
+        Access {
+            mutable: false,
+            persistent: true,
+        }
+    }
+
+    fn read(&self) -> Value {
+        Value::String(string_1_0::String {
+            value: heapless::Vec::from_slice(self.type_name.as_bytes())
+                .expect("Type name too long"),
+        })
+    }
+
+    fn write(&mut self, _value: &Value) -> Result<(), WriteError> {
+        Err(WriteError::ReadOnly)
+    }
+}
+
+/// A placeholder type for tokens that are used only to start and stop ports
+struct AnyType;
+impl Message for AnyType {}
+impl Request for AnyType {}
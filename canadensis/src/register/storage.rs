@@ -0,0 +1,195 @@
+//!
+//! Saving and loading persistent registers
+//!
+//! A [`RegisterStorage`] is a key-value store that keeps register values in non-volatile memory.
+//! A [`RegisterPersistence`] uses a storage to load all persistent registers of a
+//! [`RegisterBlock`] when the node starts, and to save them again later.
+//!
+//! Registers should be saved when the node receives an `ExecuteCommand` request with the
+//! store persistent states command (see
+//! [`Commands::store_persistent_states`](crate::command::Commands::store_persistent_states)),
+//! and can also be saved a short time after another node writes them (see
+//! [`RegisterHandler::take_persistent_modified`](crate::register::RegisterHandler::take_persistent_modified)
+//! and [`RegisterPersistence::schedule_save`]).
+//!
+//! Two storage implementations are available:
+//! * [`FlashStorage`](flash::FlashStorage) keeps values in a log in NOR flash memory
+//! * [`FileStorage`](file::FileStorage) keeps values in files in a directory
+//!   (requires the `std` feature)
+//!
+
+#[cfg(feature = "std")]
+pub mod file;
+pub mod flash;
+
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt::Debug;
+
+use crate::register::{serialize_value, Access, RegisterBlock};
+use canadensis_core::time::Instant;
+use canadensis_data_types::uavcan::register::value_1_0::Value;
+use canadensis_encoding::Deserialize;
+
+/// A key-value store in non-volatile memory
+///
+/// Keys are register names. Values are serialized `uavcan.register.Value` objects, which are
+/// never more than 259 bytes long.
+pub trait RegisterStorage {
+    /// The error type
+    type Error: Debug;
+
+    /// Reads the value associated with a key
+    ///
+    /// This function returns `Ok(None)` if the storage does not contain a value for the key.
+    fn load(&mut self, key: &str) -> Result<Option<Vec<u8>>, Self::Error>;
+
+    /// Associates a value with a key, replacing any existing value
+    fn store(&mut self, key: &str, value: &[u8]) -> Result<(), Self::Error>;
+
+    /// Removes the value associated with a key
+    ///
+    /// This function has no effect if the storage does not contain a value for the key.
+    fn erase(&mut self, key: &str) -> Result<(), Self::Error>;
+}
+
+/// Loads persistent registers from a storage and saves them
+///
+/// Only registers that are both persistent and mutable are loaded and saved. The values of
+/// registers that are not mutable are fixed by the software, so they do not need to be stored.
+///
+/// Basic steps:
+/// 1. Create a `RegisterPersistence` with a storage
+/// 2. Before creating the register handler, call `RegisterPersistence::load` with the register
+///    block
+/// 3. When the node receives a store persistent states command, call `RegisterPersistence::save`
+/// 4. Optionally, when another node writes a persistent register, call
+///    `RegisterPersistence::schedule_save` and then call `RegisterPersistence::poll` regularly
+pub struct RegisterPersistence<S, I: Instant> {
+    storage: S,
+    /// The time to wait after the last change before saving
+    save_delay: I::Duration,
+    /// The time when the registers will be saved, if a save has been scheduled
+    save_deadline: Option<I>,
+}
+
+impl<S, I> RegisterPersistence<S, I>
+where
+    S: RegisterStorage,
+    I: Instant,
+{
+    /// Creates a persistence helper
+    ///
+    /// `save_delay` is the time that [`poll`](#method.poll) waits after the most recent call to
+    /// [`schedule_save`](#method.schedule_save) before saving the registers.
+    pub fn new(storage: S, save_delay: I::Duration) -> Self {
+        RegisterPersistence {
+            storage,
+            save_delay,
+            save_deadline: None,
+        }
+    }
+
+    /// Returns a reference to the storage
+    pub fn storage(&self) -> &S {
+        &self.storage
+    }
+    /// Returns a mutable reference to the storage
+    pub fn storage_mut(&mut self) -> &mut S {
+        &mut self.storage
+    }
+
+    /// Loads the values of all persistent and mutable registers in a block from the storage
+    ///
+    /// Registers that do not have values in the storage keep their current values. If a stored
+    /// value is invalid or has the wrong type for its register, this function logs a warning and
+    /// leaves the register unchanged.
+    ///
+    /// On success, this function returns the number of registers that were loaded.
+    pub fn load<B>(&mut self, block: &mut B) -> Result<usize, S::Error>
+    where
+        B: RegisterBlock,
+    {
+        let mut loaded = 0;
+        let mut index = 0;
+        while let Some(register) = block.register_by_index_mut(index) {
+            index += 1;
+            if !is_stored(&register.access()) {
+                continue;
+            }
+            let bytes = match self.storage.load(register.name())? {
+                Some(bytes) => bytes,
+                None => continue,
+            };
+            match Value::deserialize_from_bytes(&bytes) {
+                Ok(value) => match register.write(&value) {
+                    Ok(()) => loaded += 1,
+                    Err(e) => log::warn!(
+                        "Can't load stored value of register {}: {:?}",
+                        register.name(),
+                        e
+                    ),
+                },
+                Err(e) => log::warn!(
+                    "Invalid stored value for register {}: {:?}",
+                    register.name(),
+                    e
+                ),
+            }
+        }
+        Ok(loaded)
+    }
+
+    /// Saves the values of all persistent and mutable registers in a block to the storage
+    ///
+    /// This cancels any scheduled save.
+    pub fn save<B>(&mut self, block: &B) -> Result<(), S::Error>
+    where
+        B: RegisterBlock,
+    {
+        let mut index = 0;
+        while let Some(register) = block.register_by_index(index) {
+            index += 1;
+            if is_stored(&register.access()) {
+                let bytes = serialize_value(&register.read());
+                self.storage.store(register.name(), &bytes)?;
+            }
+        }
+        self.save_deadline = None;
+        Ok(())
+    }
+
+    /// Schedules the registers to be saved after the save delay
+    ///
+    /// If a save is already scheduled, it is delayed so that the registers are saved once after
+    /// a series of changes.
+    pub fn schedule_save(&mut self, now: I) {
+        self.save_deadline = Some(self.save_delay + now);
+    }
+
+    /// Returns true if a save has been scheduled and has not happened yet
+    pub fn save_scheduled(&self) -> bool {
+        self.save_deadline.is_some()
+    }
+
+    /// Saves the registers if a scheduled save is due
+    ///
+    /// This function returns true if the registers were saved.
+    pub fn poll<B>(&mut self, block: &B, now: I) -> Result<bool, S::Error>
+    where
+        B: RegisterBlock,
+    {
+        match &self.save_deadline {
+            Some(deadline) if deadline.overflow_safe_compare(&now) != Ordering::Greater => {
+                self.save(block)?;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+}
+
+/// Returns true if a register with the provided access flags should be loaded and saved
+fn is_stored(access: &Access) -> bool {
+    access.persistent && access.mutable
+}
@@ -0,0 +1,96 @@
+//!
+//! A register storage that keeps values in files
+//!
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write as _;
+use std::fs;
+use std::io::{self, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+use crate::register::storage::RegisterStorage;
+
+/// A register storage that keeps each value in a separate file in a directory
+///
+/// Each file is named after its register. Characters other than ASCII letters, digits, `_`, `-`,
+/// and `.` (and a `.` at the beginning of the name) are replaced with `%` and two hexadecimal
+/// digits.
+///
+/// A new value is first written to a temporary file whose name begins with `~`, which then
+/// replaces the old file. This prevents a value from being partly written.
+#[derive(Debug)]
+pub struct FileStorage {
+    directory: PathBuf,
+}
+
+impl FileStorage {
+    /// Creates a storage that uses the provided directory
+    ///
+    /// If the directory does not exist, this function creates it.
+    pub fn new<P>(directory: P) -> io::Result<Self>
+    where
+        P: Into<PathBuf>,
+    {
+        let directory = directory.into();
+        fs::create_dir_all(&directory)?;
+        Ok(FileStorage { directory })
+    }
+
+    /// Returns the directory where the values are stored
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.directory.join(file_name(key))
+    }
+}
+
+impl RegisterStorage for FileStorage {
+    type Error = io::Error;
+
+    fn load(&mut self, key: &str) -> Result<Option<Vec<u8>>, Self::Error> {
+        match fs::read(self.path(key)) {
+            Ok(value) => Ok(Some(value)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn store(&mut self, key: &str, value: &[u8]) -> Result<(), Self::Error> {
+        let name = file_name(key);
+        let path = self.directory.join(&name);
+        let temporary_path = self.directory.join(format!("~{}", name));
+        {
+            let mut file = fs::File::create(&temporary_path)?;
+            file.write_all(value)?;
+            file.sync_all()?;
+        }
+        fs::rename(&temporary_path, &path)
+    }
+
+    fn erase(&mut self, key: &str) -> Result<(), Self::Error> {
+        match fs::remove_file(self.path(key)) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// Converts a register name into a file name
+fn file_name(key: &str) -> String {
+    let mut name = String::with_capacity(key.len());
+    for (i, byte) in key.bytes().enumerate() {
+        if byte.is_ascii_alphanumeric() || byte == b'_' || byte == b'-' || (byte == b'.' && i != 0)
+        {
+            name.push(char::from(byte));
+        } else {
+            // Writing to a String can't fail
+            let _ = write!(name, "%{:02X}", byte);
+        }
+    }
+    name
+}
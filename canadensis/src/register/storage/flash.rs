@@ -0,0 +1,385 @@
+//!
+//! A register storage that keeps values in a log in NOR flash memory
+//!
+
+use alloc::vec::Vec;
+
+use crate::register::storage::RegisterStorage;
+use embedded_storage::nor_flash::NorFlash;
+
+/// The value at the beginning of a bank that has been initialized
+const BANK_MAGIC: u32 = 0x5245_4743;
+/// The length of a bank header (magic and sequence number), before alignment
+const BANK_HEADER_LENGTH: usize = 8;
+/// The length of a record header (key length, value length, and CRC), before alignment
+const RECORD_HEADER_LENGTH: usize = 8;
+/// A key length that indicates that no record has been written here
+const UNWRITTEN: u16 = 0xffff;
+/// A value length that indicates that the key has been erased
+const ERASED: u16 = 0xfffe;
+/// The maximum length of a key, in bytes
+const MAX_KEY_LENGTH: usize = 256;
+
+/// A register storage that appends values to a log in NOR flash memory
+///
+/// The flash memory is divided into two banks of equal size, each a whole number of erase
+/// sectors. New values are appended to the active bank, so storing a value usually does not
+/// require erasing anything. When the active bank is full, the latest values are copied into
+/// the other bank, which then becomes the active bank. Each record has a CRC, so a record that
+/// was only partly written (for example, because power was lost) is ignored.
+///
+/// Storing a value that is equal to the current value does not write anything.
+///
+/// The flash memory must be able to read at any offset that is a multiple of its write size.
+pub struct FlashStorage<F> {
+    flash: F,
+    /// The size of each bank in bytes
+    bank_size: u32,
+    /// The index of the active bank (0 or 1)
+    active: u32,
+    /// The sequence number of the active bank
+    sequence: u32,
+    /// The offset from the beginning of the active bank where the next record will be written
+    write_offset: u32,
+}
+
+/// A record that was read from flash
+struct Record {
+    key: Vec<u8>,
+    /// The value, or None if the key was erased
+    value: Option<Vec<u8>>,
+}
+
+impl<F> FlashStorage<F>
+where
+    F: NorFlash,
+{
+    /// Creates a storage using all of the provided flash memory
+    ///
+    /// If the flash memory does not contain a valid bank, this function erases the first bank.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the flash memory is smaller than two erase sectors, or if its
+    /// write size is not a multiple of its read size.
+    pub fn new(flash: F) -> Result<Self, FlashStorageError<F::Error>> {
+        assert_eq!(
+            F::WRITE_SIZE % F::READ_SIZE,
+            0,
+            "Flash write size must be a multiple of read size"
+        );
+        let bank_size = (flash.capacity() / 2) / F::ERASE_SIZE * F::ERASE_SIZE;
+        assert_ne!(bank_size, 0, "Flash too small for two banks");
+        let mut storage = FlashStorage {
+            flash,
+            bank_size: bank_size as u32,
+            active: 0,
+            sequence: 0,
+            write_offset: 0,
+        };
+        let active = match (storage.read_bank_header(0)?, storage.read_bank_header(1)?) {
+            (Some(sequence0), Some(sequence1)) => {
+                // The bank with the later sequence number is the active one
+                if sequence1.wrapping_sub(sequence0) as i32 > 0 {
+                    Some((1, sequence1))
+                } else {
+                    Some((0, sequence0))
+                }
+            }
+            (Some(sequence0), None) => Some((0, sequence0)),
+            (None, Some(sequence1)) => Some((1, sequence1)),
+            (None, None) => None,
+        };
+        match active {
+            Some((bank, sequence)) => {
+                storage.active = bank;
+                storage.sequence = sequence;
+                storage.write_offset = storage.for_each_record(bank, |_| {})?;
+            }
+            None => {
+                log::info!("No register storage found in flash, initializing");
+                storage.erase_bank(0)?;
+                storage.write_bank_header(0, 0)?;
+                storage.write_offset = storage.align(BANK_HEADER_LENGTH) as u32;
+            }
+        }
+        Ok(storage)
+    }
+
+    /// Returns a reference to the flash memory
+    pub fn flash(&self) -> &F {
+        &self.flash
+    }
+
+    /// Returns the flash memory
+    pub fn into_flash(self) -> F {
+        self.flash
+    }
+
+    /// Rounds a length up to a multiple of the write size
+    fn align(&self, length: usize) -> usize {
+        length.div_ceil(F::WRITE_SIZE) * F::WRITE_SIZE
+    }
+
+    fn bank_start(&self, bank: u32) -> u32 {
+        bank * self.bank_size
+    }
+
+    fn erase_bank(&mut self, bank: u32) -> Result<(), FlashStorageError<F::Error>> {
+        let start = self.bank_start(bank);
+        self.flash
+            .erase(start, start + self.bank_size)
+            .map_err(FlashStorageError::Flash)
+    }
+
+    /// Reads the header of a bank and returns its sequence number, if the bank is valid
+    fn read_bank_header(&mut self, bank: u32) -> Result<Option<u32>, FlashStorageError<F::Error>> {
+        let mut header = alloc::vec![0u8; self.align(BANK_HEADER_LENGTH)];
+        self.flash
+            .read(self.bank_start(bank), &mut header)
+            .map_err(FlashStorageError::Flash)?;
+        if read_u32(&header[0..4]) == BANK_MAGIC {
+            Ok(Some(read_u32(&header[4..8])))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn write_bank_header(
+        &mut self,
+        bank: u32,
+        sequence: u32,
+    ) -> Result<(), FlashStorageError<F::Error>> {
+        let mut header = alloc::vec![0xffu8; self.align(BANK_HEADER_LENGTH)];
+        header[0..4].copy_from_slice(&BANK_MAGIC.to_le_bytes());
+        header[4..8].copy_from_slice(&sequence.to_le_bytes());
+        self.flash
+            .write(self.bank_start(bank), &header)
+            .map_err(FlashStorageError::Flash)
+    }
+
+    /// Calls a function with each valid record in a bank, in the order they were written
+    ///
+    /// This function returns the offset after the last record.
+    fn for_each_record<G>(
+        &mut self,
+        bank: u32,
+        mut operation: G,
+    ) -> Result<u32, FlashStorageError<F::Error>>
+    where
+        G: FnMut(Record),
+    {
+        let bank_start = self.bank_start(bank);
+        let header_length = self.align(RECORD_HEADER_LENGTH);
+        let mut offset = self.align(BANK_HEADER_LENGTH) as u32;
+        let mut header = alloc::vec![0u8; header_length];
+        while offset as usize + header_length <= self.bank_size as usize {
+            self.flash
+                .read(bank_start + offset, &mut header)
+                .map_err(FlashStorageError::Flash)?;
+            let key_length = read_u16(&header[0..2]);
+            let value_length = read_u16(&header[2..4]);
+            if key_length == UNWRITTEN {
+                // End of the log
+                break;
+            }
+            let data_length = usize::from(key_length) + stored_value_length(value_length);
+            let record_length = self.align(RECORD_HEADER_LENGTH + data_length);
+            if offset as usize + record_length > self.bank_size as usize {
+                // The header is damaged. Nothing more can be written to this bank.
+                log::warn!("Invalid register storage record at offset {}", offset);
+                return Ok(self.bank_size);
+            }
+            let mut record = alloc::vec![0u8; record_length];
+            self.flash
+                .read(bank_start + offset, &mut record)
+                .map_err(FlashStorageError::Flash)?;
+            offset += record_length as u32;
+
+            let data = &record[RECORD_HEADER_LENGTH..][..data_length];
+            if read_u32(&record[4..8]) != record_crc(&record[0..4], data) {
+                log::warn!("Incorrect CRC in register storage record, ignoring");
+                continue;
+            }
+            let (key, value) = data.split_at(usize::from(key_length));
+            operation(Record {
+                key: key.to_vec(),
+                value: if value_length == ERASED {
+                    None
+                } else {
+                    Some(value.to_vec())
+                },
+            });
+        }
+        Ok(offset)
+    }
+
+    /// Writes a record at the write offset of a bank, if it fits
+    ///
+    /// This function returns false if the record does not fit.
+    fn append(
+        &mut self,
+        bank: u32,
+        key: &[u8],
+        value: Option<&[u8]>,
+    ) -> Result<bool, FlashStorageError<F::Error>> {
+        let value_bytes = value.unwrap_or(&[]);
+        let data_length = key.len() + value_bytes.len();
+        let record_length = self.align(RECORD_HEADER_LENGTH + data_length);
+        if self.write_offset as usize + record_length > self.bank_size as usize {
+            return Ok(false);
+        }
+        let mut record = alloc::vec![0xffu8; record_length];
+        record[0..2].copy_from_slice(&(key.len() as u16).to_le_bytes());
+        let value_length = match value {
+            Some(value) => value.len() as u16,
+            None => ERASED,
+        };
+        record[2..4].copy_from_slice(&value_length.to_le_bytes());
+        record[RECORD_HEADER_LENGTH..][..key.len()].copy_from_slice(key);
+        record[RECORD_HEADER_LENGTH + key.len()..][..value_bytes.len()]
+            .copy_from_slice(value_bytes);
+        let crc = record_crc(
+            &record[0..4],
+            &record[RECORD_HEADER_LENGTH..][..data_length],
+        );
+        record[4..8].copy_from_slice(&crc.to_le_bytes());
+
+        self.flash
+            .write(self.bank_start(bank) + self.write_offset, &record)
+            .map_err(FlashStorageError::Flash)?;
+        self.write_offset += record_length as u32;
+        Ok(true)
+    }
+
+    /// Appends a record to the active bank, copying the latest values into the other bank first
+    /// if necessary
+    fn append_or_compact(
+        &mut self,
+        key: &[u8],
+        value: Option<&[u8]>,
+    ) -> Result<(), FlashStorageError<F::Error>> {
+        if self.append(self.active, key, value)? {
+            return Ok(());
+        }
+        self.compact()?;
+        if self.append(self.active, key, value)? {
+            Ok(())
+        } else {
+            Err(FlashStorageError::Full)
+        }
+    }
+
+    /// Copies the latest value of each key into the other bank and makes it the active bank
+    fn compact(&mut self) -> Result<(), FlashStorageError<F::Error>> {
+        log::debug!("Compacting register storage");
+        let mut latest: Vec<(Vec<u8>, Vec<u8>)> = Vec::new();
+        self.for_each_record(self.active, |record| {
+            let position = latest.iter().position(|(key, _)| *key == record.key);
+            match (position, record.value) {
+                (Some(position), Some(value)) => latest[position].1 = value,
+                (Some(position), None) => {
+                    latest.remove(position);
+                }
+                (None, Some(value)) => latest.push((record.key, value)),
+                (None, None) => {}
+            }
+        })?;
+
+        let new_bank = 1 - self.active;
+        self.erase_bank(new_bank)?;
+        self.write_offset = self.align(BANK_HEADER_LENGTH) as u32;
+        for (key, value) in latest.iter() {
+            if !self.append(new_bank, key, Some(value))? {
+                return Err(FlashStorageError::Full);
+            }
+        }
+        // Write the header last, so that the new bank becomes valid only after all the values
+        // have been copied
+        let sequence = self.sequence.wrapping_add(1);
+        self.write_bank_header(new_bank, sequence)?;
+        self.active = new_bank;
+        self.sequence = sequence;
+        Ok(())
+    }
+}
+
+impl<F> RegisterStorage for FlashStorage<F>
+where
+    F: NorFlash,
+{
+    type Error = FlashStorageError<F::Error>;
+
+    fn load(&mut self, key: &str) -> Result<Option<Vec<u8>>, Self::Error> {
+        let mut value = None;
+        self.for_each_record(self.active, |record| {
+            if record.key == key.as_bytes() {
+                value = record.value;
+            }
+        })?;
+        Ok(value)
+    }
+
+    fn store(&mut self, key: &str, value: &[u8]) -> Result<(), Self::Error> {
+        if key.len() > MAX_KEY_LENGTH || value.len() >= usize::from(ERASED) {
+            return Err(FlashStorageError::TooLong);
+        }
+        if self.load(key)?.as_deref() == Some(value) {
+            // Already stored
+            return Ok(());
+        }
+        self.append_or_compact(key.as_bytes(), Some(value))
+    }
+
+    fn erase(&mut self, key: &str) -> Result<(), Self::Error> {
+        if self.load(key)?.is_none() {
+            return Ok(());
+        }
+        self.append_or_compact(key.as_bytes(), None)
+    }
+}
+
+/// Errors that can occur when using a [`FlashStorage`]
+#[derive(Debug)]
+pub enum FlashStorageError<E> {
+    /// The flash memory reported an error
+    Flash(E),
+    /// Not enough space was available, even after removing old values
+    Full,
+    /// The key was longer than 256 bytes or the value was too long
+    TooLong,
+}
+
+/// Returns the number of bytes that a value occupies in a record, based on the value length
+/// field
+fn stored_value_length(value_length: u16) -> usize {
+    if value_length == ERASED {
+        0
+    } else {
+        usize::from(value_length)
+    }
+}
+
+fn read_u16(bytes: &[u8]) -> u16 {
+    u16::from_le_bytes([bytes[0], bytes[1]])
+}
+
+fn read_u32(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+/// Calculates the CRC-32C of the lengths and data of a record
+fn record_crc(lengths: &[u8], data: &[u8]) -> u32 {
+    let mut crc = 0xffff_ffffu32;
+    for &byte in lengths.iter().chain(data.iter()) {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0x82f6_3b78
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
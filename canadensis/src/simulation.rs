@@ -0,0 +1,201 @@
+//!
+//! Running several nodes together in one process
+//!
+//! This module helps tests run several nodes with a shared
+//! [`SimulatedClock`](crate::core::time::SimulatedClock), usually connected by a simulated
+//! transport such as `canadensis_can::simulated::SimulatedBus`.
+//!
+//! A [`LockStep`] advances the clock in fixed steps. In each step, it lets each
+//! [`Participant`] receive transfers and send queued frames. Once per simulated second, it also
+//! runs the per-second tasks of each participant.
+//!
+//! A [`SimulatedNode`] combines a node with the transfer handler that it uses, and implements
+//! `Participant`.
+//!
+//! # Step length
+//!
+//! In each step, a `SimulatedNode` calls [`Node::receive`] once. This handles at most one
+//! incoming transfer, so the step should not be longer than the time the transport needs to
+//! deliver one frame. Otherwise, nodes may fall behind when the bus is busy.
+//!
+
+use crate::core::time::{MicrosecondDuration64, Microseconds64, SimulatedClock};
+use crate::{nb, Node, TransferHandler};
+
+/// Something that runs as part of a lock-step simulation
+pub trait Participant {
+    /// Handles incoming transfers and sends outgoing frames
+    ///
+    /// The lock-step simulation calls this function once per step.
+    fn step(&mut self);
+
+    /// Runs tasks that should happen once per second
+    ///
+    /// The default implementation does nothing.
+    fn run_per_second_tasks(&mut self) {}
+}
+
+/// A function that runs with a node and its handler
+pub type NodeTask<N, H> = fn(&mut N, &mut H);
+
+/// A node and its transfer handler
+///
+/// Optional tasks can run after the node receives in each step, and once per second. For
+/// example, a [`BasicNode`](crate::node::BasicNode) needs its `run_per_second_tasks` function
+/// called once per second, and a client may need to be polled in each step:
+///
+/// ```ignore
+/// let node = SimulatedNode::new(basic_node, client)
+///     .with_step_task(|node, client| client.poll(node))
+///     .with_per_second_task(|node, _client| node.run_per_second_tasks().unwrap());
+/// ```
+pub struct SimulatedNode<N, H> {
+    node: N,
+    handler: H,
+    step_task: Option<NodeTask<N, H>>,
+    per_second_task: Option<NodeTask<N, H>>,
+}
+
+impl<N, H> SimulatedNode<N, H>
+where
+    N: Node,
+    H: TransferHandler<N::Instant, N::Transport>,
+{
+    /// Creates a simulated node with no tasks
+    pub fn new(node: N, handler: H) -> Self {
+        SimulatedNode {
+            node,
+            handler,
+            step_task: None,
+            per_second_task: None,
+        }
+    }
+
+    /// Sets a task that runs in each step, after the node receives and before it flushes
+    pub fn with_step_task(self, task: NodeTask<N, H>) -> Self {
+        SimulatedNode {
+            step_task: Some(task),
+            ..self
+        }
+    }
+
+    /// Sets a task that runs once per second
+    pub fn with_per_second_task(self, task: NodeTask<N, H>) -> Self {
+        SimulatedNode {
+            per_second_task: Some(task),
+            ..self
+        }
+    }
+
+    /// Returns a reference to the node
+    pub fn node(&self) -> &N {
+        &self.node
+    }
+
+    /// Returns a mutable reference to the node
+    pub fn node_mut(&mut self) -> &mut N {
+        &mut self.node
+    }
+
+    /// Returns a reference to the handler
+    pub fn handler(&self) -> &H {
+        &self.handler
+    }
+
+    /// Returns a mutable reference to the handler
+    pub fn handler_mut(&mut self) -> &mut H {
+        &mut self.handler
+    }
+
+    /// Returns the node and handler
+    pub fn into_parts(self) -> (N, H) {
+        (self.node, self.handler)
+    }
+}
+
+impl<N, H> Participant for SimulatedNode<N, H>
+where
+    N: Node,
+    H: TransferHandler<N::Instant, N::Transport>,
+{
+    fn step(&mut self) {
+        if let Err(e) = self.node.receive(&mut self.handler) {
+            log::warn!("Simulated node failed to receive: {:?}", e);
+        }
+        if let Some(task) = self.step_task {
+            task(&mut self.node, &mut self.handler);
+        }
+        match self.node.flush() {
+            Ok(()) | Err(nb::Error::WouldBlock) => {}
+            Err(nb::Error::Other(e)) => log::warn!("Simulated node failed to flush: {:?}", e),
+        }
+    }
+
+    fn run_per_second_tasks(&mut self) {
+        if let Some(task) = self.per_second_task {
+            task(&mut self.node, &mut self.handler);
+        }
+    }
+}
+
+/// Runs participants in fixed time steps
+pub struct LockStep {
+    clock: SimulatedClock,
+    step: MicrosecondDuration64,
+    /// The time when the per-second tasks will run next
+    next_second: Microseconds64,
+}
+
+impl LockStep {
+    /// Creates a simulation that advances the provided clock by `step` in each step
+    ///
+    /// The first per-second tasks run one second after the current time of the clock.
+    pub fn new(clock: SimulatedClock, step: MicrosecondDuration64) -> Self {
+        let next_second = MicrosecondDuration64::new(1_000_000) + clock.time();
+        LockStep {
+            clock,
+            step,
+            next_second,
+        }
+    }
+
+    /// Returns a reference to the clock
+    pub fn clock(&self) -> &SimulatedClock {
+        &self.clock
+    }
+
+    /// Returns the current time of the clock
+    pub fn now(&self) -> Microseconds64 {
+        self.clock.time()
+    }
+
+    /// Runs one step
+    ///
+    /// This function calls [`step`](Participant::step) on each participant in order, and then
+    /// advances the clock. If the clock reaches the next whole second, it then calls
+    /// [`run_per_second_tasks`](Participant::run_per_second_tasks) on each participant.
+    pub fn step(&mut self, participants: &mut [&mut dyn Participant]) {
+        for participant in participants.iter_mut() {
+            participant.step();
+        }
+        self.clock.advance(self.step);
+        while self.clock.time() >= self.next_second {
+            for participant in participants.iter_mut() {
+                participant.run_per_second_tasks();
+            }
+            self.next_second = MicrosecondDuration64::new(1_000_000) + self.next_second;
+        }
+    }
+
+    /// Runs steps until at least `duration` has passed
+    pub fn run_for(
+        &mut self,
+        duration: MicrosecondDuration64,
+        participants: &mut [&mut dyn Participant],
+    ) {
+        let end = duration + self.clock.time();
+        while self.clock.time() < end {
+            self.step(participants);
+        }
+    }
+}
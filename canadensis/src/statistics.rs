@@ -0,0 +1,151 @@
+//!
+//! Handling of `uavcan.node.GetTransportStatistics` requests
+//!
+
+use alloc::vec::Vec;
+
+use crate::{Node, ResponseToken, TransferHandler};
+use canadensis_core::time::{milliseconds, Instant};
+use canadensis_core::transfer::ServiceTransfer;
+use canadensis_core::transport::{
+    Receiver, Statistics, Transmitter, Transport, TransportStatistics,
+};
+use canadensis_data_types::uavcan::node::get_transport_statistics_0_1::{
+    self, GetTransportStatisticsRequest, GetTransportStatisticsResponse,
+};
+use canadensis_data_types::uavcan::node::io_statistics_0_1::IOStatistics;
+use canadensis_encoding::DataType;
+
+/// The maximum number of network interfaces that a response can describe
+const MAX_INTERFACES: usize = GetTransportStatisticsResponse::MAX_NETWORK_INTERFACES as usize;
+
+/// Answers `uavcan.node.GetTransportStatistics` requests with statistics from the transmitter
+/// and receiver of a node
+///
+/// When it receives a request, the handler gets the statistics from the
+/// [`Transmitter`](canadensis_core::transport::Transmitter) and
+/// [`Receiver`](canadensis_core::transport::Receiver) of the node. In the response, the transfer
+/// statistics count transfers sent and received, and the network interface statistics count
+/// frames sent and received. In both, the error count is the sum of the transmit and receive
+/// error counts.
+///
+/// By default, the response describes one network interface. For a transport with redundant
+/// network interfaces, call [`update_interfaces`](#method.update_interfaces) before receiving
+/// transfers to report statistics from the components that handle each interface (for example,
+/// a `canadensis_can::redundant::RedundantDriver` and a
+/// `canadensis_can::redundant::Deduplicator`).
+///
+/// Basic steps:
+/// 1. Create a handler using `TransportStatisticsHandler::new`
+/// 2. Call `TransportStatisticsHandler::subscribe_requests` to subscribe to statistics requests
+/// 3. When receiving transfers on the node, pass the statistics handler as a transfer handler
+#[derive(Debug, Default)]
+pub struct TransportStatisticsHandler {
+    /// Transmit and receive statistics for each network interface, or empty to report one
+    /// interface with the statistics of the node's transmitter and receiver
+    interfaces: heapless::Vec<(Statistics, Statistics), MAX_INTERFACES>,
+}
+
+impl TransportStatisticsHandler {
+    /// Creates a handler with all statistics set to zero
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Subscribes to statistics requests
+    ///
+    /// This function returns an error if the provided node does not have enough space to listen
+    /// for requests.
+    pub fn subscribe_requests<N>(
+        node: &mut N,
+    ) -> Result<(), <N::Receiver as Receiver<N::Instant>>::Error>
+    where
+        N: Node,
+    {
+        node.subscribe_request(
+            get_transport_statistics_0_1::SERVICE,
+            GetTransportStatisticsRequest::extent(),
+            milliseconds(1000),
+        )
+    }
+
+    /// Updates the network interface statistics
+    ///
+    /// The number of interfaces reported is the larger of the interface counts of
+    /// `transmitter` and `receiver`, up to three.
+    pub fn update_interfaces<Tx, Rx>(&mut self, transmitter: &Tx, receiver: &Rx)
+    where
+        Tx: TransportStatistics,
+        Rx: TransportStatistics,
+    {
+        let count = transmitter
+            .interface_count()
+            .max(receiver.interface_count())
+            .min(MAX_INTERFACES);
+        self.interfaces.clear();
+        for interface in 0..count {
+            let statistics = (
+                transmitter
+                    .interface_statistics(interface)
+                    .unwrap_or_default(),
+                receiver.interface_statistics(interface).unwrap_or_default(),
+            );
+            // Can't fail because count is limited to the capacity
+            let _ = self.interfaces.push(statistics);
+        }
+    }
+
+    fn make_response(
+        &self,
+        transmit: Statistics,
+        receive: Statistics,
+    ) -> GetTransportStatisticsResponse {
+        let frame_statistics = |(transmit, receive): &(Statistics, Statistics)| IOStatistics {
+            num_emitted: transmit.frames,
+            num_received: receive.frames,
+            num_errored: transmit.errors.wrapping_add(receive.errors),
+        };
+        let network_interface_statistics = if self.interfaces.is_empty() {
+            core::iter::once(frame_statistics(&(transmit, receive))).collect()
+        } else {
+            self.interfaces.iter().map(frame_statistics).collect()
+        };
+        GetTransportStatisticsResponse {
+            transfer_statistics: IOStatistics {
+                num_emitted: transmit.transfers,
+                num_received: receive.transfers,
+                num_errored: transmit.errors.wrapping_add(receive.errors),
+            },
+            network_interface_statistics,
+        }
+    }
+}
+
+impl<I, T> TransferHandler<I, T> for TransportStatisticsHandler
+where
+    I: Instant,
+    T: Transport,
+{
+    fn handle_request<N: Node<Instant = I, Transport = T>>(
+        &mut self,
+        node: &mut N,
+        token: ResponseToken<T>,
+        transfer: &ServiceTransfer<Vec<u8>, I, T>,
+    ) -> bool {
+        if transfer.header.service != get_transport_statistics_0_1::SERVICE {
+            return false;
+        }
+        // The request has no fields, so there is nothing to deserialize
+        let response = self.make_response(
+            node.transmitter().statistics(),
+            node.receiver().statistics(),
+        );
+        if node
+            .send_response(token, milliseconds(1000), &response)
+            .is_err()
+        {
+            log::warn!("Out of memory when sending transport statistics response");
+        }
+        true
+    }
+}
@@ -0,0 +1,487 @@
+//!
+//! Network-wide time synchronization using `uavcan.time.Synchronization`
+//!
+//! A [`TimeSyncMaster`] publishes the synchronized time, and a [`TimeSyncSlave`] estimates the
+//! offset and drift between its local clock and the master. Both implement [`SynchronizedClock`],
+//! which converts local instants into synchronized time.
+//!
+
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+use crate::{Node, PublishToken, ResponseToken, StartSendError, TransferHandler};
+use canadensis_core::time::{milliseconds, Clock, Duration, Instant};
+use canadensis_core::transfer::{Header, MessageTransfer, ServiceTransfer};
+use canadensis_core::transport::{Receiver, TransferId, Transmitter, Transport};
+use canadensis_core::{nb, Priority};
+use canadensis_data_types::uavcan::time::get_synchronization_master_info_0_1::{
+    self, GetSynchronizationMasterInfoResponse,
+};
+use canadensis_data_types::uavcan::time::synchronization_1_0::{self, Synchronization};
+use canadensis_data_types::uavcan::time::tai_info_0_1::TAIInfo;
+use canadensis_data_types::uavcan::time::time_system_0_1::TimeSystem;
+use canadensis_encoding::{DataType, Deserialize};
+
+/// The interval between synchronization messages, in milliseconds
+const PUBLICATION_PERIOD_MS: u32 = Synchronization::MAX_PUBLICATION_PERIOD as u32 * 1000;
+
+/// The time after which a slave stops following a master that has not published anything,
+/// in milliseconds
+const MASTER_TIMEOUT_MS: u32 =
+    PUBLICATION_PERIOD_MS * Synchronization::PUBLISHER_TIMEOUT_PERIOD_MULTIPLIER as u32;
+
+/// The maximum plausible drift between a local clock and the synchronized time
+const MAX_DRIFT: f32 = 0.001;
+
+/// Something that can convert local instants into synchronized network time
+pub trait SynchronizedClock<I: Instant> {
+    /// Returns the synchronized time, in microseconds, that corresponds to a local instant
+    ///
+    /// This function returns None if the synchronized time is not known.
+    fn synchronized_time(&self, local: I) -> Option<u64>;
+}
+
+impl<I, S> SynchronizedClock<I> for &S
+where
+    I: Instant,
+    S: SynchronizedClock<I> + ?Sized,
+{
+    fn synchronized_time(&self, local: I) -> Option<u64> {
+        (**self).synchronized_time(local)
+    }
+}
+
+impl<I, S> SynchronizedClock<I> for Option<S>
+where
+    I: Instant,
+    S: SynchronizedClock<I>,
+{
+    fn synchronized_time(&self, local: I) -> Option<u64> {
+        self.as_ref()
+            .and_then(|clock| clock.synchronized_time(local))
+    }
+}
+
+/// A synchronized clock that never knows the synchronized time
+#[derive(Debug, Default, Copy, Clone)]
+pub struct NotSynchronized;
+
+impl<I: Instant> SynchronizedClock<I> for NotSynchronized {
+    fn synchronized_time(&self, _local: I) -> Option<u64> {
+        None
+    }
+}
+
+/// A relationship between local time and synchronized time
+///
+/// A time reference records the synchronized time at one local instant, and the rate at which
+/// the local clock drifts relative to the synchronized time. It is only accurate for instants
+/// that are close to the reference instant, so it should be updated regularly.
+#[derive(Debug, Copy, Clone)]
+pub struct TimeReference<I> {
+    /// A local instant
+    local: I,
+    /// The synchronized time at the local instant, in microseconds
+    synchronized: u64,
+    /// (synchronized rate / local rate) - 1
+    drift: f32,
+}
+
+impl<I: Instant> TimeReference<I> {
+    /// Creates a time reference with no drift
+    pub fn new(local: I, synchronized: u64) -> Self {
+        TimeReference {
+            local,
+            synchronized,
+            drift: 0.0,
+        }
+    }
+
+    /// Returns the drift of the synchronized time relative to the local clock
+    ///
+    /// A positive value means that the synchronized time advances faster than local time.
+    pub fn drift(&self) -> f32 {
+        self.drift
+    }
+
+    /// Returns the synchronized time that corresponds to a local instant
+    pub fn time_at(&self, local: I) -> u64 {
+        let elapsed = microseconds_between(self.local, local);
+        let elapsed = elapsed + (elapsed as f32 * self.drift) as i64;
+        if elapsed >= 0 {
+            self.synchronized.saturating_add(elapsed as u64)
+        } else {
+            self.synchronized.saturating_sub(elapsed.unsigned_abs())
+        }
+    }
+}
+
+impl<I: Instant> SynchronizedClock<I> for TimeReference<I> {
+    fn synchronized_time(&self, local: I) -> Option<u64> {
+        Some(self.time_at(local))
+    }
+}
+
+/// Publishes the synchronized time and responds to `uavcan.time.GetSynchronizationMasterInfo`
+/// requests
+///
+/// By default, the synchronized time is the number of microseconds since the master was created.
+/// A master with access to a better time source can call [`set_time`](#method.set_time) to
+/// use that time.
+///
+/// Each synchronization message contains the time when the previous message was sent. The master
+/// publishes with loopback enabled, and uses the transmission time that the transport reports.
+/// If the transport does not support loopback, the master uses the time when the message was
+/// queued, so any delay between queueing and transmission reduces the accuracy of the
+/// synchronization.
+///
+/// Basic steps:
+/// 1. Create a master using `TimeSyncMaster::new`
+/// 2. Optionally, call `TimeSyncMaster::subscribe_requests` to respond to master information
+///    requests
+/// 3. When receiving transfers on the node, pass the master as a transfer handler (this is
+///    required to get transmission times)
+/// 4. Call `poll` frequently (at least once per millisecond for precise timing)
+pub struct TimeSyncMaster<I: Instant> {
+    token: PublishToken<Synchronization>,
+    reference: TimeReference<I>,
+    /// The time when the next message should be published
+    next_publication: I,
+    /// The synchronized time when the previous message was published
+    previous_publication: Option<u64>,
+    time_system: u8,
+    error_variance: f32,
+    tai_minus_utc: u16,
+}
+
+impl<I: Instant> TimeSyncMaster<I> {
+    /// Creates a time synchronization master and starts publishing synchronization messages
+    ///
+    /// This function returns an error if the node does not have enough space for another
+    /// publisher.
+    pub fn new<N>(
+        node: &mut N,
+    ) -> Result<Self, StartSendError<<N::Transmitter as Transmitter<I>>::Error>>
+    where
+        N: Node<Instant = I>,
+    {
+        let token = node.start_publishing(
+            synchronization_1_0::SUBJECT,
+            milliseconds(PUBLICATION_PERIOD_MS),
+            Priority::Fast.into(),
+        )?;
+        let now = node.clock_mut().now();
+        Ok(TimeSyncMaster {
+            token,
+            reference: TimeReference::new(now, 0),
+            next_publication: now,
+            previous_publication: None,
+            time_system: TimeSystem::MONOTONIC_SINCE_BOOT,
+            error_variance: 0.0,
+            tai_minus_utc: TAIInfo::DIFFERENCE_TAI_MINUS_UTC_UNKNOWN,
+        })
+    }
+
+    /// Subscribes to master information requests
+    ///
+    /// This function returns an error if the provided node does not have enough space to listen
+    /// for requests.
+    pub fn subscribe_requests<N>(node: &mut N) -> Result<(), <N::Receiver as Receiver<I>>::Error>
+    where
+        N: Node<Instant = I>,
+    {
+        node.subscribe_request(
+            get_synchronization_master_info_0_1::SERVICE,
+            get_synchronization_master_info_0_1::GetSynchronizationMasterInfoRequest::extent(),
+            milliseconds(1000),
+        )
+    }
+
+    /// Sets the synchronized time at a local instant
+    ///
+    /// The next synchronization message does not contain a timestamp, because the time when the
+    /// previous message was sent is no longer meaningful.
+    pub fn set_time(&mut self, local: I, synchronized: u64) {
+        self.reference = TimeReference::new(local, synchronized);
+        self.previous_publication = None;
+    }
+
+    /// Sets the time system and the difference between TAI and UTC that this master reports
+    ///
+    /// `time_system` should be one of the constants in `TimeSystem`.
+    pub fn set_time_system(&mut self, time_system: TimeSystem, tai_minus_utc: u16) {
+        self.time_system = time_system.value;
+        self.tai_minus_utc = tai_minus_utc;
+    }
+
+    /// Sets the error variance of the synchronized time, in square seconds, that this master
+    /// reports
+    pub fn set_error_variance(&mut self, error_variance: f32) {
+        self.error_variance = error_variance;
+    }
+
+    /// Publishes a synchronization message if it is time to do so
+    pub fn poll<N>(
+        &mut self,
+        node: &mut N,
+    ) -> nb::Result<(), <N::Transmitter as Transmitter<I>>::Error>
+    where
+        N: Node<Instant = I>,
+    {
+        let now = node.clock_mut().now();
+        // Keep the reference close to the current time so that it does not overflow
+        self.reference = TimeReference::new(now, self.reference.time_at(now));
+        if now.overflow_safe_compare(&self.next_publication) == Ordering::Less {
+            return Ok(());
+        }
+        let message = Synchronization {
+            previous_transmission_timestamp_microsecond: self.previous_publication.unwrap_or(0),
+        };
+        node.publish_loopback(&self.token, &message)?;
+        // If the transport supports loopback, handle_loopback will replace this with the
+        // actual transmission time
+        self.previous_publication = Some(self.reference.synchronized);
+        self.next_publication = milliseconds::<I::Duration>(PUBLICATION_PERIOD_MS) + now;
+        Ok(())
+    }
+}
+
+impl<I: Instant> SynchronizedClock<I> for TimeSyncMaster<I> {
+    fn synchronized_time(&self, local: I) -> Option<u64> {
+        Some(self.reference.time_at(local))
+    }
+}
+
+impl<I, T> TransferHandler<I, T> for TimeSyncMaster<I>
+where
+    I: Instant,
+    T: Transport,
+{
+    fn handle_request<N: Node<Instant = I, Transport = T>>(
+        &mut self,
+        node: &mut N,
+        token: ResponseToken<T>,
+        transfer: &ServiceTransfer<Vec<u8>, I, T>,
+    ) -> bool {
+        if transfer.header.service != get_synchronization_master_info_0_1::SERVICE {
+            return false;
+        }
+        let response = GetSynchronizationMasterInfoResponse {
+            error_variance: self.error_variance,
+            time_system: TimeSystem {
+                value: self.time_system,
+            },
+            tai_info: TAIInfo {
+                difference_tai_minus_utc: self.tai_minus_utc,
+            },
+        };
+        if node
+            .send_response(token, milliseconds(1000), &response)
+            .is_err()
+        {
+            log::warn!("Out of memory when sending synchronization master information");
+        }
+        true
+    }
+
+    fn handle_loopback<N: Node<Instant = I, Transport = T>>(
+        &mut self,
+        _node: &mut N,
+        header: &Header<I, T>,
+    ) -> bool {
+        match header {
+            Header::Message(header) if header.subject == synchronization_1_0::SUBJECT => {
+                self.previous_publication = Some(self.reference.time_at(header.timestamp));
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Receives `uavcan.time.Synchronization` messages and estimates the synchronized time
+///
+/// If more than one master is publishing, the slave follows the master with the lowest node ID.
+/// If the master stops publishing for three seconds, the slave follows the next master that
+/// publishes a message.
+///
+/// Basic steps:
+/// 1. Create a slave using `TimeSyncSlave::new`
+/// 2. When receiving transfers on the node, pass the slave as a transfer handler
+/// 3. Use the slave (or a [`TimeReference`] from [`reference`](#method.reference)) as a
+///    [`SynchronizedClock`]
+pub struct TimeSyncSlave<I: Instant, T: Transport> {
+    /// The master that this slave follows
+    master: Option<T::NodeId>,
+    /// The local time and transfer ID of the most recent message from the master
+    previous_message: Option<(I, T::TransferId)>,
+    /// The most recent pair of local and synchronized times
+    previous_sample: Option<(I, u64)>,
+    /// The estimated drift, if at least two samples have been received
+    drift: Option<f32>,
+    reference: Option<TimeReference<I>>,
+}
+
+impl<I, T> TimeSyncSlave<I, T>
+where
+    I: Instant,
+    T: Transport,
+    T::TransferId: PartialEq,
+{
+    /// Creates a time synchronization slave and subscribes to synchronization messages
+    ///
+    /// This function returns an error if the provided node does not have enough space to listen
+    /// for messages.
+    pub fn new<N>(node: &mut N) -> Result<Self, <N::Receiver as Receiver<I>>::Error>
+    where
+        N: Node<Instant = I, Transport = T>,
+    {
+        node.subscribe_message(
+            synchronization_1_0::SUBJECT,
+            Synchronization::extent(),
+            milliseconds(MASTER_TIMEOUT_MS),
+        )?;
+        Ok(TimeSyncSlave {
+            master: None,
+            previous_message: None,
+            previous_sample: None,
+            drift: None,
+            reference: None,
+        })
+    }
+
+    /// Returns the ID of the master that this slave follows, if any
+    pub fn master(&self) -> Option<T::NodeId> {
+        self.master.clone()
+    }
+
+    /// Returns true if this slave has received enough messages to know the synchronized time
+    pub fn is_synchronized(&self) -> bool {
+        self.reference.is_some()
+    }
+
+    /// Returns the current estimate of the synchronized time, or None if it is not known
+    ///
+    /// The returned reference is a copy that does not change when more messages arrive.
+    pub fn reference(&self) -> Option<TimeReference<I>> {
+        self.reference
+    }
+
+    fn handle_synchronization(
+        &mut self,
+        source: T::NodeId,
+        transfer_id: &T::TransferId,
+        local: I,
+        message: &Synchronization,
+    ) {
+        let master_timed_out = match &self.previous_message {
+            Some((previous_local, _)) => {
+                microseconds_between(*previous_local, local) > i64::from(MASTER_TIMEOUT_MS) * 1000
+            }
+            None => true,
+        };
+        let switch_master = match &self.master {
+            None => true,
+            Some(master) if *master == source => false,
+            Some(master) => master_timed_out || source.clone().into() < master.clone().into(),
+        };
+        if switch_master {
+            log::info!("Following time synchronization master {:?}", source);
+            self.master = Some(source.clone());
+            self.previous_message = None;
+            self.previous_sample = None;
+            self.drift = None;
+            self.reference = None;
+        } else if self.master.as_ref() != Some(&source) {
+            return;
+        }
+
+        let timestamp = message.previous_transmission_timestamp_microsecond;
+        if let Some((previous_local, previous_transfer_id)) = self.previous_message.take() {
+            // The timestamp applies to the previous message only if no messages were lost between
+            // them
+            let consecutive = previous_transfer_id.increment() == *transfer_id;
+            if timestamp != 0 && consecutive && !master_timed_out {
+                self.add_sample(previous_local, timestamp);
+            }
+        }
+        self.previous_message = Some((local, transfer_id.clone()));
+    }
+
+    /// Updates the estimate using the synchronized time at a local instant
+    fn add_sample(&mut self, local: I, synchronized: u64) {
+        if let Some((previous_local, previous_synchronized)) = self.previous_sample {
+            let local_elapsed = microseconds_between(previous_local, local);
+            let synchronized_elapsed = synchronized.wrapping_sub(previous_synchronized) as i64;
+            if local_elapsed > 0 {
+                let measured = (synchronized_elapsed - local_elapsed) as f32 / local_elapsed as f32;
+                self.drift = match self.drift {
+                    // A large change means that the master changed its time
+                    _ if measured.abs() > MAX_DRIFT => None,
+                    // Low-pass filter to reduce the effect of jitter in the reception times
+                    Some(drift) => Some(drift + (measured - drift) * 0.25),
+                    None => Some(measured),
+                };
+            }
+        }
+        self.previous_sample = Some((local, synchronized));
+        self.reference = Some(TimeReference {
+            local,
+            synchronized,
+            drift: self.drift.unwrap_or(0.0),
+        });
+    }
+}
+
+impl<I, T> SynchronizedClock<I> for TimeSyncSlave<I, T>
+where
+    I: Instant,
+    T: Transport,
+{
+    fn synchronized_time(&self, local: I) -> Option<u64> {
+        self.reference.synchronized_time(local)
+    }
+}
+
+impl<I, T> TransferHandler<I, T> for TimeSyncSlave<I, T>
+where
+    I: Instant,
+    T: Transport,
+    T::TransferId: PartialEq,
+{
+    fn handle_message<N: Node<Instant = I, Transport = T>>(
+        &mut self,
+        _node: &mut N,
+        transfer: &MessageTransfer<Vec<u8>, I, T>,
+    ) -> bool {
+        if transfer.header.subject != synchronization_1_0::SUBJECT {
+            return false;
+        }
+        // Anonymous nodes can't be masters
+        if let Some(source) = &transfer.header.source {
+            if let Ok(message) = Synchronization::deserialize_from_bytes(&transfer.payload) {
+                self.handle_synchronization(
+                    source.clone(),
+                    &transfer.header.transfer_id,
+                    transfer.header.timestamp,
+                    &message,
+                );
+            }
+        }
+        true
+    }
+}
+
+/// Returns the number of microseconds from `from` to `to`, which is negative if `to` is
+/// before `from`
+fn microseconds_between<I: Instant>(from: I, to: I) -> i64 {
+    if to.overflow_safe_compare(&from) == Ordering::Less {
+        -(duration_microseconds(from.duration_since(&to)) as i64)
+    } else {
+        duration_microseconds(to.duration_since(&from)) as i64
+    }
+}
+
+fn duration_microseconds<D: Duration>(duration: D) -> u64 {
+    duration.as_secs() * 1_000_000 + u64::from(duration.subsec_nanos() / 1000)
+}
@@ -0,0 +1,119 @@
+//!
+//! Transfer handlers that deserialize incoming messages
+//!
+
+use core::fmt::{Debug, Formatter};
+use core::marker::PhantomData;
+
+use alloc::vec::Vec;
+use canadensis_core::time::Instant;
+use canadensis_core::transfer::MessageTransfer;
+use canadensis_core::transport::Transport;
+use canadensis_core::SubjectId;
+use canadensis_encoding::{Deserialize, Message};
+
+use crate::{Node, SubscriptionToken, TransferHandler};
+
+/// A transfer handler that deserializes messages on one subject and passes them to a function
+///
+/// The function can be a closure, or a trait object like `&mut dyn FnMut(&MessageTransfer<M, I, T>)`
+/// or `Box<dyn FnMut(&MessageTransfer<M, I, T>)>`. It receives each message with its header.
+///
+/// Messages that can't be deserialized are counted, and the count is available from
+/// [`deserialize_errors`](#method.deserialize_errors).
+///
+/// Adapters for different subjects can be combined using
+/// [`TransferHandler::chain`](crate::TransferHandler::chain).
+pub struct MessageAdapter<M, F> {
+    /// The subject to handle messages on
+    subject: SubjectId,
+    /// The function that handles deserialized messages
+    handler: F,
+    /// The number of messages that could not be deserialized
+    deserialize_errors: usize,
+    _message: PhantomData<fn(M)>,
+}
+
+impl<M, F> MessageAdapter<M, F>
+where
+    M: Message + Deserialize,
+{
+    /// Creates an adapter that passes messages on the token's subject to `handler`
+    pub fn new(token: &SubscriptionToken<M>, handler: F) -> Self {
+        MessageAdapter {
+            subject: token.subject_id(),
+            handler,
+            deserialize_errors: 0,
+            _message: PhantomData,
+        }
+    }
+
+    /// Returns the subject that this adapter handles messages on
+    pub fn subject_id(&self) -> SubjectId {
+        self.subject
+    }
+
+    /// Returns the number of messages on this subject that could not be deserialized
+    pub fn deserialize_errors(&self) -> usize {
+        self.deserialize_errors
+    }
+
+    /// Returns a reference to the enclosed handler function
+    pub fn handler(&self) -> &F {
+        &self.handler
+    }
+
+    /// Returns a mutable reference to the enclosed handler function
+    pub fn handler_mut(&mut self) -> &mut F {
+        &mut self.handler
+    }
+
+    /// Consumes this adapter and returns the enclosed handler function
+    pub fn into_handler(self) -> F {
+        self.handler
+    }
+}
+
+impl<M, F, I, T> TransferHandler<I, T> for MessageAdapter<M, F>
+where
+    M: Message + Deserialize,
+    F: FnMut(&MessageTransfer<M, I, T>),
+    I: Instant,
+    T: Transport,
+{
+    fn handle_message<N: Node<Instant = I, Transport = T>>(
+        &mut self,
+        _node: &mut N,
+        transfer: &MessageTransfer<Vec<u8>, I, T>,
+    ) -> bool {
+        if transfer.header.subject != self.subject {
+            return false;
+        }
+        match M::deserialize_from_bytes(&transfer.payload) {
+            Ok(message) => {
+                (self.handler)(&MessageTransfer {
+                    header: transfer.header.clone(),
+                    payload: message,
+                });
+            }
+            Err(e) => {
+                log::debug!(
+                    "Failed to deserialize message on subject {:?}: {:?}",
+                    self.subject,
+                    e
+                );
+                self.deserialize_errors = self.deserialize_errors.saturating_add(1);
+            }
+        }
+        true
+    }
+}
+
+impl<M, F> Debug for MessageAdapter<M, F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("MessageAdapter")
+            .field("subject", &self.subject)
+            .field("deserialize_errors", &self.deserialize_errors)
+            .finish()
+    }
+}
@@ -0,0 +1,226 @@
+//! Tests of the async node API, using two nodes connected by a simulated CAN bus
+//!
+//! The simulated drivers can't be sent to other threads, so the node tasks run in a
+//! [`LocalSet`].
+
+extern crate canadensis;
+extern crate canadensis_can;
+extern crate canadensis_data_types;
+extern crate canadensis_linux;
+extern crate futures_util;
+extern crate heapless;
+extern crate tokio;
+
+mod common;
+
+use std::convert::TryFrom;
+use std::time::Duration;
+
+use futures_util::StreamExt;
+use tokio::task::LocalSet;
+
+use canadensis::asynchronous::AsyncNode;
+use canadensis::core::time::{MicrosecondDuration64, Microseconds64};
+use canadensis::core::{Priority, SubjectId};
+use canadensis::node::CoreNode;
+use canadensis::requester::TransferIdFixedMap;
+use canadensis_can::{CanNodeId, CanReceiver, CanTransmitter, CanTransport, Mtu};
+use canadensis_data_types::uavcan::node::execute_command_1_1::{
+    self, ExecuteCommandRequest, ExecuteCommandResponse,
+};
+use canadensis_data_types::uavcan::node::heartbeat_1_0::{self, Heartbeat};
+use canadensis_data_types::uavcan::primitive::scalar::integer32_1_0::Integer32;
+use canadensis_linux::SystemClock;
+
+use common::{make_bus, TestDriver};
+
+type TestNode = CoreNode<
+    SystemClock,
+    CanTransmitter<Microseconds64, TestDriver>,
+    CanReceiver<Microseconds64, TestDriver>,
+    TransferIdFixedMap<CanTransport, 4>,
+    TestDriver,
+    4,
+    4,
+>;
+
+fn make_node(id: u8, clock: &SystemClock, driver: TestDriver) -> AsyncNode<TestNode> {
+    let node_id = CanNodeId::try_from(id).unwrap();
+    let core_node = CoreNode::new(
+        clock.clone(),
+        node_id,
+        CanTransmitter::new(Mtu::Can8),
+        CanReceiver::new(node_id, Mtu::Can8),
+        driver,
+    );
+    AsyncNode::new(core_node).unwrap()
+}
+
+/// Creates two connected nodes and starts their background tasks
+///
+/// This must be called from within a [`LocalSet`].
+fn make_connected_nodes() -> (AsyncNode<TestNode>, AsyncNode<TestNode>) {
+    let bus = make_bus();
+    let clock = SystemClock::new();
+    let node_a = make_node(1, &clock, bus.add_driver());
+    let node_b = make_node(2, &clock, bus.add_driver());
+    for node in [node_a.clone(), node_b.clone()] {
+        tokio::task::spawn_local(async move {
+            node.run(|| tokio::time::sleep(Duration::from_millis(1)))
+                .await
+        });
+    }
+    (node_a, node_b)
+}
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+#[tokio::test]
+async fn heartbeat_in_background() {
+    LocalSet::new()
+        .run_until(async {
+            let (_node_a, node_b) = make_connected_nodes();
+            let mut heartbeats = node_b
+                .subscribe_message::<Heartbeat>(
+                    heartbeat_1_0::SUBJECT,
+                    7,
+                    MicrosecondDuration64::new(2_000_000),
+                    4,
+                )
+                .unwrap();
+            let heartbeat = tokio::time::timeout(TIMEOUT, heartbeats.next())
+                .await
+                .expect("No heartbeat")
+                .unwrap();
+            assert_eq!(
+                Some(CanNodeId::try_from(1u8).unwrap()),
+                heartbeat.header.source
+            );
+        })
+        .await;
+}
+
+#[tokio::test]
+async fn publish_and_stream() {
+    LocalSet::new()
+        .run_until(async {
+            let (node_a, node_b) = make_connected_nodes();
+            let subject = SubjectId::try_from(1000).unwrap();
+            let mut stream = node_b
+                .subscribe_message::<Integer32>(
+                    subject,
+                    4,
+                    MicrosecondDuration64::new(2_000_000),
+                    4,
+                )
+                .unwrap();
+            let token = node_a
+                .start_publishing::<Integer32>(
+                    subject,
+                    MicrosecondDuration64::new(1_000_000),
+                    Priority::Nominal,
+                )
+                .unwrap();
+
+            for value in [-1, 37, 1_000_000] {
+                node_a.publish(&token, &Integer32 { value }).await.unwrap();
+                let received = tokio::time::timeout(TIMEOUT, stream.next())
+                    .await
+                    .expect("No message")
+                    .unwrap();
+                let received_value = received.payload.value;
+                assert_eq!(value, received_value);
+            }
+        })
+        .await;
+}
+
+#[tokio::test]
+async fn request_and_response() {
+    LocalSet::new()
+        .run_until(async {
+            let (node_a, node_b) = make_connected_nodes();
+
+            let mut requests = node_b
+                .subscribe_request::<ExecuteCommandRequest>(
+                    execute_command_1_1::SERVICE,
+                    300,
+                    MicrosecondDuration64::new(1_000_000),
+                    4,
+                )
+                .unwrap();
+            let server = node_b.clone();
+            tokio::task::spawn_local(async move {
+                while let Some((token, request)) = requests.next().await {
+                    let response = ExecuteCommandResponse {
+                        status: request.payload.command as u8,
+                    };
+                    server
+                        .send_response(token, MicrosecondDuration64::new(1_000_000), &response)
+                        .await
+                        .unwrap();
+                }
+            });
+
+            let token = node_a
+                .start_sending_requests::<ExecuteCommandRequest>(
+                    execute_command_1_1::SERVICE,
+                    MicrosecondDuration64::new(1_000_000),
+                    48,
+                    Priority::Nominal,
+                )
+                .unwrap();
+            for command in [3u16, 7] {
+                let request = ExecuteCommandRequest {
+                    command,
+                    // Long enough to need multiple frames
+                    parameter: heapless::Vec::from_slice(&[0xaa; 20]).unwrap(),
+                };
+                let response = tokio::time::timeout(
+                    TIMEOUT,
+                    node_a.request::<_, ExecuteCommandResponse>(
+                        &token,
+                        &request,
+                        CanNodeId::try_from(2u8).unwrap(),
+                    ),
+                )
+                .await
+                .expect("No response")
+                .unwrap();
+                assert_eq!(command as u8, response.payload.status);
+                assert_eq!(CanNodeId::try_from(2u8).unwrap(), response.header.source);
+            }
+        })
+        .await;
+}
+
+#[tokio::test]
+async fn request_without_server_times_out() {
+    LocalSet::new()
+        .run_until(async {
+            let (node_a, _node_b) = make_connected_nodes();
+            let token = node_a
+                .start_sending_requests::<ExecuteCommandRequest>(
+                    execute_command_1_1::SERVICE,
+                    MicrosecondDuration64::new(1_000_000),
+                    48,
+                    Priority::Nominal,
+                )
+                .unwrap();
+            let request = ExecuteCommandRequest {
+                command: 1,
+                parameter: heapless::Vec::new(),
+            };
+            let result = tokio::time::timeout(
+                Duration::from_millis(100),
+                node_a.request::<_, ExecuteCommandResponse>(
+                    &token,
+                    &request,
+                    CanNodeId::try_from(2u8).unwrap(),
+                ),
+            )
+            .await;
+            assert!(result.is_err());
+        })
+        .await;
+}
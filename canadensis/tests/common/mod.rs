@@ -0,0 +1,59 @@
+//! Utilities shared by the integration tests
+
+use std::convert::TryFrom;
+
+use canadensis::core::time::{MicrosecondDuration64, Microseconds64, SimulatedClock};
+use canadensis::node::CoreNode;
+use canadensis::requester::TransferIdFixedMap;
+use canadensis_can::simulated::{SimulatedBus, SimulatedDriver};
+use canadensis_can::{CanNodeId, CanReceiver, CanTransmitter, CanTransport, Mtu};
+
+/// A driver connected to a simulated bus
+#[allow(dead_code)]
+pub type TestDriver = SimulatedDriver<Microseconds64>;
+
+/// A node that uses a simulated clock and a simulated CAN bus
+#[allow(dead_code)]
+pub type TestNode = CoreNode<
+    SimulatedClock,
+    CanTransmitter<Microseconds64, TestDriver>,
+    CanReceiver<Microseconds64, TestDriver>,
+    TransferIdFixedMap<CanTransport, 4>,
+    TestDriver,
+    4,
+    4,
+>;
+
+/// Creates a bus that delivers each frame on the next update after it is transmitted
+#[allow(dead_code)]
+pub fn make_bus() -> SimulatedBus<Microseconds64> {
+    SimulatedBus::new(MicrosecondDuration64::new(0))
+}
+
+#[allow(dead_code)]
+pub fn node_id(id: u8) -> CanNodeId {
+    CanNodeId::try_from(id).unwrap()
+}
+
+/// Creates a node with a clock and a driver
+#[allow(dead_code)]
+pub fn make_node(clock: &SimulatedClock, id: u8, driver: TestDriver) -> TestNode {
+    CoreNode::new(
+        clock.clone(),
+        node_id(id),
+        CanTransmitter::new(Mtu::Can8),
+        CanReceiver::new(node_id(id), Mtu::Can8),
+        driver,
+    )
+}
+
+/// Creates two nodes, with node IDs 1 and 2, that are connected to the same bus and use the
+/// same clock
+#[allow(dead_code)]
+pub fn node_pair(clock: &SimulatedClock) -> (TestNode, TestNode) {
+    let bus = make_bus();
+    (
+        make_node(clock, 1, bus.add_driver()),
+        make_node(clock, 2, bus.add_driver()),
+    )
+}
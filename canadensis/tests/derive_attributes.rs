@@ -0,0 +1,173 @@
+extern crate canadensis;
+extern crate canadensis_data_types;
+extern crate heapless;
+
+use canadensis::register::basic::{RegisterString, SimpleRegister, ValidatedRegister};
+use canadensis::register::names::{self, NameEntry};
+use canadensis::register::{DefaultRegisterBlock, RegisterBlock};
+use canadensis_data_types::uavcan::primitive::array::real32_1_0::Real32;
+use canadensis_data_types::uavcan::register::value_1_0::Value;
+
+fn non_negative(value: &f32) -> bool {
+    *value >= 0.0
+}
+
+#[derive(RegisterBlock)]
+struct MotorRegisters {
+    #[register(name = "kp", mutable, persistent, default = 1.5)]
+    kp: SimpleRegister<f32>,
+    #[register(name = "ki", mutable, validator = non_negative)]
+    ki: ValidatedRegister<f32>,
+}
+
+#[derive(RegisterBlock)]
+struct NodeRegisters {
+    #[register(name = "uavcan.node.id", mutable, persistent, default = 65535)]
+    node_id: SimpleRegister<u16>,
+    #[register(name = "uavcan.node.description", mutable, persistent)]
+    description: SimpleRegister<RegisterString>,
+}
+
+#[derive(RegisterBlock)]
+struct Registers {
+    #[register(block)]
+    node: NodeRegisters,
+    #[register(block, prefix = "motor")]
+    motors: [MotorRegisters; 2],
+    #[register(block, prefix = "spare")]
+    spare: MotorRegisters,
+    #[register(name = "read_only", default = 3)]
+    read_only: SimpleRegister<u8>,
+}
+
+#[derive(RegisterBlock)]
+struct RegistersTuple(#[register(name = "a", mutable)] SimpleRegister<u8>);
+
+fn names<B: RegisterBlock>(block: &B) -> Vec<String> {
+    (0..)
+        .map_while(|index| block.register_by_index(index))
+        .map(|register| register.name().to_owned())
+        .collect()
+}
+
+fn real32(value: f32) -> Value {
+    Value::Real32(Real32 {
+        value: heapless::Vec::from_slice(&[value]).unwrap(),
+    })
+}
+
+#[test]
+fn defaults() {
+    let registers = Registers::default();
+    assert_eq!(
+        vec![
+            "uavcan.node.id",
+            "uavcan.node.description",
+            "motor.0.kp",
+            "motor.0.ki",
+            "motor.1.kp",
+            "motor.1.ki",
+            "spare.kp",
+            "spare.ki",
+            "read_only",
+        ],
+        names(&registers)
+    );
+    assert_eq!(9, names::count(Registers::NAMES));
+    assert!(registers.register_by_index(9).is_none());
+
+    assert_eq!(65535, *registers.node.node_id.value());
+    assert_eq!(1.5, *registers.motors[1].kp.value());
+    assert_eq!(0.0, *registers.spare.ki.value());
+    assert_eq!(3, *registers.read_only.value());
+
+    let node_id = registers.register_by_index(0).unwrap().access();
+    assert!(node_id.mutable && node_id.persistent);
+    let ki = registers.register_by_index(3).unwrap().access();
+    assert!(ki.mutable && !ki.persistent);
+    let read_only = registers.register_by_index(8).unwrap().access();
+    assert!(!read_only.mutable && !read_only.persistent);
+
+    assert_eq!(vec!["a"], names(&RegistersTuple::default()));
+}
+
+#[test]
+fn nested_access() {
+    let mut registers = Registers::default();
+    registers
+        .register_by_name_mut("motor.1.kp")
+        .unwrap()
+        .write(&real32(2.0))
+        .unwrap();
+    assert_eq!(2.0, *registers.motors[1].kp.value());
+    assert_eq!(1.5, *registers.motors[0].kp.value());
+
+    // The validator rejects negative values
+    let ki = registers.register_by_name_mut("spare.ki").unwrap();
+    assert!(ki.write(&real32(-1.0)).is_err());
+    assert!(ki.write(&real32(0.5)).is_ok());
+    assert_eq!(0.5, *registers.spare.ki.value());
+
+    assert_eq!(
+        "motor.0.ki",
+        registers.register_by_index_mut(3).unwrap().name()
+    );
+    assert!(registers.register_by_name_mut("ki").is_none());
+    assert!(registers.register_by_name_mut("motor.2.kp").is_none());
+}
+
+#[test]
+fn prefixed_block() {
+    let block = NodeRegisters::with_prefix("bus.1.");
+    assert_eq!(
+        vec!["bus.1.uavcan.node.id", "bus.1.uavcan.node.description"],
+        names(&block)
+    );
+}
+
+#[test]
+fn check_names() {
+    const ELEMENT: &[NameEntry] = &[NameEntry::Register("x")];
+    names::check(&[
+        NameEntry::Register("m.10"),
+        NameEntry::Register("m.1.y"),
+        NameEntry::Array {
+            prefix: "m",
+            length: 11,
+            names: ELEMENT,
+        },
+    ]);
+}
+
+#[test]
+#[should_panic(expected = "Duplicate register name")]
+fn check_duplicate_in_array() {
+    const ELEMENT: &[NameEntry] = &[NameEntry::Register("x")];
+    names::check(&[
+        NameEntry::Array {
+            prefix: "m",
+            length: 11,
+            names: ELEMENT,
+        },
+        NameEntry::Register("m.10.x"),
+    ]);
+}
+
+#[test]
+#[should_panic(expected = "Duplicate register name")]
+fn check_duplicate_in_block() {
+    const BLOCK: &[NameEntry] = &[NameEntry::Register("node.id")];
+    names::check(&[
+        NameEntry::Register("uavcan.node.id"),
+        NameEntry::Block {
+            prefix: "uavcan",
+            names: BLOCK,
+        },
+    ]);
+}
+
+#[test]
+#[should_panic(expected = "Register name is empty")]
+fn check_empty() {
+    names::check(&[NameEntry::Register("")]);
+}
@@ -0,0 +1,168 @@
+//! Tests of publishing log records, using two nodes connected by a simulated CAN bus
+
+extern crate canadensis;
+extern crate canadensis_can;
+extern crate canadensis_data_types;
+extern crate log;
+
+mod common;
+
+use canadensis::core::time::{milliseconds, Microseconds64, SimulatedClock};
+use canadensis::core::transfer::MessageTransfer;
+use canadensis::diagnostic::{DiagnosticLogger, DiagnosticPublisher};
+use canadensis::encoding::Deserialize;
+use canadensis::time_sync::{NotSynchronized, TimeReference};
+use canadensis::{Node, TransferHandler};
+use canadensis_can::CanTransport;
+use canadensis_data_types::uavcan::diagnostic::record_1_1::{self, Record};
+use canadensis_data_types::uavcan::diagnostic::severity_1_0::Severity;
+use log::{Level, Log};
+
+use common::{node_pair, TestNode};
+
+/// Collects received records as (timestamp, severity, text)
+#[derive(Default)]
+struct RecordCollector {
+    records: Vec<(u64, u8, String)>,
+}
+
+impl TransferHandler<Microseconds64, CanTransport> for RecordCollector {
+    fn handle_message<N: Node<Instant = Microseconds64, Transport = CanTransport>>(
+        &mut self,
+        _node: &mut N,
+        transfer: &MessageTransfer<Vec<u8>, Microseconds64, CanTransport>,
+    ) -> bool {
+        let record = Record::deserialize_from_bytes(&transfer.payload).unwrap();
+        self.records.push((
+            record.timestamp.microsecond,
+            record.severity.value,
+            String::from_utf8(record.text.to_vec()).unwrap(),
+        ));
+        true
+    }
+}
+
+struct Setup {
+    clock: SimulatedClock,
+    node: TestNode,
+    publisher: DiagnosticPublisher<Microseconds64>,
+    monitor_node: TestNode,
+    collector: RecordCollector,
+}
+
+impl Setup {
+    fn new(max_records_per_second: u32) -> Self {
+        let clock = SimulatedClock::new();
+        let (mut node, mut monitor_node) = node_pair(&clock);
+        let publisher = DiagnosticPublisher::new(&mut node, max_records_per_second).unwrap();
+        monitor_node
+            .subscribe::<Record>(record_1_1::SUBJECT, milliseconds(1000))
+            .unwrap();
+        Setup {
+            clock,
+            node,
+            publisher,
+            monitor_node,
+            collector: RecordCollector::default(),
+        }
+    }
+
+    /// Publishes records and returns the records that the other node received
+    fn poll<const N: usize>(&mut self, logger: &DiagnosticLogger<N>) -> Vec<(u64, u8, String)> {
+        self.publisher
+            .poll(&mut self.node, logger, &NotSynchronized)
+            .unwrap();
+        self.receive()
+    }
+
+    fn receive(&mut self) -> Vec<(u64, u8, String)> {
+        self.node.flush().unwrap();
+        for _ in 0..256 {
+            self.monitor_node.receive(&mut self.collector).unwrap();
+        }
+        std::mem::take(&mut self.collector.records)
+    }
+}
+
+fn log(logger: &dyn Log, level: Level, text: &str) {
+    logger.log(
+        &log::Record::builder()
+            .level(level)
+            .args(format_args!("{}", text))
+            .build(),
+    );
+}
+
+#[test]
+fn levels_and_truncation() {
+    let mut setup = Setup::new(10);
+    let logger = DiagnosticLogger::<8>::new();
+    log(&logger, Level::Error, "error");
+    log(&logger, Level::Warn, "warn");
+    log(&logger, Level::Info, "info");
+    log(&logger, Level::Debug, "debug");
+    log(&logger, Level::Trace, "trace");
+    // 254 ASCII characters followed by a two-byte character that does not fit
+    let long_text = format!("{}é and more", "a".repeat(254));
+    log(&logger, Level::Info, &long_text);
+    assert_eq!(6, logger.len());
+
+    assert_eq!(
+        vec![
+            (0, Severity::ERROR, "error".to_owned()),
+            (0, Severity::WARNING, "warn".to_owned()),
+            (0, Severity::INFO, "info".to_owned()),
+            (0, Severity::DEBUG, "debug".to_owned()),
+            (0, Severity::TRACE, "trace".to_owned()),
+            (0, Severity::INFO, "a".repeat(254)),
+        ],
+        setup.poll(&logger)
+    );
+    assert!(logger.is_empty());
+}
+
+#[test]
+fn rate_limit_and_dropped_records() {
+    let mut setup = Setup::new(2);
+    let logger = DiagnosticLogger::<3>::new();
+    for i in 0..5 {
+        log(&logger, Level::Info, &format!("record {}", i));
+    }
+    // The last two records did not fit
+    assert_eq!(3, logger.len());
+
+    let expected_dropped = (0, Severity::WARNING, "2 log records dropped".to_owned());
+    assert_eq!(
+        vec![expected_dropped, (0, Severity::INFO, "record 0".to_owned())],
+        setup.poll(&logger)
+    );
+    // No more records until one second has passed
+    setup.clock.set(Microseconds64::new(999_999));
+    assert!(setup.poll(&logger).is_empty());
+    setup.clock.set(Microseconds64::new(1_000_000));
+    assert_eq!(
+        vec![
+            (0, Severity::INFO, "record 1".to_owned()),
+            (0, Severity::INFO, "record 2".to_owned())
+        ],
+        setup.poll(&logger)
+    );
+    assert!(logger.is_empty());
+}
+
+#[test]
+fn synchronized_timestamps() {
+    let mut setup = Setup::new(10);
+    let logger = DiagnosticLogger::<4>::new();
+    log(&logger, Level::Info, "synchronized");
+    setup.clock.set(Microseconds64::new(5_000));
+    let reference = TimeReference::new(Microseconds64::new(0), 1_000_000_000);
+    setup
+        .publisher
+        .poll(&mut setup.node, &logger, &reference)
+        .unwrap();
+    assert_eq!(
+        vec![(1_000_005_000, Severity::INFO, "synchronized".to_owned())],
+        setup.receive()
+    );
+}
@@ -0,0 +1,170 @@
+//! Tests of loading dynamic register values from TOML, YAML, and JSON documents
+
+extern crate canadensis;
+extern crate canadensis_data_types;
+extern crate heapless;
+
+use canadensis::register::basic::RegisterString;
+use canadensis::register::client::value_as;
+use canadensis::register::dynamic::{ConfigError, DynamicRegisterBlock, ValueErrorKind};
+use canadensis::register::{Access, Register};
+use canadensis_data_types::uavcan::primitive::array::natural8_1_0::Natural8;
+use canadensis_data_types::uavcan::primitive::array::real32_1_0::Real32;
+use canadensis_data_types::uavcan::register::value_1_0::Value;
+
+/// A block with two existing registers
+fn block() -> DynamicRegisterBlock {
+    let mut block = DynamicRegisterBlock::new();
+    let access = Access {
+        mutable: true,
+        persistent: true,
+    };
+    block
+        .add(
+            "uavcan.node.id",
+            Value::Natural8(Natural8 {
+                value: heapless::Vec::from_slice(&[0]).unwrap(),
+            }),
+            access.clone(),
+        )
+        .unwrap();
+    block
+        .add(
+            "motor.gains",
+            Value::Real32(Real32 {
+                value: heapless::Vec::new(),
+            }),
+            access,
+        )
+        .unwrap();
+    block
+}
+
+fn value<'b>(block: &'b DynamicRegisterBlock, name: &str) -> &'b Value {
+    block.get(name).unwrap().value()
+}
+
+/// Checks the values loaded from each document, which all contain the same values
+fn check_loaded(block: &DynamicRegisterBlock) {
+    assert_eq!(Some(42u8), value_as(value(block, "uavcan.node.id")).ok());
+    assert_eq!(
+        Some([0.5f32, 1.0, 2.0]),
+        value_as(value(block, "motor.gains")).ok()
+    );
+    // New registers with inferred types
+    assert_eq!(
+        Some(&b"gateway"[..]),
+        value_as::<RegisterString>(value(block, "uavcan.node.description"))
+            .ok()
+            .as_ref()
+            .map(|string| &string.0[..])
+    );
+    assert!(
+        matches!(value(block, "plugin.count"), Value::Integer64(values) if values.value[..] == [3])
+    );
+    assert!(
+        matches!(value(block, "plugin.rate"), Value::Real64(values) if values.value[..] == [2.5])
+    );
+    assert_eq!(
+        Some([true, false]),
+        value_as(value(block, "plugin.enabled")).ok()
+    );
+    assert!(block.get("plugin.enabled").unwrap().access().mutable);
+    assert_eq!(6, block.len());
+}
+
+#[test]
+fn load_toml() {
+    let mut block = block();
+    let loaded = block
+        .load_toml(
+            r#"
+"uavcan.node.description" = "gateway"
+
+[uavcan.node]
+id = 42
+
+[motor]
+gains = [0.5, 1, 2.0]
+
+[plugin]
+count = 3
+rate = 2.5
+enabled = [true, false]
+"#,
+        )
+        .unwrap();
+    assert_eq!(6, loaded);
+    check_loaded(&block);
+}
+
+#[test]
+fn load_yaml() {
+    let mut block = block();
+    block
+        .load_yaml(
+            r#"
+uavcan:
+  node:
+    id: 42
+    description: gateway
+motor.gains: [0.5, 1, 2.0]
+plugin:
+  count: 3
+  rate: 2.5
+  enabled: [true, false]
+"#,
+        )
+        .unwrap();
+    check_loaded(&block);
+}
+
+#[test]
+fn load_json() {
+    let mut block = block();
+    block
+        .load_json(
+            r#"{
+    "uavcan.node.id": 42,
+    "uavcan.node.description": "gateway",
+    "motor": { "gains": [0.5, 1, 2.0] },
+    "plugin": { "count": 3, "rate": 2.5, "enabled": [true, false] }
+}"#,
+        )
+        .unwrap();
+    check_loaded(&block);
+}
+
+#[test]
+fn load_errors() {
+    let mut block = block();
+    assert!(matches!(
+        block.load_json("[1, 2]"),
+        Err(ConfigError::Parse(_))
+    ));
+    assert!(matches!(
+        block.load_toml("not toml"),
+        Err(ConfigError::Parse(_))
+    ));
+    // Out of range for natural8
+    assert!(matches!(
+        block.load_json(r#"{ "uavcan.node.id": 256 }"#),
+        Err(ConfigError::Value { name, kind: ValueErrorKind::Range }) if name == "uavcan.node.id"
+    ));
+    assert!(matches!(
+        block.load_json(r#"{ "uavcan.node.id": "one" }"#),
+        Err(ConfigError::Value {
+            kind: ValueErrorKind::Type,
+            ..
+        })
+    ));
+    // The type of an empty array can't be determined
+    assert!(matches!(
+        block.load_json(r#"{ "new": [] }"#),
+        Err(ConfigError::Value {
+            kind: ValueErrorKind::Type,
+            ..
+        })
+    ));
+    assert_eq!(Some(0u8), value_as(value(&block, "uavcan.node.id")).ok());
+}
@@ -0,0 +1,156 @@
+//! Tests of the dynamic register block, including access from another node through a simulated CAN
+//! bus
+
+extern crate canadensis;
+extern crate canadensis_can;
+extern crate canadensis_data_types;
+extern crate heapless;
+
+mod common;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use canadensis::core::time::SimulatedClock;
+use canadensis::register::client::{value_as, RegisterClient, RegisterEvent};
+use canadensis::register::dynamic::{AddError, DynamicRegisterBlock};
+use canadensis::register::{Access, Register, RegisterBlock, RegisterHandler};
+use canadensis::Node;
+use canadensis_data_types::uavcan::primitive::array::natural16_1_0::Natural16;
+use canadensis_data_types::uavcan::primitive::array::real32_1_0::Real32;
+use canadensis_data_types::uavcan::primitive::empty_1_0::Empty;
+use canadensis_data_types::uavcan::register::value_1_0::Value;
+
+use common::{node_id, node_pair};
+
+fn natural16(values: &[u16]) -> Value {
+    Value::Natural16(Natural16 {
+        value: heapless::Vec::from_slice(values).unwrap(),
+    })
+}
+
+fn real32(value: f32) -> Value {
+    Value::Real32(Real32 {
+        value: heapless::Vec::from_slice(&[value]).unwrap(),
+    })
+}
+
+const MUTABLE: Access = Access {
+    mutable: true,
+    persistent: false,
+};
+
+fn names(block: &DynamicRegisterBlock) -> Vec<&str> {
+    (0..)
+        .map_while(|index| block.register_by_index(index))
+        .map(|register| register.name())
+        .collect()
+}
+
+#[test]
+fn add_and_remove() {
+    let mut block = DynamicRegisterBlock::new();
+    assert!(block.is_empty());
+    block.add("b", natural16(&[1]), MUTABLE).unwrap();
+    block.add("c", natural16(&[2]), MUTABLE).unwrap();
+    block.add(String::from("a"), real32(0.5), MUTABLE).unwrap();
+    // Sorted by name, regardless of the order of adding
+    assert_eq!(vec!["a", "b", "c"], names(&block));
+    assert_eq!(3, block.len());
+
+    assert_eq!(
+        Some(AddError::Duplicate),
+        block.add("b", natural16(&[3]), MUTABLE).err()
+    );
+    assert_eq!(
+        Some(AddError::InvalidName),
+        block.add("", natural16(&[3]), MUTABLE).err()
+    );
+    assert_eq!(
+        Some(AddError::Empty),
+        block.add("d", Value::Empty(Empty {}), MUTABLE).err()
+    );
+
+    let removed = block.remove("b").unwrap();
+    assert_eq!("b", removed.name());
+    assert!(block.remove("b").is_none());
+    assert!(!block.contains("b"));
+    assert_eq!(vec!["a", "c"], names(&block));
+    assert_eq!("c", block.register_by_name_mut("c").unwrap().name());
+    assert!(block.register_by_name_mut("b").is_none());
+}
+
+#[test]
+fn validator_and_callback() {
+    let changes = Rc::new(RefCell::new(Vec::new()));
+    let mut block = DynamicRegisterBlock::new();
+    let callback_changes = Rc::clone(&changes);
+    block
+        .add("limited", natural16(&[1]), MUTABLE)
+        .unwrap()
+        .set_validator(|value| matches!(value, Value::Natural16(values) if values.value.iter().all(|&v| v < 10)))
+        .set_callback(move |name, value| {
+            callback_changes
+                .borrow_mut()
+                .push((name.to_owned(), value_as::<u16>(value).ok()))
+        });
+
+    let register = block.register_by_name_mut("limited").unwrap();
+    // Wrong type
+    assert!(register.write(&real32(1.0)).is_err());
+    // Rejected by the validator
+    assert!(register.write(&natural16(&[20])).is_err());
+    assert!(register.write(&natural16(&[5])).is_ok());
+    // A different length is allowed
+    assert!(register.write(&natural16(&[6, 7])).is_ok());
+    assert_eq!(
+        vec![
+            ("limited".to_owned(), Some(5)),
+            ("limited".to_owned(), None)
+        ],
+        *changes.borrow()
+    );
+    assert!(matches!(
+        block.get("limited").unwrap().value(),
+        Value::Natural16(values) if values.value[..] == [6, 7]
+    ));
+}
+
+#[test]
+fn remote_access() {
+    let (mut client_node, mut server_node) = node_pair(&SimulatedClock::new());
+    let mut client = RegisterClient::new(&mut client_node).unwrap();
+    RegisterHandler::<DynamicRegisterBlock>::subscribe_requests(&mut server_node).unwrap();
+
+    let mut block = DynamicRegisterBlock::new();
+    block.add("gain", real32(1.0), MUTABLE).unwrap();
+    block.add("id", natural16(&[3]), MUTABLE).unwrap();
+    let mut handler = RegisterHandler::new(block);
+
+    client.read_all(node_id(2));
+    client.write(node_id(2), "gain", real32(2.5));
+    let mut events = Vec::new();
+    for _ in 0..16 {
+        client.poll(&mut client_node);
+        client_node.flush().unwrap();
+        for _ in 0..64 {
+            server_node.receive(&mut handler).unwrap();
+        }
+        server_node.flush().unwrap();
+        for _ in 0..64 {
+            client_node.receive(&mut client).unwrap();
+        }
+        events.extend(std::iter::from_fn(|| client.pop_event()));
+    }
+    assert!(client.is_idle());
+    assert_eq!(4, events.len());
+    assert!(matches!(&events[0], RegisterEvent::Listed { names, .. } if *names == ["gain", "id"]));
+    assert!(
+        matches!(&events[2], RegisterEvent::Read { name, value, .. } if name == "id" && value_as::<u16>(value).ok() == Some(3))
+    );
+    assert!(matches!(&events[3], RegisterEvent::Written { name, .. } if name == "gain"));
+    assert_eq!(
+        Some(2.5),
+        value_as::<f32>(handler.block().get("gain").unwrap().value()).ok()
+    );
+}
@@ -0,0 +1,254 @@
+//! Tests of the ExecuteCommand handler, using two nodes connected by a simulated CAN bus
+
+extern crate canadensis;
+extern crate canadensis_can;
+extern crate canadensis_data_types;
+extern crate heapless;
+
+mod common;
+
+use std::convert::TryFrom;
+
+use canadensis::client::ServiceClient;
+use canadensis::command::{CommandHandler, CommandStatus, Commands};
+use canadensis::core::time::{milliseconds, Microseconds64, SimulatedClock};
+use canadensis::core::transfer::{MessageTransfer, ServiceTransfer};
+use canadensis::core::Priority;
+use canadensis::encoding::Deserialize;
+use canadensis::node::BasicNode;
+use canadensis::{Node, TransferHandler};
+use canadensis_can::{CanNodeId, CanTransport};
+use canadensis_data_types::uavcan::node::execute_command_1_1::{
+    self, ExecuteCommandRequest, ExecuteCommandResponse,
+};
+use canadensis_data_types::uavcan::node::get_info_1_0::GetInfoResponse;
+use canadensis_data_types::uavcan::node::heartbeat_1_0::{self, Heartbeat};
+use canadensis_data_types::uavcan::node::mode_1_0::Mode;
+use canadensis_data_types::uavcan::node::version_1_0::Version;
+
+use common::{node_pair, TestNode};
+
+type TestClient =
+    ServiceClient<Microseconds64, CanTransport, ExecuteCommandRequest, ExecuteCommandResponse, 1>;
+
+/// Records the commands that were executed
+#[derive(Default)]
+struct TestCommands {
+    restarts: u32,
+    software_update: Option<(CanNodeId, Vec<u8>)>,
+    other: Vec<(u16, Vec<u8>)>,
+}
+
+impl Commands<CanTransport> for TestCommands {
+    fn restart(&mut self) -> CommandStatus {
+        self.restarts += 1;
+        CommandStatus::Success
+    }
+
+    fn begin_software_update(&mut self, server: CanNodeId, path: &[u8]) -> CommandStatus {
+        self.software_update = Some((server, path.to_vec()));
+        CommandStatus::Success
+    }
+
+    fn other(&mut self, command: u16, parameter: &[u8]) -> CommandStatus {
+        self.other.push((command, parameter.to_vec()));
+        CommandStatus::Failure
+    }
+}
+
+/// Passes responses to a client and collects the statuses and heartbeat modes
+struct ClientHandler<'c> {
+    client: &'c mut TestClient,
+    statuses: Vec<u8>,
+    modes: Vec<u8>,
+}
+
+impl TransferHandler<Microseconds64, CanTransport> for ClientHandler<'_> {
+    fn handle_message<N: Node<Instant = Microseconds64, Transport = CanTransport>>(
+        &mut self,
+        _node: &mut N,
+        transfer: &MessageTransfer<Vec<u8>, Microseconds64, CanTransport>,
+    ) -> bool {
+        if transfer.header.subject != heartbeat_1_0::SUBJECT {
+            return false;
+        }
+        let heartbeat = Heartbeat::deserialize_from_bytes(&transfer.payload).unwrap();
+        self.modes.push(heartbeat.mode.value);
+        true
+    }
+
+    fn handle_response<N: Node<Instant = Microseconds64, Transport = CanTransport>>(
+        &mut self,
+        _node: &mut N,
+        transfer: &ServiceTransfer<Vec<u8>, Microseconds64, CanTransport>,
+    ) -> bool {
+        match self.client.handle_response(transfer) {
+            Some(result) => {
+                self.statuses.push(result.unwrap().payload.status);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+struct Setup {
+    client_node: TestNode,
+    client: TestClient,
+    server_node: BasicNode<TestNode>,
+    handler: CommandHandler<TestCommands>,
+}
+
+impl Setup {
+    fn new() -> Self {
+        let (mut client_node, server_node) = node_pair(&SimulatedClock::new());
+        let node_info = GetInfoResponse {
+            protocol_version: Version { major: 1, minor: 0 },
+            hardware_version: Version { major: 0, minor: 0 },
+            software_version: Version { major: 0, minor: 1 },
+            software_vcs_revision_id: 0,
+            unique_id: [0; 16],
+            name: heapless::Vec::from_slice(b"org.samcrow.execute_command_test").unwrap(),
+            software_image_crc: heapless::Vec::new(),
+            certificate_of_authenticity: Default::default(),
+        };
+        let mut server_node = BasicNode::new(server_node, node_info).unwrap();
+        let token = client_node
+            .start_sending_requests(
+                execute_command_1_1::SERVICE,
+                milliseconds(1000),
+                48,
+                Priority::Nominal,
+            )
+            .unwrap();
+        client_node
+            .subscribe::<Heartbeat>(heartbeat_1_0::SUBJECT, milliseconds(1000))
+            .unwrap();
+        CommandHandler::<TestCommands>::subscribe_requests(&mut server_node).unwrap();
+        Setup {
+            client_node,
+            client: ServiceClient::new(token, milliseconds(500)),
+            server_node,
+            handler: CommandHandler::new(TestCommands::default()),
+        }
+    }
+
+    /// Sends a command and returns the response status
+    fn execute(&mut self, command: u16, parameter: &[u8]) -> u8 {
+        let request = ExecuteCommandRequest {
+            command,
+            parameter: heapless::Vec::from_slice(parameter).unwrap(),
+        };
+        self.client
+            .send(
+                &mut self.client_node,
+                &request,
+                CanNodeId::try_from(2u8).unwrap(),
+            )
+            .unwrap();
+        self.client_node.flush().unwrap();
+        for _ in 0..64 {
+            self.server_node.receive(&mut self.handler).unwrap();
+        }
+        self.server_node.flush().unwrap();
+        let client_handler = self.receive_on_client();
+        assert_eq!(1, client_handler.statuses.len());
+        client_handler.statuses[0]
+    }
+
+    /// Sends a heartbeat from the server and returns the mode that it reports
+    fn heartbeat_mode(&mut self) -> u8 {
+        self.handler.set_heartbeat_mode(
+            &mut self.server_node,
+            Mode {
+                value: Mode::OPERATIONAL,
+            },
+        );
+        self.server_node.run_per_second_tasks().unwrap();
+        self.server_node.flush().unwrap();
+        let client_handler = self.receive_on_client();
+        assert_eq!(1, client_handler.modes.len());
+        client_handler.modes[0]
+    }
+
+    fn receive_on_client(&mut self) -> ClientHandler<'_> {
+        let mut client_handler = ClientHandler {
+            client: &mut self.client,
+            statuses: Vec::new(),
+            modes: Vec::new(),
+        };
+        for _ in 0..8 {
+            self.client_node.receive(&mut client_handler).unwrap();
+        }
+        client_handler
+    }
+}
+
+#[test]
+fn standard_and_other_commands() {
+    let mut setup = Setup::new();
+    assert_eq!(
+        ExecuteCommandResponse::STATUS_SUCCESS,
+        setup.execute(ExecuteCommandRequest::COMMAND_RESTART, &[])
+    );
+    assert_eq!(1, setup.handler.commands().restarts);
+    // Not implemented by TestCommands
+    assert_eq!(
+        ExecuteCommandResponse::STATUS_BAD_COMMAND,
+        setup.execute(ExecuteCommandRequest::COMMAND_FACTORY_RESET, &[])
+    );
+    assert_eq!(
+        ExecuteCommandResponse::STATUS_FAILURE,
+        setup.execute(1000, b"vendor")
+    );
+    assert_eq!(
+        vec![(1000, b"vendor".to_vec())],
+        setup.handler.commands().other
+    );
+}
+
+#[test]
+fn software_update() {
+    let mut setup = Setup::new();
+    assert!(setup.handler.mode().is_none());
+    assert_eq!(Mode::OPERATIONAL, setup.heartbeat_mode());
+    assert_eq!(
+        ExecuteCommandResponse::STATUS_BAD_PARAMETER,
+        setup.execute(ExecuteCommandRequest::COMMAND_BEGIN_SOFTWARE_UPDATE, &[])
+    );
+    assert_eq!(
+        ExecuteCommandResponse::STATUS_SUCCESS,
+        setup.execute(
+            ExecuteCommandRequest::COMMAND_BEGIN_SOFTWARE_UPDATE,
+            b"images/new.bin"
+        )
+    );
+    assert_eq!(
+        Some((
+            CanNodeId::try_from(1u8).unwrap(),
+            b"images/new.bin".to_vec()
+        )),
+        setup.handler.commands_mut().software_update.take()
+    );
+    assert!(setup.handler.software_update_in_progress());
+    assert!(matches!(
+        setup.handler.mode(),
+        Some(Mode {
+            value: Mode::SOFTWARE_UPDATE
+        })
+    ));
+    assert_eq!(Mode::SOFTWARE_UPDATE, setup.heartbeat_mode());
+    // Only one update can run at a time
+    assert_eq!(
+        ExecuteCommandResponse::STATUS_BAD_STATE,
+        setup.execute(
+            ExecuteCommandRequest::COMMAND_BEGIN_SOFTWARE_UPDATE,
+            b"images/other.bin"
+        )
+    );
+    assert!(setup.handler.commands().software_update.is_none());
+
+    setup.handler.finish_software_update();
+    assert!(setup.handler.mode().is_none());
+    assert_eq!(Mode::OPERATIONAL, setup.heartbeat_mode());
+}
@@ -0,0 +1,68 @@
+//! Tests of transmission time reports for loopback transfers
+
+extern crate canadensis;
+extern crate canadensis_can;
+extern crate canadensis_data_types;
+
+mod common;
+
+use std::convert::TryFrom;
+
+use canadensis::core::time::{milliseconds, Microseconds64, SimulatedClock};
+use canadensis::core::transfer::{Header, MessageHeader};
+use canadensis::core::{Priority, SubjectId};
+use canadensis::{Node, TransferHandler};
+use canadensis_can::{CanTransferId, CanTransport};
+use canadensis_data_types::uavcan::primitive::empty_1_0::Empty;
+
+use common::{make_bus, make_node, node_id};
+
+/// Records the headers of loopback transfers
+#[derive(Default)]
+struct LoopbackRecorder {
+    headers: Vec<Header<Microseconds64, CanTransport>>,
+}
+
+impl TransferHandler<Microseconds64, CanTransport> for LoopbackRecorder {
+    fn handle_loopback<N: Node<Instant = Microseconds64, Transport = CanTransport>>(
+        &mut self,
+        _node: &mut N,
+        header: &Header<Microseconds64, CanTransport>,
+    ) -> bool {
+        self.headers.push(header.clone());
+        true
+    }
+}
+
+#[test]
+fn loopback_reports_transmission_time() {
+    let clock = SimulatedClock::new();
+    let bus = make_bus();
+    let mut node = make_node(&clock, 5, bus.add_driver());
+    let subject = SubjectId::try_from(100u16).unwrap();
+    let token = node
+        .start_publishing::<Empty>(subject, milliseconds(1000), Priority::High)
+        .unwrap();
+
+    clock.set(Microseconds64::new(2_000));
+    node.publish(&token, &Empty {}).unwrap();
+    clock.set(Microseconds64::new(3_000));
+    node.publish_loopback(&token, &Empty {}).unwrap();
+    clock.set(Microseconds64::new(4_000));
+
+    let mut recorder = LoopbackRecorder::default();
+    node.receive(&mut recorder).unwrap();
+    // Only the second transfer was marked for loopback
+    assert_eq!(
+        vec![Header::Message(MessageHeader {
+            timestamp: Microseconds64::new(3_000),
+            transfer_id: CanTransferId::try_from(1u8).unwrap(),
+            priority: Priority::High,
+            subject,
+            source: Some(node_id(5)),
+        })],
+        recorder.headers
+    );
+    node.receive(&mut recorder).unwrap();
+    assert_eq!(1, recorder.headers.len());
+}
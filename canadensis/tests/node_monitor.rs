@@ -0,0 +1,224 @@
+//! Tests of the node monitor, using two nodes connected by a simulated CAN bus
+
+extern crate canadensis;
+extern crate canadensis_can;
+extern crate canadensis_data_types;
+extern crate heapless;
+
+mod common;
+
+use std::convert::TryFrom;
+
+use canadensis::core::time::{milliseconds, Microseconds64, SimulatedClock};
+use canadensis::core::transfer::ServiceTransfer;
+use canadensis::core::Priority;
+use canadensis::monitor::{MonitorEvent, NodeMonitor};
+use canadensis::{Node, PublishToken, ResponseToken, TransferHandler};
+use canadensis_can::{CanNodeId, CanTransport};
+use canadensis_data_types::uavcan::node::get_info_1_0::{self, GetInfoResponse};
+use canadensis_data_types::uavcan::node::health_1_0::Health;
+use canadensis_data_types::uavcan::node::heartbeat_1_0::{self, Heartbeat};
+use canadensis_data_types::uavcan::node::mode_1_0::Mode;
+use canadensis_data_types::uavcan::node::version_1_0::Version;
+
+use common::{node_pair, TestNode};
+
+/// Responds to node information requests, if enabled
+struct InfoResponder {
+    enabled: bool,
+    info: GetInfoResponse,
+}
+
+impl TransferHandler<Microseconds64, CanTransport> for InfoResponder {
+    fn handle_request<N: Node<Instant = Microseconds64, Transport = CanTransport>>(
+        &mut self,
+        node: &mut N,
+        token: ResponseToken<CanTransport>,
+        transfer: &ServiceTransfer<Vec<u8>, Microseconds64, CanTransport>,
+    ) -> bool {
+        if self.enabled && transfer.header.service == get_info_1_0::SERVICE {
+            node.send_response(token, milliseconds(1000), &self.info)
+                .unwrap();
+            true
+        } else {
+            false
+        }
+    }
+}
+
+struct Setup {
+    clock: SimulatedClock,
+    monitor_node: TestNode,
+    monitor: NodeMonitor<Microseconds64, CanTransport, 4, 8>,
+    device_node: TestNode,
+    heartbeat_token: PublishToken<Heartbeat>,
+    responder: InfoResponder,
+}
+
+impl Setup {
+    fn new(query_info: bool) -> Self {
+        let clock = SimulatedClock::new();
+        let (mut monitor_node, mut device_node) = node_pair(&clock);
+        let monitor = NodeMonitor::new(&mut monitor_node, query_info).unwrap();
+        let heartbeat_token = device_node
+            .start_publishing(
+                heartbeat_1_0::SUBJECT,
+                milliseconds(1000),
+                Priority::Nominal,
+            )
+            .unwrap();
+        device_node
+            .subscribe_request(get_info_1_0::SERVICE, 0, milliseconds(1000))
+            .unwrap();
+        Setup {
+            clock,
+            monitor_node,
+            monitor,
+            device_node,
+            heartbeat_token,
+            responder: InfoResponder {
+                enabled: true,
+                info: GetInfoResponse {
+                    protocol_version: Version { major: 1, minor: 0 },
+                    hardware_version: Version { major: 0, minor: 0 },
+                    software_version: Version { major: 2, minor: 3 },
+                    software_vcs_revision_id: 0,
+                    unique_id: [7; 16],
+                    name: heapless::Vec::from_slice(b"org.samcrow.device").unwrap(),
+                    software_image_crc: heapless::Vec::new(),
+                    certificate_of_authenticity: Default::default(),
+                },
+            },
+        }
+    }
+
+    fn send_heartbeat(&mut self, uptime: u32, health: u8) {
+        let heartbeat = Heartbeat {
+            uptime,
+            health: Health { value: health },
+            mode: Mode {
+                value: Mode::OPERATIONAL,
+            },
+            vendor_specific_status_code: 42,
+        };
+        self.device_node
+            .publish(&self.heartbeat_token, &heartbeat)
+            .unwrap();
+    }
+
+    /// Exchanges transfers between the nodes and returns the monitor events
+    fn run(&mut self) -> Vec<MonitorEvent<CanNodeId>> {
+        for _ in 0..4 {
+            self.device_node.flush().unwrap();
+            for _ in 0..64 {
+                self.monitor_node.receive(&mut self.monitor).unwrap();
+            }
+            self.monitor.poll(&mut self.monitor_node);
+            self.monitor_node.flush().unwrap();
+            for _ in 0..64 {
+                self.device_node.receive(&mut self.responder).unwrap();
+            }
+        }
+        std::iter::from_fn(|| self.monitor.pop_event()).collect()
+    }
+}
+
+fn device_id() -> CanNodeId {
+    CanNodeId::try_from(2u8).unwrap()
+}
+
+#[test]
+fn online_restart_offline() {
+    let mut setup = Setup::new(false);
+    assert!(setup.run().is_empty());
+    setup.send_heartbeat(10, Health::NOMINAL);
+    assert_eq!(vec![MonitorEvent::NodeOnline(device_id())], setup.run());
+    let status = setup.monitor.node(&device_id()).unwrap();
+    assert_eq!(10, status.uptime());
+    assert_eq!(Health::NOMINAL, status.health().value);
+    assert_eq!(Mode::OPERATIONAL, status.mode().value);
+    assert_eq!(42, status.vendor_specific_status_code());
+    assert!(status.info().is_none());
+
+    setup.clock.advance(milliseconds(1000));
+    setup.send_heartbeat(11, Health::CAUTION);
+    assert!(setup.run().is_empty());
+    let status = setup.monitor.node(&device_id()).unwrap();
+    assert_eq!(Health::CAUTION, status.health().value);
+    assert_eq!(Microseconds64::new(1_000_000), status.last_seen());
+
+    setup.clock.advance(milliseconds(1000));
+    setup.send_heartbeat(0, Health::NOMINAL);
+    assert_eq!(vec![MonitorEvent::NodeRestarted(device_id())], setup.run());
+
+    // Still online at the offline timeout
+    setup.clock.advance(milliseconds(3000));
+    assert!(setup.run().is_empty());
+    setup.clock.advance(milliseconds(1));
+    assert_eq!(vec![MonitorEvent::NodeOffline(device_id())], setup.run());
+    assert!(setup.monitor.node(&device_id()).is_none());
+    assert_eq!(0, setup.monitor.nodes().count());
+
+    setup.send_heartbeat(5, Health::NOMINAL);
+    assert_eq!(vec![MonitorEvent::NodeOnline(device_id())], setup.run());
+}
+
+#[test]
+fn node_info() {
+    let mut setup = Setup::new(true);
+    setup.send_heartbeat(10, Health::NOMINAL);
+    assert_eq!(
+        vec![
+            MonitorEvent::NodeOnline(device_id()),
+            MonitorEvent::InfoUpdated(device_id())
+        ],
+        setup.run()
+    );
+    let info = setup.monitor.node(&device_id()).unwrap().info().unwrap();
+    assert_eq!(&b"org.samcrow.device"[..], &info.name[..]);
+    assert_eq!(2, info.software_version.major);
+
+    // After a restart, the monitor requests the information again
+    setup.responder.info.software_version.major = 3;
+    setup.clock.advance(milliseconds(1000));
+    setup.send_heartbeat(0, Health::NOMINAL);
+    assert_eq!(
+        vec![
+            MonitorEvent::NodeRestarted(device_id()),
+            MonitorEvent::InfoUpdated(device_id())
+        ],
+        setup.run()
+    );
+    let info = setup.monitor.node(&device_id()).unwrap().info().unwrap();
+    assert_eq!(3, info.software_version.major);
+}
+
+#[test]
+fn node_info_retries() {
+    let mut setup = Setup::new(true);
+    setup.responder.enabled = false;
+    setup.send_heartbeat(10, Health::NOMINAL);
+    assert_eq!(vec![MonitorEvent::NodeOnline(device_id())], setup.run());
+    // The first request times out, and the second one is also not answered
+    setup.clock.advance(milliseconds(1001));
+    setup.send_heartbeat(11, Health::NOMINAL);
+    assert!(setup.run().is_empty());
+    // The third request gets a response
+    setup.responder.enabled = true;
+    setup.clock.advance(milliseconds(1001));
+    setup.send_heartbeat(12, Health::NOMINAL);
+    assert_eq!(vec![MonitorEvent::InfoUpdated(device_id())], setup.run());
+
+    // After three failed attempts, the monitor gives up
+    let mut setup = Setup::new(true);
+    setup.responder.enabled = false;
+    for uptime in 10..16 {
+        setup.send_heartbeat(uptime, Health::NOMINAL);
+        setup.run();
+        setup.clock.advance(milliseconds(1001));
+    }
+    setup.responder.enabled = true;
+    setup.send_heartbeat(16, Health::NOMINAL);
+    assert!(setup.run().is_empty());
+    assert!(setup.monitor.node(&device_id()).unwrap().info().is_none());
+}
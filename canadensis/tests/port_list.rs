@@ -0,0 +1,214 @@
+//! Tests of the port list that BasicNode maintains, using two nodes connected by a simulated CAN
+//! bus
+
+extern crate canadensis;
+extern crate canadensis_can;
+extern crate canadensis_data_types;
+extern crate heapless;
+
+mod common;
+
+use std::convert::TryFrom;
+
+use canadensis::core::time::{milliseconds, Microseconds64, SimulatedClock};
+use canadensis::core::transfer::MessageTransfer;
+use canadensis::core::{Priority, ServiceId, SubjectId};
+use canadensis::encoding::Deserialize;
+use canadensis::node::BasicNode;
+use canadensis::{Node, TransferHandler};
+use canadensis_can::CanTransport;
+use canadensis_data_types::uavcan::node::get_info_1_0::GetInfoResponse;
+use canadensis_data_types::uavcan::node::heartbeat_1_0;
+use canadensis_data_types::uavcan::node::port::list_0_1::{self, List};
+use canadensis_data_types::uavcan::node::port::subject_id_list_0_1::SubjectIDList;
+use canadensis_data_types::uavcan::node::version_1_0::Version;
+use canadensis_data_types::uavcan::primitive::empty_1_0::Empty;
+use canadensis_data_types::uavcan::register::access_1_0::{self, AccessRequest};
+
+use common::{node_pair, TestNode};
+
+/// Collects received port lists
+#[derive(Default)]
+struct ListCollector {
+    lists: Vec<List>,
+}
+
+impl TransferHandler<Microseconds64, CanTransport> for ListCollector {
+    fn handle_message<N: Node<Instant = Microseconds64, Transport = CanTransport>>(
+        &mut self,
+        _node: &mut N,
+        transfer: &MessageTransfer<Vec<u8>, Microseconds64, CanTransport>,
+    ) -> bool {
+        if transfer.header.subject == list_0_1::SUBJECT {
+            self.lists
+                .push(List::deserialize_from_bytes(&transfer.payload).unwrap());
+            true
+        } else {
+            false
+        }
+    }
+}
+
+struct Setup {
+    node: BasicNode<TestNode>,
+    monitor_node: TestNode,
+    collector: ListCollector,
+}
+
+impl Setup {
+    fn new() -> Self {
+        let (node, mut monitor_node) = node_pair(&SimulatedClock::new());
+        let node_info = GetInfoResponse {
+            protocol_version: Version { major: 1, minor: 0 },
+            hardware_version: Version { major: 0, minor: 0 },
+            software_version: Version { major: 0, minor: 1 },
+            software_vcs_revision_id: 0,
+            unique_id: [0; 16],
+            name: heapless::Vec::from_slice(b"org.samcrow.port_list_test").unwrap(),
+            software_image_crc: heapless::Vec::new(),
+            certificate_of_authenticity: Default::default(),
+        };
+        let node = BasicNode::new(node, node_info).unwrap();
+        monitor_node
+            .subscribe::<List>(list_0_1::SUBJECT, milliseconds(1000))
+            .unwrap();
+        Setup {
+            node,
+            monitor_node,
+            collector: ListCollector::default(),
+        }
+    }
+
+    /// Runs the per-second tasks and returns the port lists that the other node received
+    fn tick(&mut self) -> Vec<List> {
+        self.node.run_per_second_tasks().unwrap();
+        self.node.flush().unwrap();
+        for _ in 0..1024 {
+            self.monitor_node.receive(&mut self.collector).unwrap();
+        }
+        std::mem::take(&mut self.collector.lists)
+    }
+}
+
+fn sparse_list(list: &SubjectIDList) -> Vec<u16> {
+    match list {
+        SubjectIDList::SparseList(list) => {
+            let mut subjects: Vec<u16> = list.iter().map(|subject| subject.value).collect();
+            subjects.sort_unstable();
+            subjects
+        }
+        _ => panic!("Not a sparse list"),
+    }
+}
+
+fn subject(id: u16) -> SubjectId {
+    SubjectId::try_from(id).unwrap()
+}
+
+#[test]
+fn published_on_change() {
+    let mut setup = Setup::new();
+    // The initial list is published right away
+    let lists = setup.tick();
+    assert_eq!(1, lists.len());
+    assert_eq!(
+        vec![
+            u16::from(heartbeat_1_0::SUBJECT),
+            u16::from(list_0_1::SUBJECT)
+        ],
+        sparse_list(&lists[0].publishers)
+    );
+    assert!(sparse_list(&lists[0].subscribers).is_empty());
+    assert!(setup.tick().is_empty());
+
+    let token = setup
+        .node
+        .start_publishing::<Empty>(subject(100), milliseconds(1000), Priority::Nominal)
+        .unwrap();
+    setup
+        .node
+        .subscribe::<Empty>(subject(200), milliseconds(1000))
+        .unwrap();
+    setup
+        .node
+        .start_sending_requests::<AccessRequest>(
+            access_1_0::SERVICE,
+            milliseconds(1000),
+            300,
+            Priority::Nominal,
+        )
+        .unwrap();
+    // All the changes are published together
+    let lists = setup.tick();
+    assert_eq!(1, lists.len());
+    assert_eq!(
+        vec![
+            100,
+            u16::from(heartbeat_1_0::SUBJECT),
+            u16::from(list_0_1::SUBJECT)
+        ],
+        sparse_list(&lists[0].publishers)
+    );
+    assert_eq!(vec![200], sparse_list(&lists[0].subscribers));
+    assert!(lists[0].clients.mask.get(access_1_0::SERVICE.into()));
+    assert!(!lists[0]
+        .clients
+        .mask
+        .get(ServiceId::try_from(0u16).unwrap().into()));
+
+    // Subscribing again does not change anything
+    setup
+        .node
+        .subscribe::<Empty>(subject(200), milliseconds(1000))
+        .unwrap();
+    assert!(setup.tick().is_empty());
+
+    setup.node.stop_publishing(token);
+    let lists = setup.tick();
+    assert_eq!(1, lists.len());
+    assert_eq!(
+        vec![
+            u16::from(heartbeat_1_0::SUBJECT),
+            u16::from(list_0_1::SUBJECT)
+        ],
+        sparse_list(&lists[0].publishers)
+    );
+
+    // Without changes, the list is published every 10 seconds
+    let published_count: usize = (0..20).map(|_| setup.tick().len()).sum();
+    assert_eq!(2, published_count);
+}
+
+#[test]
+fn switch_to_mask() {
+    let mut setup = Setup::new();
+    for id in 0..255 {
+        setup
+            .node
+            .subscribe::<Empty>(subject(id), milliseconds(1000))
+            .unwrap();
+    }
+    assert_eq!(255, sparse_list(&setup.node.port_list().subscribers).len());
+    // The sparse list can hold only 255 subject IDs
+    setup
+        .node
+        .subscribe::<Empty>(subject(8000), milliseconds(1000))
+        .unwrap();
+    match &setup.node.port_list().subscribers {
+        SubjectIDList::Mask(mask) => {
+            let subjects: Vec<usize> = mask
+                .iter()
+                .enumerate()
+                .filter(|&(_, in_list)| in_list)
+                .map(|(id, _)| id)
+                .collect();
+            assert_eq!(256, subjects.len());
+            assert_eq!(Some(&254), subjects.get(254));
+            assert_eq!(Some(&8000), subjects.last());
+        }
+        _ => panic!("Expected a mask"),
+    }
+    let lists = setup.tick();
+    assert_eq!(1, lists.len());
+    assert!(matches!(lists[0].subscribers, SubjectIDList::Mask(_)));
+}
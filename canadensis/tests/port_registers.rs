@@ -0,0 +1,264 @@
+//! Tests of the standard port ID registers, using two nodes connected by a simulated CAN bus
+
+extern crate canadensis;
+extern crate canadensis_can;
+extern crate canadensis_data_types;
+extern crate heapless;
+
+mod common;
+
+use std::convert::TryFrom;
+
+use canadensis::core::time::{milliseconds, Microseconds64, SimulatedClock};
+use canadensis::core::transfer::MessageTransfer;
+use canadensis::core::{Priority, SubjectId};
+use canadensis::encoding::Deserialize;
+use canadensis::node::BasicNode;
+use canadensis::register::port::{PortHandle, PortRegisters, UNSET_PORT_ID};
+use canadensis::register::{RegisterBlock, RegisterHandler};
+use canadensis::{Node, ServiceToken, TransferHandler};
+use canadensis_can::CanTransport;
+use canadensis_data_types::uavcan::node::get_info_1_0::GetInfoResponse;
+use canadensis_data_types::uavcan::node::port::subject_id_list_0_1::SubjectIDList;
+use canadensis_data_types::uavcan::node::version_1_0::Version;
+use canadensis_data_types::uavcan::primitive::array::natural16_1_0;
+use canadensis_data_types::uavcan::primitive::scalar::natural16_1_0::Natural16;
+use canadensis_data_types::uavcan::register::access_1_0::{self, AccessRequest};
+use canadensis_data_types::uavcan::register::name_1_0::Name;
+use canadensis_data_types::uavcan::register::value_1_0::Value;
+
+use common::{node_id, node_pair, TestNode};
+
+const TYPE_NAME: &str = "uavcan.primitive.scalar.Natural16.1.0";
+
+/// Collects received messages and their subject IDs
+#[derive(Default)]
+struct MessageCollector {
+    messages: Vec<(u16, u16)>,
+}
+
+impl TransferHandler<Microseconds64, CanTransport> for MessageCollector {
+    fn handle_message<N: Node<Instant = Microseconds64, Transport = CanTransport>>(
+        &mut self,
+        _node: &mut N,
+        transfer: &MessageTransfer<Vec<u8>, Microseconds64, CanTransport>,
+    ) -> bool {
+        let message = Natural16::deserialize_from_bytes(&transfer.payload).unwrap();
+        self.messages
+            .push((u16::from(transfer.header.subject), message.value));
+        true
+    }
+}
+
+struct Setup {
+    node: BasicNode<TestNode>,
+    handler: RegisterHandler<PortRegisters>,
+    publisher: PortHandle<Natural16>,
+    subscriber: PortHandle<Natural16>,
+    node_collector: MessageCollector,
+    other_node: TestNode,
+    other_collector: MessageCollector,
+    access_token: ServiceToken<AccessRequest>,
+}
+
+impl Setup {
+    fn new() -> Self {
+        let (node, mut other_node) = node_pair(&SimulatedClock::new());
+        let node_info = GetInfoResponse {
+            protocol_version: Version { major: 1, minor: 0 },
+            hardware_version: Version { major: 0, minor: 0 },
+            software_version: Version { major: 0, minor: 1 },
+            software_vcs_revision_id: 0,
+            unique_id: [0; 16],
+            name: heapless::Vec::from_slice(b"org.samcrow.port_registers_test").unwrap(),
+            software_image_crc: heapless::Vec::new(),
+            certificate_of_authenticity: Default::default(),
+        };
+        let mut node = BasicNode::new(node, node_info).unwrap();
+        RegisterHandler::<PortRegisters>::subscribe_requests(&mut node).unwrap();
+
+        let mut ports = PortRegisters::new();
+        let publisher = ports.add_publisher::<Natural16>(
+            "counter",
+            TYPE_NAME,
+            Some(subject(100)),
+            Priority::Nominal,
+        );
+        let subscriber = ports.add_subscriber::<Natural16>("setpoint", TYPE_NAME, None);
+        assert!(ports.apply(&mut node).unwrap());
+
+        for id in [100, 200, 300] {
+            other_node
+                .subscribe::<Natural16>(subject(id), milliseconds(1000))
+                .unwrap();
+        }
+        let access_token = other_node
+            .start_sending_requests(
+                access_1_0::SERVICE,
+                milliseconds(1000),
+                300,
+                Priority::Nominal,
+            )
+            .unwrap();
+        Setup {
+            node,
+            handler: RegisterHandler::new(ports),
+            publisher,
+            subscriber,
+            node_collector: MessageCollector::default(),
+            other_node,
+            other_collector: MessageCollector::default(),
+            access_token,
+        }
+    }
+
+    /// Exchanges transfers between the nodes
+    fn run(&mut self) {
+        for _ in 0..2 {
+            self.other_node.flush().unwrap();
+            let mut handler = (&mut self.handler).chain(&mut self.node_collector);
+            for _ in 0..64 {
+                self.node.receive(&mut handler).unwrap();
+            }
+            self.node.flush().unwrap();
+            for _ in 0..64 {
+                self.other_node.receive(&mut self.other_collector).unwrap();
+            }
+        }
+    }
+
+    /// Writes a port ID register from the other node
+    fn write_id(&mut self, name: &str, id: u16) {
+        let request = AccessRequest {
+            name: Name {
+                name: heapless::Vec::from_slice(name.as_bytes()).unwrap(),
+            },
+            value: natural16(id),
+        };
+        self.other_node
+            .send_request(&self.access_token, &request, node_id(1))
+            .unwrap();
+        self.run();
+    }
+
+    fn publish(&mut self, value: u16) {
+        let token = self.handler.block().publish_token(self.publisher).unwrap();
+        self.node.publish(&token, &Natural16 { value }).unwrap();
+        self.run();
+    }
+}
+
+fn subject(id: u16) -> SubjectId {
+    SubjectId::try_from(id).unwrap()
+}
+
+fn sparse_list(list: &SubjectIDList) -> Vec<u16> {
+    match list {
+        SubjectIDList::SparseList(list) => {
+            let mut subjects: Vec<u16> = list.iter().map(|subject| subject.value).collect();
+            subjects.sort_unstable();
+            subjects
+        }
+        _ => panic!("Not a sparse list"),
+    }
+}
+
+fn natural16(value: u16) -> Value {
+    Value::Natural16(natural16_1_0::Natural16 {
+        value: heapless::Vec::from_slice(&[value]).unwrap(),
+    })
+}
+
+#[test]
+fn registers() {
+    let mut ports = PortRegisters::new();
+    ports.add_publisher::<Natural16>("counter", TYPE_NAME, Some(subject(100)), Priority::Low);
+    ports.add_subscriber::<Natural16>("setpoint", TYPE_NAME, None);
+    let names: Vec<&str> = (0..)
+        .map_while(|index| ports.register_by_index(index))
+        .map(|register| register.name())
+        .collect();
+    assert_eq!(
+        vec![
+            "uavcan.pub.counter.id",
+            "uavcan.pub.counter.type",
+            "uavcan.sub.setpoint.id",
+            "uavcan.sub.setpoint.type"
+        ],
+        names
+    );
+
+    let type_register = ports.register_by_index(1).unwrap();
+    assert!(!type_register.access().mutable);
+    match type_register.read() {
+        Value::String(string) => assert_eq!(TYPE_NAME.as_bytes(), &string.value[..]),
+        _ => panic!("Type register is not a string"),
+    }
+
+    let id_register = ports
+        .register_by_name_mut("uavcan.sub.setpoint.id")
+        .unwrap();
+    let access = id_register.access();
+    assert!(access.mutable && access.persistent);
+    assert!(matches!(
+        id_register.read(),
+        Value::Natural16(id) if id.value[..] == [UNSET_PORT_ID]
+    ));
+    // Not a valid subject ID
+    assert!(id_register.write(&natural16(8192)).is_err());
+    assert!(id_register.write(&natural16(8191)).is_ok());
+    assert!(id_register.write(&natural16(UNSET_PORT_ID)).is_ok());
+}
+
+#[test]
+fn reconfigure() {
+    let mut setup = Setup::new();
+    assert_eq!(
+        Some(subject(100)),
+        setup.handler.block().subject_id(setup.publisher)
+    );
+    assert!(setup
+        .handler
+        .block()
+        .subscription_token(setup.subscriber)
+        .is_none());
+    setup.publish(1);
+    assert_eq!(vec![(100, 1)], setup.other_collector.messages);
+
+    setup.write_id("uavcan.pub.counter.id", 200);
+    setup.write_id("uavcan.sub.setpoint.id", 300);
+    assert!(setup.handler.take_persistent_modified());
+    // The changes take effect after they are applied
+    assert!(setup.handler.block_mut().apply(&mut setup.node).unwrap());
+    assert!(!setup.handler.block_mut().apply(&mut setup.node).unwrap());
+    let port_list = setup.node.port_list();
+    assert!(sparse_list(&port_list.publishers).contains(&200));
+    assert!(!sparse_list(&port_list.publishers).contains(&100));
+    assert_eq!(vec![300], sparse_list(&port_list.subscribers));
+
+    setup.other_collector.messages.clear();
+    setup.publish(2);
+    assert_eq!(vec![(200, 2)], setup.other_collector.messages);
+
+    let token = setup
+        .other_node
+        .start_publishing(subject(300), milliseconds(1000), Priority::Nominal)
+        .unwrap();
+    setup
+        .other_node
+        .publish(&token, &Natural16 { value: 3 })
+        .unwrap();
+    setup.run();
+    assert_eq!(vec![(300, 3)], setup.node_collector.messages);
+
+    // Disable the subscriber
+    setup.write_id("uavcan.sub.setpoint.id", UNSET_PORT_ID);
+    assert!(setup.handler.block_mut().apply(&mut setup.node).unwrap());
+    assert!(sparse_list(&setup.node.port_list().subscribers).is_empty());
+    setup
+        .other_node
+        .publish(&token, &Natural16 { value: 4 })
+        .unwrap();
+    setup.run();
+    assert_eq!(vec![(300, 3)], setup.node_collector.messages);
+}
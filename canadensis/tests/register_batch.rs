@@ -0,0 +1,312 @@
+//! Tests of register batches, using two nodes connected by a simulated CAN bus
+
+extern crate canadensis;
+extern crate canadensis_can;
+extern crate canadensis_data_types;
+extern crate heapless;
+
+mod common;
+
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+
+use canadensis::core::time::{Microseconds64, SimulatedClock};
+use canadensis::register::basic::{RegisterString, SimpleRegister, ValidatedRegister};
+use canadensis::register::batch::{
+    BatchClient, BatchError, BatchEvent, InvalidNodeId, RegisterBatch, ValueErrorKind,
+};
+use canadensis::register::client::RegisterError;
+use canadensis::register::{RegisterBlock, RegisterHandler};
+use canadensis::Node;
+use canadensis_can::{CanNodeId, CanTransport};
+use canadensis_data_types::uavcan::primitive::array::natural8_1_0::Natural8;
+use canadensis_data_types::uavcan::primitive::array::real32_1_0::Real32;
+use canadensis_data_types::uavcan::primitive::string_1_0;
+use canadensis_data_types::uavcan::register::value_1_0::Value;
+
+use common::{node_id, node_pair, TestNode};
+
+#[derive(RegisterBlock)]
+struct Registers {
+    node_id: SimpleRegister<u16>,
+    description: SimpleRegister<RegisterString>,
+    gains: SimpleRegister<[f32; 3]>,
+    read_only: SimpleRegister<u8>,
+    limited: ValidatedRegister<u8>,
+}
+
+impl Default for Registers {
+    fn default() -> Self {
+        Registers {
+            node_id: SimpleRegister::with_value("uavcan.node.id", true, true, 2),
+            description: SimpleRegister::new("uavcan.node.description", true, true),
+            gains: SimpleRegister::with_value("gains", true, true, [1.0, 1.0, 1.0]),
+            read_only: SimpleRegister::with_value("read_only", false, false, 3),
+            limited: ValidatedRegister::with_value("limited", true, false, 1, |value| *value < 10),
+        }
+    }
+}
+
+struct Setup {
+    client_node: TestNode,
+    client: BatchClient<Microseconds64, CanTransport>,
+    server_node: TestNode,
+    handler: RegisterHandler<Registers>,
+}
+
+impl Setup {
+    fn new() -> Self {
+        let (mut client_node, mut server_node) = node_pair(&SimulatedClock::new());
+        let client = BatchClient::new(&mut client_node).unwrap();
+        RegisterHandler::<Registers>::subscribe_requests(&mut server_node).unwrap();
+        Setup {
+            client_node,
+            client,
+            server_node,
+            handler: RegisterHandler::new(Registers::default()),
+        }
+    }
+
+    /// Exchanges transfers between the nodes until the client is idle, and returns the client
+    /// events
+    fn run(&mut self) -> Vec<BatchEvent<CanNodeId>> {
+        for _ in 0..64 {
+            self.client.poll(&mut self.client_node);
+            self.client_node.flush().unwrap();
+            for _ in 0..64 {
+                self.server_node.receive(&mut self.handler).unwrap();
+            }
+            self.server_node.flush().unwrap();
+            for _ in 0..64 {
+                self.client_node.receive(&mut self.client).unwrap();
+            }
+            if self.client.is_idle() {
+                break;
+            }
+        }
+        assert!(self.client.is_idle());
+        std::iter::from_fn(|| self.client.pop_event()).collect()
+    }
+}
+
+/// Returns the events for each register name
+fn by_name(events: Vec<BatchEvent<CanNodeId>>) -> BTreeMap<String, BatchEvent<CanNodeId>> {
+    events
+        .into_iter()
+        .map(|event| {
+            let name = match &event {
+                BatchEvent::Written { name, .. } | BatchEvent::NotWritten { name, .. } => {
+                    name.clone()
+                }
+                BatchEvent::Failed { name, .. } => name.clone().unwrap(),
+            };
+            (name, event)
+        })
+        .collect()
+}
+
+#[test]
+fn parse_documents() {
+    let batch = RegisterBatch::from_yaml(
+        r#"
+42:
+  uavcan.node:
+    id: 42
+    description: gateway
+  motor.gains: [0.5, 1.0, 2.0]
+7:
+  uavcan.node.id: 7
+"#,
+    )
+    .unwrap();
+    assert_eq!(vec![7, 42], batch.nodes().collect::<Vec<_>>());
+    assert_eq!(
+        vec!["motor.gains", "uavcan.node.description", "uavcan.node.id"],
+        batch.register_names(42).collect::<Vec<_>>()
+    );
+    assert_eq!(4, batch.len());
+
+    let batch = RegisterBatch::from_json(
+        r#"{ "42": { "uavcan": { "node": { "id": 42 } } }, "7": { "uavcan.node.id": 7 } }"#,
+    )
+    .unwrap();
+    assert!(batch.contains(42, "uavcan.node.id"));
+    assert!(batch.contains(7, "uavcan.node.id"));
+    assert_eq!(2, batch.len());
+
+    assert!(RegisterBatch::from_json(r#"{ "node": { "a": 1 } }"#).is_err());
+    assert!(RegisterBatch::from_json(r#"{ "1": 1 }"#).is_err());
+    assert!(RegisterBatch::from_json("[1]").is_err());
+    assert!(RegisterBatch::from_yaml("1: [").is_err());
+}
+
+#[test]
+fn save_documents() {
+    let mut batch = RegisterBatch::new();
+    batch.insert(
+        3,
+        "gains",
+        &Value::Real32(Real32 {
+            value: heapless::Vec::from_slice(&[0.1, 2.0]).unwrap(),
+        }),
+    );
+    batch.insert(
+        3,
+        "id",
+        &Value::Natural8(Natural8 {
+            value: heapless::Vec::from_slice(&[3]).unwrap(),
+        }),
+    );
+    batch.insert(
+        10,
+        "name",
+        &Value::String(string_1_0::String {
+            value: heapless::Vec::from_slice(b"node").unwrap(),
+        }),
+    );
+    assert_eq!(
+        "---\n3:\n  gains:\n    - 0.1\n    - 2.0\n  id: 3\n10:\n  name: node\n",
+        batch.to_yaml()
+    );
+    assert_eq!(
+        r#"{
+  "3": {
+    "gains": [
+      0.1,
+      2.0
+    ],
+    "id": 3
+  },
+  "10": {
+    "name": "node"
+  }
+}"#,
+        batch.to_json()
+    );
+}
+
+#[test]
+fn apply_batch() {
+    let mut setup = Setup::new();
+    let batch = RegisterBatch::from_yaml(
+        r#"
+2:
+  uavcan.node:
+    id: 42
+    description: test node
+  gains: [0.5, 1, 0.1]
+  read_only: 4
+  limited: 50
+  missing: 1
+"#,
+    )
+    .unwrap();
+    setup.client.apply(&batch).unwrap();
+    let mut events = by_name(setup.run());
+    assert_eq!(6, events.len());
+    for name in &["uavcan.node.id", "uavcan.node.description", "gains"] {
+        assert!(matches!(
+            events.remove(*name),
+            Some(BatchEvent::Written { server, .. }) if server == node_id(2)
+        ));
+    }
+    assert!(matches!(
+        events.remove("read_only"),
+        Some(BatchEvent::NotWritten { mutable: false, .. })
+    ));
+    assert!(matches!(
+        events.remove("limited"),
+        Some(BatchEvent::NotWritten { mutable: true, .. })
+    ));
+    assert!(matches!(
+        events.remove("missing"),
+        Some(BatchEvent::Failed {
+            error: BatchError::Register(RegisterError::NotFound),
+            ..
+        })
+    ));
+    assert!(events.is_empty());
+
+    let registers = setup.handler.block();
+    assert_eq!(42, *registers.node_id.value());
+    assert_eq!(&b"test node"[..], &registers.description.value().0[..]);
+    assert_eq!([0.5, 1.0, 0.1], *registers.gains.value());
+    assert_eq!(1, *registers.limited.value());
+}
+
+#[test]
+fn apply_conversion_errors() {
+    let mut setup = Setup::new();
+    let batch = RegisterBatch::from_json(
+        r#"{ "2": { "uavcan.node.id": 70000, "gains": "fast", "limited": 5 } }"#,
+    )
+    .unwrap();
+    setup.client.apply(&batch).unwrap();
+    let mut events = by_name(setup.run());
+    assert!(matches!(
+        events.remove("uavcan.node.id"),
+        Some(BatchEvent::Failed {
+            error: BatchError::Value(ValueErrorKind::Range),
+            ..
+        })
+    ));
+    assert!(matches!(
+        events.remove("gains"),
+        Some(BatchEvent::Failed {
+            error: BatchError::Value(ValueErrorKind::Type),
+            ..
+        })
+    ));
+    assert!(matches!(
+        events.remove("limited"),
+        Some(BatchEvent::Written { .. })
+    ));
+    assert_eq!(2, *setup.handler.block().node_id.value());
+    assert_eq!(5, *setup.handler.block().limited.value());
+
+    // CAN node IDs must be 127 or less
+    let batch =
+        RegisterBatch::from_json(r#"{ "2": { "limited": 6 }, "200": { "limited": 6 } }"#).unwrap();
+    assert_eq!(Err(InvalidNodeId(200)), setup.client.apply(&batch));
+    assert!(setup.client.is_idle());
+}
+
+#[test]
+fn dump_and_restore() {
+    let mut setup = Setup::new();
+    {
+        let registers = setup.handler.block_mut();
+        registers.node_id.set_value(9);
+        registers.gains.set_value([0.25, 0.1, 3.0]);
+        registers
+            .description
+            .set_value(RegisterString::try_from("saved").unwrap());
+    }
+    setup.client.dump(node_id(2));
+    assert!(setup.run().is_empty());
+    let snapshot = setup.client.take_snapshot();
+    assert_eq!(5, snapshot.len());
+    let yaml = snapshot.to_yaml();
+    assert_eq!(
+        "---\n2:\n  gains:\n    - 0.25\n    - 0.1\n    - 3.0\n  limited: 1\n  read_only: 3\n  uavcan.node.description: saved\n  uavcan.node.id: 9\n",
+        yaml
+    );
+    assert!(setup.client.take_snapshot().is_empty());
+
+    // Restore the saved values to a node with the default values
+    let mut setup = Setup::new();
+    let batch = RegisterBatch::from_yaml(&yaml).unwrap();
+    setup.client.apply(&batch).unwrap();
+    let events = by_name(setup.run());
+    for (name, event) in &events {
+        match event {
+            BatchEvent::Written { .. } => {}
+            BatchEvent::NotWritten { mutable: false, .. } if name == "read_only" => {}
+            _ => panic!("Unexpected result for {}", name),
+        }
+    }
+    assert_eq!(5, events.len());
+    setup.client.dump(node_id(2));
+    setup.run();
+    assert_eq!(yaml, setup.client.take_snapshot().to_yaml());
+}
@@ -0,0 +1,245 @@
+//! Tests of the register client, using two nodes connected by a simulated CAN bus
+
+extern crate canadensis;
+extern crate canadensis_can;
+extern crate canadensis_data_types;
+extern crate heapless;
+
+mod common;
+
+use canadensis::core::time::{milliseconds, Microseconds64, SimulatedClock};
+use canadensis::register::basic::{RegisterString, SimpleRegister, ValidatedRegister};
+use canadensis::register::client::{value_as, RegisterClient, RegisterError, RegisterEvent};
+use canadensis::register::{RegisterBlock, RegisterHandler};
+use canadensis::{Node, TransferHandler};
+use canadensis_can::{CanNodeId, CanTransport};
+use canadensis_data_types::uavcan::primitive::array::natural8_1_0::Natural8;
+use canadensis_data_types::uavcan::register::value_1_0::Value;
+
+use common::{node_id, node_pair, TestNode};
+
+#[derive(RegisterBlock)]
+struct Registers {
+    node_id: SimpleRegister<u16>,
+    description: SimpleRegister<RegisterString>,
+    read_only: SimpleRegister<u8>,
+    limited: ValidatedRegister<u8>,
+}
+
+impl Default for Registers {
+    fn default() -> Self {
+        Registers {
+            node_id: SimpleRegister::with_value("uavcan.node.id", true, true, 2),
+            description: SimpleRegister::new("uavcan.node.description", true, true),
+            read_only: SimpleRegister::with_value("read_only", false, false, 3),
+            limited: ValidatedRegister::with_value("limited", true, false, 1, |value| *value < 10),
+        }
+    }
+}
+
+/// A transfer handler that does not handle any transfers
+struct IgnoreTransfers;
+
+impl TransferHandler<Microseconds64, CanTransport> for IgnoreTransfers {}
+
+struct Setup {
+    clock: SimulatedClock,
+    client_node: TestNode,
+    client: RegisterClient<Microseconds64, CanTransport>,
+    server_node: TestNode,
+    handler: RegisterHandler<Registers>,
+    /// If false, the server does not receive requests
+    server_enabled: bool,
+}
+
+impl Setup {
+    fn new() -> Self {
+        let clock = SimulatedClock::new();
+        let (mut client_node, mut server_node) = node_pair(&clock);
+        let client = RegisterClient::new(&mut client_node).unwrap();
+        RegisterHandler::<Registers>::subscribe_requests(&mut server_node).unwrap();
+        Setup {
+            clock,
+            client_node,
+            client,
+            server_node,
+            handler: RegisterHandler::new(Registers::default()),
+            server_enabled: true,
+        }
+    }
+
+    /// Exchanges transfers between the nodes until the client is idle or nothing happens, and
+    /// returns the client events
+    fn run(&mut self) -> Vec<RegisterEvent<CanNodeId>> {
+        for _ in 0..64 {
+            self.client.poll(&mut self.client_node);
+            self.client_node.flush().unwrap();
+            for _ in 0..64 {
+                if self.server_enabled {
+                    self.server_node.receive(&mut self.handler).unwrap();
+                } else {
+                    self.server_node.receive(&mut IgnoreTransfers).unwrap();
+                }
+            }
+            self.server_node.flush().unwrap();
+            for _ in 0..64 {
+                self.client_node.receive(&mut self.client).unwrap();
+            }
+            if self.client.is_idle() {
+                break;
+            }
+        }
+        std::iter::from_fn(|| self.client.pop_event()).collect()
+    }
+}
+
+fn natural8(value: u8) -> Value {
+    Value::Natural8(Natural8 {
+        value: heapless::Vec::from_slice(&[value]).unwrap(),
+    })
+}
+
+#[test]
+fn list_and_read_all() {
+    let mut setup = Setup::new();
+    let id = setup.client.read_all(node_id(2));
+    let events = setup.run();
+    assert_eq!(5, events.len());
+    match &events[0] {
+        RegisterEvent::Listed {
+            id: event_id,
+            server,
+            names,
+        } => {
+            assert_eq!(id, *event_id);
+            assert_eq!(node_id(2), *server);
+            assert_eq!(
+                vec![
+                    "uavcan.node.id",
+                    "uavcan.node.description",
+                    "read_only",
+                    "limited"
+                ],
+                *names
+            );
+        }
+        _ => panic!("Expected Listed event"),
+    }
+    let values: Vec<(&str, &Value)> = events[1..]
+        .iter()
+        .map(|event| match event {
+            RegisterEvent::Read {
+                id: event_id,
+                name,
+                value,
+                ..
+            } => {
+                assert_eq!(id, *event_id);
+                (name.as_str(), value)
+            }
+            _ => panic!("Expected Read event"),
+        })
+        .collect();
+    assert_eq!("uavcan.node.id", values[0].0);
+    assert_eq!(2u16, value_as::<u16>(values[0].1).unwrap());
+    assert_eq!(b"", &value_as::<RegisterString>(values[1].1).unwrap().0[..]);
+    assert_eq!(3u8, value_as::<u8>(values[2].1).unwrap());
+    // Wrong type
+    assert!(value_as::<u16>(values[2].1).is_err());
+}
+
+#[test]
+fn read_missing() {
+    let mut setup = Setup::new();
+    let id = setup
+        .client
+        .read_batch(node_id(2), ["read_only", "nonexistent"]);
+    let events = setup.run();
+    assert_eq!(2, events.len());
+    assert!(matches!(
+        &events[0],
+        RegisterEvent::Read { id: event_id, name, value, .. }
+            if *event_id == id && name == "read_only" && value_as::<u8>(value).ok() == Some(3)
+    ));
+    assert!(matches!(
+        &events[1],
+        RegisterEvent::Failed { id: event_id, name: Some(name), error: RegisterError::NotFound, .. }
+            if *event_id == id && name == "nonexistent"
+    ));
+}
+
+#[test]
+fn write_and_verify() {
+    let mut setup = Setup::new();
+    let written = setup
+        .client
+        .write_typed(node_id(2), "uavcan.node.id", &7u16);
+    let batch = setup.client.write_batch(
+        node_id(2),
+        vec![
+            ("limited", natural8(5)),
+            // Rejected by the validator
+            ("limited", natural8(20)),
+            ("read_only", natural8(4)),
+        ],
+    );
+    let events = setup.run();
+    assert_eq!(4, events.len());
+    assert!(matches!(
+        &events[0],
+        RegisterEvent::Written { id, name, value, .. }
+            if *id == written && name == "uavcan.node.id" && value_as::<u16>(value).ok() == Some(7)
+    ));
+    assert!(matches!(
+        &events[1],
+        RegisterEvent::Written { id, name, value, .. }
+            if *id == batch && name == "limited" && value_as::<u8>(value).ok() == Some(5)
+    ));
+    assert!(matches!(
+        &events[2],
+        RegisterEvent::NotWritten { id, name, value, mutable: true, .. }
+            if *id == batch && name == "limited" && value_as::<u8>(value).ok() == Some(5)
+    ));
+    assert!(matches!(
+        &events[3],
+        RegisterEvent::NotWritten { id, name, value, mutable: false, .. }
+            if *id == batch && name == "read_only" && value_as::<u8>(value).ok() == Some(3)
+    ));
+    assert_eq!(7, *setup.handler.block().node_id.value());
+    assert_eq!(5, *setup.handler.block().limited.value());
+}
+
+#[test]
+fn retry_and_timeout() {
+    let mut setup = Setup::new();
+    setup.server_enabled = false;
+    let id = setup.client.read(node_id(2), "read_only");
+    assert!(setup.run().is_empty());
+    // The first two attempts time out, and the third succeeds
+    setup.clock.advance(milliseconds(1001));
+    assert!(setup.run().is_empty());
+    assert!(!setup.client.is_idle());
+    setup.server_enabled = true;
+    setup.clock.advance(milliseconds(1001));
+    let events = setup.run();
+    assert_eq!(1, events.len());
+    assert!(matches!(&events[0], RegisterEvent::Read { id: event_id, .. } if *event_id == id));
+
+    // All three attempts time out
+    setup.server_enabled = false;
+    let id = setup.client.list(node_id(2));
+    assert!(setup.run().is_empty());
+    for _ in 0..2 {
+        setup.clock.advance(milliseconds(1001));
+        assert!(setup.run().is_empty());
+    }
+    setup.clock.advance(milliseconds(1001));
+    let events = setup.run();
+    assert_eq!(1, events.len());
+    assert!(matches!(
+        &events[0],
+        RegisterEvent::Failed { id: event_id, name: None, error: RegisterError::Timeout, .. }
+            if *event_id == id
+    ));
+    assert!(setup.client.is_idle());
+}
@@ -0,0 +1,121 @@
+//! Tests of loading register values from environment variables
+
+extern crate canadensis;
+extern crate heapless;
+
+use std::collections::HashMap;
+use std::ffi::OsString;
+
+use canadensis::register::basic::{FixedStringRegister, RegisterString, SimpleRegister};
+use canadensis::register::environment::{
+    load_environment, load_variables, variable_name, EnvironmentError, EnvironmentErrorKind,
+};
+use canadensis::register::RegisterBlock;
+
+#[derive(RegisterBlock)]
+struct Registers {
+    node_id: SimpleRegister<u16>,
+    description: SimpleRegister<RegisterString>,
+    gains: SimpleRegister<[f32; 3]>,
+    flags: SimpleRegister<[bool; 2]>,
+    offset: SimpleRegister<i8>,
+    version: FixedStringRegister,
+}
+
+impl Default for Registers {
+    fn default() -> Self {
+        Registers {
+            node_id: SimpleRegister::with_value("uavcan.node.id", true, true, 65535),
+            description: SimpleRegister::new("uavcan.node.description", true, true),
+            gains: SimpleRegister::new("control.gains", true, true),
+            flags: SimpleRegister::new("control.flags", true, true),
+            offset: SimpleRegister::new("control.offset", true, true),
+            version: FixedStringRegister::new("app.version", "1.0").unwrap(),
+        }
+    }
+}
+
+fn load(registers: &mut Registers, variables: &[(&str, &str)]) -> Result<usize, EnvironmentError> {
+    let variables: HashMap<String, OsString> = variables
+        .iter()
+        .map(|&(name, value)| (name.to_owned(), OsString::from(value)))
+        .collect();
+    load_variables(registers, |name| variables.get(name).cloned())
+}
+
+#[test]
+fn names() {
+    assert_eq!("UAVCAN__NODE__ID", variable_name("uavcan.node.id"));
+    assert_eq!("UAVCAN__UDP__IFACE", variable_name("uavcan.udp.iface"));
+}
+
+#[test]
+fn load_values() {
+    let mut registers = Registers::default();
+    let loaded = load(
+        &mut registers,
+        &[
+            ("UAVCAN__NODE__ID", "42"),
+            ("UAVCAN__NODE__DESCRIPTION", "Test node, version 2"),
+            ("CONTROL__GAINS", "1.5 -2,  3e2"),
+            ("CONTROL__FLAGS", "true 0"),
+            ("CONTROL__OFFSET", "-7"),
+            ("UNRELATED", "x"),
+        ],
+    )
+    .unwrap();
+    assert_eq!(5, loaded);
+    assert_eq!(42, *registers.node_id.value());
+    assert_eq!(
+        &b"Test node, version 2"[..],
+        &registers.description.value().0[..]
+    );
+    assert_eq!(&[1.5, -2.0, 300.0], registers.gains.value());
+    assert_eq!(&[true, false], registers.flags.value());
+    assert_eq!(-7, *registers.offset.value());
+}
+
+#[test]
+fn errors() {
+    let cases = [
+        ("UAVCAN__NODE__ID", "forty-two"),
+        ("UAVCAN__NODE__ID", "70000"),
+        ("UAVCAN__NODE__ID", "1 2"),
+        ("CONTROL__GAINS", "1 2"),
+        ("CONTROL__FLAGS", "yes no"),
+        ("APP__VERSION", "2.0"),
+    ];
+    for &(name, value) in cases.iter() {
+        let mut registers = Registers::default();
+        let error = load(&mut registers, &[(name, value)]).unwrap_err();
+        assert_eq!(name, error.variable());
+        match (name, error.kind()) {
+            ("UAVCAN__NODE__ID", EnvironmentErrorKind::Parse) if value != "1 2" => {}
+            ("UAVCAN__NODE__ID", EnvironmentErrorKind::Write(_)) if value == "1 2" => {}
+            ("CONTROL__GAINS", EnvironmentErrorKind::Write(_)) => {}
+            ("CONTROL__FLAGS", EnvironmentErrorKind::Parse) => {}
+            ("APP__VERSION", EnvironmentErrorKind::NotMutable) => {}
+            (name, kind) => panic!("Unexpected error for {}={}: {:?}", name, value, kind),
+        }
+        // The register keeps its value
+        assert_eq!(65535, *registers.node_id.value());
+    }
+}
+
+#[derive(RegisterBlock)]
+struct TestRegisters {
+    value: SimpleRegister<u8>,
+    missing: SimpleRegister<u8>,
+}
+
+#[test]
+fn process_environment() {
+    std::env::set_var("CANADENSIS_TEST__ENVIRONMENT__VALUE", "12");
+    let mut registers = TestRegisters {
+        value: SimpleRegister::new("canadensis_test.environment.value", true, false),
+        missing: SimpleRegister::new("canadensis_test.environment.missing", true, false),
+    };
+    assert_eq!(1, load_environment(&mut registers).unwrap());
+    assert_eq!(12, *registers.value.value());
+    assert_eq!(0, *registers.missing.value());
+}
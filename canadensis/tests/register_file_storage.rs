@@ -0,0 +1,66 @@
+//! Tests of the file register storage
+
+extern crate canadensis;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use canadensis::register::storage::file::FileStorage;
+use canadensis::register::storage::RegisterStorage;
+
+/// A temporary directory that is deleted when dropped
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!(
+            "canadensis_register_storage_{}_{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&path);
+        TempDir(path)
+    }
+
+    fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+#[test]
+fn store_load_erase() {
+    let dir = TempDir::new("store_load_erase");
+    let mut storage = FileStorage::new(dir.path()).unwrap();
+    assert_eq!(None, storage.load("uavcan.node.id").unwrap());
+    storage.store("uavcan.node.id", &[1, 2, 3]).unwrap();
+    storage.store("uavcan.node.id", &[4]).unwrap();
+    assert_eq!(Some(vec![4]), storage.load("uavcan.node.id").unwrap());
+
+    // Values are still available after opening the directory again
+    let mut storage = FileStorage::new(dir.path()).unwrap();
+    assert_eq!(Some(vec![4]), storage.load("uavcan.node.id").unwrap());
+    storage.erase("uavcan.node.id").unwrap();
+    assert_eq!(None, storage.load("uavcan.node.id").unwrap());
+    storage.erase("uavcan.node.id").unwrap();
+}
+
+#[test]
+fn unusual_names() {
+    let dir = TempDir::new("unusual_names");
+    let mut storage = FileStorage::new(dir.path()).unwrap();
+    let names = ["a/b", "..", ".hidden", "a b", "A%41", "AA", "~AA"];
+    for (i, name) in names.iter().enumerate() {
+        storage.store(name, &[i as u8]).unwrap();
+    }
+    for (i, name) in names.iter().enumerate() {
+        assert_eq!(Some(vec![i as u8]), storage.load(name).unwrap());
+    }
+    // All files are in the directory
+    assert_eq!(names.len(), fs::read_dir(dir.path()).unwrap().count());
+}
@@ -0,0 +1,301 @@
+//! Tests of the flash register storage and register persistence, using flash memory simulated
+//! in RAM
+
+extern crate canadensis;
+extern crate embedded_storage;
+
+use canadensis::core::time::{milliseconds, Microseconds32};
+use canadensis::register::basic::{RegisterString, SimpleRegister};
+use canadensis::register::storage::flash::{FlashStorage, FlashStorageError};
+use canadensis::register::storage::{RegisterPersistence, RegisterStorage};
+use canadensis::register::RegisterBlock;
+use embedded_storage::nor_flash::{
+    ErrorType, NorFlash, NorFlashError, NorFlashErrorKind, ReadNorFlash,
+};
+
+const SECTOR_SIZE: usize = 256;
+
+/// NOR flash memory in RAM
+///
+/// Like real flash memory, writes can only change bits from 1 to 0.
+#[derive(Debug, Clone)]
+struct RamFlash {
+    memory: Vec<u8>,
+    erase_count: usize,
+}
+
+impl RamFlash {
+    fn new(sectors: usize) -> Self {
+        RamFlash {
+            memory: vec![0xff; sectors * SECTOR_SIZE],
+            erase_count: 0,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct RamFlashError;
+
+impl NorFlashError for RamFlashError {
+    fn kind(&self) -> NorFlashErrorKind {
+        NorFlashErrorKind::Other
+    }
+}
+
+impl ErrorType for RamFlash {
+    type Error = RamFlashError;
+}
+
+impl ReadNorFlash for RamFlash {
+    const READ_SIZE: usize = 1;
+
+    fn read(&mut self, offset: u32, bytes: &mut [u8]) -> Result<(), Self::Error> {
+        let offset = offset as usize;
+        bytes.copy_from_slice(&self.memory[offset..offset + bytes.len()]);
+        Ok(())
+    }
+
+    fn capacity(&self) -> usize {
+        self.memory.len()
+    }
+}
+
+impl NorFlash for RamFlash {
+    const WRITE_SIZE: usize = 4;
+    const ERASE_SIZE: usize = SECTOR_SIZE;
+
+    fn erase(&mut self, from: u32, to: u32) -> Result<(), Self::Error> {
+        let (from, to) = (from as usize, to as usize);
+        assert_eq!(0, from % SECTOR_SIZE, "Unaligned erase start");
+        assert_eq!(0, to % SECTOR_SIZE, "Unaligned erase end");
+        self.memory[from..to]
+            .iter_mut()
+            .for_each(|byte| *byte = 0xff);
+        self.erase_count += 1;
+        Ok(())
+    }
+
+    fn write(&mut self, offset: u32, bytes: &[u8]) -> Result<(), Self::Error> {
+        let offset = offset as usize;
+        assert_eq!(0, offset % Self::WRITE_SIZE, "Unaligned write offset");
+        assert_eq!(0, bytes.len() % Self::WRITE_SIZE, "Unaligned write length");
+        for (old, new) in self.memory[offset..].iter_mut().zip(bytes) {
+            assert_eq!(*new, *old & *new, "Write to flash that was not erased");
+            *old = *new;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn store_and_load() {
+    let mut storage = FlashStorage::new(RamFlash::new(2)).unwrap();
+    assert_eq!(None, storage.load("uavcan.node.id").unwrap());
+    storage.store("uavcan.node.id", &[1, 2, 3]).unwrap();
+    storage.store("uavcan.node.description", b"").unwrap();
+    assert_eq!(Some(vec![1, 2, 3]), storage.load("uavcan.node.id").unwrap());
+    assert_eq!(
+        Some(vec![]),
+        storage.load("uavcan.node.description").unwrap()
+    );
+    storage.store("uavcan.node.id", &[4, 5]).unwrap();
+    assert_eq!(Some(vec![4, 5]), storage.load("uavcan.node.id").unwrap());
+
+    storage.erase("uavcan.node.id").unwrap();
+    assert_eq!(None, storage.load("uavcan.node.id").unwrap());
+    // Erasing a key that is not stored has no effect
+    storage.erase("uavcan.node.id").unwrap();
+    storage.erase("other").unwrap();
+    assert_eq!(
+        Some(vec![]),
+        storage.load("uavcan.node.description").unwrap()
+    );
+}
+
+#[test]
+fn reopen() {
+    let mut storage = FlashStorage::new(RamFlash::new(4)).unwrap();
+    storage.store("a", &[1]).unwrap();
+    storage.store("b", &[2]).unwrap();
+    storage.store("a", &[3]).unwrap();
+    storage.erase("b").unwrap();
+
+    let mut storage = FlashStorage::new(storage.into_flash()).unwrap();
+    assert_eq!(Some(vec![3]), storage.load("a").unwrap());
+    assert_eq!(None, storage.load("b").unwrap());
+    // New values are appended after the existing ones
+    storage.store("c", &[4]).unwrap();
+    let mut storage = FlashStorage::new(storage.into_flash()).unwrap();
+    assert_eq!(Some(vec![3]), storage.load("a").unwrap());
+    assert_eq!(Some(vec![4]), storage.load("c").unwrap());
+}
+
+#[test]
+fn unchanged_value_not_written() {
+    let mut storage = FlashStorage::new(RamFlash::new(2)).unwrap();
+    storage.store("a", &[1, 2]).unwrap();
+    let before = storage.flash().memory.clone();
+    storage.store("a", &[1, 2]).unwrap();
+    assert_eq!(before, storage.flash().memory);
+}
+
+#[test]
+fn compaction() {
+    let mut storage = FlashStorage::new(RamFlash::new(2)).unwrap();
+    storage.store("constant", &[0xaa; 16]).unwrap();
+    storage.store("erased", &[0xbb; 16]).unwrap();
+    storage.erase("erased").unwrap();
+    // Each bank is one sector, so this needs several compactions
+    for i in 0..100u8 {
+        storage.store("counter", &[i, i, i]).unwrap();
+        assert_eq!(Some(vec![i, i, i]), storage.load("counter").unwrap());
+    }
+    assert!(storage.flash().erase_count > 2);
+    assert_eq!(Some(vec![0xaa; 16]), storage.load("constant").unwrap());
+    assert_eq!(None, storage.load("erased").unwrap());
+
+    let mut storage = FlashStorage::new(storage.into_flash()).unwrap();
+    assert_eq!(Some(vec![99, 99, 99]), storage.load("counter").unwrap());
+    assert_eq!(Some(vec![0xaa; 16]), storage.load("constant").unwrap());
+    assert_eq!(None, storage.load("erased").unwrap());
+}
+
+#[test]
+fn full() {
+    let mut storage = FlashStorage::new(RamFlash::new(2)).unwrap();
+    storage.store("a", &[1; 200]).unwrap();
+    assert!(matches!(
+        storage.store("b", &[2; 200]),
+        Err(FlashStorageError::Full)
+    ));
+    assert_eq!(Some(vec![1; 200]), storage.load("a").unwrap());
+}
+
+#[test]
+fn damaged_record_ignored() {
+    let mut storage = FlashStorage::new(RamFlash::new(2)).unwrap();
+    storage.store("a", &[1]).unwrap();
+    storage.store("a", &[2]).unwrap();
+    let mut flash = storage.into_flash();
+    // Clear a bit in the value of the second record, as if writing it had been interrupted.
+    // The bank header is 8 bytes, and each record is 8 + 1 + 1 bytes, padded to 12.
+    flash.memory[8 + 12 + 9] = 0xfd;
+    let mut storage = FlashStorage::new(flash).unwrap();
+    assert_eq!(Some(vec![1]), storage.load("a").unwrap());
+    storage.store("a", &[3]).unwrap();
+    assert_eq!(Some(vec![3]), storage.load("a").unwrap());
+}
+
+#[derive(RegisterBlock)]
+struct Registers {
+    node_id: SimpleRegister<u16>,
+    description: SimpleRegister<RegisterString>,
+    volatile: SimpleRegister<u8>,
+    read_only: SimpleRegister<u8>,
+}
+
+impl Default for Registers {
+    fn default() -> Self {
+        Registers {
+            node_id: SimpleRegister::with_value("uavcan.node.id", true, true, 65535),
+            description: SimpleRegister::new("uavcan.node.description", true, true),
+            volatile: SimpleRegister::new("volatile", true, false),
+            read_only: SimpleRegister::with_value("read_only", false, true, 3),
+        }
+    }
+}
+
+fn make_persistence(
+    flash: RamFlash,
+) -> RegisterPersistence<FlashStorage<RamFlash>, Microseconds32> {
+    RegisterPersistence::new(FlashStorage::new(flash).unwrap(), milliseconds(500))
+}
+
+#[test]
+fn save_and_load_registers() {
+    let mut persistence = make_persistence(RamFlash::new(4));
+    let mut registers = Registers::default();
+    // Nothing stored yet
+    assert_eq!(0, persistence.load(&mut registers).unwrap());
+    assert_eq!(65535, *registers.node_id.value());
+
+    registers.node_id.set_value(42);
+    registers
+        .description
+        .value_mut()
+        .0
+        .extend_from_slice(b"test node")
+        .unwrap();
+    registers.volatile.set_value(7);
+    persistence.save(&registers).unwrap();
+    // Registers that are not persistent or not mutable are not stored
+    assert_eq!(None, persistence.storage_mut().load("volatile").unwrap());
+    assert_eq!(None, persistence.storage_mut().load("read_only").unwrap());
+
+    let flash = persistence.storage().flash().clone();
+    let mut persistence = make_persistence(flash);
+    let mut registers = Registers::default();
+    assert_eq!(2, persistence.load(&mut registers).unwrap());
+    assert_eq!(42, *registers.node_id.value());
+    assert_eq!(&b"test node"[..], &registers.description.value().0[..]);
+    assert_eq!(0, *registers.volatile.value());
+}
+
+#[test]
+fn invalid_stored_value_ignored() {
+    let mut persistence = make_persistence(RamFlash::new(4));
+    // Not a valid Value
+    persistence
+        .storage_mut()
+        .store("uavcan.node.id", &[0xff, 0xff])
+        .unwrap();
+    // A valid Value of the wrong type (empty)
+    persistence
+        .storage_mut()
+        .store("uavcan.node.description", &[0])
+        .unwrap();
+    let mut registers = Registers::default();
+    assert_eq!(0, persistence.load(&mut registers).unwrap());
+    assert_eq!(65535, *registers.node_id.value());
+}
+
+#[test]
+fn debounced_save() {
+    let mut persistence = make_persistence(RamFlash::new(4));
+    let mut registers = Registers::default();
+    assert!(!persistence
+        .poll(&registers, Microseconds32::new(0))
+        .unwrap());
+
+    registers.node_id.set_value(10);
+    persistence.schedule_save(Microseconds32::new(0));
+    assert!(persistence.save_scheduled());
+    // Another change delays the save
+    registers.node_id.set_value(11);
+    persistence.schedule_save(Microseconds32::new(300_000));
+    assert!(!persistence
+        .poll(&registers, Microseconds32::new(700_000))
+        .unwrap());
+    assert_eq!(
+        None,
+        persistence.storage_mut().load("uavcan.node.id").unwrap()
+    );
+    assert!(persistence
+        .poll(&registers, Microseconds32::new(800_000))
+        .unwrap());
+    assert!(!persistence.save_scheduled());
+    assert!(persistence
+        .storage_mut()
+        .load("uavcan.node.id")
+        .unwrap()
+        .is_some());
+    // Already saved
+    assert!(!persistence
+        .poll(&registers, Microseconds32::new(900_000))
+        .unwrap());
+
+    let flash = persistence.storage().flash().clone();
+    let mut registers = Registers::default();
+    make_persistence(flash).load(&mut registers).unwrap();
+    assert_eq!(11, *registers.node_id.value());
+}
@@ -0,0 +1,174 @@
+//! Tests of register write error categories and the register handler write hook
+
+extern crate canadensis;
+extern crate canadensis_can;
+extern crate canadensis_data_types;
+extern crate heapless;
+
+mod common;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use canadensis::core::time::SimulatedClock;
+use canadensis::register::basic::{FixedStringRegister, SimpleRegister, ValidatedRegister};
+use canadensis::register::client::{value_as, RegisterClient, RegisterEvent};
+use canadensis::register::{Register, RegisterBlock, RegisterHandler, WriteError, WriteHook};
+use canadensis::time_sync::NotSynchronized;
+use canadensis::Node;
+use canadensis_can::CanNodeId;
+use canadensis_data_types::uavcan::primitive::array::natural8_1_0::Natural8;
+use canadensis_data_types::uavcan::primitive::array::real32_1_0::Real32;
+use canadensis_data_types::uavcan::register::value_1_0::Value;
+
+use common::{node_id, node_pair};
+
+#[derive(RegisterBlock)]
+struct Registers {
+    gain: SimpleRegister<f32>,
+    read_only: SimpleRegister<u8>,
+    limited: ValidatedRegister<u8>,
+}
+
+impl Default for Registers {
+    fn default() -> Self {
+        Registers {
+            gain: SimpleRegister::with_value("gain", true, true, 1.0),
+            read_only: SimpleRegister::with_value("read_only", false, false, 3),
+            limited: ValidatedRegister::with_value("limited", true, false, 1, |value| *value < 10),
+        }
+    }
+}
+
+/// A write hook that records all writes
+#[derive(Default)]
+struct Recorder {
+    written: Vec<(String, Option<f32>, Option<f32>)>,
+    failed: Vec<(String, WriteError)>,
+}
+
+impl WriteHook for Recorder {
+    fn register_written(&mut self, name: &str, old_value: &Value, new_value: &Value) {
+        self.written.push((
+            name.to_owned(),
+            value_as(old_value).ok(),
+            value_as(new_value).ok(),
+        ));
+    }
+
+    fn write_failed(&mut self, name: &str, _value: &Value, error: WriteError) {
+        self.failed.push((name.to_owned(), error));
+    }
+}
+
+fn real32(value: f32) -> Value {
+    Value::Real32(Real32 {
+        value: heapless::Vec::from_slice(&[value]).unwrap(),
+    })
+}
+
+fn natural8(value: u8) -> Value {
+    Value::Natural8(Natural8 {
+        value: heapless::Vec::from_slice(&[value]).unwrap(),
+    })
+}
+
+/// Sends write requests from a client node to a node with the provided handler, and returns the
+/// client events
+fn remote_write<H>(
+    handler: &mut RegisterHandler<Registers, NotSynchronized, H>,
+    writes: Vec<(&str, Value)>,
+) -> Vec<RegisterEvent<CanNodeId>>
+where
+    H: WriteHook,
+{
+    let (mut client_node, mut server_node) = node_pair(&SimulatedClock::new());
+    let mut client = RegisterClient::new(&mut client_node).unwrap();
+    RegisterHandler::<Registers>::subscribe_requests(&mut server_node).unwrap();
+    for (name, value) in writes {
+        client.write(node_id(2), name, value);
+    }
+    let mut events = Vec::new();
+    for _ in 0..16 {
+        client.poll(&mut client_node);
+        client_node.flush().unwrap();
+        for _ in 0..64 {
+            server_node.receive(handler).unwrap();
+        }
+        server_node.flush().unwrap();
+        for _ in 0..64 {
+            client_node.receive(&mut client).unwrap();
+        }
+        events.extend(std::iter::from_fn(|| client.pop_event()));
+    }
+    assert!(client.is_idle());
+    events
+}
+
+#[test]
+fn write_error_categories() {
+    let mut registers = Registers::default();
+    assert_eq!(Err(WriteError::Type), registers.limited.write(&real32(1.0)));
+    assert_eq!(
+        Err(WriteError::Invalid),
+        registers.limited.write(&natural8(10))
+    );
+    assert_eq!(Ok(()), registers.limited.write(&natural8(9)));
+
+    let mut fixed = FixedStringRegister::new("info", "fixed").unwrap();
+    assert_eq!(Err(WriteError::ReadOnly), fixed.write(&natural8(1)));
+}
+
+#[test]
+fn hook_receives_writes_and_failures() {
+    let mut handler =
+        RegisterHandler::new(Registers::default()).with_write_hook(Recorder::default());
+    let events = remote_write(
+        &mut handler,
+        vec![
+            ("gain", real32(2.5)),
+            ("read_only", natural8(4)),
+            ("limited", natural8(20)),
+            ("gain", natural8(1)),
+        ],
+    );
+    assert_eq!(4, events.len());
+    assert!(matches!(&events[0], RegisterEvent::Written { name, .. } if name == "gain"));
+    for event in &events[1..] {
+        assert!(matches!(event, RegisterEvent::NotWritten { .. }));
+    }
+
+    let recorder = handler.write_hook();
+    assert_eq!(
+        vec![("gain".to_owned(), Some(1.0), Some(2.5))],
+        recorder.written
+    );
+    assert_eq!(
+        vec![
+            ("read_only".to_owned(), WriteError::ReadOnly),
+            ("limited".to_owned(), WriteError::Invalid),
+            ("gain".to_owned(), WriteError::Type),
+        ],
+        recorder.failed
+    );
+    assert!(handler.take_persistent_modified());
+}
+
+#[test]
+fn closure_hook() {
+    let changes = Rc::new(RefCell::new(Vec::new()));
+    let hook_changes = Rc::clone(&changes);
+    let mut handler = RegisterHandler::new(Registers::default()).with_write_hook(
+        move |name: &str, _old: &Value, new: &Value| {
+            hook_changes
+                .borrow_mut()
+                .push((name.to_owned(), value_as::<u8>(new).ok()))
+        },
+    );
+    remote_write(
+        &mut handler,
+        vec![("limited", natural8(5)), ("limited", natural8(50))],
+    );
+    assert_eq!(vec![("limited".to_owned(), Some(5))], *changes.borrow());
+    assert_eq!(5, *handler.block().limited.value());
+}
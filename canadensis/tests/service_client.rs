@@ -0,0 +1,259 @@
+//! Tests of the service client, using two nodes connected by a simulated CAN bus
+
+extern crate canadensis;
+extern crate canadensis_can;
+extern crate canadensis_data_types;
+extern crate heapless;
+
+mod common;
+
+use std::convert::TryFrom;
+
+use canadensis::client::{ClientError, ClientSendError, ServiceClient};
+use canadensis::core::time::{milliseconds, Clock, Microseconds64, SimulatedClock};
+use canadensis::core::transfer::{ServiceHeader, ServiceTransfer};
+use canadensis::core::transport::TransferId;
+use canadensis::core::{nb, Priority};
+use canadensis::encoding::Deserialize;
+use canadensis::{Node, ResponseToken, TransferHandler};
+use canadensis_can::{CanNodeId, CanTransport};
+use canadensis_data_types::uavcan::node::execute_command_1_1::{
+    self, ExecuteCommandRequest, ExecuteCommandResponse,
+};
+
+use common::{node_pair, TestNode};
+
+type TestClient =
+    ServiceClient<Microseconds64, CanTransport, ExecuteCommandRequest, ExecuteCommandResponse, 2>;
+
+/// Returns a client node with a client, and a server node that handles ExecuteCommand requests
+fn make_client_and_server() -> (TestNode, TestClient, TestNode) {
+    let (mut client_node, mut server_node) = node_pair(&SimulatedClock::new());
+    let token = client_node
+        .start_sending_requests(
+            execute_command_1_1::SERVICE,
+            milliseconds(1000),
+            48,
+            Priority::Nominal,
+        )
+        .unwrap();
+    server_node
+        .subscribe_request(execute_command_1_1::SERVICE, 300, milliseconds(1000))
+        .unwrap();
+    let client = ServiceClient::new(token, milliseconds(500));
+    (client_node, client, server_node)
+}
+
+/// Responds to each request with the low 8 bits of the command as the status
+struct Server;
+
+impl TransferHandler<Microseconds64, CanTransport> for Server {
+    fn handle_request<N: Node<Instant = Microseconds64, Transport = CanTransport>>(
+        &mut self,
+        node: &mut N,
+        token: ResponseToken<CanTransport>,
+        transfer: &ServiceTransfer<Vec<u8>, Microseconds64, CanTransport>,
+    ) -> bool {
+        let request = ExecuteCommandRequest::deserialize_from_bytes(&transfer.payload).unwrap();
+        let response = ExecuteCommandResponse {
+            status: request.command as u8,
+        };
+        node.send_response(token, milliseconds(1000), &response)
+            .unwrap();
+        true
+    }
+}
+
+/// Passes responses to a client and collects the results
+struct ClientHandler<'c> {
+    client: &'c mut TestClient,
+    results: Vec<
+        Result<
+            ServiceTransfer<ExecuteCommandResponse, Microseconds64, CanTransport>,
+            ClientError<CanTransport>,
+        >,
+    >,
+}
+
+impl TransferHandler<Microseconds64, CanTransport> for ClientHandler<'_> {
+    fn handle_response<N: Node<Instant = Microseconds64, Transport = CanTransport>>(
+        &mut self,
+        _node: &mut N,
+        transfer: &ServiceTransfer<Vec<u8>, Microseconds64, CanTransport>,
+    ) -> bool {
+        match self.client.handle_response(transfer) {
+            Some(result) => {
+                self.results.push(result);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+fn receive_all<H>(node: &mut TestNode, handler: &mut H)
+where
+    H: TransferHandler<Microseconds64, CanTransport>,
+{
+    for _ in 0..32 {
+        node.receive(handler).unwrap();
+    }
+    node.flush().unwrap();
+}
+
+fn receive_responses(
+    node: &mut TestNode,
+    client: &mut TestClient,
+) -> Vec<
+    Result<
+        ServiceTransfer<ExecuteCommandResponse, Microseconds64, CanTransport>,
+        ClientError<CanTransport>,
+    >,
+> {
+    let mut handler = ClientHandler {
+        client,
+        results: Vec::new(),
+    };
+    receive_all(node, &mut handler);
+    handler.results
+}
+
+fn request(command: u16) -> ExecuteCommandRequest {
+    ExecuteCommandRequest {
+        command,
+        // Long enough to need multiple frames
+        parameter: heapless::Vec::from_slice(&[0x55; 20]).unwrap(),
+    }
+}
+
+#[test]
+fn responses_matched_to_requests() {
+    let (mut client_node, mut client, mut server_node) = make_client_and_server();
+    let server_id = CanNodeId::try_from(2u8).unwrap();
+
+    let id_a = client
+        .send(&mut client_node, &request(3), server_id)
+        .unwrap();
+    let id_b = client
+        .send(&mut client_node, &request(9), server_id)
+        .unwrap();
+    assert_ne!(id_a, id_b);
+    assert_eq!(2, client.pending_count());
+    client_node.flush().unwrap();
+
+    receive_all(&mut server_node, &mut Server);
+    let results = receive_responses(&mut client_node, &mut client);
+    assert_eq!(2, results.len());
+    let mut matched: Vec<_> = results
+        .into_iter()
+        .map(|result| {
+            let response = result.unwrap();
+            assert_eq!(server_id, response.header.source);
+            (response.header.transfer_id, response.payload.status)
+        })
+        .collect();
+    matched.sort_by_key(|&(_, status)| status);
+    assert_eq!(vec![(id_a, 3), (id_b, 9)], matched);
+    assert_eq!(0, client.pending_count());
+}
+
+#[test]
+fn response_from_other_server_ignored() {
+    let (mut client_node, mut client, _server_node) = make_client_and_server();
+    let server_id = CanNodeId::try_from(2u8).unwrap();
+    let transfer_id = client
+        .send(&mut client_node, &request(3), server_id)
+        .unwrap();
+
+    let mut response = ServiceTransfer {
+        header: ServiceHeader {
+            timestamp: client_node.clock_mut().now(),
+            transfer_id,
+            priority: Priority::Nominal,
+            service: execute_command_1_1::SERVICE,
+            source: CanNodeId::try_from(3u8).unwrap(),
+            destination: CanNodeId::try_from(1u8).unwrap(),
+        },
+        payload: vec![3],
+    };
+    assert!(client.handle_response(&response).is_none());
+    response.header.source = server_id;
+    response.header.transfer_id = transfer_id.increment();
+    assert!(client.handle_response(&response).is_none());
+    assert_eq!(1, client.pending_count());
+
+    response.header.transfer_id = transfer_id;
+    let matched = client.handle_response(&response).unwrap().unwrap();
+    assert_eq!(3, matched.payload.status);
+    assert_eq!(0, client.pending_count());
+}
+
+#[test]
+fn timeout() {
+    let (mut client_node, mut client, _server_node) = make_client_and_server();
+    let server_id = CanNodeId::try_from(2u8).unwrap();
+    let transfer_id = client
+        .send(&mut client_node, &request(1), server_id)
+        .unwrap();
+
+    client_node.clock_mut().advance(milliseconds(500));
+    assert!(client
+        .check_timeouts(client_node.clock_mut().now())
+        .is_none());
+    client_node.clock_mut().advance(milliseconds(1));
+    match client.check_timeouts(client_node.clock_mut().now()) {
+        Some(ClientError::Timeout {
+            server,
+            transfer_id: timed_out_id,
+        }) => {
+            assert_eq!(server_id, server);
+            assert_eq!(transfer_id, timed_out_id);
+        }
+        other => panic!("Unexpected result {:?}", other),
+    }
+    assert!(client
+        .check_timeouts(client_node.clock_mut().now())
+        .is_none());
+    assert_eq!(0, client.pending_count());
+}
+
+#[test]
+fn late_response_ignored() {
+    let (mut client_node, mut client, mut server_node) = make_client_and_server();
+    let server_id = CanNodeId::try_from(2u8).unwrap();
+    client
+        .send(&mut client_node, &request(1), server_id)
+        .unwrap();
+    client_node.flush().unwrap();
+    // The server responds after the client's timeout
+    client_node.clock_mut().advance(milliseconds(600));
+    receive_all(&mut server_node, &mut Server);
+
+    let results = receive_responses(&mut client_node, &mut client);
+    assert!(results.is_empty());
+    assert!(matches!(
+        client.check_timeouts(client_node.clock_mut().now()),
+        Some(ClientError::Timeout { .. })
+    ));
+}
+
+#[test]
+fn capacity() {
+    let (mut client_node, mut client, _server_node) = make_client_and_server();
+    let server_id = CanNodeId::try_from(2u8).unwrap();
+    let transfer_id = client
+        .send(&mut client_node, &request(1), server_id)
+        .unwrap();
+    client
+        .send(&mut client_node, &request(2), server_id)
+        .unwrap();
+    assert!(matches!(
+        client.send(&mut client_node, &request(3), server_id),
+        Err(nb::Error::Other(ClientSendError::Capacity))
+    ));
+    assert!(client.cancel(&server_id, &transfer_id));
+    assert!(!client.cancel(&server_id, &transfer_id));
+    client
+        .send(&mut client_node, &request(3), server_id)
+        .unwrap();
+}
@@ -0,0 +1,240 @@
+//! Tests that run several nodes on a simulated CAN bus
+
+extern crate canadensis;
+extern crate canadensis_can;
+extern crate canadensis_data_types;
+extern crate heapless;
+
+use std::convert::TryFrom;
+
+use canadensis::core::time::{MicrosecondDuration64, Microseconds64, SimulatedClock};
+use canadensis::monitor::{MonitorEvent, NodeMonitor};
+use canadensis::node::{BasicNode, CoreNode};
+use canadensis::register::basic::SimpleRegister;
+use canadensis::register::client::{value_as, RegisterClient, RegisterError, RegisterEvent};
+use canadensis::register::{RegisterBlock, RegisterHandler};
+use canadensis::requester::TransferIdFixedMap;
+use canadensis::simulation::{LockStep, Participant, SimulatedNode};
+use canadensis_can::simulated::{SimulatedBus, SimulatedDriver};
+use canadensis_can::{CanNodeId, CanReceiver, CanTransmitter, CanTransport, Mtu};
+use canadensis_data_types::uavcan::node::get_info_1_0::GetInfoResponse;
+use canadensis_data_types::uavcan::node::version_1_0::Version;
+use canadensis_data_types::uavcan::primitive::array::real32_1_0::Real32;
+use canadensis_data_types::uavcan::register::value_1_0::Value;
+
+/// The time required to transmit one frame, and the length of each simulation step
+const FRAME_TIME: u64 = 100;
+
+type Driver = SimulatedDriver<Microseconds64>;
+type TestNode = CoreNode<
+    SimulatedClock,
+    CanTransmitter<Microseconds64, Driver>,
+    CanReceiver<Microseconds64, Driver>,
+    TransferIdFixedMap<CanTransport, 4>,
+    Driver,
+    4,
+    4,
+>;
+type Monitor = NodeMonitor<Microseconds64, CanTransport, 8, 16>;
+type Device = SimulatedNode<BasicNode<TestNode>, RegisterHandler<Registers>>;
+
+#[derive(RegisterBlock)]
+struct Registers {
+    gain: SimpleRegister<f32>,
+}
+
+impl Default for Registers {
+    fn default() -> Self {
+        Registers {
+            gain: SimpleRegister::with_value("gain", true, true, 1.0),
+        }
+    }
+}
+
+struct Setup {
+    clock: SimulatedClock,
+    bus: SimulatedBus<Microseconds64>,
+}
+
+impl Setup {
+    fn new() -> Self {
+        Setup {
+            clock: SimulatedClock::new(),
+            bus: SimulatedBus::new(MicrosecondDuration64::new(FRAME_TIME)),
+        }
+    }
+
+    fn lock_step(&self) -> LockStep {
+        LockStep::new(self.clock.clone(), MicrosecondDuration64::new(FRAME_TIME))
+    }
+
+    fn make_node(&self, id: u8) -> TestNode {
+        CoreNode::new(
+            self.clock.clone(),
+            node_id(id),
+            CanTransmitter::new(Mtu::Can8),
+            CanReceiver::new(node_id(id), Mtu::Can8),
+            self.bus.add_driver(),
+        )
+    }
+
+    /// Creates a node that sends heartbeats, responds to node information requests, and has
+    /// registers
+    fn make_device(&self, id: u8) -> Device {
+        let node_info = GetInfoResponse {
+            protocol_version: Version { major: 1, minor: 0 },
+            hardware_version: Version { major: 0, minor: 0 },
+            software_version: Version {
+                major: 1,
+                minor: id,
+            },
+            software_vcs_revision_id: 0,
+            unique_id: [id; 16],
+            name: heapless::Vec::from_slice(b"org.samcrow.device").unwrap(),
+            software_image_crc: heapless::Vec::new(),
+            certificate_of_authenticity: Default::default(),
+        };
+        let mut node = BasicNode::new(self.make_node(id), node_info).unwrap();
+        RegisterHandler::<Registers>::subscribe_requests(&mut node).unwrap();
+        SimulatedNode::new(node, RegisterHandler::new(Registers::default()))
+            .with_per_second_task(|node, _| node.run_per_second_tasks().unwrap())
+    }
+}
+
+fn node_id(id: u8) -> CanNodeId {
+    CanNodeId::try_from(id).unwrap()
+}
+
+fn seconds(seconds: u64) -> MicrosecondDuration64 {
+    MicrosecondDuration64::new(seconds * 1_000_000)
+}
+
+/// Returns a list of participants with the first participant followed by the devices
+fn participants<'a>(
+    first: &'a mut dyn Participant,
+    devices: &'a mut [Device],
+) -> Vec<&'a mut dyn Participant> {
+    let mut participants = vec![first];
+    participants.extend(
+        devices
+            .iter_mut()
+            .map(|device| device as &mut dyn Participant),
+    );
+    participants
+}
+
+fn real32(value: f32) -> Value {
+    Value::Real32(Real32 {
+        value: heapless::Vec::from_slice(&[value]).unwrap(),
+    })
+}
+
+#[test]
+fn heartbeats_and_node_info() {
+    let setup = Setup::new();
+    let mut monitor_node = {
+        let mut node = setup.make_node(1);
+        let monitor = Monitor::new(&mut node, true).unwrap();
+        SimulatedNode::new(node, monitor).with_step_task(|node, monitor| monitor.poll(node))
+    };
+    let mut devices: Vec<Device> = (2..=4).map(|id| setup.make_device(id)).collect();
+    let mut simulation = setup.lock_step();
+
+    simulation.run_for(
+        MicrosecondDuration64::new(2_500_000),
+        &mut participants(&mut monitor_node, &mut devices),
+    );
+
+    let monitor = monitor_node.handler_mut();
+    let events: Vec<_> = std::iter::from_fn(|| monitor.pop_event()).collect();
+    // All devices sent heartbeats at the same time. The lowest node ID won arbitration.
+    assert_eq!(
+        vec![
+            MonitorEvent::NodeOnline(node_id(2)),
+            MonitorEvent::NodeOnline(node_id(3)),
+            MonitorEvent::NodeOnline(node_id(4)),
+        ],
+        events[..3]
+    );
+    for id in 2..=4 {
+        assert!(events.contains(&MonitorEvent::InfoUpdated(node_id(id))));
+        let status = monitor.node(&node_id(id)).unwrap();
+        assert_eq!(2, status.uptime());
+        assert_eq!(id, status.info().unwrap().software_version.minor);
+        // Heartbeats were sent at 2 seconds, and each took one frame time
+        assert_eq!(
+            Microseconds64::new(2_000_000 + u64::from(id - 1) * FRAME_TIME),
+            status.last_seen()
+        );
+    }
+    assert_eq!(6, events.len());
+    assert_eq!(0, setup.bus.expired_frames());
+
+    // Stop running node 4
+    let mut device_4 = devices.pop().unwrap();
+    simulation.run_for(
+        seconds(4),
+        &mut participants(&mut monitor_node, &mut devices),
+    );
+    let monitor = monitor_node.handler_mut();
+    assert_eq!(
+        Some(MonitorEvent::NodeOffline(node_id(4))),
+        monitor.pop_event()
+    );
+    assert_eq!(None, monitor.pop_event());
+    assert_eq!(6, monitor.node(&node_id(3)).unwrap().uptime());
+
+    // Node 4 comes back with an uptime that continues from where it stopped
+    let mut all = participants(&mut monitor_node, &mut devices);
+    all.push(&mut device_4);
+    simulation.run_for(seconds(2), &mut all);
+    let monitor = monitor_node.handler_mut();
+    assert_eq!(
+        Some(MonitorEvent::NodeOnline(node_id(4))),
+        monitor.pop_event()
+    );
+    assert_eq!(4, monitor.node(&node_id(4)).unwrap().uptime());
+}
+
+#[test]
+fn register_access() {
+    let setup = Setup::new();
+    let mut client_node = {
+        let mut node = setup.make_node(1);
+        let client = RegisterClient::new(&mut node).unwrap();
+        SimulatedNode::new(node, client).with_step_task(|node, client| client.poll(node))
+    };
+    let mut device_2 = setup.make_device(2);
+    let mut device_3 = setup.make_device(3);
+    let mut simulation = setup.lock_step();
+
+    let client = client_node.handler_mut();
+    client.read(node_id(2), "gain");
+    client.write(node_id(3), "gain", real32(0.5));
+    client.read(node_id(5), "gain");
+    let mut events = Vec::new();
+    while !client_node.handler().is_idle() {
+        assert!(simulation.now() < Microseconds64::new(10_000_000));
+        simulation.step(&mut [&mut client_node, &mut device_2, &mut device_3]);
+        events.extend(std::iter::from_fn(|| client_node.handler_mut().pop_event()));
+    }
+
+    assert_eq!(3, events.len());
+    assert!(matches!(
+        &events[0],
+        RegisterEvent::Read { server, name, value, .. }
+            if *server == node_id(2) && name == "gain" && value_as::<f32>(value) == Ok(1.0)
+    ));
+    assert!(matches!(
+        &events[1],
+        RegisterEvent::Written { server, name, .. } if *server == node_id(3) && name == "gain"
+    ));
+    // Node 5 does not exist. The client tried three times, waiting one second for each response.
+    assert!(matches!(
+        &events[2],
+        RegisterEvent::Failed { server, error: RegisterError::Timeout, .. } if *server == node_id(5)
+    ));
+    assert!(simulation.now() >= Microseconds64::new(3_000_000));
+    assert_eq!(0.5, *device_3.handler().block().gain.value());
+    assert_eq!(1.0, *device_2.handler().block().gain.value());
+}
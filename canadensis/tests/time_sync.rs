@@ -0,0 +1,324 @@
+//! Tests of time synchronization, using two nodes connected by a simulated CAN bus
+
+extern crate canadensis;
+extern crate canadensis_can;
+extern crate canadensis_data_types;
+
+mod common;
+
+use std::cell::Cell;
+use std::convert::TryFrom;
+use std::rc::Rc;
+
+use canadensis::client::ServiceClient;
+use canadensis::core::subscription::Subscription;
+use canadensis::core::time::{milliseconds, Clock, Microseconds64};
+use canadensis::core::transfer::ServiceTransfer;
+use canadensis::core::{nb, OutOfMemoryError, Priority};
+use canadensis::node::CoreNode;
+use canadensis::requester::TransferIdFixedMap;
+use canadensis::time_sync::{SynchronizedClock, TimeSyncMaster, TimeSyncSlave};
+use canadensis::{Node, TransferHandler};
+use canadensis_can::driver::{ReceiveDriver, TransmitDriver};
+use canadensis_can::{CanNodeId, CanReceiver, CanTransmitter, CanTransport, Frame, Mtu};
+use canadensis_data_types::uavcan::time::get_synchronization_master_info_0_1::{
+    self, GetSynchronizationMasterInfoRequest, GetSynchronizationMasterInfoResponse,
+};
+use canadensis_data_types::uavcan::time::time_system_0_1::TimeSystem;
+
+use common::{make_bus, TestDriver};
+
+/// A clock with an offset and drift relative to a shared true time
+#[derive(Clone)]
+struct DriftingClock {
+    /// True time in microseconds
+    time: Rc<Cell<u64>>,
+    offset: u64,
+    drift_ppm: u64,
+}
+
+impl DriftingClock {
+    /// Converts a true time into the time of this clock
+    fn local_time(&self, time: Microseconds64) -> Microseconds64 {
+        let time = time.as_microseconds();
+        Microseconds64::new(self.offset + time + time * self.drift_ppm / 1_000_000)
+    }
+
+    /// Converts a time of this clock into a true time, relative to the current time
+    fn true_time(&self, local: Microseconds64) -> Microseconds64 {
+        let now = self.time.get();
+        let local_now = self.local_time(Microseconds64::new(now)).as_microseconds();
+        Microseconds64::new((now + local.as_microseconds()).saturating_sub(local_now))
+    }
+
+    /// Converts the timestamp of a frame from true time into the time of this clock
+    fn local_frame(&self, mut frame: Frame<Microseconds64>) -> Frame<Microseconds64> {
+        frame.set_timestamp(self.local_time(frame.timestamp()));
+        frame
+    }
+}
+
+impl Clock for DriftingClock {
+    type Instant = Microseconds64;
+
+    fn now(&mut self) -> Self::Instant {
+        self.local_time(Microseconds64::new(self.time.get()))
+    }
+}
+
+/// A driver for a node with a drifting clock
+///
+/// The simulated bus uses the true time. This driver converts the deadlines of outgoing frames
+/// and the timestamps of incoming frames between the true time and the time of the node's clock.
+struct DriftingDriver {
+    inner: TestDriver,
+    clock: DriftingClock,
+}
+
+impl TransmitDriver<Microseconds64> for DriftingDriver {
+    type Error = <TestDriver as TransmitDriver<Microseconds64>>::Error;
+
+    fn try_reserve(&mut self, frames: usize) -> Result<(), OutOfMemoryError> {
+        self.inner.try_reserve(frames)
+    }
+
+    fn transmit(
+        &mut self,
+        mut frame: Frame<Microseconds64>,
+        _now: Microseconds64,
+    ) -> nb::Result<Option<Frame<Microseconds64>>, Self::Error> {
+        frame.set_timestamp(self.clock.true_time(frame.timestamp()));
+        let now = Microseconds64::new(self.clock.time.get());
+        self.inner
+            .transmit(frame, now)
+            .map(|removed| removed.map(|frame| self.clock.local_frame(frame)))
+    }
+
+    fn flush(&mut self, _now: Microseconds64) -> nb::Result<(), Self::Error> {
+        self.inner.flush(Microseconds64::new(self.clock.time.get()))
+    }
+
+    fn pop_loopback(&mut self) -> Option<Frame<Microseconds64>> {
+        self.inner
+            .pop_loopback()
+            .map(|frame| self.clock.local_frame(frame))
+    }
+}
+
+impl ReceiveDriver<Microseconds64> for DriftingDriver {
+    type Error = <TestDriver as ReceiveDriver<Microseconds64>>::Error;
+
+    fn receive(&mut self, _now: Microseconds64) -> nb::Result<Frame<Microseconds64>, Self::Error> {
+        self.inner
+            .receive(Microseconds64::new(self.clock.time.get()))
+            .map(|frame| self.clock.local_frame(frame))
+    }
+
+    fn apply_filters<S>(&mut self, local_node: Option<CanNodeId>, subscriptions: S)
+    where
+        S: IntoIterator<Item = Subscription>,
+    {
+        self.inner.apply_filters(local_node, subscriptions)
+    }
+
+    fn apply_accept_all(&mut self) {
+        self.inner.apply_accept_all()
+    }
+}
+
+type TestNode = CoreNode<
+    DriftingClock,
+    CanTransmitter<Microseconds64, DriftingDriver>,
+    CanReceiver<Microseconds64, DriftingDriver>,
+    TransferIdFixedMap<CanTransport, 4>,
+    DriftingDriver,
+    4,
+    4,
+>;
+
+struct Setup {
+    time: Rc<Cell<u64>>,
+    master_node: TestNode,
+    master: TimeSyncMaster<Microseconds64>,
+    slave_node: TestNode,
+    slave: TimeSyncSlave<Microseconds64, CanTransport>,
+}
+
+impl Setup {
+    fn new() -> Self {
+        let time = Rc::new(Cell::new(0));
+        let bus = make_bus();
+        let mut master_node = make_node(1, bus.add_driver(), &time, 5_000_000, 0);
+        // The slave clock runs 100 ppm fast
+        let mut slave_node = make_node(2, bus.add_driver(), &time, 1_000, 100);
+        let master = TimeSyncMaster::new(&mut master_node).unwrap();
+        TimeSyncMaster::subscribe_requests(&mut master_node).unwrap();
+        let slave = TimeSyncSlave::new(&mut slave_node).unwrap();
+        Setup {
+            time,
+            master_node,
+            master,
+            slave_node,
+            slave,
+        }
+    }
+
+    fn run(&mut self, milliseconds: u32) {
+        for _ in 0..milliseconds {
+            self.time.set(self.time.get() + 1000);
+            self.master.poll(&mut self.master_node).unwrap();
+            self.master_node.flush().unwrap();
+            // Let the master find out when its messages were transmitted
+            self.master_node.receive(&mut self.master).unwrap();
+            for _ in 0..4 {
+                self.slave_node.receive(&mut self.slave).unwrap();
+            }
+        }
+    }
+
+    /// Returns the synchronized time according to the master and the slave
+    fn synchronized_times(&mut self) -> (Option<u64>, Option<u64>) {
+        let master_now = self.master_node.clock_mut().now();
+        let slave_now = self.slave_node.clock_mut().now();
+        (
+            self.master.synchronized_time(master_now),
+            self.slave.synchronized_time(slave_now),
+        )
+    }
+}
+
+fn make_node(
+    id: u8,
+    driver: TestDriver,
+    time: &Rc<Cell<u64>>,
+    offset: u64,
+    drift_ppm: u64,
+) -> TestNode {
+    let node_id = CanNodeId::try_from(id).unwrap();
+    let clock = DriftingClock {
+        time: time.clone(),
+        offset,
+        drift_ppm,
+    };
+    CoreNode::new(
+        clock.clone(),
+        node_id,
+        CanTransmitter::new(Mtu::Can8),
+        CanReceiver::new(node_id, Mtu::Can8),
+        DriftingDriver {
+            inner: driver,
+            clock,
+        },
+    )
+}
+
+#[test]
+fn slave_follows_master() {
+    let mut setup = Setup::new();
+    // The first message has no timestamp, and the second one provides the offset
+    setup.run(500);
+    assert!(!setup.slave.is_synchronized());
+    assert_eq!(
+        Some(CanNodeId::try_from(1u8).unwrap()),
+        setup.slave.master()
+    );
+    setup.run(1000);
+    assert!(setup.slave.is_synchronized());
+
+    // After a few more messages, the slave has also estimated the drift
+    setup.run(5000);
+    let (master_time, slave_time) = setup.synchronized_times();
+    let master_time = master_time.unwrap();
+    let slave_time = slave_time.unwrap();
+    assert!(
+        master_time.abs_diff(slave_time) <= 10,
+        "master {}, slave {}",
+        master_time,
+        slave_time
+    );
+    let drift = setup.slave.reference().unwrap().drift();
+    assert!((drift + 100e-6).abs() < 5e-6, "drift {}", drift);
+
+    // The slave keeps up when the master changes its time
+    let master_now = setup.master_node.clock_mut().now();
+    setup.master.set_time(master_now, 1_000_000_000);
+    setup.run(3000);
+    let (master_time, slave_time) = setup.synchronized_times();
+    assert!(master_time.unwrap() > 1_000_000_000);
+    assert!(master_time.unwrap().abs_diff(slave_time.unwrap()) <= 10);
+}
+
+type InfoClient = ServiceClient<
+    Microseconds64,
+    CanTransport,
+    GetSynchronizationMasterInfoRequest,
+    GetSynchronizationMasterInfoResponse,
+    1,
+>;
+
+/// Passes responses to a client and collects the time systems
+struct ClientHandler<'c> {
+    client: &'c mut InfoClient,
+    time_systems: Vec<u8>,
+}
+
+impl TransferHandler<Microseconds64, CanTransport> for ClientHandler<'_> {
+    fn handle_response<N: Node<Instant = Microseconds64, Transport = CanTransport>>(
+        &mut self,
+        _node: &mut N,
+        transfer: &ServiceTransfer<Vec<u8>, Microseconds64, CanTransport>,
+    ) -> bool {
+        match self.client.handle_response(transfer) {
+            Some(result) => {
+                let response = result.unwrap().payload;
+                assert_eq!(0.25, response.error_variance);
+                assert_eq!(37, response.tai_info.difference_tai_minus_utc);
+                self.time_systems.push(response.time_system.value);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[test]
+fn master_info() {
+    let mut setup = Setup::new();
+    setup.master.set_time_system(
+        TimeSystem {
+            value: TimeSystem::TAI,
+        },
+        37,
+    );
+    setup.master.set_error_variance(0.25);
+    let token = setup
+        .slave_node
+        .start_sending_requests(
+            get_synchronization_master_info_0_1::SERVICE,
+            milliseconds(1000),
+            192,
+            Priority::Nominal,
+        )
+        .unwrap();
+    let mut client = InfoClient::new(token, milliseconds(1000));
+    client
+        .send(
+            &mut setup.slave_node,
+            &GetSynchronizationMasterInfoRequest {},
+            CanNodeId::try_from(1u8).unwrap(),
+        )
+        .unwrap();
+    setup.slave_node.flush().unwrap();
+    for _ in 0..8 {
+        setup.master_node.receive(&mut setup.master).unwrap();
+    }
+    setup.master_node.flush().unwrap();
+    let mut handler = ClientHandler {
+        client: &mut client,
+        time_systems: Vec::new(),
+    };
+    for _ in 0..8 {
+        setup.slave_node.receive(&mut handler).unwrap();
+    }
+    assert_eq!(vec![TimeSystem::TAI], handler.time_systems);
+}
@@ -0,0 +1,112 @@
+//! Tests of the transport statistics handler, using two nodes connected by a simulated CAN bus
+
+extern crate canadensis;
+extern crate canadensis_can;
+extern crate canadensis_data_types;
+
+mod common;
+
+use canadensis::core::time::{milliseconds, Microseconds64, SimulatedClock};
+use canadensis::core::transfer::ServiceTransfer;
+use canadensis::core::Priority;
+use canadensis::encoding::Deserialize;
+use canadensis::statistics::TransportStatisticsHandler;
+use canadensis::{Node, ServiceToken, TransferHandler};
+use canadensis_can::CanTransport;
+use canadensis_data_types::uavcan::node::get_transport_statistics_0_1::{
+    self, GetTransportStatisticsRequest, GetTransportStatisticsResponse,
+};
+use canadensis_data_types::uavcan::node::io_statistics_0_1::IOStatistics;
+
+use common::{node_id, node_pair, TestNode};
+
+/// Collects received statistics responses
+#[derive(Default)]
+struct ResponseCollector {
+    responses: Vec<GetTransportStatisticsResponse>,
+}
+
+impl TransferHandler<Microseconds64, CanTransport> for ResponseCollector {
+    fn handle_response<N: Node<Instant = Microseconds64, Transport = CanTransport>>(
+        &mut self,
+        _node: &mut N,
+        transfer: &ServiceTransfer<Vec<u8>, Microseconds64, CanTransport>,
+    ) -> bool {
+        self.responses.push(
+            GetTransportStatisticsResponse::deserialize_from_bytes(&transfer.payload).unwrap(),
+        );
+        true
+    }
+}
+
+struct Setup {
+    node: TestNode,
+    handler: TransportStatisticsHandler,
+    client_node: TestNode,
+    token: ServiceToken<GetTransportStatisticsRequest>,
+    collector: ResponseCollector,
+}
+
+impl Setup {
+    fn new() -> Self {
+        let (mut node, mut client_node) = node_pair(&SimulatedClock::new());
+        TransportStatisticsHandler::subscribe_requests(&mut node).unwrap();
+        let token = client_node
+            .start_sending_requests(
+                get_transport_statistics_0_1::SERVICE,
+                milliseconds(1000),
+                192,
+                Priority::Nominal,
+            )
+            .unwrap();
+        Setup {
+            node,
+            handler: TransportStatisticsHandler::new(),
+            client_node,
+            token,
+            collector: ResponseCollector::default(),
+        }
+    }
+
+    /// Sends a request and returns the response
+    fn request(&mut self) -> GetTransportStatisticsResponse {
+        self.client_node
+            .send_request(&self.token, &GetTransportStatisticsRequest {}, node_id(1))
+            .unwrap();
+        self.client_node.flush().unwrap();
+        for _ in 0..16 {
+            self.node.receive(&mut self.handler).unwrap();
+        }
+        self.node.flush().unwrap();
+        for _ in 0..16 {
+            self.client_node.receive(&mut self.collector).unwrap();
+        }
+        assert_eq!(1, self.collector.responses.len());
+        self.collector.responses.pop().unwrap()
+    }
+}
+
+fn assert_io_statistics(expected: (u64, u64, u64), actual: &IOStatistics) {
+    assert_eq!(
+        expected,
+        (actual.num_emitted, actual.num_received, actual.num_errored)
+    );
+}
+
+#[test]
+fn counts_transfers_and_frames() {
+    let mut setup = Setup::new();
+    // The statistics are read when the request is handled, so they include the request but not
+    // the response
+    let response = setup.request();
+    assert_io_statistics((0, 1, 0), &response.transfer_statistics);
+    assert_eq!(1, response.network_interface_statistics.len());
+    assert_io_statistics((0, 1, 0), &response.network_interface_statistics[0]);
+
+    // The first response has been counted. With classic CAN, the 31-byte response and its CRC
+    // take 5 frames.
+    let response = setup.request();
+    assert_io_statistics((1, 2, 0), &response.transfer_statistics);
+    assert_eq!(1, response.network_interface_statistics.len());
+    assert_io_statistics((5, 2, 0), &response.network_interface_statistics[0]);
+}
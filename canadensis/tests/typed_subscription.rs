@@ -0,0 +1,156 @@
+//! Tests of typed subscriptions, using two nodes connected by a simulated CAN bus
+
+extern crate canadensis;
+extern crate canadensis_can;
+extern crate canadensis_data_types;
+extern crate heapless;
+
+mod common;
+
+use std::convert::TryFrom;
+
+use canadensis::core::time::{milliseconds, Microseconds64, SimulatedClock};
+use canadensis::core::transfer::MessageTransfer;
+use canadensis::core::{Priority, SubjectId};
+use canadensis::typed::MessageAdapter;
+use canadensis::{Node, TransferHandler};
+use canadensis_can::{CanNodeId, CanTransport};
+use canadensis_data_types::uavcan::primitive::scalar::integer32_1_0::Integer32;
+use canadensis_data_types::uavcan::primitive::string_1_0::String as CyphalString;
+
+use common::{node_pair, TestNode};
+
+/// A boxed message handler that can be used as a trait object
+type BoxedHandler<'a> =
+    Box<dyn FnMut(&MessageTransfer<Integer32, Microseconds64, CanTransport>) + 'a>;
+
+fn make_connected_nodes() -> (TestNode, TestNode) {
+    node_pair(&SimulatedClock::new())
+}
+
+fn receive_all<H>(node: &mut TestNode, handler: &mut H)
+where
+    H: TransferHandler<Microseconds64, CanTransport>,
+{
+    for _ in 0..64 {
+        node.receive(handler).unwrap();
+    }
+}
+
+#[test]
+fn closure_receives_messages() {
+    let (mut publisher, mut subscriber) = make_connected_nodes();
+    let subject = SubjectId::try_from(300).unwrap();
+    let token = subscriber
+        .subscribe::<CyphalString>(subject, milliseconds(1000))
+        .unwrap();
+    assert_eq!(subject, token.subject_id());
+    let publish_token = publisher
+        .start_publishing::<CyphalString>(subject, milliseconds(1000), Priority::Nominal)
+        .unwrap();
+
+    // Long enough to need many frames
+    let text = [b'c'; 200];
+    publisher
+        .publish(
+            &publish_token,
+            &CyphalString {
+                value: heapless::Vec::from_slice(&text).unwrap(),
+            },
+        )
+        .unwrap();
+    publisher.flush().unwrap();
+
+    let mut received = Vec::new();
+    {
+        let mut adapter = MessageAdapter::new(
+            &token,
+            |transfer: &MessageTransfer<CyphalString, Microseconds64, CanTransport>| {
+                received.push((transfer.header.source, transfer.payload.value.to_vec()));
+            },
+        );
+        receive_all(&mut subscriber, &mut adapter);
+        assert_eq!(0, adapter.deserialize_errors());
+    }
+    assert_eq!(
+        vec![(Some(CanNodeId::try_from(1u8).unwrap()), text.to_vec())],
+        received
+    );
+}
+
+#[test]
+fn trait_objects_chained() {
+    let (mut publisher, mut subscriber) = make_connected_nodes();
+    let subject_a = SubjectId::try_from(10).unwrap();
+    let subject_b = SubjectId::try_from(11).unwrap();
+    let token_a = subscriber
+        .subscribe::<Integer32>(subject_a, milliseconds(1000))
+        .unwrap();
+    let token_b = subscriber
+        .subscribe::<Integer32>(subject_b, milliseconds(1000))
+        .unwrap();
+    let publish_a = publisher
+        .start_publishing::<Integer32>(subject_a, milliseconds(1000), Priority::Nominal)
+        .unwrap();
+    let publish_b = publisher
+        .start_publishing::<Integer32>(subject_b, milliseconds(1000), Priority::Nominal)
+        .unwrap();
+    publisher
+        .publish(&publish_a, &Integer32 { value: 1 })
+        .unwrap();
+    publisher
+        .publish(&publish_b, &Integer32 { value: 2 })
+        .unwrap();
+    publisher
+        .publish(&publish_a, &Integer32 { value: 3 })
+        .unwrap();
+    publisher.flush().unwrap();
+
+    let mut values_a = Vec::new();
+    let mut values_b = Vec::new();
+    {
+        let handler_a: BoxedHandler<'_> =
+            Box::new(|transfer| values_a.push(transfer.payload.value));
+        let mut handle_b = |transfer: &MessageTransfer<Integer32, Microseconds64, CanTransport>| {
+            values_b.push(transfer.payload.value)
+        };
+        let handler_b: &mut dyn FnMut(&MessageTransfer<Integer32, Microseconds64, CanTransport>) =
+            &mut handle_b;
+        let mut handler = MessageAdapter::new(&token_a, handler_a)
+            .chain(MessageAdapter::new(&token_b, handler_b));
+        receive_all(&mut subscriber, &mut handler);
+    }
+    assert_eq!(vec![1, 3], values_a);
+    assert_eq!(vec![2], values_b);
+}
+
+#[test]
+fn deserialize_errors_counted() {
+    let (mut publisher, mut subscriber) = make_connected_nodes();
+    let subject = SubjectId::try_from(300).unwrap();
+    let token = subscriber
+        .subscribe::<CyphalString>(subject, milliseconds(1000))
+        .unwrap();
+    // Publish a different type on the same subject. As a string, this has a length that is
+    // too large.
+    let publish_token = publisher
+        .start_publishing::<Integer32>(subject, milliseconds(1000), Priority::Nominal)
+        .unwrap();
+    for _ in 0..2 {
+        publisher
+            .publish(&publish_token, &Integer32 { value: 0xffff })
+            .unwrap();
+    }
+    publisher.flush().unwrap();
+
+    let mut message_count = 0;
+    {
+        let mut adapter = MessageAdapter::new(
+            &token,
+            |_: &MessageTransfer<CyphalString, Microseconds64, CanTransport>| message_count += 1,
+        );
+        receive_all(&mut subscriber, &mut adapter);
+        assert_eq!(2, adapter.deserialize_errors());
+    }
+    assert_eq!(0, message_count);
+}
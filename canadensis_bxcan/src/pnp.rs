@@ -4,10 +4,13 @@
 
 use crate::BxCanDriver;
 use bxcan::{Can, FilterOwner, Instance};
+use canadensis::anonymous::AnonymousPublishError;
+use canadensis::core::nb;
 use canadensis::core::time::Clock;
 use canadensis_can::queue::{SingleFrameQueue, SingleQueueDriver};
 use canadensis_can::{CanNodeId, CanReceiver, CanTransmitter, CanTransport, Error, Mtu};
-use canadensis_pnp_client::{AllocationMessage, PnpClient};
+use canadensis_pnp_client::{AllocationMessage, PnpClient, PnpClientError};
+use core::convert::Infallible;
 
 /// A plug-and-play node ID assignment client that uses a bxCAN peripheral
 pub struct BxCanPnpClient<C: Clock, M, I: Instance + FilterOwner> {
@@ -52,10 +55,21 @@ where
     /// Publishes an ID allocation request, sending it onto the CAN bus
     ///
     /// This function returns an error if no transmit mailbox was available for the frame.
-    pub fn publish_request(&mut self) {
+    pub fn publish_request(&mut self) -> nb::Result<(), AnonymousPublishError<Error<Infallible>>> {
         self.client.send_request(&mut self.clock, &mut self.driver)
     }
 
+    /// Runs the allocation procedure, sending requests at random intervals and handling
+    /// incoming frames
+    ///
+    /// This function should be called frequently. It returns a node ID when one has been
+    /// allocated.
+    pub fn poll(
+        &mut self,
+    ) -> Result<Option<CanNodeId>, PnpClientError<Error<Infallible>, Error<()>>> {
+        self.client.poll(&mut self.clock, &mut self.driver)
+    }
+
     /// Handles and parses incoming CAN frames, and returns a node ID if one was received
     pub fn handle_incoming_frames(&mut self, now: C::Instant) -> Option<CanNodeId> {
         match self.client.receive(now, &mut self.driver) {
@@ -71,10 +71,13 @@ pub const FRAME_CAPACITY: usize = 8;
 pub struct Frame<I> {
     /// For RX frames: reception timestamp.
     /// For TX frames: transmission deadline.
+    /// For loopback frames: the time when the frame was transmitted.
     /// The time system may be arbitrary as long as the clock is monotonic (steady).
     timestamp: I,
     /// 29-bit extended ID
     id: CanId,
+    /// True if the driver should report when this frame has been transmitted
+    loopback: bool,
     /// The frame data
     data: heapless::Vec<u8, FRAME_CAPACITY>,
 }
@@ -88,9 +91,29 @@ impl<I> Frame<I> {
         Frame {
             timestamp,
             id,
+            loopback: false,
             data: heapless::Vec::from_slice(data).expect("Data to large for a frame"),
         }
     }
+    /// Sets the timestamp of this frame
+    ///
+    /// A driver can use this function to record the time when a loopback frame was transmitted.
+    #[inline]
+    pub fn set_timestamp(&mut self, timestamp: I) {
+        self.timestamp = timestamp;
+    }
+    /// Sets the loopback flag
+    ///
+    /// If loopback is true, the driver should report when this frame has been transmitted.
+    #[inline]
+    pub fn set_loopback(&mut self, loopback: bool) {
+        self.loopback = loopback;
+    }
+    /// Returns true if the driver should report when this frame has been transmitted
+    #[inline]
+    pub fn loopback(&self) -> bool {
+        self.loopback
+    }
     /// Returns the ID of this frame
     #[inline]
     pub fn id(&self) -> CanId {
@@ -34,6 +34,16 @@ pub trait TransmitDriver<I> {
     /// Attempts to flush all frames out of any in-memory queues that may exist and transmit
     /// them
     fn flush(&mut self, now: I) -> nb::Result<(), Self::Error>;
+
+    /// Removes and returns a frame that had its loopback flag set and has been transmitted
+    ///
+    /// The timestamp of the returned frame is the time when the frame was transmitted.
+    ///
+    /// The default implementation always returns None, which is appropriate for drivers that
+    /// do not support loopback.
+    fn pop_loopback(&mut self) -> Option<Frame<I>> {
+        None
+    }
 }
 
 /// A CAN driver that can receive frames
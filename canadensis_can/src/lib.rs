@@ -29,6 +29,7 @@ pub mod driver;
 pub mod queue;
 pub mod redundant;
 mod rx;
+pub mod simulated;
 mod tx;
 mod types;
 
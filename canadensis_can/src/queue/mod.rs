@@ -101,6 +101,10 @@ where
     fn flush(&mut self, now: I) -> nb::Result<(), Self::Error> {
         flush_single_queue(&mut self.queue, &mut self.driver, now)
     }
+
+    fn pop_loopback(&mut self) -> Option<Frame<I>> {
+        self.driver.pop_loopback()
+    }
 }
 
 impl<I, Q, D> ReceiveDriver<I> for SingleQueueDriver<Q, D>
@@ -27,6 +27,12 @@ use heapless::Deque;
 /// * `TC`: The transmit queue capacity, in frames
 /// * `RC`: The receive queue capacity, in frames
 ///
+/// ## Loopback
+///
+/// When external code transmits a frame whose [`loopback`](Frame::loopback) flag is set, it should
+/// set the frame timestamp to the time of transmission and pass the frame to
+/// [`push_loopback_frame`](#method.push_loopback_frame).
+///
 /// ## Filters
 ///
 /// When a driver calls `apply_filters()`, this struct stores a `Vec` of the current subscriptions.
@@ -36,6 +42,8 @@ use heapless::Deque;
 pub struct QueueOnlyDriver<I, const TC: usize, const RC: usize> {
     tx_queue: ArrayQueue<I, TC>,
     rx_queue: Deque<Frame<I>, RC>,
+    /// Transmitted frames with the loopback flag set
+    loopback_queue: Deque<Frame<I>, TC>,
     subscriptions: Option<Vec<Subscription>>,
 }
 
@@ -45,6 +53,7 @@ impl<I: Default + Clone, const TC: usize, const RC: usize> QueueOnlyDriver<I, TC
         QueueOnlyDriver {
             tx_queue: ArrayQueue::new(),
             rx_queue: Deque::new(),
+            loopback_queue: Deque::new(),
             subscriptions: None,
         }
     }
@@ -69,6 +78,15 @@ impl<I: Default + Clone, const TC: usize, const RC: usize> QueueOnlyDriver<I, TC
         self.tx_queue.return_frame(frame)
     }
 
+    /// Records that a frame with the loopback flag set has been transmitted
+    ///
+    /// The timestamp of the frame should be the time when it was transmitted.
+    pub fn push_loopback_frame(&mut self, frame: Frame<I>) -> Result<(), OutOfMemoryError> {
+        self.loopback_queue
+            .push_back(frame)
+            .map_err(|_| OutOfMemoryError)
+    }
+
     /// Returns the subscriptions provided in the last call to `apply_filters()`
     ///
     /// This function returns None
@@ -99,6 +117,10 @@ impl<I: Default + Clone, const TC: usize, const RC: usize> TransmitDriver<I>
         // Can't do anything here. Frames have to be removed externally.
         Ok(())
     }
+
+    fn pop_loopback(&mut self) -> Option<Frame<I>> {
+        self.loopback_queue.pop_front()
+    }
 }
 
 impl<I: Default + Clone, const TC: usize, const RC: usize> ReceiveDriver<I>
@@ -1,5 +1,6 @@
 use crate::Frame;
 use canadensis_core::time::Instant;
+use canadensis_core::transport::{Statistics, TransportStatistics};
 
 /// Deduplicates incoming frames from multiple transports
 ///
@@ -18,6 +19,9 @@ use canadensis_core::time::Instant;
 /// After the timeout expires, the first transport that receives a frame becomes active. The newly
 /// active transport may be the same transport that was previously active, or a different one.
 ///
+/// The deduplicator counts the frames received on each transport, and reports them through its
+/// [`TransportStatistics`] implementation.
+///
 /// For more explanation, see [the comments in pycyphal](https://github.com/OpenCyphal/pycyphal/blob/87c27a978119d24ac77c9a7f2d6f289846ac96fd/pyuavcan/transport/redundant/__init__.py).
 ///
 #[derive(Debug)]
@@ -58,7 +62,9 @@ where
     /// discarded.
     pub fn accept(&mut self, frame: &Frame<I>, index: usize) -> bool {
         // Update frame time
-        self.states[index].last_frame_time = Some(frame.timestamp());
+        let state = &mut self.states[index];
+        state.last_frame_time = Some(frame.timestamp());
+        state.frame_count = state.frame_count.wrapping_add(1);
 
         if self.active_transport_timed_out(frame.timestamp()) {
             // The transport that received this frame is definitely doing something. Switch to it.
@@ -83,16 +89,50 @@ where
     }
 }
 
+impl<I, const N: usize> TransportStatistics for Deduplicator<I, N>
+where
+    I: Instant,
+{
+    /// Returns the total number of frames received on all transports
+    fn statistics(&self) -> Statistics {
+        Statistics {
+            transfers: 0,
+            frames: self
+                .states
+                .iter()
+                .fold(0u64, |sum, state| sum.wrapping_add(state.frame_count)),
+            errors: 0,
+        }
+    }
+
+    fn interface_count(&self) -> usize {
+        N
+    }
+
+    /// Returns the number of frames received on a transport, including frames that were
+    /// discarded because the transport was not active
+    fn interface_statistics(&self, interface: usize) -> Option<Statistics> {
+        self.states.get(interface).map(|state| Statistics {
+            transfers: 0,
+            frames: state.frame_count,
+            errors: 0,
+        })
+    }
+}
+
 /// Information about a transport
 #[derive(Debug, Copy, Clone)]
 struct TransportState<I> {
     last_frame_time: Option<I>,
+    /// The number of frames received on this transport
+    frame_count: u64,
 }
 
 impl<I> Default for TransportState<I> {
     fn default() -> Self {
         TransportState {
             last_frame_time: None,
+            frame_count: 0,
         }
     }
 }
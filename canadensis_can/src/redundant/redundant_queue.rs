@@ -1,5 +1,7 @@
+use crate::data::FRAME_CAPACITY;
 use crate::driver::TransmitDriver;
-use crate::Frame;
+use crate::{CanId, Frame};
+use canadensis_core::transport::{Statistics, TransportStatistics};
 use canadensis_core::{nb, OutOfMemoryError};
 
 /// An aggregation of two outgoing frame queues that can be used for double-redundant transports
@@ -9,6 +11,13 @@ use canadensis_core::{nb, OutOfMemoryError};
 ///
 /// Double-redundant drivers can be nested for use with triple-redundant transports.
 ///
+/// The driver counts the frames transmitted on each interface, and reports them through its
+/// [`TransportStatistics`] implementation. A nested driver counts as one interface.
+///
+/// When a loopback frame is transmitted on both interfaces, the driver reports only the copy
+/// that one of the interfaces reports first. The driver remembers up to 8 of these frames
+/// until the other interface also reports them.
+///
 pub struct RedundantDriver<D0, D1> {
     /// Driver 0
     driver0: D0,
@@ -18,6 +27,36 @@ pub struct RedundantDriver<D0, D1> {
     status0: Result<(), OutOfMemoryError>,
     /// Result of the last try_reserve() call on driver 1
     status1: Result<(), OutOfMemoryError>,
+    /// Frames transmitted and errors on driver 0 and driver 1
+    statistics: [Statistics; 2],
+    /// Loopback frames that were transmitted on both interfaces, oldest first
+    loopback_copies: heapless::Vec<LoopbackCopy, LOOPBACK_CAPACITY>,
+}
+
+/// The maximum number of loopback frames sent on both interfaces that a driver remembers
+const LOOPBACK_CAPACITY: usize = 8;
+
+/// A loopback frame that was transmitted on both interfaces
+struct LoopbackCopy {
+    id: CanId,
+    data: heapless::Vec<u8, FRAME_CAPACITY>,
+    /// The index of the interface that reported this frame first, if any
+    reported_by: Option<usize>,
+}
+
+impl LoopbackCopy {
+    fn new<I>(frame: &Frame<I>) -> Self {
+        LoopbackCopy {
+            id: frame.id(),
+            // The frame data always fits
+            data: heapless::Vec::from_slice(frame.data()).unwrap(),
+            reported_by: None,
+        }
+    }
+
+    fn matches<I>(&self, frame: &Frame<I>) -> bool {
+        self.id == frame.id() && self.data[..] == *frame.data()
+    }
 }
 
 impl<D0, D1> RedundantDriver<D0, D1> {
@@ -28,6 +67,60 @@ impl<D0, D1> RedundantDriver<D0, D1> {
             driver1,
             status0: Ok(()),
             status1: Ok(()),
+            statistics: [Statistics::default(); 2],
+            loopback_copies: heapless::Vec::new(),
+        }
+    }
+
+    /// Returns a reference to driver 0
+    pub fn driver0(&self) -> &D0 {
+        &self.driver0
+    }
+    /// Returns a mutable reference to driver 0
+    pub fn driver0_mut(&mut self) -> &mut D0 {
+        &mut self.driver0
+    }
+    /// Returns a reference to driver 1
+    pub fn driver1(&self) -> &D1 {
+        &self.driver1
+    }
+    /// Returns a mutable reference to driver 1
+    pub fn driver1_mut(&mut self) -> &mut D1 {
+        &mut self.driver1
+    }
+
+    /// Records a loopback frame that was transmitted on both interfaces
+    fn add_loopback_copy(&mut self, copy: LoopbackCopy) {
+        if self.loopback_copies.is_full() {
+            // One interface did not report the oldest frame, so forget about it
+            self.loopback_copies.remove(0);
+        }
+        let _ = self.loopback_copies.push(copy);
+    }
+
+    /// Returns true if a loopback frame from an interface should be reported
+    ///
+    /// A frame is not reported if it was transmitted on both interfaces and the other interface
+    /// has already reported it.
+    fn report_loopback<I>(&mut self, frame: &Frame<I>, interface: usize) -> bool {
+        let index = self
+            .loopback_copies
+            .iter()
+            .position(|copy| copy.matches(frame) && copy.reported_by != Some(interface));
+        match index {
+            Some(index) => match self.loopback_copies[index].reported_by {
+                Some(_) => {
+                    // The other interface already reported this frame
+                    self.loopback_copies.remove(index);
+                    false
+                }
+                None => {
+                    self.loopback_copies[index].reported_by = Some(interface);
+                    true
+                }
+            },
+            // Transmitted on only one interface
+            None => true,
         }
     }
 }
@@ -56,6 +149,11 @@ where
     /// [`try_reserve`](#method.try_reserve), this function does not attempt to push a frame onto
     /// that queue.
     fn transmit(&mut self, frame: Frame<I>, now: I) -> nb::Result<Option<Frame<I>>, Self::Error> {
+        let loopback_copy = if frame.loopback() {
+            Some(LoopbackCopy::new(&frame))
+        } else {
+            None
+        };
         // If a queue failed to reserve memory in the last call to try_reserve(),
         // don't try to push the frame there.
         let push_status_0 = if self.status0.is_ok() {
@@ -68,6 +166,11 @@ where
         } else {
             Err(nb::Error::WouldBlock)
         };
+        count_frame(&mut self.statistics[0], &push_status_0);
+        count_frame(&mut self.statistics[1], &push_status_1);
+        if let (Ok(_), Ok(_), Some(copy)) = (&push_status_0, &push_status_1, loopback_copy) {
+            self.add_loopback_copy(copy);
+        }
         // This is successful if the frame got onto at least one queue.
         // If two frames were returned, send the first and ignore the second.
         match (push_status_0, push_status_1) {
@@ -98,6 +201,50 @@ where
             (_, Err(nb::Error::Other(e))) => Err(nb::Error::Other(RedundantError::Driver1(e))),
         }
     }
+
+    /// Returns a loopback frame from driver 0 or driver 1
+    ///
+    /// A frame that was transmitted on both interfaces is returned only once.
+    fn pop_loopback(&mut self) -> Option<Frame<I>> {
+        loop {
+            let (frame, interface) = match self.driver0.pop_loopback() {
+                Some(frame) => (frame, 0),
+                None => (self.driver1.pop_loopback()?, 1),
+            };
+            if self.report_loopback(&frame, interface) {
+                return Some(frame);
+            }
+        }
+    }
+}
+
+impl<D0, D1> TransportStatistics for RedundantDriver<D0, D1> {
+    /// Returns the sums of the frame and error counts from both interfaces
+    fn statistics(&self) -> Statistics {
+        let [statistics0, statistics1] = &self.statistics;
+        Statistics {
+            transfers: 0,
+            frames: statistics0.frames.wrapping_add(statistics1.frames),
+            errors: statistics0.errors.wrapping_add(statistics1.errors),
+        }
+    }
+
+    fn interface_count(&self) -> usize {
+        2
+    }
+
+    fn interface_statistics(&self, interface: usize) -> Option<Statistics> {
+        self.statistics.get(interface).copied()
+    }
+}
+
+/// Updates the statistics for one interface based on the result of transmitting a frame
+fn count_frame<T, E>(statistics: &mut Statistics, status: &nb::Result<T, E>) {
+    match status {
+        Ok(_) => statistics.frames = statistics.frames.wrapping_add(1),
+        Err(nb::Error::Other(_)) => statistics.errors = statistics.errors.wrapping_add(1),
+        Err(nb::Error::WouldBlock) => {}
+    }
 }
 
 /// An error from a DoubleRedundantQueueDriver
@@ -21,7 +21,7 @@ use crate::types::{CanNodeId, CanTransferId, CanTransport, Error};
 use crate::Mtu;
 use canadensis_core::time::Instant;
 use canadensis_core::transfer::{Header, MessageHeader, ServiceHeader, Transfer};
-use canadensis_core::transport::Receiver;
+use canadensis_core::transport::{Receiver, Statistics};
 use canadensis_core::{
     nb, OutOfMemoryError, PortId, Priority, ServiceId, ServiceSubscribeError, SubjectId,
 };
@@ -46,6 +46,8 @@ pub struct CanReceiver<I: Instant, D> {
     /// Errors include failure to allocate memory (when handling incoming frames only), missing
     /// frames, and malformed frames.
     error_count: u64,
+    /// Number of frames received from the driver
+    frame_count: u64,
     /// The driver that supplies incoming frames
     _driver: PhantomData<D>,
 }
@@ -71,6 +73,7 @@ where
         loop {
             match driver.receive(now) {
                 Ok(frame) => {
+                    self.frame_count = self.frame_count.wrapping_add(1);
                     match self.accept_frame(frame) {
                         Ok(Some(transfer)) => break Ok(Some(transfer)),
                         Ok(None) => { /* Keep going and try another frame */ }
@@ -211,6 +214,14 @@ where
         self.unsubscribe(TransferKind::Response, PortId::from(service));
         self.apply_frame_filters(driver);
     }
+
+    fn statistics(&self) -> Statistics {
+        Statistics {
+            transfers: self.transfer_count,
+            frames: self.frame_count,
+            errors: self.error_count,
+        }
+    }
 }
 
 impl<I, D> CanReceiver<I, D>
@@ -241,6 +252,7 @@ where
             mtu,
             transfer_count: 0,
             error_count: 0,
+            frame_count: 0,
             _driver: PhantomData,
         }
     }
@@ -401,6 +413,12 @@ where
     pub fn error_count(&self) -> u64 {
         self.error_count
     }
+    /// Returns the number of frames received from the driver
+    ///
+    /// This includes frames that were ignored or could not be used.
+    pub fn frame_count(&self) -> u64 {
+        self.frame_count
+    }
 
     fn increment_transfer_count(&mut self) {
         self.transfer_count = self.transfer_count.wrapping_add(1)
@@ -460,7 +478,7 @@ pub enum CanIdParseError {
 }
 
 /// Parses a transfer header from a CAN ID, frame timestamp, and frame transfer ID
-fn parse_can_id<I>(
+pub(crate) fn parse_can_id<I>(
     id: CanId,
     timestamp: I,
     transfer_id: CanTransferId,
@@ -635,10 +653,10 @@ mod test {
 }
 
 pub(crate) struct TailByte {
-    start: bool,
-    end: bool,
-    toggle: bool,
-    transfer_id: CanTransferId,
+    pub start: bool,
+    pub end: bool,
+    pub toggle: bool,
+    pub transfer_id: CanTransferId,
 }
 
 impl TailByte {
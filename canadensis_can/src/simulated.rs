@@ -0,0 +1,393 @@
+//! A simulated CAN bus that connects several drivers in the same process
+//!
+//! This is useful for testing several nodes together without any CAN hardware.
+//!
+//! A [`SimulatedBus`] creates any number of [`SimulatedDriver`]s. The bus transmits one frame at
+//! a time, and each frame occupies the bus for a fixed frame time. When several drivers have
+//! frames waiting, the frame with the lowest CAN ID wins arbitration, like on a real CAN bus.
+//! Frames with equal IDs are transmitted in the order they were queued.
+//!
+//! The bus does not have its own clock. Its state advances whenever a driver is used, based on
+//! the `now` value passed to the driver. A frame is available to the other drivers at the end
+//! of its frame time, and the timestamp of the received frame is the time when its
+//! transmission finished. Frames whose transmit deadline passes before they win arbitration
+//! are dropped.
+//!
+//! # Examples
+//!
+//! ```
+//! # use canadensis_can::simulated::SimulatedBus;
+//! # use canadensis_can::driver::{ReceiveDriver, TransmitDriver};
+//! # use canadensis_can::{CanId, Frame};
+//! # use canadensis_core::time::{MicrosecondDuration64, Microseconds64};
+//! # use std::convert::TryFrom;
+//! let bus = SimulatedBus::new(MicrosecondDuration64::new(100));
+//! let mut a = bus.add_driver();
+//! let mut b = bus.add_driver();
+//!
+//! let deadline = Microseconds64::new(10_000);
+//! let id = CanId::try_from(0x107d_552a).unwrap();
+//! a.transmit(Frame::new(deadline, id, &[0xe0]), Microseconds64::new(0)).unwrap();
+//! // The frame is still being transmitted
+//! assert!(b.receive(Microseconds64::new(50)).is_err());
+//! let frame = b.receive(Microseconds64::new(100)).unwrap();
+//! assert_eq!(id, frame.id());
+//! assert_eq!(Microseconds64::new(100), frame.timestamp());
+//! ```
+//!
+
+use crate::driver::{optimize_filters, ReceiveDriver, TransmitDriver};
+use crate::types::CanNodeId;
+use crate::Frame;
+use alloc::collections::VecDeque;
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+use canadensis_core::subscription::Subscription;
+use canadensis_core::time::Instant;
+use canadensis_core::{nb, OutOfMemoryError};
+use canadensis_filter_config::Filter;
+use core::cell::RefCell;
+use core::cmp::Ordering;
+use core::convert::Infallible;
+use fallible_collections::FallibleVec;
+
+/// A simulated CAN bus
+///
+/// Clones of a bus refer to the same bus.
+pub struct SimulatedBus<I: Instant> {
+    bus: Rc<RefCell<Bus<I>>>,
+}
+
+impl<I: Instant> SimulatedBus<I> {
+    /// Creates a bus with no drivers
+    ///
+    /// `frame_time` is the time required to transmit each frame. At 1 Mbit/s, a CAN frame with
+    /// 8 bytes of data and an extended ID takes about 130 microseconds. With a frame time of
+    /// zero, frames are available to the other drivers as soon as they are queued, which is
+    /// useful for tests that do not depend on bus timing.
+    pub fn new(frame_time: I::Duration) -> Self {
+        SimulatedBus {
+            bus: Rc::new(RefCell::new(Bus {
+                frame_time,
+                free_at: None,
+                endpoints: Vec::new(),
+                sequence: 0,
+                transmitted: 0,
+                expired: 0,
+            })),
+        }
+    }
+
+    /// Creates a driver connected to this bus
+    ///
+    /// The new driver initially receives all frames that other drivers transmit.
+    pub fn add_driver(&self) -> SimulatedDriver<I> {
+        let mut bus = self.bus.borrow_mut();
+        bus.endpoints.push(Endpoint {
+            transmit: Vec::new(),
+            receive: VecDeque::new(),
+            loopback: VecDeque::new(),
+            filters: None,
+            connected: true,
+        });
+        SimulatedDriver {
+            bus: Rc::clone(&self.bus),
+            index: bus.endpoints.len() - 1,
+        }
+    }
+
+    /// Returns the number of frames that are waiting to be transmitted or have been transmitted
+    /// but not yet received
+    ///
+    /// This does not include loopback frames.
+    pub fn pending_frames(&self) -> usize {
+        self.bus
+            .borrow()
+            .endpoints
+            .iter()
+            .map(|endpoint| endpoint.transmit.len() + endpoint.receive.len())
+            .sum()
+    }
+
+    /// Returns the number of frames that have been transmitted on this bus
+    pub fn transmitted_frames(&self) -> u64 {
+        self.bus.borrow().transmitted
+    }
+
+    /// Returns the number of frames that were dropped because their deadlines passed before
+    /// they could be transmitted
+    pub fn expired_frames(&self) -> u64 {
+        self.bus.borrow().expired
+    }
+}
+
+impl<I: Instant> Clone for SimulatedBus<I> {
+    fn clone(&self) -> Self {
+        SimulatedBus {
+            bus: Rc::clone(&self.bus),
+        }
+    }
+}
+
+/// A driver connected to a [`SimulatedBus`]
+///
+/// Each driver has its own transmit and receive queues, which can grow as large as memory
+/// allows. A driver does not receive the frames that it transmits (except as loopback frames).
+///
+/// When a node applies filters, the driver uses [`optimize_filters`] to create exact filters
+/// for the subscriptions and drops all incoming frames that the filters do not accept.
+///
+/// A driver can be disconnected from the bus using [`set_connected`](#method.set_connected)
+/// to simulate a node that has failed or been unplugged.
+pub struct SimulatedDriver<I: Instant> {
+    bus: Rc<RefCell<Bus<I>>>,
+    index: usize,
+}
+
+impl<I: Instant> SimulatedDriver<I> {
+    /// Returns the number of frames waiting in this driver's receive queue that are available
+    /// at the provided time
+    pub fn available_frames(&self, now: I) -> usize {
+        let mut bus = self.bus.borrow_mut();
+        bus.update(now);
+        bus.endpoints[self.index]
+            .receive
+            .iter()
+            .filter(|frame| is_at_or_before(&frame.timestamp(), &now))
+            .count()
+    }
+
+    /// Connects this driver to the bus or disconnects it
+    ///
+    /// When the driver is disconnected, all frames waiting to be transmitted or received are
+    /// discarded. While it is disconnected, frames passed to the driver are discarded and it
+    /// does not receive any frames.
+    pub fn set_connected(&mut self, connected: bool) {
+        let mut bus = self.bus.borrow_mut();
+        let endpoint = &mut bus.endpoints[self.index];
+        endpoint.connected = connected;
+        if !connected {
+            endpoint.transmit.clear();
+            endpoint.receive.clear();
+            endpoint.loopback.clear();
+        }
+    }
+
+    /// Returns true if this driver is connected to the bus
+    pub fn is_connected(&self) -> bool {
+        self.bus.borrow().endpoints[self.index].connected
+    }
+}
+
+impl<I: Instant> TransmitDriver<I> for SimulatedDriver<I> {
+    type Error = Infallible;
+
+    fn try_reserve(&mut self, frames: usize) -> Result<(), OutOfMemoryError> {
+        FallibleVec::try_reserve(
+            &mut self.bus.borrow_mut().endpoints[self.index].transmit,
+            frames,
+        )
+        .map_err(|_| OutOfMemoryError)
+    }
+
+    fn transmit(&mut self, frame: Frame<I>, now: I) -> nb::Result<Option<Frame<I>>, Self::Error> {
+        let mut bus = self.bus.borrow_mut();
+        bus.update(now);
+        if !bus.endpoints[self.index].connected {
+            return Ok(None);
+        }
+        let sequence = bus.sequence;
+        bus.sequence = bus.sequence.wrapping_add(1);
+        let queued = QueuedFrame {
+            frame,
+            queued_at: now,
+            sequence,
+        };
+        FallibleVec::try_push(&mut bus.endpoints[self.index].transmit, queued)
+            .map(|_| None)
+            .map_err(|_| nb::Error::WouldBlock)
+    }
+
+    fn flush(&mut self, now: I) -> nb::Result<(), Self::Error> {
+        self.bus.borrow_mut().update(now);
+        Ok(())
+    }
+
+    fn pop_loopback(&mut self) -> Option<Frame<I>> {
+        self.bus.borrow_mut().endpoints[self.index]
+            .loopback
+            .pop_front()
+    }
+}
+
+impl<I: Instant> ReceiveDriver<I> for SimulatedDriver<I> {
+    type Error = Infallible;
+
+    fn receive(&mut self, now: I) -> nb::Result<Frame<I>, Self::Error> {
+        let mut bus = self.bus.borrow_mut();
+        bus.update(now);
+        let receive = &mut bus.endpoints[self.index].receive;
+        match receive.front() {
+            Some(frame) if is_at_or_before(&frame.timestamp(), &now) => {
+                Ok(receive.pop_front().unwrap())
+            }
+            _ => Err(nb::Error::WouldBlock),
+        }
+    }
+
+    fn apply_filters<S>(&mut self, local_node: Option<CanNodeId>, subscriptions: S)
+    where
+        S: IntoIterator<Item = Subscription>,
+    {
+        let mut bus = self.bus.borrow_mut();
+        let endpoint = &mut bus.endpoints[self.index];
+        let result = optimize_filters(local_node, subscriptions, usize::MAX, |filters| {
+            let mut copy = Vec::new();
+            endpoint.filters = match FallibleVec::try_reserve(&mut copy, filters.len()) {
+                Ok(()) => {
+                    copy.extend(filters.iter().cloned());
+                    Some(copy)
+                }
+                Err(_) => None,
+            };
+        });
+        if result.is_err() {
+            // Without memory for the filters, accept everything
+            endpoint.filters = None;
+        }
+    }
+
+    fn apply_accept_all(&mut self) {
+        self.bus.borrow_mut().endpoints[self.index].filters = None;
+    }
+}
+
+/// The shared state of a simulated bus
+struct Bus<I: Instant> {
+    /// The time required to transmit one frame
+    frame_time: I::Duration,
+    /// The time when the most recently transmitted frame finished, or None if no frames have
+    /// been transmitted
+    free_at: Option<I>,
+    /// The queues and filters of each driver
+    endpoints: Vec<Endpoint<I>>,
+    /// The sequence number to assign to the next frame queued for transmission
+    sequence: u64,
+    /// The number of frames transmitted
+    transmitted: u64,
+    /// The number of frames dropped because their deadlines passed
+    expired: u64,
+}
+
+impl<I: Instant> Bus<I> {
+    /// Transmits and delivers all frames that finish transmission at or before `now`
+    fn update(&mut self, now: I) {
+        // Find the earliest time when a waiting frame can start transmission
+        while let Some(earliest_queued) = self
+            .endpoints
+            .iter()
+            .flat_map(|endpoint| endpoint.transmit.iter())
+            .map(|queued| queued.queued_at)
+            .min_by(|a, b| a.overflow_safe_compare(b))
+        {
+            let start = match self.free_at {
+                Some(free_at) if !is_at_or_before(&free_at, &earliest_queued) => free_at,
+                _ => earliest_queued,
+            };
+            let end = self.frame_time + start;
+            if !is_at_or_before(&end, &now) {
+                // The next frame will not be finished yet
+                break;
+            }
+
+            // Arbitration: Of the frames ready at the start time, the lowest CAN ID wins
+            let (endpoint_index, frame_index) = self
+                .endpoints
+                .iter()
+                .enumerate()
+                .flat_map(|(endpoint_index, endpoint)| {
+                    endpoint
+                        .transmit
+                        .iter()
+                        .enumerate()
+                        .map(move |(frame_index, queued)| (endpoint_index, frame_index, queued))
+                })
+                .filter(|(_, _, queued)| is_at_or_before(&queued.queued_at, &start))
+                .min_by(|(_, _, a), (_, _, b)| {
+                    u32::from(a.frame.id())
+                        .cmp(&u32::from(b.frame.id()))
+                        .then(a.sequence.cmp(&b.sequence))
+                })
+                .map(|(endpoint_index, frame_index, _)| (endpoint_index, frame_index))
+                .expect("No frame ready at start time");
+            let frame = self.endpoints[endpoint_index]
+                .transmit
+                .remove(frame_index)
+                .frame;
+
+            if frame.timestamp().overflow_safe_compare(&start) == Ordering::Less {
+                log::debug!("Simulated bus dropping expired frame {:?}", frame.id());
+                self.expired = self.expired.wrapping_add(1);
+                continue;
+            }
+            self.free_at = Some(end);
+            self.transmitted = self.transmitted.wrapping_add(1);
+
+            for (index, endpoint) in self.endpoints.iter_mut().enumerate() {
+                if index != endpoint_index && endpoint.accepts(&frame) {
+                    endpoint
+                        .receive
+                        .push_back(Frame::new(end, frame.id(), frame.data()));
+                }
+            }
+            if frame.loopback() {
+                let mut loopback_frame = frame;
+                loopback_frame.set_timestamp(end);
+                self.endpoints[endpoint_index]
+                    .loopback
+                    .push_back(loopback_frame);
+            }
+        }
+    }
+}
+
+/// The queues and filters for one driver
+struct Endpoint<I> {
+    /// Frames waiting for transmission
+    transmit: Vec<QueuedFrame<I>>,
+    /// Transmitted frames, timestamped with the time when transmission finished
+    receive: VecDeque<Frame<I>>,
+    /// Transmitted frames with the loopback flag set
+    loopback: VecDeque<Frame<I>>,
+    /// The reception filters, or None to accept all frames
+    filters: Option<Vec<Filter>>,
+    /// If this is false, the endpoint does not transmit or receive frames
+    connected: bool,
+}
+
+impl<I> Endpoint<I> {
+    fn accepts(&self, frame: &Frame<I>) -> bool {
+        if !self.connected {
+            return false;
+        }
+        match &self.filters {
+            Some(filters) => filters
+                .iter()
+                .any(|filter| filter.accepts(u32::from(frame.id()))),
+            None => true,
+        }
+    }
+}
+
+/// A frame waiting for transmission
+struct QueuedFrame<I> {
+    frame: Frame<I>,
+    /// The time when the frame was passed to the driver
+    queued_at: I,
+    /// A number that increases with each frame, used to transmit frames with equal IDs in order
+    sequence: u64,
+}
+
+fn is_at_or_before<I: Instant>(instant: &I, other: &I) -> bool {
+    instant.overflow_safe_compare(other) != Ordering::Greater
+}
@@ -9,11 +9,12 @@ use core::marker::PhantomData;
 use canadensis_core::nb;
 use canadensis_core::time::{Clock, Instant};
 use canadensis_core::transfer::{Header, ServiceHeader, Transfer};
-use canadensis_core::transport::Transmitter;
+use canadensis_core::transport::{Statistics, Transmitter};
 
 use crate::crc::TransferCrc;
 use crate::data::Frame;
 use crate::driver::TransmitDriver;
+use crate::rx::{parse_can_id, TailByte};
 use crate::tx::breakdown::Breakdown;
 use crate::types::{CanNodeId, CanTransport, Error};
 use crate::{CanId, Mtu};
@@ -35,6 +36,8 @@ pub struct CanTransmitter<I, D> {
     ///
     /// A failure to allocate memory is considered an error. CAN bus errors are ignored.
     error_count: u64,
+    /// Number of frames successfully passed to the driver
+    frame_count: u64,
     _instant: PhantomData<I>,
     _driver: PhantomData<D>,
 }
@@ -64,22 +67,21 @@ where
         A: AsRef<[u8]>,
         C: Clock<Instant = I>,
     {
-        // Convert the transfer payload into borrowed form
-        let transfer = Transfer {
-            header: transfer.header,
-            payload: transfer.payload.as_ref(),
-        };
+        self.push_counted(transfer, false, clock.now(), driver)
+    }
 
-        match self.push_inner(transfer, clock.now(), driver) {
-            Ok(()) => {
-                self.transfer_count = self.transfer_count.wrapping_add(1);
-                Ok(())
-            }
-            Err(e) => {
-                self.error_count = self.error_count.wrapping_add(1);
-                Err(e.into())
-            }
-        }
+    /// Breaks a transfer into frames with the loopback flag set
+    fn push_loopback<A, C>(
+        &mut self,
+        transfer: Transfer<A, I, CanTransport>,
+        clock: &mut C,
+        driver: &mut D,
+    ) -> nb::Result<(), Self::Error>
+    where
+        A: AsRef<[u8]>,
+        C: Clock<Instant = I>,
+    {
+        self.push_counted(transfer, true, clock.now(), driver)
     }
 
     fn flush<C>(&mut self, clock: &mut C, driver: &mut D) -> nb::Result<(), Self::Error>
@@ -93,6 +95,33 @@ where
         // Subtract 1 for the tail byte
         self.mtu - 1
     }
+
+    /// Returns the header of a loopback transfer when the driver reports that the first frame of
+    /// the transfer has been transmitted
+    fn pop_loopback(&mut self, driver: &mut D) -> Option<Header<I, CanTransport>> {
+        while let Some(frame) = driver.pop_loopback() {
+            let tail = match frame.data().last() {
+                Some(&tail) => TailByte::parse(tail),
+                None => continue,
+            };
+            if !tail.start {
+                // Only the first frame of each transfer is reported
+                continue;
+            }
+            if let Ok(header) = parse_can_id(frame.id(), frame.timestamp(), tail.transfer_id) {
+                return Some(header);
+            }
+        }
+        None
+    }
+
+    fn statistics(&self) -> Statistics {
+        Statistics {
+            transfers: self.transfer_count,
+            frames: self.frame_count,
+            errors: self.error_count,
+        }
+    }
 }
 
 impl<I, D> CanTransmitter<I, D>
@@ -107,6 +136,7 @@ where
             mtu: mtu as usize,
             transfer_count: 0,
             error_count: 0,
+            frame_count: 0,
             _instant: PhantomData,
             _driver: PhantomData,
         }
@@ -119,9 +149,40 @@ where
         self.mtu = mtu as usize;
     }
 
+    /// Breaks a transfer into frames and updates the transfer and error counts
+    fn push_counted<A>(
+        &mut self,
+        transfer: Transfer<A, I, CanTransport>,
+        loopback: bool,
+        now: I,
+        driver: &mut D,
+    ) -> nb::Result<(), Error<D::Error>>
+    where
+        A: AsRef<[u8]>,
+        I: Clone,
+    {
+        // Convert the transfer payload into borrowed form
+        let transfer = Transfer {
+            header: transfer.header,
+            payload: transfer.payload.as_ref(),
+        };
+
+        match self.push_inner(transfer, loopback, now, driver) {
+            Ok(()) => {
+                self.transfer_count = self.transfer_count.wrapping_add(1);
+                Ok(())
+            }
+            Err(e) => {
+                self.error_count = self.error_count.wrapping_add(1);
+                Err(e)
+            }
+        }
+    }
+
     fn push_inner(
         &mut self,
         transfer: Transfer<&[u8], I, CanTransport>,
+        loopback: bool,
         now: I,
         driver: &mut D,
     ) -> nb::Result<(), Error<D::Error>>
@@ -154,6 +215,7 @@ where
                 self.push_frame(
                     transfer.header.timestamp(),
                     can_id,
+                    loopback,
                     &frame_data,
                     driver,
                     now.clone(),
@@ -174,6 +236,7 @@ where
                     self.push_frame(
                         transfer.header.timestamp(),
                         can_id,
+                        loopback,
                         &frame_data,
                         driver,
                         now.clone(),
@@ -186,6 +249,7 @@ where
         self.push_frame(
             transfer.header.timestamp(),
             can_id,
+            loopback,
             &last_frame_data,
             driver,
             now,
@@ -199,6 +263,7 @@ where
         &mut self,
         timestamp: I,
         id: CanId,
+        loopback: bool,
         data: &[u8],
         driver: &mut D,
         now: I,
@@ -206,8 +271,11 @@ where
     where
         I: Clone,
     {
-        let frame = Frame::new(timestamp, id, data);
-        driver.transmit(frame, now).map(|removed| drop(removed))
+        let mut frame = Frame::new(timestamp, id, data);
+        frame.set_loopback(loopback);
+        driver.transmit(frame, now)?;
+        self.frame_count = self.frame_count.wrapping_add(1);
+        Ok(())
     }
 
     /// Returns the number of transfers successfully transmitted
@@ -226,6 +294,12 @@ where
     pub fn error_count(&self) -> u64 {
         self.error_count
     }
+
+    /// Returns the number of frames successfully passed to the driver
+    #[inline]
+    pub fn frame_count(&self) -> u64 {
+        self.frame_count
+    }
 }
 
 fn make_can_id<I>(header: &Header<I, CanTransport>, payload: &[u8]) -> CanId {
@@ -0,0 +1,158 @@
+//!
+//! Simulated bus integration tests
+//!
+
+extern crate canadensis_can;
+extern crate canadensis_core;
+
+use core::convert::TryFrom;
+
+use canadensis_can::driver::{ReceiveDriver, TransmitDriver};
+use canadensis_can::simulated::{SimulatedBus, SimulatedDriver};
+use canadensis_can::{CanId, CanNodeId, Frame};
+use canadensis_core::subscription::Subscription;
+use canadensis_core::time::{Clock, MicrosecondDuration64, Microseconds64, SimulatedClock};
+use canadensis_core::SubjectId;
+
+fn instant(microseconds: u64) -> Microseconds64 {
+    Microseconds64::new(microseconds)
+}
+
+fn frame(id: u32, data: &[u8]) -> Frame<Microseconds64> {
+    Frame::new(instant(1_000_000), CanId::try_from(id).unwrap(), data)
+}
+
+/// Receives all frames available at the provided time and returns their IDs and timestamps
+fn receive_all(driver: &mut SimulatedDriver<Microseconds64>, now: u64) -> Vec<(u32, u64)> {
+    std::iter::from_fn(|| driver.receive(instant(now)).ok())
+        .map(|frame| (u32::from(frame.id()), frame.timestamp().as_microseconds()))
+        .collect()
+}
+
+#[test]
+fn test_simulated_clock() {
+    let mut clock = SimulatedClock::new();
+    let mut other = clock.clone();
+    assert_eq!(instant(0), clock.now());
+    clock.advance(MicrosecondDuration64::new(150));
+    assert_eq!(instant(150), other.now());
+    other.set(instant(1000));
+    assert_eq!(instant(1000), clock.now());
+    assert_eq!(instant(1000), clock.time());
+}
+
+#[test]
+#[should_panic]
+fn test_simulated_clock_backwards() {
+    let clock = SimulatedClock::new();
+    clock.set(instant(10));
+    clock.set(instant(9));
+}
+
+#[test]
+fn test_arbitration() {
+    let bus = SimulatedBus::new(MicrosecondDuration64::new(100));
+    let mut a = bus.add_driver();
+    let mut b = bus.add_driver();
+    let mut receiver = bus.add_driver();
+
+    // All of these frames are waiting when the bus becomes free
+    a.transmit(frame(0x300, &[1]), instant(0)).unwrap();
+    a.transmit(frame(0x100, &[2]), instant(0)).unwrap();
+    b.transmit(frame(0x200, &[3]), instant(0)).unwrap();
+    b.transmit(frame(0x100, &[4]), instant(0)).unwrap();
+    assert_eq!(4, bus.pending_frames());
+
+    assert!(receive_all(&mut receiver, 99).is_empty());
+    assert_eq!(vec![(0x100, 100)], receive_all(&mut receiver, 100));
+    // Frames with equal IDs are transmitted in the order they were queued
+    assert_eq!(a.receive(instant(200)).unwrap().data(), &[4]);
+    // Frame 0x050 arrives after 0x200 won arbitration at time 200, so it waits for 0x200
+    // to finish and then wins over 0x300
+    a.transmit(frame(0x050, &[5]), instant(250)).unwrap();
+    assert_eq!(
+        vec![(0x100, 200), (0x200, 300), (0x050, 400), (0x300, 500)],
+        receive_all(&mut receiver, 1000)
+    );
+    assert_eq!(5, bus.transmitted_frames());
+    assert_eq!(0, bus.expired_frames());
+
+    // The bus was idle, so a new frame starts immediately
+    b.transmit(frame(0x400, &[6]), instant(2000)).unwrap();
+    assert_eq!(vec![(0x400, 2100)], receive_all(&mut receiver, 2100));
+    assert_eq!(2, a.available_frames(instant(2100)));
+    // Frames that a and b have not received
+    assert_eq!(5, bus.pending_frames());
+}
+
+#[test]
+fn test_deadline_and_loopback() {
+    let bus = SimulatedBus::new(MicrosecondDuration64::new(100));
+    let mut a = bus.add_driver();
+    let mut b = bus.add_driver();
+
+    let mut looped = frame(0x10, &[1]);
+    looped.set_loopback(true);
+    a.transmit(looped, instant(0)).unwrap();
+    // This frame must start before time 50, but the bus is busy until time 100
+    let expiring = Frame::new(instant(50), CanId::try_from(0x20).unwrap(), &[2]);
+    a.transmit(expiring, instant(0)).unwrap();
+    a.transmit(frame(0x30, &[3]), instant(0)).unwrap();
+    a.flush(instant(500)).unwrap();
+
+    assert_eq!(vec![(0x10, 100), (0x30, 200)], receive_all(&mut b, 500));
+    assert_eq!(1, bus.expired_frames());
+    let loopback = a.pop_loopback().unwrap();
+    assert_eq!(0x10, u32::from(loopback.id()));
+    assert_eq!(instant(100), loopback.timestamp());
+    assert!(a.pop_loopback().is_none());
+    // The sender does not receive its own frames
+    assert!(receive_all(&mut a, 500).is_empty());
+}
+
+#[test]
+fn test_filters() {
+    let bus = SimulatedBus::new(MicrosecondDuration64::new(100));
+    let mut sender = bus.add_driver();
+    let mut receiver = bus.add_driver();
+    receiver.apply_filters(
+        Some(CanNodeId::try_from(3u8).unwrap()),
+        vec![Subscription::Message(SubjectId::try_from(7509).unwrap())],
+    );
+
+    // Heartbeat from node 42
+    sender
+        .transmit(frame(0x107d552a, &[0]), instant(0))
+        .unwrap();
+    // Port list from node 42
+    sender
+        .transmit(frame(0x1f3bfd2a, &[0]), instant(0))
+        .unwrap();
+    assert_eq!(vec![(0x107d552a, 100)], receive_all(&mut receiver, 1000));
+
+    receiver.apply_accept_all();
+    sender
+        .transmit(frame(0x1f3bfd2a, &[0]), instant(1000))
+        .unwrap();
+    assert_eq!(vec![(0x1f3bfd2a, 1100)], receive_all(&mut receiver, 2000));
+}
+
+#[test]
+fn test_disconnect() {
+    let bus = SimulatedBus::new(MicrosecondDuration64::new(0));
+    let mut a = bus.add_driver();
+    let mut b = bus.add_driver();
+
+    a.transmit(frame(0x10, &[1]), instant(0)).unwrap();
+    b.set_connected(false);
+    assert!(!b.is_connected());
+    // Frames sent to or by a disconnected driver are discarded
+    assert!(receive_all(&mut b, 100).is_empty());
+    b.transmit(frame(0x20, &[2]), instant(100)).unwrap();
+    assert!(receive_all(&mut a, 100).is_empty());
+    assert_eq!(0, bus.pending_frames());
+
+    b.set_connected(true);
+    a.transmit(frame(0x30, &[3]), instant(200)).unwrap();
+    assert_eq!(vec![(0x30, 200)], receive_all(&mut b, 200));
+}
@@ -10,10 +10,12 @@ use std::collections::VecDeque;
 use std::convert::Infallible;
 
 use canadensis_can::driver::TransmitDriver;
+use canadensis_can::queue::QueueOnlyDriver;
+use canadensis_can::redundant::RedundantDriver;
 use canadensis_can::{CanId, CanNodeId, CanTransferId, CanTransmitter, Frame, Mtu};
 use canadensis_core::time::{Clock, Microseconds32};
 use canadensis_core::transfer::*;
-use canadensis_core::transport::Transmitter;
+use canadensis_core::transport::{Statistics, Transmitter, TransportStatistics};
 use canadensis_core::{OutOfMemoryError, Priority, ServiceId, SubjectId};
 
 fn instant(ticks: u32) -> Microseconds32 {
@@ -252,6 +254,148 @@ fn test_array() {
     assert_eq!(None, driver.pop_frame());
 }
 
+#[test]
+fn test_loopback() {
+    let mut driver: QueueOnlyDriver<Microseconds32, 4, 4> = QueueOnlyDriver::new();
+    let mut tx = CanTransmitter::new(Mtu::Can8);
+    let header = Header::Message(MessageHeader {
+        timestamp: instant(0),
+        transfer_id: CanTransferId::try_from(3).unwrap(),
+        priority: Priority::Fast,
+        subject: SubjectId::try_from(7168).unwrap(),
+        source: Some(CanNodeId::try_from(12u8).unwrap()),
+    });
+    // This transfer needs two frames
+    tx.push_loopback(
+        Transfer {
+            header: header.clone(),
+            payload: &[1, 2, 3, 4, 5, 6, 7, 8, 9][..],
+        },
+        &mut ZeroClock,
+        &mut driver,
+    )
+    .unwrap();
+
+    // The driver reports each frame as transmitted, with the transmission time
+    while let Some(mut frame) = driver.pop_tx_frame() {
+        assert!(frame.loopback());
+        frame.set_timestamp(instant(1200));
+        driver.push_loopback_frame(frame).unwrap();
+    }
+    // Only the first frame of the transfer is reported
+    let mut expected = header;
+    expected.set_timestamp(instant(1200));
+    assert_eq!(Some(expected), tx.pop_loopback(&mut driver));
+    assert_eq!(None, tx.pop_loopback(&mut driver));
+}
+
+#[test]
+fn test_redundant_loopback() {
+    let mut driver = RedundantDriver::new(
+        QueueOnlyDriver::<Microseconds32, 4, 4>::new(),
+        QueueOnlyDriver::<Microseconds32, 4, 4>::new(),
+    );
+    let mut tx = CanTransmitter::new(Mtu::Can8);
+    let header = |transfer_id| {
+        Header::Message(MessageHeader {
+            timestamp: instant(0),
+            transfer_id: CanTransferId::try_from(transfer_id).unwrap(),
+            priority: Priority::Fast,
+            subject: SubjectId::try_from(7168).unwrap(),
+            source: Some(CanNodeId::try_from(12u8).unwrap()),
+        })
+    };
+    let push = |tx: &mut CanTransmitter<_, _>, driver: &mut _, transfer_id| {
+        tx.push_loopback(
+            Transfer {
+                header: header(transfer_id),
+                payload: &[1, 2, 3][..],
+            },
+            &mut ZeroClock,
+            driver,
+        )
+        .unwrap();
+    };
+    // Sent on both interfaces, and reported by interface 1 first
+    push(&mut tx, &mut driver, 0);
+    let mut frame1 = driver.driver1_mut().pop_tx_frame().unwrap();
+    frame1.set_timestamp(instant(100));
+    driver.driver1_mut().push_loopback_frame(frame1).unwrap();
+    let mut expected = header(0);
+    expected.set_timestamp(instant(100));
+    assert_eq!(Some(expected), tx.pop_loopback(&mut driver));
+
+    // The same frame is reported later by interface 0, at the same time as the next frame
+    let mut frame0 = driver.driver0_mut().pop_tx_frame().unwrap();
+    frame0.set_timestamp(instant(150));
+    driver.driver0_mut().push_loopback_frame(frame0).unwrap();
+    push(&mut tx, &mut driver, 1);
+    let mut frame0 = driver.driver0_mut().pop_tx_frame().unwrap();
+    frame0.set_timestamp(instant(200));
+    driver.driver0_mut().push_loopback_frame(frame0).unwrap();
+    let mut expected = header(1);
+    expected.set_timestamp(instant(200));
+    assert_eq!(Some(expected), tx.pop_loopback(&mut driver));
+    assert_eq!(None, tx.pop_loopback(&mut driver));
+
+    // Interface 1 reports its copy of the second frame later
+    let mut frame1 = driver.driver1_mut().pop_tx_frame().unwrap();
+    frame1.set_timestamp(instant(250));
+    driver.driver1_mut().push_loopback_frame(frame1).unwrap();
+    assert_eq!(None, tx.pop_loopback(&mut driver));
+}
+
+#[test]
+fn test_redundant_statistics() {
+    // The queue for interface 1 has space for only one frame
+    let mut driver = RedundantDriver::new(
+        QueueOnlyDriver::<Microseconds32, 4, 4>::new(),
+        QueueOnlyDriver::<Microseconds32, 1, 4>::new(),
+    );
+    let mut tx = CanTransmitter::new(Mtu::Can8);
+    let header = Header::Message(MessageHeader {
+        timestamp: instant(0),
+        transfer_id: CanTransferId::try_from(0).unwrap(),
+        priority: Priority::Nominal,
+        subject: SubjectId::try_from(100).unwrap(),
+        source: Some(CanNodeId::try_from(12u8).unwrap()),
+    });
+    // Two frames, only sent on interface 0
+    tx.push(
+        Transfer {
+            header: header.clone(),
+            payload: &[1, 2, 3, 4, 5, 6, 7, 8, 9][..],
+        },
+        &mut ZeroClock,
+        &mut driver,
+    )
+    .unwrap();
+    // One frame, sent on both interfaces
+    tx.push(
+        Transfer {
+            header,
+            payload: &[1, 2, 3][..],
+        },
+        &mut ZeroClock,
+        &mut driver,
+    )
+    .unwrap();
+
+    assert_eq!(
+        Statistics {
+            transfers: 2,
+            frames: 3,
+            errors: 0
+        },
+        tx.statistics()
+    );
+    assert_eq!(2, driver.interface_count());
+    assert_eq!(3, driver.interface_statistics(0).unwrap().frames);
+    assert_eq!(1, driver.interface_statistics(1).unwrap().frames);
+    assert_eq!(None, driver.interface_statistics(2));
+    assert_eq!(4, driver.statistics().frames);
+}
+
 /// A simple driver that uses a `VecDeque`
 ///
 /// This does not keep the frames in order by priority, but it is correct as long as it is used for
@@ -31,6 +31,7 @@ pub mod reg {
                     }
                     impl ::canadensis_encoding::DataType for Note {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 12;
                     }
                     impl ::canadensis_encoding::Message for Note {}
                     impl Note {}
@@ -83,6 +84,7 @@ pub torque: crate::uavcan::si::unit::torque::scalar_1_0::Scalar,
 }
                         impl ::canadensis_encoding::DataType for Planar {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 16;
                         }
                         impl ::canadensis_encoding::Message for Planar {}
                         impl Planar {}
@@ -132,6 +134,7 @@ pub value: crate::reg::drone::physics::dynamics::rotation::planar_0_1::Planar,
 }
                         impl ::canadensis_encoding::DataType for PlanarTs {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 23;
                         }
                         impl ::canadensis_encoding::Message for PlanarTs {}
                         impl PlanarTs {}
@@ -183,6 +186,7 @@ pub force: crate::uavcan::si::unit::force::scalar_1_0::Scalar,
 }
                         impl ::canadensis_encoding::DataType for Linear {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 16;
                         }
                         impl ::canadensis_encoding::Message for Linear {}
                         impl Linear {}
@@ -232,6 +236,7 @@ pub value: crate::reg::drone::physics::dynamics::translation::linear_0_1::Linear
 }
                         impl ::canadensis_encoding::DataType for LinearTs {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 23;
                         }
                         impl ::canadensis_encoding::Message for LinearTs {}
                         impl LinearTs {}
@@ -284,6 +289,7 @@ pub value: crate::reg::drone::physics::dynamics::translation::linear_0_1::Linear
                     }
                     impl ::canadensis_encoding::DataType for Power {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 8;
                     }
                     impl ::canadensis_encoding::Message for Power {}
                     impl Power {}
@@ -331,6 +337,7 @@ pub value: crate::reg::drone::physics::dynamics::translation::linear_0_1::Linear
                     }
                     impl ::canadensis_encoding::DataType for PowerTs {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 15;
                     }
                     impl ::canadensis_encoding::Message for PowerTs {}
                     impl PowerTs {}
@@ -382,6 +389,7 @@ pub value: crate::reg::drone::physics::dynamics::translation::linear_0_1::Linear
                     }
                     impl ::canadensis_encoding::DataType for Source {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 16;
                     }
                     impl ::canadensis_encoding::Message for Source {}
                     impl Source {}
@@ -430,6 +438,7 @@ pub value: crate::reg::drone::physics::dynamics::translation::linear_0_1::Linear
                     }
                     impl ::canadensis_encoding::DataType for SourceTs {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 23;
                     }
                     impl ::canadensis_encoding::Message for SourceTs {}
                     impl SourceTs {}
@@ -475,6 +484,7 @@ pub value: crate::reg::drone::physics::dynamics::translation::linear_0_1::Linear
                         }
                         impl ::canadensis_encoding::DataType for Point {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 24;
                         }
                         impl ::canadensis_encoding::Message for Point {}
                         impl Point {}
@@ -526,6 +536,7 @@ pub value: crate::reg::drone::physics::dynamics::translation::linear_0_1::Linear
                         }
                         impl ::canadensis_encoding::DataType for PointState {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 36;
                         }
                         impl ::canadensis_encoding::Message for PointState {}
                         impl PointState {}
@@ -577,6 +588,7 @@ pub velocity: crate::reg::drone::physics::kinematics::translation::velocity3_var
 }
                         impl ::canadensis_encoding::DataType for PointStateVar {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 60;
                         }
                         impl ::canadensis_encoding::Message for PointStateVar {}
                         impl PointStateVar {}
@@ -626,6 +638,7 @@ pub value: crate::reg::drone::physics::kinematics::cartesian::point_state_var_0_
 }
                         impl ::canadensis_encoding::DataType for PointStateVarTs {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 67;
                         }
                         impl ::canadensis_encoding::Message for PointStateVarTs {}
                         impl PointStateVarTs {}
@@ -676,6 +689,7 @@ pub value: crate::reg::drone::physics::kinematics::cartesian::point_state_var_0_
                         }
                         impl ::canadensis_encoding::DataType for PointVar {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 36;
                         }
                         impl ::canadensis_encoding::Message for PointVar {}
                         impl PointVar {}
@@ -729,6 +743,7 @@ pub value: crate::reg::drone::physics::kinematics::cartesian::point_state_var_0_
                         }
                         impl ::canadensis_encoding::DataType for Pose {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 40;
                         }
                         impl ::canadensis_encoding::Message for Pose {}
                         impl Pose {}
@@ -781,6 +796,7 @@ pub value: crate::reg::drone::physics::kinematics::cartesian::point_state_var_0_
                         }
                         impl ::canadensis_encoding::DataType for PoseVar {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 82;
                         }
                         impl ::canadensis_encoding::Message for PoseVar {}
                         impl PoseVar {}
@@ -830,6 +846,7 @@ pub value: crate::reg::drone::physics::kinematics::cartesian::pose_var_0_1::Pose
 }
                         impl ::canadensis_encoding::DataType for PoseVarTs {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 89;
                         }
                         impl ::canadensis_encoding::Message for PoseVarTs {}
                         impl PoseVarTs {}
@@ -881,6 +898,7 @@ pub value: crate::reg::drone::physics::kinematics::cartesian::pose_var_0_1::Pose
                         }
                         impl ::canadensis_encoding::DataType for State {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 64;
                         }
                         impl ::canadensis_encoding::Message for State {}
                         impl State {}
@@ -932,6 +950,7 @@ pub twist: crate::reg::drone::physics::kinematics::cartesian::twist_var_0_1::Twi
 }
                         impl ::canadensis_encoding::DataType for StateVar {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 148;
                         }
                         impl ::canadensis_encoding::Message for StateVar {}
                         impl StateVar {}
@@ -981,6 +1000,7 @@ pub value: crate::reg::drone::physics::kinematics::cartesian::state_var_0_1::Sta
 }
                         impl ::canadensis_encoding::DataType for StateVarTs {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 155;
                         }
                         impl ::canadensis_encoding::Message for StateVarTs {}
                         impl StateVarTs {}
@@ -1031,6 +1051,7 @@ pub value: crate::reg::drone::physics::kinematics::cartesian::state_var_0_1::Sta
                         }
                         impl ::canadensis_encoding::DataType for Twist {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 24;
                         }
                         impl ::canadensis_encoding::Message for Twist {}
                         impl Twist {}
@@ -1083,6 +1104,7 @@ pub value: crate::reg::drone::physics::kinematics::cartesian::state_var_0_1::Sta
                         }
                         impl ::canadensis_encoding::DataType for TwistVar {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 66;
                         }
                         impl ::canadensis_encoding::Message for TwistVar {}
                         impl TwistVar {}
@@ -1132,6 +1154,7 @@ pub value: crate::reg::drone::physics::kinematics::cartesian::twist_var_0_1::Twi
 }
                         impl ::canadensis_encoding::DataType for TwistVarTs {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 73;
                         }
                         impl ::canadensis_encoding::Message for TwistVarTs {}
                         impl TwistVarTs {}
@@ -1189,6 +1212,7 @@ pub value: crate::reg::drone::physics::kinematics::cartesian::twist_var_0_1::Twi
                         }
                         impl ::canadensis_encoding::DataType for Point {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 24;
                         }
                         impl ::canadensis_encoding::Message for Point {}
                         impl Point {}
@@ -1242,6 +1266,7 @@ pub value: crate::reg::drone::physics::kinematics::cartesian::twist_var_0_1::Twi
                         }
                         impl ::canadensis_encoding::DataType for PointState {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 36;
                         }
                         impl ::canadensis_encoding::Message for PointState {}
                         impl PointState {}
@@ -1293,6 +1318,7 @@ pub velocity: crate::reg::drone::physics::kinematics::translation::velocity3_var
 }
                         impl ::canadensis_encoding::DataType for PointStateVar {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 60;
                         }
                         impl ::canadensis_encoding::Message for PointStateVar {}
                         impl PointStateVar {}
@@ -1342,6 +1368,7 @@ pub value: crate::reg::drone::physics::kinematics::geodetic::point_state_var_0_1
 }
                         impl ::canadensis_encoding::DataType for PointStateVarTs {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 67;
                         }
                         impl ::canadensis_encoding::Message for PointStateVarTs {}
                         impl PointStateVarTs {}
@@ -1392,6 +1419,7 @@ pub value: crate::reg::drone::physics::kinematics::geodetic::point_state_var_0_1
                         }
                         impl ::canadensis_encoding::DataType for PointVar {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 36;
                         }
                         impl ::canadensis_encoding::Message for PointVar {}
                         impl PointVar {}
@@ -1445,6 +1473,7 @@ pub value: crate::reg::drone::physics::kinematics::geodetic::point_state_var_0_1
                         }
                         impl ::canadensis_encoding::DataType for Pose {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 40;
                         }
                         impl ::canadensis_encoding::Message for Pose {}
                         impl Pose {}
@@ -1497,6 +1526,7 @@ pub value: crate::reg::drone::physics::kinematics::geodetic::point_state_var_0_1
                         }
                         impl ::canadensis_encoding::DataType for PoseVar {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 82;
                         }
                         impl ::canadensis_encoding::Message for PoseVar {}
                         impl PoseVar {}
@@ -1550,6 +1580,7 @@ pub value: crate::reg::drone::physics::kinematics::geodetic::point_state_var_0_1
                         }
                         impl ::canadensis_encoding::DataType for State {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 64;
                         }
                         impl ::canadensis_encoding::Message for State {}
                         impl State {}
@@ -1601,6 +1632,7 @@ pub twist: crate::reg::drone::physics::kinematics::cartesian::twist_var_0_1::Twi
 }
                         impl ::canadensis_encoding::DataType for StateVar {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 148;
                         }
                         impl ::canadensis_encoding::Message for StateVar {}
                         impl StateVar {}
@@ -1650,6 +1682,7 @@ pub value: crate::reg::drone::physics::kinematics::geodetic::state_var_0_1::Stat
 }
                         impl ::canadensis_encoding::DataType for StateVarTs {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 155;
                         }
                         impl ::canadensis_encoding::Message for StateVarTs {}
                         impl StateVarTs {}
@@ -1709,6 +1742,7 @@ pub value: crate::reg::drone::physics::kinematics::geodetic::state_var_0_1::Stat
                         }
                         impl ::canadensis_encoding::DataType for Planar {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 12;
                         }
                         impl ::canadensis_encoding::Message for Planar {}
                         impl Planar {}
@@ -1762,6 +1796,7 @@ pub value: crate::reg::drone::physics::kinematics::rotation::planar_0_1::Planar,
 }
                         impl ::canadensis_encoding::DataType for PlanarTs {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 19;
                         }
                         impl ::canadensis_encoding::Message for PlanarTs {}
                         impl PlanarTs {}
@@ -1819,6 +1854,7 @@ pub value: crate::reg::drone::physics::kinematics::rotation::planar_0_1::Planar,
                         }
                         impl ::canadensis_encoding::DataType for Linear {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 12;
                         }
                         impl ::canadensis_encoding::Message for Linear {}
                         impl Linear {}
@@ -1869,6 +1905,7 @@ pub value: crate::reg::drone::physics::kinematics::translation::linear_0_1::Line
 }
                         impl ::canadensis_encoding::DataType for LinearTs {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 19;
                         }
                         impl ::canadensis_encoding::Message for LinearTs {}
                         impl LinearTs {}
@@ -1926,6 +1963,7 @@ pub acceleration_error_variance: ::half::f16,
 }
                         impl ::canadensis_encoding::DataType for LinearVarTs {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 25;
                         }
                         impl ::canadensis_encoding::Message for LinearVarTs {}
                         impl LinearVarTs {}
@@ -1977,6 +2015,7 @@ pub acceleration_error_variance: ::half::f16,
                         }
                         impl ::canadensis_encoding::DataType for Velocity1VarTs {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 13;
                         }
                         impl ::canadensis_encoding::Message for Velocity1VarTs {}
                         impl Velocity1VarTs {}
@@ -2024,6 +2063,7 @@ pub acceleration_error_variance: ::half::f16,
                         }
                         impl ::canadensis_encoding::DataType for Velocity3Var {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 31;
                         }
                         impl ::canadensis_encoding::Message for Velocity3Var {}
                         impl Velocity3Var {}
@@ -2084,6 +2124,7 @@ pub acceleration_error_variance: ::half::f16,
                         }
                         impl ::canadensis_encoding::DataType for Velocity3Var {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 24;
                         }
                         impl ::canadensis_encoding::Message for Velocity3Var {}
                         impl Velocity3Var {}
@@ -2144,6 +2185,7 @@ pub acceleration_error_variance: ::half::f16,
                     }
                     impl ::canadensis_encoding::DataType for HighColor {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 2;
                     }
                     impl ::canadensis_encoding::Message for HighColor {}
                     impl HighColor {
@@ -2207,6 +2249,7 @@ pub acceleration_error_variance: ::half::f16,
                     }
                     impl ::canadensis_encoding::DataType for PressureTempVarTs {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 21;
                     }
                     impl ::canadensis_encoding::Message for PressureTempVarTs {}
                     impl PressureTempVarTs {}
@@ -2258,6 +2301,7 @@ pub acceleration_error_variance: ::half::f16,
                     }
                     impl ::canadensis_encoding::DataType for TAI64 {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 8;
                     }
                     impl ::canadensis_encoding::Message for TAI64 {}
                     impl TAI64 {}
@@ -2305,6 +2349,7 @@ pub acceleration_error_variance: ::half::f16,
                     }
                     impl ::canadensis_encoding::DataType for TAI64Var {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 12;
                     }
                     impl ::canadensis_encoding::Message for TAI64Var {}
                     impl TAI64Var {}
@@ -2352,6 +2397,7 @@ pub acceleration_error_variance: ::half::f16,
                     }
                     impl ::canadensis_encoding::DataType for TAI64VarTs {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 19;
                     }
                     impl ::canadensis_encoding::Message for TAI64VarTs {}
                     impl TAI64VarTs {}
@@ -2392,6 +2438,7 @@ pub acceleration_error_variance: ::half::f16,
                         pub struct _0 {}
                         impl ::canadensis_encoding::DataType for _0 {
                             const EXTENT_BYTES: Option<u32> = Some(0);
+                            const MAX_SIZE_BYTES: u32 = 0;
                         }
                         impl ::canadensis_encoding::Message for _0 {}
                         impl _0 {
@@ -2483,6 +2530,7 @@ pub acceleration_error_variance: ::half::f16,
                         }
                         impl ::canadensis_encoding::DataType for FaultFlags {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 2;
                         }
                         impl ::canadensis_encoding::Message for FaultFlags {}
                         impl FaultFlags {}
@@ -2551,6 +2599,7 @@ pub acceleration_error_variance: ::half::f16,
                         }
                         impl ::canadensis_encoding::DataType for Feedback {
                             const EXTENT_BYTES: Option<u32> = Some(63);
+                            const MAX_SIZE_BYTES: u32 = 3;
                         }
                         impl ::canadensis_encoding::Message for Feedback {}
                         impl Feedback {}
@@ -2590,6 +2639,7 @@ pub acceleration_error_variance: ::half::f16,
                             pub struct _0 {}
                             impl ::canadensis_encoding::DataType for _0 {
                                 const EXTENT_BYTES: Option<u32> = Some(0);
+                                const MAX_SIZE_BYTES: u32 = 0;
                             }
                             impl ::canadensis_encoding::Message for _0 {}
                             impl _0 {
@@ -2639,6 +2689,7 @@ pub acceleration_error_variance: ::half::f16,
                             }
                             impl ::canadensis_encoding::DataType for Scalar {
                                 const EXTENT_BYTES: Option<u32> = Some(512);
+                                const MAX_SIZE_BYTES: u32 = 2;
                             }
                             impl ::canadensis_encoding::Message for Scalar {}
                             impl Scalar {}
@@ -2687,6 +2738,7 @@ pub acceleration_error_variance: ::half::f16,
                             }
                             impl ::canadensis_encoding::DataType for Vector2 {
                                 const EXTENT_BYTES: Option<u32> = Some(512);
+                                const MAX_SIZE_BYTES: u32 = 4;
                             }
                             impl ::canadensis_encoding::Message for Vector2 {}
                             impl Vector2 {}
@@ -2735,6 +2787,7 @@ pub acceleration_error_variance: ::half::f16,
                             }
                             impl ::canadensis_encoding::DataType for Vector31 {
                                 const EXTENT_BYTES: Option<u32> = Some(512);
+                                const MAX_SIZE_BYTES: u32 = 62;
                             }
                             impl ::canadensis_encoding::Message for Vector31 {}
                             impl Vector31 {}
@@ -2783,6 +2836,7 @@ pub acceleration_error_variance: ::half::f16,
                             }
                             impl ::canadensis_encoding::DataType for Vector3 {
                                 const EXTENT_BYTES: Option<u32> = Some(512);
+                                const MAX_SIZE_BYTES: u32 = 6;
                             }
                             impl ::canadensis_encoding::Message for Vector3 {}
                             impl Vector3 {}
@@ -2831,6 +2885,7 @@ pub acceleration_error_variance: ::half::f16,
                             }
                             impl ::canadensis_encoding::DataType for Vector4 {
                                 const EXTENT_BYTES: Option<u32> = Some(512);
+                                const MAX_SIZE_BYTES: u32 = 8;
                             }
                             impl ::canadensis_encoding::Message for Vector4 {}
                             impl Vector4 {}
@@ -2879,6 +2934,7 @@ pub acceleration_error_variance: ::half::f16,
                             }
                             impl ::canadensis_encoding::DataType for Vector6 {
                                 const EXTENT_BYTES: Option<u32> = Some(512);
+                                const MAX_SIZE_BYTES: u32 = 12;
                             }
                             impl ::canadensis_encoding::Message for Vector6 {}
                             impl Vector6 {}
@@ -2927,6 +2983,7 @@ pub acceleration_error_variance: ::half::f16,
                             }
                             impl ::canadensis_encoding::DataType for Vector8 {
                                 const EXTENT_BYTES: Option<u32> = Some(512);
+                                const MAX_SIZE_BYTES: u32 = 16;
                             }
                             impl ::canadensis_encoding::Message for Vector8 {}
                             impl Vector8 {}
@@ -2989,6 +3046,7 @@ pub fault_flags: crate::reg::drone::service::actuator::common::fault_flags_0_1::
 }
                         impl ::canadensis_encoding::DataType for Status {
                             const EXTENT_BYTES: Option<u32> = Some(63);
+                            const MAX_SIZE_BYTES: u32 = 14;
                         }
                         impl ::canadensis_encoding::Message for Status {}
                         impl Status {}
@@ -3033,6 +3091,7 @@ pub fault_flags: crate::reg::drone::service::actuator::common::fault_flags_0_1::
                         pub struct _0 {}
                         impl ::canadensis_encoding::DataType for _0 {
                             const EXTENT_BYTES: Option<u32> = Some(0);
+                            const MAX_SIZE_BYTES: u32 = 0;
                         }
                         impl ::canadensis_encoding::Message for _0 {}
                         impl _0 {}
@@ -3073,6 +3132,7 @@ pub fault_flags: crate::reg::drone::service::actuator::common::fault_flags_0_1::
                         pub struct _0 {}
                         impl ::canadensis_encoding::DataType for _0 {
                             const EXTENT_BYTES: Option<u32> = Some(0);
+                            const MAX_SIZE_BYTES: u32 = 0;
                         }
                         impl ::canadensis_encoding::Message for _0 {}
                         impl _0 {}
@@ -3114,6 +3174,7 @@ pub fault_flags: crate::reg::drone::service::actuator::common::fault_flags_0_1::
                     pub struct _0 {}
                     impl ::canadensis_encoding::DataType for _0 {
                         const EXTENT_BYTES: Option<u32> = Some(0);
+                        const MAX_SIZE_BYTES: u32 = 0;
                     }
                     impl ::canadensis_encoding::Message for _0 {}
                     impl _0 {
@@ -3153,6 +3214,7 @@ pub fault_flags: crate::reg::drone::service::actuator::common::fault_flags_0_1::
                     pub struct _0 {}
                     impl ::canadensis_encoding::DataType for _0 {
                         const EXTENT_BYTES: Option<u32> = Some(0);
+                        const MAX_SIZE_BYTES: u32 = 0;
                     }
                     impl ::canadensis_encoding::Message for _0 {}
                     impl _0 {}
@@ -3194,6 +3256,7 @@ pub fault_flags: crate::reg::drone::service::actuator::common::fault_flags_0_1::
                     }
                     impl ::canadensis_encoding::DataType for Error {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 1;
                     }
                     impl ::canadensis_encoding::Message for Error {}
                     impl Error {
@@ -3322,6 +3385,7 @@ pub fault_flags: crate::reg::drone::service::actuator::common::fault_flags_0_1::
                     }
                     impl ::canadensis_encoding::DataType for Parameters {
                         const EXTENT_BYTES: Option<u32> = Some(63);
+                        const MAX_SIZE_BYTES: u32 = 54;
                     }
                     impl ::canadensis_encoding::Message for Parameters {}
                     impl Parameters {}
@@ -3467,6 +3531,7 @@ pub fault_flags: crate::reg::drone::service::actuator::common::fault_flags_0_1::
                     }
                     impl ::canadensis_encoding::DataType for Parameters {
                         const EXTENT_BYTES: Option<u32> = Some(63);
+                        const MAX_SIZE_BYTES: u32 = 54;
                     }
                     impl ::canadensis_encoding::Message for Parameters {}
                     impl Parameters {}
@@ -3615,6 +3680,7 @@ pub fault_flags: crate::reg::drone::service::actuator::common::fault_flags_0_1::
                     }
                     impl ::canadensis_encoding::DataType for Parameters {
                         const EXTENT_BYTES: Option<u32> = Some(67);
+                        const MAX_SIZE_BYTES: u32 = 58;
                     }
                     impl ::canadensis_encoding::Message for Parameters {}
                     impl Parameters {}
@@ -3713,6 +3779,7 @@ pub fault_flags: crate::reg::drone::service::actuator::common::fault_flags_0_1::
                     }
                     impl ::canadensis_encoding::DataType for Status {
                         const EXTENT_BYTES: Option<u32> = Some(63);
+                        const MAX_SIZE_BYTES: u32 = 23;
                     }
                     impl ::canadensis_encoding::Message for Status {}
                     impl Status {}
@@ -3789,6 +3856,7 @@ pub fault_flags: crate::reg::drone::service::actuator::common::fault_flags_0_1::
                     }
                     impl ::canadensis_encoding::DataType for Status {
                         const EXTENT_BYTES: Option<u32> = Some(600);
+                        const MAX_SIZE_BYTES: u32 = 534;
                     }
                     impl ::canadensis_encoding::Message for Status {}
                     impl Status {
@@ -3868,6 +3936,7 @@ pub fault_flags: crate::reg::drone::service::actuator::common::fault_flags_0_1::
                     }
                     impl ::canadensis_encoding::DataType for Technology {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 1;
                     }
                     impl ::canadensis_encoding::Message for Technology {}
                     impl Technology {
@@ -3940,6 +4009,7 @@ pub fault_flags: crate::reg::drone::service::actuator::common::fault_flags_0_1::
                     }
                     impl ::canadensis_encoding::DataType for Heartbeat {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 2;
                     }
                     impl ::canadensis_encoding::Message for Heartbeat {}
                     impl Heartbeat {
@@ -3981,6 +4051,7 @@ pub fault_flags: crate::reg::drone::service::actuator::common::fault_flags_0_1::
                     }
                     impl ::canadensis_encoding::DataType for Readiness {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 1;
                     }
                     impl ::canadensis_encoding::Message for Readiness {}
                     impl Readiness {
@@ -4020,6 +4091,7 @@ pub fault_flags: crate::reg::drone::service::actuator::common::fault_flags_0_1::
                     pub struct _0 {}
                     impl ::canadensis_encoding::DataType for _0 {
                         const EXTENT_BYTES: Option<u32> = Some(0);
+                        const MAX_SIZE_BYTES: u32 = 0;
                     }
                     impl ::canadensis_encoding::Message for _0 {}
                     impl _0 {}
@@ -4091,6 +4163,7 @@ pub fault_flags: crate::reg::drone::service::actuator::common::fault_flags_0_1::
                     }
                     impl ::canadensis_encoding::DataType for DilutionOfPrecision {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 14;
                     }
                     impl ::canadensis_encoding::Message for DilutionOfPrecision {}
                     impl DilutionOfPrecision {}
@@ -4185,6 +4258,7 @@ pub rtk_fix: bool,
 }
                     impl ::canadensis_encoding::DataType for Heartbeat {
                         const EXTENT_BYTES: Option<u32> = Some(124);
+                        const MAX_SIZE_BYTES: u32 = 25;
                     }
                     impl ::canadensis_encoding::Message for Heartbeat {}
                     impl Heartbeat {}
@@ -4343,6 +4417,7 @@ pub rtk_fix: bool,
                     }
                     impl ::canadensis_encoding::DataType for Sources {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 6;
                     }
                     impl ::canadensis_encoding::Message for Sources {}
                     impl Sources {}
@@ -4440,6 +4515,7 @@ pub rtk_fix: bool,
                     }
                     impl ::canadensis_encoding::DataType for Time {
                         const EXTENT_BYTES: Option<u32> = Some(63);
+                        const MAX_SIZE_BYTES: u32 = 21;
                     }
                     impl ::canadensis_encoding::Message for Time {}
                     impl Time {}
@@ -4495,6 +4571,7 @@ pub rtk_fix: bool,
                     }
                     impl ::canadensis_encoding::DataType for Status {
                         const EXTENT_BYTES: Option<u32> = Some(63);
+                        const MAX_SIZE_BYTES: u32 = 12;
                     }
                     impl ::canadensis_encoding::Message for Status {}
                     impl Status {
@@ -4561,6 +4638,7 @@ pub mod uavcan {
             }
             impl ::canadensis_encoding::DataType for Record {
                 const EXTENT_BYTES: Option<u32> = Some(300);
+                const MAX_SIZE_BYTES: u32 = 121;
             }
             impl ::canadensis_encoding::Message for Record {}
             impl Record {}
@@ -4628,6 +4706,7 @@ pub mod uavcan {
             }
             impl ::canadensis_encoding::DataType for Record {
                 const EXTENT_BYTES: Option<u32> = Some(300);
+                const MAX_SIZE_BYTES: u32 = 264;
             }
             impl ::canadensis_encoding::Message for Record {}
             impl Record {}
@@ -4681,6 +4760,7 @@ pub mod uavcan {
             }
             impl ::canadensis_encoding::DataType for Severity {
                 const EXTENT_BYTES: Option<u32> = None;
+                const MAX_SIZE_BYTES: u32 = 1;
             }
             impl ::canadensis_encoding::Message for Severity {}
             impl Severity {
@@ -4731,6 +4811,7 @@ pub mod uavcan {
             }
             impl ::canadensis_encoding::DataType for Error {
                 const EXTENT_BYTES: Option<u32> = None;
+                const MAX_SIZE_BYTES: u32 = 2;
             }
             impl ::canadensis_encoding::Message for Error {}
             impl Error {
@@ -4785,6 +4866,7 @@ pub mod uavcan {
             }
             impl ::canadensis_encoding::DataType for GetInfoRequest {
                 const EXTENT_BYTES: Option<u32> = Some(300);
+                const MAX_SIZE_BYTES: u32 = 113;
             }
             impl ::canadensis_encoding::Request for GetInfoRequest {}
             impl GetInfoRequest {}
@@ -4852,6 +4934,7 @@ pub mod uavcan {
             }
             impl ::canadensis_encoding::DataType for GetInfoResponse {
                 const EXTENT_BYTES: Option<u32> = Some(48);
+                const MAX_SIZE_BYTES: u32 = 13;
             }
             impl ::canadensis_encoding::Response for GetInfoResponse {}
             impl GetInfoResponse {}
@@ -4905,6 +4988,7 @@ pub mod uavcan {
             }
             impl ::canadensis_encoding::DataType for GetInfoRequest {
                 const EXTENT_BYTES: Option<u32> = Some(300);
+                const MAX_SIZE_BYTES: u32 = 256;
             }
             impl ::canadensis_encoding::Request for GetInfoRequest {}
             impl GetInfoRequest {}
@@ -4972,6 +5056,7 @@ pub mod uavcan {
             }
             impl ::canadensis_encoding::DataType for GetInfoResponse {
                 const EXTENT_BYTES: Option<u32> = Some(48);
+                const MAX_SIZE_BYTES: u32 = 13;
             }
             impl ::canadensis_encoding::Response for GetInfoResponse {}
             impl GetInfoResponse {}
@@ -5031,6 +5116,7 @@ pub mod uavcan {
             }
             impl ::canadensis_encoding::DataType for ListRequest {
                 const EXTENT_BYTES: Option<u32> = Some(300);
+                const MAX_SIZE_BYTES: u32 = 121;
             }
             impl ::canadensis_encoding::Request for ListRequest {}
             impl ListRequest {}
@@ -5074,6 +5160,7 @@ pub mod uavcan {
             }
             impl ::canadensis_encoding::DataType for ListResponse {
                 const EXTENT_BYTES: Option<u32> = Some(300);
+                const MAX_SIZE_BYTES: u32 = 117;
             }
             impl ::canadensis_encoding::Response for ListResponse {}
             impl ListResponse {}
@@ -5124,6 +5211,7 @@ pub mod uavcan {
             }
             impl ::canadensis_encoding::DataType for ListRequest {
                 const EXTENT_BYTES: Option<u32> = Some(300);
+                const MAX_SIZE_BYTES: u32 = 264;
             }
             impl ::canadensis_encoding::Request for ListRequest {}
             impl ListRequest {}
@@ -5167,6 +5255,7 @@ pub mod uavcan {
             }
             impl ::canadensis_encoding::DataType for ListResponse {
                 const EXTENT_BYTES: Option<u32> = Some(300);
+                const MAX_SIZE_BYTES: u32 = 260;
             }
             impl ::canadensis_encoding::Response for ListResponse {}
             impl ListResponse {}
@@ -5227,6 +5316,7 @@ pub mod uavcan {
             }
             impl ::canadensis_encoding::DataType for ModifyRequest {
                 const EXTENT_BYTES: Option<u32> = Some(600);
+                const MAX_SIZE_BYTES: u32 = 230;
             }
             impl ::canadensis_encoding::Request for ModifyRequest {}
             impl ModifyRequest {}
@@ -5275,6 +5365,7 @@ pub mod uavcan {
             }
             impl ::canadensis_encoding::DataType for ModifyResponse {
                 const EXTENT_BYTES: Option<u32> = Some(48);
+                const MAX_SIZE_BYTES: u32 = 2;
             }
             impl ::canadensis_encoding::Response for ModifyResponse {}
             impl ModifyResponse {}
@@ -5334,6 +5425,7 @@ pub mod uavcan {
             }
             impl ::canadensis_encoding::DataType for ModifyRequest {
                 const EXTENT_BYTES: Option<u32> = Some(600);
+                const MAX_SIZE_BYTES: u32 = 516;
             }
             impl ::canadensis_encoding::Request for ModifyRequest {}
             impl ModifyRequest {}
@@ -5382,6 +5474,7 @@ pub mod uavcan {
             }
             impl ::canadensis_encoding::DataType for ModifyResponse {
                 const EXTENT_BYTES: Option<u32> = Some(48);
+                const MAX_SIZE_BYTES: u32 = 2;
             }
             impl ::canadensis_encoding::Response for ModifyResponse {}
             impl ModifyResponse {}
@@ -5422,6 +5515,7 @@ pub mod uavcan {
             }
             impl ::canadensis_encoding::DataType for Path {
                 const EXTENT_BYTES: Option<u32> = None;
+                const MAX_SIZE_BYTES: u32 = 113;
             }
             impl ::canadensis_encoding::Message for Path {}
             impl Path {
@@ -5474,6 +5568,7 @@ pub mod uavcan {
             }
             impl ::canadensis_encoding::DataType for Path {
                 const EXTENT_BYTES: Option<u32> = None;
+                const MAX_SIZE_BYTES: u32 = 256;
             }
             impl ::canadensis_encoding::Message for Path {}
             impl Path {
@@ -5534,6 +5629,7 @@ pub mod uavcan {
             }
             impl ::canadensis_encoding::DataType for ReadRequest {
                 const EXTENT_BYTES: Option<u32> = Some(300);
+                const MAX_SIZE_BYTES: u32 = 118;
             }
             impl ::canadensis_encoding::Request for ReadRequest {}
             impl ReadRequest {}
@@ -5577,6 +5673,7 @@ pub mod uavcan {
             }
             impl ::canadensis_encoding::DataType for ReadResponse {
                 const EXTENT_BYTES: Option<u32> = Some(300);
+                const MAX_SIZE_BYTES: u32 = 260;
             }
             impl ::canadensis_encoding::Response for ReadResponse {}
             impl ReadResponse {}
@@ -5636,6 +5733,7 @@ pub mod uavcan {
             }
             impl ::canadensis_encoding::DataType for ReadRequest {
                 const EXTENT_BYTES: Option<u32> = Some(300);
+                const MAX_SIZE_BYTES: u32 = 261;
             }
             impl ::canadensis_encoding::Request for ReadRequest {}
             impl ReadRequest {}
@@ -5679,6 +5777,7 @@ pub mod uavcan {
             }
             impl ::canadensis_encoding::DataType for ReadResponse {
                 const EXTENT_BYTES: Option<u32> = Some(300);
+                const MAX_SIZE_BYTES: u32 = 260;
             }
             impl ::canadensis_encoding::Response for ReadResponse {}
             impl ReadResponse {}
@@ -5731,6 +5830,7 @@ pub mod uavcan {
             }
             impl ::canadensis_encoding::DataType for WriteRequest {
                 const EXTENT_BYTES: Option<u32> = Some(600);
+                const MAX_SIZE_BYTES: u32 = 311;
             }
             impl ::canadensis_encoding::Request for WriteRequest {}
             impl WriteRequest {}
@@ -5785,6 +5885,7 @@ pub mod uavcan {
             }
             impl ::canadensis_encoding::DataType for WriteResponse {
                 const EXTENT_BYTES: Option<u32> = Some(48);
+                const MAX_SIZE_BYTES: u32 = 2;
             }
             impl ::canadensis_encoding::Response for WriteResponse {}
             impl WriteResponse {}
@@ -5838,6 +5939,7 @@ pub mod uavcan {
             }
             impl ::canadensis_encoding::DataType for WriteRequest {
                 const EXTENT_BYTES: Option<u32> = Some(600);
+                const MAX_SIZE_BYTES: u32 = 519;
             }
             impl ::canadensis_encoding::Request for WriteRequest {}
             impl WriteRequest {}
@@ -5880,6 +5982,7 @@ pub mod uavcan {
             }
             impl ::canadensis_encoding::DataType for WriteResponse {
                 const EXTENT_BYTES: Option<u32> = Some(48);
+                const MAX_SIZE_BYTES: u32 = 2;
             }
             impl ::canadensis_encoding::Response for WriteResponse {}
             impl WriteResponse {}
@@ -5931,6 +6034,7 @@ pub mod uavcan {
                 }
                 impl ::canadensis_encoding::DataType for HandleIncomingPacketRequest {
                     const EXTENT_BYTES: Option<u32> = Some(600);
+                    const MAX_SIZE_BYTES: u32 = 313;
                 }
                 impl ::canadensis_encoding::Request for HandleIncomingPacketRequest {}
                 impl HandleIncomingPacketRequest {}
@@ -5979,6 +6083,7 @@ pub mod uavcan {
                 pub struct HandleIncomingPacketResponse {}
                 impl ::canadensis_encoding::DataType for HandleIncomingPacketResponse {
                     const EXTENT_BYTES: Option<u32> = Some(63);
+                    const MAX_SIZE_BYTES: u32 = 0;
                 }
                 impl ::canadensis_encoding::Response for HandleIncomingPacketResponse {}
                 impl HandleIncomingPacketResponse {}
@@ -6029,6 +6134,7 @@ pub mod uavcan {
                 }
                 impl ::canadensis_encoding::DataType for HandleIncomingPacketRequest {
                     const EXTENT_BYTES: Option<u32> = Some(600);
+                    const MAX_SIZE_BYTES: u32 = 512;
                 }
                 impl ::canadensis_encoding::Request for HandleIncomingPacketRequest {}
                 impl HandleIncomingPacketRequest {}
@@ -6077,6 +6183,7 @@ pub mod uavcan {
                 pub struct HandleIncomingPacketResponse {}
                 impl ::canadensis_encoding::DataType for HandleIncomingPacketResponse {
                     const EXTENT_BYTES: Option<u32> = Some(63);
+                    const MAX_SIZE_BYTES: u32 = 0;
                 }
                 impl ::canadensis_encoding::Response for HandleIncomingPacketResponse {}
                 impl HandleIncomingPacketResponse {}
@@ -6148,6 +6255,7 @@ pub mod uavcan {
                 }
                 impl ::canadensis_encoding::DataType for OutgoingPacket {
                     const EXTENT_BYTES: Option<u32> = Some(600);
+                    const MAX_SIZE_BYTES: u32 = 313;
                 }
                 impl ::canadensis_encoding::Message for OutgoingPacket {}
                 impl OutgoingPacket {
@@ -6264,6 +6372,7 @@ pub mod uavcan {
                 }
                 impl ::canadensis_encoding::DataType for OutgoingPacket {
                     const EXTENT_BYTES: Option<u32> = Some(600);
+                    const MAX_SIZE_BYTES: u32 = 561;
                 }
                 impl ::canadensis_encoding::Message for OutgoingPacket {}
                 impl OutgoingPacket {
@@ -6354,6 +6463,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
 }
                 impl ::canadensis_encoding::DataType for ArbitrationID {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 5;
                 }
                 impl ::canadensis_encoding::Message for ArbitrationID {}
                 impl ArbitrationID {}
@@ -6403,6 +6513,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for BaseArbitrationID {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 4;
                 }
                 impl ::canadensis_encoding::Message for BaseArbitrationID {}
                 impl BaseArbitrationID {}
@@ -6447,6 +6558,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for DataClassic {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 14;
                 }
                 impl ::canadensis_encoding::Message for DataClassic {}
                 impl DataClassic {}
@@ -6506,6 +6618,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for DataFD {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 70;
                 }
                 impl ::canadensis_encoding::Message for DataFD {}
                 impl DataFD {}
@@ -6555,6 +6668,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Error {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 4;
                 }
                 impl ::canadensis_encoding::Message for Error {}
                 impl Error {}
@@ -6591,6 +6705,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for ExtendedArbitrationID {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 4;
                 }
                 impl ::canadensis_encoding::Message for ExtendedArbitrationID {}
                 impl ExtendedArbitrationID {}
@@ -6636,6 +6751,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Frame {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 78;
                 }
                 impl ::canadensis_encoding::Message for Frame {}
                 impl Frame {}
@@ -6678,6 +6794,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Frame {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 71;
                 }
                 impl ::canadensis_encoding::Message for Frame {}
                 impl Frame {}
@@ -6746,6 +6863,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Manifestation {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 71;
                 }
                 impl ::canadensis_encoding::Message for Manifestation {}
                 impl Manifestation {}
@@ -6812,6 +6930,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for RTR {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 5;
                 }
                 impl ::canadensis_encoding::Message for RTR {}
                 impl RTR {}
@@ -6853,6 +6972,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for EtherType {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 2;
                 }
                 impl ::canadensis_encoding::Message for EtherType {}
                 impl EtherType {
@@ -6913,6 +7033,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Frame {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 9232;
                 }
                 impl ::canadensis_encoding::Message for Frame {}
                 impl Frame {}
@@ -7001,6 +7122,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Fragment {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 265;
                 }
                 impl ::canadensis_encoding::Message for Fragment {}
                 impl Fragment {
@@ -7056,6 +7178,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Fragment {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 2050;
                 }
                 impl ::canadensis_encoding::Message for Fragment {}
                 impl Fragment {
@@ -7122,6 +7245,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Endpoint {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 32;
                 }
                 impl ::canadensis_encoding::Message for Endpoint {}
                 impl Endpoint {}
@@ -7209,6 +7333,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Frame {
                     const EXTENT_BYTES: Option<u32> = Some(10240);
+                    const MAX_SIZE_BYTES: u32 = 9262;
                 }
                 impl ::canadensis_encoding::Message for Frame {}
                 impl Frame {
@@ -7283,6 +7408,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
             }
             impl ::canadensis_encoding::DataType for ExecuteCommandRequest {
                 const EXTENT_BYTES: Option<u32> = Some(300);
+                const MAX_SIZE_BYTES: u32 = 115;
             }
             impl ::canadensis_encoding::Request for ExecuteCommandRequest {}
             impl ExecuteCommandRequest {
@@ -7342,6 +7468,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
             }
             impl ::canadensis_encoding::DataType for ExecuteCommandResponse {
                 const EXTENT_BYTES: Option<u32> = Some(48);
+                const MAX_SIZE_BYTES: u32 = 1;
             }
             impl ::canadensis_encoding::Response for ExecuteCommandResponse {}
             impl ExecuteCommandResponse {
@@ -7401,6 +7528,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
             }
             impl ::canadensis_encoding::DataType for ExecuteCommandRequest {
                 const EXTENT_BYTES: Option<u32> = Some(300);
+                const MAX_SIZE_BYTES: u32 = 258;
             }
             impl ::canadensis_encoding::Request for ExecuteCommandRequest {}
             impl ExecuteCommandRequest {
@@ -7460,6 +7588,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
             }
             impl ::canadensis_encoding::DataType for ExecuteCommandResponse {
                 const EXTENT_BYTES: Option<u32> = Some(48);
+                const MAX_SIZE_BYTES: u32 = 1;
             }
             impl ::canadensis_encoding::Response for ExecuteCommandResponse {}
             impl ExecuteCommandResponse {
@@ -7510,6 +7639,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
             pub struct GetInfoRequest {}
             impl ::canadensis_encoding::DataType for GetInfoRequest {
                 const EXTENT_BYTES: Option<u32> = None;
+                const MAX_SIZE_BYTES: u32 = 0;
             }
             impl ::canadensis_encoding::Request for GetInfoRequest {}
             impl GetInfoRequest {}
@@ -7583,6 +7713,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
             }
             impl ::canadensis_encoding::DataType for GetInfoResponse {
                 const EXTENT_BYTES: Option<u32> = Some(448);
+                const MAX_SIZE_BYTES: u32 = 313;
             }
             impl ::canadensis_encoding::Response for GetInfoResponse {}
             impl GetInfoResponse {}
@@ -7700,6 +7831,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
             pub struct GetTransportStatisticsRequest {}
             impl ::canadensis_encoding::DataType for GetTransportStatisticsRequest {
                 const EXTENT_BYTES: Option<u32> = None;
+                const MAX_SIZE_BYTES: u32 = 0;
             }
             impl ::canadensis_encoding::Request for GetTransportStatisticsRequest {}
             impl GetTransportStatisticsRequest {}
@@ -7747,6 +7879,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
             }
             impl ::canadensis_encoding::DataType for GetTransportStatisticsResponse {
                 const EXTENT_BYTES: Option<u32> = Some(192);
+                const MAX_SIZE_BYTES: u32 = 61;
             }
             impl ::canadensis_encoding::Response for GetTransportStatisticsResponse {}
             impl GetTransportStatisticsResponse {
@@ -7802,6 +7935,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
             }
             impl ::canadensis_encoding::DataType for Health {
                 const EXTENT_BYTES: Option<u32> = None;
+                const MAX_SIZE_BYTES: u32 = 1;
             }
             impl ::canadensis_encoding::Message for Health {}
             impl Health {
@@ -7862,6 +7996,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
             }
             impl ::canadensis_encoding::DataType for Heartbeat {
                 const EXTENT_BYTES: Option<u32> = Some(12);
+                const MAX_SIZE_BYTES: u32 = 7;
             }
             impl ::canadensis_encoding::Message for Heartbeat {}
             impl Heartbeat {
@@ -7910,6 +8045,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
             }
             impl ::canadensis_encoding::DataType for ID {
                 const EXTENT_BYTES: Option<u32> = None;
+                const MAX_SIZE_BYTES: u32 = 2;
             }
             impl ::canadensis_encoding::Message for ID {}
             impl ID {}
@@ -7960,6 +8096,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
             }
             impl ::canadensis_encoding::DataType for IOStatistics {
                 const EXTENT_BYTES: Option<u32> = None;
+                const MAX_SIZE_BYTES: u32 = 15;
             }
             impl ::canadensis_encoding::Message for IOStatistics {}
             impl IOStatistics {}
@@ -8001,6 +8138,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
             }
             impl ::canadensis_encoding::DataType for Mode {
                 const EXTENT_BYTES: Option<u32> = None;
+                const MAX_SIZE_BYTES: u32 = 1;
             }
             impl ::canadensis_encoding::Message for Mode {}
             impl Mode {
@@ -8043,6 +8181,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for ID {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 3;
                 }
                 impl ::canadensis_encoding::Message for ID {}
                 impl ID {}
@@ -8109,6 +8248,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for List {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 2194;
                 }
                 impl ::canadensis_encoding::Message for List {}
                 impl List {
@@ -8161,6 +8301,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for ServiceID {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 2;
                 }
                 impl ::canadensis_encoding::Message for ServiceID {}
                 impl ServiceID {
@@ -8200,6 +8341,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for ServiceIDList {
                     const EXTENT_BYTES: Option<u32> = Some(128);
+                    const MAX_SIZE_BYTES: u32 = 64;
                 }
                 impl ::canadensis_encoding::Message for ServiceIDList {}
                 impl ServiceIDList {
@@ -8243,6 +8385,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for SubjectID {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 2;
                 }
                 impl ::canadensis_encoding::Message for SubjectID {}
                 impl SubjectID {
@@ -8285,6 +8428,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for SubjectIDList {
                     const EXTENT_BYTES: Option<u32> = Some(4097);
+                    const MAX_SIZE_BYTES: u32 = 1025;
                 }
                 impl ::canadensis_encoding::Message for SubjectIDList {}
                 impl SubjectIDList {
@@ -8372,6 +8516,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
             }
             impl ::canadensis_encoding::DataType for Version {
                 const EXTENT_BYTES: Option<u32> = None;
+                const MAX_SIZE_BYTES: u32 = 2;
             }
             impl ::canadensis_encoding::Message for Version {}
             impl Version {}
@@ -8439,6 +8584,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for AppendEntriesRequest {
                     const EXTENT_BYTES: Option<u32> = Some(96);
+                    const MAX_SIZE_BYTES: u32 = 35;
                 }
                 impl ::canadensis_encoding::Request for AppendEntriesRequest {}
                 impl AppendEntriesRequest {
@@ -8507,6 +8653,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for AppendEntriesResponse {
                     const EXTENT_BYTES: Option<u32> = Some(48);
+                    const MAX_SIZE_BYTES: u32 = 5;
                 }
                 impl ::canadensis_encoding::Response for AppendEntriesResponse {}
                 impl AppendEntriesResponse {}
@@ -8555,6 +8702,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Discovery {
                     const EXTENT_BYTES: Option<u32> = Some(96);
+                    const MAX_SIZE_BYTES: u32 = 12;
                 }
                 impl ::canadensis_encoding::Message for Discovery {}
                 impl Discovery {
@@ -8627,6 +8775,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Entry {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 22;
                 }
                 impl ::canadensis_encoding::Message for Entry {}
                 impl Entry {}
@@ -8684,6 +8833,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for RequestVoteRequest {
                     const EXTENT_BYTES: Option<u32> = Some(48);
+                    const MAX_SIZE_BYTES: u32 = 10;
                 }
                 impl ::canadensis_encoding::Request for RequestVoteRequest {}
                 impl RequestVoteRequest {}
@@ -8736,6 +8886,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for RequestVoteResponse {
                     const EXTENT_BYTES: Option<u32> = Some(48);
+                    const MAX_SIZE_BYTES: u32 = 5;
                 }
                 impl ::canadensis_encoding::Response for RequestVoteResponse {}
                 impl RequestVoteResponse {}
@@ -8784,6 +8935,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
             }
             impl ::canadensis_encoding::DataType for NodeIDAllocationData {
                 const EXTENT_BYTES: Option<u32> = None;
+                const MAX_SIZE_BYTES: u32 = 9;
             }
             impl ::canadensis_encoding::Message for NodeIDAllocationData {}
             impl NodeIDAllocationData {}
@@ -8847,6 +8999,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
             }
             impl ::canadensis_encoding::DataType for NodeIDAllocationData {
                 const EXTENT_BYTES: Option<u32> = Some(48);
+                const MAX_SIZE_BYTES: u32 = 18;
             }
             impl ::canadensis_encoding::Message for NodeIDAllocationData {}
             impl NodeIDAllocationData {}
@@ -8897,6 +9050,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Bit {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 258;
                 }
                 impl ::canadensis_encoding::Message for Bit {}
                 impl Bit {}
@@ -8941,6 +9095,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Integer16 {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 257;
                 }
                 impl ::canadensis_encoding::Message for Integer16 {}
                 impl Integer16 {}
@@ -8994,6 +9149,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Integer32 {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 257;
                 }
                 impl ::canadensis_encoding::Message for Integer32 {}
                 impl Integer32 {}
@@ -9047,6 +9203,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Integer64 {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 257;
                 }
                 impl ::canadensis_encoding::Message for Integer64 {}
                 impl Integer64 {}
@@ -9100,6 +9257,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Integer8 {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 258;
                 }
                 impl ::canadensis_encoding::Message for Integer8 {}
                 impl Integer8 {}
@@ -9153,6 +9311,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Natural16 {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 257;
                 }
                 impl ::canadensis_encoding::Message for Natural16 {}
                 impl Natural16 {}
@@ -9206,6 +9365,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Natural32 {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 257;
                 }
                 impl ::canadensis_encoding::Message for Natural32 {}
                 impl Natural32 {}
@@ -9259,6 +9419,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Natural64 {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 257;
                 }
                 impl ::canadensis_encoding::Message for Natural64 {}
                 impl Natural64 {}
@@ -9312,6 +9473,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Natural8 {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 258;
                 }
                 impl ::canadensis_encoding::Message for Natural8 {}
                 impl Natural8 {}
@@ -9363,6 +9525,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Real16 {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 257;
                 }
                 impl ::canadensis_encoding::Message for Real16 {}
                 impl Real16 {}
@@ -9416,6 +9579,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Real32 {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 257;
                 }
                 impl ::canadensis_encoding::Message for Real32 {}
                 impl Real32 {}
@@ -9469,6 +9633,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Real64 {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 257;
                 }
                 impl ::canadensis_encoding::Message for Real64 {}
                 impl Real64 {}
@@ -9519,6 +9684,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
             pub struct Empty {}
             impl ::canadensis_encoding::DataType for Empty {
                 const EXTENT_BYTES: Option<u32> = None;
+                const MAX_SIZE_BYTES: u32 = 0;
             }
             impl ::canadensis_encoding::Message for Empty {}
             impl Empty {}
@@ -9559,6 +9725,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Bit {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 1;
                 }
                 impl ::canadensis_encoding::Message for Bit {}
                 impl Bit {}
@@ -9598,6 +9765,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Integer16 {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 2;
                 }
                 impl ::canadensis_encoding::Message for Integer16 {}
                 impl Integer16 {}
@@ -9640,6 +9808,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Integer32 {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 4;
                 }
                 impl ::canadensis_encoding::Message for Integer32 {}
                 impl Integer32 {}
@@ -9682,6 +9851,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Integer64 {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 8;
                 }
                 impl ::canadensis_encoding::Message for Integer64 {}
                 impl Integer64 {}
@@ -9724,6 +9894,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Integer8 {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 1;
                 }
                 impl ::canadensis_encoding::Message for Integer8 {}
                 impl Integer8 {}
@@ -9766,6 +9937,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Natural16 {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 2;
                 }
                 impl ::canadensis_encoding::Message for Natural16 {}
                 impl Natural16 {}
@@ -9808,6 +9980,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Natural32 {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 4;
                 }
                 impl ::canadensis_encoding::Message for Natural32 {}
                 impl Natural32 {}
@@ -9850,6 +10023,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Natural64 {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 8;
                 }
                 impl ::canadensis_encoding::Message for Natural64 {}
                 impl Natural64 {}
@@ -9892,6 +10066,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Natural8 {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 1;
                 }
                 impl ::canadensis_encoding::Message for Natural8 {}
                 impl Natural8 {}
@@ -9934,6 +10109,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Real16 {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 2;
                 }
                 impl ::canadensis_encoding::Message for Real16 {}
                 impl Real16 {}
@@ -9976,6 +10152,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Real32 {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 4;
                 }
                 impl ::canadensis_encoding::Message for Real32 {}
                 impl Real32 {}
@@ -10018,6 +10195,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Real64 {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 8;
                 }
                 impl ::canadensis_encoding::Message for Real64 {}
                 impl Real64 {}
@@ -10059,6 +10237,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
             }
             impl ::canadensis_encoding::DataType for String {
                 const EXTENT_BYTES: Option<u32> = None;
+                const MAX_SIZE_BYTES: u32 = 258;
             }
             impl ::canadensis_encoding::Message for String {}
             impl String {}
@@ -10108,6 +10287,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
             }
             impl ::canadensis_encoding::DataType for Unstructured {
                 const EXTENT_BYTES: Option<u32> = None;
+                const MAX_SIZE_BYTES: u32 = 258;
             }
             impl ::canadensis_encoding::Message for Unstructured {}
             impl Unstructured {}
@@ -10167,6 +10347,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
             }
             impl ::canadensis_encoding::DataType for AccessRequest {
                 const EXTENT_BYTES: Option<u32> = None;
+                const MAX_SIZE_BYTES: u32 = 515;
             }
             impl ::canadensis_encoding::Request for AccessRequest {}
             impl AccessRequest {}
@@ -10222,6 +10403,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
             }
             impl ::canadensis_encoding::DataType for AccessResponse {
                 const EXTENT_BYTES: Option<u32> = None;
+                const MAX_SIZE_BYTES: u32 = 267;
             }
             impl ::canadensis_encoding::Response for AccessResponse {}
             impl AccessResponse {}
@@ -10274,6 +10456,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
             }
             impl ::canadensis_encoding::DataType for ListRequest {
                 const EXTENT_BYTES: Option<u32> = None;
+                const MAX_SIZE_BYTES: u32 = 2;
             }
             impl ::canadensis_encoding::Request for ListRequest {}
             impl ListRequest {}
@@ -10313,6 +10496,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
             }
             impl ::canadensis_encoding::DataType for ListResponse {
                 const EXTENT_BYTES: Option<u32> = None;
+                const MAX_SIZE_BYTES: u32 = 256;
             }
             impl ::canadensis_encoding::Response for ListResponse {}
             impl ListResponse {}
@@ -10350,6 +10534,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
             }
             impl ::canadensis_encoding::DataType for Name {
                 const EXTENT_BYTES: Option<u32> = None;
+                const MAX_SIZE_BYTES: u32 = 256;
             }
             impl ::canadensis_encoding::Message for Name {}
             impl Name {}
@@ -10424,6 +10609,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
             }
             impl ::canadensis_encoding::DataType for Value {
                 const EXTENT_BYTES: Option<u32> = None;
+                const MAX_SIZE_BYTES: u32 = 259;
             }
             impl ::canadensis_encoding::Message for Value {}
             impl Value {}
@@ -10563,6 +10749,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 11;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -10608,6 +10795,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Vector3 {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 19;
                     }
                     impl ::canadensis_encoding::Message for Vector3 {}
                     impl Vector3 {}
@@ -10659,6 +10847,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Quaternion {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 23;
                     }
                     impl ::canadensis_encoding::Message for Quaternion {}
                     impl Quaternion {}
@@ -10713,6 +10902,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 11;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -10760,6 +10950,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 11;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -10805,6 +10996,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Vector3 {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 19;
                     }
                     impl ::canadensis_encoding::Message for Vector3 {}
                     impl Vector3 {}
@@ -10856,6 +11048,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 11;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -10901,6 +11094,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Vector3 {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 19;
                     }
                     impl ::canadensis_encoding::Message for Vector3 {}
                     impl Vector3 {}
@@ -10952,6 +11146,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 11;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -10997,6 +11192,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for WideScalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 15;
                     }
                     impl ::canadensis_encoding::Message for WideScalar {}
                     impl WideScalar {}
@@ -11044,6 +11240,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 11;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -11091,6 +11288,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 11;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -11138,6 +11336,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 11;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -11185,6 +11384,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 11;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -11230,6 +11430,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Vector3 {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 19;
                     }
                     impl ::canadensis_encoding::Message for Vector3 {}
                     impl Vector3 {}
@@ -11281,6 +11482,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 11;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -11328,6 +11530,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 11;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -11373,6 +11576,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Vector3 {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 19;
                     }
                     impl ::canadensis_encoding::Message for Vector3 {}
                     impl Vector3 {}
@@ -11422,6 +11626,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for WideScalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 15;
                     }
                     impl ::canadensis_encoding::Message for WideScalar {}
                     impl WideScalar {}
@@ -11467,6 +11672,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for WideVector3 {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 31;
                     }
                     impl ::canadensis_encoding::Message for WideVector3 {}
                     impl WideVector3 {}
@@ -11518,6 +11724,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 11;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -11563,6 +11770,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Vector3 {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 19;
                     }
                     impl ::canadensis_encoding::Message for Vector3 {}
                     impl Vector3 {}
@@ -11614,6 +11822,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 11;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -11661,6 +11870,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 11;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -11708,6 +11918,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 11;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -11755,6 +11966,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 11;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -11802,6 +12014,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 11;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -11847,6 +12060,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Vector3 {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 19;
                     }
                     impl ::canadensis_encoding::Message for Vector3 {}
                     impl Vector3 {}
@@ -11898,6 +12112,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 11;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -11943,6 +12158,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Vector3 {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 19;
                     }
                     impl ::canadensis_encoding::Message for Vector3 {}
                     impl Vector3 {}
@@ -11994,6 +12210,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 11;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -12041,6 +12258,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 11;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -12088,6 +12306,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 11;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -12133,6 +12352,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 4;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -12178,6 +12398,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Vector3 {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 12;
                     }
                     impl ::canadensis_encoding::Message for Vector3 {}
                     impl Vector3 {}
@@ -12225,6 +12446,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Quaternion {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 16;
                     }
                     impl ::canadensis_encoding::Message for Quaternion {}
                     impl Quaternion {}
@@ -12267,6 +12489,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 4;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -12311,6 +12534,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 4;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -12356,6 +12580,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Vector3 {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 12;
                     }
                     impl ::canadensis_encoding::Message for Vector3 {}
                     impl Vector3 {}
@@ -12403,6 +12628,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 4;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -12445,6 +12671,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Vector3 {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 12;
                     }
                     impl ::canadensis_encoding::Message for Vector3 {}
                     impl Vector3 {}
@@ -12489,6 +12716,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 4;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -12531,6 +12759,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for WideScalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 8;
                     }
                     impl ::canadensis_encoding::Message for WideScalar {}
                     impl WideScalar {}
@@ -12575,6 +12804,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 4;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -12619,6 +12849,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 4;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -12663,6 +12894,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 4;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -12707,6 +12939,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 4;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -12749,6 +12982,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Vector3 {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 12;
                     }
                     impl ::canadensis_encoding::Message for Vector3 {}
                     impl Vector3 {}
@@ -12793,6 +13027,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 4;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -12837,6 +13072,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 4;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -12879,6 +13115,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Vector3 {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 12;
                     }
                     impl ::canadensis_encoding::Message for Vector3 {}
                     impl Vector3 {}
@@ -12921,6 +13158,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for WideScalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 8;
                     }
                     impl ::canadensis_encoding::Message for WideScalar {}
                     impl WideScalar {}
@@ -12963,6 +13201,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for WideVector3 {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 24;
                     }
                     impl ::canadensis_encoding::Message for WideVector3 {}
                     impl WideVector3 {}
@@ -13007,6 +13246,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 4;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -13049,6 +13289,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Vector3 {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 12;
                     }
                     impl ::canadensis_encoding::Message for Vector3 {}
                     impl Vector3 {}
@@ -13093,6 +13334,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 4;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -13137,6 +13379,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 4;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -13181,6 +13424,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 4;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -13225,6 +13469,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 4;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -13269,6 +13514,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 4;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -13311,6 +13557,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Vector3 {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 12;
                     }
                     impl ::canadensis_encoding::Message for Vector3 {}
                     impl Vector3 {}
@@ -13355,6 +13602,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 4;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -13397,6 +13645,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Vector3 {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 12;
                     }
                     impl ::canadensis_encoding::Message for Vector3 {}
                     impl Vector3 {}
@@ -13441,6 +13690,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 4;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -13485,6 +13735,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 4;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -13529,6 +13780,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 4;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -13575,6 +13827,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
             pub struct GetSynchronizationMasterInfoRequest {}
             impl ::canadensis_encoding::DataType for GetSynchronizationMasterInfoRequest {
                 const EXTENT_BYTES: Option<u32> = Some(48);
+                const MAX_SIZE_BYTES: u32 = 0;
             }
             impl ::canadensis_encoding::Request for GetSynchronizationMasterInfoRequest {}
             impl GetSynchronizationMasterInfoRequest {}
@@ -13626,6 +13879,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
             }
             impl ::canadensis_encoding::DataType for GetSynchronizationMasterInfoResponse {
                 const EXTENT_BYTES: Option<u32> = Some(192);
+                const MAX_SIZE_BYTES: u32 = 7;
             }
             impl ::canadensis_encoding::Response for GetSynchronizationMasterInfoResponse {}
             impl GetSynchronizationMasterInfoResponse {}
@@ -13670,6 +13924,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
             }
             impl ::canadensis_encoding::DataType for Synchronization {
                 const EXTENT_BYTES: Option<u32> = None;
+                const MAX_SIZE_BYTES: u32 = 7;
             }
             impl ::canadensis_encoding::Message for Synchronization {}
             impl Synchronization {
@@ -13710,6 +13965,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
             }
             impl ::canadensis_encoding::DataType for SynchronizedTimestamp {
                 const EXTENT_BYTES: Option<u32> = None;
+                const MAX_SIZE_BYTES: u32 = 7;
             }
             impl ::canadensis_encoding::Message for SynchronizedTimestamp {}
             impl SynchronizedTimestamp {
@@ -13749,6 +14005,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
             }
             impl ::canadensis_encoding::DataType for TAIInfo {
                 const EXTENT_BYTES: Option<u32> = None;
+                const MAX_SIZE_BYTES: u32 = 2;
             }
             impl ::canadensis_encoding::Message for TAIInfo {}
             impl TAIInfo {
@@ -13789,6 +14046,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
             }
             impl ::canadensis_encoding::DataType for TimeSystem {
                 const EXTENT_BYTES: Option<u32> = None;
+                const MAX_SIZE_BYTES: u32 = 1;
             }
             impl ::canadensis_encoding::Message for TimeSystem {}
             impl TimeSystem {
@@ -27,6 +27,9 @@ impl Display for ImplementDataType<'_> {
                 )?;
             }
         }
+        let max_size_bytes = self.0.size.max_value().div_ceil(8);
+        let max_size_bytes = u32::try_from(max_size_bytes).expect("Maximum size too large for u32");
+        writeln!(f, "const MAX_SIZE_BYTES: u32 = {};", max_size_bytes)?;
         writeln!(f, "}}")?;
 
         // Part 2: Role
@@ -3,6 +3,8 @@
 pub mod u48;
 
 use crate::time::u48::U48;
+use alloc::rc::Rc;
+use core::cell::Cell;
 use core::cmp::Ordering;
 use core::convert::TryInto;
 use core::fmt::Debug;
@@ -359,6 +361,53 @@ pub trait Clock {
     fn now(&mut self) -> Self::Instant;
 }
 
+/// A clock that only changes when it is explicitly advanced
+///
+/// Clones of a simulated clock share the same time, so several simulated nodes can use clones
+/// of one clock and a test can control the time of all of them.
+///
+/// A new clock starts at time zero.
+#[derive(Debug, Clone, Default)]
+pub struct SimulatedClock(Rc<Cell<Microseconds64>>);
+
+impl SimulatedClock {
+    /// Creates a clock with the time set to zero
+    pub fn new() -> Self {
+        SimulatedClock::default()
+    }
+
+    /// Returns the current time
+    ///
+    /// This is equivalent to [`now`](Clock::now), but does not need a mutable reference.
+    pub fn time(&self) -> Microseconds64 {
+        self.0.get()
+    }
+
+    /// Moves the time forward by the provided duration
+    pub fn advance(&self, duration: MicrosecondDuration64) {
+        self.0.set(duration + self.0.get());
+    }
+
+    /// Sets the current time
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `now` is earlier than the current time, because clocks must be
+    /// monotonic.
+    pub fn set(&self, now: Microseconds64) {
+        assert!(now >= self.0.get(), "Simulated clock moved backwards");
+        self.0.set(now);
+    }
+}
+
+impl Clock for SimulatedClock {
+    type Instant = Microseconds64;
+
+    fn now(&mut self) -> Self::Instant {
+        self.time()
+    }
+}
+
 /// Creates a duration from a number of milliseconds
 ///
 /// This is a convenient wrapper for T::from_millis() when T has a long-difficult-to-type name.
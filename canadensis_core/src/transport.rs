@@ -2,7 +2,7 @@
 
 use crate::error::{OutOfMemoryError, ServiceSubscribeError};
 use crate::time::{Clock, Instant};
-use crate::transfer::Transfer;
+use crate::transfer::{Header, Transfer};
 use crate::{ServiceId, SubjectId};
 use alloc::vec::Vec;
 use core::convert::TryFrom;
@@ -49,6 +49,26 @@ where
         A: AsRef<[u8]>,
         C: Clock<Instant = I>;
 
+    /// Starts the process of sending an outgoing transfer and marks it for loopback
+    ///
+    /// This works like [`push`](#tymethod.push). When the transfer has been transmitted,
+    /// [`pop_loopback`](#method.pop_loopback) returns its header with the transmission time.
+    ///
+    /// The default implementation calls `push`, which is appropriate for transports that
+    /// do not support loopback.
+    fn push_loopback<A, C>(
+        &mut self,
+        transfer: Transfer<A, I, Self::Transport>,
+        clock: &mut C,
+        driver: &mut Self::Driver,
+    ) -> nb::Result<(), Self::Error>
+    where
+        A: AsRef<[u8]>,
+        C: Clock<Instant = I>,
+    {
+        self.push(transfer, clock, driver)
+    }
+
     /// Attempts to send all queued outgoing frames
     ///
     /// If ths transport's `push` implementation blocks until all frames have been sent,
@@ -65,6 +85,19 @@ where
     where
         C: Clock<Instant = I>;
 
+    /// Returns the header of an outgoing transfer that was passed to
+    /// [`push_loopback`](#method.push_loopback) and has been transmitted
+    ///
+    /// The timestamp in the returned header is the time when the transfer (or its first frame)
+    /// was transmitted.
+    ///
+    /// The default implementation always returns None, which is appropriate for transports that
+    /// do not support loopback.
+    fn pop_loopback(&mut self, driver: &mut Self::Driver) -> Option<Header<I, Self::Transport>> {
+        let _ = driver;
+        None
+    }
+
     /// Returns the maximum transmission unit of this transport, in bytes
     ///
     /// A message larger than this will need to be split into multiple frames.
@@ -72,6 +105,15 @@ where
     /// For example, UAVCAN/CAN over classic CAN can transfer up to 7 bytes per frame (the eighth
     /// byte is used up by the tail byte), so it would return 7.
     fn mtu(&self) -> usize;
+
+    /// Returns the number of transfers and frames that this transmitter has sent, and the number
+    /// of errors
+    ///
+    /// The default implementation always returns zero counts, which is appropriate for
+    /// transmitters that do not keep statistics.
+    fn statistics(&self) -> Statistics {
+        Statistics::default()
+    }
 }
 
 /// A receiver that can assemble incoming frames into transfers
@@ -192,6 +234,15 @@ where
 
     /// Unsubscribes from responses for a service
     fn unsubscribe_response(&mut self, service: ServiceId, driver: &mut Self::Driver);
+
+    /// Returns the number of transfers and frames that this receiver has received, and the number
+    /// of errors
+    ///
+    /// The default implementation always returns zero counts, which is appropriate for receivers
+    /// that do not keep statistics.
+    fn statistics(&self) -> Statistics {
+        Statistics::default()
+    }
 }
 
 /// Required operations for a transfer ID
@@ -202,3 +253,51 @@ pub trait TransferId: Default + Debug + Clone {
     /// minimum allowed value.
     fn increment(self) -> Self;
 }
+
+/// Counts of the transfers, frames, and errors that a transmitter or receiver has handled
+///
+/// All counts start at zero and wrap around when they reach the maximum value.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct Statistics {
+    /// The number of transfers successfully transmitted or received
+    pub transfers: u64,
+    /// The number of frames successfully transmitted or received
+    pub frames: u64,
+    /// The number of errors
+    ///
+    /// What counts as an error depends on the transport. Examples include failures to allocate
+    /// memory, malformed frames, and missing frames.
+    pub errors: u64,
+}
+
+/// Something that keeps statistics about the frames it has handled on one or more network
+/// interfaces
+///
+/// Transmitters and receivers report their total statistics using
+/// [`Transmitter::statistics`](Transmitter::statistics) and
+/// [`Receiver::statistics`](Receiver::statistics). Components of redundant transports, like
+/// drivers that send frames on more than one network interface, implement this trait to report
+/// statistics for each interface.
+pub trait TransportStatistics {
+    /// Returns the total statistics
+    fn statistics(&self) -> Statistics;
+
+    /// Returns the number of network interfaces
+    ///
+    /// The default implementation returns 1.
+    fn interface_count(&self) -> usize {
+        1
+    }
+
+    /// Returns the statistics for one network interface, or None if `interface` is not less than
+    /// [`interface_count`](#method.interface_count)
+    ///
+    /// The default implementation returns the total statistics for interface 0.
+    fn interface_statistics(&self, interface: usize) -> Option<Statistics> {
+        if interface == 0 {
+            Some(self.statistics())
+        } else {
+            None
+        }
+    }
+}
@@ -10,6 +10,9 @@
 //!       binary and clear the CRC.
 //! * Load the .elf file onto the target microcontroller and run it as usual
 //!
+//! [`Crc64We`] calculates CRCs the same way as `canadensis_write_crc`. It can be used to check
+//! a software image before installing it.
+//!
 
 #![no_std]
 #![deny(missing_docs)]
@@ -39,3 +42,85 @@ pub fn get_crc() -> Option<u64> {
         None
     }
 }
+
+/// Calculates a Wolfgang Ehrhardt 64-bit CRC (CRC-64/WE) the same way as `canadensis_write_crc`
+///
+/// If the total length of the data is not a multiple of 8 bytes, [`finish`](#method.finish) pads
+/// it with zero bytes.
+///
+/// # Examples
+///
+/// ```
+/// # use canadensis_crc::Crc64We;
+/// let mut crc = Crc64We::new();
+/// crc.digest(b"1234");
+/// crc.digest(b"5678");
+/// assert_eq!(crc.clone().finish(), 0x3461b5f4e1840c46);
+/// // Data with a length that is not a multiple of 8 is padded with zeros
+/// crc.digest(b"9");
+/// assert_eq!(crc.finish(), 0xc2f614de2de0227f);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Crc64We {
+    /// The current CRC value, without the final XOR
+    value: u64,
+    /// The number of bytes processed, modulo 8
+    length_mod_8: u8,
+}
+
+impl Crc64We {
+    const POLYNOMIAL: u64 = 0x42f0_e1eb_a9ea_3693;
+    const TABLE: [u64; 256] = make_table(Self::POLYNOMIAL);
+
+    /// Creates a CRC calculator with no data
+    pub fn new() -> Self {
+        Crc64We {
+            value: u64::MAX,
+            length_mod_8: 0,
+        }
+    }
+
+    /// Adds data to the CRC
+    pub fn digest(&mut self, data: &[u8]) {
+        for &byte in data {
+            let index = ((self.value >> 56) as u8 ^ byte) as usize;
+            self.value = (self.value << 8) ^ Self::TABLE[index];
+        }
+        self.length_mod_8 = ((self.length_mod_8 as usize + data.len()) % 8) as u8;
+    }
+
+    /// Pads the data to a multiple of 8 bytes and returns the CRC
+    pub fn finish(mut self) -> u64 {
+        if self.length_mod_8 != 0 {
+            let padding = [0u8; 8];
+            self.digest(&padding[self.length_mod_8 as usize..]);
+        }
+        self.value ^ u64::MAX
+    }
+}
+
+impl Default for Crc64We {
+    fn default() -> Self {
+        Crc64We::new()
+    }
+}
+
+const fn make_table(polynomial: u64) -> [u64; 256] {
+    let mut table = [0u64; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut value = (i as u64) << 56;
+        let mut bit = 0;
+        while bit < 8 {
+            value = if value & (1 << 63) != 0 {
+                (value << 1) ^ polynomial
+            } else {
+                value << 1
+            };
+            bit += 1;
+        }
+        table[i] = value;
+        i += 1;
+    }
+    table
+}
@@ -31,6 +31,7 @@ pub mod reg {
                     }
                     impl ::canadensis_encoding::DataType for Note {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 12;
                     }
                     impl ::canadensis_encoding::Message for Note {}
                     impl Note {}
@@ -83,6 +84,7 @@ pub torque: crate::uavcan::si::unit::torque::scalar_1_0::Scalar,
 }
                         impl ::canadensis_encoding::DataType for Planar {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 16;
                         }
                         impl ::canadensis_encoding::Message for Planar {}
                         impl Planar {}
@@ -132,6 +134,7 @@ pub value: crate::reg::udral::physics::dynamics::rotation::planar_0_1::Planar,
 }
                         impl ::canadensis_encoding::DataType for PlanarTs {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 23;
                         }
                         impl ::canadensis_encoding::Message for PlanarTs {}
                         impl PlanarTs {}
@@ -183,6 +186,7 @@ pub force: crate::uavcan::si::unit::force::scalar_1_0::Scalar,
 }
                         impl ::canadensis_encoding::DataType for Linear {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 16;
                         }
                         impl ::canadensis_encoding::Message for Linear {}
                         impl Linear {}
@@ -232,6 +236,7 @@ pub value: crate::reg::udral::physics::dynamics::translation::linear_0_1::Linear
 }
                         impl ::canadensis_encoding::DataType for LinearTs {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 23;
                         }
                         impl ::canadensis_encoding::Message for LinearTs {}
                         impl LinearTs {}
@@ -284,6 +289,7 @@ pub value: crate::reg::udral::physics::dynamics::translation::linear_0_1::Linear
                     }
                     impl ::canadensis_encoding::DataType for Power {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 8;
                     }
                     impl ::canadensis_encoding::Message for Power {}
                     impl Power {}
@@ -331,6 +337,7 @@ pub value: crate::reg::udral::physics::dynamics::translation::linear_0_1::Linear
                     }
                     impl ::canadensis_encoding::DataType for PowerTs {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 15;
                     }
                     impl ::canadensis_encoding::Message for PowerTs {}
                     impl PowerTs {}
@@ -382,6 +389,7 @@ pub value: crate::reg::udral::physics::dynamics::translation::linear_0_1::Linear
                     }
                     impl ::canadensis_encoding::DataType for Source {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 16;
                     }
                     impl ::canadensis_encoding::Message for Source {}
                     impl Source {}
@@ -430,6 +438,7 @@ pub value: crate::reg::udral::physics::dynamics::translation::linear_0_1::Linear
                     }
                     impl ::canadensis_encoding::DataType for SourceTs {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 23;
                     }
                     impl ::canadensis_encoding::Message for SourceTs {}
                     impl SourceTs {}
@@ -475,6 +484,7 @@ pub value: crate::reg::udral::physics::dynamics::translation::linear_0_1::Linear
                         }
                         impl ::canadensis_encoding::DataType for Point {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 24;
                         }
                         impl ::canadensis_encoding::Message for Point {}
                         impl Point {}
@@ -526,6 +536,7 @@ pub value: crate::reg::udral::physics::dynamics::translation::linear_0_1::Linear
                         }
                         impl ::canadensis_encoding::DataType for PointState {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 36;
                         }
                         impl ::canadensis_encoding::Message for PointState {}
                         impl PointState {}
@@ -577,6 +588,7 @@ pub velocity: crate::reg::udral::physics::kinematics::translation::velocity3_var
 }
                         impl ::canadensis_encoding::DataType for PointStateVar {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 60;
                         }
                         impl ::canadensis_encoding::Message for PointStateVar {}
                         impl PointStateVar {}
@@ -626,6 +638,7 @@ pub value: crate::reg::udral::physics::kinematics::cartesian::point_state_var_0_
 }
                         impl ::canadensis_encoding::DataType for PointStateVarTs {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 67;
                         }
                         impl ::canadensis_encoding::Message for PointStateVarTs {}
                         impl PointStateVarTs {}
@@ -676,6 +689,7 @@ pub value: crate::reg::udral::physics::kinematics::cartesian::point_state_var_0_
                         }
                         impl ::canadensis_encoding::DataType for PointVar {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 36;
                         }
                         impl ::canadensis_encoding::Message for PointVar {}
                         impl PointVar {}
@@ -729,6 +743,7 @@ pub value: crate::reg::udral::physics::kinematics::cartesian::point_state_var_0_
                         }
                         impl ::canadensis_encoding::DataType for Pose {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 40;
                         }
                         impl ::canadensis_encoding::Message for Pose {}
                         impl Pose {}
@@ -781,6 +796,7 @@ pub value: crate::reg::udral::physics::kinematics::cartesian::point_state_var_0_
                         }
                         impl ::canadensis_encoding::DataType for PoseVar {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 82;
                         }
                         impl ::canadensis_encoding::Message for PoseVar {}
                         impl PoseVar {}
@@ -830,6 +846,7 @@ pub value: crate::reg::udral::physics::kinematics::cartesian::pose_var_0_1::Pose
 }
                         impl ::canadensis_encoding::DataType for PoseVarTs {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 89;
                         }
                         impl ::canadensis_encoding::Message for PoseVarTs {}
                         impl PoseVarTs {}
@@ -881,6 +898,7 @@ pub value: crate::reg::udral::physics::kinematics::cartesian::pose_var_0_1::Pose
                         }
                         impl ::canadensis_encoding::DataType for State {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 64;
                         }
                         impl ::canadensis_encoding::Message for State {}
                         impl State {}
@@ -932,6 +950,7 @@ pub twist: crate::reg::udral::physics::kinematics::cartesian::twist_var_0_1::Twi
 }
                         impl ::canadensis_encoding::DataType for StateVar {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 148;
                         }
                         impl ::canadensis_encoding::Message for StateVar {}
                         impl StateVar {}
@@ -981,6 +1000,7 @@ pub value: crate::reg::udral::physics::kinematics::cartesian::state_var_0_1::Sta
 }
                         impl ::canadensis_encoding::DataType for StateVarTs {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 155;
                         }
                         impl ::canadensis_encoding::Message for StateVarTs {}
                         impl StateVarTs {}
@@ -1031,6 +1051,7 @@ pub value: crate::reg::udral::physics::kinematics::cartesian::state_var_0_1::Sta
                         }
                         impl ::canadensis_encoding::DataType for Twist {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 24;
                         }
                         impl ::canadensis_encoding::Message for Twist {}
                         impl Twist {}
@@ -1083,6 +1104,7 @@ pub value: crate::reg::udral::physics::kinematics::cartesian::state_var_0_1::Sta
                         }
                         impl ::canadensis_encoding::DataType for TwistVar {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 66;
                         }
                         impl ::canadensis_encoding::Message for TwistVar {}
                         impl TwistVar {}
@@ -1132,6 +1154,7 @@ pub value: crate::reg::udral::physics::kinematics::cartesian::twist_var_0_1::Twi
 }
                         impl ::canadensis_encoding::DataType for TwistVarTs {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 73;
                         }
                         impl ::canadensis_encoding::Message for TwistVarTs {}
                         impl TwistVarTs {}
@@ -1189,6 +1212,7 @@ pub value: crate::reg::udral::physics::kinematics::cartesian::twist_var_0_1::Twi
                         }
                         impl ::canadensis_encoding::DataType for Point {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 24;
                         }
                         impl ::canadensis_encoding::Message for Point {}
                         impl Point {}
@@ -1242,6 +1266,7 @@ pub value: crate::reg::udral::physics::kinematics::cartesian::twist_var_0_1::Twi
                         }
                         impl ::canadensis_encoding::DataType for PointState {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 36;
                         }
                         impl ::canadensis_encoding::Message for PointState {}
                         impl PointState {}
@@ -1293,6 +1318,7 @@ pub velocity: crate::reg::udral::physics::kinematics::translation::velocity3_var
 }
                         impl ::canadensis_encoding::DataType for PointStateVar {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 60;
                         }
                         impl ::canadensis_encoding::Message for PointStateVar {}
                         impl PointStateVar {}
@@ -1342,6 +1368,7 @@ pub value: crate::reg::udral::physics::kinematics::geodetic::point_state_var_0_1
 }
                         impl ::canadensis_encoding::DataType for PointStateVarTs {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 67;
                         }
                         impl ::canadensis_encoding::Message for PointStateVarTs {}
                         impl PointStateVarTs {}
@@ -1392,6 +1419,7 @@ pub value: crate::reg::udral::physics::kinematics::geodetic::point_state_var_0_1
                         }
                         impl ::canadensis_encoding::DataType for PointVar {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 36;
                         }
                         impl ::canadensis_encoding::Message for PointVar {}
                         impl PointVar {}
@@ -1445,6 +1473,7 @@ pub value: crate::reg::udral::physics::kinematics::geodetic::point_state_var_0_1
                         }
                         impl ::canadensis_encoding::DataType for Pose {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 40;
                         }
                         impl ::canadensis_encoding::Message for Pose {}
                         impl Pose {}
@@ -1497,6 +1526,7 @@ pub value: crate::reg::udral::physics::kinematics::geodetic::point_state_var_0_1
                         }
                         impl ::canadensis_encoding::DataType for PoseVar {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 82;
                         }
                         impl ::canadensis_encoding::Message for PoseVar {}
                         impl PoseVar {}
@@ -1550,6 +1580,7 @@ pub value: crate::reg::udral::physics::kinematics::geodetic::point_state_var_0_1
                         }
                         impl ::canadensis_encoding::DataType for State {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 64;
                         }
                         impl ::canadensis_encoding::Message for State {}
                         impl State {}
@@ -1601,6 +1632,7 @@ pub twist: crate::reg::udral::physics::kinematics::cartesian::twist_var_0_1::Twi
 }
                         impl ::canadensis_encoding::DataType for StateVar {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 148;
                         }
                         impl ::canadensis_encoding::Message for StateVar {}
                         impl StateVar {}
@@ -1650,6 +1682,7 @@ pub value: crate::reg::udral::physics::kinematics::geodetic::state_var_0_1::Stat
 }
                         impl ::canadensis_encoding::DataType for StateVarTs {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 155;
                         }
                         impl ::canadensis_encoding::Message for StateVarTs {}
                         impl StateVarTs {}
@@ -1709,6 +1742,7 @@ pub value: crate::reg::udral::physics::kinematics::geodetic::state_var_0_1::Stat
                         }
                         impl ::canadensis_encoding::DataType for Planar {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 12;
                         }
                         impl ::canadensis_encoding::Message for Planar {}
                         impl Planar {}
@@ -1762,6 +1796,7 @@ pub value: crate::reg::udral::physics::kinematics::rotation::planar_0_1::Planar,
 }
                         impl ::canadensis_encoding::DataType for PlanarTs {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 19;
                         }
                         impl ::canadensis_encoding::Message for PlanarTs {}
                         impl PlanarTs {}
@@ -1819,6 +1854,7 @@ pub value: crate::reg::udral::physics::kinematics::rotation::planar_0_1::Planar,
                         }
                         impl ::canadensis_encoding::DataType for Linear {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 12;
                         }
                         impl ::canadensis_encoding::Message for Linear {}
                         impl Linear {}
@@ -1869,6 +1905,7 @@ pub value: crate::reg::udral::physics::kinematics::translation::linear_0_1::Line
 }
                         impl ::canadensis_encoding::DataType for LinearTs {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 19;
                         }
                         impl ::canadensis_encoding::Message for LinearTs {}
                         impl LinearTs {}
@@ -1926,6 +1963,7 @@ pub acceleration_error_variance: ::half::f16,
 }
                         impl ::canadensis_encoding::DataType for LinearVarTs {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 25;
                         }
                         impl ::canadensis_encoding::Message for LinearVarTs {}
                         impl LinearVarTs {}
@@ -1977,6 +2015,7 @@ pub acceleration_error_variance: ::half::f16,
                         }
                         impl ::canadensis_encoding::DataType for Velocity1VarTs {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 13;
                         }
                         impl ::canadensis_encoding::Message for Velocity1VarTs {}
                         impl Velocity1VarTs {}
@@ -2024,6 +2063,7 @@ pub acceleration_error_variance: ::half::f16,
                         }
                         impl ::canadensis_encoding::DataType for Velocity3Var {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 31;
                         }
                         impl ::canadensis_encoding::Message for Velocity3Var {}
                         impl Velocity3Var {}
@@ -2084,6 +2124,7 @@ pub acceleration_error_variance: ::half::f16,
                         }
                         impl ::canadensis_encoding::DataType for Velocity3Var {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 24;
                         }
                         impl ::canadensis_encoding::Message for Velocity3Var {}
                         impl Velocity3Var {}
@@ -2144,6 +2185,7 @@ pub acceleration_error_variance: ::half::f16,
                     }
                     impl ::canadensis_encoding::DataType for HighColor {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 2;
                     }
                     impl ::canadensis_encoding::Message for HighColor {}
                     impl HighColor {
@@ -2207,6 +2249,7 @@ pub acceleration_error_variance: ::half::f16,
                     }
                     impl ::canadensis_encoding::DataType for PressureTempVarTs {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 21;
                     }
                     impl ::canadensis_encoding::Message for PressureTempVarTs {}
                     impl PressureTempVarTs {}
@@ -2258,6 +2301,7 @@ pub acceleration_error_variance: ::half::f16,
                     }
                     impl ::canadensis_encoding::DataType for TAI64 {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 8;
                     }
                     impl ::canadensis_encoding::Message for TAI64 {}
                     impl TAI64 {}
@@ -2305,6 +2349,7 @@ pub acceleration_error_variance: ::half::f16,
                     }
                     impl ::canadensis_encoding::DataType for TAI64Var {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 12;
                     }
                     impl ::canadensis_encoding::Message for TAI64Var {}
                     impl TAI64Var {}
@@ -2352,6 +2397,7 @@ pub acceleration_error_variance: ::half::f16,
                     }
                     impl ::canadensis_encoding::DataType for TAI64VarTs {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 19;
                     }
                     impl ::canadensis_encoding::Message for TAI64VarTs {}
                     impl TAI64VarTs {}
@@ -2392,6 +2438,7 @@ pub acceleration_error_variance: ::half::f16,
                         pub struct _0 {}
                         impl ::canadensis_encoding::DataType for _0 {
                             const EXTENT_BYTES: Option<u32> = Some(0);
+                            const MAX_SIZE_BYTES: u32 = 0;
                         }
                         impl ::canadensis_encoding::Message for _0 {}
                         impl _0 {
@@ -2483,6 +2530,7 @@ pub acceleration_error_variance: ::half::f16,
                         }
                         impl ::canadensis_encoding::DataType for FaultFlags {
                             const EXTENT_BYTES: Option<u32> = None;
+                            const MAX_SIZE_BYTES: u32 = 2;
                         }
                         impl ::canadensis_encoding::Message for FaultFlags {}
                         impl FaultFlags {}
@@ -2551,6 +2599,7 @@ pub acceleration_error_variance: ::half::f16,
                         }
                         impl ::canadensis_encoding::DataType for Feedback {
                             const EXTENT_BYTES: Option<u32> = Some(63);
+                            const MAX_SIZE_BYTES: u32 = 3;
                         }
                         impl ::canadensis_encoding::Message for Feedback {}
                         impl Feedback {}
@@ -2590,6 +2639,7 @@ pub acceleration_error_variance: ::half::f16,
                             pub struct _0 {}
                             impl ::canadensis_encoding::DataType for _0 {
                                 const EXTENT_BYTES: Option<u32> = Some(0);
+                                const MAX_SIZE_BYTES: u32 = 0;
                             }
                             impl ::canadensis_encoding::Message for _0 {}
                             impl _0 {
@@ -2639,6 +2689,7 @@ pub acceleration_error_variance: ::half::f16,
                             }
                             impl ::canadensis_encoding::DataType for Scalar {
                                 const EXTENT_BYTES: Option<u32> = Some(512);
+                                const MAX_SIZE_BYTES: u32 = 2;
                             }
                             impl ::canadensis_encoding::Message for Scalar {}
                             impl Scalar {}
@@ -2687,6 +2738,7 @@ pub acceleration_error_variance: ::half::f16,
                             }
                             impl ::canadensis_encoding::DataType for Vector2 {
                                 const EXTENT_BYTES: Option<u32> = Some(512);
+                                const MAX_SIZE_BYTES: u32 = 4;
                             }
                             impl ::canadensis_encoding::Message for Vector2 {}
                             impl Vector2 {}
@@ -2735,6 +2787,7 @@ pub acceleration_error_variance: ::half::f16,
                             }
                             impl ::canadensis_encoding::DataType for Vector31 {
                                 const EXTENT_BYTES: Option<u32> = Some(512);
+                                const MAX_SIZE_BYTES: u32 = 62;
                             }
                             impl ::canadensis_encoding::Message for Vector31 {}
                             impl Vector31 {}
@@ -2783,6 +2836,7 @@ pub acceleration_error_variance: ::half::f16,
                             }
                             impl ::canadensis_encoding::DataType for Vector3 {
                                 const EXTENT_BYTES: Option<u32> = Some(512);
+                                const MAX_SIZE_BYTES: u32 = 6;
                             }
                             impl ::canadensis_encoding::Message for Vector3 {}
                             impl Vector3 {}
@@ -2831,6 +2885,7 @@ pub acceleration_error_variance: ::half::f16,
                             }
                             impl ::canadensis_encoding::DataType for Vector4 {
                                 const EXTENT_BYTES: Option<u32> = Some(512);
+                                const MAX_SIZE_BYTES: u32 = 8;
                             }
                             impl ::canadensis_encoding::Message for Vector4 {}
                             impl Vector4 {}
@@ -2879,6 +2934,7 @@ pub acceleration_error_variance: ::half::f16,
                             }
                             impl ::canadensis_encoding::DataType for Vector6 {
                                 const EXTENT_BYTES: Option<u32> = Some(512);
+                                const MAX_SIZE_BYTES: u32 = 12;
                             }
                             impl ::canadensis_encoding::Message for Vector6 {}
                             impl Vector6 {}
@@ -2927,6 +2983,7 @@ pub acceleration_error_variance: ::half::f16,
                             }
                             impl ::canadensis_encoding::DataType for Vector8 {
                                 const EXTENT_BYTES: Option<u32> = Some(512);
+                                const MAX_SIZE_BYTES: u32 = 16;
                             }
                             impl ::canadensis_encoding::Message for Vector8 {}
                             impl Vector8 {}
@@ -2989,6 +3046,7 @@ pub fault_flags: crate::reg::udral::service::actuator::common::fault_flags_0_1::
 }
                         impl ::canadensis_encoding::DataType for Status {
                             const EXTENT_BYTES: Option<u32> = Some(63);
+                            const MAX_SIZE_BYTES: u32 = 14;
                         }
                         impl ::canadensis_encoding::Message for Status {}
                         impl Status {}
@@ -3033,6 +3091,7 @@ pub fault_flags: crate::reg::udral::service::actuator::common::fault_flags_0_1::
                         pub struct _0 {}
                         impl ::canadensis_encoding::DataType for _0 {
                             const EXTENT_BYTES: Option<u32> = Some(0);
+                            const MAX_SIZE_BYTES: u32 = 0;
                         }
                         impl ::canadensis_encoding::Message for _0 {}
                         impl _0 {}
@@ -3073,6 +3132,7 @@ pub fault_flags: crate::reg::udral::service::actuator::common::fault_flags_0_1::
                         pub struct _0 {}
                         impl ::canadensis_encoding::DataType for _0 {
                             const EXTENT_BYTES: Option<u32> = Some(0);
+                            const MAX_SIZE_BYTES: u32 = 0;
                         }
                         impl ::canadensis_encoding::Message for _0 {}
                         impl _0 {}
@@ -3114,6 +3174,7 @@ pub fault_flags: crate::reg::udral::service::actuator::common::fault_flags_0_1::
                     pub struct _0 {}
                     impl ::canadensis_encoding::DataType for _0 {
                         const EXTENT_BYTES: Option<u32> = Some(0);
+                        const MAX_SIZE_BYTES: u32 = 0;
                     }
                     impl ::canadensis_encoding::Message for _0 {}
                     impl _0 {}
@@ -3155,6 +3216,7 @@ pub fault_flags: crate::reg::udral::service::actuator::common::fault_flags_0_1::
                     }
                     impl ::canadensis_encoding::DataType for Error {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 1;
                     }
                     impl ::canadensis_encoding::Message for Error {}
                     impl Error {
@@ -3298,6 +3360,7 @@ pub fault_flags: crate::reg::udral::service::actuator::common::fault_flags_0_1::
                     }
                     impl ::canadensis_encoding::DataType for Parameters {
                         const EXTENT_BYTES: Option<u32> = Some(300);
+                        const MAX_SIZE_BYTES: u32 = 128;
                     }
                     impl ::canadensis_encoding::Message for Parameters {}
                     impl Parameters {}
@@ -3434,6 +3497,7 @@ pub fault_flags: crate::reg::udral::service::actuator::common::fault_flags_0_1::
                     }
                     impl ::canadensis_encoding::DataType for Status {
                         const EXTENT_BYTES: Option<u32> = Some(600);
+                        const MAX_SIZE_BYTES: u32 = 526;
                     }
                     impl ::canadensis_encoding::Message for Status {}
                     impl Status {
@@ -3508,6 +3572,7 @@ pub fault_flags: crate::reg::udral::service::actuator::common::fault_flags_0_1::
                     }
                     impl ::canadensis_encoding::DataType for Technology {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 1;
                     }
                     impl ::canadensis_encoding::Message for Technology {}
                     impl Technology {
@@ -3580,6 +3645,7 @@ pub fault_flags: crate::reg::udral::service::actuator::common::fault_flags_0_1::
                     }
                     impl ::canadensis_encoding::DataType for Heartbeat {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 2;
                     }
                     impl ::canadensis_encoding::Message for Heartbeat {}
                     impl Heartbeat {
@@ -3621,6 +3687,7 @@ pub fault_flags: crate::reg::udral::service::actuator::common::fault_flags_0_1::
                     }
                     impl ::canadensis_encoding::DataType for Readiness {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 1;
                     }
                     impl ::canadensis_encoding::Message for Readiness {}
                     impl Readiness {
@@ -3678,6 +3745,7 @@ pub fault_flags: crate::reg::udral::service::actuator::common::fault_flags_0_1::
                     }
                     impl ::canadensis_encoding::DataType for Status {
                         const EXTENT_BYTES: Option<u32> = Some(63);
+                        const MAX_SIZE_BYTES: u32 = 12;
                     }
                     impl ::canadensis_encoding::Message for Status {}
                     impl Status {
@@ -3744,6 +3812,7 @@ pub mod uavcan {
             }
             impl ::canadensis_encoding::DataType for Record {
                 const EXTENT_BYTES: Option<u32> = Some(300);
+                const MAX_SIZE_BYTES: u32 = 121;
             }
             impl ::canadensis_encoding::Message for Record {}
             impl Record {}
@@ -3811,6 +3880,7 @@ pub mod uavcan {
             }
             impl ::canadensis_encoding::DataType for Record {
                 const EXTENT_BYTES: Option<u32> = Some(300);
+                const MAX_SIZE_BYTES: u32 = 264;
             }
             impl ::canadensis_encoding::Message for Record {}
             impl Record {}
@@ -3864,6 +3934,7 @@ pub mod uavcan {
             }
             impl ::canadensis_encoding::DataType for Severity {
                 const EXTENT_BYTES: Option<u32> = None;
+                const MAX_SIZE_BYTES: u32 = 1;
             }
             impl ::canadensis_encoding::Message for Severity {}
             impl Severity {
@@ -3914,6 +3985,7 @@ pub mod uavcan {
             }
             impl ::canadensis_encoding::DataType for Error {
                 const EXTENT_BYTES: Option<u32> = None;
+                const MAX_SIZE_BYTES: u32 = 2;
             }
             impl ::canadensis_encoding::Message for Error {}
             impl Error {
@@ -3968,6 +4040,7 @@ pub mod uavcan {
             }
             impl ::canadensis_encoding::DataType for GetInfoRequest {
                 const EXTENT_BYTES: Option<u32> = Some(300);
+                const MAX_SIZE_BYTES: u32 = 113;
             }
             impl ::canadensis_encoding::Request for GetInfoRequest {}
             impl GetInfoRequest {}
@@ -4035,6 +4108,7 @@ pub mod uavcan {
             }
             impl ::canadensis_encoding::DataType for GetInfoResponse {
                 const EXTENT_BYTES: Option<u32> = Some(48);
+                const MAX_SIZE_BYTES: u32 = 13;
             }
             impl ::canadensis_encoding::Response for GetInfoResponse {}
             impl GetInfoResponse {}
@@ -4088,6 +4162,7 @@ pub mod uavcan {
             }
             impl ::canadensis_encoding::DataType for GetInfoRequest {
                 const EXTENT_BYTES: Option<u32> = Some(300);
+                const MAX_SIZE_BYTES: u32 = 256;
             }
             impl ::canadensis_encoding::Request for GetInfoRequest {}
             impl GetInfoRequest {}
@@ -4155,6 +4230,7 @@ pub mod uavcan {
             }
             impl ::canadensis_encoding::DataType for GetInfoResponse {
                 const EXTENT_BYTES: Option<u32> = Some(48);
+                const MAX_SIZE_BYTES: u32 = 13;
             }
             impl ::canadensis_encoding::Response for GetInfoResponse {}
             impl GetInfoResponse {}
@@ -4214,6 +4290,7 @@ pub mod uavcan {
             }
             impl ::canadensis_encoding::DataType for ListRequest {
                 const EXTENT_BYTES: Option<u32> = Some(300);
+                const MAX_SIZE_BYTES: u32 = 121;
             }
             impl ::canadensis_encoding::Request for ListRequest {}
             impl ListRequest {}
@@ -4257,6 +4334,7 @@ pub mod uavcan {
             }
             impl ::canadensis_encoding::DataType for ListResponse {
                 const EXTENT_BYTES: Option<u32> = Some(300);
+                const MAX_SIZE_BYTES: u32 = 117;
             }
             impl ::canadensis_encoding::Response for ListResponse {}
             impl ListResponse {}
@@ -4307,6 +4385,7 @@ pub mod uavcan {
             }
             impl ::canadensis_encoding::DataType for ListRequest {
                 const EXTENT_BYTES: Option<u32> = Some(300);
+                const MAX_SIZE_BYTES: u32 = 264;
             }
             impl ::canadensis_encoding::Request for ListRequest {}
             impl ListRequest {}
@@ -4350,6 +4429,7 @@ pub mod uavcan {
             }
             impl ::canadensis_encoding::DataType for ListResponse {
                 const EXTENT_BYTES: Option<u32> = Some(300);
+                const MAX_SIZE_BYTES: u32 = 260;
             }
             impl ::canadensis_encoding::Response for ListResponse {}
             impl ListResponse {}
@@ -4410,6 +4490,7 @@ pub mod uavcan {
             }
             impl ::canadensis_encoding::DataType for ModifyRequest {
                 const EXTENT_BYTES: Option<u32> = Some(600);
+                const MAX_SIZE_BYTES: u32 = 230;
             }
             impl ::canadensis_encoding::Request for ModifyRequest {}
             impl ModifyRequest {}
@@ -4458,6 +4539,7 @@ pub mod uavcan {
             }
             impl ::canadensis_encoding::DataType for ModifyResponse {
                 const EXTENT_BYTES: Option<u32> = Some(48);
+                const MAX_SIZE_BYTES: u32 = 2;
             }
             impl ::canadensis_encoding::Response for ModifyResponse {}
             impl ModifyResponse {}
@@ -4517,6 +4599,7 @@ pub mod uavcan {
             }
             impl ::canadensis_encoding::DataType for ModifyRequest {
                 const EXTENT_BYTES: Option<u32> = Some(600);
+                const MAX_SIZE_BYTES: u32 = 516;
             }
             impl ::canadensis_encoding::Request for ModifyRequest {}
             impl ModifyRequest {}
@@ -4565,6 +4648,7 @@ pub mod uavcan {
             }
             impl ::canadensis_encoding::DataType for ModifyResponse {
                 const EXTENT_BYTES: Option<u32> = Some(48);
+                const MAX_SIZE_BYTES: u32 = 2;
             }
             impl ::canadensis_encoding::Response for ModifyResponse {}
             impl ModifyResponse {}
@@ -4605,6 +4689,7 @@ pub mod uavcan {
             }
             impl ::canadensis_encoding::DataType for Path {
                 const EXTENT_BYTES: Option<u32> = None;
+                const MAX_SIZE_BYTES: u32 = 113;
             }
             impl ::canadensis_encoding::Message for Path {}
             impl Path {
@@ -4657,6 +4742,7 @@ pub mod uavcan {
             }
             impl ::canadensis_encoding::DataType for Path {
                 const EXTENT_BYTES: Option<u32> = None;
+                const MAX_SIZE_BYTES: u32 = 256;
             }
             impl ::canadensis_encoding::Message for Path {}
             impl Path {
@@ -4717,6 +4803,7 @@ pub mod uavcan {
             }
             impl ::canadensis_encoding::DataType for ReadRequest {
                 const EXTENT_BYTES: Option<u32> = Some(300);
+                const MAX_SIZE_BYTES: u32 = 118;
             }
             impl ::canadensis_encoding::Request for ReadRequest {}
             impl ReadRequest {}
@@ -4760,6 +4847,7 @@ pub mod uavcan {
             }
             impl ::canadensis_encoding::DataType for ReadResponse {
                 const EXTENT_BYTES: Option<u32> = Some(300);
+                const MAX_SIZE_BYTES: u32 = 260;
             }
             impl ::canadensis_encoding::Response for ReadResponse {}
             impl ReadResponse {}
@@ -4819,6 +4907,7 @@ pub mod uavcan {
             }
             impl ::canadensis_encoding::DataType for ReadRequest {
                 const EXTENT_BYTES: Option<u32> = Some(300);
+                const MAX_SIZE_BYTES: u32 = 261;
             }
             impl ::canadensis_encoding::Request for ReadRequest {}
             impl ReadRequest {}
@@ -4862,6 +4951,7 @@ pub mod uavcan {
             }
             impl ::canadensis_encoding::DataType for ReadResponse {
                 const EXTENT_BYTES: Option<u32> = Some(300);
+                const MAX_SIZE_BYTES: u32 = 260;
             }
             impl ::canadensis_encoding::Response for ReadResponse {}
             impl ReadResponse {}
@@ -4914,6 +5004,7 @@ pub mod uavcan {
             }
             impl ::canadensis_encoding::DataType for WriteRequest {
                 const EXTENT_BYTES: Option<u32> = Some(600);
+                const MAX_SIZE_BYTES: u32 = 311;
             }
             impl ::canadensis_encoding::Request for WriteRequest {}
             impl WriteRequest {}
@@ -4968,6 +5059,7 @@ pub mod uavcan {
             }
             impl ::canadensis_encoding::DataType for WriteResponse {
                 const EXTENT_BYTES: Option<u32> = Some(48);
+                const MAX_SIZE_BYTES: u32 = 2;
             }
             impl ::canadensis_encoding::Response for WriteResponse {}
             impl WriteResponse {}
@@ -5021,6 +5113,7 @@ pub mod uavcan {
             }
             impl ::canadensis_encoding::DataType for WriteRequest {
                 const EXTENT_BYTES: Option<u32> = Some(600);
+                const MAX_SIZE_BYTES: u32 = 519;
             }
             impl ::canadensis_encoding::Request for WriteRequest {}
             impl WriteRequest {}
@@ -5063,6 +5156,7 @@ pub mod uavcan {
             }
             impl ::canadensis_encoding::DataType for WriteResponse {
                 const EXTENT_BYTES: Option<u32> = Some(48);
+                const MAX_SIZE_BYTES: u32 = 2;
             }
             impl ::canadensis_encoding::Response for WriteResponse {}
             impl WriteResponse {}
@@ -5114,6 +5208,7 @@ pub mod uavcan {
                 }
                 impl ::canadensis_encoding::DataType for HandleIncomingPacketRequest {
                     const EXTENT_BYTES: Option<u32> = Some(600);
+                    const MAX_SIZE_BYTES: u32 = 313;
                 }
                 impl ::canadensis_encoding::Request for HandleIncomingPacketRequest {}
                 impl HandleIncomingPacketRequest {}
@@ -5162,6 +5257,7 @@ pub mod uavcan {
                 pub struct HandleIncomingPacketResponse {}
                 impl ::canadensis_encoding::DataType for HandleIncomingPacketResponse {
                     const EXTENT_BYTES: Option<u32> = Some(63);
+                    const MAX_SIZE_BYTES: u32 = 0;
                 }
                 impl ::canadensis_encoding::Response for HandleIncomingPacketResponse {}
                 impl HandleIncomingPacketResponse {}
@@ -5212,6 +5308,7 @@ pub mod uavcan {
                 }
                 impl ::canadensis_encoding::DataType for HandleIncomingPacketRequest {
                     const EXTENT_BYTES: Option<u32> = Some(600);
+                    const MAX_SIZE_BYTES: u32 = 512;
                 }
                 impl ::canadensis_encoding::Request for HandleIncomingPacketRequest {}
                 impl HandleIncomingPacketRequest {}
@@ -5260,6 +5357,7 @@ pub mod uavcan {
                 pub struct HandleIncomingPacketResponse {}
                 impl ::canadensis_encoding::DataType for HandleIncomingPacketResponse {
                     const EXTENT_BYTES: Option<u32> = Some(63);
+                    const MAX_SIZE_BYTES: u32 = 0;
                 }
                 impl ::canadensis_encoding::Response for HandleIncomingPacketResponse {}
                 impl HandleIncomingPacketResponse {}
@@ -5331,6 +5429,7 @@ pub mod uavcan {
                 }
                 impl ::canadensis_encoding::DataType for OutgoingPacket {
                     const EXTENT_BYTES: Option<u32> = Some(600);
+                    const MAX_SIZE_BYTES: u32 = 313;
                 }
                 impl ::canadensis_encoding::Message for OutgoingPacket {}
                 impl OutgoingPacket {
@@ -5447,6 +5546,7 @@ pub mod uavcan {
                 }
                 impl ::canadensis_encoding::DataType for OutgoingPacket {
                     const EXTENT_BYTES: Option<u32> = Some(600);
+                    const MAX_SIZE_BYTES: u32 = 561;
                 }
                 impl ::canadensis_encoding::Message for OutgoingPacket {}
                 impl OutgoingPacket {
@@ -5537,6 +5637,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
 }
                 impl ::canadensis_encoding::DataType for ArbitrationID {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 5;
                 }
                 impl ::canadensis_encoding::Message for ArbitrationID {}
                 impl ArbitrationID {}
@@ -5586,6 +5687,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for BaseArbitrationID {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 4;
                 }
                 impl ::canadensis_encoding::Message for BaseArbitrationID {}
                 impl BaseArbitrationID {}
@@ -5630,6 +5732,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for DataClassic {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 14;
                 }
                 impl ::canadensis_encoding::Message for DataClassic {}
                 impl DataClassic {}
@@ -5689,6 +5792,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for DataFD {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 70;
                 }
                 impl ::canadensis_encoding::Message for DataFD {}
                 impl DataFD {}
@@ -5738,6 +5842,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Error {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 4;
                 }
                 impl ::canadensis_encoding::Message for Error {}
                 impl Error {}
@@ -5774,6 +5879,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for ExtendedArbitrationID {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 4;
                 }
                 impl ::canadensis_encoding::Message for ExtendedArbitrationID {}
                 impl ExtendedArbitrationID {}
@@ -5819,6 +5925,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Frame {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 78;
                 }
                 impl ::canadensis_encoding::Message for Frame {}
                 impl Frame {}
@@ -5861,6 +5968,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Frame {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 71;
                 }
                 impl ::canadensis_encoding::Message for Frame {}
                 impl Frame {}
@@ -5929,6 +6037,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Manifestation {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 71;
                 }
                 impl ::canadensis_encoding::Message for Manifestation {}
                 impl Manifestation {}
@@ -5995,6 +6104,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for RTR {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 5;
                 }
                 impl ::canadensis_encoding::Message for RTR {}
                 impl RTR {}
@@ -6036,6 +6146,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for EtherType {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 2;
                 }
                 impl ::canadensis_encoding::Message for EtherType {}
                 impl EtherType {
@@ -6096,6 +6207,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Frame {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 9232;
                 }
                 impl ::canadensis_encoding::Message for Frame {}
                 impl Frame {}
@@ -6184,6 +6296,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Fragment {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 265;
                 }
                 impl ::canadensis_encoding::Message for Fragment {}
                 impl Fragment {
@@ -6239,6 +6352,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Fragment {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 2050;
                 }
                 impl ::canadensis_encoding::Message for Fragment {}
                 impl Fragment {
@@ -6305,6 +6419,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Endpoint {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 32;
                 }
                 impl ::canadensis_encoding::Message for Endpoint {}
                 impl Endpoint {}
@@ -6392,6 +6507,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Frame {
                     const EXTENT_BYTES: Option<u32> = Some(10240);
+                    const MAX_SIZE_BYTES: u32 = 9262;
                 }
                 impl ::canadensis_encoding::Message for Frame {}
                 impl Frame {
@@ -6466,6 +6582,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
             }
             impl ::canadensis_encoding::DataType for ExecuteCommandRequest {
                 const EXTENT_BYTES: Option<u32> = Some(300);
+                const MAX_SIZE_BYTES: u32 = 115;
             }
             impl ::canadensis_encoding::Request for ExecuteCommandRequest {}
             impl ExecuteCommandRequest {
@@ -6525,6 +6642,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
             }
             impl ::canadensis_encoding::DataType for ExecuteCommandResponse {
                 const EXTENT_BYTES: Option<u32> = Some(48);
+                const MAX_SIZE_BYTES: u32 = 1;
             }
             impl ::canadensis_encoding::Response for ExecuteCommandResponse {}
             impl ExecuteCommandResponse {
@@ -6584,6 +6702,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
             }
             impl ::canadensis_encoding::DataType for ExecuteCommandRequest {
                 const EXTENT_BYTES: Option<u32> = Some(300);
+                const MAX_SIZE_BYTES: u32 = 258;
             }
             impl ::canadensis_encoding::Request for ExecuteCommandRequest {}
             impl ExecuteCommandRequest {
@@ -6643,6 +6762,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
             }
             impl ::canadensis_encoding::DataType for ExecuteCommandResponse {
                 const EXTENT_BYTES: Option<u32> = Some(48);
+                const MAX_SIZE_BYTES: u32 = 1;
             }
             impl ::canadensis_encoding::Response for ExecuteCommandResponse {}
             impl ExecuteCommandResponse {
@@ -6693,6 +6813,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
             pub struct GetInfoRequest {}
             impl ::canadensis_encoding::DataType for GetInfoRequest {
                 const EXTENT_BYTES: Option<u32> = None;
+                const MAX_SIZE_BYTES: u32 = 0;
             }
             impl ::canadensis_encoding::Request for GetInfoRequest {}
             impl GetInfoRequest {}
@@ -6766,6 +6887,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
             }
             impl ::canadensis_encoding::DataType for GetInfoResponse {
                 const EXTENT_BYTES: Option<u32> = Some(448);
+                const MAX_SIZE_BYTES: u32 = 313;
             }
             impl ::canadensis_encoding::Response for GetInfoResponse {}
             impl GetInfoResponse {}
@@ -6883,6 +7005,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
             pub struct GetTransportStatisticsRequest {}
             impl ::canadensis_encoding::DataType for GetTransportStatisticsRequest {
                 const EXTENT_BYTES: Option<u32> = None;
+                const MAX_SIZE_BYTES: u32 = 0;
             }
             impl ::canadensis_encoding::Request for GetTransportStatisticsRequest {}
             impl GetTransportStatisticsRequest {}
@@ -6930,6 +7053,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
             }
             impl ::canadensis_encoding::DataType for GetTransportStatisticsResponse {
                 const EXTENT_BYTES: Option<u32> = Some(192);
+                const MAX_SIZE_BYTES: u32 = 61;
             }
             impl ::canadensis_encoding::Response for GetTransportStatisticsResponse {}
             impl GetTransportStatisticsResponse {
@@ -6985,6 +7109,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
             }
             impl ::canadensis_encoding::DataType for Health {
                 const EXTENT_BYTES: Option<u32> = None;
+                const MAX_SIZE_BYTES: u32 = 1;
             }
             impl ::canadensis_encoding::Message for Health {}
             impl Health {
@@ -7045,6 +7170,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
             }
             impl ::canadensis_encoding::DataType for Heartbeat {
                 const EXTENT_BYTES: Option<u32> = Some(12);
+                const MAX_SIZE_BYTES: u32 = 7;
             }
             impl ::canadensis_encoding::Message for Heartbeat {}
             impl Heartbeat {
@@ -7093,6 +7219,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
             }
             impl ::canadensis_encoding::DataType for ID {
                 const EXTENT_BYTES: Option<u32> = None;
+                const MAX_SIZE_BYTES: u32 = 2;
             }
             impl ::canadensis_encoding::Message for ID {}
             impl ID {}
@@ -7143,6 +7270,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
             }
             impl ::canadensis_encoding::DataType for IOStatistics {
                 const EXTENT_BYTES: Option<u32> = None;
+                const MAX_SIZE_BYTES: u32 = 15;
             }
             impl ::canadensis_encoding::Message for IOStatistics {}
             impl IOStatistics {}
@@ -7184,6 +7312,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
             }
             impl ::canadensis_encoding::DataType for Mode {
                 const EXTENT_BYTES: Option<u32> = None;
+                const MAX_SIZE_BYTES: u32 = 1;
             }
             impl ::canadensis_encoding::Message for Mode {}
             impl Mode {
@@ -7226,6 +7355,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for ID {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 3;
                 }
                 impl ::canadensis_encoding::Message for ID {}
                 impl ID {}
@@ -7292,6 +7422,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for List {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 2194;
                 }
                 impl ::canadensis_encoding::Message for List {}
                 impl List {
@@ -7344,6 +7475,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for ServiceID {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 2;
                 }
                 impl ::canadensis_encoding::Message for ServiceID {}
                 impl ServiceID {
@@ -7383,6 +7515,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for ServiceIDList {
                     const EXTENT_BYTES: Option<u32> = Some(128);
+                    const MAX_SIZE_BYTES: u32 = 64;
                 }
                 impl ::canadensis_encoding::Message for ServiceIDList {}
                 impl ServiceIDList {
@@ -7426,6 +7559,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for SubjectID {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 2;
                 }
                 impl ::canadensis_encoding::Message for SubjectID {}
                 impl SubjectID {
@@ -7468,6 +7602,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for SubjectIDList {
                     const EXTENT_BYTES: Option<u32> = Some(4097);
+                    const MAX_SIZE_BYTES: u32 = 1025;
                 }
                 impl ::canadensis_encoding::Message for SubjectIDList {}
                 impl SubjectIDList {
@@ -7555,6 +7690,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
             }
             impl ::canadensis_encoding::DataType for Version {
                 const EXTENT_BYTES: Option<u32> = None;
+                const MAX_SIZE_BYTES: u32 = 2;
             }
             impl ::canadensis_encoding::Message for Version {}
             impl Version {}
@@ -7622,6 +7758,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for AppendEntriesRequest {
                     const EXTENT_BYTES: Option<u32> = Some(96);
+                    const MAX_SIZE_BYTES: u32 = 35;
                 }
                 impl ::canadensis_encoding::Request for AppendEntriesRequest {}
                 impl AppendEntriesRequest {
@@ -7690,6 +7827,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for AppendEntriesResponse {
                     const EXTENT_BYTES: Option<u32> = Some(48);
+                    const MAX_SIZE_BYTES: u32 = 5;
                 }
                 impl ::canadensis_encoding::Response for AppendEntriesResponse {}
                 impl AppendEntriesResponse {}
@@ -7738,6 +7876,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Discovery {
                     const EXTENT_BYTES: Option<u32> = Some(96);
+                    const MAX_SIZE_BYTES: u32 = 12;
                 }
                 impl ::canadensis_encoding::Message for Discovery {}
                 impl Discovery {
@@ -7810,6 +7949,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Entry {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 22;
                 }
                 impl ::canadensis_encoding::Message for Entry {}
                 impl Entry {}
@@ -7867,6 +8007,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for RequestVoteRequest {
                     const EXTENT_BYTES: Option<u32> = Some(48);
+                    const MAX_SIZE_BYTES: u32 = 10;
                 }
                 impl ::canadensis_encoding::Request for RequestVoteRequest {}
                 impl RequestVoteRequest {}
@@ -7919,6 +8060,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for RequestVoteResponse {
                     const EXTENT_BYTES: Option<u32> = Some(48);
+                    const MAX_SIZE_BYTES: u32 = 5;
                 }
                 impl ::canadensis_encoding::Response for RequestVoteResponse {}
                 impl RequestVoteResponse {}
@@ -7967,6 +8109,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
             }
             impl ::canadensis_encoding::DataType for NodeIDAllocationData {
                 const EXTENT_BYTES: Option<u32> = None;
+                const MAX_SIZE_BYTES: u32 = 9;
             }
             impl ::canadensis_encoding::Message for NodeIDAllocationData {}
             impl NodeIDAllocationData {}
@@ -8030,6 +8173,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
             }
             impl ::canadensis_encoding::DataType for NodeIDAllocationData {
                 const EXTENT_BYTES: Option<u32> = Some(48);
+                const MAX_SIZE_BYTES: u32 = 18;
             }
             impl ::canadensis_encoding::Message for NodeIDAllocationData {}
             impl NodeIDAllocationData {}
@@ -8080,6 +8224,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Bit {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 258;
                 }
                 impl ::canadensis_encoding::Message for Bit {}
                 impl Bit {}
@@ -8124,6 +8269,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Integer16 {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 257;
                 }
                 impl ::canadensis_encoding::Message for Integer16 {}
                 impl Integer16 {}
@@ -8177,6 +8323,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Integer32 {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 257;
                 }
                 impl ::canadensis_encoding::Message for Integer32 {}
                 impl Integer32 {}
@@ -8230,6 +8377,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Integer64 {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 257;
                 }
                 impl ::canadensis_encoding::Message for Integer64 {}
                 impl Integer64 {}
@@ -8283,6 +8431,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Integer8 {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 258;
                 }
                 impl ::canadensis_encoding::Message for Integer8 {}
                 impl Integer8 {}
@@ -8336,6 +8485,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Natural16 {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 257;
                 }
                 impl ::canadensis_encoding::Message for Natural16 {}
                 impl Natural16 {}
@@ -8389,6 +8539,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Natural32 {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 257;
                 }
                 impl ::canadensis_encoding::Message for Natural32 {}
                 impl Natural32 {}
@@ -8442,6 +8593,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Natural64 {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 257;
                 }
                 impl ::canadensis_encoding::Message for Natural64 {}
                 impl Natural64 {}
@@ -8495,6 +8647,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Natural8 {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 258;
                 }
                 impl ::canadensis_encoding::Message for Natural8 {}
                 impl Natural8 {}
@@ -8546,6 +8699,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Real16 {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 257;
                 }
                 impl ::canadensis_encoding::Message for Real16 {}
                 impl Real16 {}
@@ -8599,6 +8753,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Real32 {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 257;
                 }
                 impl ::canadensis_encoding::Message for Real32 {}
                 impl Real32 {}
@@ -8652,6 +8807,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Real64 {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 257;
                 }
                 impl ::canadensis_encoding::Message for Real64 {}
                 impl Real64 {}
@@ -8702,6 +8858,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
             pub struct Empty {}
             impl ::canadensis_encoding::DataType for Empty {
                 const EXTENT_BYTES: Option<u32> = None;
+                const MAX_SIZE_BYTES: u32 = 0;
             }
             impl ::canadensis_encoding::Message for Empty {}
             impl Empty {}
@@ -8742,6 +8899,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Bit {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 1;
                 }
                 impl ::canadensis_encoding::Message for Bit {}
                 impl Bit {}
@@ -8781,6 +8939,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Integer16 {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 2;
                 }
                 impl ::canadensis_encoding::Message for Integer16 {}
                 impl Integer16 {}
@@ -8823,6 +8982,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Integer32 {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 4;
                 }
                 impl ::canadensis_encoding::Message for Integer32 {}
                 impl Integer32 {}
@@ -8865,6 +9025,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Integer64 {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 8;
                 }
                 impl ::canadensis_encoding::Message for Integer64 {}
                 impl Integer64 {}
@@ -8907,6 +9068,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Integer8 {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 1;
                 }
                 impl ::canadensis_encoding::Message for Integer8 {}
                 impl Integer8 {}
@@ -8949,6 +9111,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Natural16 {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 2;
                 }
                 impl ::canadensis_encoding::Message for Natural16 {}
                 impl Natural16 {}
@@ -8991,6 +9154,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Natural32 {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 4;
                 }
                 impl ::canadensis_encoding::Message for Natural32 {}
                 impl Natural32 {}
@@ -9033,6 +9197,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Natural64 {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 8;
                 }
                 impl ::canadensis_encoding::Message for Natural64 {}
                 impl Natural64 {}
@@ -9075,6 +9240,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Natural8 {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 1;
                 }
                 impl ::canadensis_encoding::Message for Natural8 {}
                 impl Natural8 {}
@@ -9117,6 +9283,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Real16 {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 2;
                 }
                 impl ::canadensis_encoding::Message for Real16 {}
                 impl Real16 {}
@@ -9159,6 +9326,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Real32 {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 4;
                 }
                 impl ::canadensis_encoding::Message for Real32 {}
                 impl Real32 {}
@@ -9201,6 +9369,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                 }
                 impl ::canadensis_encoding::DataType for Real64 {
                     const EXTENT_BYTES: Option<u32> = None;
+                    const MAX_SIZE_BYTES: u32 = 8;
                 }
                 impl ::canadensis_encoding::Message for Real64 {}
                 impl Real64 {}
@@ -9242,6 +9411,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
             }
             impl ::canadensis_encoding::DataType for String {
                 const EXTENT_BYTES: Option<u32> = None;
+                const MAX_SIZE_BYTES: u32 = 258;
             }
             impl ::canadensis_encoding::Message for String {}
             impl String {}
@@ -9291,6 +9461,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
             }
             impl ::canadensis_encoding::DataType for Unstructured {
                 const EXTENT_BYTES: Option<u32> = None;
+                const MAX_SIZE_BYTES: u32 = 258;
             }
             impl ::canadensis_encoding::Message for Unstructured {}
             impl Unstructured {}
@@ -9350,6 +9521,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
             }
             impl ::canadensis_encoding::DataType for AccessRequest {
                 const EXTENT_BYTES: Option<u32> = None;
+                const MAX_SIZE_BYTES: u32 = 515;
             }
             impl ::canadensis_encoding::Request for AccessRequest {}
             impl AccessRequest {}
@@ -9405,6 +9577,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
             }
             impl ::canadensis_encoding::DataType for AccessResponse {
                 const EXTENT_BYTES: Option<u32> = None;
+                const MAX_SIZE_BYTES: u32 = 267;
             }
             impl ::canadensis_encoding::Response for AccessResponse {}
             impl AccessResponse {}
@@ -9457,6 +9630,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
             }
             impl ::canadensis_encoding::DataType for ListRequest {
                 const EXTENT_BYTES: Option<u32> = None;
+                const MAX_SIZE_BYTES: u32 = 2;
             }
             impl ::canadensis_encoding::Request for ListRequest {}
             impl ListRequest {}
@@ -9496,6 +9670,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
             }
             impl ::canadensis_encoding::DataType for ListResponse {
                 const EXTENT_BYTES: Option<u32> = None;
+                const MAX_SIZE_BYTES: u32 = 256;
             }
             impl ::canadensis_encoding::Response for ListResponse {}
             impl ListResponse {}
@@ -9533,6 +9708,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
             }
             impl ::canadensis_encoding::DataType for Name {
                 const EXTENT_BYTES: Option<u32> = None;
+                const MAX_SIZE_BYTES: u32 = 256;
             }
             impl ::canadensis_encoding::Message for Name {}
             impl Name {}
@@ -9607,6 +9783,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
             }
             impl ::canadensis_encoding::DataType for Value {
                 const EXTENT_BYTES: Option<u32> = None;
+                const MAX_SIZE_BYTES: u32 = 259;
             }
             impl ::canadensis_encoding::Message for Value {}
             impl Value {}
@@ -9746,6 +9923,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 11;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -9791,6 +9969,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Vector3 {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 19;
                     }
                     impl ::canadensis_encoding::Message for Vector3 {}
                     impl Vector3 {}
@@ -9842,6 +10021,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Quaternion {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 23;
                     }
                     impl ::canadensis_encoding::Message for Quaternion {}
                     impl Quaternion {}
@@ -9896,6 +10076,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 11;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -9943,6 +10124,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 11;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -9988,6 +10170,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Vector3 {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 19;
                     }
                     impl ::canadensis_encoding::Message for Vector3 {}
                     impl Vector3 {}
@@ -10039,6 +10222,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 11;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -10084,6 +10268,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Vector3 {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 19;
                     }
                     impl ::canadensis_encoding::Message for Vector3 {}
                     impl Vector3 {}
@@ -10135,6 +10320,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 11;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -10180,6 +10366,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for WideScalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 15;
                     }
                     impl ::canadensis_encoding::Message for WideScalar {}
                     impl WideScalar {}
@@ -10227,6 +10414,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 11;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -10274,6 +10462,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 11;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -10321,6 +10510,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 11;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -10368,6 +10558,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 11;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -10413,6 +10604,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Vector3 {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 19;
                     }
                     impl ::canadensis_encoding::Message for Vector3 {}
                     impl Vector3 {}
@@ -10464,6 +10656,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 11;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -10511,6 +10704,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 11;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -10556,6 +10750,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Vector3 {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 19;
                     }
                     impl ::canadensis_encoding::Message for Vector3 {}
                     impl Vector3 {}
@@ -10605,6 +10800,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for WideScalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 15;
                     }
                     impl ::canadensis_encoding::Message for WideScalar {}
                     impl WideScalar {}
@@ -10650,6 +10846,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for WideVector3 {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 31;
                     }
                     impl ::canadensis_encoding::Message for WideVector3 {}
                     impl WideVector3 {}
@@ -10701,6 +10898,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 11;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -10746,6 +10944,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Vector3 {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 19;
                     }
                     impl ::canadensis_encoding::Message for Vector3 {}
                     impl Vector3 {}
@@ -10797,6 +10996,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 11;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -10844,6 +11044,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 11;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -10891,6 +11092,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 11;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -10938,6 +11140,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 11;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -10985,6 +11188,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 11;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -11030,6 +11234,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Vector3 {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 19;
                     }
                     impl ::canadensis_encoding::Message for Vector3 {}
                     impl Vector3 {}
@@ -11081,6 +11286,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 11;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -11126,6 +11332,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Vector3 {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 19;
                     }
                     impl ::canadensis_encoding::Message for Vector3 {}
                     impl Vector3 {}
@@ -11177,6 +11384,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 11;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -11224,6 +11432,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 11;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -11271,6 +11480,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 11;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -11316,6 +11526,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 4;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -11361,6 +11572,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Vector3 {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 12;
                     }
                     impl ::canadensis_encoding::Message for Vector3 {}
                     impl Vector3 {}
@@ -11408,6 +11620,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Quaternion {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 16;
                     }
                     impl ::canadensis_encoding::Message for Quaternion {}
                     impl Quaternion {}
@@ -11450,6 +11663,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 4;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -11494,6 +11708,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 4;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -11539,6 +11754,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Vector3 {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 12;
                     }
                     impl ::canadensis_encoding::Message for Vector3 {}
                     impl Vector3 {}
@@ -11586,6 +11802,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 4;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -11628,6 +11845,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Vector3 {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 12;
                     }
                     impl ::canadensis_encoding::Message for Vector3 {}
                     impl Vector3 {}
@@ -11672,6 +11890,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 4;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -11714,6 +11933,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for WideScalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 8;
                     }
                     impl ::canadensis_encoding::Message for WideScalar {}
                     impl WideScalar {}
@@ -11758,6 +11978,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 4;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -11802,6 +12023,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 4;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -11846,6 +12068,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 4;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -11890,6 +12113,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 4;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -11932,6 +12156,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Vector3 {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 12;
                     }
                     impl ::canadensis_encoding::Message for Vector3 {}
                     impl Vector3 {}
@@ -11976,6 +12201,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 4;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -12020,6 +12246,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 4;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -12062,6 +12289,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Vector3 {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 12;
                     }
                     impl ::canadensis_encoding::Message for Vector3 {}
                     impl Vector3 {}
@@ -12104,6 +12332,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for WideScalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 8;
                     }
                     impl ::canadensis_encoding::Message for WideScalar {}
                     impl WideScalar {}
@@ -12146,6 +12375,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for WideVector3 {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 24;
                     }
                     impl ::canadensis_encoding::Message for WideVector3 {}
                     impl WideVector3 {}
@@ -12190,6 +12420,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 4;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -12232,6 +12463,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Vector3 {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 12;
                     }
                     impl ::canadensis_encoding::Message for Vector3 {}
                     impl Vector3 {}
@@ -12276,6 +12508,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 4;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -12320,6 +12553,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 4;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -12364,6 +12598,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 4;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -12408,6 +12643,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 4;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -12452,6 +12688,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 4;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -12494,6 +12731,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Vector3 {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 12;
                     }
                     impl ::canadensis_encoding::Message for Vector3 {}
                     impl Vector3 {}
@@ -12538,6 +12776,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 4;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -12580,6 +12819,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Vector3 {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 12;
                     }
                     impl ::canadensis_encoding::Message for Vector3 {}
                     impl Vector3 {}
@@ -12624,6 +12864,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 4;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -12668,6 +12909,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 4;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -12712,6 +12954,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
                     }
                     impl ::canadensis_encoding::DataType for Scalar {
                         const EXTENT_BYTES: Option<u32> = None;
+                        const MAX_SIZE_BYTES: u32 = 4;
                     }
                     impl ::canadensis_encoding::Message for Scalar {}
                     impl Scalar {}
@@ -12758,6 +13001,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
             pub struct GetSynchronizationMasterInfoRequest {}
             impl ::canadensis_encoding::DataType for GetSynchronizationMasterInfoRequest {
                 const EXTENT_BYTES: Option<u32> = Some(48);
+                const MAX_SIZE_BYTES: u32 = 0;
             }
             impl ::canadensis_encoding::Request for GetSynchronizationMasterInfoRequest {}
             impl GetSynchronizationMasterInfoRequest {}
@@ -12809,6 +13053,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
             }
             impl ::canadensis_encoding::DataType for GetSynchronizationMasterInfoResponse {
                 const EXTENT_BYTES: Option<u32> = Some(192);
+                const MAX_SIZE_BYTES: u32 = 7;
             }
             impl ::canadensis_encoding::Response for GetSynchronizationMasterInfoResponse {}
             impl GetSynchronizationMasterInfoResponse {}
@@ -12853,6 +13098,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
             }
             impl ::canadensis_encoding::DataType for Synchronization {
                 const EXTENT_BYTES: Option<u32> = None;
+                const MAX_SIZE_BYTES: u32 = 7;
             }
             impl ::canadensis_encoding::Message for Synchronization {}
             impl Synchronization {
@@ -12893,6 +13139,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
             }
             impl ::canadensis_encoding::DataType for SynchronizedTimestamp {
                 const EXTENT_BYTES: Option<u32> = None;
+                const MAX_SIZE_BYTES: u32 = 7;
             }
             impl ::canadensis_encoding::Message for SynchronizedTimestamp {}
             impl SynchronizedTimestamp {
@@ -12932,6 +13179,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
             }
             impl ::canadensis_encoding::DataType for TAIInfo {
                 const EXTENT_BYTES: Option<u32> = None;
+                const MAX_SIZE_BYTES: u32 = 2;
             }
             impl ::canadensis_encoding::Message for TAIInfo {}
             impl TAIInfo {
@@ -12972,6 +13220,7 @@ Extended(crate::uavcan::metatransport::can::extended_arbitration_id_0_1::Extende
             }
             impl ::canadensis_encoding::DataType for TimeSystem {
                 const EXTENT_BYTES: Option<u32> = None;
+                const MAX_SIZE_BYTES: u32 = 1;
             }
             impl ::canadensis_encoding::Message for TimeSystem {}
             impl TimeSystem {
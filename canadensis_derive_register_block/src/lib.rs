@@ -1,19 +1,54 @@
 extern crate proc_macro;
 use proc_macro::TokenStream;
 
-use proc_macro2::{Literal, TokenTree};
+use proc_macro2::{Ident, Literal, TokenTree};
 use quote::quote;
-use syn::{parse_macro_input, Data, DeriveInput, Fields};
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{parse_macro_input, Data, DeriveInput, Expr, Field, Fields, LitStr, Token, Type};
 
 /// Implements `canadensis::register::RegisterBlock` for a struct that contains zero or more
 /// register fields
 ///
 /// Each field in the struct must implement `canadensis::register::Register`.
-#[proc_macro_derive(RegisterBlock)]
+///
+/// # Attributes
+///
+/// If every field has a `#[register]` attribute, this macro also implements
+/// `canadensis::register::DefaultRegisterBlock` and `Default` for the struct.
+///
+/// A register field has an attribute like
+/// `#[register(name = "uavcan.node.id", mutable, persistent, default = 65535)]`:
+/// * `name`: The register name (required)
+/// * `mutable`: If present, other nodes can write the register
+/// * `persistent`: If present, the register value is preserved when the node restarts
+/// * `default`: The initial value (if not present, the default value of the register type is
+///   used)
+/// * `validator`: A function that accepts or rejects new values, for fields of type
+///   `ValidatedRegister`
+///
+/// The field type must have `new` and `with_value` functions like `SimpleRegister` or
+/// `ValidatedRegister`.
+///
+/// A field that contains a nested block has an attribute like `#[register(block)]` or
+/// `#[register(block, prefix = "motor")]`. The nested block must also be derived with
+/// `#[register]` attributes. With a prefix, each register name in the nested block starts with
+/// the prefix and a `.`.
+///
+/// A field can also contain an array of nested blocks. The name of each register in an array
+/// element starts with the prefix and the array index, like `motor.0.kp`.
+///
+/// The register names are checked at compile time. Each name must be distinct, not empty, and
+/// no more than 256 bytes long.
+#[proc_macro_derive(RegisterBlock, attributes(register))]
 pub fn derive_register_block(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
 
-    let result = implement_register_block(input);
+    let result = match attributed_fields(&input.data) {
+        Ok(Some(fields)) => implement_attributed_block(&input.ident, fields),
+        Ok(None) => implement_register_block(input),
+        Err(e) => e.to_compile_error(),
+    };
     result.into()
 }
 
@@ -51,6 +86,395 @@ fn implement_register_block(input: DeriveInput) -> proc_macro2::TokenStream {
     }
 }
 
+/// A field with a `#[register]` attribute
+struct AttributedField {
+    /// The field name
+    name: TokenTree,
+    ty: Type,
+    kind: FieldKind,
+}
+
+enum FieldKind {
+    /// A register
+    Register {
+        name: LitStr,
+        mutable: bool,
+        persistent: bool,
+        default: Option<Expr>,
+        validator: Option<Expr>,
+    },
+    /// A nested block
+    Block { prefix: LitStr },
+    /// An array of nested blocks
+    Array {
+        prefix: LitStr,
+        element: Type,
+        length: Expr,
+    },
+}
+
+/// Implements `RegisterBlock`, `DefaultRegisterBlock`, and `Default` for a struct where every
+/// field has a `#[register]` attribute
+fn implement_attributed_block(
+    struct_name: &Ident,
+    fields: Vec<AttributedField>,
+) -> proc_macro2::TokenStream {
+    let names = fields.iter().map(|field| match &field.kind {
+        FieldKind::Register { name, .. } => {
+            quote! { ::canadensis::register::names::NameEntry::Register(#name) }
+        }
+        FieldKind::Block { prefix } => {
+            let ty = &field.ty;
+            quote! {
+                ::canadensis::register::names::NameEntry::Block {
+                    prefix: #prefix,
+                    names: <#ty as ::canadensis::register::DefaultRegisterBlock>::NAMES,
+                }
+            }
+        }
+        FieldKind::Array {
+            prefix,
+            element,
+            length,
+        } => quote! {
+            ::canadensis::register::names::NameEntry::Array {
+                prefix: #prefix,
+                length: #length,
+                names: <#element as ::canadensis::register::DefaultRegisterBlock>::NAMES,
+            }
+        },
+    });
+    let initializers = fields.iter().map(|field| {
+        let field_name = &field.name;
+        let ty = &field.ty;
+        let value = match &field.kind {
+            FieldKind::Register {
+                name,
+                mutable,
+                persistent,
+                default,
+                validator,
+            } => {
+                let name = quote! { ::canadensis::register::names::join(prefix, #name) };
+                let validator = validator.iter();
+                match default {
+                    Some(default) => quote! {
+                        <#ty>::with_value(#name, #mutable, #persistent, #default #(, #validator)*)
+                    },
+                    None => quote! {
+                        <#ty>::new(#name, #mutable, #persistent #(, #validator)*)
+                    },
+                }
+            }
+            FieldKind::Block { prefix } => quote! {
+                <#ty as ::canadensis::register::DefaultRegisterBlock>::with_prefix(
+                    &::canadensis::register::names::block_prefix(prefix, #prefix, None)
+                )
+            },
+            FieldKind::Array {
+                prefix, element, ..
+            } => quote! {
+                ::core::array::from_fn(|index| {
+                    <#element as ::canadensis::register::DefaultRegisterBlock>::with_prefix(
+                        &::canadensis::register::names::block_prefix(prefix, #prefix, Some(index))
+                    )
+                })
+            },
+        };
+        quote! { #field_name: #value }
+    });
+
+    let by_index = fields.iter().map(|field| {
+        let field_name = &field.name;
+        match &field.kind {
+            FieldKind::Register { .. } => quote! {
+                if index == 0 {
+                    return Some(&self.#field_name);
+                }
+                index -= 1;
+            },
+            FieldKind::Block { .. } => {
+                let count = block_count(&field.ty);
+                quote! {
+                    if index < #count {
+                        return ::canadensis::register::RegisterBlock::register_by_index(&self.#field_name, index);
+                    }
+                    index -= #count;
+                }
+            }
+            FieldKind::Array { element, .. } => {
+                let count = block_count(element);
+                quote! {
+                    for block in self.#field_name.iter() {
+                        if index < #count {
+                            return ::canadensis::register::RegisterBlock::register_by_index(block, index);
+                        }
+                        index -= #count;
+                    }
+                }
+            }
+        }
+    });
+    let by_index_mut = fields.iter().map(|field| {
+        let field_name = &field.name;
+        match &field.kind {
+            FieldKind::Register { .. } => quote! {
+                if index == 0 {
+                    return Some(&mut self.#field_name);
+                }
+                index -= 1;
+            },
+            FieldKind::Block { .. } => {
+                let count = block_count(&field.ty);
+                quote! {
+                    if index < #count {
+                        return ::canadensis::register::RegisterBlock::register_by_index_mut(&mut self.#field_name, index);
+                    }
+                    index -= #count;
+                }
+            }
+            FieldKind::Array { element, .. } => {
+                let count = block_count(element);
+                quote! {
+                    for block in self.#field_name.iter_mut() {
+                        if index < #count {
+                            return ::canadensis::register::RegisterBlock::register_by_index_mut(block, index);
+                        }
+                        index -= #count;
+                    }
+                }
+            }
+        }
+    });
+    let by_name = fields.iter().map(|field| {
+        let field_name = &field.name;
+        match &field.kind {
+            FieldKind::Register { .. } => quote! {
+                if name == ::canadensis::register::Register::name(&self.#field_name) {
+                    return Some(&mut self.#field_name);
+                }
+            },
+            FieldKind::Block { .. } => quote! {
+                if let Some(register) = ::canadensis::register::RegisterBlock::register_by_name_mut(&mut self.#field_name, name) {
+                    return Some(register);
+                }
+            },
+            FieldKind::Array { .. } => quote! {
+                for block in self.#field_name.iter_mut() {
+                    if let Some(register) = ::canadensis::register::RegisterBlock::register_by_name_mut(block, name) {
+                        return Some(register);
+                    }
+                }
+            },
+        }
+    });
+
+    quote! {
+        impl ::canadensis::register::RegisterBlock for #struct_name {
+            #[allow(unused_mut, unused_assignments)]
+            fn register_by_index(&self, mut index: usize) -> Option<&dyn ::canadensis::register::Register> {
+                #( #by_index )*
+                None
+            }
+            #[allow(unused_mut, unused_assignments)]
+            fn register_by_index_mut(&mut self, mut index: usize) -> Option<&mut dyn ::canadensis::register::Register> {
+                #( #by_index_mut )*
+                None
+            }
+            #[allow(unused_variables)]
+            fn register_by_name_mut(&mut self, name: &str) -> Option<&mut dyn ::canadensis::register::Register> {
+                #( #by_name )*
+                None
+            }
+        }
+        impl ::canadensis::register::DefaultRegisterBlock for #struct_name {
+            const NAMES: &'static [::canadensis::register::names::NameEntry] = &[ #( #names, )* ];
+
+            #[allow(unused_variables)]
+            fn with_prefix(prefix: &str) -> Self {
+                #struct_name {
+                    #( #initializers, )*
+                }
+            }
+        }
+        impl ::core::default::Default for #struct_name {
+            fn default() -> Self {
+                <Self as ::canadensis::register::DefaultRegisterBlock>::with_prefix("")
+            }
+        }
+        // Check the register names at compile time
+        const _: () = ::canadensis::register::names::check(
+            <#struct_name as ::canadensis::register::DefaultRegisterBlock>::NAMES
+        );
+    }
+}
+
+/// Returns an expression for the number of registers in a nested block
+fn block_count(ty: &Type) -> proc_macro2::TokenStream {
+    quote! {
+        ::canadensis::register::names::count(<#ty as ::canadensis::register::DefaultRegisterBlock>::NAMES)
+    }
+}
+
+/// Parses the `#[register]` attributes of a struct's fields
+///
+/// This function returns None if no fields have attributes, or an error if only some fields
+/// have attributes.
+fn attributed_fields(data: &Data) -> syn::Result<Option<Vec<AttributedField>>> {
+    let fields = match data {
+        Data::Struct(data_struct) => &data_struct.fields,
+        // Reported later
+        Data::Enum(_) | Data::Union(_) => return Ok(None),
+    };
+    let has_attribute = |field: &Field| {
+        field
+            .attrs
+            .iter()
+            .any(|attr| attr.path.is_ident("register"))
+    };
+    if !fields.iter().any(has_attribute) {
+        return Ok(None);
+    }
+    let mut parsed: Vec<AttributedField> = Vec::with_capacity(fields.len());
+    for (i, field) in fields.iter().enumerate() {
+        let name = match &field.ident {
+            Some(ident) => TokenTree::Ident(ident.clone()),
+            None => TokenTree::Literal(Literal::usize_unsuffixed(i)),
+        };
+        let attribute = field
+            .attrs
+            .iter()
+            .find(|attr| attr.path.is_ident("register"))
+            .ok_or_else(|| {
+                syn::Error::new_spanned(
+                    field,
+                    "Every field needs a #[register] attribute if any field has one",
+                )
+            })?;
+        let arguments =
+            attribute.parse_args_with(Punctuated::<Argument, Token![,]>::parse_terminated)?;
+        let kind = field_kind(attribute, &field.ty, arguments)?;
+        if let FieldKind::Register {
+            name: register_name,
+            ..
+        } = &kind
+        {
+            let duplicate = parsed.iter().any(|other| {
+                matches!(&other.kind, FieldKind::Register { name, .. } if name.value() == register_name.value())
+            });
+            if duplicate {
+                return Err(syn::Error::new_spanned(
+                    register_name,
+                    "Duplicate register name",
+                ));
+            }
+        }
+        parsed.push(AttributedField {
+            name,
+            ty: field.ty.clone(),
+            kind,
+        });
+    }
+    Ok(Some(parsed))
+}
+
+/// One argument in a `#[register]` attribute: a flag like `mutable` or a value like
+/// `name = "uavcan.node.id"`
+struct Argument {
+    key: Ident,
+    value: Option<Expr>,
+}
+
+impl Parse for Argument {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let key: Ident = input.parse()?;
+        let value = if input.peek(Token![=]) {
+            input.parse::<Token![=]>()?;
+            Some(input.parse()?)
+        } else {
+            None
+        };
+        Ok(Argument { key, value })
+    }
+}
+
+fn field_kind(
+    attribute: &syn::Attribute,
+    ty: &Type,
+    arguments: Punctuated<Argument, Token![,]>,
+) -> syn::Result<FieldKind> {
+    let mut name = None;
+    let mut mutable = false;
+    let mut persistent = false;
+    let mut default = None;
+    let mut validator = None;
+    let mut block = false;
+    let mut prefix = None;
+    for argument in arguments {
+        let key = argument.key.to_string();
+        match (key.as_str(), argument.value) {
+            ("name", Some(value)) => name = Some(string_literal(value)?),
+            ("prefix", Some(value)) => prefix = Some(string_literal(value)?),
+            ("default", Some(value)) => default = Some(value),
+            ("validator", Some(value)) => validator = Some(value),
+            ("mutable", None) => mutable = true,
+            ("persistent", None) => persistent = true,
+            ("block", None) => block = true,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    argument.key,
+                    "Unexpected register attribute argument",
+                ))
+            }
+        }
+    }
+
+    if block {
+        if name.is_some() || mutable || persistent || default.is_some() || validator.is_some() {
+            return Err(syn::Error::new_spanned(
+                attribute,
+                "A nested block can only have a prefix",
+            ));
+        }
+        let prefix = prefix.unwrap_or_else(|| LitStr::new("", proc_macro2::Span::call_site()));
+        match ty {
+            Type::Array(array) => Ok(FieldKind::Array {
+                prefix,
+                element: (*array.elem).clone(),
+                length: array.len.clone(),
+            }),
+            _ => Ok(FieldKind::Block { prefix }),
+        }
+    } else {
+        if prefix.is_some() {
+            return Err(syn::Error::new_spanned(
+                attribute,
+                "A prefix is only allowed on a nested block",
+            ));
+        }
+        let name = name.ok_or_else(|| {
+            syn::Error::new_spanned(attribute, "A register attribute needs a name")
+        })?;
+        Ok(FieldKind::Register {
+            name,
+            mutable,
+            persistent,
+            default,
+            validator,
+        })
+    }
+}
+
+fn string_literal(expression: Expr) -> syn::Result<LitStr> {
+    match expression {
+        Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Str(literal),
+            ..
+        }) => Ok(literal),
+        other => Err(syn::Error::new_spanned(other, "Expected a string literal")),
+    }
+}
+
 fn get_struct_field_names(data: Data) -> Vec<TokenTree> {
     match data {
         Data::Struct(data_struct) => {
@@ -20,6 +20,21 @@ use zerocopy::{AsBytes, FromBytes};
 pub trait DataType {
     /// The sealed or delimited property of this type
     const EXTENT_BYTES: Option<u32>;
+    /// The maximum length of a serialized value of this type, in bytes
+    ///
+    /// For a delimited type, this does not include the delimiter header.
+    ///
+    /// Code generated by `canadensis_codegen_rust` defines this. The default value, 65535,
+    /// is for implementations that do not know the maximum length (for example, types generated
+    /// by older versions of the code generator), and is larger than any standard data type.
+    const MAX_SIZE_BYTES: u32 = 65535;
+
+    /// Returns the number of bytes that a receiver needs to accept for a value of this type
+    ///
+    /// This is the extent of a delimited type, or the maximum size of a sealed type.
+    fn extent() -> usize {
+        Self::EXTENT_BYTES.unwrap_or(Self::MAX_SIZE_BYTES) as usize
+    }
 }
 
 /// Trait for types that can be serialized into UAVCAN transfers
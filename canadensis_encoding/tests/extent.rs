@@ -0,0 +1,32 @@
+extern crate canadensis_encoding;
+
+use canadensis_encoding::DataType;
+
+struct Sealed;
+
+impl DataType for Sealed {
+    const EXTENT_BYTES: Option<u32> = None;
+    const MAX_SIZE_BYTES: u32 = 9;
+}
+
+struct Delimited;
+
+impl DataType for Delimited {
+    const EXTENT_BYTES: Option<u32> = Some(64);
+    const MAX_SIZE_BYTES: u32 = 9;
+}
+
+/// A sealed type that does not define its maximum size
+struct SealedUnknownSize;
+
+impl DataType for SealedUnknownSize {
+    const EXTENT_BYTES: Option<u32> = None;
+}
+
+#[test]
+fn extent() {
+    assert_eq!(9, Sealed::extent());
+    assert_eq!(64, Delimited::extent());
+    assert_eq!(65535, SealedUnknownSize::MAX_SIZE_BYTES);
+    assert_eq!(65535, SealedUnknownSize::extent());
+}
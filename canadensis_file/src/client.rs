@@ -0,0 +1,412 @@
+//!
+//! A client that downloads and uploads whole files
+//!
+
+use alloc::vec::Vec;
+use core::cmp;
+
+use canadensis::client::{ClientError, ServiceClient};
+use canadensis::core::time::{milliseconds, Clock, Instant};
+use canadensis::core::transfer::ServiceTransfer;
+use canadensis::core::transport::{Receiver, Transport};
+use canadensis::core::Priority;
+use canadensis::encoding::DataType;
+use canadensis::{nb, Node, StartSendError, TransferHandler};
+use canadensis_data_types::uavcan::file::path_2_0::Path;
+use canadensis_data_types::uavcan::file::read_1_1::{self, ReadRequest, ReadResponse};
+use canadensis_data_types::uavcan::file::write_1_1::{self, WriteRequest, WriteResponse};
+use canadensis_data_types::uavcan::primitive::unstructured_1_0::Unstructured;
+
+use crate::{FileError, Name};
+
+/// The maximum number of bytes of file data in each read response or write request
+pub(crate) const CHUNK_SIZE: usize = 256;
+
+/// Downloads and uploads files using `uavcan.file.Read` and `uavcan.file.Write`
+///
+/// The client transfers one file at a time, in chunks of up to 256 bytes. If a request does not
+/// get a response before the response timeout, or cannot be sent, the client sends it again up to
+/// `max_retries` times.
+///
+/// Basic steps:
+/// 1. Create a client using `FileClient::new`
+/// 2. Call [`start_download`](#method.start_download) or [`start_upload`](#method.start_upload)
+/// 3. When receiving transfers on the node, pass the client as a transfer handler so that it can
+///    handle responses
+/// 4. Call [`poll`](#method.poll) periodically until it returns a result
+pub struct FileClient<I, T>
+where
+    I: Instant,
+    T: Transport,
+{
+    reader: ServiceClient<I, T, ReadRequest, ReadResponse, 1>,
+    writer: ServiceClient<I, T, WriteRequest, WriteResponse, 1>,
+    /// The maximum number of times to send each request again
+    max_retries: u8,
+    /// The transfer in progress
+    operation: Option<Operation<T>>,
+    /// The result of the most recent transfer, if poll() has not returned it yet
+    result: Option<Result<Completed, FileClientError>>,
+}
+
+/// A download or upload in progress
+struct Operation<T: Transport> {
+    server: T::NodeId,
+    path: Name,
+    direction: Direction,
+    /// The offset of the next chunk to read or write
+    offset: u64,
+    /// The number of times the current chunk has been sent again
+    retries: u8,
+    /// The transfer ID of the request for the current chunk, if it is waiting for a response
+    pending: Option<T::TransferId>,
+}
+
+enum Direction {
+    /// Data received so far
+    Download(Vec<u8>),
+    /// Downloading, with each chunk passed to the caller instead of stored
+    ReadChunks,
+    /// Data to send
+    Upload(Vec<u8>),
+}
+
+impl<I, T> FileClient<I, T>
+where
+    I: Instant,
+    T: Transport,
+    T::TransferId: PartialEq,
+{
+    /// Creates a file client and starts sending read and write requests
+    ///
+    /// * `node`: The node used to send requests
+    /// * `response_timeout`: The maximum time to wait for each response
+    /// * `max_retries`: The number of times to send a request again if it fails or does not get
+    ///   a response
+    pub fn new<N>(
+        node: &mut N,
+        response_timeout: I::Duration,
+        max_retries: u8,
+    ) -> Result<Self, StartSendError<<N::Receiver as Receiver<I>>::Error>>
+    where
+        N: Node<Instant = I, Transport = T>,
+    {
+        let read_token = node.start_sending_requests(
+            read_1_1::SERVICE,
+            milliseconds(1000),
+            ReadResponse::extent(),
+            Priority::Nominal.into(),
+        )?;
+        let write_token = node.start_sending_requests(
+            write_1_1::SERVICE,
+            milliseconds(1000),
+            WriteResponse::extent(),
+            Priority::Nominal.into(),
+        )?;
+        Ok(FileClient {
+            reader: ServiceClient::new(read_token, response_timeout),
+            writer: ServiceClient::new(write_token, response_timeout),
+            max_retries,
+            operation: None,
+            result: None,
+        })
+    }
+
+    /// Returns true if a download or upload is in progress
+    pub fn is_busy(&self) -> bool {
+        self.operation.is_some()
+    }
+
+    /// Starts downloading a file from a server
+    ///
+    /// This function returns an error if another transfer is in progress or the path is too long.
+    pub fn start_download(
+        &mut self,
+        server: T::NodeId,
+        path: &[u8],
+    ) -> Result<(), FileClientError> {
+        self.start(server, path, Direction::Download(Vec::new()))
+    }
+
+    /// Starts uploading a file to a server
+    ///
+    /// After writing the data, the client sends an empty write request so that the server
+    /// truncates any existing data after the end of the new file.
+    ///
+    /// This function returns an error if another transfer is in progress or the path is too long.
+    pub fn start_upload(
+        &mut self,
+        server: T::NodeId,
+        path: &[u8],
+        data: Vec<u8>,
+    ) -> Result<(), FileClientError> {
+        self.start(server, path, Direction::Upload(data))
+    }
+
+    /// Starts downloading a file from a server, without storing the data
+    ///
+    /// Each chunk is passed to the function provided to
+    /// [`handle_response_with`](#method.handle_response_with). When the download finishes,
+    /// [`poll`](#method.poll) returns `Completed::Downloaded` with no data.
+    pub(crate) fn start_reading_chunks(
+        &mut self,
+        server: T::NodeId,
+        path: &[u8],
+    ) -> Result<(), FileClientError> {
+        self.start(server, path, Direction::ReadChunks)
+    }
+
+    fn start(
+        &mut self,
+        server: T::NodeId,
+        path: &[u8],
+        direction: Direction,
+    ) -> Result<(), FileClientError> {
+        if self.operation.is_some() {
+            return Err(FileClientError::Busy);
+        }
+        let path = Name::from_slice(path).map_err(|_| FileClientError::PathTooLong)?;
+        self.result = None;
+        self.operation = Some(Operation {
+            server,
+            path,
+            direction,
+            offset: 0,
+            retries: 0,
+            pending: None,
+        });
+        Ok(())
+    }
+
+    /// Sends requests, checks for timeouts, and returns the result of the transfer when it
+    /// finishes
+    ///
+    /// This function returns None if no transfer is in progress or the transfer has not
+    /// finished yet.
+    pub fn poll<N>(&mut self, node: &mut N) -> Option<Result<Completed, FileClientError>>
+    where
+        N: Node<Instant = I, Transport = T>,
+    {
+        let now = node.clock_mut().now();
+        let timed_out =
+            self.reader.check_timeouts(now).is_some() | self.writer.check_timeouts(now).is_some();
+        if timed_out {
+            log::debug!("File request timed out");
+            self.retry();
+        }
+        self.send_chunk(node);
+        self.result.take()
+    }
+
+    /// Cancels the transfer in progress, if any
+    ///
+    /// A response to a request that has already been sent will be ignored.
+    pub fn cancel(&mut self) {
+        if let Some(operation) = self.operation.take() {
+            if let Some(transfer_id) = &operation.pending {
+                self.reader.cancel(&operation.server, transfer_id);
+                self.writer.cancel(&operation.server, transfer_id);
+            }
+        }
+    }
+
+    /// Sends a request for the current chunk if one is not already waiting for a response
+    fn send_chunk<N>(&mut self, node: &mut N)
+    where
+        N: Node<Instant = I, Transport = T>,
+    {
+        let operation = match self.operation.as_mut() {
+            Some(operation) if operation.pending.is_none() => operation,
+            _ => return,
+        };
+        let status = match &operation.direction {
+            Direction::Download(_) | Direction::ReadChunks => {
+                let request = ReadRequest {
+                    offset: operation.offset,
+                    path: Path {
+                        path: operation.path.clone(),
+                    },
+                };
+                self.reader.send(node, &request, operation.server.clone())
+            }
+            Direction::Upload(data) => {
+                let start = operation.offset as usize;
+                let end = cmp::min(start + CHUNK_SIZE, data.len());
+                let request = WriteRequest {
+                    offset: operation.offset,
+                    path: Path {
+                        path: operation.path.clone(),
+                    },
+                    data: Unstructured {
+                        // The chunk is not longer than CHUNK_SIZE
+                        value: heapless::Vec::from_slice(&data[start..end]).unwrap(),
+                    },
+                };
+                self.writer.send(node, &request, operation.server.clone())
+            }
+        };
+        match status {
+            Ok(transfer_id) => operation.pending = Some(transfer_id),
+            // Try again on the next call to poll()
+            Err(nb::Error::WouldBlock) => {}
+            Err(nb::Error::Other(e)) => {
+                log::warn!("Failed to send file request: {:?}", e);
+                self.retry();
+            }
+        }
+    }
+
+    /// Prepares to send the current chunk again, or fails the transfer if it has been retried
+    /// too many times
+    fn retry(&mut self) {
+        if let Some(operation) = self.operation.as_mut() {
+            operation.pending = None;
+            if operation.retries >= self.max_retries {
+                self.finish(Err(FileClientError::RetriesExhausted));
+            } else {
+                operation.retries += 1;
+            }
+        }
+    }
+
+    fn finish(&mut self, result: Result<Completed, FileClientError>) {
+        self.operation = None;
+        self.result = Some(result);
+    }
+
+    fn handle_read_response(&mut self, response: ReadResponse, on_chunk: ChunkFn<'_>) {
+        let operation = match self.operation.as_mut() {
+            Some(operation) => operation,
+            None => return,
+        };
+        if let Some(error) = FileError::from_code(response.error.value) {
+            self.finish(Err(FileClientError::Server(error)));
+            return;
+        }
+        let chunk = &response.data.value;
+        match &mut operation.direction {
+            Direction::Download(data) => data.extend_from_slice(chunk),
+            Direction::ReadChunks => {
+                if !on_chunk(operation.offset, chunk) {
+                    // The caller has stopped the download
+                    self.operation = None;
+                    return;
+                }
+            }
+            Direction::Upload(_) => return,
+        }
+        operation.offset += chunk.len() as u64;
+        operation.retries = 0;
+        operation.pending = None;
+        if chunk.len() < CHUNK_SIZE {
+            let data = match &mut operation.direction {
+                Direction::Download(data) => core::mem::take(data),
+                _ => Vec::new(),
+            };
+            self.finish(Ok(Completed::Downloaded(data)));
+        }
+    }
+
+    fn handle_write_response(&mut self, response: WriteResponse) {
+        let operation = match self.operation.as_mut() {
+            Some(operation) => operation,
+            None => return,
+        };
+        if let Some(error) = FileError::from_code(response.error.value) {
+            self.finish(Err(FileClientError::Server(error)));
+            return;
+        }
+        if let Direction::Upload(data) = &operation.direction {
+            let remaining = data.len() - operation.offset as usize;
+            operation.retries = 0;
+            operation.pending = None;
+            if remaining == 0 {
+                // The final empty write has been acknowledged
+                self.finish(Ok(Completed::Uploaded));
+            } else {
+                operation.offset += cmp::min(remaining, CHUNK_SIZE) as u64;
+            }
+        }
+    }
+
+    fn handle_client_error(&mut self, error: ClientError<T>) {
+        log::debug!("Invalid file response: {:?}", error);
+        self.retry();
+    }
+
+    /// Handles a response, passing any chunk read by
+    /// [`start_reading_chunks`](#method.start_reading_chunks) to `on_chunk`
+    ///
+    /// `on_chunk` receives the offset and content of each chunk. If it returns false, the
+    /// download stops without a result.
+    ///
+    /// This function returns true if the transfer was a response to this client.
+    pub(crate) fn handle_response_with<N>(
+        &mut self,
+        node: &mut N,
+        transfer: &ServiceTransfer<Vec<u8>, I, T>,
+        on_chunk: ChunkFn<'_>,
+    ) -> bool
+    where
+        N: Node<Instant = I, Transport = T>,
+    {
+        if let Some(result) = self.reader.handle_response(transfer) {
+            match result {
+                Ok(response) => self.handle_read_response(response.payload, on_chunk),
+                Err(e) => self.handle_client_error(e),
+            }
+        } else if let Some(result) = self.writer.handle_response(transfer) {
+            match result {
+                Ok(response) => self.handle_write_response(response.payload),
+                Err(e) => self.handle_client_error(e),
+            }
+        } else {
+            return false;
+        }
+        // Request the next chunk immediately
+        self.send_chunk(node);
+        true
+    }
+}
+
+/// A function that receives the offset and content of each chunk of a file
+pub(crate) type ChunkFn<'a> = &'a mut dyn FnMut(u64, &[u8]) -> bool;
+
+impl<I, T> TransferHandler<I, T> for FileClient<I, T>
+where
+    I: Instant,
+    T: Transport,
+    T::TransferId: PartialEq,
+{
+    fn handle_response<N: Node<Instant = I, Transport = T>>(
+        &mut self,
+        node: &mut N,
+        transfer: &ServiceTransfer<Vec<u8>, I, T>,
+    ) -> bool {
+        // Downloads started by start_download() do not use the chunk function
+        self.handle_response_with(node, transfer, &mut |_, _| true)
+    }
+}
+
+/// A successfully completed file transfer
+#[derive(Debug, PartialEq, Eq)]
+pub enum Completed {
+    /// A file was downloaded, and this is its content
+    Downloaded(Vec<u8>),
+    /// A file was uploaded
+    Uploaded,
+}
+
+/// Errors that can occur when transferring a file
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FileClientError {
+    /// Another transfer is already in progress
+    Busy,
+    /// The path is longer than 255 bytes
+    PathTooLong,
+    /// The server responded with an error
+    Server(FileError),
+    /// A request failed or did not get a response, and was sent again the maximum number of
+    /// times
+    RetriesExhausted,
+}
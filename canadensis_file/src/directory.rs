@@ -0,0 +1,339 @@
+//!
+//! A file store that provides access to the files in a directory
+//!
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, ErrorKind, Read, Seek, SeekFrom, Write};
+use std::path::{Component, Path, PathBuf};
+use std::str;
+use std::time::UNIX_EPOCH;
+use std::vec::Vec;
+
+use crate::{FileError, FileInfo, FileStore, ModifyOperation, Name};
+
+/// The maximum number of symbolic links to follow when checking a path
+const MAX_LINKS: u32 = 40;
+
+/// A file store that provides access to the files in a directory and its subdirectories
+///
+/// Paths from requests are interpreted relative to the root directory. Paths must be valid UTF-8.
+/// Paths that contain `..` components, or that lead outside the root directory through
+/// symbolic links, are rejected with [`FileError::AccessDenied`].
+///
+/// Moving or copying an entry onto itself or into one of its subdirectories is rejected with
+/// [`FileError::InvalidValue`]. Copying a directory copies the symbolic links in it as links.
+#[derive(Debug, Clone)]
+pub struct DirectoryStore {
+    /// The canonical path to the root directory
+    root: PathBuf,
+}
+
+impl DirectoryStore {
+    /// Creates a store that provides access to the files in `root`
+    ///
+    /// This function returns an error if `root` does not exist or is not a directory.
+    pub fn new<P: AsRef<Path>>(root: P) -> io::Result<Self> {
+        let root = root.as_ref().canonicalize()?;
+        if !root.is_dir() {
+            return Err(io::Error::other("File store root is not a directory"));
+        }
+        Ok(DirectoryStore { root })
+    }
+
+    /// Returns the canonical path to the root directory
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Converts a path from a request into a path in the root directory
+    ///
+    /// The returned path may not exist, but its closest existing ancestor is inside the root
+    /// directory. If the path or one of its ancestors is a symbolic link to something that does
+    /// not exist, the link must also point inside the root directory.
+    fn resolve(&self, path: &[u8]) -> Result<PathBuf, FileError> {
+        let path = str::from_utf8(path).map_err(|_| FileError::InvalidValue)?;
+        let mut resolved = self.root.clone();
+        for component in path.split('/') {
+            match component {
+                "" | "." => {}
+                ".." => return Err(FileError::AccessDenied),
+                component => {
+                    // Reject anything that the platform would not treat as a single plain name
+                    // (for example, a Windows drive prefix)
+                    let mut components = Path::new(component).components();
+                    match (components.next(), components.next()) {
+                        (Some(Component::Normal(_)), None) => resolved.push(component),
+                        _ => return Err(FileError::InvalidValue),
+                    }
+                }
+            }
+        }
+        self.check_inside_root(&resolved, 0)?;
+        Ok(resolved)
+    }
+
+    /// Checks that a path, after following the symbolic links in the part of it that exists,
+    /// is inside the root directory
+    fn check_inside_root(&self, path: &Path, links_followed: u32) -> Result<(), FileError> {
+        // The closest ancestor that exists, possibly as a link to something that does not exist
+        let existing = path
+            .ancestors()
+            .find(|ancestor| fs::symlink_metadata(ancestor).is_ok())
+            .ok_or(FileError::NotFound)?;
+        match existing.canonicalize() {
+            Ok(canonical) if canonical.starts_with(&self.root) => Ok(()),
+            Ok(_) => Err(FileError::AccessDenied),
+            Err(e) if e.kind() == ErrorKind::NotFound => {
+                // This is a link to something that does not exist. Creating a file here would
+                // create it at the target of the link, so the target must also be inside
+                // the root.
+                if links_followed >= MAX_LINKS {
+                    return Err(FileError::AccessDenied);
+                }
+                let target = fs::read_link(existing).map_err(map_io_error)?;
+                let target = match existing.parent() {
+                    Some(parent) => parent.join(target),
+                    None => target,
+                };
+                self.check_inside_root(&target, links_followed + 1)
+            }
+            Err(e) => Err(map_io_error(e)),
+        }
+    }
+
+    /// Resolves a path that must not be the root directory itself
+    fn resolve_entry(&self, path: &[u8]) -> Result<PathBuf, FileError> {
+        let resolved = self.resolve(path)?;
+        if resolved == self.root {
+            Err(FileError::AccessDenied)
+        } else {
+            Ok(resolved)
+        }
+    }
+
+    fn move_or_copy(
+        &self,
+        source: &[u8],
+        destination: &[u8],
+        overwrite_destination: bool,
+        preserve_source: bool,
+    ) -> Result<(), FileError> {
+        let source = self.resolve_entry(source)?;
+        let destination = self.resolve_entry(destination)?;
+        if !source.exists() {
+            return Err(FileError::NotFound);
+        }
+        // Moving or copying an entry onto itself or into itself would remove or endlessly
+        // copy the source
+        if canonicalize_existing(&destination)?.starts_with(canonicalize_existing(&source)?) {
+            return Err(FileError::InvalidValue);
+        }
+        if destination.exists() {
+            if !overwrite_destination {
+                return Err(FileError::InvalidValue);
+            }
+            remove(&destination)?;
+        }
+        if let Some(parent) = destination.parent() {
+            fs::create_dir_all(parent).map_err(map_io_error)?;
+        }
+        if preserve_source {
+            copy(&source, &destination)
+        } else {
+            fs::rename(&source, &destination).map_err(map_io_error)
+        }
+    }
+}
+
+impl FileStore for DirectoryStore {
+    fn read(&mut self, path: &[u8], offset: u64, buffer: &mut [u8]) -> Result<usize, FileError> {
+        let path = self.resolve(path)?;
+        if path.is_dir() {
+            return Err(FileError::IsDirectory);
+        }
+        let mut file = File::open(path).map_err(map_io_error)?;
+        file.seek(SeekFrom::Start(offset)).map_err(map_io_error)?;
+        let mut length = 0;
+        while length < buffer.len() {
+            match file.read(&mut buffer[length..]) {
+                Ok(0) => break,
+                Ok(bytes) => length += bytes,
+                Err(e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) => return Err(map_io_error(e)),
+            }
+        }
+        Ok(length)
+    }
+
+    fn write(&mut self, path: &[u8], offset: u64, data: &[u8]) -> Result<(), FileError> {
+        let path = self.resolve_entry(path)?;
+        let mut file = open_for_writing(&path)?;
+        file.seek(SeekFrom::Start(offset)).map_err(map_io_error)?;
+        file.write_all(data).map_err(map_io_error)
+    }
+
+    fn truncate(&mut self, path: &[u8], length: u64) -> Result<(), FileError> {
+        let path = self.resolve_entry(path)?;
+        let file = open_for_writing(&path)?;
+        file.set_len(length).map_err(map_io_error)
+    }
+
+    fn get_info(&mut self, path: &[u8]) -> Result<FileInfo, FileError> {
+        let path = self.resolve(path)?;
+        let link_metadata = fs::symlink_metadata(&path).map_err(map_io_error)?;
+        let metadata = fs::metadata(&path).map_err(map_io_error)?;
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+            .map(|since_epoch| since_epoch.as_secs())
+            .unwrap_or(0);
+        Ok(FileInfo {
+            size: if metadata.is_file() {
+                metadata.len()
+            } else {
+                0
+            },
+            unix_timestamp_of_last_modification: modified,
+            is_file: metadata.is_file(),
+            is_link: link_metadata.file_type().is_symlink(),
+            is_readable: true,
+            is_writeable: !metadata.permissions().readonly(),
+        })
+    }
+
+    fn list(&mut self, directory: &[u8], index: u32) -> Result<Option<Name>, FileError> {
+        let directory = self.resolve(directory)?;
+        if !directory.is_dir() {
+            return Err(FileError::NotFound);
+        }
+        // Sort the names so that the indexes stay the same between requests
+        let mut names = fs::read_dir(directory)
+            .map_err(map_io_error)?
+            .map(|entry| entry.map(|entry| entry.file_name()))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(map_io_error)?;
+        names.sort();
+        match names.get(index as usize) {
+            Some(name) => {
+                let name = name.to_str().ok_or(FileError::InvalidValue)?;
+                let name =
+                    Name::from_slice(name.as_bytes()).map_err(|_| FileError::InvalidValue)?;
+                Ok(Some(name))
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn modify(&mut self, operation: ModifyOperation<'_>) -> Result<(), FileError> {
+        match operation {
+            ModifyOperation::Move {
+                source,
+                destination,
+                overwrite_destination,
+            } => self.move_or_copy(source, destination, overwrite_destination, false),
+            ModifyOperation::Copy {
+                source,
+                destination,
+                overwrite_destination,
+            } => self.move_or_copy(source, destination, overwrite_destination, true),
+            ModifyOperation::Touch(path) => {
+                let path = self.resolve_entry(path)?;
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent).map_err(map_io_error)?;
+                }
+                if path.is_dir() {
+                    return Ok(());
+                }
+                let file = open_for_writing(&path)?;
+                file.set_modified(std::time::SystemTime::now())
+                    .map_err(map_io_error)
+            }
+            ModifyOperation::Remove(path) => {
+                let path = self.resolve_entry(path)?;
+                remove(&path)
+            }
+        }
+    }
+}
+
+/// Canonicalizes the part of a path that exists, and appends the rest of the path
+fn canonicalize_existing(path: &Path) -> Result<PathBuf, FileError> {
+    let existing = path
+        .ancestors()
+        .find(|ancestor| ancestor.exists())
+        .ok_or(FileError::NotFound)?;
+    let mut canonical = existing.canonicalize().map_err(map_io_error)?;
+    if let Ok(rest) = path.strip_prefix(existing) {
+        canonical.push(rest);
+    }
+    Ok(canonical)
+}
+
+fn open_for_writing(path: &Path) -> Result<File, FileError> {
+    if path.is_dir() {
+        return Err(FileError::IsDirectory);
+    }
+    OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path)
+        .map_err(map_io_error)
+}
+
+/// Removes a file, link, or directory and its contents
+fn remove(path: &Path) -> Result<(), FileError> {
+    let metadata = fs::symlink_metadata(path).map_err(map_io_error)?;
+    if metadata.is_dir() {
+        fs::remove_dir_all(path).map_err(map_io_error)
+    } else {
+        fs::remove_file(path).map_err(map_io_error)
+    }
+}
+
+/// Copies a file, a symbolic link, or a directory and its contents
+///
+/// Symbolic links are copied as links, not as the things they point to. Following them could
+/// copy files from outside the root directory, or recurse forever through a link to a parent
+/// directory.
+fn copy(source: &Path, destination: &Path) -> Result<(), FileError> {
+    let metadata = fs::symlink_metadata(source).map_err(map_io_error)?;
+    if metadata.file_type().is_symlink() {
+        copy_link(source, destination)
+    } else if metadata.is_dir() {
+        fs::create_dir(destination).map_err(map_io_error)?;
+        for entry in fs::read_dir(source).map_err(map_io_error)? {
+            let entry = entry.map_err(map_io_error)?;
+            copy(&entry.path(), &destination.join(entry.file_name()))?;
+        }
+        Ok(())
+    } else {
+        fs::copy(source, destination)
+            .map(drop)
+            .map_err(map_io_error)
+    }
+}
+
+/// Creates a symbolic link at `destination` with the same target as the link at `source`
+#[cfg(unix)]
+fn copy_link(source: &Path, destination: &Path) -> Result<(), FileError> {
+    let target = fs::read_link(source).map_err(map_io_error)?;
+    std::os::unix::fs::symlink(target, destination).map_err(map_io_error)
+}
+
+/// Copying symbolic links is only supported on Unix-like platforms
+#[cfg(not(unix))]
+fn copy_link(_source: &Path, _destination: &Path) -> Result<(), FileError> {
+    Err(FileError::NotSupported)
+}
+
+fn map_io_error(error: io::Error) -> FileError {
+    match error.kind() {
+        ErrorKind::NotFound => FileError::NotFound,
+        ErrorKind::PermissionDenied => FileError::AccessDenied,
+        ErrorKind::InvalidInput | ErrorKind::InvalidData => FileError::InvalidValue,
+        ErrorKind::Unsupported => FileError::NotSupported,
+        _ => FileError::IoError,
+    }
+}
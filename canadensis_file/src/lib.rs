@@ -0,0 +1,221 @@
+//!
+//! # Canadensis file transfer
+//!
+//! This library implements the `uavcan.file` services.
+//!
+//! [`FileServer`] is a transfer handler that responds to file requests from other nodes, using
+//! a [`FileStore`] to access the files. With the `std` feature enabled,
+//! [`DirectoryStore`](directory::DirectoryStore) provides access to the files in a directory.
+//!
+//! [`FileClient`](client::FileClient) downloads and uploads whole files from and to a server.
+//!
+//! [`SoftwareUpdater`](update::SoftwareUpdater) downloads a software image into an
+//! [`ImageStorage`](update::ImageStorage), checks its CRC, and commits it.
+//!
+//! The server and client use these versions of the data types:
+//! * `uavcan.file.GetInfo.0.2`
+//! * `uavcan.file.List.0.2`
+//! * `uavcan.file.Modify.1.1`
+//! * `uavcan.file.Read.1.1`
+//! * `uavcan.file.Write.1.1`
+//!
+
+#![no_std]
+#![deny(missing_docs)]
+
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+extern crate canadensis;
+extern crate canadensis_crc;
+extern crate canadensis_data_types;
+extern crate heapless;
+extern crate log;
+
+pub mod client;
+#[cfg(feature = "std")]
+pub mod directory;
+mod server;
+pub mod update;
+
+pub use crate::server::FileServer;
+
+use canadensis_data_types::uavcan::file::error_1_0::Error;
+
+/// The maximum length of a path or a file name, in bytes
+pub const MAX_PATH_LENGTH: usize = 255;
+
+/// A file or directory name, or a path with components separated by `/`
+pub type Name = heapless::Vec<u8, MAX_PATH_LENGTH>;
+
+/// Provides access to files for a [`FileServer`]
+///
+/// Paths are byte strings with components separated by `/`. The store determines how paths are
+/// interpreted.
+///
+/// Only [`read`](#tymethod.read) is required. The default implementations of the other functions
+/// return [`FileError::NotSupported`].
+pub trait FileStore {
+    /// Reads data from a file starting at `offset`
+    ///
+    /// This function should fill `buffer` unless the end of the file is reached. It returns the
+    /// number of bytes read.
+    fn read(&mut self, path: &[u8], offset: u64, buffer: &mut [u8]) -> Result<usize, FileError>;
+
+    /// Writes data into a file starting at `offset`, creating the file if it does not exist
+    fn write(&mut self, path: &[u8], offset: u64, data: &[u8]) -> Result<(), FileError> {
+        let _ = (path, offset, data);
+        Err(FileError::NotSupported)
+    }
+
+    /// Sets the length of a file, creating the file if it does not exist
+    ///
+    /// The server calls this function when a client has finished writing a file.
+    fn truncate(&mut self, path: &[u8], length: u64) -> Result<(), FileError> {
+        let _ = (path, length);
+        Err(FileError::NotSupported)
+    }
+
+    /// Returns information about a file or directory
+    fn get_info(&mut self, path: &[u8]) -> Result<FileInfo, FileError> {
+        let _ = path;
+        Err(FileError::NotSupported)
+    }
+
+    /// Returns the name of an entry in a directory
+    ///
+    /// Entries are numbered from 0 in a consistent order. If `index` is greater than or equal
+    /// to the number of entries, this function returns None.
+    fn list(&mut self, directory: &[u8], index: u32) -> Result<Option<Name>, FileError> {
+        let _ = (directory, index);
+        Err(FileError::NotSupported)
+    }
+
+    /// Moves, copies, creates, or removes a file or directory
+    fn modify(&mut self, operation: ModifyOperation<'_>) -> Result<(), FileError> {
+        let _ = operation;
+        Err(FileError::NotSupported)
+    }
+}
+
+/// Information about a file or directory
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileInfo {
+    /// The size of the file in bytes (zero for directories)
+    pub size: u64,
+    /// The time when the entry was last modified, in seconds since the Unix epoch, or zero if
+    /// unknown
+    pub unix_timestamp_of_last_modification: u64,
+    /// True if this is a file, false if this is a directory
+    pub is_file: bool,
+    /// True if this is a link to another entry
+    pub is_link: bool,
+    /// True if the entry can be read
+    pub is_readable: bool,
+    /// True if the entry can be written
+    pub is_writeable: bool,
+}
+
+/// An operation requested using `uavcan.file.Modify`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ModifyOperation<'p> {
+    /// Move or rename an entry
+    ///
+    /// If the destination is in directories that do not exist, they should be created.
+    Move {
+        /// The entry to move
+        source: &'p [u8],
+        /// The new path
+        destination: &'p [u8],
+        /// If this is true and the destination exists, it should be replaced. Otherwise,
+        /// the operation should fail if the destination exists.
+        overwrite_destination: bool,
+    },
+    /// Copy an entry
+    ///
+    /// If the destination is in directories that do not exist, they should be created.
+    Copy {
+        /// The entry to copy
+        source: &'p [u8],
+        /// The path of the copy
+        destination: &'p [u8],
+        /// If this is true and the destination exists, it should be replaced. Otherwise,
+        /// the operation should fail if the destination exists.
+        overwrite_destination: bool,
+    },
+    /// Update the modification time of an entry, or create an empty file if it does not exist
+    Touch(&'p [u8]),
+    /// Remove a file or directory (including its contents)
+    Remove(&'p [u8]),
+}
+
+/// Errors that can occur when accessing files
+///
+/// These correspond to the values of `uavcan.file.Error`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FileError {
+    /// The file or directory does not exist
+    NotFound,
+    /// An input/output error occurred
+    IoError,
+    /// Permission to access the entry was denied
+    AccessDenied,
+    /// A file was expected, but the entry is a directory
+    IsDirectory,
+    /// A value (like a path) in the request was invalid
+    InvalidValue,
+    /// The file is too large
+    FileTooLarge,
+    /// No space is available to store more data
+    OutOfSpace,
+    /// The operation is not supported
+    NotSupported,
+    /// Another error, with its `uavcan.file.Error` code
+    Other(u16),
+}
+
+impl FileError {
+    /// Converts an error code from a `uavcan.file.Error` into a file error
+    ///
+    /// This function returns None if the code is `OK`.
+    pub fn from_code(code: u16) -> Option<Self> {
+        match code {
+            Error::OK => None,
+            Error::NOT_FOUND => Some(FileError::NotFound),
+            Error::IO_ERROR => Some(FileError::IoError),
+            Error::ACCESS_DENIED => Some(FileError::AccessDenied),
+            Error::IS_DIRECTORY => Some(FileError::IsDirectory),
+            Error::INVALID_VALUE => Some(FileError::InvalidValue),
+            Error::FILE_TOO_LARGE => Some(FileError::FileTooLarge),
+            Error::OUT_OF_SPACE => Some(FileError::OutOfSpace),
+            Error::NOT_SUPPORTED => Some(FileError::NotSupported),
+            other => Some(FileError::Other(other)),
+        }
+    }
+
+    /// Returns the `uavcan.file.Error` code for this error
+    pub fn code(&self) -> u16 {
+        match self {
+            FileError::NotFound => Error::NOT_FOUND,
+            FileError::IoError => Error::IO_ERROR,
+            FileError::AccessDenied => Error::ACCESS_DENIED,
+            FileError::IsDirectory => Error::IS_DIRECTORY,
+            FileError::InvalidValue => Error::INVALID_VALUE,
+            FileError::FileTooLarge => Error::FILE_TOO_LARGE,
+            FileError::OutOfSpace => Error::OUT_OF_SPACE,
+            FileError::NotSupported => Error::NOT_SUPPORTED,
+            FileError::Other(code) => *code,
+        }
+    }
+}
+
+/// Converts a result into a `uavcan.file.Error`
+fn result_to_error<T>(result: &Result<T, FileError>) -> Error {
+    Error {
+        value: match result {
+            Ok(_) => Error::OK,
+            Err(e) => e.code(),
+        },
+    }
+}
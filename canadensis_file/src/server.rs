@@ -0,0 +1,251 @@
+use alloc::vec::Vec;
+
+use canadensis::core::time::{milliseconds, Instant};
+use canadensis::core::transfer::ServiceTransfer;
+use canadensis::core::transport::{Receiver, Transport};
+use canadensis::encoding::{DataType, Deserialize, Response, Serialize};
+use canadensis::{Node, ResponseToken, TransferHandler};
+use canadensis_data_types::uavcan::file::get_info_0_2::{self, GetInfoRequest, GetInfoResponse};
+use canadensis_data_types::uavcan::file::list_0_2::{self, ListRequest, ListResponse};
+use canadensis_data_types::uavcan::file::modify_1_1::{self, ModifyRequest, ModifyResponse};
+use canadensis_data_types::uavcan::file::path_2_0::Path;
+use canadensis_data_types::uavcan::file::read_1_1::{self, ReadRequest, ReadResponse};
+use canadensis_data_types::uavcan::file::write_1_1::{self, WriteRequest, WriteResponse};
+use canadensis_data_types::uavcan::primitive::unstructured_1_0::Unstructured;
+
+use crate::{result_to_error, FileError, FileStore, ModifyOperation};
+
+/// The maximum number of bytes in a read response
+///
+/// A response with fewer bytes indicates the end of the file.
+const READ_CHUNK_SIZE: usize = 256;
+
+/// A server that handles `uavcan.file` requests from other nodes
+///
+/// The server handles Read, Write, List, Modify, and GetInfo requests by passing them to a
+/// [`FileStore`].
+///
+/// A write request with no data marks the end of a file: the server truncates the file to the
+/// offset in the request.
+pub struct FileServer<S> {
+    store: S,
+}
+
+impl<S> FileServer<S>
+where
+    S: FileStore,
+{
+    /// Creates a file server and subscribes to file requests
+    ///
+    /// This function returns an error if the provided node does not have enough space to listen
+    /// for requests.
+    pub fn new<N>(
+        node: &mut N,
+        store: S,
+    ) -> Result<Self, <N::Receiver as Receiver<N::Instant>>::Error>
+    where
+        N: Node,
+    {
+        node.subscribe_request(read_1_1::SERVICE, ReadRequest::extent(), milliseconds(1000))?;
+        node.subscribe_request(
+            write_1_1::SERVICE,
+            WriteRequest::extent(),
+            milliseconds(1000),
+        )?;
+        node.subscribe_request(list_0_2::SERVICE, ListRequest::extent(), milliseconds(1000))?;
+        node.subscribe_request(
+            modify_1_1::SERVICE,
+            ModifyRequest::extent(),
+            milliseconds(1000),
+        )?;
+        node.subscribe_request(
+            get_info_0_2::SERVICE,
+            GetInfoRequest::extent(),
+            milliseconds(1000),
+        )?;
+        Ok(FileServer { store })
+    }
+
+    /// Returns a reference to the file store
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    /// Returns a mutable reference to the file store
+    pub fn store_mut(&mut self) -> &mut S {
+        &mut self.store
+    }
+
+    /// Consumes this server and returns its file store
+    ///
+    /// The node will still be subscribed to file requests.
+    pub fn into_store(self) -> S {
+        self.store
+    }
+
+    fn handle_read(&mut self, request: &ReadRequest) -> ReadResponse {
+        let offset = request.offset;
+        log::debug!("Handling file read request, offset {}", offset);
+        let mut buffer = [0u8; READ_CHUNK_SIZE];
+        let result = self
+            .store
+            .read(&request.path.path, offset, &mut buffer)
+            .map(|length| length.min(READ_CHUNK_SIZE));
+        let data = match result {
+            // The data fits because length <= READ_CHUNK_SIZE
+            Ok(length) => heapless::Vec::from_slice(&buffer[..length]).unwrap(),
+            Err(_) => heapless::Vec::new(),
+        };
+        ReadResponse {
+            error: result_to_error(&result),
+            data: Unstructured { value: data },
+        }
+    }
+
+    fn handle_write(&mut self, request: &WriteRequest) -> WriteResponse {
+        let offset = request.offset;
+        log::debug!(
+            "Handling file write request, offset {}, {} bytes",
+            offset,
+            request.data.value.len()
+        );
+        let result = if request.data.value.is_empty() {
+            self.store.truncate(&request.path.path, offset)
+        } else {
+            self.store
+                .write(&request.path.path, offset, &request.data.value)
+        };
+        WriteResponse {
+            error: result_to_error(&result),
+        }
+    }
+
+    fn handle_list(&mut self, request: &ListRequest) -> ListResponse {
+        let index = request.entry_index;
+        log::debug!("Handling file list request, index {}", index);
+        let name = match self.store.list(&request.directory_path.path, index) {
+            Ok(Some(name)) => name,
+            // An empty name indicates the end of the directory or an error
+            Ok(None) | Err(_) => heapless::Vec::new(),
+        };
+        ListResponse {
+            entry_base_name: Path { path: name },
+        }
+    }
+
+    fn handle_modify(&mut self, request: &ModifyRequest) -> ModifyResponse {
+        log::debug!("Handling file modify request");
+        let source = &request.source.path[..];
+        let destination = &request.destination.path[..];
+        let result = match (source.is_empty(), destination.is_empty()) {
+            (true, true) => Err(FileError::InvalidValue),
+            (true, false) => self.store.modify(ModifyOperation::Touch(destination)),
+            (false, true) => self.store.modify(ModifyOperation::Remove(source)),
+            (false, false) => {
+                let overwrite_destination = request.overwrite_destination;
+                if request.preserve_source {
+                    self.store.modify(ModifyOperation::Copy {
+                        source,
+                        destination,
+                        overwrite_destination,
+                    })
+                } else {
+                    self.store.modify(ModifyOperation::Move {
+                        source,
+                        destination,
+                        overwrite_destination,
+                    })
+                }
+            }
+        };
+        ModifyResponse {
+            error: result_to_error(&result),
+        }
+    }
+
+    fn handle_get_info(&mut self, request: &GetInfoRequest) -> GetInfoResponse {
+        log::debug!("Handling file get info request");
+        let result = self.store.get_info(&request.path.path);
+        let error = result_to_error(&result);
+        let info = result.unwrap_or_default();
+        GetInfoResponse {
+            error,
+            size: info.size,
+            unix_timestamp_of_last_modification: info.unix_timestamp_of_last_modification,
+            is_file_not_directory: info.is_file,
+            is_link: info.is_link,
+            is_readable: info.is_readable,
+            is_writeable: info.is_writeable,
+        }
+    }
+}
+
+impl<I, T, S> TransferHandler<I, T> for FileServer<S>
+where
+    I: Instant,
+    T: Transport,
+    S: FileStore,
+{
+    fn handle_request<N: Node<Instant = I, Transport = T>>(
+        &mut self,
+        node: &mut N,
+        token: ResponseToken<T>,
+        transfer: &ServiceTransfer<Vec<u8>, I, T>,
+    ) -> bool {
+        match transfer.header.service {
+            read_1_1::SERVICE => match ReadRequest::deserialize_from_bytes(&transfer.payload) {
+                Ok(request) => {
+                    let response = self.handle_read(&request);
+                    respond(node, token, &response);
+                    true
+                }
+                Err(_) => false,
+            },
+            write_1_1::SERVICE => match WriteRequest::deserialize_from_bytes(&transfer.payload) {
+                Ok(request) => {
+                    let response = self.handle_write(&request);
+                    respond(node, token, &response);
+                    true
+                }
+                Err(_) => false,
+            },
+            list_0_2::SERVICE => match ListRequest::deserialize_from_bytes(&transfer.payload) {
+                Ok(request) => {
+                    let response = self.handle_list(&request);
+                    respond(node, token, &response);
+                    true
+                }
+                Err(_) => false,
+            },
+            modify_1_1::SERVICE => match ModifyRequest::deserialize_from_bytes(&transfer.payload) {
+                Ok(request) => {
+                    let response = self.handle_modify(&request);
+                    respond(node, token, &response);
+                    true
+                }
+                Err(_) => false,
+            },
+            get_info_0_2::SERVICE => {
+                match GetInfoRequest::deserialize_from_bytes(&transfer.payload) {
+                    Ok(request) => {
+                        let response = self.handle_get_info(&request);
+                        respond(node, token, &response);
+                        true
+                    }
+                    Err(_) => false,
+                }
+            }
+            _ => false,
+        }
+    }
+}
+
+fn respond<N, R>(node: &mut N, token: ResponseToken<N::Transport>, response: &R)
+where
+    N: Node,
+    R: Response + Serialize,
+{
+    if let Err(e) = node.send_response(token, milliseconds(1000), response) {
+        log::warn!("Failed to send file response: {:?}", e);
+    }
+}
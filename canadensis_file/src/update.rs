@@ -0,0 +1,350 @@
+//!
+//! Downloading, checking, and installing software images
+//!
+
+use alloc::vec::Vec;
+use core::fmt::Debug;
+
+use canadensis::core::time::Instant;
+use canadensis::core::transfer::ServiceTransfer;
+use canadensis::core::transport::{Receiver, Transport};
+use canadensis::{Node, StartSendError, TransferHandler};
+use canadensis_crc::Crc64We;
+
+use crate::client::{FileClient, FileClientError, CHUNK_SIZE};
+use crate::FileError;
+
+/// Storage for a new software image
+///
+/// This is usually a region of flash memory that is not used by the running software.
+pub trait ImageStorage {
+    /// The error type
+    type Error: Debug;
+
+    /// Prepares to receive a new image (for example, by erasing flash memory)
+    fn begin(&mut self) -> Result<(), Self::Error>;
+
+    /// Writes part of the image starting at `offset`
+    ///
+    /// Chunks are written in order, with no gaps.
+    fn write(&mut self, offset: u64, data: &[u8]) -> Result<(), Self::Error>;
+
+    /// Marks the complete image, `length` bytes long, as ready to be installed
+    ///
+    /// This function is only called after the image has been checked.
+    fn commit(&mut self, length: u64) -> Result<(), Self::Error>;
+
+    /// Discards a partially written image after an update fails or is cancelled
+    fn abort(&mut self) {}
+}
+
+/// How to check that an image was received correctly
+///
+/// Both methods use the CRC-64/WE algorithm from `canadensis_crc`, including its padding of the
+/// image to a multiple of 8 bytes.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ImageCheck {
+    /// The CRC of the image must be equal to this value
+    Expected(u64),
+    /// The image contains its own CRC (little-endian) at the provided offset,
+    /// as written by `canadensis_write_crc`
+    ///
+    /// The CRC is calculated with the 8 bytes at the offset set to zero.
+    Embedded {
+        /// The offset of the CRC from the beginning of the image
+        offset: u64,
+    },
+}
+
+/// Downloads a software image using `uavcan.file.Read`, checks its CRC, and commits it to
+/// an [`ImageStorage`]
+///
+/// The updater reads the image with a [`FileClient`] and writes each chunk to the storage as it
+/// arrives, so the image does not need to fit into memory. If a request does not get a response
+/// before the response timeout, or cannot be sent, the updater sends it again up to `max_retries`
+/// times.
+///
+/// A node that receives a `COMMAND_BEGIN_SOFTWARE_UPDATE` command (see
+/// [`canadensis::command`]) can start an update using the server node ID and path from
+/// the command.
+///
+/// Basic steps:
+/// 1. Create an updater using `SoftwareUpdater::new`
+/// 2. Call [`start`](#method.start)
+/// 3. When receiving transfers on the node, pass the updater as a transfer handler so that it can
+///    handle responses
+/// 4. Call [`poll`](#method.poll) periodically until it returns a result
+pub struct SoftwareUpdater<I, T, S>
+where
+    I: Instant,
+    T: Transport,
+    S: ImageStorage,
+{
+    client: FileClient<I, T>,
+    storage: S,
+    /// The update in progress
+    update: Option<Update>,
+    /// The result of the most recent update, if poll() has not returned it yet
+    result: Option<Result<u64, UpdateError<S::Error>>>,
+}
+
+/// An update in progress
+struct Update {
+    check: ImageCheck,
+    /// The number of bytes of the image received so far
+    offset: u64,
+    /// The CRC of the image so far
+    crc: Crc64We,
+    /// The CRC bytes found in the image, for ImageCheck::Embedded
+    embedded_crc: [u8; 8],
+}
+
+impl<I, T, S> SoftwareUpdater<I, T, S>
+where
+    I: Instant,
+    T: Transport,
+    T::TransferId: PartialEq,
+    S: ImageStorage,
+{
+    /// Creates an updater and starts sending read requests
+    ///
+    /// * `node`: The node used to send requests
+    /// * `storage`: The storage that new images will be written to
+    /// * `response_timeout`: The maximum time to wait for each response
+    /// * `max_retries`: The number of times to send a request again if it fails or does not get
+    ///   a response
+    pub fn new<N>(
+        node: &mut N,
+        storage: S,
+        response_timeout: I::Duration,
+        max_retries: u8,
+    ) -> Result<Self, StartSendError<<N::Receiver as Receiver<I>>::Error>>
+    where
+        N: Node<Instant = I, Transport = T>,
+    {
+        Ok(SoftwareUpdater {
+            client: FileClient::new(node, response_timeout, max_retries)?,
+            storage,
+            update: None,
+            result: None,
+        })
+    }
+
+    /// Returns a reference to the image storage
+    pub fn storage(&self) -> &S {
+        &self.storage
+    }
+
+    /// Returns a mutable reference to the image storage
+    pub fn storage_mut(&mut self) -> &mut S {
+        &mut self.storage
+    }
+
+    /// Returns true if an update is in progress
+    pub fn is_busy(&self) -> bool {
+        self.update.is_some()
+    }
+
+    /// Returns the number of bytes of the image that have been received, if an update is in
+    /// progress
+    pub fn bytes_received(&self) -> Option<u64> {
+        self.update.as_ref().map(|update| update.offset)
+    }
+
+    /// Starts downloading an image from the file `path` on the node `server`
+    ///
+    /// This function calls [`ImageStorage::begin`]. It returns an error if another update is in
+    /// progress, the path is too long, or the storage returns an error.
+    pub fn start(
+        &mut self,
+        server: T::NodeId,
+        path: &[u8],
+        check: ImageCheck,
+    ) -> Result<(), UpdateError<S::Error>> {
+        if self.update.is_some() {
+            return Err(UpdateError::Busy);
+        }
+        self.client
+            .start_reading_chunks(server, path)
+            .map_err(UpdateError::from)?;
+        if let Err(e) = self.storage.begin() {
+            self.client.cancel();
+            return Err(UpdateError::Storage(e));
+        }
+        self.result = None;
+        self.update = Some(Update {
+            check,
+            offset: 0,
+            crc: Crc64We::new(),
+            embedded_crc: [0; 8],
+        });
+        Ok(())
+    }
+
+    /// Sends requests, checks for timeouts, and returns the result of the update when it
+    /// finishes
+    ///
+    /// When an update succeeds, this function returns the length of the image.
+    ///
+    /// This function returns None if no update is in progress or the update has not
+    /// finished yet.
+    pub fn poll<N>(&mut self, node: &mut N) -> Option<Result<u64, UpdateError<S::Error>>>
+    where
+        N: Node<Instant = I, Transport = T>,
+    {
+        match self.client.poll(node) {
+            Some(Ok(_)) => self.finish(),
+            Some(Err(e)) => self.fail(e.into()),
+            None => {}
+        }
+        self.result.take()
+    }
+
+    /// Cancels the update in progress, if any, and calls [`ImageStorage::abort`]
+    pub fn cancel(&mut self) {
+        if self.update.take().is_some() {
+            self.client.cancel();
+            self.storage.abort();
+        }
+    }
+
+    fn fail(&mut self, error: UpdateError<S::Error>) {
+        self.update = None;
+        self.storage.abort();
+        self.result = Some(Err(error));
+    }
+
+    /// Checks and commits the image after the client has read all of it
+    fn finish(&mut self) {
+        let update = match self.update.take() {
+            Some(update) => update,
+            None => return,
+        };
+        match update.check_crc() {
+            Ok(()) => match self.storage.commit(update.offset) {
+                Ok(()) => self.result = Some(Ok(update.offset)),
+                Err(e) => self.fail(UpdateError::Storage(e)),
+            },
+            Err(e) => self.fail(e),
+        }
+    }
+}
+
+impl Update {
+    /// Adds a chunk of the image at the current offset to the CRC
+    fn add_to_crc(&mut self, chunk: &[u8]) {
+        // Read responses never contain more than CHUNK_SIZE bytes
+        match self.check {
+            ImageCheck::Expected(_) => self.crc.digest(chunk),
+            ImageCheck::Embedded { offset } => {
+                // Copy the CRC out of the image and replace it with zeros
+                let mut chunk_copy = [0u8; CHUNK_SIZE];
+                let chunk_copy = &mut chunk_copy[..chunk.len()];
+                chunk_copy.copy_from_slice(chunk);
+                for (position, byte) in (self.offset..).zip(chunk_copy.iter_mut()) {
+                    if position >= offset && position < offset + 8 {
+                        self.embedded_crc[(position - offset) as usize] = *byte;
+                        *byte = 0;
+                    }
+                }
+                self.crc.digest(chunk_copy);
+            }
+        }
+    }
+
+    /// Checks the CRC after receiving the complete image
+    fn check_crc<E>(&self) -> Result<(), UpdateError<E>> {
+        let expected = match self.check {
+            ImageCheck::Expected(expected) => expected,
+            ImageCheck::Embedded { offset } => {
+                if self.offset < offset + 8 {
+                    return Err(UpdateError::MissingCrc);
+                }
+                u64::from_le_bytes(self.embedded_crc)
+            }
+        };
+        let actual = self.crc.clone().finish();
+        if actual == expected {
+            Ok(())
+        } else {
+            Err(UpdateError::CrcMismatch { expected, actual })
+        }
+    }
+}
+
+impl<I, T, S> TransferHandler<I, T> for SoftwareUpdater<I, T, S>
+where
+    I: Instant,
+    T: Transport,
+    T::TransferId: PartialEq,
+    S: ImageStorage,
+{
+    fn handle_response<N: Node<Instant = I, Transport = T>>(
+        &mut self,
+        node: &mut N,
+        transfer: &ServiceTransfer<Vec<u8>, I, T>,
+    ) -> bool {
+        let storage = &mut self.storage;
+        let update = &mut self.update;
+        let mut storage_error = None;
+        let handled = self
+            .client
+            .handle_response_with(node, transfer, &mut |offset, chunk| {
+                let update = match update.as_mut() {
+                    Some(update) => update,
+                    None => return false,
+                };
+                match storage.write(offset, chunk) {
+                    Ok(()) => {
+                        update.add_to_crc(chunk);
+                        update.offset += chunk.len() as u64;
+                        true
+                    }
+                    Err(e) => {
+                        storage_error = Some(e);
+                        false
+                    }
+                }
+            });
+        if let Some(e) = storage_error {
+            self.fail(UpdateError::Storage(e));
+        }
+        handled
+    }
+}
+
+/// Errors that can occur when updating software
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UpdateError<E> {
+    /// Another update is already in progress
+    Busy,
+    /// The path is longer than 255 bytes
+    PathTooLong,
+    /// The file server responded with an error
+    Server(FileError),
+    /// A request failed or did not get a response, and was sent again the maximum number of
+    /// times
+    RetriesExhausted,
+    /// The image storage returned an error
+    Storage(E),
+    /// The image is too short to contain an embedded CRC at the expected offset
+    MissingCrc,
+    /// The CRC of the received image is incorrect
+    CrcMismatch {
+        /// The expected CRC
+        expected: u64,
+        /// The CRC of the received image
+        actual: u64,
+    },
+}
+
+impl<E> From<FileClientError> for UpdateError<E> {
+    fn from(error: FileClientError) -> Self {
+        match error {
+            FileClientError::Busy => UpdateError::Busy,
+            FileClientError::PathTooLong => UpdateError::PathTooLong,
+            FileClientError::Server(error) => UpdateError::Server(error),
+            FileClientError::RetriesExhausted => UpdateError::RetriesExhausted,
+        }
+    }
+}
@@ -0,0 +1,73 @@
+//! Utilities shared by the integration tests
+
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+
+use canadensis::core::time::{MicrosecondDuration64, Microseconds64, SimulatedClock};
+use canadensis::node::CoreNode;
+use canadensis::requester::TransferIdFixedMap;
+use canadensis_can::simulated::{SimulatedBus, SimulatedDriver};
+use canadensis_can::{CanNodeId, CanReceiver, CanTransmitter, CanTransport, Mtu};
+use canadensis_file::{FileError, FileStore};
+
+/// A node that uses a simulated clock and a simulated CAN FD bus
+pub type TestNode = CoreNode<
+    SimulatedClock,
+    CanTransmitter<Microseconds64, SimulatedDriver<Microseconds64>>,
+    CanReceiver<Microseconds64, SimulatedDriver<Microseconds64>>,
+    TransferIdFixedMap<CanTransport, 4>,
+    SimulatedDriver<Microseconds64>,
+    4,
+    4,
+>;
+
+/// Creates a bus that transmits one frame every 100 microseconds
+pub fn make_bus() -> SimulatedBus<Microseconds64> {
+    SimulatedBus::new(MicrosecondDuration64::new(100))
+}
+
+/// Creates a node connected to a bus
+pub fn make_node(bus: &SimulatedBus<Microseconds64>, clock: &SimulatedClock, id: u8) -> TestNode {
+    let node_id = CanNodeId::try_from(id).unwrap();
+    CoreNode::new(
+        clock.clone(),
+        node_id,
+        CanTransmitter::new(Mtu::CanFd64),
+        CanReceiver::new(node_id, Mtu::CanFd64),
+        bus.add_driver(),
+    )
+}
+
+/// A file store that keeps files in memory
+#[derive(Default)]
+pub struct MemoryStore {
+    pub files: BTreeMap<Vec<u8>, Vec<u8>>,
+}
+
+impl FileStore for MemoryStore {
+    fn read(&mut self, path: &[u8], offset: u64, buffer: &mut [u8]) -> Result<usize, FileError> {
+        let file = self.files.get(path).ok_or(FileError::NotFound)?;
+        let start = (offset as usize).min(file.len());
+        let length = buffer.len().min(file.len() - start);
+        buffer[..length].copy_from_slice(&file[start..start + length]);
+        Ok(length)
+    }
+
+    fn write(&mut self, path: &[u8], offset: u64, data: &[u8]) -> Result<(), FileError> {
+        let file = self.files.entry(path.to_vec()).or_default();
+        let end = offset as usize + data.len();
+        if file.len() < end {
+            file.resize(end, 0);
+        }
+        file[offset as usize..end].copy_from_slice(data);
+        Ok(())
+    }
+
+    fn truncate(&mut self, path: &[u8], length: u64) -> Result<(), FileError> {
+        self.files
+            .entry(path.to_vec())
+            .or_default()
+            .resize(length as usize, 0);
+        Ok(())
+    }
+}
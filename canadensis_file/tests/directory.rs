@@ -0,0 +1,250 @@
+//! Tests of the directory file store
+
+extern crate canadensis_file;
+
+use std::fs;
+use std::path::PathBuf;
+
+use canadensis_file::directory::DirectoryStore;
+use canadensis_file::{FileError, FileStore, ModifyOperation};
+
+/// A temporary directory that is removed when dropped
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn new(name: &str) -> Self {
+        let path =
+            std::env::temp_dir().join(format!("canadensis_file_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(path.join("root")).unwrap();
+        TempDir(path)
+    }
+
+    fn root(&self) -> PathBuf {
+        self.0.join("root")
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+#[test]
+fn read_write_truncate() {
+    let dir = TempDir::new("read_write");
+    let mut store = DirectoryStore::new(dir.root()).unwrap();
+    store.write(b"sub/../x", 0, b"abc").unwrap_err();
+    assert_eq!(
+        Err(FileError::NotFound),
+        store.write(b"missing/x", 0, b"abc")
+    );
+
+    store.write(b"/a.txt", 0, b"hello world").unwrap();
+    store.write(b"a.txt", 6, b"there").unwrap();
+    let mut buffer = [0u8; 32];
+    let length = store.read(b"./a.txt", 0, &mut buffer).unwrap();
+    assert_eq!(b"hello there", &buffer[..length]);
+    let length = store.read(b"a.txt", 6, &mut buffer).unwrap();
+    assert_eq!(b"there", &buffer[..length]);
+
+    store.truncate(b"a.txt", 5).unwrap();
+    assert_eq!(b"hello", &fs::read(dir.root().join("a.txt")).unwrap()[..]);
+
+    let info = store.get_info(b"a.txt").unwrap();
+    assert_eq!(5, info.size);
+    assert!(info.is_file);
+    assert!(!store.get_info(b"").unwrap().is_file);
+    assert_eq!(Err(FileError::IsDirectory), store.read(b"", 0, &mut buffer));
+}
+
+#[test]
+fn path_traversal() {
+    let dir = TempDir::new("traversal");
+    fs::write(dir.0.join("secret"), b"secret").unwrap();
+    let mut store = DirectoryStore::new(dir.root()).unwrap();
+    let mut buffer = [0u8; 32];
+    for path in [&b"../secret"[..], b"a/../../secret", b".."] {
+        assert_eq!(
+            Err(FileError::AccessDenied),
+            store.read(path, 0, &mut buffer)
+        );
+        assert_eq!(Err(FileError::AccessDenied), store.write(path, 0, b"x"));
+    }
+    // Leading separators do not make the path absolute
+    assert_eq!(
+        Err(FileError::NotFound),
+        store.read(
+            dir.0.join("secret").to_str().unwrap().as_bytes(),
+            0,
+            &mut buffer
+        )
+    );
+    assert_eq!(
+        Err(FileError::AccessDenied),
+        store.modify(ModifyOperation::Remove(b"/"))
+    );
+    assert!(dir.root().exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn symbolic_link_outside_root() {
+    let dir = TempDir::new("symlink");
+    fs::create_dir(dir.0.join("outside")).unwrap();
+    fs::write(dir.0.join("outside/secret"), b"secret").unwrap();
+    std::os::unix::fs::symlink(dir.0.join("outside"), dir.root().join("link")).unwrap();
+    let mut store = DirectoryStore::new(dir.root()).unwrap();
+    let mut buffer = [0u8; 32];
+    assert_eq!(
+        Err(FileError::AccessDenied),
+        store.read(b"link/secret", 0, &mut buffer)
+    );
+    assert_eq!(
+        Err(FileError::AccessDenied),
+        store.write(b"link/new", 0, b"x")
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn dangling_symbolic_link() {
+    let dir = TempDir::new("dangling");
+    fs::create_dir(dir.0.join("outside")).unwrap();
+    std::os::unix::fs::symlink(dir.0.join("outside/new"), dir.root().join("out")).unwrap();
+    std::os::unix::fs::symlink(dir.root().join("out"), dir.root().join("chain")).unwrap();
+    std::os::unix::fs::symlink(dir.root().join("target"), dir.root().join("in")).unwrap();
+    let mut store = DirectoryStore::new(dir.root()).unwrap();
+    for path in [&b"out"[..], b"chain"] {
+        assert_eq!(Err(FileError::AccessDenied), store.write(path, 0, b"x"));
+        assert_eq!(
+            Err(FileError::AccessDenied),
+            store.modify(ModifyOperation::Touch(path))
+        );
+    }
+    assert!(!dir.0.join("outside/new").exists());
+    // A link to a file in the root that does not exist yet can be used
+    store.write(b"in", 0, b"x").unwrap();
+    assert_eq!(b"x", &fs::read(dir.root().join("target")).unwrap()[..]);
+}
+
+#[cfg(unix)]
+#[test]
+fn copy_directory_with_symbolic_links() {
+    let dir = TempDir::new("copy_links");
+    fs::create_dir(dir.0.join("outside")).unwrap();
+    fs::write(dir.0.join("outside/secret"), b"secret").unwrap();
+    fs::create_dir(dir.root().join("a")).unwrap();
+    fs::write(dir.root().join("a/file"), b"data").unwrap();
+    // One link points outside the root, and the other points to a parent directory
+    std::os::unix::fs::symlink(dir.0.join("outside"), dir.root().join("a/out")).unwrap();
+    std::os::unix::fs::symlink("..", dir.root().join("a/parent")).unwrap();
+    let mut store = DirectoryStore::new(dir.root()).unwrap();
+    store
+        .modify(ModifyOperation::Copy {
+            source: b"a",
+            destination: b"b",
+            overwrite_destination: false,
+        })
+        .unwrap();
+
+    assert_eq!(b"data", &fs::read(dir.root().join("b/file")).unwrap()[..]);
+    // The links were copied as links
+    for name in ["out", "parent"] {
+        let copy = dir.root().join("b").join(name);
+        assert!(fs::symlink_metadata(&copy)
+            .unwrap()
+            .file_type()
+            .is_symlink());
+        assert_eq!(
+            fs::read_link(dir.root().join("a").join(name)).unwrap(),
+            fs::read_link(&copy).unwrap()
+        );
+    }
+    // The copied link still can't be used to read outside the root
+    let mut buffer = [0u8; 32];
+    assert_eq!(
+        Err(FileError::AccessDenied),
+        store.read(b"b/out/secret", 0, &mut buffer)
+    );
+}
+
+#[test]
+fn move_or_copy_into_itself() {
+    let dir = TempDir::new("into_itself");
+    fs::create_dir(dir.root().join("a")).unwrap();
+    fs::write(dir.root().join("a/file"), b"data").unwrap();
+    let mut store = DirectoryStore::new(dir.root()).unwrap();
+    for (source, destination) in [
+        (&b"a"[..], &b"a/b"[..]),
+        (b"a", b"a"),
+        (b"a/file", b"a/file"),
+    ] {
+        assert_eq!(
+            Err(FileError::InvalidValue),
+            store.modify(ModifyOperation::Copy {
+                source,
+                destination,
+                overwrite_destination: true,
+            })
+        );
+        assert_eq!(
+            Err(FileError::InvalidValue),
+            store.modify(ModifyOperation::Move {
+                source,
+                destination,
+                overwrite_destination: true,
+            })
+        );
+    }
+    assert_eq!(b"data", &fs::read(dir.root().join("a/file")).unwrap()[..]);
+    assert!(!dir.root().join("a/b").exists());
+}
+
+#[test]
+fn list_and_modify() {
+    let dir = TempDir::new("list_modify");
+    let mut store = DirectoryStore::new(dir.root()).unwrap();
+    store.modify(ModifyOperation::Touch(b"b")).unwrap();
+    store.write(b"a", 0, b"data").unwrap();
+    store
+        .modify(ModifyOperation::Copy {
+            source: b"a",
+            destination: b"dir/c",
+            overwrite_destination: false,
+        })
+        .unwrap();
+
+    let names: Vec<Vec<u8>> = (0..)
+        .map_while(|i| store.list(b"", i).unwrap())
+        .map(|name| name.to_vec())
+        .collect();
+    assert_eq!(vec![b"a".to_vec(), b"b".to_vec(), b"dir".to_vec()], names);
+    assert_eq!(Some(&b"c"[..]), store.list(b"dir", 0).unwrap().as_deref());
+
+    assert_eq!(
+        Err(FileError::InvalidValue),
+        store.modify(ModifyOperation::Move {
+            source: b"a",
+            destination: b"b",
+            overwrite_destination: false,
+        })
+    );
+    store
+        .modify(ModifyOperation::Move {
+            source: b"a",
+            destination: b"b",
+            overwrite_destination: true,
+        })
+        .unwrap();
+    assert_eq!(b"data", &fs::read(dir.root().join("b")).unwrap()[..]);
+    assert!(!dir.root().join("a").exists());
+
+    store.modify(ModifyOperation::Remove(b"dir")).unwrap();
+    assert!(!dir.root().join("dir").exists());
+    assert_eq!(
+        Err(FileError::NotFound),
+        store.modify(ModifyOperation::Remove(b"dir"))
+    );
+}
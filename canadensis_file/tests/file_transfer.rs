@@ -0,0 +1,170 @@
+//! Tests of the file server and client on an in-memory CAN bus
+
+extern crate canadensis;
+extern crate canadensis_can;
+extern crate canadensis_file;
+
+mod common;
+
+use std::convert::TryFrom;
+
+use canadensis::core::time::{milliseconds, MicrosecondDuration64, Microseconds64, SimulatedClock};
+use canadensis::Node;
+use canadensis_can::{CanNodeId, CanTransport};
+use canadensis_file::client::{Completed, FileClient, FileClientError};
+use canadensis_file::{FileError, FileServer};
+
+use common::{make_bus, make_node, MemoryStore, TestNode};
+
+struct Setup {
+    clock: SimulatedClock,
+    server_node: TestNode,
+    server: FileServer<MemoryStore>,
+    client_node: TestNode,
+    client: FileClient<Microseconds64, CanTransport>,
+}
+
+impl Setup {
+    fn new(max_retries: u8) -> Self {
+        let bus = make_bus();
+        let clock = SimulatedClock::new();
+        let mut server_node = make_node(&bus, &clock, 10);
+        let mut client_node = make_node(&bus, &clock, 20);
+        let server = FileServer::new(&mut server_node, MemoryStore::default()).unwrap();
+        let client = FileClient::new(&mut client_node, milliseconds(100), max_retries).unwrap();
+        Setup {
+            clock,
+            server_node,
+            server,
+            client_node,
+            client,
+        }
+    }
+
+    /// Runs the nodes until the client finishes a transfer or the time limit expires
+    fn run(&mut self, milliseconds: u32) -> Option<Result<Completed, FileClientError>> {
+        for _ in 0..milliseconds {
+            self.clock.advance(MicrosecondDuration64::new(1000));
+            for _ in 0..64 {
+                self.server_node.receive(&mut self.server).unwrap();
+                self.client_node.receive(&mut self.client).unwrap();
+            }
+            let result = self.client.poll(&mut self.client_node);
+            self.server_node.flush().unwrap();
+            self.client_node.flush().unwrap();
+            if result.is_some() {
+                return result;
+            }
+        }
+        None
+    }
+}
+
+fn server_id() -> CanNodeId {
+    CanNodeId::try_from(10u8).unwrap()
+}
+
+fn test_data(length: usize) -> Vec<u8> {
+    (0..length).map(|i| (i * 7) as u8).collect()
+}
+
+#[test]
+fn upload_and_download() {
+    let mut setup = Setup::new(0);
+    // 1000 bytes is not a multiple of the chunk size, and 512 bytes is
+    for &length in &[1000, 512, 0] {
+        let data = test_data(length);
+        let path = format!("files/test_{}.bin", length);
+        setup
+            .client
+            .start_upload(server_id(), path.as_bytes(), data.clone())
+            .unwrap();
+        assert_eq!(Some(Ok(Completed::Uploaded)), setup.run(1000));
+        assert_eq!(Some(&data), setup.server.store().files.get(path.as_bytes()));
+
+        setup
+            .client
+            .start_download(server_id(), path.as_bytes())
+            .unwrap();
+        assert_eq!(Some(Ok(Completed::Downloaded(data))), setup.run(1000));
+    }
+}
+
+#[test]
+fn upload_replaces_longer_file() {
+    let mut setup = Setup::new(0);
+    setup
+        .server
+        .store_mut()
+        .files
+        .insert(b"a".to_vec(), test_data(700));
+    let data = vec![1, 2, 3];
+    setup
+        .client
+        .start_upload(server_id(), b"a", data.clone())
+        .unwrap();
+    assert_eq!(Some(Ok(Completed::Uploaded)), setup.run(1000));
+    assert_eq!(Some(&data), setup.server.store().files.get(&b"a"[..]));
+}
+
+#[test]
+fn server_error() {
+    let mut setup = Setup::new(0);
+    setup
+        .client
+        .start_download(server_id(), b"missing")
+        .unwrap();
+    assert_eq!(
+        Some(Err(FileClientError::Server(FileError::NotFound))),
+        setup.run(1000)
+    );
+}
+
+#[test]
+fn busy_and_path_too_long() {
+    let mut setup = Setup::new(0);
+    assert_eq!(
+        Err(FileClientError::PathTooLong),
+        setup.client.start_download(server_id(), &[b'a'; 256])
+    );
+    setup.client.start_download(server_id(), b"a").unwrap();
+    assert!(setup.client.is_busy());
+    assert_eq!(
+        Err(FileClientError::Busy),
+        setup.client.start_download(server_id(), b"b")
+    );
+    setup.client.cancel();
+    assert!(!setup.client.is_busy());
+}
+
+#[test]
+fn retry_after_timeout() {
+    let mut setup = Setup::new(3);
+    setup
+        .server
+        .store_mut()
+        .files
+        .insert(b"a".to_vec(), test_data(600));
+    // The server misses the first two requests
+    setup.server_node.driver_mut().set_connected(false);
+    setup.client.start_download(server_id(), b"a").unwrap();
+    assert_eq!(None, setup.run(250));
+    setup.server_node.driver_mut().set_connected(true);
+    assert_eq!(
+        Some(Ok(Completed::Downloaded(test_data(600)))),
+        setup.run(1000)
+    );
+}
+
+#[test]
+fn retries_exhausted() {
+    let mut setup = Setup::new(2);
+    setup.server_node.driver_mut().set_connected(false);
+    setup
+        .client
+        .start_upload(server_id(), b"a", vec![1])
+        .unwrap();
+    // The request is sent three times, and each one times out after 100 milliseconds
+    assert_eq!(None, setup.run(250));
+    assert_eq!(Some(Err(FileClientError::RetriesExhausted)), setup.run(100));
+}
@@ -0,0 +1,262 @@
+//! Tests of software updates started with ExecuteCommand, on an in-memory CAN bus
+
+extern crate canadensis;
+extern crate canadensis_can;
+extern crate canadensis_crc;
+extern crate canadensis_data_types;
+extern crate canadensis_file;
+extern crate heapless;
+
+mod common;
+
+use std::convert::TryFrom;
+
+use canadensis::client::ServiceClient;
+use canadensis::command::{CommandHandler, CommandStatus, Commands};
+use canadensis::core::time::{milliseconds, MicrosecondDuration64, Microseconds64, SimulatedClock};
+use canadensis::core::Priority;
+use canadensis::{Node, TransferHandler};
+use canadensis_can::{CanNodeId, CanTransport};
+use canadensis_crc::Crc64We;
+use canadensis_data_types::uavcan::node::execute_command_1_1::{
+    self, ExecuteCommandRequest, ExecuteCommandResponse,
+};
+use canadensis_file::update::{ImageCheck, ImageStorage, SoftwareUpdater, UpdateError};
+use canadensis_file::{FileError, FileServer};
+
+use common::{make_bus, make_node, MemoryStore, TestNode};
+
+type CommandClient =
+    ServiceClient<Microseconds64, CanTransport, ExecuteCommandRequest, ExecuteCommandResponse, 1>;
+
+/// Image storage in memory
+#[derive(Default)]
+struct MemoryImage {
+    image: Vec<u8>,
+    committed: Option<u64>,
+    aborted: bool,
+}
+
+impl ImageStorage for MemoryImage {
+    type Error = ();
+
+    fn begin(&mut self) -> Result<(), Self::Error> {
+        self.image.clear();
+        self.committed = None;
+        self.aborted = false;
+        Ok(())
+    }
+
+    fn write(&mut self, offset: u64, data: &[u8]) -> Result<(), Self::Error> {
+        assert_eq!(offset, self.image.len() as u64);
+        self.image.extend_from_slice(data);
+        Ok(())
+    }
+
+    fn commit(&mut self, length: u64) -> Result<(), Self::Error> {
+        self.committed = Some(length);
+        Ok(())
+    }
+
+    fn abort(&mut self) {
+        self.aborted = true;
+    }
+}
+
+/// Records a software update request
+#[derive(Default)]
+struct DeviceCommands {
+    update_request: Option<(CanNodeId, Vec<u8>)>,
+}
+
+impl Commands<CanTransport> for DeviceCommands {
+    fn begin_software_update(&mut self, server: CanNodeId, path: &[u8]) -> CommandStatus {
+        self.update_request = Some((server, path.to_vec()));
+        CommandStatus::Success
+    }
+}
+
+/// A file server that also sends commands, and a device that updates its software
+struct Setup {
+    clock: SimulatedClock,
+    server_node: TestNode,
+    server: FileServer<MemoryStore>,
+    command_client: CommandClient,
+    device_node: TestNode,
+    commands: CommandHandler<DeviceCommands>,
+    updater: SoftwareUpdater<Microseconds64, CanTransport, MemoryImage>,
+}
+
+impl Setup {
+    fn new(path: &[u8], image: Vec<u8>) -> Self {
+        let bus = make_bus();
+        let clock = SimulatedClock::new();
+        let mut server_node = make_node(&bus, &clock, 10);
+        let mut device_node = make_node(&bus, &clock, 30);
+        let mut store = MemoryStore::default();
+        store.files.insert(path.to_vec(), image);
+        let server = FileServer::new(&mut server_node, store).unwrap();
+        let command_token = server_node
+            .start_sending_requests(
+                execute_command_1_1::SERVICE,
+                milliseconds(1000),
+                1,
+                Priority::Nominal,
+            )
+            .unwrap();
+        CommandHandler::<DeviceCommands>::subscribe_requests(&mut device_node).unwrap();
+        let updater = SoftwareUpdater::new(
+            &mut device_node,
+            MemoryImage::default(),
+            milliseconds(100),
+            2,
+        )
+        .unwrap();
+        Setup {
+            clock,
+            server_node,
+            server,
+            command_client: ServiceClient::new(command_token, milliseconds(1000)),
+            device_node,
+            commands: CommandHandler::new(DeviceCommands::default()),
+            updater,
+        }
+    }
+
+    fn send_update_command(&mut self, path: &[u8]) {
+        let request = ExecuteCommandRequest {
+            command: ExecuteCommandRequest::COMMAND_BEGIN_SOFTWARE_UPDATE,
+            parameter: heapless::Vec::from_slice(path).unwrap(),
+        };
+        self.command_client
+            .send(
+                &mut self.server_node,
+                &request,
+                CanNodeId::try_from(30u8).unwrap(),
+            )
+            .unwrap();
+    }
+
+    /// Runs the nodes until the update finishes or the time limit expires
+    fn run(
+        &mut self,
+        check: ImageCheck,
+        milliseconds: u32,
+    ) -> Option<Result<u64, UpdateError<()>>> {
+        for _ in 0..milliseconds {
+            self.clock.advance(MicrosecondDuration64::new(1000));
+            for _ in 0..64 {
+                self.server_node.receive(&mut self.server).unwrap();
+                self.device_node
+                    .receive(&mut (&mut self.commands).chain(&mut self.updater))
+                    .unwrap();
+            }
+            if let Some((server, path)) = self.commands.commands_mut().update_request.take() {
+                self.updater.start(server, &path, check).unwrap();
+            }
+            let result = self.updater.poll(&mut self.device_node);
+            self.server_node.flush().unwrap();
+            self.device_node.flush().unwrap();
+            if result.is_some() {
+                assert!(self.commands.software_update_in_progress());
+                self.commands.finish_software_update();
+                return result;
+            }
+        }
+        None
+    }
+}
+
+fn test_image(length: usize) -> Vec<u8> {
+    (0..length).map(|i| (i * 13 + 5) as u8).collect()
+}
+
+fn crc(data: &[u8]) -> u64 {
+    let mut crc = Crc64We::new();
+    crc.digest(data);
+    crc.finish()
+}
+
+/// Returns an image with a CRC embedded at an offset, like one from `canadensis_write_crc`
+fn image_with_embedded_crc(length: usize, offset: usize) -> Vec<u8> {
+    let mut image = test_image(length);
+    image[offset..][..8].copy_from_slice(&[0; 8]);
+    let crc = crc(&image);
+    image[offset..][..8].copy_from_slice(&crc.to_le_bytes());
+    image
+}
+
+#[test]
+fn update_with_expected_crc() {
+    let image = test_image(1000);
+    let check = ImageCheck::Expected(crc(&image));
+    let mut setup = Setup::new(b"images/app.bin", image.clone());
+    setup.send_update_command(b"images/app.bin");
+    assert_eq!(Some(Ok(1000)), setup.run(check, 1000));
+    let storage = setup.updater.storage();
+    assert_eq!(image, storage.image);
+    assert_eq!(Some(1000), storage.committed);
+    assert!(!storage.aborted);
+    assert!(setup.commands.mode().is_none());
+}
+
+#[test]
+fn update_with_embedded_crc() {
+    // The CRC crosses the boundary between the first two chunks
+    let image = image_with_embedded_crc(700, 252);
+    let mut setup = Setup::new(b"app", image.clone());
+    setup.send_update_command(b"app");
+    let check = ImageCheck::Embedded { offset: 252 };
+    assert_eq!(Some(Ok(700)), setup.run(check, 1000));
+    assert_eq!(image, setup.updater.storage().image);
+    assert_eq!(Some(700), setup.updater.storage().committed);
+}
+
+#[test]
+fn corrupted_image_not_committed() {
+    let mut image = image_with_embedded_crc(700, 252);
+    image[600] ^= 1;
+    let mut setup = Setup::new(b"app", image);
+    setup.send_update_command(b"app");
+    let check = ImageCheck::Embedded { offset: 252 };
+    assert!(matches!(
+        setup.run(check, 1000),
+        Some(Err(UpdateError::CrcMismatch { .. }))
+    ));
+    assert_eq!(None, setup.updater.storage().committed);
+    assert!(setup.updater.storage().aborted);
+
+    // An image that is too short to contain the CRC
+    let mut setup = Setup::new(b"app", test_image(100));
+    setup.send_update_command(b"app");
+    assert_eq!(Some(Err(UpdateError::MissingCrc)), setup.run(check, 1000));
+}
+
+#[test]
+fn missing_image() {
+    let mut setup = Setup::new(b"app", test_image(10));
+    setup.send_update_command(b"other");
+    assert_eq!(
+        Some(Err(UpdateError::Server(FileError::NotFound))),
+        setup.run(ImageCheck::Expected(0), 1000)
+    );
+    assert!(setup.updater.storage().aborted);
+}
+
+#[test]
+fn server_offline() {
+    let mut setup = Setup::new(b"app", test_image(10));
+    setup.send_update_command(b"app");
+    // Let the command through, then disconnect the file server
+    for _ in 0..5 {
+        if setup.updater.is_busy() {
+            break;
+        }
+        assert_eq!(None, setup.run(ImageCheck::Expected(0), 1));
+    }
+    setup.server_node.driver_mut().set_connected(false);
+    assert_eq!(
+        Some(Err(UpdateError::RetriesExhausted)),
+        setup.run(ImageCheck::Expected(0), 1000)
+    );
+}
@@ -7,6 +7,7 @@
 extern crate canadensis_can;
 extern crate canadensis_core;
 extern crate canadensis_filter_config;
+extern crate libc;
 extern crate log;
 extern crate socketcan;
 
@@ -15,21 +16,110 @@ use canadensis_can::{CanNodeId, Frame};
 use canadensis_core::subscription::Subscription;
 use canadensis_core::time::{Clock, Instant, Microseconds64};
 use canadensis_core::{nb, OutOfMemoryError};
-use socketcan::CANSocket;
+use socketcan::{CANFilter, CANSocket};
 use std::cmp::Ordering;
+use std::collections::VecDeque;
 use std::convert::TryInto;
 use std::io;
 use std::io::ErrorKind;
+use std::mem;
+use std::os::unix::io::AsRawFd;
+
+/// The maximum number of transmitted frames to remember while waiting for the socket to
+/// receive them
+const MAX_SENT_FRAMES: usize = 1024;
 
 /// An adapter between SocketCAN and the canadensis frame format
+///
+/// ## Loopback
+///
+/// After [`set_loopback(true)`](#method.set_loopback) is called, the socket receives a copy of
+/// each frame that it sends. `LinuxCan` uses these copies to report when frames with the loopback
+/// flag set have been transmitted, and does not pass them on as incoming frames.
 pub struct LinuxCan {
     socket: CANSocket,
+    /// The ID of this node and the filters from the last call to `apply_filters`, or None if
+    /// the socket accepts all frames
+    filters: Option<(Option<CanNodeId>, Vec<CANFilter>)>,
+    /// If loopback is enabled, the frames that have been transmitted but not received back
+    sent: Option<VecDeque<Frame<Microseconds64>>>,
+    /// Transmitted frames with the loopback flag set, with their transmission times
+    loopback: VecDeque<Frame<Microseconds64>>,
 }
 
 impl LinuxCan {
     /// Creates a Linux CAN adapter around a SocketCAN socket
     pub fn new(socket: CANSocket) -> Self {
-        LinuxCan { socket }
+        LinuxCan {
+            socket,
+            filters: None,
+            sent: None,
+            loopback: VecDeque::new(),
+        }
+    }
+
+    /// Enables or disables reception of this socket's own frames, which is required to report
+    /// when loopback frames have been transmitted
+    pub fn set_loopback(&mut self, enabled: bool) -> io::Result<()> {
+        let value = libc::c_int::from(enabled);
+        // Safety: The socket file descriptor is valid, and the option value is a c_int
+        // as the kernel expects
+        let status = unsafe {
+            libc::setsockopt(
+                self.socket.as_raw_fd(),
+                libc::SOL_CAN_RAW,
+                libc::CAN_RAW_RECV_OWN_MSGS,
+                &value as *const libc::c_int as *const libc::c_void,
+                mem::size_of::<libc::c_int>() as libc::socklen_t,
+            )
+        };
+        if status != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        self.sent = if enabled { Some(VecDeque::new()) } else { None };
+        // Update the filters to allow or block this node's own frames
+        self.set_socket_filters()
+    }
+
+    fn set_socket_filters(&self) -> io::Result<()> {
+        match &self.filters {
+            Some((local_node, filters)) => match (local_node, &self.sent) {
+                (Some(local_node), Some(_)) => {
+                    // Also accept frames from this node, which are copies of transmitted frames
+                    let mut filters = filters.clone();
+                    filters.push(CANFilter::new(u32::from(u8::from(*local_node)), 0x7f).unwrap());
+                    self.socket.set_filter(&filters)
+                }
+                _ => self.socket.set_filter(filters),
+            },
+            None => self.socket.filter_accept_all(),
+        }
+    }
+
+    /// Checks if a received frame is a copy of a frame that this socket sent
+    ///
+    /// If it is, this function returns true and records the transmission time of loopback frames.
+    fn handle_own_frame(&mut self, frame: &Frame<Microseconds64>, now: Microseconds64) -> bool {
+        let sent = match &mut self.sent {
+            Some(sent) => sent,
+            None => return false,
+        };
+        let position = sent.iter().position(|sent_frame| {
+            sent_frame.id() == frame.id() && sent_frame.data() == frame.data()
+        });
+        match position {
+            Some(position) => {
+                // Any earlier frames will never be received
+                sent.drain(..position);
+                let mut sent_frame = sent.pop_front().unwrap();
+                if sent_frame.loopback() {
+                    sent_frame.set_timestamp(now);
+                    self.loopback.push_back(sent_frame);
+                }
+                true
+            }
+            None => false,
+        }
     }
 }
 
@@ -56,20 +146,30 @@ impl TransmitDriver<Microseconds64> for LinuxCan {
                 .expect("Invalid frame format");
         self.socket
             .write_frame_insist(&socketcan_frame)
-            .map(|()| None)
             .map_err(|e| {
                 if e.kind() == ErrorKind::WouldBlock {
                     nb::Error::WouldBlock
                 } else {
                     nb::Error::Other(e)
                 }
-            })
+            })?;
+        if let Some(sent) = &mut self.sent {
+            if sent.len() == MAX_SENT_FRAMES {
+                sent.pop_front();
+            }
+            sent.push_back(frame);
+        }
+        Ok(None)
     }
 
     fn flush(&mut self, _now: Microseconds64) -> canadensis_core::nb::Result<(), Self::Error> {
         // Presumably this happens automatically
         Ok(())
     }
+
+    fn pop_loopback(&mut self) -> Option<Frame<Microseconds64>> {
+        self.loopback.pop_front()
+    }
 }
 
 impl ReceiveDriver<Microseconds64> for LinuxCan {
@@ -84,7 +184,9 @@ impl ReceiveDriver<Microseconds64> for LinuxCan {
                     socketcan_frame.id().try_into().expect("Invalid CAN ID"),
                     socketcan_frame.data(),
                 );
-                return Ok(uavcan_frame);
+                if !self.handle_own_frame(&uavcan_frame, now) {
+                    return Ok(uavcan_frame);
+                }
             } else {
                 log::warn!(
                     "Ignoring a frame {} bytes long, which is too large",
@@ -101,15 +203,17 @@ impl ReceiveDriver<Microseconds64> for LinuxCan {
         optimize_filters(local_node, subscriptions, usize::MAX, |optimized| {
             let socketcan_filters = optimized
                 .iter()
-                .map(|filter| socketcan::CANFilter::new(filter.id(), filter.mask()).unwrap())
+                .map(|filter| CANFilter::new(filter.id(), filter.mask()).unwrap())
                 .collect::<Vec<_>>();
-            self.socket.set_filter(&socketcan_filters).unwrap();
+            self.filters = Some((local_node, socketcan_filters));
         })
-        .unwrap()
+        .unwrap();
+        self.set_socket_filters().unwrap();
     }
 
     fn apply_accept_all(&mut self) {
-        self.socket.filter_accept_all().unwrap();
+        self.filters = None;
+        self.set_socket_filters().unwrap();
     }
 }
 
@@ -3,6 +3,10 @@
 //!
 //! This library implements the UAVCAN plug-and-play node ID allocation protocol.
 //!
+//! [`PnpClient`] can use version 1.0 of `uavcan.pnp.NodeIDAllocationData`, which fits into one
+//! Classic CAN frame, or version 2.0, which contains the full unique ID and requires a transport
+//! with a larger MTU (like CAN FD or UDP).
+//!
 
 #![no_std]
 #![deny(missing_docs)]
@@ -15,29 +19,79 @@ extern crate canadensis_filter_config;
 extern crate crc_any;
 extern crate heapless;
 
-use canadensis::anonymous::AnonymousPublisher;
-use canadensis::core::time::{milliseconds, Clock};
+use canadensis::anonymous::{AnonymousPublishError, AnonymousPublisher};
+use canadensis::core::nb;
+use canadensis::core::time::{milliseconds, Clock, Instant};
 use canadensis::core::transport::{Receiver, Transmitter, Transport};
 use canadensis::core::{Priority, SubjectId};
 use canadensis::encoding::{Deserialize, Message, Serialize};
-use canadensis_data_types::uavcan::pnp::node_id_allocation_data_1_0::{self, NodeIDAllocationData};
+use canadensis_data_types::uavcan::node::id_1_0::ID;
+use canadensis_data_types::uavcan::pnp::node_id_allocation_data_1_0::{
+    self, NodeIDAllocationData as NodeIDAllocationDataV1,
+};
+use canadensis_data_types::uavcan::pnp::node_id_allocation_data_2_0::{
+    self, NodeIDAllocationData as NodeIDAllocationDataV2,
+};
+use core::cmp::Ordering;
 use core::convert::TryFrom;
 use core::marker::PhantomData;
 use crc_any::CRCu64;
 
+/// The node ID type of a transmitter's transport
+type NodeIdOf<I, T> = <<T as Transmitter<I>>::Transport as Transport>::NodeId;
+/// The error type of a client's transmitter and receiver
+type ClientError<I, T, R> = PnpClientError<<T as Transmitter<I>>::Error, <R as Receiver<I>>::Error>;
+
 /// A plug-and-play allocation client that can be used to find a node ID
+///
+/// The simplest way to use the client is to call [`poll`](#method.poll) frequently until it
+/// returns a node ID. This follows the allocation procedure in the Cyphal specification:
+///
+/// 1. The client waits for a random interval
+/// 2. The client sends a request and starts waiting for another random interval
+/// 3. If the client receives a response with its unique ID, the procedure is complete. Otherwise,
+///    the client goes back to step 2.
+///
+/// The random intervals can be adjusted with [`set_retry_config`](#method.set_retry_config).
+///
+/// The [`send_request`](#method.send_request) and [`receive`](#method.receive) functions can also
+/// be used to manage requests manually.
 pub struct PnpClient<C: Clock, M, T: Transmitter<C::Instant>, R: Receiver<C::Instant>> {
     /// The unique ID of this node
     unique_id: [u8; 16],
+    /// The node ID to request, if any
+    preferred_node_id: Option<NodeIdOf<C::Instant, T>>,
     /// Publisher used to send messages
     publisher: AnonymousPublisher<C, M, T>,
     /// Transmitter used along with the publisher to send messages
     transmitter: T,
     /// Receiver used to receive messages
     receiver: R,
+    /// Request timing
+    retry_config: RetryConfig,
+    /// The current state of the allocation procedure
+    state: State<C::Instant, NodeIdOf<C::Instant, T>>,
+    /// The maximum length of the current random interval, in milliseconds
+    max_interval_ms: u32,
+    /// The number of requests sent
+    attempts: u32,
+    /// Random number generator state
+    random_state: u64,
     _message: PhantomData<M>,
 }
 
+/// States of the allocation procedure
+enum State<I, N> {
+    /// Waiting for the first call to poll()
+    Starting,
+    /// Waiting for a response, or for the time to send the next request
+    Waiting { deadline: I },
+    /// A node ID has been allocated
+    Allocated(N),
+    /// No response was received after the maximum number of attempts
+    Failed,
+}
+
 impl<C, M, T, R, P> PnpClient<C, M, T, R>
 where
     C: Clock,
@@ -49,16 +103,26 @@ where
     /// Creates a new plug-and-play client
     ///
     /// * `unique_id`: The unique ID of this node
+    ///
+    /// The client uses the default [`RetryConfig`] and does not request any particular node ID.
     pub fn new(
         transmitter: T,
         mut receiver: R,
         unique_id: [u8; 16],
         driver: &mut R::Driver,
     ) -> Result<Self, R::Error> {
-        receiver.subscribe_message(M::SUBJECT, 9, milliseconds(1000), driver)?;
+        let payload_size_max = M::extent();
+        receiver.subscribe_message(M::SUBJECT, payload_size_max, milliseconds(1000), driver)?;
 
+        let retry_config = RetryConfig::default();
+        // Seed the random number generator from the unique ID, which should be different for
+        // every node that starts at the same time
+        let mut crc = CRCu64::crc64we();
+        crc.digest(&unique_id);
+        let random_state = crc.get_crc() | 1;
         Ok(PnpClient {
             unique_id,
+            preferred_node_id: None,
             publisher: AnonymousPublisher::new(
                 M::SUBJECT,
                 Priority::Nominal.into(),
@@ -66,20 +130,146 @@ where
             ),
             transmitter,
             receiver,
+            max_interval_ms: retry_config.initial_interval_ms,
+            retry_config,
+            state: State::Starting,
+            attempts: 0,
+            random_state,
             _message: PhantomData,
         })
     }
 
-    /// Creates an outgoing node ID allocation message and gives it to the transmitter
-    pub fn send_request(&mut self, clock: &mut C, driver: &mut T::Driver) {
-        let message = M::with_unique_id(&self.unique_id);
-        let status = self
-            .publisher
-            .send(&message, clock, &mut self.transmitter, driver);
-        match status {
-            Ok(()) => {}
-            Err(_) => panic!("Can't fit transfer into one frame"),
+    /// Sets the node ID that this client will request
+    ///
+    /// The allocator will allocate this node ID if it is available. If `node_id` is None, the
+    /// client does not request any particular node ID.
+    ///
+    /// Version 1.0 of `uavcan.pnp.NodeIDAllocationData` can't contain a preferred node ID, so
+    /// this has no effect when using that version.
+    pub fn set_preferred_node_id(&mut self, node_id: Option<P::NodeId>) {
+        self.preferred_node_id = node_id;
+    }
+
+    /// Sets the timing of requests
+    ///
+    /// This also resets the number of attempts and the request interval.
+    pub fn set_retry_config(&mut self, config: RetryConfig) {
+        self.max_interval_ms = config.initial_interval_ms;
+        self.attempts = 0;
+        self.retry_config = config;
+    }
+
+    /// Returns the number of requests that this client has sent using [`poll`](#method.poll)
+    pub fn attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    /// Runs the allocation procedure: handles incoming frames, and sends a request if it is
+    /// time to do so
+    ///
+    /// This function should be called frequently. It returns `Ok(Some(node_id))` when a node ID
+    /// has been allocated, and every time it is called after that.
+    ///
+    /// It returns an error if a request could not be sent or a frame could not be received.
+    /// If the retry configuration has a maximum number of attempts and no response was received,
+    /// this function returns [`PnpClientError::NoResponse`].
+    pub fn poll(
+        &mut self,
+        clock: &mut C,
+        driver: &mut T::Driver,
+    ) -> Result<Option<P::NodeId>, ClientError<C::Instant, T, R>>
+    where
+        R: Receiver<C::Instant, Driver = T::Driver>,
+    {
+        let now = clock.now();
+        match &self.state {
+            State::Allocated(node_id) => return Ok(Some(node_id.clone())),
+            State::Failed => return Err(PnpClientError::NoResponse),
+            State::Starting => {
+                let deadline = self.next_deadline(now);
+                self.state = State::Waiting { deadline };
+            }
+            State::Waiting { .. } => {}
+        }
+
+        while let Some(transfer) = self
+            .receiver
+            .receive(now, driver)
+            .map_err(PnpClientError::Receive)?
+        {
+            if transfer.header.source().is_none() {
+                // Another node's request
+                continue;
+            }
+            if let Some(node_id) = self.check_response(&transfer.payload) {
+                self.receiver.unsubscribe_message(M::SUBJECT, driver);
+                self.state = State::Allocated(node_id.clone());
+                return Ok(Some(node_id));
+            }
         }
+
+        if let State::Waiting { deadline } = &self.state {
+            if now.overflow_safe_compare(deadline) != Ordering::Less {
+                if let Some(max_attempts) = self.retry_config.max_attempts {
+                    if self.attempts >= max_attempts {
+                        self.state = State::Failed;
+                        return Err(PnpClientError::NoResponse);
+                    }
+                }
+                match self.send_request(clock, driver) {
+                    Ok(()) => {}
+                    // Try again on the next call
+                    Err(nb::Error::WouldBlock) => return Ok(None),
+                    Err(nb::Error::Other(e)) => return Err(PnpClientError::Transmit(e)),
+                }
+                match self.transmitter.flush(clock, driver) {
+                    Ok(()) | Err(nb::Error::WouldBlock) => {}
+                    Err(nb::Error::Other(e)) => {
+                        return Err(PnpClientError::Transmit(AnonymousPublishError::Transport(
+                            e,
+                        )))
+                    }
+                }
+                self.attempts = self.attempts.saturating_add(1);
+                let deadline = self.next_deadline(now);
+                self.state = State::Waiting { deadline };
+                self.max_interval_ms = self
+                    .max_interval_ms
+                    .saturating_mul(2)
+                    .min(self.retry_config.max_interval_ms);
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns a random time to send the next request
+    fn next_deadline(&mut self, now: C::Instant) -> C::Instant {
+        // Xorshift
+        let mut x = self.random_state;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.random_state = x;
+        let max_interval = u64::from(self.max_interval_ms.max(1));
+        // The interval is in the range (0, max_interval]
+        let interval = 1 + (x % max_interval) as u32;
+        milliseconds::<<C::Instant as Instant>::Duration>(interval) + now
+    }
+
+    /// Creates an outgoing node ID allocation message and gives it to the transmitter
+    ///
+    /// The frame is not sent until the transmitter is flushed.
+    ///
+    /// This function returns an error if the message does not fit into one frame or the
+    /// transmitter returns an error.
+    pub fn send_request(
+        &mut self,
+        clock: &mut C,
+        driver: &mut T::Driver,
+    ) -> nb::Result<(), AnonymousPublishError<T::Error>> {
+        let message = M::with_unique_id(&self.unique_id, self.preferred_node_id.clone());
+        self.publisher
+            .send(&message, clock, &mut self.transmitter, driver)
     }
 
     /// Handles an incoming frame and checks if it provides an ID for this node
@@ -91,17 +281,22 @@ where
         driver: &mut R::Driver,
     ) -> Result<Option<P::NodeId>, R::Error> {
         if let Some(transfer_in) = self.receiver.receive(now, driver)? {
-            if let Ok(message) = M::deserialize_from_bytes(&transfer_in.payload) {
-                if message.matches_unique_id(&self.unique_id) {
-                    if let Some(node_id) = message.node_id() {
-                        return Ok(Some(node_id));
-                    }
-                }
+            // Anonymous messages are requests from other nodes
+            if transfer_in.header.source().is_some() {
+                return Ok(self.check_response(&transfer_in.payload));
             }
         }
         Ok(None)
     }
 
+    /// Checks if a message from an allocator provides an ID for this node
+    fn check_response(&self, payload: &[u8]) -> Option<P::NodeId> {
+        M::deserialize_from_bytes(payload)
+            .ok()
+            .filter(|message| message.matches_unique_id(&self.unique_id))
+            .and_then(|message| message.node_id())
+    }
+
     /// Returns a reference to the transmitter
     pub fn transmitter(&self) -> &T {
         &self.transmitter
@@ -120,18 +315,56 @@ where
     }
 }
 
+/// Timing settings for allocation requests
+///
+/// The client waits for a random interval before sending each request. The first interval is
+/// between 0 and `initial_interval_ms` milliseconds. After each request without a response, the
+/// maximum interval doubles, up to `max_interval_ms`.
+#[derive(Debug, Clone)]
+pub struct RetryConfig {
+    /// The maximum length of the first interval, in milliseconds
+    pub initial_interval_ms: u32,
+    /// The maximum length of any interval, in milliseconds
+    pub max_interval_ms: u32,
+    /// The maximum number of requests to send, or None to keep sending requests until
+    /// a response arrives
+    pub max_attempts: Option<u32>,
+}
+
+impl Default for RetryConfig {
+    /// Returns a configuration with intervals of up to 1 second, as recommended by the
+    /// specification, and no maximum number of attempts
+    fn default() -> Self {
+        RetryConfig {
+            initial_interval_ms: 1000,
+            max_interval_ms: 1000,
+            max_attempts: None,
+        }
+    }
+}
+
+/// Errors that a plug-and-play client can encounter
+#[derive(Debug)]
+pub enum PnpClientError<T, R> {
+    /// A request could not be sent
+    Transmit(AnonymousPublishError<T>),
+    /// An error occurred when receiving frames
+    Receive(R),
+    /// The maximum number of requests have been sent, and no response was received
+    NoResponse,
+}
+
 /// A node ID allocation message
 ///
-/// This is currently implemented for `uavcan.pnp.NodeIdAllocationData` version 1.0. In the future,
-/// it may also be implemented for version 2.0 of that data type.
+/// This is implemented for versions 1.0 and 2.0 of `uavcan.pnp.NodeIdAllocationData`.
 pub trait AllocationMessage<T: Transport>: Message + Serialize + Deserialize {
     /// The fixed subject ID for this message
     const SUBJECT: SubjectId;
 
-    /// Creates a message with the provided unique ID and no allocated node ID
+    /// Creates a request message with the provided unique ID and preferred node ID
     ///
     /// The message must fit into one frame of the transport that is being used.
-    fn with_unique_id(id: &[u8; 16]) -> Self;
+    fn with_unique_id(id: &[u8; 16], preferred_node_id: Option<T::NodeId>) -> Self;
 
     /// Determines if this message matches the provided unique ID
     fn matches_unique_id(&self, id: &[u8; 16]) -> bool;
@@ -140,12 +373,12 @@ pub trait AllocationMessage<T: Transport>: Message + Serialize + Deserialize {
     fn node_id(&self) -> Option<T::NodeId>;
 }
 
-impl<T: Transport> AllocationMessage<T> for NodeIDAllocationData {
+impl<T: Transport> AllocationMessage<T> for NodeIDAllocationDataV1 {
     const SUBJECT: SubjectId = node_id_allocation_data_1_0::SUBJECT;
 
-    fn with_unique_id(id: &[u8; 16]) -> Self {
+    fn with_unique_id(id: &[u8; 16], _preferred_node_id: Option<T::NodeId>) -> Self {
         let id_hash = crc_64we_48_bits(id);
-        NodeIDAllocationData {
+        NodeIDAllocationDataV1 {
             unique_id_hash: id_hash,
             allocated_node_id: heapless::Vec::new(),
         }
@@ -165,9 +398,60 @@ impl<T: Transport> AllocationMessage<T> for NodeIDAllocationData {
     }
 }
 
+impl<T: Transport> AllocationMessage<T> for NodeIDAllocationDataV2 {
+    const SUBJECT: SubjectId = node_id_allocation_data_2_0::SUBJECT;
+
+    fn with_unique_id(id: &[u8; 16], preferred_node_id: Option<T::NodeId>) -> Self {
+        // With no preference, the specification recommends requesting the highest node ID
+        let node_id = match preferred_node_id {
+            Some(node_id) => {
+                let value: usize = node_id.into();
+                value as u16
+            }
+            None => max_node_id::<T::NodeId>(),
+        };
+        NodeIDAllocationDataV2 {
+            node_id: ID { value: node_id },
+            unique_id: *id,
+        }
+    }
+
+    fn matches_unique_id(&self, id: &[u8; 16]) -> bool {
+        self.unique_id == *id
+    }
+
+    fn node_id(&self) -> Option<T::NodeId> {
+        let value = self.node_id.value;
+        T::NodeId::try_from(value).ok()
+    }
+}
+
+/// Returns the highest value that can be converted into a node ID of type `N`
+///
+/// This assumes that all values from zero up to some maximum are valid.
+fn max_node_id<N: TryFrom<u16>>() -> u16 {
+    let mut low = 0u16;
+    let mut high = u16::MAX;
+    if N::try_from(high).is_ok() {
+        return high;
+    }
+    // Invariant: low is valid (or zero), high is not valid
+    while high - low > 1 {
+        let middle = low + (high - low) / 2;
+        if N::try_from(middle).is_ok() {
+            low = middle;
+        } else {
+            high = middle;
+        }
+    }
+    low
+}
+
 /// Calculates a CRC-64WE hash of the provided ID and returns the less significant 48 bits of the
 /// result
-fn crc_64we_48_bits(id: &[u8; 16]) -> u64 {
+///
+/// This is the unique ID hash used in `uavcan.pnp.NodeIDAllocationData` version 1.0.
+pub fn crc_64we_48_bits(id: &[u8; 16]) -> u64 {
     let mut crc = CRCu64::crc64we();
     crc.digest(id);
     let value = crc.get_crc();
@@ -0,0 +1,1018 @@
+//!
+//! A redundant plug-and-play allocator that uses the Raft consensus algorithm
+//!
+//! Several [`ClusterAllocator`]s on different nodes form a cluster that shares one allocation
+//! table. The table is the Raft log, which the allocators replicate using
+//! `uavcan.pnp.cluster.AppendEntries` and `uavcan.pnp.cluster.RequestVote`. The allocators find
+//! each other using `uavcan.pnp.cluster.Discovery` messages, so the only setting that must be the
+//! same on all of them is the cluster size.
+//!
+//! Only the leader of the cluster responds to allocation requests. When a node requests a node ID
+//! that is not in the log, the leader adds a new entry to the log and responds after a majority of
+//! the cluster has stored the entry. The requesting node is expected to send the request again
+//! until it gets a response.
+//!
+//! Because the log contains full unique IDs, the cluster only handles allocation requests that
+//! use version 2.0 of `uavcan.pnp.NodeIDAllocationData`.
+//!
+
+pub mod storage;
+
+use alloc::vec::Vec;
+use canadensis::core::time::{milliseconds, Clock, Instant};
+use canadensis::core::transfer::{MessageTransfer, ServiceTransfer};
+use canadensis::core::transport::{Receiver, Transmitter, Transport};
+use canadensis::core::Priority;
+use canadensis::encoding::{DataType, Deserialize};
+use canadensis::node::NodeError;
+use canadensis::{
+    Node, PublishToken, ResponseToken, ServiceToken, StartSendError, TransferHandler,
+};
+use canadensis_data_types::uavcan::node::heartbeat_1_0::{self, Heartbeat};
+use canadensis_data_types::uavcan::node::id_1_0::ID;
+use canadensis_data_types::uavcan::pnp::cluster::append_entries_1_0::{
+    self, AppendEntriesRequest, AppendEntriesResponse,
+};
+use canadensis_data_types::uavcan::pnp::cluster::discovery_1_0::{self, Discovery};
+use canadensis_data_types::uavcan::pnp::cluster::entry_1_0::Entry;
+use canadensis_data_types::uavcan::pnp::cluster::request_vote_1_0::{
+    self, RequestVoteRequest, RequestVoteResponse,
+};
+use canadensis_data_types::uavcan::pnp::node_id_allocation_data_2_0::{self, NodeIDAllocationData};
+use core::cmp::Ordering;
+use core::convert::TryFrom;
+
+use self::storage::{LogEntry, RaftStorage};
+use crate::node_id_to_message;
+
+/// The maximum number of other allocators in a cluster
+const MAX_PEERS: usize = Discovery::MAX_CLUSTER_SIZE as usize - 1;
+
+/// An error that can occur when creating a cluster allocator on a node of type `N`
+pub type CreateError<N> = NodeError<
+    StartSendError<<<N as Node>::Transmitter as Transmitter<<N as Node>::Instant>>::Error>,
+    StartSendError<<<N as Node>::Receiver as Receiver<<N as Node>::Instant>>::Error>,
+>;
+
+/// Settings for a cluster allocator
+#[derive(Debug, Clone)]
+pub struct ClusterConfig<N> {
+    /// The number of allocators in the cluster, from 1 to 5
+    ///
+    /// This must be the same on all allocators.
+    pub cluster_size: u8,
+    /// The highest node ID that can be allocated
+    pub max_node_id: N,
+    /// The minimum time without contact from a leader before an allocator starts an election,
+    /// in milliseconds
+    pub min_election_timeout_ms: u32,
+    /// The maximum time without contact from a leader before an allocator starts an election,
+    /// in milliseconds
+    pub max_election_timeout_ms: u32,
+    /// A value used to randomize election timeouts
+    ///
+    /// This is combined with the node ID, so allocators in the same cluster will have different
+    /// timeouts even if they use the same seed.
+    pub random_seed: u32,
+}
+
+impl<N> ClusterConfig<N> {
+    /// Creates a configuration with the default election timeouts from the specification
+    pub fn new(cluster_size: u8, max_node_id: N) -> Self {
+        ClusterConfig {
+            cluster_size,
+            max_node_id,
+            min_election_timeout_ms: u32::from(AppendEntriesRequest::DEFAULT_MIN_ELECTION_TIMEOUT)
+                * 1000,
+            max_election_timeout_ms: u32::from(AppendEntriesRequest::DEFAULT_MAX_ELECTION_TIMEOUT)
+                * 1000,
+            random_seed: 0,
+        }
+    }
+}
+
+/// The role of an allocator in the cluster
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Role {
+    /// Accepts log entries from the leader
+    Follower,
+    /// Requesting votes to become the leader
+    Candidate,
+    /// Responds to allocation requests and sends log entries to the followers
+    Leader,
+}
+
+/// Information about another allocator in the cluster
+struct Peer<N, X> {
+    node_id: N,
+    /// The index of the next log entry to send to this allocator (used by the leader)
+    next_index: u16,
+    /// The highest log index known to be stored on this allocator (used by the leader)
+    match_index: u16,
+    /// The AppendEntries request that is waiting for a response from this allocator
+    pending: Option<PendingAppend<X>>,
+    /// True if this allocator voted for this allocator in the current election
+    vote_granted: bool,
+}
+
+/// An AppendEntries request that has been sent
+struct PendingAppend<X> {
+    /// The transfer ID of the request, which the response must also have
+    transfer_id: X,
+    term: u32,
+    prev_log_index: u16,
+    entry_count: u16,
+}
+
+/// A plug-and-play node ID allocator that is part of a redundant cluster
+///
+/// The allocator must be used as a transfer handler for incoming transfers, and
+/// [`run_tasks`](#method.run_tasks) must be called frequently (at least every few milliseconds)
+/// to send discovery messages, start elections, and replicate the log.
+///
+/// Like [`PnpServer`](crate::PnpServer), the leader adds node IDs seen in heartbeats to the
+/// log (with a unique ID of zero) so that they will not be allocated to other nodes.
+pub struct ClusterAllocator<I, T, S>
+where
+    I: Instant,
+    T: Transport,
+{
+    config: ClusterConfig<T::NodeId>,
+    storage: S,
+    /// The other allocators that have been discovered
+    peers: heapless::Vec<Peer<T::NodeId, T::TransferId>, MAX_PEERS>,
+    role: Role,
+    /// The current leader, if known
+    leader: Option<T::NodeId>,
+    /// The highest log index known to be committed
+    commit_index: u16,
+    /// The time when this allocator will start an election if it does not hear from a leader
+    election_deadline: I,
+    /// The time when this allocator will next publish a discovery message
+    next_discovery: I,
+    /// The time when the leader will next send an AppendEntries request
+    next_append: I,
+    /// The index in `peers` of the allocator that the leader will send the next request to
+    next_peer: usize,
+    /// Random number generator state
+    random_state: u32,
+    discovery_token: PublishToken<Discovery>,
+    allocation_token: PublishToken<NodeIDAllocationData>,
+    append_entries_token: ServiceToken<AppendEntriesRequest>,
+    request_vote_token: ServiceToken<RequestVoteRequest>,
+}
+
+impl<I, T, S> ClusterAllocator<I, T, S>
+where
+    I: Instant,
+    T: Transport,
+    T::TransferId: PartialEq,
+    S: RaftStorage<T::NodeId>,
+{
+    /// Creates a cluster allocator
+    ///
+    /// This function subscribes to cluster messages and requests, allocation requests, and
+    /// heartbeats, and starts publishing cluster messages and allocation responses.
+    ///
+    /// * `node`: The node that the allocator runs on
+    /// * `storage`: The persistent Raft log and state, which may contain entries from a
+    ///   previous run
+    /// * `config`: The cluster settings
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the cluster size is not between 1 and 5, or if the minimum election
+    /// timeout is not less than the maximum election timeout.
+    pub fn new<N>(
+        node: &mut N,
+        storage: S,
+        config: ClusterConfig<T::NodeId>,
+    ) -> Result<Self, CreateError<N>>
+    where
+        N: Node<Instant = I, Transport = T>,
+    {
+        assert!(
+            config.cluster_size >= 1 && config.cluster_size <= Discovery::MAX_CLUSTER_SIZE,
+            "Invalid cluster size"
+        );
+        assert!(
+            config.min_election_timeout_ms < config.max_election_timeout_ms,
+            "Invalid election timeouts"
+        );
+        node.subscribe::<Discovery>(discovery_1_0::SUBJECT, milliseconds(1000))
+            .map_err(|e| NodeError::Receiver(StartSendError::Transport(e)))?;
+        node.subscribe::<NodeIDAllocationData>(
+            node_id_allocation_data_2_0::SUBJECT,
+            milliseconds(1000),
+        )
+        .map_err(|e| NodeError::Receiver(StartSendError::Transport(e)))?;
+        node.subscribe::<Heartbeat>(heartbeat_1_0::SUBJECT, milliseconds(1000))
+            .map_err(|e| NodeError::Receiver(StartSendError::Transport(e)))?;
+        node.subscribe_request(
+            append_entries_1_0::SERVICE,
+            AppendEntriesRequest::extent(),
+            milliseconds(1000),
+        )
+        .map_err(|e| NodeError::Receiver(StartSendError::Transport(e)))?;
+        node.subscribe_request(
+            request_vote_1_0::SERVICE,
+            RequestVoteRequest::extent(),
+            milliseconds(1000),
+        )
+        .map_err(|e| NodeError::Receiver(StartSendError::Transport(e)))?;
+        let append_entries_token = node
+            .start_sending_requests(
+                append_entries_1_0::SERVICE,
+                milliseconds(1000),
+                AppendEntriesResponse::extent(),
+                Priority::Slow.into(),
+            )
+            .map_err(NodeError::Receiver)?;
+        let request_vote_token = node
+            .start_sending_requests(
+                request_vote_1_0::SERVICE,
+                milliseconds(1000),
+                RequestVoteResponse::extent(),
+                Priority::Slow.into(),
+            )
+            .map_err(NodeError::Receiver)?;
+        let discovery_token = node
+            .start_publishing(
+                discovery_1_0::SUBJECT,
+                milliseconds(1000),
+                Priority::Slow.into(),
+            )
+            .map_err(NodeError::Transmitter)?;
+        let allocation_token = node
+            .start_publishing(
+                node_id_allocation_data_2_0::SUBJECT,
+                milliseconds(1000),
+                Priority::Nominal.into(),
+            )
+            .map_err(NodeError::Transmitter)?;
+
+        let own_id: usize = node.node_id().into();
+        let mut random_state = config.random_seed ^ (own_id as u32).wrapping_mul(0x9e37_79b9);
+        if random_state == 0 {
+            random_state = 0x2545_f491;
+        }
+        let now = node.clock_mut().now();
+        let mut allocator = ClusterAllocator {
+            config,
+            storage,
+            peers: heapless::Vec::new(),
+            role: Role::Follower,
+            leader: None,
+            commit_index: 0,
+            election_deadline: now,
+            next_discovery: now,
+            next_append: now,
+            next_peer: 0,
+            random_state,
+            discovery_token,
+            allocation_token,
+            append_entries_token,
+            request_vote_token,
+        };
+        allocator.reset_election_deadline(now);
+        Ok(allocator)
+    }
+
+    /// Returns a reference to the Raft storage
+    pub fn storage(&self) -> &S {
+        &self.storage
+    }
+
+    /// Returns the current role of this allocator
+    pub fn role(&self) -> Role {
+        self.role
+    }
+
+    /// Returns the node ID of the current leader, if known
+    pub fn leader(&self) -> Option<&T::NodeId> {
+        self.leader.as_ref()
+    }
+
+    /// Returns the current term
+    pub fn current_term(&self) -> u32 {
+        self.storage.current_term()
+    }
+
+    /// Returns the highest log index that is known to be committed
+    pub fn commit_index(&self) -> u16 {
+        self.commit_index
+    }
+
+    /// Returns true if this allocator has discovered all other allocators in the cluster
+    pub fn discovery_complete(&self) -> bool {
+        self.peers.len() + 1 >= usize::from(self.config.cluster_size)
+    }
+
+    /// Returns an iterator over the node IDs of the other allocators that have been discovered
+    pub fn peers(&self) -> impl Iterator<Item = &T::NodeId> {
+        self.peers.iter().map(|peer| &peer.node_id)
+    }
+
+    /// Publishes discovery messages, starts elections, and sends log entries to followers
+    /// when needed
+    ///
+    /// This function should be called frequently. Errors that occur when sending transfers
+    /// are logged.
+    pub fn run_tasks<N>(&mut self, node: &mut N)
+    where
+        N: Node<Instant = I, Transport = T>,
+    {
+        let now = node.clock_mut().now();
+        if !self.discovery_complete() && is_due(now, self.next_discovery) {
+            self.next_discovery =
+                milliseconds::<I::Duration>(u32::from(Discovery::BROADCASTING_PERIOD) * 1000) + now;
+            self.publish_discovery(node);
+        }
+        match self.role {
+            Role::Leader => {
+                if is_due(now, self.next_append) {
+                    self.next_append = self.append_interval() + now;
+                    self.send_next_append_entries(node);
+                }
+            }
+            Role::Follower | Role::Candidate => {
+                // An election can only succeed if a majority of the cluster is known
+                if self.peers.len() + 1 >= self.quorum() && is_due(now, self.election_deadline) {
+                    self.start_election(node, now);
+                }
+            }
+        }
+    }
+
+    /// Returns the number of allocators that make up a majority of the cluster
+    fn quorum(&self) -> usize {
+        usize::from(self.config.cluster_size) / 2 + 1
+    }
+
+    /// Returns the time between AppendEntries requests from the leader
+    ///
+    /// The leader sends requests to one follower at a time, so that each follower receives
+    /// two requests per minimum election timeout.
+    fn append_interval(&self) -> I::Duration {
+        let followers = u32::from(self.config.cluster_size).saturating_sub(1).max(1);
+        milliseconds(self.config.min_election_timeout_ms / 2 / followers)
+    }
+
+    fn reset_election_deadline(&mut self, now: I) {
+        // Xorshift
+        let mut x = self.random_state;
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        self.random_state = x;
+        // The timeout is in the range (min, max]
+        let range = self.config.max_election_timeout_ms - self.config.min_election_timeout_ms;
+        let timeout = self.config.min_election_timeout_ms + 1 + x % range;
+        self.election_deadline = milliseconds::<I::Duration>(timeout) + now;
+    }
+
+    fn term_at(&self, index: u16) -> Option<u32> {
+        if index == 0 {
+            Some(0)
+        } else {
+            self.storage.entry(index).map(|entry| entry.term)
+        }
+    }
+
+    fn last_log_term(&self) -> u32 {
+        self.term_at(self.storage.last_index()).unwrap_or(0)
+    }
+
+    /// Saves a new term and vote
+    fn set_term_and_vote(&mut self, term: u32, voted_for: Option<T::NodeId>) -> bool {
+        match self.storage.set_term_and_vote(term, voted_for) {
+            Ok(()) => true,
+            Err(e) => {
+                log::warn!("Failed to save Raft term and vote: {:?}", e);
+                false
+            }
+        }
+    }
+
+    /// Switches to a newer term as a follower
+    fn step_down(&mut self, term: u32, now: I) {
+        log::debug!("Following in term {}", term);
+        self.set_term_and_vote(term, None);
+        if self.role != Role::Follower {
+            self.role = Role::Follower;
+            self.reset_election_deadline(now);
+        }
+        self.leader = None;
+    }
+
+    /// Adds an allocator to the list of known allocators if it is not already there
+    fn add_peer(&mut self, own_id: &T::NodeId, node_id: &T::NodeId) {
+        if node_id == own_id || self.peers.iter().any(|peer| peer.node_id == *node_id) {
+            return;
+        }
+        if self.discovery_complete() {
+            log::warn!(
+                "Found allocator {:?}, but the cluster is already full",
+                node_id
+            );
+            return;
+        }
+        log::info!("Discovered allocator {:?}", node_id);
+        let next_index = self.storage.last_index() + 1;
+        // discovery_complete() returned false, so there is space
+        let _ = self.peers.push(Peer {
+            node_id: node_id.clone(),
+            next_index,
+            match_index: 0,
+            pending: None,
+            vote_granted: false,
+        });
+    }
+
+    fn publish_discovery<N>(&mut self, node: &mut N)
+    where
+        N: Node<Instant = I, Transport = T>,
+    {
+        let mut known_nodes = heapless::Vec::new();
+        let _ = known_nodes.push(node_id_to_message(node.node_id()));
+        for peer in self.peers.iter() {
+            let _ = known_nodes.push(node_id_to_message(peer.node_id.clone()));
+        }
+        let message = Discovery {
+            configured_cluster_size: self.config.cluster_size,
+            known_nodes,
+        };
+        if let Err(e) = node.publish(&self.discovery_token, &message) {
+            log::warn!("Failed to send discovery message: {:?}", e);
+        }
+    }
+
+    fn handle_discovery<N>(&mut self, node: &mut N, source: &T::NodeId, payload: &[u8])
+    where
+        N: Node<Instant = I, Transport = T>,
+    {
+        let message = match Discovery::deserialize_from_bytes(payload) {
+            Ok(message) => message,
+            Err(_) => return,
+        };
+        if message.configured_cluster_size != self.config.cluster_size {
+            log::warn!(
+                "Allocator {:?} has cluster size {}, expected {}",
+                source,
+                message.configured_cluster_size,
+                self.config.cluster_size
+            );
+            return;
+        }
+        let own_id = node.node_id();
+        self.add_peer(&own_id, source);
+        for known in message.known_nodes.iter() {
+            if let Some(known) = node_id_from_message(known) {
+                self.add_peer(&own_id, &known);
+            }
+        }
+        // Help an allocator that has restarted. If discovery is not complete, this allocator is
+        // already publishing periodically.
+        if message.known_nodes.len() < usize::from(self.config.cluster_size)
+            && self.discovery_complete()
+        {
+            self.publish_discovery(node);
+        }
+    }
+
+    fn start_election<N>(&mut self, node: &mut N, now: I)
+    where
+        N: Node<Instant = I, Transport = T>,
+    {
+        let own_id = node.node_id();
+        let term = self.storage.current_term().wrapping_add(1);
+        if !self.set_term_and_vote(term, Some(own_id.clone())) {
+            self.reset_election_deadline(now);
+            return;
+        }
+        log::info!("Starting election for term {}", term);
+        self.role = Role::Candidate;
+        self.leader = None;
+        self.reset_election_deadline(now);
+        for peer in self.peers.iter_mut() {
+            peer.vote_granted = false;
+        }
+        if self.quorum() == 1 {
+            self.become_leader(node, now);
+            return;
+        }
+        let request = RequestVoteRequest {
+            term,
+            last_log_term: self.last_log_term(),
+            last_log_index: self.storage.last_index(),
+        };
+        for peer in self.peers.iter() {
+            if let Err(e) =
+                node.send_request(&self.request_vote_token, &request, peer.node_id.clone())
+            {
+                log::warn!("Failed to send vote request: {:?}", e);
+            }
+        }
+    }
+
+    fn become_leader<N>(&mut self, node: &mut N, now: I)
+    where
+        N: Node<Instant = I, Transport = T>,
+    {
+        let own_id = node.node_id();
+        log::info!("Became leader in term {}", self.storage.current_term());
+        self.role = Role::Leader;
+        self.leader = Some(own_id.clone());
+        let next_index = self.storage.last_index() + 1;
+        for peer in self.peers.iter_mut() {
+            peer.next_index = next_index;
+            peer.match_index = 0;
+            peer.pending = None;
+        }
+        self.next_append = now;
+        // Make sure that the log contains at least one entry, for this allocator. A leader only
+        // commits entries from its own term, so entries from earlier terms that may not have
+        // been committed (for example, after a restart, when the commit index starts at 0) also
+        // need a new entry from this term before they can be committed.
+        if self.find_node_id(&own_id).is_none() || self.commit_index < self.storage.last_index() {
+            self.append_entry([0; 16], own_id);
+        }
+    }
+
+    /// Returns the index of the log entry for a node ID, if one exists
+    fn find_node_id(&self, node_id: &T::NodeId) -> Option<u16> {
+        (1..=self.storage.last_index()).find(|&index| {
+            self.storage
+                .entry(index)
+                .map(|entry| entry.node_id == *node_id)
+                .unwrap_or(false)
+        })
+    }
+
+    /// Returns the log entry for a unique ID, if one exists
+    fn find_unique_id(&self, unique_id: &[u8; 16]) -> Option<(u16, LogEntry<T::NodeId>)> {
+        (1..=self.storage.last_index()).find_map(|index| {
+            self.storage
+                .entry(index)
+                .filter(|entry| entry.unique_id == *unique_id)
+                .map(|entry| (index, entry))
+        })
+    }
+
+    /// Adds an entry to the log as the leader
+    ///
+    /// This function returns the index of the new entry.
+    fn append_entry(&mut self, unique_id: [u8; 16], node_id: T::NodeId) -> Option<u16> {
+        let entry = LogEntry {
+            term: self.storage.current_term(),
+            unique_id,
+            node_id,
+        };
+        match self.storage.append(entry) {
+            Ok(()) => {
+                self.advance_commit_index();
+                Some(self.storage.last_index())
+            }
+            Err(e) => {
+                log::warn!("Failed to add entry to Raft log: {:?}", e);
+                None
+            }
+        }
+    }
+
+    /// Updates the commit index based on the log entries that a majority of the cluster has
+    /// stored (used by the leader)
+    fn advance_commit_index(&mut self) {
+        let current_term = self.storage.current_term();
+        let quorum = self.quorum();
+        let mut index = self.storage.last_index();
+        while index > self.commit_index {
+            // Entries from earlier terms are committed indirectly
+            if self.term_at(index) == Some(current_term) {
+                let replicas = 1 + self
+                    .peers
+                    .iter()
+                    .filter(|peer| peer.match_index >= index)
+                    .count();
+                if replicas >= quorum {
+                    log::debug!("Committed log index {}", index);
+                    self.commit_index = index;
+                    return;
+                }
+            }
+            index -= 1;
+        }
+    }
+
+    fn send_next_append_entries<N>(&mut self, node: &mut N)
+    where
+        N: Node<Instant = I, Transport = T>,
+    {
+        if self.peers.is_empty() {
+            return;
+        }
+        self.next_peer %= self.peers.len();
+        let last_index = self.storage.last_index();
+        let term = self.storage.current_term();
+        let leader_commit = self.commit_index;
+        let next_index = self.peers[self.next_peer].next_index;
+        let prev_log_index = next_index - 1;
+        let prev_log_term = self.term_at(prev_log_index).unwrap_or(0);
+        let mut entries = heapless::Vec::new();
+        if next_index <= last_index {
+            if let Some(entry) = self.storage.entry(next_index) {
+                let _ = entries.push(Entry {
+                    term: entry.term,
+                    unique_id: entry.unique_id,
+                    node_id: node_id_to_message(entry.node_id),
+                });
+            }
+        }
+        let entry_count = entries.len() as u16;
+        let request = AppendEntriesRequest {
+            term,
+            prev_log_term,
+            prev_log_index,
+            leader_commit,
+            entries,
+        };
+        let peer = &mut self.peers[self.next_peer];
+        self.next_peer += 1;
+        match node.send_request(&self.append_entries_token, &request, peer.node_id.clone()) {
+            Ok(transfer_id) => {
+                peer.pending = Some(PendingAppend {
+                    transfer_id,
+                    term,
+                    prev_log_index,
+                    entry_count,
+                })
+            }
+            Err(e) => log::warn!("Failed to send AppendEntries request: {:?}", e),
+        }
+    }
+
+    fn handle_append_entries_request(
+        &mut self,
+        request: &AppendEntriesRequest,
+        source: &T::NodeId,
+        now: I,
+    ) -> AppendEntriesResponse {
+        let current_term = self.storage.current_term();
+        if request.term < current_term {
+            return AppendEntriesResponse {
+                term: current_term,
+                success: false,
+            };
+        }
+        if request.term > current_term {
+            self.step_down(request.term, now);
+        } else if self.role != Role::Follower {
+            // Another allocator won the election in this term
+            self.role = Role::Follower;
+        }
+        self.leader = Some(source.clone());
+        self.reset_election_deadline(now);
+        let term = self.storage.current_term();
+
+        if self.term_at(request.prev_log_index) != Some(request.prev_log_term) {
+            return AppendEntriesResponse {
+                term,
+                success: false,
+            };
+        }
+        let mut index = request.prev_log_index;
+        for entry in request.entries.iter() {
+            index += 1;
+            let node_id = match node_id_from_message(&entry.node_id) {
+                Some(node_id) => node_id,
+                None => {
+                    return AppendEntriesResponse {
+                        term,
+                        success: false,
+                    }
+                }
+            };
+            match self.term_at(index) {
+                Some(existing_term) if existing_term == entry.term => continue,
+                Some(_) => {
+                    // Conflicting entry: remove it and everything after it
+                    if let Err(e) = self.storage.truncate(index - 1) {
+                        log::warn!("Failed to truncate Raft log: {:?}", e);
+                        return AppendEntriesResponse {
+                            term,
+                            success: false,
+                        };
+                    }
+                }
+                None => {}
+            }
+            let new_entry = LogEntry {
+                term: entry.term,
+                unique_id: entry.unique_id,
+                node_id,
+            };
+            if let Err(e) = self.storage.append(new_entry) {
+                log::warn!("Failed to add entry to Raft log: {:?}", e);
+                return AppendEntriesResponse {
+                    term,
+                    success: false,
+                };
+            }
+        }
+        if request.leader_commit > self.commit_index {
+            self.commit_index = request.leader_commit.min(index);
+        }
+        AppendEntriesResponse {
+            term,
+            success: true,
+        }
+    }
+
+    fn handle_append_entries_response(
+        &mut self,
+        response: &AppendEntriesResponse,
+        source: &T::NodeId,
+        transfer_id: &T::TransferId,
+        now: I,
+    ) {
+        if response.term > self.storage.current_term() {
+            self.step_down(response.term, now);
+            return;
+        }
+        if self.role != Role::Leader {
+            return;
+        }
+        let current_term = self.storage.current_term();
+        let peer = match self.peers.iter_mut().find(|peer| peer.node_id == *source) {
+            Some(peer) => peer,
+            None => return,
+        };
+        // Only a response to the most recent request says which entries the follower has.
+        // A response to an earlier request (for example, one that arrives after a retry)
+        // is ignored.
+        match &peer.pending {
+            Some(pending) if pending.transfer_id == *transfer_id => {}
+            _ => return,
+        }
+        let pending = match peer.pending.take() {
+            Some(pending) if pending.term == current_term => pending,
+            _ => return,
+        };
+        if response.success {
+            peer.match_index = pending.prev_log_index + pending.entry_count;
+            peer.next_index = peer.match_index + 1;
+            self.advance_commit_index();
+        } else {
+            // The follower's log does not match, so try an earlier entry next time
+            peer.next_index = pending.prev_log_index.max(1);
+        }
+    }
+
+    fn handle_request_vote_request(
+        &mut self,
+        request: &RequestVoteRequest,
+        source: &T::NodeId,
+        now: I,
+    ) -> RequestVoteResponse {
+        if request.term > self.storage.current_term() {
+            self.step_down(request.term, now);
+        }
+        let term = self.storage.current_term();
+        let can_vote = match self.storage.voted_for() {
+            Some(voted_for) => voted_for == *source,
+            None => true,
+        };
+        let last_log_term = self.last_log_term();
+        let log_up_to_date = request.last_log_term > last_log_term
+            || (request.last_log_term == last_log_term
+                && request.last_log_index >= self.storage.last_index());
+        let vote_granted = request.term == term
+            && can_vote
+            && log_up_to_date
+            && self.set_term_and_vote(term, Some(source.clone()));
+        if vote_granted {
+            log::debug!("Voted for {:?} in term {}", source, term);
+            self.reset_election_deadline(now);
+        }
+        RequestVoteResponse { term, vote_granted }
+    }
+
+    fn handle_request_vote_response<N>(
+        &mut self,
+        node: &mut N,
+        response: &RequestVoteResponse,
+        source: &T::NodeId,
+        now: I,
+    ) where
+        N: Node<Instant = I, Transport = T>,
+    {
+        if response.term > self.storage.current_term() {
+            self.step_down(response.term, now);
+            return;
+        }
+        if self.role != Role::Candidate
+            || response.term != self.storage.current_term()
+            || !response.vote_granted
+        {
+            return;
+        }
+        if let Some(peer) = self.peers.iter_mut().find(|peer| peer.node_id == *source) {
+            peer.vote_granted = true;
+        }
+        let votes = 1 + self.peers.iter().filter(|peer| peer.vote_granted).count();
+        if votes >= self.quorum() {
+            self.become_leader(node, now);
+        }
+    }
+
+    /// Returns true if this allocator is the leader and all its log entries are committed
+    fn can_allocate(&self) -> bool {
+        self.role == Role::Leader && self.commit_index == self.storage.last_index()
+    }
+
+    fn handle_allocation_request<N>(&mut self, node: &mut N, payload: &[u8])
+    where
+        N: Node<Instant = I, Transport = T>,
+    {
+        let request = match NodeIDAllocationData::deserialize_from_bytes(payload) {
+            Ok(request) => request,
+            Err(_) => return,
+        };
+        let unique_id = request.unique_id;
+        let allocated = match self.find_unique_id(&unique_id) {
+            Some((index, entry)) => Some((index, entry.node_id)),
+            None => {
+                if !self.can_allocate() {
+                    return;
+                }
+                self.choose_node_id(&node.node_id(), request.node_id.value)
+                    .and_then(|node_id| {
+                        self.append_entry(unique_id, node_id.clone())
+                            .map(|index| (index, node_id))
+                    })
+            }
+        };
+        // Respond only after the entry has been committed. If it has not been committed yet,
+        // the requesting node will try again later.
+        if let Some((index, node_id)) = allocated {
+            if self.role == Role::Leader && index <= self.commit_index {
+                let response = NodeIDAllocationData {
+                    node_id: node_id_to_message(node_id),
+                    unique_id,
+                };
+                if let Err(e) = node.publish(&self.allocation_token, &response) {
+                    log::warn!("Failed to send allocation response: {:?}", e);
+                }
+            }
+        }
+    }
+
+    /// Chooses a node ID that is not in the log, starting at the preferred node ID and searching
+    /// upwards and then downwards
+    fn choose_node_id(&self, own_node_id: &T::NodeId, preferred: u16) -> Option<T::NodeId> {
+        let max: usize = self.config.max_node_id.clone().into();
+        let preferred = usize::from(preferred);
+        let preferred = if preferred <= max { preferred } else { max };
+        for candidate in (preferred..=max).chain((0..preferred).rev()) {
+            let node_id = match u16::try_from(candidate)
+                .ok()
+                .and_then(|candidate| T::NodeId::try_from(candidate).ok())
+            {
+                Some(node_id) => node_id,
+                None => continue,
+            };
+            if node_id == *own_node_id
+                || self.peers.iter().any(|peer| peer.node_id == node_id)
+                || self.find_node_id(&node_id).is_some()
+            {
+                continue;
+            }
+            return Some(node_id);
+        }
+        log::warn!("No node IDs available for allocation");
+        None
+    }
+
+    fn handle_heartbeat(&mut self, source: &T::NodeId) {
+        // Only the leader can add entries, and only one entry at a time
+        if self.can_allocate() && self.find_node_id(source).is_none() {
+            log::debug!("Adding node {:?} from heartbeat", source);
+            self.append_entry([0; 16], source.clone());
+        }
+    }
+}
+
+impl<I, T, S> TransferHandler<I, T> for ClusterAllocator<I, T, S>
+where
+    I: Instant,
+    T: Transport,
+    T::TransferId: PartialEq,
+    S: RaftStorage<T::NodeId>,
+{
+    fn handle_message<N: Node<Instant = I, Transport = T>>(
+        &mut self,
+        node: &mut N,
+        transfer: &MessageTransfer<Vec<u8>, I, T>,
+    ) -> bool {
+        match transfer.header.subject {
+            heartbeat_1_0::SUBJECT => {
+                if let Some(source) = &transfer.header.source {
+                    self.handle_heartbeat(source);
+                }
+                // Other handlers may also be interested in heartbeats
+                false
+            }
+            discovery_1_0::SUBJECT => {
+                if let Some(source) = &transfer.header.source {
+                    self.handle_discovery(node, source, &transfer.payload);
+                }
+                true
+            }
+            node_id_allocation_data_2_0::SUBJECT => {
+                // Non-anonymous messages are responses from allocators
+                if transfer.header.source.is_none() {
+                    self.handle_allocation_request(node, &transfer.payload);
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn handle_request<N: Node<Instant = I, Transport = T>>(
+        &mut self,
+        node: &mut N,
+        token: ResponseToken<T>,
+        transfer: &ServiceTransfer<Vec<u8>, I, T>,
+    ) -> bool {
+        let now = node.clock_mut().now();
+        let own_id = node.node_id();
+        let source = &transfer.header.source;
+        match transfer.header.service {
+            append_entries_1_0::SERVICE => {
+                if let Ok(request) = AppendEntriesRequest::deserialize_from_bytes(&transfer.payload)
+                {
+                    self.add_peer(&own_id, source);
+                    let response = self.handle_append_entries_request(&request, source, now);
+                    if let Err(e) = node.send_response(token, milliseconds(1000), &response) {
+                        log::warn!("Failed to send AppendEntries response: {:?}", e);
+                    }
+                }
+                true
+            }
+            request_vote_1_0::SERVICE => {
+                if let Ok(request) = RequestVoteRequest::deserialize_from_bytes(&transfer.payload) {
+                    self.add_peer(&own_id, source);
+                    let response = self.handle_request_vote_request(&request, source, now);
+                    if let Err(e) = node.send_response(token, milliseconds(1000), &response) {
+                        log::warn!("Failed to send RequestVote response: {:?}", e);
+                    }
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn handle_response<N: Node<Instant = I, Transport = T>>(
+        &mut self,
+        node: &mut N,
+        transfer: &ServiceTransfer<Vec<u8>, I, T>,
+    ) -> bool {
+        let now = node.clock_mut().now();
+        let source = &transfer.header.source;
+        match transfer.header.service {
+            append_entries_1_0::SERVICE => {
+                if let Ok(response) =
+                    AppendEntriesResponse::deserialize_from_bytes(&transfer.payload)
+                {
+                    self.handle_append_entries_response(
+                        &response,
+                        source,
+                        &transfer.header.transfer_id,
+                        now,
+                    );
+                }
+                true
+            }
+            request_vote_1_0::SERVICE => {
+                if let Ok(response) = RequestVoteResponse::deserialize_from_bytes(&transfer.payload)
+                {
+                    self.handle_request_vote_response(node, &response, source, now);
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Returns true if `now` is at or after `deadline`
+fn is_due<I: Instant>(now: I, deadline: I) -> bool {
+    now.overflow_safe_compare(&deadline) != Ordering::Less
+}
+
+fn node_id_from_message<N: TryFrom<u16>>(id: &ID) -> Option<N> {
+    let value = id.value;
+    N::try_from(value).ok()
+}
@@ -0,0 +1,125 @@
+//!
+//! Persistent storage for the Raft log and state
+//!
+
+use core::fmt::Debug;
+
+/// An entry in the Raft log, which records one node ID allocation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogEntry<N> {
+    /// The term when the leader created this entry
+    pub term: u32,
+    /// The unique ID of the node that received the allocation, or zero if it is not known
+    pub unique_id: [u8; 16],
+    /// The allocated node ID
+    pub node_id: N,
+}
+
+/// Storage for the Raft log and the other state that must be persistent
+///
+/// Log indices start at 1. Index 0 is the position before the first entry, and its term is 0.
+///
+/// For the cluster to behave correctly, all changes must be saved in persistent storage
+/// before the functions that make them return.
+pub trait RaftStorage<N> {
+    /// The error that may occur when saving changes
+    type Error: Debug;
+
+    /// Returns the latest term that this allocator has seen (initially 0)
+    fn current_term(&self) -> u32;
+
+    /// Returns the allocator that this allocator voted for in the current term, if any
+    fn voted_for(&self) -> Option<N>;
+
+    /// Sets the current term and the allocator that this allocator voted for in that term
+    fn set_term_and_vote(&mut self, term: u32, voted_for: Option<N>) -> Result<(), Self::Error>;
+
+    /// Returns the index of the last entry in the log, or 0 if the log is empty
+    fn last_index(&self) -> u16;
+
+    /// Returns the entry at the provided index, or None if no entry exists at that index
+    fn entry(&self, index: u16) -> Option<LogEntry<N>>;
+
+    /// Adds an entry to the end of the log
+    fn append(&mut self, entry: LogEntry<N>) -> Result<(), Self::Error>;
+
+    /// Removes all entries with indices greater than `last_index`
+    fn truncate(&mut self, last_index: u16) -> Result<(), Self::Error>;
+}
+
+/// Raft storage in memory, with space for up to `C` log entries
+///
+/// This storage does not require dynamic memory allocation, but its contents are lost when
+/// the allocator restarts. It is intended for testing.
+pub struct MemoryStorage<N, const C: usize> {
+    current_term: u32,
+    voted_for: Option<N>,
+    entries: heapless::Vec<LogEntry<N>, C>,
+}
+
+impl<N, const C: usize> MemoryStorage<N, C> {
+    /// Creates an empty storage
+    pub fn new() -> Self {
+        MemoryStorage {
+            current_term: 0,
+            voted_for: None,
+            entries: heapless::Vec::new(),
+        }
+    }
+
+    /// Returns the entries in the log
+    pub fn entries(&self) -> &[LogEntry<N>] {
+        &self.entries
+    }
+}
+
+impl<N, const C: usize> Default for MemoryStorage<N, C> {
+    fn default() -> Self {
+        MemoryStorage::new()
+    }
+}
+
+impl<N, const C: usize> RaftStorage<N> for MemoryStorage<N, C>
+where
+    N: Clone,
+{
+    type Error = LogFullError;
+
+    fn current_term(&self) -> u32 {
+        self.current_term
+    }
+
+    fn voted_for(&self) -> Option<N> {
+        self.voted_for.clone()
+    }
+
+    fn set_term_and_vote(&mut self, term: u32, voted_for: Option<N>) -> Result<(), Self::Error> {
+        self.current_term = term;
+        self.voted_for = voted_for;
+        Ok(())
+    }
+
+    fn last_index(&self) -> u16 {
+        self.entries.len() as u16
+    }
+
+    fn entry(&self, index: u16) -> Option<LogEntry<N>> {
+        usize::from(index)
+            .checked_sub(1)
+            .and_then(|offset| self.entries.get(offset))
+            .cloned()
+    }
+
+    fn append(&mut self, entry: LogEntry<N>) -> Result<(), Self::Error> {
+        self.entries.push(entry).map_err(|_| LogFullError)
+    }
+
+    fn truncate(&mut self, last_index: u16) -> Result<(), Self::Error> {
+        self.entries.truncate(usize::from(last_index));
+        Ok(())
+    }
+}
+
+/// An error indicating that a Raft log has no space for another entry
+#[derive(Debug)]
+pub struct LogFullError;
@@ -0,0 +1,284 @@
+//!
+//! # Canadensis plug-and-play server
+//!
+//! This library implements the allocator (server) side of the Cyphal plug-and-play node ID
+//! allocation protocol.
+//!
+//! [`PnpServer`] is a transfer handler that responds to allocation requests using version 1.0
+//! and version 2.0 of `uavcan.pnp.NodeIDAllocationData`.
+//!
+//! [`ClusterAllocator`](cluster::ClusterAllocator) is a redundant allocator that shares its
+//! allocation table with other allocators using the `uavcan.pnp.cluster` Raft protocol.
+//!
+
+#![no_std]
+#![deny(missing_docs)]
+
+extern crate alloc;
+
+extern crate canadensis;
+extern crate canadensis_data_types;
+extern crate canadensis_pnp_client;
+extern crate heapless;
+extern crate log;
+
+pub mod cluster;
+pub mod table;
+
+use alloc::vec::Vec;
+use canadensis::core::time::{milliseconds, Instant};
+use canadensis::core::transfer::MessageTransfer;
+use canadensis::core::transport::{Receiver, Transmitter, Transport};
+use canadensis::core::Priority;
+use canadensis::encoding::Deserialize;
+use canadensis::node::NodeError;
+use canadensis::{Node, PublishToken, StartSendError, TransferHandler};
+use canadensis_data_types::uavcan::node::heartbeat_1_0::{self, Heartbeat};
+use canadensis_data_types::uavcan::node::id_1_0::ID;
+use canadensis_data_types::uavcan::pnp::node_id_allocation_data_1_0::{
+    self, NodeIDAllocationData as NodeIDAllocationDataV1,
+};
+use canadensis_data_types::uavcan::pnp::node_id_allocation_data_2_0::{
+    self, NodeIDAllocationData as NodeIDAllocationDataV2,
+};
+use canadensis_pnp_client::crc_64we_48_bits;
+use core::convert::TryFrom;
+
+use crate::table::{AllocationTable, MemoryTable};
+
+/// An error that can occur when creating an allocator on a node of type `N`
+pub type CreateError<N> = NodeError<
+    StartSendError<<<N as Node>::Transmitter as Transmitter<<N as Node>::Instant>>::Error>,
+    <<N as Node>::Receiver as Receiver<<N as Node>::Instant>>::Error,
+>;
+
+/// A plug-and-play node ID allocator
+///
+/// The allocator responds to anonymous allocation requests. When a node sends a request, the
+/// allocator looks up its unique ID hash in the allocation table. If the node already has a
+/// node ID, the allocator responds with the same ID. Otherwise, it allocates a node ID that is not
+/// in the table and not used by the allocator itself, starting with the node's preferred node ID
+/// (or `max_node_id` if it has no preference) and searching upwards and then downwards.
+///
+/// The allocator also adds node IDs seen in heartbeats to the allocation table, so that they
+/// will not be allocated to other nodes.
+///
+/// Requests using version 1.0 of `uavcan.pnp.NodeIDAllocationData` get version 1.0 responses,
+/// and requests using version 2.0 get version 2.0 responses. Both versions use the same table,
+/// keyed by the 48-bit unique ID hash.
+pub struct PnpServer<T, S = MemoryTable<<T as Transport>::NodeId, 128>>
+where
+    T: Transport,
+{
+    /// The allocation table
+    table: S,
+    /// The highest node ID that can be allocated
+    max_node_id: T::NodeId,
+    /// Token used to send version 1.0 responses
+    token_v1: PublishToken<NodeIDAllocationDataV1>,
+    /// Token used to send version 2.0 responses
+    token_v2: PublishToken<NodeIDAllocationDataV2>,
+}
+
+impl<T, S> PnpServer<T, S>
+where
+    T: Transport,
+    S: AllocationTable<T::NodeId>,
+{
+    /// Creates an allocator
+    ///
+    /// This function subscribes to allocation requests and heartbeats, and starts publishing
+    /// allocation responses.
+    ///
+    /// * `node`: The node that the allocator runs on
+    /// * `table`: The allocation table, which may already contain some entries
+    /// * `max_node_id`: The highest node ID that the allocator will allocate. The Cyphal
+    ///   specification recommends not allocating the two highest node IDs that the transport
+    ///   allows, because they are reserved for diagnostic and debugging tools.
+    pub fn new<N>(node: &mut N, table: S, max_node_id: T::NodeId) -> Result<Self, CreateError<N>>
+    where
+        N: Node<Transport = T>,
+    {
+        node.subscribe::<NodeIDAllocationDataV1>(
+            node_id_allocation_data_1_0::SUBJECT,
+            milliseconds(1000),
+        )
+        .map_err(NodeError::Receiver)?;
+        node.subscribe::<NodeIDAllocationDataV2>(
+            node_id_allocation_data_2_0::SUBJECT,
+            milliseconds(1000),
+        )
+        .map_err(NodeError::Receiver)?;
+        node.subscribe::<Heartbeat>(heartbeat_1_0::SUBJECT, milliseconds(1000))
+            .map_err(NodeError::Receiver)?;
+        let token_v1 = node
+            .start_publishing(
+                node_id_allocation_data_1_0::SUBJECT,
+                milliseconds(1000),
+                Priority::Nominal.into(),
+            )
+            .map_err(NodeError::Transmitter)?;
+        let token_v2 = node
+            .start_publishing(
+                node_id_allocation_data_2_0::SUBJECT,
+                milliseconds(1000),
+                Priority::Nominal.into(),
+            )
+            .map_err(NodeError::Transmitter)?;
+
+        Ok(PnpServer {
+            table,
+            max_node_id,
+            token_v1,
+            token_v2,
+        })
+    }
+
+    /// Returns a reference to the allocation table
+    pub fn table(&self) -> &S {
+        &self.table
+    }
+
+    /// Returns a mutable reference to the allocation table
+    pub fn table_mut(&mut self) -> &mut S {
+        &mut self.table
+    }
+
+    /// Finds or allocates a node ID for a node
+    ///
+    /// This function returns None if no node ID is available.
+    fn allocate(
+        &mut self,
+        own_node_id: &T::NodeId,
+        unique_id_hash: u64,
+        preferred: Option<u16>,
+    ) -> Option<T::NodeId> {
+        if let Some(node_id) = self.table.find_by_hash(unique_id_hash) {
+            return Some(node_id);
+        }
+        let max: usize = self.max_node_id.clone().into();
+        let preferred = preferred
+            .map(usize::from)
+            .filter(|&preferred| preferred <= max)
+            .unwrap_or(max);
+        // Search upwards from the preferred node ID, then downwards
+        for candidate in (preferred..=max).chain((0..preferred).rev()) {
+            let node_id = match u16::try_from(candidate)
+                .ok()
+                .and_then(|candidate| T::NodeId::try_from(candidate).ok())
+            {
+                Some(node_id) => node_id,
+                None => continue,
+            };
+            if node_id == *own_node_id || self.table.is_used(&node_id) {
+                continue;
+            }
+            return match self.table.insert(node_id.clone(), Some(unique_id_hash)) {
+                Ok(()) => Some(node_id),
+                Err(e) => {
+                    log::warn!("Failed to add allocation to table: {:?}", e);
+                    None
+                }
+            };
+        }
+        log::warn!("No node IDs available for allocation");
+        None
+    }
+
+    fn handle_request_v1<N>(&mut self, node: &mut N, payload: &[u8])
+    where
+        N: Node<Transport = T>,
+    {
+        let request = match NodeIDAllocationDataV1::deserialize_from_bytes(payload) {
+            Ok(request) => request,
+            Err(_) => return,
+        };
+        let unique_id_hash = request.unique_id_hash & 0x0000_ffff_ffff_ffff;
+        let preferred = request.allocated_node_id.first().map(|id| id.value);
+        if let Some(node_id) = self.allocate(&node.node_id(), unique_id_hash, preferred) {
+            let mut allocated_node_id = heapless::Vec::new();
+            // The vector has capacity 1, so this can't fail
+            let _ = allocated_node_id.push(node_id_to_message(node_id));
+            let response = NodeIDAllocationDataV1 {
+                unique_id_hash,
+                allocated_node_id,
+            };
+            if let Err(e) = node.publish(&self.token_v1, &response) {
+                log::warn!("Failed to send allocation response: {:?}", e);
+            }
+        }
+    }
+
+    fn handle_request_v2<N>(&mut self, node: &mut N, payload: &[u8])
+    where
+        N: Node<Transport = T>,
+    {
+        let request = match NodeIDAllocationDataV2::deserialize_from_bytes(payload) {
+            Ok(request) => request,
+            Err(_) => return,
+        };
+        let unique_id_hash = crc_64we_48_bits(&request.unique_id);
+        let preferred = request.node_id.value;
+        if let Some(node_id) = self.allocate(&node.node_id(), unique_id_hash, Some(preferred)) {
+            let response = NodeIDAllocationDataV2 {
+                node_id: node_id_to_message(node_id),
+                unique_id: request.unique_id,
+            };
+            if let Err(e) = node.publish(&self.token_v2, &response) {
+                log::warn!("Failed to send allocation response: {:?}", e);
+            }
+        }
+    }
+
+    fn handle_heartbeat(&mut self, source: &T::NodeId) {
+        if !self.table.is_used(source) {
+            if let Err(e) = self.table.insert(source.clone(), None) {
+                log::warn!("Failed to add node from heartbeat to table: {:?}", e);
+            }
+        }
+    }
+}
+
+impl<I, T, S> TransferHandler<I, T> for PnpServer<T, S>
+where
+    I: Instant,
+    T: Transport,
+    S: AllocationTable<T::NodeId>,
+{
+    fn handle_message<N: Node<Instant = I, Transport = T>>(
+        &mut self,
+        node: &mut N,
+        transfer: &MessageTransfer<Vec<u8>, I, T>,
+    ) -> bool {
+        match transfer.header.subject {
+            heartbeat_1_0::SUBJECT => {
+                if let Some(source) = &transfer.header.source {
+                    self.handle_heartbeat(source);
+                }
+                // Other handlers may also be interested in heartbeats
+                false
+            }
+            node_id_allocation_data_1_0::SUBJECT => {
+                // Non-anonymous messages are responses from allocators, including this one
+                if transfer.header.source.is_none() {
+                    self.handle_request_v1(node, &transfer.payload);
+                }
+                true
+            }
+            node_id_allocation_data_2_0::SUBJECT => {
+                if transfer.header.source.is_none() {
+                    self.handle_request_v2(node, &transfer.payload);
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+pub(crate) fn node_id_to_message<N: Into<usize>>(node_id: N) -> ID {
+    let value: usize = node_id.into();
+    ID {
+        value: value as u16,
+    }
+}
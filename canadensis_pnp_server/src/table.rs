@@ -0,0 +1,118 @@
+//!
+//! Storage for node ID allocations
+//!
+
+use core::fmt::Debug;
+
+/// Stores the node IDs that have been allocated, and other node IDs that are known to be in use
+///
+/// The Cyphal specification recommends that an allocator keep its allocation table in persistent
+/// storage, so that a node that restarts gets the same node ID.
+pub trait AllocationTable<N> {
+    /// The error that may occur when adding an entry
+    type Error: Debug;
+
+    /// Returns the node ID that was allocated to the node with the provided unique ID hash,
+    /// if one exists
+    fn find_by_hash(&self, unique_id_hash: u64) -> Option<N>;
+
+    /// Returns true if the provided node ID has been allocated or is known to be in use
+    fn is_used(&self, node_id: &N) -> bool;
+
+    /// Adds a node ID to the table
+    ///
+    /// `unique_id_hash` is None if the node ID is in use by a node that did not request an
+    /// allocation (for example, a node with a statically configured node ID).
+    ///
+    /// If the node ID is already in the table, its entry is replaced.
+    fn insert(&mut self, node_id: N, unique_id_hash: Option<u64>) -> Result<(), Self::Error>;
+}
+
+/// An allocation table stored in memory, with space for up to `C` entries
+///
+/// This table does not require dynamic memory allocation, but its contents are lost when
+/// the allocator restarts.
+pub struct MemoryTable<N, const C: usize> {
+    entries: heapless::Vec<Entry<N>, C>,
+}
+
+/// An entry in a memory allocation table
+struct Entry<N> {
+    node_id: N,
+    unique_id_hash: Option<u64>,
+}
+
+impl<N, const C: usize> MemoryTable<N, C> {
+    /// Creates an empty table
+    pub fn new() -> Self {
+        MemoryTable {
+            entries: heapless::Vec::new(),
+        }
+    }
+
+    /// Returns the number of entries in this table
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if this table has no entries
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns an iterator over the node IDs in this table and the corresponding unique ID
+    /// hashes, if known
+    pub fn iter(&self) -> impl Iterator<Item = (&N, Option<u64>)> {
+        self.entries
+            .iter()
+            .map(|entry| (&entry.node_id, entry.unique_id_hash))
+    }
+}
+
+impl<N, const C: usize> Default for MemoryTable<N, C> {
+    fn default() -> Self {
+        MemoryTable::new()
+    }
+}
+
+impl<N, const C: usize> AllocationTable<N> for MemoryTable<N, C>
+where
+    N: Clone + PartialEq,
+{
+    type Error = TableFullError;
+
+    fn find_by_hash(&self, unique_id_hash: u64) -> Option<N> {
+        self.entries
+            .iter()
+            .find(|entry| entry.unique_id_hash == Some(unique_id_hash))
+            .map(|entry| entry.node_id.clone())
+    }
+
+    fn is_used(&self, node_id: &N) -> bool {
+        self.entries.iter().any(|entry| entry.node_id == *node_id)
+    }
+
+    fn insert(&mut self, node_id: N, unique_id_hash: Option<u64>) -> Result<(), Self::Error> {
+        match self
+            .entries
+            .iter_mut()
+            .find(|entry| entry.node_id == node_id)
+        {
+            Some(entry) => {
+                entry.unique_id_hash = unique_id_hash;
+                Ok(())
+            }
+            None => self
+                .entries
+                .push(Entry {
+                    node_id,
+                    unique_id_hash,
+                })
+                .map_err(|_| TableFullError),
+        }
+    }
+}
+
+/// An error indicating that an allocation table has no space for another entry
+#[derive(Debug)]
+pub struct TableFullError;
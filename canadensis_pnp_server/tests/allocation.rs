@@ -0,0 +1,369 @@
+//! Tests of the allocator with clients and other nodes on a simulated CAN bus
+
+extern crate canadensis;
+extern crate canadensis_can;
+extern crate canadensis_data_types;
+extern crate canadensis_pnp_client;
+extern crate canadensis_pnp_server;
+extern crate heapless;
+
+mod common;
+
+use std::convert::TryFrom;
+
+use canadensis::anonymous::AnonymousPublishError;
+use canadensis::anonymous::AnonymousPublisher;
+use canadensis::core::time::{milliseconds, Clock, Microseconds64, SimulatedClock};
+use canadensis::core::transport::{Receiver, Transmitter};
+use canadensis::core::Priority;
+use canadensis::encoding::Deserialize;
+use canadensis::node::MinimalNode;
+use canadensis::Node;
+use canadensis_can::simulated::SimulatedBus;
+use canadensis_can::{CanNodeId, CanReceiver, CanTransmitter, CanTransport, Mtu};
+use canadensis_data_types::uavcan::node::id_1_0::ID;
+use canadensis_data_types::uavcan::pnp::node_id_allocation_data_1_0::NodeIDAllocationData as NodeIDAllocationDataV1;
+use canadensis_data_types::uavcan::pnp::node_id_allocation_data_2_0::{
+    self, NodeIDAllocationData as NodeIDAllocationDataV2,
+};
+use canadensis_pnp_client::{PnpClient, PnpClientError, RetryConfig};
+use canadensis_pnp_server::table::{AllocationTable, MemoryTable};
+use canadensis_pnp_server::PnpServer;
+
+use common::{advance, make_bus, make_node, TestDriver, TestNode};
+
+type TestServer = PnpServer<CanTransport, MemoryTable<CanNodeId, 8>>;
+
+fn make_server(
+    bus: &SimulatedBus<Microseconds64>,
+    clock: &SimulatedClock,
+    mtu: Mtu,
+) -> (TestNode, TestServer) {
+    let mut node = make_node(bus, clock, 125, mtu);
+    let server = PnpServer::new(
+        &mut node,
+        MemoryTable::new(),
+        CanNodeId::try_from(125u8).unwrap(),
+    )
+    .unwrap();
+    (node, server)
+}
+
+fn run_server(node: &mut TestNode, server: &mut TestServer) {
+    for _ in 0..32 {
+        node.receive(server).unwrap();
+    }
+    node.flush().unwrap();
+}
+
+/// A version 1.0 allocation client
+struct TestClient {
+    client: PnpClient<
+        SimulatedClock,
+        NodeIDAllocationDataV1,
+        CanTransmitter<Microseconds64, TestDriver>,
+        CanReceiver<Microseconds64, TestDriver>,
+    >,
+    driver: TestDriver,
+    clock: SimulatedClock,
+}
+
+impl TestClient {
+    fn new(
+        bus: &SimulatedBus<Microseconds64>,
+        clock: &SimulatedClock,
+        unique_id: [u8; 16],
+    ) -> Self {
+        let mut driver = bus.add_driver();
+        let client = PnpClient::new(
+            CanTransmitter::new(Mtu::Can8),
+            CanReceiver::new_anonymous(Mtu::Can8),
+            unique_id,
+            &mut driver,
+        )
+        .unwrap();
+        TestClient {
+            client,
+            driver,
+            clock: clock.clone(),
+        }
+    }
+
+    fn send_request(&mut self) {
+        self.client
+            .send_request(&mut self.clock, &mut self.driver)
+            .unwrap();
+        self.client
+            .transmitter_mut()
+            .flush(&mut self.clock, &mut self.driver)
+            .unwrap();
+    }
+
+    fn receive(&mut self) -> Option<CanNodeId> {
+        let mut node_id = None;
+        for _ in 0..32 {
+            if let Some(id) = self
+                .client
+                .receive(self.clock.now(), &mut self.driver)
+                .unwrap()
+            {
+                node_id = Some(id);
+            }
+        }
+        node_id
+    }
+}
+
+fn node_id(id: u8) -> CanNodeId {
+    CanNodeId::try_from(id).unwrap()
+}
+
+#[test]
+fn allocate_v1() {
+    let bus = make_bus();
+    let clock = SimulatedClock::new();
+    let (mut server_node, mut server) = make_server(&bus, &clock, Mtu::Can8);
+    let mut client_a = TestClient::new(&bus, &clock, [1; 16]);
+    let mut client_b = TestClient::new(&bus, &clock, [2; 16]);
+
+    // The server's own node ID is the maximum, so the first allocation is just below it
+    client_a.send_request();
+    run_server(&mut server_node, &mut server);
+    assert_eq!(Some(node_id(124)), client_a.receive());
+    // Client B sees the response to client A, but ignores it
+    assert_eq!(None, client_b.receive());
+
+    client_b.send_request();
+    run_server(&mut server_node, &mut server);
+    assert_eq!(Some(node_id(123)), client_b.receive());
+    assert_eq!(None, client_a.receive());
+
+    // Repeated requests get the same node ID
+    client_a.send_request();
+    run_server(&mut server_node, &mut server);
+    assert_eq!(Some(node_id(124)), client_a.receive());
+    assert_eq!(2, server.table().len());
+}
+
+#[test]
+fn avoid_ids_from_heartbeats() {
+    let bus = make_bus();
+    let clock = SimulatedClock::new();
+    let (mut server_node, mut server) = make_server(&bus, &clock, Mtu::Can8);
+    // A node with a static ID that would otherwise be allocated
+    let mut other_node = MinimalNode::new(make_node(&bus, &clock, 124, Mtu::Can8)).unwrap();
+    other_node.run_per_second_tasks().unwrap();
+    other_node.node_mut().flush().unwrap();
+    run_server(&mut server_node, &mut server);
+    assert!(server.table().is_used(&node_id(124)));
+    assert_eq!(None, server.table().find_by_hash(0));
+
+    let mut client = TestClient::new(&bus, &clock, [3; 16]);
+    client.send_request();
+    run_server(&mut server_node, &mut server);
+    assert_eq!(Some(node_id(123)), client.receive());
+}
+
+#[test]
+fn allocate_v2_preferred() {
+    let bus = make_bus();
+    let clock = SimulatedClock::new();
+    let (mut server_node, mut server) = make_server(&bus, &clock, Mtu::CanFd64);
+    let mut client_driver = bus.add_driver();
+    let mut client_transmitter = CanTransmitter::new(Mtu::CanFd64);
+    let mut client_receiver = CanReceiver::new_anonymous(Mtu::CanFd64);
+    client_receiver
+        .subscribe_message(
+            node_id_allocation_data_2_0::SUBJECT,
+            18,
+            milliseconds(1000),
+            &mut client_driver,
+        )
+        .unwrap();
+    let mut publisher = AnonymousPublisher::<SimulatedClock, NodeIDAllocationDataV2, _>::new(
+        node_id_allocation_data_2_0::SUBJECT,
+        Priority::Nominal,
+        milliseconds(1000),
+    );
+
+    let unique_id = [0x3c; 16];
+    // Request node ID 10, which has not been allocated
+    for _ in 0..2 {
+        publisher
+            .send(
+                &NodeIDAllocationDataV2 {
+                    node_id: ID { value: 10 },
+                    unique_id,
+                },
+                &mut clock.clone(),
+                &mut client_transmitter,
+                &mut client_driver,
+            )
+            .unwrap();
+        client_transmitter
+            .flush(&mut clock.clone(), &mut client_driver)
+            .unwrap();
+        run_server(&mut server_node, &mut server);
+
+        let transfer = client_receiver
+            .receive(clock.clone().now(), &mut client_driver)
+            .unwrap()
+            .expect("No response");
+        assert_eq!(Some(&node_id(125)), transfer.header.source());
+        let response = NodeIDAllocationDataV2::deserialize_from_bytes(&transfer.payload).unwrap();
+        let allocated = response.node_id.value;
+        let response_unique_id = response.unique_id;
+        assert_eq!(10, allocated);
+        assert_eq!(unique_id, response_unique_id);
+    }
+}
+
+#[test]
+fn v1_responses_ignored() {
+    let bus = make_bus();
+    let clock = SimulatedClock::new();
+    let (mut server_node, mut server) = make_server(&bus, &clock, Mtu::Can8);
+    let mut other_allocator = make_node(&bus, &clock, 100, Mtu::Can8);
+    let token = other_allocator
+        .start_publishing(
+            canadensis_data_types::uavcan::pnp::node_id_allocation_data_1_0::SUBJECT,
+            milliseconds(1000),
+            Priority::Nominal,
+        )
+        .unwrap();
+    other_allocator
+        .publish(
+            &token,
+            &NodeIDAllocationDataV1 {
+                unique_id_hash: 37,
+                allocated_node_id: heapless::Vec::new(),
+            },
+        )
+        .unwrap();
+    other_allocator.flush().unwrap();
+    run_server(&mut server_node, &mut server);
+    assert!(server.table().is_empty());
+}
+
+type PollingClient<M> = PnpClient<
+    SimulatedClock,
+    M,
+    CanTransmitter<Microseconds64, TestDriver>,
+    CanReceiver<Microseconds64, TestDriver>,
+>;
+
+fn make_polling_client<M>(
+    bus: &SimulatedBus<Microseconds64>,
+    mtu: Mtu,
+    unique_id: [u8; 16],
+) -> (PollingClient<M>, TestDriver)
+where
+    M: canadensis_pnp_client::AllocationMessage<CanTransport>,
+{
+    let mut driver = bus.add_driver();
+    let client = PnpClient::new(
+        CanTransmitter::new(mtu),
+        CanReceiver::new_anonymous(mtu),
+        unique_id,
+        &mut driver,
+    )
+    .unwrap();
+    (client, driver)
+}
+
+#[test]
+fn poll_v2_preferred() {
+    let bus = make_bus();
+    let clock = SimulatedClock::new();
+    let (mut server_node, mut server) = make_server(&bus, &clock, Mtu::CanFd64);
+    let (mut client, mut client_driver) =
+        make_polling_client::<NodeIDAllocationDataV2>(&bus, Mtu::CanFd64, [0x51; 16]);
+    client.set_preferred_node_id(Some(node_id(42)));
+
+    let mut allocated = None;
+    for _ in 0..500 {
+        advance(&clock, 10);
+        allocated = client.poll(&mut clock.clone(), &mut client_driver).unwrap();
+        if allocated.is_some() {
+            break;
+        }
+        run_server(&mut server_node, &mut server);
+    }
+    assert_eq!(Some(node_id(42)), allocated);
+    assert_eq!(1, client.attempts());
+    // After allocation, no more requests are sent
+    advance(&clock, 5000);
+    assert_eq!(
+        Some(node_id(42)),
+        client.poll(&mut clock.clone(), &mut client_driver).unwrap()
+    );
+    assert_eq!(1, client.attempts());
+}
+
+#[test]
+fn poll_v2_on_classic_can() {
+    let bus = make_bus();
+    let clock = SimulatedClock::new();
+    // Version 2.0 requests do not fit into one Classic CAN frame
+    let (mut client, mut client_driver) =
+        make_polling_client::<NodeIDAllocationDataV2>(&bus, Mtu::Can8, [0x52; 16]);
+    let mut result = Ok(None);
+    for _ in 0..200 {
+        advance(&clock, 10);
+        result = client.poll(&mut clock.clone(), &mut client_driver);
+        if result.is_err() {
+            break;
+        }
+    }
+    assert!(matches!(
+        result,
+        Err(PnpClientError::Transmit(AnonymousPublishError::Length))
+    ));
+}
+
+#[test]
+fn poll_retries_and_gives_up() {
+    let bus = make_bus();
+    let clock = SimulatedClock::new();
+    let mut server_driver = bus.add_driver();
+    let mut server_receiver = CanReceiver::new(node_id(125), Mtu::Can8);
+    server_receiver
+        .subscribe_message(
+            canadensis_data_types::uavcan::pnp::node_id_allocation_data_1_0::SUBJECT,
+            9,
+            milliseconds(1000),
+            &mut server_driver,
+        )
+        .unwrap();
+    let (mut client, mut client_driver) =
+        make_polling_client::<NodeIDAllocationDataV1>(&bus, Mtu::Can8, [0x53; 16]);
+    client.set_retry_config(RetryConfig {
+        initial_interval_ms: 100,
+        max_interval_ms: 400,
+        max_attempts: Some(4),
+    });
+
+    // With no allocator, the client sends 4 requests and then fails. The intervals are at most
+    // 100 + 200 + 400 + 400 + 400 milliseconds.
+    let mut requests = 0;
+    let mut elapsed = 0;
+    let result = loop {
+        advance(&clock, 10);
+        elapsed += 10;
+        let result = client.poll(&mut clock.clone(), &mut client_driver);
+        while server_receiver
+            .receive(clock.clone().now(), &mut server_driver)
+            .unwrap()
+            .is_some()
+        {
+            requests += 1;
+        }
+        if result.is_err() || elapsed > 5000 {
+            break result;
+        }
+    };
+    assert!(matches!(result, Err(PnpClientError::NoResponse)));
+    assert_eq!(4, requests);
+    assert_eq!(4, client.attempts());
+    assert!(elapsed <= 1500);
+}
@@ -0,0 +1,532 @@
+//! Tests of cluster allocators and clients on a simulated CAN bus
+
+extern crate canadensis;
+extern crate canadensis_can;
+extern crate canadensis_data_types;
+extern crate canadensis_pnp_server;
+
+mod common;
+
+use std::convert::TryFrom;
+
+use canadensis::anonymous::AnonymousPublisher;
+use canadensis::core::time::{milliseconds, Clock, Microseconds64, SimulatedClock};
+use canadensis::core::transfer::{MessageTransfer, ServiceTransfer};
+use canadensis::core::transport::{Receiver, Transmitter};
+use canadensis::core::Priority;
+use canadensis::encoding::{DataType, Deserialize};
+use canadensis::{Node, ResponseToken, TransferHandler};
+use canadensis_can::simulated::SimulatedBus;
+use canadensis_can::{CanNodeId, CanReceiver, CanTransmitter, CanTransport, Mtu};
+use canadensis_data_types::uavcan::node::id_1_0::ID;
+use canadensis_data_types::uavcan::pnp::cluster::append_entries_1_0::{
+    self, AppendEntriesRequest, AppendEntriesResponse,
+};
+use canadensis_data_types::uavcan::pnp::node_id_allocation_data_2_0::{self, NodeIDAllocationData};
+use canadensis_pnp_server::cluster::storage::{LogEntry, MemoryStorage, RaftStorage};
+use canadensis_pnp_server::cluster::{ClusterAllocator, ClusterConfig, Role};
+
+use common::{advance, make_bus, make_node, TestDriver, TestNode};
+
+type TestAllocator = ClusterAllocator<Microseconds64, CanTransport, MemoryStorage<CanNodeId, 16>>;
+
+/// An allocator and the node it runs on
+struct Member {
+    node: TestNode,
+    allocator: TestAllocator,
+    /// If this is false, the allocator does not run
+    running: bool,
+}
+
+impl Member {
+    fn new(
+        bus: &SimulatedBus<Microseconds64>,
+        clock: &SimulatedClock,
+        id: u8,
+        cluster_size: u8,
+    ) -> Self {
+        Member::with_storage(bus, clock, id, cluster_size, MemoryStorage::new())
+    }
+
+    /// Creates an allocator that starts with the terms, vote, and log entries in `storage`
+    fn with_storage(
+        bus: &SimulatedBus<Microseconds64>,
+        clock: &SimulatedClock,
+        id: u8,
+        cluster_size: u8,
+        storage: MemoryStorage<CanNodeId, 16>,
+    ) -> Self {
+        let mut node = make_node(bus, clock, id, Mtu::CanFd64);
+        let allocator = ClusterAllocator::new(
+            &mut node,
+            storage,
+            ClusterConfig::new(cluster_size, self::node_id(100)),
+        )
+        .unwrap();
+        Member {
+            node,
+            allocator,
+            running: true,
+        }
+    }
+
+    fn run(&mut self) {
+        if !self.running {
+            return;
+        }
+        for _ in 0..64 {
+            self.node.receive(&mut self.allocator).unwrap();
+        }
+        self.allocator.run_tasks(&mut self.node);
+        self.node.flush().unwrap();
+    }
+
+    fn stop(&mut self) {
+        self.running = false;
+        self.node.driver_mut().set_connected(false);
+    }
+}
+
+/// A node that sends version 2.0 allocation requests
+struct TestClient {
+    unique_id: [u8; 16],
+    transmitter: CanTransmitter<Microseconds64, TestDriver>,
+    receiver: CanReceiver<Microseconds64, TestDriver>,
+    publisher: AnonymousPublisher<
+        SimulatedClock,
+        NodeIDAllocationData,
+        CanTransmitter<Microseconds64, TestDriver>,
+    >,
+    driver: TestDriver,
+    clock: SimulatedClock,
+}
+
+impl TestClient {
+    fn new(
+        bus: &SimulatedBus<Microseconds64>,
+        clock: &SimulatedClock,
+        unique_id: [u8; 16],
+    ) -> Self {
+        let mut driver = bus.add_driver();
+        let mut receiver = CanReceiver::new_anonymous(Mtu::CanFd64);
+        receiver
+            .subscribe_message(
+                node_id_allocation_data_2_0::SUBJECT,
+                18,
+                milliseconds(1000),
+                &mut driver,
+            )
+            .unwrap();
+        TestClient {
+            unique_id,
+            transmitter: CanTransmitter::new(Mtu::CanFd64),
+            receiver,
+            publisher: AnonymousPublisher::new(
+                node_id_allocation_data_2_0::SUBJECT,
+                Priority::Nominal,
+                milliseconds(1000),
+            ),
+            driver,
+            clock: clock.clone(),
+        }
+    }
+
+    fn send_request(&mut self, preferred: u16) {
+        self.publisher
+            .send(
+                &NodeIDAllocationData {
+                    node_id: ID { value: preferred },
+                    unique_id: self.unique_id,
+                },
+                &mut self.clock,
+                &mut self.transmitter,
+                &mut self.driver,
+            )
+            .unwrap();
+        self.transmitter
+            .flush(&mut self.clock, &mut self.driver)
+            .unwrap();
+    }
+
+    /// Receives responses and returns the node ID allocated to this client, if any
+    fn receive(&mut self) -> Option<(CanNodeId, u16)> {
+        let now = self.clock.now();
+        let mut allocated = None;
+        while let Some(transfer) = self.receiver.receive(now, &mut self.driver).unwrap() {
+            let response = NodeIDAllocationData::deserialize_from_bytes(&transfer.payload).unwrap();
+            let response_unique_id = response.unique_id;
+            if response_unique_id == self.unique_id {
+                let source = *transfer.header.source().unwrap();
+                allocated = Some((source, response.node_id.value));
+            }
+        }
+        allocated
+    }
+}
+
+/// A node that receives AppendEntries requests in place of a stopped allocator, and responds
+/// only when the test tells it to
+struct Impostor {
+    node: TestNode,
+    /// Requests that have not been answered, oldest first
+    requests: Vec<(ResponseToken<CanTransport>, AppendEntriesRequest)>,
+}
+
+impl Impostor {
+    fn new(bus: &SimulatedBus<Microseconds64>, clock: &SimulatedClock, id: u8) -> Self {
+        let mut node = make_node(bus, clock, id, Mtu::CanFd64);
+        node.subscribe_request(
+            append_entries_1_0::SERVICE,
+            AppendEntriesRequest::extent(),
+            milliseconds(1000),
+        )
+        .unwrap();
+        Impostor {
+            node,
+            requests: Vec::new(),
+        }
+    }
+
+    fn run(&mut self) {
+        let mut handler = ImpostorHandler(Vec::new());
+        for _ in 0..64 {
+            self.node.receive(&mut handler).unwrap();
+        }
+        self.requests.extend(handler.0);
+    }
+
+    /// Sends a successful response to a request
+    fn respond(&mut self, token: ResponseToken<CanTransport>, term: u32) {
+        let response = AppendEntriesResponse {
+            term,
+            success: true,
+        };
+        self.node
+            .send_response(token, milliseconds(1000), &response)
+            .unwrap();
+        self.node.flush().unwrap();
+    }
+}
+
+struct ImpostorHandler(Vec<(ResponseToken<CanTransport>, AppendEntriesRequest)>);
+
+impl TransferHandler<Microseconds64, CanTransport> for ImpostorHandler {
+    fn handle_message<N: Node<Instant = Microseconds64, Transport = CanTransport>>(
+        &mut self,
+        _node: &mut N,
+        _transfer: &MessageTransfer<Vec<u8>, Microseconds64, CanTransport>,
+    ) -> bool {
+        false
+    }
+
+    fn handle_request<N: Node<Instant = Microseconds64, Transport = CanTransport>>(
+        &mut self,
+        _node: &mut N,
+        token: ResponseToken<CanTransport>,
+        transfer: &ServiceTransfer<Vec<u8>, Microseconds64, CanTransport>,
+    ) -> bool {
+        let request = AppendEntriesRequest::deserialize_from_bytes(&transfer.payload).unwrap();
+        self.0.push((token, request));
+        true
+    }
+}
+
+fn node_id(id: u8) -> CanNodeId {
+    CanNodeId::try_from(id).unwrap()
+}
+
+/// Runs all allocators for a number of milliseconds, in steps of 10 milliseconds
+fn run_for(clock: &SimulatedClock, members: &mut [Member], milliseconds: u32) {
+    for _ in 0..milliseconds / 10 {
+        advance(clock, 10);
+        for member in members.iter_mut() {
+            member.run();
+        }
+    }
+}
+
+/// Runs all allocators and an impostor for a number of milliseconds, in steps of 10 milliseconds
+fn run_with_impostor(
+    clock: &SimulatedClock,
+    members: &mut [Member],
+    impostor: &mut Impostor,
+    milliseconds: u32,
+) {
+    for _ in 0..milliseconds / 10 {
+        run_for(clock, members, 10);
+        impostor.run();
+    }
+}
+
+/// Returns the index of the only running leader, and checks that all running allocators
+/// agree on the leader
+fn find_leader(members: &[Member]) -> usize {
+    let running = || members.iter().enumerate().filter(|(_, m)| m.running);
+    let leaders: Vec<usize> = running()
+        .filter(|(_, m)| m.allocator.role() == Role::Leader)
+        .map(|(i, _)| i)
+        .collect();
+    assert_eq!(1, leaders.len(), "Expected exactly one leader");
+    let leader_id = members[leaders[0]].node.node_id();
+    for (_, member) in running() {
+        assert_eq!(Some(&leader_id), member.allocator.leader());
+        assert_eq!(
+            members[leaders[0]].allocator.current_term(),
+            member.allocator.current_term()
+        );
+    }
+    leaders[0]
+}
+
+/// Sends requests from a client until it gets a response or the time limit expires
+fn request_allocation(
+    clock: &SimulatedClock,
+    members: &mut [Member],
+    client: &mut TestClient,
+    preferred: u16,
+) -> Option<(CanNodeId, u16)> {
+    for _ in 0..20 {
+        client.send_request(preferred);
+        run_for(clock, members, 500);
+        if let Some(allocated) = client.receive() {
+            return Some(allocated);
+        }
+    }
+    None
+}
+
+/// Creates storage that contains a term, a vote, and log entries as `(term, unique ID, node ID)`
+fn saved_storage(
+    term: u32,
+    voted_for: u8,
+    entries: &[(u32, [u8; 16], u8)],
+) -> MemoryStorage<CanNodeId, 16> {
+    let mut storage = MemoryStorage::new();
+    storage
+        .set_term_and_vote(term, Some(node_id(voted_for)))
+        .unwrap();
+    for &(term, unique_id, id) in entries {
+        storage
+            .append(LogEntry {
+                term,
+                unique_id,
+                node_id: node_id(id),
+            })
+            .unwrap();
+    }
+    storage
+}
+
+fn make_cluster(
+    bus: &SimulatedBus<Microseconds64>,
+    clock: &SimulatedClock,
+    ids: &[u8],
+) -> Vec<Member> {
+    ids.iter()
+        .map(|&id| Member::new(bus, clock, id, ids.len() as u8))
+        .collect()
+}
+
+#[test]
+fn single_allocator() {
+    let bus = make_bus();
+    let clock = SimulatedClock::new();
+    let mut members = make_cluster(&bus, &clock, &[125]);
+    run_for(&clock, &mut members, 5000);
+    assert_eq!(0, find_leader(&members));
+    // The leader's own allocation is the first entry
+    let storage = members[0].allocator.storage();
+    assert_eq!(1, storage.last_index());
+    assert_eq!(node_id(125), storage.entries()[0].node_id);
+
+    let mut client = TestClient::new(&bus, &clock, [7; 16]);
+    assert_eq!(
+        Some((node_id(125), 20)),
+        request_allocation(&clock, &mut members, &mut client, 20)
+    );
+    assert_eq!(2, members[0].allocator.commit_index());
+}
+
+#[test]
+fn single_allocator_restart() {
+    let bus = make_bus();
+    let clock = SimulatedClock::new();
+    // Before the restart, the allocator added its own entry and allocated one node ID. The
+    // commit index is not saved.
+    let storage = saved_storage(1, 125, &[(1, [0; 16], 125), (1, [7; 16], 20)]);
+    let mut members = vec![Member::with_storage(&bus, &clock, 125, 1, storage)];
+    run_for(&clock, &mut members, 5000);
+    assert_eq!(0, find_leader(&members));
+    let allocator = &members[0].allocator;
+    assert!(allocator.current_term() > 1);
+    assert_eq!(allocator.storage().last_index(), allocator.commit_index());
+
+    // The allocator remembers the allocation and can make new allocations
+    let mut client = TestClient::new(&bus, &clock, [7; 16]);
+    assert_eq!(
+        Some((node_id(125), 20)),
+        request_allocation(&clock, &mut members, &mut client, 30)
+    );
+    let mut client = TestClient::new(&bus, &clock, [8; 16]);
+    assert_eq!(
+        Some((node_id(125), 30)),
+        request_allocation(&clock, &mut members, &mut client, 30)
+    );
+}
+
+#[test]
+fn cluster_restart() {
+    let bus = make_bus();
+    let clock = SimulatedClock::new();
+    // Before the restart, allocator 121 was the leader in term 2 and added an entry that only
+    // allocator 122 stored
+    let committed = [(1, [0; 16], 121)];
+    let uncommitted = [(1, [0; 16], 121), (2, [0xa; 16], 40)];
+    let mut members = vec![
+        Member::with_storage(&bus, &clock, 121, 3, saved_storage(2, 121, &uncommitted)),
+        Member::with_storage(&bus, &clock, 122, 3, saved_storage(2, 121, &uncommitted)),
+        Member::with_storage(&bus, &clock, 123, 3, saved_storage(2, 121, &committed)),
+    ];
+    run_for(&clock, &mut members, 10_000);
+    let leader = find_leader(&members);
+    // Allocator 123 can't become the leader because its log is behind
+    assert_ne!(2, leader);
+    let last_index = members[leader].allocator.storage().last_index();
+    assert!(last_index > 2);
+    run_for(&clock, &mut members, 3000);
+    for member in members.iter() {
+        assert_eq!(last_index, member.allocator.commit_index());
+    }
+
+    let mut client_a = TestClient::new(&bus, &clock, [0xa; 16]);
+    let (_, allocated_a) = request_allocation(&clock, &mut members, &mut client_a, 0).unwrap();
+    assert_eq!(40, allocated_a);
+    let mut client_b = TestClient::new(&bus, &clock, [0xb; 16]);
+    let (_, allocated_b) = request_allocation(&clock, &mut members, &mut client_b, 40).unwrap();
+    assert_ne!(40, allocated_b);
+}
+
+#[test]
+fn discovery_and_election() {
+    let bus = make_bus();
+    let clock = SimulatedClock::new();
+    let mut members = make_cluster(&bus, &clock, &[121, 122, 123]);
+    run_for(&clock, &mut members, 10_000);
+    for member in members.iter() {
+        assert!(member.allocator.discovery_complete());
+        assert_eq!(2, member.allocator.peers().count());
+    }
+    let leader = find_leader(&members);
+    let leader_id = members[leader].node.node_id();
+    // The leader's own entry has been replicated and committed everywhere
+    for member in members.iter() {
+        let storage = member.allocator.storage();
+        assert_eq!(1, storage.last_index());
+        assert_eq!(leader_id, storage.entries()[0].node_id);
+        assert_eq!(1, member.allocator.commit_index());
+    }
+}
+
+#[test]
+fn allocation_survives_leader_failure() {
+    let bus = make_bus();
+    let clock = SimulatedClock::new();
+    let mut members = make_cluster(&bus, &clock, &[121, 122, 123]);
+    run_for(&clock, &mut members, 10_000);
+    let first_leader = find_leader(&members);
+
+    let mut client_a = TestClient::new(&bus, &clock, [0xa; 16]);
+    let (responder, allocated_a) =
+        request_allocation(&clock, &mut members, &mut client_a, 0).unwrap();
+    assert_eq!(members[first_leader].node.node_id(), responder);
+    // Allocator node IDs are never allocated to other nodes
+    assert!(![121, 122, 123].contains(&allocated_a));
+    // Let the followers learn that the entry has been committed
+    run_for(&clock, &mut members, 3000);
+    for member in members.iter() {
+        assert_eq!(2, member.allocator.commit_index());
+    }
+
+    members[first_leader].stop();
+    run_for(&clock, &mut members, 10_000);
+    let second_leader = find_leader(&members);
+    assert_ne!(first_leader, second_leader);
+    let term = members[second_leader].allocator.current_term();
+    assert!(term > members[first_leader].allocator.current_term());
+
+    // The new leader remembers the allocation
+    let (responder, allocated_again) =
+        request_allocation(&clock, &mut members, &mut client_a, 0).unwrap();
+    assert_eq!(members[second_leader].node.node_id(), responder);
+    assert_eq!(allocated_a, allocated_again);
+
+    // The remaining two allocators are a majority, so new allocations still work
+    let mut client_b = TestClient::new(&bus, &clock, [0xb; 16]);
+    let (_, allocated_b) = request_allocation(&clock, &mut members, &mut client_b, 0).unwrap();
+    assert_ne!(allocated_a, allocated_b);
+}
+
+#[test]
+fn no_allocation_without_majority() {
+    let bus = make_bus();
+    let clock = SimulatedClock::new();
+    let mut members = make_cluster(&bus, &clock, &[121, 122, 123]);
+    // Only one allocator is running, so it can't become the leader
+    members[1].stop();
+    members[2].stop();
+    run_for(&clock, &mut members, 10_000);
+    assert_ne!(Role::Leader, members[0].allocator.role());
+
+    let mut client = TestClient::new(&bus, &clock, [0xc; 16]);
+    assert_eq!(
+        None,
+        request_allocation(&clock, &mut members, &mut client, 0)
+    );
+}
+
+#[test]
+fn stale_append_entries_response_ignored() {
+    let bus = make_bus();
+    let clock = SimulatedClock::new();
+    let mut members = make_cluster(&bus, &clock, &[121, 122, 123]);
+    run_for(&clock, &mut members, 10_000);
+    let leader = find_leader(&members);
+    let term = members[leader].allocator.current_term();
+    let followers: Vec<usize> = (0..3).filter(|&i| i != leader).collect();
+    for &follower in followers.iter() {
+        members[follower].stop();
+    }
+    // The impostor takes the place of the first follower
+    let follower_id = u8::from(members[followers[0]].node.node_id());
+    let mut impostor = Impostor::new(&bus, &clock, follower_id);
+
+    // Collect a request that has no entries, and do not respond yet. The leader sends a request
+    // to each follower every second.
+    run_with_impostor(&clock, &mut members, &mut impostor, 2000);
+    let (stale_token, stale_request) = impostor.requests.pop().unwrap();
+    assert!(stale_request.entries.is_empty());
+
+    // The leader adds an entry and sends it to the impostor
+    let mut client = TestClient::new(&bus, &clock, [0xd; 16]);
+    client.send_request(0);
+    run_with_impostor(&clock, &mut members, &mut impostor, 2000);
+    assert_eq!(2, members[leader].allocator.storage().last_index());
+    assert_eq!(1, impostor.requests.last().unwrap().1.entries.len());
+
+    // A late response to the earlier request does not mean that the follower has the new entry
+    impostor.respond(stale_token, term);
+    run_with_impostor(&clock, &mut members, &mut impostor, 500);
+    assert_eq!(1, members[leader].allocator.commit_index());
+    assert_eq!(None, client.receive());
+
+    // A response to the most recent request commits the entry
+    impostor.requests.clear();
+    while impostor.requests.is_empty() {
+        run_with_impostor(&clock, &mut members, &mut impostor, 10);
+    }
+    let (token, request) = impostor.requests.pop().unwrap();
+    assert_eq!(1, request.entries.len());
+    impostor.respond(token, term);
+    run_with_impostor(&clock, &mut members, &mut impostor, 100);
+    assert_eq!(2, members[leader].allocator.commit_index());
+}
@@ -0,0 +1,50 @@
+//! Utilities shared by the integration tests
+
+use std::convert::TryFrom;
+
+use canadensis::core::time::{MicrosecondDuration64, Microseconds64, SimulatedClock};
+use canadensis::node::CoreNode;
+use canadensis::requester::TransferIdFixedMap;
+use canadensis_can::simulated::{SimulatedBus, SimulatedDriver};
+use canadensis_can::{CanNodeId, CanReceiver, CanTransmitter, CanTransport, Mtu};
+
+/// A driver connected to a simulated bus
+pub type TestDriver = SimulatedDriver<Microseconds64>;
+
+/// A node that uses a simulated clock and a simulated CAN bus
+pub type TestNode = CoreNode<
+    SimulatedClock,
+    CanTransmitter<Microseconds64, TestDriver>,
+    CanReceiver<Microseconds64, TestDriver>,
+    TransferIdFixedMap<CanTransport, 4>,
+    TestDriver,
+    4,
+    4,
+>;
+
+/// Creates a bus that delivers each frame on the next update after it is transmitted
+pub fn make_bus() -> SimulatedBus<Microseconds64> {
+    SimulatedBus::new(MicrosecondDuration64::new(0))
+}
+
+/// Creates a node connected to a bus
+pub fn make_node(
+    bus: &SimulatedBus<Microseconds64>,
+    clock: &SimulatedClock,
+    id: u8,
+    mtu: Mtu,
+) -> TestNode {
+    let node_id = CanNodeId::try_from(id).unwrap();
+    CoreNode::new(
+        clock.clone(),
+        node_id,
+        CanTransmitter::new(mtu),
+        CanReceiver::new(node_id, mtu),
+        bus.add_driver(),
+    )
+}
+
+/// Advances a clock by a number of milliseconds
+pub fn advance(clock: &SimulatedClock, milliseconds: u32) {
+    clock.advance(MicrosecondDuration64::new(u64::from(milliseconds) * 1000));
+}
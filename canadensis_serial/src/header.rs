@@ -28,8 +28,10 @@ impl SerialHeader {
     const ANONYMOUS_SOURCE: u16 = 0xffff;
     /// Destination for broadcast transfers
     const BROADCAST_DESTINATION: u16 = 0xffff;
+    /// Bit set in frame_index_eot if this is the last frame in a transfer
+    const LAST_FRAME: u32 = 0x8000_0000;
     /// Frame index/EOT for single-frame transfers
-    const SINGLE_FRAME_INDEX: u32 = 0x8000_0000;
+    const SINGLE_FRAME_INDEX: u32 = SerialHeader::LAST_FRAME;
 
     /// Returns the bytes of self, excluding the CRC field
     fn bytes_to_crc(&self) -> &[u8] {
@@ -50,7 +52,31 @@ impl SerialHeader {
         crc.get_crc() == self.header_crc
     }
 
+    /// Returns the index of this frame in its transfer
+    pub fn frame_index(&self) -> u32 {
+        self.frame_index_eot & !SerialHeader::LAST_FRAME
+    }
+
+    /// Returns true if this is the last frame in its transfer
+    pub fn is_last_frame(&self) -> bool {
+        (self.frame_index_eot & SerialHeader::LAST_FRAME) != 0
+    }
+
+    /// Sets the frame index and end-of-transfer flag, and updates the header CRC
+    pub fn set_frame_index(&mut self, index: u32, last_frame: bool) {
+        self.frame_index_eot = if last_frame {
+            SerialHeader::LAST_FRAME | index
+        } else {
+            index
+        };
+        self.update_crc();
+    }
+
     /// Parses this serial header into a transfer header
+    ///
+    /// The frame index and end-of-transfer flag are not part of the transfer header. They should
+    /// be read using [`frame_index`](#method.frame_index) and
+    /// [`is_last_frame`](#method.is_last_frame) before calling this function.
     pub fn into_header<I>(
         self,
         timestamp: I,
@@ -61,9 +87,6 @@ impl SerialHeader {
         if self.version != SerialHeader::VERSION {
             return Err(HeaderParseError::Version);
         }
-        if self.frame_index_eot != SerialHeader::SINGLE_FRAME_INDEX {
-            return Err(HeaderParseError::NotSingleFrame);
-        }
         let priority = Priority::try_from(self.priority).map_err(|_| HeaderParseError::Priority)?;
         let transfer_id: SerialTransferId = self.transfer_id.into();
 
@@ -163,8 +186,6 @@ pub enum HeaderParseError {
     Version,
     /// An invalid priority value
     Priority,
-    /// A transfer that does not fit into a single frame
-    NotSingleFrame,
     /// A service from an anonymous source or with a broadcast destination
     AnonymousService,
 }
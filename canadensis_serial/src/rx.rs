@@ -6,7 +6,7 @@ use alloc::vec::Vec;
 use canadensis_core::subscription::SubscriptionManager;
 use canadensis_core::time::Instant;
 use canadensis_core::transfer::{Header, Transfer};
-use canadensis_core::transport::Receiver;
+use canadensis_core::transport::{Receiver, Statistics};
 use canadensis_core::{nb, OutOfMemoryError, ServiceId, ServiceSubscribeError, SubjectId};
 use core::cmp::Ordering;
 use core::marker::PhantomData;
@@ -15,11 +15,14 @@ use fallible_collections::{FallibleVec, TryHashMap};
 
 /// A serial transport receiver
 ///
-/// This implementation does not support multi-frame transfers or timestamps.
+/// Multi-frame transfers are reassembled if all their frames arrive in order. The timestamp of
+/// each received transfer is the time when the first byte of its first frame was received.
 pub struct SerialReceiver<I, D, S> {
     state: State<I>,
     node_id: Option<SerialNodeId>,
     subscriptions: S,
+    /// Transfers and frames received, and errors
+    statistics: Statistics,
     _driver: PhantomData<D>,
 }
 
@@ -34,6 +37,7 @@ where
             state: State::Idle,
             node_id: Some(node_id),
             subscriptions: S::default(),
+            statistics: Statistics::default(),
             _driver: PhantomData,
         }
     }
@@ -42,6 +46,7 @@ where
             state: State::Idle,
             node_id: None,
             subscriptions: S::default(),
+            statistics: Statistics::default(),
             _driver: PhantomData,
         }
     }
@@ -79,11 +84,16 @@ where
                             // Got the first byte of the header
                             let mut header = HeaderCollector::new();
                             header.push(byte);
-                            State::Header { unescaper, header }
+                            State::Header {
+                                unescaper,
+                                header,
+                                timestamp: now,
+                            }
                         }
                         Ok(None) => State::Header {
                             unescaper,
                             header: HeaderCollector::new(),
+                            timestamp: now,
                         },
                         Err(_) => unreachable!("Unescaper returned an error for a non-zero input"),
                     }
@@ -95,6 +105,7 @@ where
             State::Header {
                 mut unescaper,
                 mut header,
+                timestamp,
             } => {
                 match unescaper.accept(byte) {
                     Ok(Some(byte)) => {
@@ -103,17 +114,28 @@ where
                         if header.is_done() {
                             // Got the complete header
                             let header = header.as_header();
-                            match header.into_header(now) {
+                            let frame_index = header.frame_index();
+                            let last_frame = header.is_last_frame();
+                            match header.into_header(timestamp) {
                                 Ok(header) => {
-                                    if let Some(subscription) = self.is_interested(&header) {
-                                        // Try to allocate memory for the incoming transfer
-                                        // (add 4 bytes at the end for the CRC)
+                                    if header.source().is_none()
+                                        && !(frame_index == 0 && last_frame)
+                                    {
+                                        // Anonymous transfers must fit into one frame
+                                        log::debug!("Got an anonymous multi-frame transfer");
+                                        State::Idle
+                                    } else if let Some(subscription) = self.is_interested(&header) {
+                                        // Try to allocate memory for the incoming frame
+                                        // (add 4 bytes for a transfer CRC and 4 bytes for
+                                        // the frame CRC)
                                         match FallibleVec::try_with_capacity(
-                                            subscription.payload_size_max + 4,
+                                            subscription.payload_size_max + 8,
                                         ) {
                                             Ok(payload) => State::Payload {
                                                 unescaper,
                                                 header,
+                                                frame_index,
+                                                last_frame,
                                                 payload,
                                             },
                                             Err(_) => {
@@ -131,17 +153,26 @@ where
                                 Err(e) => {
                                     // Invalid header CRC or format
                                     log::debug!("Header format or CRC invalid: {:?}", e);
+                                    self.count_error();
                                     State::Idle
                                 }
                             }
                         } else {
                             // Wait for more header bytes
-                            State::Header { unescaper, header }
+                            State::Header {
+                                unescaper,
+                                header,
+                                timestamp,
+                            }
                         }
                     }
                     Ok(None) => {
                         // Keep the same state
-                        State::Header { unescaper, header }
+                        State::Header {
+                            unescaper,
+                            header,
+                            timestamp,
+                        }
                     }
                     // Unexpected zero byte
                     Err(_) => State::Idle,
@@ -150,20 +181,26 @@ where
             State::Payload {
                 mut unescaper,
                 header,
+                frame_index,
+                last_frame,
                 mut payload,
             } => {
                 match unescaper.accept(byte) {
                     Ok(Some(byte)) => {
                         if payload.len() == payload.capacity() {
-                            // Reached maximum payload length, forced to finish the transfer
+                            // Reached maximum payload length, forced to finish the frame
                             self.state = State::Idle;
-                            return Ok(self.complete_transfer(header, payload));
+                            return self
+                                .complete_frame(header, frame_index, last_frame, payload)
+                                .map_err(Error::Memory);
                         } else {
                             // Keep collecting bytes
                             payload.push(byte);
                             State::Payload {
                                 unescaper,
                                 header,
+                                frame_index,
+                                last_frame,
                                 payload,
                             }
                         }
@@ -173,14 +210,18 @@ where
                         State::Payload {
                             unescaper,
                             header,
+                            frame_index,
+                            last_frame,
                             payload,
                         }
                     }
                     Err(_) => {
                         // Got a zero (end delimiter)
                         self.state = State::BetweenTransfers;
-                        // Check and finish the transfer
-                        return Ok(self.complete_transfer(header, payload));
+                        // Check and finish the frame
+                        return self
+                            .complete_frame(header, frame_index, last_frame, payload)
+                            .map_err(Error::Memory);
                     }
                 }
             }
@@ -208,9 +249,15 @@ where
         loop {
             match driver.receive_byte() {
                 Ok(byte) => match self.handle_byte(byte, now) {
-                    Ok(Some(transfer)) => break Ok(Some(transfer)),
+                    Ok(Some(transfer)) => {
+                        self.statistics.transfers = self.statistics.transfers.wrapping_add(1);
+                        break Ok(Some(transfer));
+                    }
                     Ok(None) => { /* Keep going and try another byte */ }
-                    Err(e) => break Err(e),
+                    Err(e) => {
+                        self.count_error();
+                        break Err(e);
+                    }
                 },
                 Err(nb::Error::WouldBlock) => break Ok(None),
                 Err(nb::Error::Other(e)) => break Err(Error::Driver(e)),
@@ -273,6 +320,14 @@ where
     fn unsubscribe_response(&mut self, service: ServiceId, _driver: &mut D) {
         self.subscriptions.unsubscribe_response(service);
     }
+
+    /// Returns the number of transfers and frames received
+    ///
+    /// Errors include frames with incorrect CRCs or invalid headers, and failures to allocate
+    /// memory. Frames with correct CRCs are counted even if they do not complete a transfer.
+    fn statistics(&self) -> Statistics {
+        self.statistics
+    }
 }
 
 impl<I, D, S> SerialReceiver<I, D, S>
@@ -280,6 +335,10 @@ where
     I: Instant,
     S: SubscriptionManager<Subscription<I>>,
 {
+    fn count_error(&mut self) {
+        self.statistics.errors = self.statistics.errors.wrapping_add(1);
+    }
+
     /// Finds and returns a subscription that matches the provided header (and, for service
     /// transfers, has this node as its destination) if any exists
     fn find_subscription_mut(
@@ -320,7 +379,7 @@ where
                     Some(source) => {
                         match subscription.sessions.get(source) {
                             Some(session) => {
-                                if session.last_transfer_id < *header.transfer_id() {
+                                if session.last_transfer_id < Some(*header.transfer_id()) {
                                     Some(subscription)
                                 } else {
                                     // Duplicate transfer
@@ -341,48 +400,70 @@ where
             })
     }
 
-    fn complete_transfer(
+    /// Checks the CRC of a received frame and adds it to its transfer
+    ///
+    /// This function returns a transfer if the frame was the last frame of a transfer and the
+    /// transfer is valid.
+    fn complete_frame(
         &mut self,
         header: Header<I, SerialTransport>,
+        frame_index: u32,
+        last_frame: bool,
         mut payload_and_crc: Vec<u8>,
-    ) -> Option<Transfer<Vec<u8>, I, SerialTransport>> {
-        if payload_and_crc.len() >= 4 {
-            let mut crc_bytes = [0u8; 4];
-            crc_bytes.copy_from_slice(&payload_and_crc[payload_and_crc.len() - 4..]);
-            let crc = u32::from_le_bytes(crc_bytes);
-
-            payload_and_crc.truncate(payload_and_crc.len() - 4);
-            let payload = payload_and_crc;
-            if crc != make_payload_crc(&payload) {
-                // Incorrect CRC
-                return None;
-            }
+    ) -> Result<Option<Transfer<Vec<u8>, I, SerialTransport>>, OutOfMemoryError> {
+        if !remove_and_check_crc(&mut payload_and_crc) {
+            log::debug!("Frame CRC incorrect or missing");
+            self.count_error();
+            return Ok(None);
+        }
+        self.statistics.frames = self.statistics.frames.wrapping_add(1);
+        let frame_payload = payload_and_crc;
 
-            // Record that this transfer was received
-            if let Some(subscription) = self.find_subscription_mut(&header) {
-                if let Some(source_node) = header.source() {
-                    // This may fail to allocate memory.
-                    // TODO: Handle allocation failure
-                    let _ = subscription.sessions.insert(
-                        source_node.clone(),
-                        Session {
-                            expiration_time: subscription.timeout + header.timestamp(),
-                            last_transfer_id: header.transfer_id().clone(),
-                        },
-                    );
+        match self.find_subscription_mut(&header) {
+            Some(subscription) => match header.source() {
+                Some(source_node) => {
+                    let source_node = *source_node;
+                    subscription.accept_frame(
+                        source_node,
+                        header,
+                        frame_index,
+                        last_frame,
+                        frame_payload,
+                    )
                 }
-                Some(Transfer { header, payload })
-            } else {
+                None => {
+                    // Anonymous transfers are always single-frame and don't have sessions
+                    Ok(Some(Transfer {
+                        header,
+                        payload: frame_payload,
+                    }))
+                }
+            },
+            None => {
                 // The subscription was removed while receiving the transfer
-                None
+                Ok(None)
             }
-        } else {
-            // Not enough bytes for a CRC
-            None
         }
     }
 }
 
+/// Checks that the last 4 bytes of the provided vector are a correct CRC of the preceding bytes,
+/// and removes the CRC
+///
+/// This function returns false if the CRC is incorrect or there are fewer than 4 bytes.
+fn remove_and_check_crc(payload_and_crc: &mut Vec<u8>) -> bool {
+    if payload_and_crc.len() >= 4 {
+        let mut crc_bytes = [0u8; 4];
+        crc_bytes.copy_from_slice(&payload_and_crc[payload_and_crc.len() - 4..]);
+        let crc = u32::from_le_bytes(crc_bytes);
+
+        payload_and_crc.truncate(payload_and_crc.len() - 4);
+        crc == make_payload_crc(payload_and_crc)
+    } else {
+        false
+    }
+}
+
 pub struct Subscription<I>
 where
     I: Instant,
@@ -409,6 +490,108 @@ where
         }
     }
 
+    /// Handles a frame (with its CRC already checked and removed) from a non-anonymous source
+    ///
+    /// This function returns a transfer if the frame completed a valid transfer.
+    fn accept_frame(
+        &mut self,
+        source: SerialNodeId,
+        mut header: Header<I, SerialTransport>,
+        frame_index: u32,
+        last_frame: bool,
+        frame_payload: Vec<u8>,
+    ) -> Result<Option<Transfer<Vec<u8>, I, SerialTransport>>, OutOfMemoryError> {
+        let transfer_id = *header.transfer_id();
+        if frame_index == 0 {
+            let last_transfer_id = self
+                .sessions
+                .get(&source)
+                .and_then(|session| session.last_transfer_id);
+            let expiration_time = self.timeout + header.timestamp();
+            if last_frame {
+                // Single-frame transfer, already complete
+                self.sessions.insert(
+                    source,
+                    Session {
+                        expiration_time,
+                        last_transfer_id: Some(transfer_id),
+                        buildup: None,
+                    },
+                )?;
+                Ok(Some(Transfer {
+                    header,
+                    payload: frame_payload,
+                }))
+            } else {
+                // Start of a multi-frame transfer
+                // (add 4 bytes at the end for the transfer CRC)
+                let mut payload: Vec<u8> =
+                    FallibleVec::try_with_capacity(self.payload_size_max + 4)?;
+                if frame_payload.len() > payload.capacity() {
+                    log::debug!("First frame longer than maximum payload length");
+                    return Ok(None);
+                }
+                payload.extend_from_slice(&frame_payload);
+                self.sessions.insert(
+                    source,
+                    Session {
+                        expiration_time,
+                        last_transfer_id,
+                        buildup: Some(Buildup {
+                            transfer_id,
+                            timestamp: header.timestamp(),
+                            next_frame_index: 1,
+                            payload,
+                        }),
+                    },
+                )?;
+                Ok(None)
+            }
+        } else {
+            let mut session = match self.sessions.remove(&source) {
+                Some(session) => session,
+                None => {
+                    log::debug!("Got a non-first frame without a session");
+                    return Ok(None);
+                }
+            };
+            let transfer = session.buildup.take().and_then(|mut buildup| {
+                if buildup.transfer_id != transfer_id || buildup.next_frame_index != frame_index {
+                    log::debug!("Got a frame with an unexpected transfer ID or frame index");
+                    return None;
+                }
+                if header.timestamp().duration_since(&buildup.timestamp) > self.timeout {
+                    log::debug!("Transfer timed out before all frames arrived");
+                    return None;
+                }
+                if buildup.payload.len() + frame_payload.len() > buildup.payload.capacity() {
+                    log::debug!("Transfer longer than maximum payload length");
+                    return None;
+                }
+                buildup.payload.extend_from_slice(&frame_payload);
+
+                if last_frame {
+                    let mut payload = buildup.payload;
+                    if remove_and_check_crc(&mut payload) {
+                        session.last_transfer_id = Some(transfer_id);
+                        header.set_timestamp(buildup.timestamp);
+                        Some(Transfer { header, payload })
+                    } else {
+                        log::debug!("Transfer CRC incorrect");
+                        None
+                    }
+                } else {
+                    buildup.next_frame_index += 1;
+                    session.buildup = Some(buildup);
+                    None
+                }
+            });
+            // Put the session back. There is space for it because it was just removed.
+            self.sessions.insert(source, session)?;
+            Ok(transfer)
+        }
+    }
+
     /// Removes all sessions that have expired
     fn clean_expired_sessions(&mut self, now: I) {
         loop {
@@ -429,8 +612,27 @@ where
 }
 
 struct Session<I> {
+    /// The time when this session expires (the time when the first frame of the most recent
+    /// transfer arrived plus the transfer ID timeout)
     expiration_time: I,
-    last_transfer_id: SerialTransferId,
+    /// The ID of the last transfer that was completely received
+    last_transfer_id: Option<SerialTransferId>,
+    /// A multi-frame transfer that is being reassembled
+    buildup: Option<Buildup<I>>,
+}
+
+/// A partially received multi-frame transfer
+struct Buildup<I> {
+    /// The ID of the transfer
+    transfer_id: SerialTransferId,
+    /// The time when the first frame arrived
+    timestamp: I,
+    /// The index of the next expected frame
+    next_frame_index: u32,
+    /// The payload bytes collected so far, including the transfer CRC if it has been received
+    ///
+    /// The capacity is set to the maximum payload length plus 4 bytes.
+    payload: Vec<u8>,
 }
 
 /// Receiver states
@@ -445,15 +647,20 @@ enum State<I> {
     Header {
         unescaper: Unescaper,
         header: HeaderCollector,
+        /// The time when the first byte of this frame arrived
+        timestamp: I,
     },
     /// Got a header, collecting payload bytes
     ///
-    /// The last 4 bytes of the payload may be the payload CRC.
+    /// The last 4 bytes of the payload may be the frame CRC.
     ///
-    /// The capacity of the payload is set to the maximum payload length plus 4 bytes.
+    /// The capacity of the payload is set to the maximum payload length plus 8 bytes
+    /// (for a transfer CRC and a frame CRC).
     Payload {
         unescaper: Unescaper,
         header: Header<I, SerialTransport>,
+        frame_index: u32,
+        last_frame: bool,
         payload: Vec<u8>,
     },
 }
@@ -8,7 +8,7 @@ use zerocopy::AsBytes;
 
 use canadensis_core::time::{Clock, Instant};
 use canadensis_core::transfer::Transfer;
-use canadensis_core::transport::Transmitter;
+use canadensis_core::transport::{Statistics, Transmitter};
 use canadensis_core::{nb, OutOfMemoryError};
 
 use crate::driver::TransmitDriver;
@@ -29,6 +29,10 @@ const DELIMITER: u8 = 0x0;
 pub struct SerialTransmitter<D, const C: usize> {
     /// Queue of outgoing bytes
     queue: TransmitQueue<C>,
+    /// Maximum number of payload bytes in each frame
+    mtu: usize,
+    /// Transfers and frames added to the queue, and transfers that did not fit
+    statistics: Statistics,
     _driver: PhantomData<D>,
 }
 
@@ -36,9 +40,107 @@ impl<D, const C: usize> SerialTransmitter<D, C> {
     pub fn new() -> Self {
         SerialTransmitter {
             queue: TransmitQueue::new(),
+            mtu: usize::MAX,
+            statistics: Statistics::default(),
             _driver: PhantomData,
         }
     }
+
+    /// Sets the maximum number of payload bytes in each frame
+    ///
+    /// Transfers with payloads longer than this will be split into multiple frames. By default,
+    /// the MTU is unlimited and every transfer is sent in a single frame.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `mtu` is zero.
+    pub fn set_mtu(&mut self, mtu: usize) {
+        assert_ne!(mtu, 0, "MTU must not be zero");
+        self.mtu = mtu;
+    }
+
+    /// Splits a transfer into frames and adds them to the queue
+    ///
+    /// This function returns the number of frames added, or an error if the queue does not have
+    /// enough space for all the frames.
+    fn push_inner(
+        &mut self,
+        mut header: SerialHeader,
+        payload: &[u8],
+    ) -> Result<u64, OutOfMemoryError> {
+        if payload.len() <= self.mtu {
+            // Single frame
+            if worst_case_length_on_wire(payload.len()) > self.queue.free_space() {
+                return Err(OutOfMemoryError);
+            }
+            self.push_frame(&header, payload)?;
+            Ok(1)
+        } else {
+            // Multi-frame: Append the transfer CRC and split into frames
+            let transfer_crc = crate::make_payload_crc(payload);
+            let mut payload_and_crc: Vec<u8> = FallibleVec::try_with_capacity(payload.len() + 4)
+                .map_err(OutOfMemoryError::from)?;
+            payload_and_crc.extend_from_slice(payload);
+            payload_and_crc.extend_from_slice(&transfer_crc.to_le_bytes());
+
+            // Check queue capacity for all frames with worst-case escaping
+            let length_on_wire: usize = payload_and_crc
+                .chunks(self.mtu)
+                .map(|chunk| worst_case_length_on_wire(chunk.len()))
+                .sum();
+            if length_on_wire > self.queue.free_space() {
+                return Err(OutOfMemoryError);
+            }
+
+            let frame_count = payload_and_crc.chunks(self.mtu).len();
+            for (index, chunk) in payload_and_crc.chunks(self.mtu).enumerate() {
+                let last_frame = index == frame_count - 1;
+                header.set_frame_index(index as u32, last_frame);
+                self.push_frame(&header, chunk)?;
+            }
+            Ok(frame_count as u64)
+        }
+    }
+
+    /// Escapes a frame and adds it to the queue
+    ///
+    /// The caller must ensure that the queue has enough space for the worst-case escaped length
+    /// of the frame.
+    fn push_frame(
+        &mut self,
+        header: &SerialHeader,
+        frame_payload: &[u8],
+    ) -> Result<(), OutOfMemoryError> {
+        let frame_length = frame_payload.len() + PER_FRAME_ESCAPED_OVERHEAD;
+        let escaped_length = cobs::escaped_size(frame_length);
+        let frame_crc = crate::make_payload_crc(frame_payload);
+        // Escape the header, payload, and payload CRC into a temporary buffer
+        let mut escape_buffer: Vec<u8> = FallibleVec::try_with_capacity(escaped_length)?;
+        escape_buffer.resize(escaped_length, 0);
+        let data_to_escape = header
+            .as_bytes()
+            .iter()
+            .copied()
+            .chain(frame_payload.iter().copied())
+            .chain(frame_crc.as_bytes().iter().copied());
+        let escaped_length = cobs::escape_from_iter(data_to_escape, &mut escape_buffer)
+            .expect("Incorrect escaped length");
+
+        // Put in the queue: delimiter, escaped data, delimiter
+        self.queue.push_back(DELIMITER)?;
+        for &byte in &escape_buffer[..escaped_length] {
+            self.queue.push_back(byte)?;
+        }
+        self.queue.push_back(DELIMITER)?;
+        Ok(())
+    }
+}
+
+/// Returns the maximum number of bytes that a frame with the provided payload length can
+/// occupy in the queue
+fn worst_case_length_on_wire(frame_payload_length: usize) -> usize {
+    cobs::escaped_size(frame_payload_length + PER_FRAME_ESCAPED_OVERHEAD)
+        + PER_FRAME_UNESCAPED_OVERHEAD
 }
 
 impl<I, D, const C: usize> Transmitter<I> for SerialTransmitter<D, C>
@@ -60,44 +162,18 @@ where
         A: AsRef<[u8]>,
         CL: Clock<Instant = I>,
     {
-        // Check queue capacity with worst-case escaping
-        let frame_length = transfer.payload.as_ref().len() + PER_FRAME_ESCAPED_OVERHEAD;
-        let escaped_length = cobs::escaped_size(frame_length);
-        let length_on_wire = escaped_length + PER_FRAME_UNESCAPED_OVERHEAD;
-
-        if length_on_wire > (self.queue.capacity() - self.queue.len()) {
-            return Err(nb::Error::Other(Error::Memory(OutOfMemoryError)));
-        }
         let header = SerialHeader::from(transfer.header);
-        let payload_crc = crate::make_payload_crc(transfer.payload.as_ref());
-        // Escape the header, payload, and payload CRC into a temporary buffer
-        let mut escape_buffer: Vec<u8> = FallibleVec::try_with_capacity(escaped_length)
-            .map_err(|e| Error::Memory(OutOfMemoryError::from(e)))?;
-        for _ in 0..escaped_length {
-            escape_buffer.push(0);
-        }
-        let data_to_escape = header
-            .as_bytes()
-            .iter()
-            .copied()
-            .chain(transfer.payload.as_ref().iter().copied())
-            .chain(payload_crc.as_bytes().iter().copied());
-        let escaped_length = cobs::escape_from_iter(data_to_escape, &mut escape_buffer)
-            .expect("Incorrect escaped length");
-        // Calculate the required queue capacity based on the real escaped length
-        let length_on_wire = escaped_length + PER_FRAME_UNESCAPED_OVERHEAD;
-        if length_on_wire > (self.queue.capacity() - self.queue.len()) {
-            return Err(nb::Error::Other(Error::Memory(OutOfMemoryError)));
-        }
-
-        // Put in the queue: delimiter, escaped data, delimiter
-        self.queue.push_back(DELIMITER).unwrap();
-        for &byte in &escape_buffer[..escaped_length] {
-            self.queue.push_back(byte).unwrap();
+        match self.push_inner(header, transfer.payload.as_ref()) {
+            Ok(frames) => {
+                self.statistics.transfers = self.statistics.transfers.wrapping_add(1);
+                self.statistics.frames = self.statistics.frames.wrapping_add(frames);
+                Ok(())
+            }
+            Err(e) => {
+                self.statistics.errors = self.statistics.errors.wrapping_add(1);
+                Err(nb::Error::Other(Error::Memory(e)))
+            }
         }
-        self.queue.push_back(DELIMITER).unwrap();
-
-        Ok(())
     }
 
     fn flush<CL>(&mut self, _clock: &mut CL, driver: &mut D) -> nb::Result<(), Self::Error>
@@ -124,8 +200,15 @@ where
     }
 
     fn mtu(&self) -> usize {
-        // Virtually unlimited
-        usize::MAX
+        self.mtu
+    }
+
+    /// Returns the number of transfers and frames added to the queue
+    ///
+    /// Errors are transfers that could not be added because the queue did not have enough
+    /// space or memory could not be allocated.
+    fn statistics(&self) -> Statistics {
+        self.statistics
     }
 }
 
@@ -141,14 +224,11 @@ impl<const C: usize> TransmitQueue<C> {
         self.0.push_back(item).map_err(|_| OutOfMemoryError)
     }
 
-    fn capacity(&self) -> usize {
-        self.0.capacity()
+    /// Returns the number of bytes that can be added to this queue
+    fn free_space(&self) -> usize {
+        self.0.capacity() - self.0.len()
     }
 
-    /// Returns the number of bytes in this queue
-    pub fn len(&self) -> usize {
-        self.0.len()
-    }
     /// Removes the byte from the front of the queue
     pub fn pop_front(&mut self) -> Option<u8> {
         self.0.pop_front()
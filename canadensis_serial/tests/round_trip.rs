@@ -61,6 +61,139 @@ fn round_trip_no_payload() {
     assert_eq!(transfer, received);
 }
 
+#[test]
+fn round_trip_multi_frame() {
+    let subject = SubjectId::try_from(9u16).unwrap();
+    let transfer = make_message_transfer(subject, (0..=255).collect());
+
+    let mut tx_driver = MockDriver::default();
+    let mut tx = SerialTransmitter::<_, 1024>::new();
+    tx.set_mtu(16);
+    tx.push(transfer.clone(), &mut ZeroClock, &mut tx_driver)
+        .unwrap();
+    tx.flush(&mut ZeroClock, &mut tx_driver).unwrap();
+    let wire_bytes: Vec<u8> = tx_driver.iter().copied().collect();
+
+    let mut rx = make_receiver();
+    let mut rx_driver = MockDriver::default();
+    rx.subscribe_message(
+        subject,
+        256,
+        MicrosecondDuration32::new(1000),
+        &mut rx_driver,
+    )
+    .unwrap();
+
+    // Deliver the first half of the bytes at one time and the rest later
+    let (first, second) = wire_bytes.split_at(wire_bytes.len() / 2);
+    rx_driver.bytes.extend(first.iter().copied());
+    assert_eq!(
+        None,
+        rx.receive(Microseconds32::new(10), &mut rx_driver).unwrap()
+    );
+    rx_driver.bytes.extend(second.iter().copied());
+    let received = rx
+        .receive(Microseconds32::new(20), &mut rx_driver)
+        .unwrap()
+        .expect("No transfer");
+
+    // The timestamp is the time when the first frame arrived
+    let mut expected = transfer;
+    expected.header.set_timestamp(Microseconds32::new(10));
+    assert_eq!(expected, received);
+}
+
+#[test]
+fn multi_frame_missing_frame() {
+    let subject = SubjectId::try_from(9u16).unwrap();
+    let transfer = make_message_transfer(subject, (0..64).collect());
+
+    let mut driver = MockDriver::default();
+    let mut tx = SerialTransmitter::<_, 1024>::new();
+    tx.set_mtu(16);
+    tx.push(transfer, &mut ZeroClock, &mut driver).unwrap();
+    tx.flush(&mut ZeroClock, &mut driver).unwrap();
+
+    // Remove the second frame (each frame begins and ends with a zero byte)
+    let wire_bytes: Vec<u8> = driver.bytes.drain(..).collect();
+    let frames: Vec<&[u8]> = wire_bytes
+        .split(|&byte| byte == 0)
+        .filter(|frame| !frame.is_empty())
+        .collect();
+    assert_eq!(5, frames.len());
+    for (i, frame) in frames.iter().enumerate() {
+        if i != 1 {
+            driver.bytes.push_back(0);
+            driver.bytes.extend(frame.iter().copied());
+            driver.bytes.push_back(0);
+        }
+    }
+
+    let mut rx = make_receiver();
+    rx.subscribe_message(subject, 64, MicrosecondDuration32::new(1000), &mut driver)
+        .unwrap();
+    assert_eq!(
+        None,
+        rx.receive(Microseconds32::new(0), &mut driver).unwrap()
+    );
+}
+
+#[test]
+fn multi_frame_timeout() {
+    let subject = SubjectId::try_from(9u16).unwrap();
+    let transfer = make_message_transfer(subject, (0..64).collect());
+
+    let mut tx_driver = MockDriver::default();
+    let mut tx = SerialTransmitter::<_, 1024>::new();
+    tx.set_mtu(16);
+    tx.push(transfer, &mut ZeroClock, &mut tx_driver).unwrap();
+    tx.flush(&mut ZeroClock, &mut tx_driver).unwrap();
+    let wire_bytes: Vec<u8> = tx_driver.iter().copied().collect();
+
+    let mut rx = make_receiver();
+    let mut rx_driver = MockDriver::default();
+    rx.subscribe_message(subject, 64, MicrosecondDuration32::new(100), &mut rx_driver)
+        .unwrap();
+
+    // The rest of the transfer arrives after the transfer ID timeout
+    let (first, second) = wire_bytes.split_at(wire_bytes.len() / 2);
+    rx_driver.bytes.extend(first.iter().copied());
+    assert_eq!(
+        None,
+        rx.receive(Microseconds32::new(0), &mut rx_driver).unwrap()
+    );
+    rx_driver.bytes.extend(second.iter().copied());
+    assert_eq!(
+        None,
+        rx.receive(Microseconds32::new(200), &mut rx_driver)
+            .unwrap()
+    );
+}
+
+fn make_message_transfer(
+    subject: SubjectId,
+    payload: Vec<u8>,
+) -> Transfer<Vec<u8>, Microseconds32, SerialTransport> {
+    Transfer {
+        header: Header::Message(MessageHeader {
+            timestamp: Microseconds32::new(0),
+            transfer_id: 330.into(),
+            priority: Priority::Low,
+            subject,
+            source: Some(37u16.try_into().unwrap()),
+        }),
+        payload,
+    }
+}
+
+fn make_receiver() -> SerialReceiver<
+    Microseconds32,
+    MockDriver,
+    DynamicSubscriptionManager<Subscription<Microseconds32>>,
+> {
+    SerialReceiver::new(SerialNodeId::try_from(360).unwrap())
+}
+
 /// A driver that stores frames in a queue and allows frames written to be read back
 #[derive(Default)]
 pub struct MockDriver {
@@ -36,7 +36,7 @@ fn main() {
     const MTU: usize = 1300;
     let mut receiver = UdpReceiver::<
         Microseconds64,
-        SessionDynamicMap<Microseconds64, UdpNodeId, UdpTransferId, UdpSessionData>,
+        SessionDynamicMap<Microseconds64, UdpNodeId, UdpTransferId, UdpSessionData<Microseconds64>>,
         MTU,
     >::new(address);
     receiver
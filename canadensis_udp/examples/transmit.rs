@@ -23,7 +23,7 @@ fn main() {
     let mut clock = SystemClock::new();
 
     const MTU: usize = 1200;
-    let mut transmitter = UdpTransmitter::<MTU>::new(address).unwrap();
+    let mut transmitter = UdpTransmitter::<_, MTU>::new(address).unwrap();
 
     // Make a payload compatible with the uavcan.metatransport.ethernet.Frame.0.1 format format.
     let mut payload = Vec::with_capacity(6 + 6 + 2 + 2 + MAJOR_GENERAL_SONG.len());
@@ -36,7 +36,7 @@ pub const PADDING0: u16 = 0;
 /// Bit set in frame_index_eot if this is the last frame in the transfer
 pub const LAST_FRAME: u32 = 0x8000_0000;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ValidatedUdpHeader {
     pub priority: Priority,
     pub frame_index: u32,
@@ -13,7 +13,7 @@ use zerocopy::FromBytes;
 use canadensis_core::session::{Session, SessionTracker};
 use canadensis_core::time::Instant;
 use canadensis_core::transfer::{Header, MessageHeader, ServiceHeader, Transfer};
-use canadensis_core::transport::Receiver;
+use canadensis_core::transport::{Receiver, Statistics};
 use canadensis_core::{ServiceId, ServiceSubscribeError, SubjectId};
 
 use crate::address::{Address, UdpPort};
@@ -30,25 +30,28 @@ where
 {
     subscriptions: Subscriptions<I, T>,
     address: NodeAddress,
+    /// Transfers and packets received, and errors
+    statistics: Statistics,
     _session_tracker: PhantomData<T>,
 }
 
 impl<I, T, const MTU: usize> UdpReceiver<I, T, MTU>
 where
     I: Instant,
-    T: SessionTracker<I, UdpNodeId, UdpTransferId, UdpSessionData> + Default,
+    T: SessionTracker<I, UdpNodeId, UdpTransferId, UdpSessionData<I>> + Default,
 {
     pub fn new(address: NodeAddress) -> Self {
         UdpReceiver {
             subscriptions: Subscriptions::new(),
             address,
+            statistics: Statistics::default(),
             _session_tracker: PhantomData,
         }
     }
 
     fn clean_expired_sessions(&mut self, now: I)
     where
-        T: SessionTracker<I, UdpNodeId, UdpTransferId, UdpSessionData> + Default,
+        T: SessionTracker<I, UdpNodeId, UdpTransferId, UdpSessionData<I>> + Default,
     {
         for subscription in self.subscriptions.message_iter_mut() {
             subscription.clean_expired_sessions(now);
@@ -113,7 +116,7 @@ where
 impl<I, T, const MTU: usize> Receiver<I> for UdpReceiver<I, T, MTU>
 where
     I: Instant,
-    T: SessionTracker<I, UdpNodeId, UdpTransferId, UdpSessionData> + Default,
+    T: SessionTracker<I, UdpNodeId, UdpTransferId, UdpSessionData<I>> + Default,
 {
     type Transport = UdpTransport;
     /// The UDP receiver uses multiple sockets internally instead of a separate driver.
@@ -128,13 +131,23 @@ where
         // Loop until all incoming packets have been read
         let result = loop {
             match self.accept_inner(now) {
-                Ok(Some(transfer)) => break Ok(Some(transfer)),
-                Ok(None) => { /* Keep going and try to read another packet */ }
+                Ok(Some(transfer)) => {
+                    self.statistics.frames = self.statistics.frames.wrapping_add(1);
+                    self.statistics.transfers = self.statistics.transfers.wrapping_add(1);
+                    break Ok(Some(transfer));
+                }
+                Ok(None) => {
+                    // Keep going and try to read another packet
+                    self.statistics.frames = self.statistics.frames.wrapping_add(1);
+                }
                 Err(Error::Socket(e)) if e.kind() == std::io::ErrorKind::WouldBlock => {
                     // Can't read any more
                     break Ok(None);
                 }
-                Err(e) => break Err(e),
+                Err(e) => {
+                    self.statistics.errors = self.statistics.errors.wrapping_add(1);
+                    break Err(e);
+                }
             }
         };
         self.clean_expired_sessions(now);
@@ -198,6 +211,14 @@ where
     fn unsubscribe_response(&mut self, service: ServiceId, _driver: &mut ()) {
         self.subscriptions.unsubscribe_response(service);
     }
+
+    /// Returns the number of transfers and packets received
+    ///
+    /// Errors include socket errors and failures to allocate memory. Packets that were ignored
+    /// are counted as frames.
+    fn statistics(&self) -> Statistics {
+        self.statistics
+    }
 }
 
 pub struct Subscription<I, T>
@@ -221,7 +242,7 @@ enum SubscriptionKind {
 impl<I, T> Subscription<I, T>
 where
     I: Instant,
-    T: SessionTracker<I, UdpNodeId, UdpTransferId, UdpSessionData> + Default,
+    T: SessionTracker<I, UdpNodeId, UdpTransferId, UdpSessionData<I>> + Default,
 {
     /// Creates a message subscription
     fn new_message(
@@ -366,7 +387,7 @@ where
         }
         session.set_last_activity(now);
 
-        let result = session.handle_frame(header, bytes_after_header, self.payload_size_max);
+        let result = session.handle_frame(header, bytes_after_header, self.payload_size_max, now);
 
         match result {
             Ok(Some(reassembled)) => {
@@ -413,9 +434,14 @@ where
     }
 }
 
-#[derive(Default)]
-pub struct UdpSessionData {
-    buildup: Option<Buildup>,
+pub struct UdpSessionData<I> {
+    buildup: Option<Buildup<I>>,
+}
+
+impl<I> Default for UdpSessionData<I> {
+    fn default() -> Self {
+        UdpSessionData { buildup: None }
+    }
 }
 
 trait UdpSession<I>
@@ -427,10 +453,11 @@ where
         header: &ValidatedUdpHeader,
         bytes_after_header: &[u8],
         max_payload_length: usize,
+        now: I,
     ) -> Result<Option<Vec<u8>>, Error>;
 }
 
-impl<I> UdpSession<I> for Session<I, UdpTransferId, UdpSessionData>
+impl<I> UdpSession<I> for Session<I, UdpTransferId, UdpSessionData<I>>
 where
     I: Instant,
 {
@@ -439,112 +466,229 @@ where
         header: &ValidatedUdpHeader,
         bytes_after_header: &[u8],
         max_payload_length: usize,
+        now: I,
     ) -> Result<Option<Vec<u8>>, Error> {
         log::debug!(
             "UdpSession::handle_frame, buildup = {:?}",
             &self.data().buildup
         );
-        if header.frame_index == 0 {
-            if header.last_frame {
-                // Special case for a single-frame transfer
-                if bytes_after_header.len() <= max_payload_length {
-                    let mut payload: Vec<u8> =
-                        FallibleVec::try_with_capacity(bytes_after_header.len())?;
-                    payload.extend_from_slice(bytes_after_header);
-                    Ok(Some(payload))
-                } else {
-                    // Frame too large
-                    Ok(None)
-                }
+        if header.frame_index == 0 && header.last_frame {
+            // Special case for a single-frame transfer
+            return if bytes_after_header.len() <= max_payload_length {
+                let mut payload: Vec<u8> =
+                    FallibleVec::try_with_capacity(bytes_after_header.len())?;
+                payload.extend_from_slice(bytes_after_header);
+                Ok(Some(payload))
             } else {
-                // Start a buildup
-                log::debug!("Creating buildup for first frame");
-                match Buildup::new(header, bytes_after_header, max_payload_length) {
-                    Ok(buildup) => self.data_mut().buildup = Some(buildup),
+                // Frame too large
+                Ok(None)
+            };
+        }
+
+        // Frames may arrive in any order. Any frame of a newer transfer starts a new buildup,
+        // and a buildup that has taken longer than the timeout is discarded.
+        let timeout = self.timeout();
+        let buildup = match self.data_mut().buildup.take() {
+            Some(buildup)
+                if now.duration_since(&buildup.start_time()) > timeout
+                    || header.transfer_id > buildup.transfer_id() =>
+            {
+                log::debug!("Replacing buildup for transfer {:?}", buildup.transfer_id());
+                None
+            }
+            Some(buildup) if header.transfer_id < buildup.transfer_id() => {
+                // Frame from an older transfer, keep working on the newer one
+                log::debug!("Ignoring frame from an older transfer");
+                self.data_mut().buildup = Some(buildup);
+                return Ok(None);
+            }
+            other => other,
+        };
+        let buildup = match buildup {
+            Some(mut buildup) => match buildup.push(header, bytes_after_header) {
+                Ok(()) => buildup,
+                Err(e) => {
+                    // Reassembly error. Give up on the reassembly.
+                    log::warn!("Reassembly error: {:?}", e);
+                    return Ok(None);
+                }
+            },
+            None => {
+                log::debug!("Creating buildup for frame {}", header.frame_index);
+                match Buildup::new(header, bytes_after_header, max_payload_length, now) {
+                    Ok(buildup) => buildup,
                     Err(e) => {
                         // Couldn't create buildup due to a problem with the frame
-                        log::warn!("Can't create buildup from first frame: {:?}", e);
+                        log::warn!("Can't create buildup from frame: {:?}", e);
                         return Ok(None);
                     }
                 }
-                Ok(None)
             }
-        } else if header.last_frame {
-            match self.data_mut().buildup.take() {
-                Some(mut buildup) => {
-                    match buildup.push(header, bytes_after_header) {
-                        Ok(()) => {
-                            // Completed reassembly
-                            let mut payload_and_crc = buildup.into_payload();
-
-                            let expected_crc =
-                                match payload_and_crc.get(payload_and_crc.len() - 4..) {
-                                    Some(crc_slice) => {
-                                        let mut crc_bytes = [0u8; 4];
-                                        crc_bytes.copy_from_slice(crc_slice);
-                                        u32::from_le_bytes(crc_bytes)
-                                    }
-                                    None => {
-                                        // Not enough payload to include a CRC
-                                        log::warn!("Payload too short to contain CRC");
-                                        return Ok(None);
-                                    }
-                                };
-                            payload_and_crc.truncate(payload_and_crc.len() - 4);
-                            let payload = payload_and_crc;
-
-                            let calculated_crc = {
-                                let mut crc = CRCu32::crc32c();
-                                crc.digest(&payload);
-                                crc.get_crc()
-                            };
-
-                            // Check crc
-                            if calculated_crc == expected_crc {
-                                Ok(Some(payload))
-                            } else {
-                                // Incorrect CRC
-                                log::warn!(
-                                    "Incorrect CRC: calculated {:#08x}, got {:#08x}",
-                                    calculated_crc,
-                                    expected_crc
-                                );
-                                Ok(None)
-                            }
-                        }
-                        Err(e) => {
-                            // Reassembly error. Give up on the reassembly.
-                            log::warn!("Reassembly error on last frame: {:?}", e);
-                            Ok(None)
-                        }
-                    }
-                }
-                None => {
-                    // Should have a buildup from the first frame, but none exists
-                    log::warn!("Last frame, buildup does not exist");
-                    Ok(None)
-                }
+        };
+        if !buildup.is_complete() {
+            self.data_mut().buildup = Some(buildup);
+            return Ok(None);
+        }
+
+        // Completed reassembly
+        let mut payload_and_crc = buildup.into_payload()?;
+
+        let expected_crc = match payload_and_crc.len().checked_sub(4) {
+            Some(crc_start) => {
+                let mut crc_bytes = [0u8; 4];
+                crc_bytes.copy_from_slice(&payload_and_crc[crc_start..]);
+                u32::from_le_bytes(crc_bytes)
             }
-        } else {
-            // A frame in the middle
-            match self.data_mut().buildup.as_mut() {
-                Some(buildup) => {
-                    match buildup.push(header, bytes_after_header) {
-                        Ok(()) => Ok(None),
-                        Err(e) => {
-                            // Reassembly error. Give up on the reassembly.
-                            log::warn!("Reassembly error on middle frame: {:?}", e);
-                            self.data_mut().buildup = None;
-                            Ok(None)
-                        }
-                    }
-                }
-                None => {
-                    // Missed the first frame, can't use this transfer
-                    log::warn!("Middle frame, buildup does not exist");
-                    Ok(None)
-                }
+            None => {
+                // Not enough payload to include a CRC
+                log::warn!("Payload too short to contain CRC");
+                return Ok(None);
             }
+        };
+        payload_and_crc.truncate(payload_and_crc.len() - 4);
+        let payload = payload_and_crc;
+
+        let calculated_crc = {
+            let mut crc = CRCu32::crc32c();
+            crc.digest(&payload);
+            crc.get_crc()
+        };
+
+        // Check crc
+        if calculated_crc == expected_crc {
+            Ok(Some(payload))
+        } else {
+            // Incorrect CRC
+            log::warn!(
+                "Incorrect CRC: calculated {:#08x}, got {:#08x}",
+                calculated_crc,
+                expected_crc
+            );
+            Ok(None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{UdpSession, UdpSessionData};
+    use crate::header::ValidatedUdpHeader;
+    use crate::UdpTransferId;
+    use canadensis_core::session::Session;
+    use canadensis_core::time::{MicrosecondDuration32, Microseconds32};
+    use canadensis_core::Priority;
+    use crc_any::CRCu32;
+
+    /// Appends the transfer CRC to a payload and splits it into frames of up to 8 bytes
+    fn make_frames(payload: &[u8], transfer_id: u64) -> Vec<(ValidatedUdpHeader, Vec<u8>)> {
+        let mut crc = CRCu32::crc32c();
+        crc.digest(payload);
+        let mut payload_and_crc = payload.to_vec();
+        payload_and_crc.extend_from_slice(&crc.get_crc().to_le_bytes());
+
+        let frame_count = payload_and_crc.chunks(8).len();
+        payload_and_crc
+            .chunks(8)
+            .enumerate()
+            .map(|(i, chunk)| {
+                let header = ValidatedUdpHeader {
+                    priority: Priority::Nominal,
+                    frame_index: i as u32,
+                    last_frame: i == frame_count - 1,
+                    transfer_id: transfer_id.into(),
+                };
+                (header, chunk.to_vec())
+            })
+            .collect()
+    }
+
+    fn make_session() -> Session<Microseconds32, UdpTransferId, UdpSessionData<Microseconds32>> {
+        Session::new(
+            Microseconds32::new(0),
+            MicrosecondDuration32::new(1000),
+            None,
+            UdpSessionData::default(),
+        )
+    }
+
+    #[test]
+    fn out_of_order_with_duplicates() {
+        let payload: Vec<u8> = (0..30).collect();
+        let frames = make_frames(&payload, 1);
+        assert_eq!(5, frames.len());
+        let mut session = make_session();
+        for &i in &[3, 1, 3, 4, 0, 1] {
+            let (header, bytes) = &frames[i];
+            let result = session
+                .handle_frame(header, bytes, 64, Microseconds32::new(10))
+                .unwrap();
+            assert_eq!(None, result);
+        }
+        let (header, bytes) = &frames[2];
+        let result = session
+            .handle_frame(header, bytes, 64, Microseconds32::new(20))
+            .unwrap();
+        assert_eq!(Some(payload), result);
+    }
+
+    #[test]
+    fn newer_transfer_replaces_incomplete() {
+        let old_payload: Vec<u8> = (0..30).collect();
+        let new_payload: Vec<u8> = (100..120).collect();
+        let old_frames = make_frames(&old_payload, 1);
+        let new_frames = make_frames(&new_payload, 2);
+        let mut session = make_session();
+        let now = Microseconds32::new(10);
+
+        // Old transfer is missing a frame
+        for (header, bytes) in &old_frames[1..] {
+            assert_eq!(None, session.handle_frame(header, bytes, 64, now).unwrap());
+        }
+        let (new_last, new_others) = new_frames.split_last().unwrap();
+        for (header, bytes) in new_others.iter().rev() {
+            assert_eq!(None, session.handle_frame(header, bytes, 64, now).unwrap());
+        }
+        // The missing frame of the old transfer arrives late and is ignored
+        let (header, bytes) = &old_frames[0];
+        assert_eq!(None, session.handle_frame(header, bytes, 64, now).unwrap());
+
+        let (header, bytes) = new_last;
+        let result = session.handle_frame(header, bytes, 64, now).unwrap();
+        assert_eq!(Some(new_payload), result);
+    }
+
+    #[test]
+    fn timeout() {
+        let payload: Vec<u8> = (0..30).collect();
+        let frames = make_frames(&payload, 1);
+        let mut session = make_session();
+        let (last, others) = frames.split_last().unwrap();
+        for (header, bytes) in others {
+            let result = session
+                .handle_frame(header, bytes, 64, Microseconds32::new(0))
+                .unwrap();
+            assert_eq!(None, result);
+        }
+        // The last frame arrives after the timeout
+        let (header, bytes) = last;
+        let result = session
+            .handle_frame(header, bytes, 64, Microseconds32::new(1001))
+            .unwrap();
+        assert_eq!(None, result);
+    }
+
+    #[test]
+    fn incorrect_crc() {
+        let payload: Vec<u8> = (0..30).collect();
+        let mut frames = make_frames(&payload, 1);
+        frames[2].1[0] ^= 0x01;
+        let mut session = make_session();
+        let mut result = None;
+        for (header, bytes) in frames.iter().rev() {
+            result = session
+                .handle_frame(header, bytes, 64, Microseconds32::new(0))
+                .unwrap();
         }
+        assert_eq!(None, result);
     }
 }
@@ -2,27 +2,47 @@
 
 use crate::header::ValidatedUdpHeader;
 use crate::UdpTransferId;
+use canadensis_core::time::Instant;
 use canadensis_core::{OutOfMemoryError, Priority};
 use fallible_collections::{FallibleVec, TryReserveError};
 
-// TODO: Add support for reassembling out-of-order frames
-
-/// Collects UDP packets, reassembles them int a transfer, and calculates the CRC of the
-/// entire payload (not including the header in each packet)
+/// Collects UDP packets, which may arrive in any order, and reassembles them into a transfer
+///
+/// The payload bytes of each frame are stored in the order the frames arrive. When the last frame
+/// and all frames before it have been collected, the payloads are put in order by frame index.
 #[derive(Debug)]
-pub struct Buildup {
-    /// The transfer bytes (not including UDP frame headers) that have been collected so far
+pub struct Buildup<I> {
+    /// The transfer bytes (not including UDP frame headers) that have been collected so far,
+    /// in the order that the frames arrived
     bytes: Vec<u8>,
-    /// The expected index of the next frame
-    next_frame_index: u32,
+    /// The location of each collected frame's payload in `bytes`
+    frames: Vec<FrameRecord>,
+    /// The index of the last frame, if the last frame has been collected
+    last_frame_index: Option<u32>,
     /// The priority of the first frame, which all other frames should match
     priority: Priority,
     /// The transfer ID of the first frame, which all other frames should match
     transfer_id: UdpTransferId,
+    /// The time when the first frame (not necessarily the frame with index 0) arrived
+    start_time: I,
+}
+
+/// The location of the payload of one frame
+#[derive(Debug)]
+struct FrameRecord {
+    /// The index of the frame
+    index: u32,
+    /// The offset in the buildup bytes where the frame payload starts
+    offset: usize,
+    /// The length of the frame payload
+    length: usize,
 }
 
-impl Buildup {
-    /// Creates a buildup from an initial frame
+impl<I> Buildup<I>
+where
+    I: Instant,
+{
+    /// Creates a buildup from an initial frame, which may have any index
     ///
     /// This function attempts to allocate space for `max_length` payload bytes and returns an error
     /// if the allocation fails.
@@ -30,55 +50,124 @@ impl Buildup {
         header: &ValidatedUdpHeader,
         bytes_after_header: &[u8],
         max_length: usize,
+        now: I,
     ) -> Result<Self, BuildupError> {
-        if bytes_after_header.len() > max_length {
-            return Err(BuildupError::Length);
-        }
-        let mut bytes: Vec<u8> = FallibleVec::try_with_capacity(max_length)?;
-        bytes.extend_from_slice(bytes_after_header);
-
-        Ok(Buildup {
+        let bytes: Vec<u8> = FallibleVec::try_with_capacity(max_length)?;
+        let mut buildup = Buildup {
             bytes,
-            next_frame_index: header.frame_index + 1,
+            frames: Vec::new(),
+            last_frame_index: None,
             priority: header.priority,
             transfer_id: header.transfer_id,
-        })
+            start_time: now,
+        };
+        buildup.push(header, bytes_after_header)?;
+        Ok(buildup)
+    }
+
+    /// Returns the ID of the transfer that this buildup is collecting
+    pub fn transfer_id(&self) -> UdpTransferId {
+        self.transfer_id
+    }
+
+    /// Returns the time when the first frame of this buildup arrived
+    pub fn start_time(&self) -> I {
+        self.start_time
     }
 
     /// Adds a frame to this buildup
     ///
-    /// This function does not use dynamic memory allocation.
+    /// Frames may be added in any order. A frame with an index that has already been collected
+    /// is ignored.
+    ///
+    /// This function does not allocate memory for the frame payload, but it may allocate
+    /// memory to record the location of the frame.
     pub fn push(
         &mut self,
         header: &ValidatedUdpHeader,
         bytes_after_header: &[u8],
     ) -> Result<(), BuildupError> {
-        if header.frame_index != self.next_frame_index {
-            return Err(BuildupError::Index);
-        }
         if header.transfer_id != self.transfer_id {
             return Err(BuildupError::TransferId);
         }
         if header.priority != self.priority {
             return Err(BuildupError::Priority);
         }
+        if self
+            .frames
+            .iter()
+            .any(|frame| frame.index == header.frame_index)
+        {
+            // Duplicate frame
+            log::debug!("Ignoring duplicate frame {}", header.frame_index);
+            return Ok(());
+        }
+        match self.last_frame_index {
+            Some(last_index) => {
+                if header.last_frame || header.frame_index > last_index {
+                    return Err(BuildupError::Index);
+                }
+            }
+            None => {
+                if header.last_frame
+                    && self
+                        .frames
+                        .iter()
+                        .any(|frame| frame.index > header.frame_index)
+                {
+                    return Err(BuildupError::Index);
+                }
+            }
+        }
+        // Every frame except the last must have some payload, so a valid frame index can't be
+        // greater than the maximum length. This limits the number of frame records.
+        if header.frame_index as usize > self.bytes.capacity() {
+            return Err(BuildupError::Index);
+        }
         if self.bytes.len() + bytes_after_header.len() > self.bytes.capacity() {
             return Err(BuildupError::Length);
         }
+        self.frames.try_push(FrameRecord {
+            index: header.frame_index,
+            offset: self.bytes.len(),
+            length: bytes_after_header.len(),
+        })?;
         self.bytes.extend_from_slice(bytes_after_header);
+        if header.last_frame {
+            self.last_frame_index = Some(header.frame_index);
+        }
         Ok(())
     }
 
+    /// Returns true if the last frame and all frames before it have been collected
+    pub fn is_complete(&self) -> bool {
+        match self.last_frame_index {
+            // All collected frames have indices less than or equal to the last index, and there
+            // are no duplicates.
+            Some(last_index) => self.frames.len() == last_index as usize + 1,
+            None => false,
+        }
+    }
+
     /// Consumes this buildup and returns the payload bytes (possibly including a CRC at the end)
-    pub fn into_payload(self) -> Vec<u8> {
-        self.bytes
+    /// in the correct order
+    ///
+    /// This function should only be called if `is_complete()` returns true.
+    pub fn into_payload(mut self) -> Result<Vec<u8>, OutOfMemoryError> {
+        debug_assert!(self.is_complete());
+        let mut payload: Vec<u8> = FallibleVec::try_with_capacity(self.bytes.len())?;
+        self.frames.sort_unstable_by_key(|frame| frame.index);
+        for frame in &self.frames {
+            payload.extend_from_slice(&self.bytes[frame.offset..frame.offset + frame.length]);
+        }
+        Ok(payload)
     }
 }
 
 /// Errors that the buildup may produce
 #[derive(Debug)]
 pub enum BuildupError {
-    /// The frame index did not match
+    /// The frame index was not consistent with the index of the last frame
     Index,
     /// The frame priority did not match, or a header had an invalid priority value
     Priority,
@@ -100,3 +189,98 @@ impl From<TryReserveError> for BuildupError {
         BuildupError::Memory(inner.into())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::Buildup;
+    use crate::header::ValidatedUdpHeader;
+    use canadensis_core::time::Microseconds32;
+    use canadensis_core::Priority;
+
+    /// Splits a payload into frames of up to 4 bytes and returns each header with its bytes
+    fn make_frames(payload: &[u8]) -> Vec<(ValidatedUdpHeader, &[u8])> {
+        let frame_count = payload.chunks(4).len();
+        payload
+            .chunks(4)
+            .enumerate()
+            .map(|(i, chunk)| {
+                let header = ValidatedUdpHeader {
+                    priority: Priority::Nominal,
+                    frame_index: i as u32,
+                    last_frame: i == frame_count - 1,
+                    transfer_id: 3.into(),
+                };
+                (header, chunk)
+            })
+            .collect()
+    }
+
+    fn reassemble(frames: &[(ValidatedUdpHeader, &[u8])], max_length: usize) -> Option<Vec<u8>> {
+        let mut frames = frames.iter();
+        let (header, bytes) = frames.next()?;
+        let mut buildup = Buildup::new(header, bytes, max_length, Microseconds32::new(0)).ok()?;
+        for (header, bytes) in frames {
+            buildup.push(header, bytes).ok()?;
+        }
+        if buildup.is_complete() {
+            Some(buildup.into_payload().unwrap())
+        } else {
+            None
+        }
+    }
+
+    #[test]
+    fn in_order() {
+        let payload: Vec<u8> = (0..18).collect();
+        let frames = make_frames(&payload);
+        assert_eq!(Some(payload.clone()), reassemble(&frames, 18));
+    }
+
+    #[test]
+    fn shuffled() {
+        let payload: Vec<u8> = (0..18).collect();
+        let frames = make_frames(&payload);
+        for order in [
+            [4, 3, 2, 1, 0],
+            [2, 0, 4, 1, 3],
+            [1, 2, 3, 4, 0],
+            [0, 4, 1, 3, 2],
+        ] {
+            let shuffled: Vec<_> = order.iter().map(|&i| frames[i].clone()).collect();
+            assert_eq!(Some(payload.clone()), reassemble(&shuffled, 18));
+        }
+    }
+
+    #[test]
+    fn duplicated() {
+        let payload: Vec<u8> = (0..18).collect();
+        let frames = make_frames(&payload);
+        let duplicated: Vec<_> = [3, 1, 3, 0, 1, 2, 0, 4]
+            .iter()
+            .map(|&i| frames[i].clone())
+            .collect();
+        assert_eq!(Some(payload.clone()), reassemble(&duplicated, 18));
+    }
+
+    #[test]
+    fn missing() {
+        let payload: Vec<u8> = (0..18).collect();
+        let frames = make_frames(&payload);
+        for missing in 0..frames.len() {
+            let incomplete: Vec<_> = frames
+                .iter()
+                .enumerate()
+                .filter(|&(i, _)| i != missing)
+                .map(|(_, frame)| frame.clone())
+                .collect();
+            assert_eq!(None, reassemble(&incomplete, 18));
+        }
+    }
+
+    #[test]
+    fn too_long() {
+        let payload: Vec<u8> = (0..18).collect();
+        let frames = make_frames(&payload);
+        assert_eq!(None, reassemble(&frames, 17));
+    }
+}
@@ -6,19 +6,28 @@ use crate::{bind_socket, header};
 use crate::{Error, UdpTransferId, UdpTransport};
 use canadensis_core::time::{Clock, Instant};
 use canadensis_core::transfer::{Header, Transfer};
-use canadensis_core::transport::Transmitter;
+use canadensis_core::transport::{Statistics, Transmitter};
 use canadensis_core::{nb, Priority};
 use crc_any::CRCu32;
 use std::cmp::Ordering;
+use std::collections::VecDeque;
 use std::net::{SocketAddrV4, UdpSocket};
 
-pub struct UdpTransmitter<const MTU: usize> {
+/// Splits outgoing transfers into frames and sends them
+///
+/// Sending blocks until all frames have been passed to the operating system. For transfers
+/// marked for loopback, the transmission time is the time when the first frame was sent.
+pub struct UdpTransmitter<I, const MTU: usize> {
     /// The socket used to send frames
     socket: UdpSocket,
     /// The address of this node
     address: NodeAddress,
+    /// Headers of transmitted loopback transfers, with their transmission times
+    loopback: VecDeque<Header<I, UdpTransport>>,
+    /// Transfers and frames sent, and transfers that could not be sent
+    statistics: Statistics,
 }
-impl<const MTU: usize> UdpTransmitter<MTU> {
+impl<I: Instant, const MTU: usize> UdpTransmitter<I, MTU> {
     /// Creates a transmitter
     ///
     /// # Panics
@@ -31,10 +40,17 @@ impl<const MTU: usize> UdpTransmitter<MTU> {
         // Bind to an ephemeral port
         let socket = bind_socket(address.clone().into(), 0)?;
 
-        Ok(UdpTransmitter { socket, address })
+        Ok(UdpTransmitter {
+            socket,
+            address,
+            loopback: VecDeque::new(),
+            statistics: Statistics::default(),
+        })
     }
 
-    fn push_inner<I, C>(
+    /// Sends a transfer and returns the time when the first frame was sent, or None if no frames
+    /// were sent before the deadline
+    fn push_inner<C>(
         &mut self,
         dest: SocketAddrV4,
         deadline: I,
@@ -42,9 +58,8 @@ impl<const MTU: usize> UdpTransmitter<MTU> {
         priority: Priority,
         payload: &[u8],
         clock: &mut C,
-    ) -> Result<(), Error>
+    ) -> Result<Option<I>, Error>
     where
-        I: Instant,
         C: Clock<Instant = I>,
     {
         if breakdown::fits_into_one_frame::<MTU>(payload.len()) {
@@ -74,41 +89,35 @@ impl<const MTU: usize> UdpTransmitter<MTU> {
         }
     }
 
-    fn send_frames<I, B, C>(&mut self, breakdown: B, clock: &mut C) -> Result<(), Error>
+    fn send_frames<B, C>(&mut self, breakdown: B, clock: &mut C) -> Result<Option<I>, Error>
     where
-        I: Instant,
         B: IntoIterator<Item = UdpFrame<I, MTU>>,
         C: Clock<Instant = I>,
     {
+        let mut first_frame_time = None;
         for frame in breakdown {
             if frame.deadline.overflow_safe_compare(&clock.now()) == Ordering::Greater {
                 self.socket.send_to(&frame.data, frame.remote_address)?;
+                self.statistics.frames = self.statistics.frames.wrapping_add(1);
+                first_frame_time.get_or_insert_with(|| clock.now());
             }
         }
-        Ok(())
+        Ok(first_frame_time)
     }
-}
-
-impl<I, const MTU: usize> Transmitter<I> for UdpTransmitter<MTU>
-where
-    I: Instant,
-{
-    type Transport = UdpTransport;
-    /// The UDP transport uses an internal socket instead of a separate driver.
-    type Driver = ();
-    type Error = Error;
 
-    fn push<A, C>(
+    /// Sends a transfer, records its transmission time if `loopback` is true, and updates the
+    /// statistics
+    fn push_transfer<A, C>(
         &mut self,
-        transfer: Transfer<A, I, Self::Transport>,
+        transfer: Transfer<A, I, UdpTransport>,
+        loopback: bool,
         clock: &mut C,
-        _driver: &mut (),
     ) -> nb::Result<(), Error>
     where
         A: AsRef<[u8]>,
         C: Clock<Instant = I>,
     {
-        match transfer.header {
+        let sent_time = match &transfer.header {
             Header::Message(header) => {
                 let multicast_addr = self.address.multicast_address(header.subject);
                 let dest_port: u16 = UdpPort::Message.into();
@@ -145,8 +154,59 @@ where
                     clock,
                 )
             }
+        };
+        let sent_time = match sent_time {
+            Ok(sent_time) => {
+                self.statistics.transfers = self.statistics.transfers.wrapping_add(1);
+                sent_time
+            }
+            Err(e) => {
+                self.statistics.errors = self.statistics.errors.wrapping_add(1);
+                return Err(nb::Error::Other(e));
+            }
+        };
+        if let (true, Some(sent_time)) = (loopback, sent_time) {
+            let mut header = transfer.header;
+            header.set_timestamp(sent_time);
+            self.loopback.push_back(header);
         }
-        .map_err(nb::Error::Other)
+        Ok(())
+    }
+}
+
+impl<I, const MTU: usize> Transmitter<I> for UdpTransmitter<I, MTU>
+where
+    I: Instant,
+{
+    type Transport = UdpTransport;
+    /// The UDP transport uses an internal socket instead of a separate driver.
+    type Driver = ();
+    type Error = Error;
+
+    fn push<A, C>(
+        &mut self,
+        transfer: Transfer<A, I, Self::Transport>,
+        clock: &mut C,
+        _driver: &mut (),
+    ) -> nb::Result<(), Error>
+    where
+        A: AsRef<[u8]>,
+        C: Clock<Instant = I>,
+    {
+        self.push_transfer(transfer, false, clock)
+    }
+
+    fn push_loopback<A, C>(
+        &mut self,
+        transfer: Transfer<A, I, Self::Transport>,
+        clock: &mut C,
+        _driver: &mut (),
+    ) -> nb::Result<(), Error>
+    where
+        A: AsRef<[u8]>,
+        C: Clock<Instant = I>,
+    {
+        self.push_transfer(transfer, true, clock)
     }
 
     fn flush<C>(
@@ -166,6 +226,18 @@ where
         // Subtract to get the maximum number of payload bytes per frame
         MTU - header::SIZE
     }
+
+    fn pop_loopback(&mut self, _driver: &mut ()) -> Option<Header<I, UdpTransport>> {
+        self.loopback.pop_front()
+    }
+
+    /// Returns the number of transfers and frames sent
+    ///
+    /// Errors include socket errors. Frames that were not sent because their deadlines had
+    /// passed are not counted.
+    fn statistics(&self) -> Statistics {
+        self.statistics
+    }
 }
 
 pub(crate) struct UdpFrame<I, const MTU: usize> {